@@ -0,0 +1,68 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use syntree::{Builder, ChildIndex, Error, Node, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Array,
+    Number,
+    Lit,
+    Comma,
+}
+
+use Syntax::*;
+
+/// Build a single array with `n` elements.
+fn build(n: usize) -> Result<Tree<Syntax>, Error> {
+    let mut builder = Builder::new();
+
+    builder.open(Array)?;
+
+    for i in 0..n {
+        builder.open(Number)?;
+        builder.token(Lit, 1 + i % 7)?;
+        builder.close()?;
+        builder.token(Comma, 1)?;
+    }
+
+    builder.close()?;
+    builder.build()
+}
+
+/// Find the child covering `offset` by scanning the children of `node`.
+fn scan(node: Node<'_, Syntax>, offset: usize) -> Option<Node<'_, Syntax>> {
+    node.children().find(|n| n.range().contains(&offset))
+}
+
+fn setup(c: &mut Criterion) {
+    let sizes = [64, 1024, 16384];
+
+    let mut group = c.benchmark_group("child_by_offset");
+
+    for size in sizes {
+        let tree = build(size).expect("failed to build tree");
+        let index = ChildIndex::new(&tree);
+        let array = tree.first().expect("missing array");
+        let end = array.range().end;
+        let offsets = (0..end).step_by(end / 64).collect::<Vec<_>>();
+
+        for &offset in &offsets {
+            assert_eq!(scan(array, offset), index.child_by_offset(array, offset));
+        }
+
+        group.bench_with_input(BenchmarkId::new("scan", size), &array, |b, &array| {
+            b.iter(|| offsets.iter().filter_map(|&o| scan(array, o)).count())
+        });
+
+        group.bench_with_input(BenchmarkId::new("child_index", size), &index, |b, index| {
+            b.iter(|| {
+                offsets
+                    .iter()
+                    .filter_map(|&o| index.child_by_offset(array, o))
+                    .count()
+            })
+        });
+    }
+}
+
+criterion_group!(benches, setup);
+criterion_main!(benches);
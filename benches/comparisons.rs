@@ -5,6 +5,7 @@ use syntree::{Builder, Error, Tree};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u16)]
+#[allow(clippy::upper_case_acronyms)]
 enum Syntax {
     STRING,
     ENTRY,
@@ -70,7 +71,7 @@ fn rowan_tree(n: usize, strings: &[Box<str>]) -> SyntaxNode<Lang> {
 
     for (_, s) in (0..n).zip(strings.iter().cycle()) {
         builder.start_node(ENTRY.into());
-        builder.token(STRING.into(), &**s);
+        builder.token(STRING.into(), s);
         builder.finish_node();
         builder.token(WHITESPACE.into(), " ");
     }
@@ -87,9 +88,9 @@ fn syntree_tree(n: usize, strings: &[Box<str>]) -> Result<Tree<Syntax>, Error> {
 
     for (_, s) in (0..n).zip(strings.iter().cycle()) {
         builder.open(ENTRY)?;
-        builder.token(STRING.into(), s.len())?;
+        builder.token(STRING, s.len())?;
         builder.close()?;
-        builder.token(WHITESPACE.into(), 1)?;
+        builder.token(WHITESPACE, 1)?;
     }
 
     builder.close_at(&c, ROOT)?;
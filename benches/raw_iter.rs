@@ -0,0 +1,53 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use syntree::{Builder, Error, Kind, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Number,
+    Lit,
+    Whitespace,
+    Root,
+}
+
+use Syntax::*;
+
+fn build(n: usize) -> Result<Tree<Syntax>, Error> {
+    let mut builder = Builder::new();
+
+    let c = builder.checkpoint()?;
+
+    for i in 0..n {
+        builder.open(Number)?;
+        builder.token(Lit, 1 + i % 7)?;
+        builder.close()?;
+        builder.token(Whitespace, 1)?;
+    }
+
+    builder.close_at(&c, Root)?;
+    builder.build()
+}
+
+fn setup(c: &mut Criterion) {
+    let sizes = [1024, 16384, 262144];
+
+    let mut group = c.benchmark_group("count_tokens");
+
+    for size in sizes {
+        let tree = build(size).expect("failed to build tree");
+
+        let walk = tree.walk().filter(|n| n.kind() == Kind::Token).count();
+        let raw = tree.raw_iter().filter(|e| e.kind() == Kind::Token).count();
+        assert_eq!(walk, raw);
+
+        group.bench_with_input(BenchmarkId::new("walk", size), &tree, |b, tree| {
+            b.iter(|| tree.walk().filter(|n| n.kind() == Kind::Token).count())
+        });
+
+        group.bench_with_input(BenchmarkId::new("raw_iter", size), &tree, |b, tree| {
+            b.iter(|| tree.raw_iter().filter(|e| e.kind() == Kind::Token).count())
+        });
+    }
+}
+
+criterion_group!(benches, setup);
+criterion_main!(benches);
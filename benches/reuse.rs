@@ -0,0 +1,91 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use syntree::{Builder, Error, Kind, Node, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Number,
+    Lit,
+    Whitespace,
+    Root,
+}
+
+use Syntax::*;
+
+fn build(n: usize) -> Result<Tree<Syntax>, Error> {
+    let mut builder = Builder::new();
+
+    builder.open(Root)?;
+
+    for i in 0..n {
+        builder.open(Number)?;
+        builder.token(Lit, 1 + i % 7)?;
+        builder.close()?;
+        builder.token(Whitespace, 1)?;
+    }
+
+    builder.close()?;
+    builder.build()
+}
+
+fn replay(builder: &mut Builder<Syntax>, node: Node<'_, Syntax>) -> Result<(), Error> {
+    match node.kind() {
+        Kind::Token => {
+            builder.token(*node.value(), node.range().len())?;
+        }
+        _ => {
+            builder.open(*node.value())?;
+
+            for child in node.children() {
+                replay(builder, child)?;
+            }
+
+            builder.close()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn rebuild(tree: &Tree<Syntax>, reuse: bool) -> Result<Tree<Syntax>, Error> {
+    let mut builder = Builder::new();
+
+    builder.open(Root)?;
+    builder.token(Lit, 3)?;
+
+    for node in tree.reusable_nodes(0..0) {
+        if reuse {
+            builder.reuse(node)?;
+        } else {
+            replay(&mut builder, node)?;
+        }
+    }
+
+    builder.close()?;
+    builder.build()
+}
+
+fn setup(c: &mut Criterion) {
+    let sizes = [1024, 16384, 262144];
+
+    let mut group = c.benchmark_group("rebuild");
+
+    for size in sizes {
+        let tree = build(size).expect("failed to build tree");
+
+        assert_eq!(
+            rebuild(&tree, true).expect("failed to reuse"),
+            rebuild(&tree, false).expect("failed to replay")
+        );
+
+        group.bench_with_input(BenchmarkId::new("replay", size), &tree, |b, tree| {
+            b.iter(|| rebuild(tree, false).expect("failed to replay"))
+        });
+
+        group.bench_with_input(BenchmarkId::new("reuse", size), &tree, |b, tree| {
+            b.iter(|| rebuild(tree, true).expect("failed to reuse"))
+        });
+    }
+}
+
+criterion_group!(benches, setup);
+criterion_main!(benches);
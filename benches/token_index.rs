@@ -0,0 +1,68 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use syntree::{Builder, Error, Kind, Node, TokenIndex, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Number,
+    Lit,
+    Whitespace,
+    Root,
+}
+
+use Syntax::*;
+
+fn build(n: usize) -> Result<Tree<Syntax>, Error> {
+    let mut builder = Builder::new();
+
+    let c = builder.checkpoint()?;
+
+    for i in 0..n {
+        builder.open(Number)?;
+        builder.token(Lit, 1 + i % 7)?;
+        builder.close()?;
+        builder.token(Whitespace, 1)?;
+    }
+
+    builder.close_at(&c, Root)?;
+    builder.build()
+}
+
+/// Find the token at `offset` by descending from the roots through the
+/// children which cover it.
+fn descend(tree: &Tree<Syntax>, offset: usize) -> Option<Node<'_, Syntax>> {
+    let mut node = tree.children().find(|n| n.range().contains(&offset))?;
+
+    while node.kind() == Kind::Node {
+        node = node.children().find(|n| n.range().contains(&offset))?;
+    }
+
+    Some(node)
+}
+
+fn setup(c: &mut Criterion) {
+    let sizes = [1024, 16384, 262144];
+
+    let mut group = c.benchmark_group("token_at");
+
+    for size in sizes {
+        let tree = build(size).expect("failed to build tree");
+        let index = TokenIndex::new(&tree);
+        let end = tree.span().range().end;
+        let offsets = (0..end).step_by(end / 64).collect::<Vec<_>>();
+
+        for &offset in &offsets {
+            assert_eq!(descend(&tree, offset), index.token_at(offset));
+        }
+
+        group.bench_with_input(BenchmarkId::new("descend", size), &tree, |b, tree| {
+            b.iter(|| offsets.iter().filter_map(|&o| descend(tree, o)).count())
+        });
+
+        group.bench_with_input(BenchmarkId::new("token_index", size), &index, |b, index| {
+            b.iter(|| offsets.iter().filter_map(|&o| index.token_at(o)).count())
+        });
+    }
+}
+
+criterion_group!(benches, setup);
+criterion_main!(benches);
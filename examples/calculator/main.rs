@@ -12,7 +12,7 @@ mod lexer;
 mod parsing;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 enum Syntax {
     NUMBER,
     PLUS,
@@ -23,19 +23,17 @@ impl<'a> Parser<'a> {
 
     /// Peek the next token.
     pub fn peek(&mut self) -> Result<Token, Error> {
-        loop {
-            // Fill up buffer.
-            self.fill()?;
-
-            if let Some(tok) = self.buf {
-                return Ok(tok);
-            }
+        // Fill up buffer.
+        self.fill()?;
 
-            return Ok(Token {
-                len: 0,
-                syntax: EOF,
-            });
+        if let Some(tok) = self.buf {
+            return Ok(tok);
         }
+
+        Ok(Token {
+            len: 0,
+            syntax: EOF,
+        })
     }
 
     /// Test if the parser is currently at EOF.
@@ -77,7 +75,7 @@ impl<'a> Parser<'a> {
         loop {
             let t = self.peek()?;
 
-            if t.syntax == EOF || any.iter().any(|s| *s == t.syntax) {
+            if t.syntax == EOF || any.contains(&t.syntax) {
                 break;
             }
 
@@ -0,0 +1,62 @@
+//! Example that feeds the output of a hand-rolled lexer into a builder.
+//!
+//! Lexers commonly skip trivia such as whitespace, which leaves gaps between
+//! the ranges of the tokens they produce. These gaps are filled with
+//! whitespace tokens here so that the tree covers the whole source.
+
+use std::ops::Range;
+
+use anyhow::Result;
+use syntree::{print, Builder};
+
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)]
+enum Syntax {
+    STATEMENT,
+    IDENT,
+    NUMBER,
+    PUNCT,
+    WHITESPACE,
+}
+
+use Syntax::{IDENT, NUMBER, PUNCT, STATEMENT, WHITESPACE};
+
+/// A lexer which skips whitespace and produces the range of every token.
+fn lexer(source: &str) -> impl Iterator<Item = (Syntax, Range<usize>)> + '_ {
+    let mut it = source.char_indices().peekable();
+
+    std::iter::from_fn(move || {
+        let (start, c) = it.find(|(_, c)| !c.is_whitespace())?;
+
+        let syntax = match c {
+            'a'..='z' => {
+                while it.next_if(|(_, c)| c.is_ascii_alphanumeric()).is_some() {}
+                IDENT
+            }
+            '0'..='9' => {
+                while it.next_if(|(_, c)| c.is_ascii_digit()).is_some() {}
+                NUMBER
+            }
+            _ => PUNCT,
+        };
+
+        let end = it.peek().map_or(source.len(), |(n, _)| *n);
+        Some((syntax, start..end))
+    })
+}
+
+fn main() -> Result<()> {
+    let source = "let answer = 42;";
+
+    let mut tree = Builder::new();
+
+    tree.open(STATEMENT)?;
+    tree.extend_from_lexer_with_gaps(lexer(source), WHITESPACE)?;
+    tree.close()?;
+
+    let tree = tree.build()?;
+
+    assert_eq!(tree.span().range(), 0..source.len());
+    print::print_with_source(std::io::stdout(), &tree, source)?;
+    Ok(())
+}
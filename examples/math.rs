@@ -5,7 +5,7 @@ use std::iter::Peekable;
 use syntree::{print, Builder, Error, Tree};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 enum Syntax {
     ADD,
     SUB,
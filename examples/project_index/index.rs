@@ -0,0 +1,315 @@
+//! Parsing and indexing of a project made up of several small files.
+//!
+//! Each file consists of lines of the form `def <name>`, which defines a
+//! symbol, or `use <name>`, which refers to a symbol defined in any file of
+//! the project.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::Result;
+use syntree::{Builder, Id, IdMap, LineIndex, Node, Span, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Root,
+    Def,
+    Use,
+    Keyword,
+    Ident,
+    Whitespace,
+    Newline,
+    Error,
+}
+
+/// A parsed file together with its source.
+pub struct File {
+    pub name: String,
+    pub source: String,
+    pub tree: Tree<Syntax, Span>,
+    pub lines: LineIndex,
+}
+
+impl File {
+    /// Parse the file `name` with the given `source`.
+    pub fn parse(name: &str, source: &str) -> Result<Self> {
+        let mut b = Builder::new();
+        b.open(Syntax::Root)?;
+
+        for line in source.split_inclusive('\n') {
+            let (line, newline) = match line.strip_suffix('\n') {
+                Some(line) => (line, true),
+                None => (line, false),
+            };
+
+            parse_line(&mut b, line)?;
+
+            if newline {
+                b.token(Syntax::Newline, 1)?;
+            }
+        }
+
+        b.close()?;
+
+        Ok(Self {
+            name: name.to_owned(),
+            source: source.to_owned(),
+            tree: b.build()?,
+            lines: LineIndex::new(source),
+        })
+    }
+
+    /// Get the source text of the given node.
+    pub fn text(&self, node: Node<'_, Syntax, Span>) -> &str {
+        &self.source[node.range()]
+    }
+
+    /// Get the identifier of a `def` or `use` item.
+    pub fn ident<'a>(&'a self, item: Node<'a, Syntax, Span>) -> Option<Node<'a, Syntax, Span>> {
+        item.children().find(|n| *n.value() == Syntax::Ident)
+    }
+
+    /// Format the position of the given node as `name:line:column`.
+    pub fn position(&self, node: Node<'_, Syntax, Span>) -> String {
+        match self.lines.line_column(node.range().start) {
+            Some(position) => format!("{}:{position}", self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Parse a single line without its terminating newline.
+fn parse_line(b: &mut Builder<Syntax, Span>, line: &str) -> Result<()> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+
+    if indent > 0 {
+        b.token(Syntax::Whitespace, indent)?;
+    }
+
+    if trimmed.is_empty() {
+        return Ok(());
+    }
+
+    let keyword = word(trimmed);
+
+    let item = match keyword {
+        "def" => Syntax::Def,
+        "use" => Syntax::Use,
+        _ => {
+            b.token(Syntax::Error, trimmed.len())?;
+            return Ok(());
+        }
+    };
+
+    b.open(item)?;
+    b.token(Syntax::Keyword, keyword.len())?;
+
+    let rest = &trimmed[keyword.len()..];
+    let name = rest.trim_start();
+
+    if rest.len() > name.len() {
+        b.token(Syntax::Whitespace, rest.len() - name.len())?;
+    }
+
+    let ident = word(name);
+
+    if !ident.is_empty() {
+        b.token(Syntax::Ident, ident.len())?;
+    }
+
+    if name.len() > ident.len() {
+        b.token(Syntax::Error, name.len() - ident.len())?;
+    }
+
+    b.close()?;
+    Ok(())
+}
+
+/// Get the leading word of `s`.
+fn word(s: &str) -> &str {
+    let end = s.find(char::is_whitespace).unwrap_or(s.len());
+    &s[..end]
+}
+
+/// A reference to a node in a particular file of a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub file: usize,
+    pub id: Id,
+}
+
+/// A project of files with a symbol index.
+pub struct Project {
+    pub files: Vec<File>,
+    /// Definitions by name.
+    definitions: HashMap<String, Symbol>,
+    /// For each file, the definition each `use` item resolves to.
+    resolved: Vec<IdMap<Symbol>>,
+    /// Diagnostics as positioned messages.
+    diagnostics: Vec<(Symbol, String)>,
+}
+
+impl Project {
+    /// Parse and index the given files.
+    pub fn new(files: &[(&str, &str)]) -> Result<Self> {
+        let files = files
+            .iter()
+            .map(|(name, source)| File::parse(name, source))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut project = Self {
+            resolved: files
+                .iter()
+                .map(|f| IdMap::with_capacity_for(&f.tree))
+                .collect(),
+            files,
+            definitions: HashMap::new(),
+            diagnostics: Vec::new(),
+        };
+
+        project.index();
+        project.resolve();
+
+        // Report diagnostics in the order of the files and their positions.
+        let files = &project.files;
+
+        project.diagnostics.sort_by_key(|(symbol, _)| {
+            let start = files[symbol.file]
+                .tree
+                .get(symbol.id)
+                .map(|n| n.range().start);
+
+            (symbol.file, start)
+        });
+
+        Ok(project)
+    }
+
+    /// Get the node a symbol refers to.
+    pub fn node(&self, symbol: Symbol) -> Option<Node<'_, Syntax, Span>> {
+        self.files.get(symbol.file)?.tree.get(symbol.id)
+    }
+
+    /// Look up the definition of `name`.
+    pub fn definition(&self, name: &str) -> Option<Symbol> {
+        self.definitions.get(name).copied()
+    }
+
+    /// Get the definition the `use` item `symbol` resolves to.
+    pub fn resolution(&self, symbol: Symbol) -> Option<Symbol> {
+        self.resolved.get(symbol.file)?.get(symbol.id).copied()
+    }
+
+    /// Find the `use` items in the given file in document order.
+    pub fn uses(&self, file: usize) -> impl Iterator<Item = Symbol> + '_ {
+        self.files[file]
+            .tree
+            .walk()
+            .filter(|n| *n.value() == Syntax::Use)
+            .map(move |n| Symbol { file, id: n.id() })
+    }
+
+    /// Render diagnostics with their positions.
+    pub fn render_diagnostics(&self) -> String {
+        let mut out = String::new();
+
+        for &(symbol, ref message) in &self.diagnostics {
+            let file = &self.files[symbol.file];
+
+            let Some(node) = file.tree.get(symbol.id) else {
+                continue;
+            };
+
+            let _ = writeln!(out, "{}: error: {message}", file.position(node));
+
+            if let Some(range) = file
+                .lines
+                .line_column(node.range().start)
+                .and_then(|p| file.lines.line_range(p.line))
+            {
+                let column = node.range().start - range.start;
+                let _ = writeln!(out, "  | {}", &file.source[range]);
+                let _ = writeln!(
+                    out,
+                    "  | {:column$}{:^<len$}",
+                    "",
+                    "",
+                    len = node.range().len()
+                );
+            }
+        }
+
+        out
+    }
+
+    /// Collect all definitions, reporting duplicates.
+    fn index(&mut self) {
+        for (index, file) in self.files.iter().enumerate() {
+            for item in file.tree.walk().filter(|n| *n.value() == Syntax::Def) {
+                let Some(ident) = file.ident(item) else {
+                    self.diagnostics.push((
+                        Symbol {
+                            file: index,
+                            id: item.id(),
+                        },
+                        String::from("definition without a name"),
+                    ));
+                    continue;
+                };
+
+                let name = file.text(ident);
+
+                let symbol = Symbol {
+                    file: index,
+                    id: ident.id(),
+                };
+
+                if let Some(&existing) = self.definitions.get(name) {
+                    let previous = &self.files[existing.file];
+
+                    let Some(node) = previous.tree.get(existing.id) else {
+                        continue;
+                    };
+
+                    self.diagnostics.push((
+                        symbol,
+                        format!("`{name}` is already defined at {}", previous.position(node)),
+                    ));
+
+                    continue;
+                }
+
+                self.definitions.insert(name.to_owned(), symbol);
+            }
+        }
+    }
+
+    /// Resolve all `use` items against the collected definitions.
+    fn resolve(&mut self) {
+        for (index, file) in self.files.iter().enumerate() {
+            for item in file.tree.walk().filter(|n| *n.value() == Syntax::Use) {
+                let Some(ident) = file.ident(item) else {
+                    continue;
+                };
+
+                let name = file.text(ident);
+
+                match self.definitions.get(name) {
+                    Some(&definition) => {
+                        self.resolved[index].insert(item.id(), definition);
+                    }
+                    None => {
+                        self.diagnostics.push((
+                            Symbol {
+                                file: index,
+                                id: ident.id(),
+                            },
+                            format!("unresolved name `{name}`"),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Indexing a project made up of several files, resolving references
+//! between them and reporting diagnostics with line and column positions.
+
+use anyhow::Result;
+
+mod index;
+
+use self::index::Project;
+
+const FILES: &[(&str, &str)] = &[
+    ("math.txt", "def add\ndef sub\n"),
+    ("main.txt", "use add\n  use mul\nuse div\ndef main\n"),
+    ("util.txt", "def mul\ndef add\nuse sub\n"),
+];
+
+fn main() -> Result<()> {
+    let project = Project::new(FILES)?;
+
+    for (n, file) in project.files.iter().enumerate() {
+        for symbol in project.uses(n) {
+            let Some(item) = project.node(symbol) else {
+                continue;
+            };
+
+            let Some(name) = file.ident(item).map(|ident| file.text(ident)) else {
+                continue;
+            };
+
+            let Some(definition) = project.resolution(symbol) else {
+                println!("{}: `{name}` is unresolved", file.position(item));
+                continue;
+            };
+
+            let target = &project.files[definition.file];
+
+            if let Some(node) = project.node(definition) {
+                println!(
+                    "{}: `{name}` resolves to {}",
+                    file.position(item),
+                    target.position(node)
+                );
+            }
+        }
+    }
+
+    print!("{}", project.render_diagnostics());
+
+    if let Some(main) = project.definition("main") {
+        println!("entrypoint is defined in {}", project.files[main.file].name);
+    }
+
+    Ok(())
+}
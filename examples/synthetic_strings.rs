@@ -8,6 +8,7 @@ use anyhow::Result;
 use syntree::{print, Builder};
 
 #[derive(Debug, Clone, Copy)]
+#[allow(clippy::upper_case_acronyms)]
 enum Syntax {
     /// A string referenced somewhere else using the provided ID.
     SYNTHETIC(Option<usize>),
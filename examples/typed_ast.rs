@@ -0,0 +1,169 @@
+//! Layering a typed abstract syntax tree on top of a syntree.
+
+use anyhow::{Context, Result};
+use syntree::node::TypedNode;
+use syntree::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Root,
+    FnDef,
+    Param,
+    Ident,
+    Whitespace,
+    Fn,
+    OpenParen,
+    CloseParen,
+    Comma,
+}
+
+syntree::typed_node! {
+    /// The root of a source file.
+    struct Root(Syntax) where value == Syntax::Root;
+    /// A function definition.
+    struct FnDef(Syntax) where value == Syntax::FnDef;
+    /// A parameter of a function definition.
+    struct Param(Syntax) where value == Syntax::Param;
+    /// An identifier.
+    struct Ident(Syntax) where value == Syntax::Ident;
+}
+
+impl<'a> Root<'a> {
+    fn functions(&self) -> impl Iterator<Item = FnDef<'a>> {
+        self.node().children_of_type()
+    }
+}
+
+impl<'a> FnDef<'a> {
+    fn name(&self) -> Option<Ident<'a>> {
+        self.node().first_of_type()
+    }
+
+    fn params(&self) -> impl Iterator<Item = Param<'a>> {
+        self.node().children_of_type()
+    }
+}
+
+impl<'a> Param<'a> {
+    fn name(&self) -> Option<Ident<'a>> {
+        self.node().first_of_type()
+    }
+}
+
+impl Ident<'_> {
+    fn text<'s>(&self, source: &'s str) -> &'s str {
+        &source[self.node().range()]
+    }
+}
+
+fn parse(source: &str) -> Result<syntree::Tree<Syntax, Span>> {
+    // A tiny hand-rolled parser for `fn <ident>(<ident>, ...)` definitions
+    // separated by whitespace.
+    let mut tree = syntree::Builder::new();
+    let mut it = source.char_indices().peekable();
+
+    tree.open(Syntax::Root)?;
+
+    while let Some(&(start, c)) = it.peek() {
+        if c.is_whitespace() {
+            let len = eat(&mut it, start, char::is_whitespace);
+            tree.token(Syntax::Whitespace, len)?;
+            continue;
+        }
+
+        let len = eat(&mut it, start, char::is_alphanumeric);
+        anyhow::ensure!(
+            &source[start..start + len] == "fn",
+            "expected `fn` at {start}"
+        );
+
+        tree.open(Syntax::FnDef)?;
+        tree.token(Syntax::Fn, len)?;
+
+        // Identifiers inside of the parenthesis are parameters.
+        let mut in_params = false;
+
+        while let Some(&(start, c)) = it.peek() {
+            match c {
+                '(' => {
+                    it.next();
+                    tree.token(Syntax::OpenParen, 1)?;
+                    in_params = true;
+                }
+                ',' => {
+                    it.next();
+                    tree.token(Syntax::Comma, 1)?;
+                }
+                ')' => {
+                    it.next();
+                    tree.token(Syntax::CloseParen, 1)?;
+                    break;
+                }
+                c if c.is_whitespace() => {
+                    let len = eat(&mut it, start, char::is_whitespace);
+                    tree.token(Syntax::Whitespace, len)?;
+                }
+                _ => {
+                    let len = eat(&mut it, start, char::is_alphanumeric);
+                    anyhow::ensure!(len > 0, "unexpected `{c}` at {start}");
+
+                    if in_params {
+                        tree.open(Syntax::Param)?;
+                        tree.token(Syntax::Ident, len)?;
+                        tree.close()?;
+                    } else {
+                        tree.token(Syntax::Ident, len)?;
+                    }
+                }
+            }
+        }
+
+        tree.close()?;
+    }
+
+    tree.close()?;
+    Ok(tree.build()?)
+}
+
+fn eat(
+    it: &mut std::iter::Peekable<std::str::CharIndices<'_>>,
+    start: usize,
+    cond: fn(char) -> bool,
+) -> usize {
+    let mut end = start;
+
+    while let Some(&(n, c)) = it.peek() {
+        if !cond(c) {
+            break;
+        }
+
+        end = n + c.len_utf8();
+        it.next();
+    }
+
+    end - start
+}
+
+fn main() -> Result<()> {
+    let source = "fn add(a, b) fn neg(a)";
+    let tree = parse(source)?;
+
+    let root = tree.first().and_then(Root::cast).context("missing root")?;
+
+    let mut signatures = Vec::new();
+
+    for function in root.functions() {
+        let name = function.name().context("missing name")?;
+
+        let params = function
+            .params()
+            .flat_map(|p| p.name())
+            .map(|ident| ident.text(source))
+            .collect::<Vec<_>>();
+
+        signatures.push(format!("{}/{}", name.text(source), params.join(",")));
+    }
+
+    assert_eq!(signatures, ["add/a,b", "neg/a"]);
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Exercises the parts of syntree which are available without the standard
+//! library, so that building this crate verifies that they keep compiling
+//! under `#![no_std]`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use syntree::diff::{self, DiffOptions};
+use syntree::edit::ChangeSet;
+use syntree::print::Labels;
+use syntree::sexpr::{self, ParseError};
+use syntree::{Builder, Error, Span, Tree};
+
+/// Build a small tree.
+pub fn build() -> Result<Tree<&'static str>, Error> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    tree.token("number", 3)?;
+    let c = tree.checkpoint()?;
+    tree.token("ident", 2)?;
+    tree.close_at(&c, "wrap")?;
+    tree.close()?;
+
+    tree.build()
+}
+
+/// Walk a tree, collecting its values.
+pub fn walk(tree: &Tree<&'static str>) -> Vec<&'static str> {
+    tree.walk().map(|n| *n.value()).collect()
+}
+
+/// Edit a tree, removing its first token.
+pub fn edit(tree: &Tree<&'static str>) -> Result<Tree<&'static str>, Error> {
+    let mut change = ChangeSet::new();
+
+    if let Some(node) = tree.first().and_then(|n| n.first()) {
+        change.remove(node.id());
+    }
+
+    change.modify(tree)
+}
+
+/// Render the differences between two trees.
+pub fn render_diff(a: &Tree<&'static str>, b: &Tree<&'static str>) -> String {
+    let mut out = String::new();
+    _ = diff::write_diff(&mut out, a, b, &DiffOptions::new());
+    _ = write!(out, "{}", diff::diff(a, b).len());
+    out
+}
+
+/// Parse a tree with labelled values.
+pub fn parse_labeled(input: &str) -> Result<Tree<u32, Span>, ParseError> {
+    let labels = [(1, "NUMBER"), (2, "IDENT")]
+        .into_iter()
+        .collect::<Labels<u32>>();
+    sexpr::from_sexpr_labeled(input, &labels)
+}
+
+/// Errors implement `core::error::Error` through the `core-error` feature.
+pub fn boxed_error(error: Error) -> Box<dyn core::error::Error> {
+    Box::new(error)
+}
@@ -0,0 +1,208 @@
+//! Generation of arbitrary trees for fuzzing and property testing.
+//!
+//! This is enabled through the `arbitrary` feature, which implements
+//! [`Arbitrary`] for [`Tree`] and provides a [`Generator`] to control the
+//! shape of generated trees.
+//!
+//! Trees are generated through a [`Builder`], so they uphold the same
+//! invariants as any other tree. Structure is always balanced and spans are
+//! contiguous, starting at zero.
+//!
+//! [`Builder`]: crate::Builder
+//!
+//! # Examples
+//!
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use syntree::Tree;
+//!
+//! let data = [7, 42, 1, 3, 200, 9, 4, 8, 31, 2, 77, 5];
+//! let mut u = Unstructured::new(&data);
+//!
+//! let tree = Tree::<u8>::arbitrary(&mut u)?;
+//! assert_eq!(tree.walk().count(), tree.len());
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::builder::Builder;
+use crate::span::{Index, TreeSpan};
+use crate::tree::Tree;
+
+/// Generates structurally valid trees with a configurable shape from
+/// unstructured data.
+///
+/// This is what the [`Arbitrary`] implementation of [`Tree`] uses with its
+/// default settings.
+///
+/// # Examples
+///
+/// ```
+/// use arbitrary::Unstructured;
+/// use syntree::arbitrary::Generator;
+/// use syntree::Tree;
+///
+/// let generator = Generator::new()
+///     .max_depth(2)
+///     .max_children(3)
+///     .token_len(1..=4);
+///
+/// let data = (0..=255).collect::<Vec<u8>>();
+/// let mut u = Unstructured::new(&data);
+///
+/// let tree: Tree<&str> = generator.generate(&mut u, |u| {
+///     Ok(*u.choose(&["ident", "number", "ws"])?)
+/// })?;
+///
+/// assert!(tree.children().len() <= 3);
+///
+/// for (depth, node) in tree.walk().with_depths() {
+///     assert!(depth < 2);
+///     assert!(node.children().len() <= 3);
+///
+///     if node.kind() == syntree::Kind::Token {
+///         assert!((1..=4).contains(&node.range().len()));
+///     }
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generator {
+    max_depth: usize,
+    max_children: usize,
+    token_len: RangeInclusive<usize>,
+}
+
+impl Generator {
+    /// Construct a generator with the default settings, which generates trees
+    /// at most 8 levels deep with at most 8 children per node and tokens
+    /// which are up to 16 long.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_depth: 8,
+            max_children: 8,
+            token_len: 0..=16,
+        }
+    }
+
+    /// Limit the depth of generated trees, where the roots of the tree are at
+    /// depth one. Nodes at the maximum depth are always tokens.
+    #[must_use]
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Limit the number of children of each node, and the number of roots of
+    /// the tree.
+    #[must_use]
+    pub fn max_children(self, max_children: usize) -> Self {
+        Self {
+            max_children,
+            ..self
+        }
+    }
+
+    /// Set the range of lengths for generated tokens, from which lengths are
+    /// picked uniformly. This has no effect if the span of the tree is
+    /// [`Empty`].
+    ///
+    /// [`Empty`]: crate::span::Empty
+    #[must_use]
+    pub fn token_len(self, token_len: RangeInclusive<usize>) -> Self {
+        Self { token_len, ..self }
+    }
+
+    /// Generate a tree from `u`, where values are generated using `value`.
+    ///
+    /// Generation stops gracefully once `u` is exhausted, so this produces a
+    /// possibly empty but valid tree for any input.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `value` errors, or with
+    /// [`Error::IncorrectFormat`][arbitrary::Error::IncorrectFormat] if the
+    /// range of token lengths is empty or the total length of the tree
+    /// overflows.
+    pub fn generate<'a, T, S, F>(
+        &self,
+        u: &mut Unstructured<'a>,
+        mut value: F,
+    ) -> arbitrary::Result<Tree<T, S>>
+    where
+        S: TreeSpan,
+        F: FnMut(&mut Unstructured<'a>) -> arbitrary::Result<T>,
+    {
+        if self.token_len.is_empty() {
+            return Err(arbitrary::Error::IncorrectFormat);
+        }
+
+        let mut b = Builder::new_with();
+        let mut cursor: Index = 0;
+
+        // Each entry is the number of children left to generate at a depth,
+        // starting with the roots of the tree.
+        let mut stack = Vec::new();
+
+        if self.max_depth > 0 {
+            stack.push(u.int_in_range(0..=self.max_children)?);
+        }
+
+        while let Some(remaining) = stack.last_mut() {
+            let Some(next) = remaining.checked_sub(1) else {
+                stack.pop();
+
+                if !stack.is_empty() {
+                    b.close().map_err(|_| arbitrary::Error::IncorrectFormat)?;
+                }
+
+                continue;
+            };
+
+            *remaining = next;
+            let value = value(u)?;
+
+            if stack.len() < self.max_depth && u.arbitrary::<bool>()? {
+                b.open(value)
+                    .map_err(|_| arbitrary::Error::IncorrectFormat)?;
+                stack.push(u.int_in_range(0..=self.max_children)?);
+                continue;
+            }
+
+            let len = u.int_in_range(self.token_len.clone())?;
+
+            let end = Index::try_from(len)
+                .ok()
+                .and_then(|len| cursor.checked_add(len))
+                .ok_or(arbitrary::Error::IncorrectFormat)?;
+
+            b.token_with_span(value, S::new(cursor, end))
+                .map_err(|_| arbitrary::Error::IncorrectFormat)?;
+            cursor = end;
+        }
+
+        b.build().map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+impl Default for Generator {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, S> Arbitrary<'a> for Tree<T, S>
+where
+    T: Arbitrary<'a>,
+    S: TreeSpan,
+{
+    #[inline]
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Generator::new().generate(u, T::arbitrary)
+    }
+}
@@ -0,0 +1,766 @@
+//! A compact, self-describing binary encoding of trees.
+//!
+//! This is enabled through the `binary` feature, and is used through
+//! [`write()`] and [`read()`], or equivalently [`Tree::encode`], [`Tree::decode`]
+//! and [`Tree::decode_compat`].
+//!
+//! An encoded tree starts with a small header, which consists of:
+//! * The magic bytes `SYNT`.
+//! * A format version, currently [`VERSION`].
+//! * The width in bytes of the indexes used by spans, which is 4 if
+//!   `syntree_compact` is enabled and otherwise the width of `usize`.
+//! * A tag identifying the span type, `0` for [`Span`] and `1` for [`Empty`].
+//!
+//! The header is followed by the events of the tree in depth-first order,
+//! where values are encoded through [`Encode`] and lengths are encoded as
+//! variable-length integers. Every node and token records the gap from the
+//! end of the previous token to where it starts together with its flags and
+//! marks, and tokens record their lengths. Marks and whether a gap or flags
+//! follow are stored in the same byte as the event, so trees without them
+//! are encoded as compactly as in version 1. Spans are recomputed from these
+//! when decoding, in the same way as when using a [`Builder`] with
+//! [`CursorPolicy::Monotonic`].
+//!
+//! Trees whose tokens aren't in document order, which can be constructed
+//! through [`CursorPolicy::Unordered`], can't be encoded.
+//!
+//! [`Builder`]: crate::Builder
+//! [`CursorPolicy::Monotonic`]: crate::CursorPolicy::Monotonic
+//! [`CursorPolicy::Unordered`]: crate::CursorPolicy::Unordered
+//! [`Span`]: crate::Span
+//! [`Empty`]: crate::span::Empty
+//!
+//! # Examples
+//!
+//! ```
+//! use syntree::Tree;
+//!
+//! let tree = syntree::tree! {
+//!     "root" => {
+//!         ("lit", 3),
+//!         ("whitespace", 1),
+//!     }
+//! };
+//!
+//! let mut out = Vec::new();
+//! tree.encode(&mut out)?;
+//!
+//! let decoded = Tree::<String>::decode(&out[..])?;
+//! assert!(tree.walk().map(|n| *n.value()).eq(decoded.walk().map(|n| n.value().as_str())));
+//! assert_eq!(decoded.range(), 0..4);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem::size_of;
+use std::io::{self, Read};
+
+use crate::builder::{Builder, CursorPolicy};
+use crate::error::Error;
+use crate::links::{MARK_ERROR, MARK_TRIVIA};
+use crate::node::Event;
+use crate::span::{Empty, Index, Span, TreeSpan};
+use crate::tree::{Kind, Tree};
+
+/// The magic bytes every encoded tree starts with.
+const MAGIC: [u8; 4] = *b"SYNT";
+
+/// The current version of the format, which is the version written by
+/// [`Tree::encode`].
+pub const VERSION: u8 = 2;
+
+/// The width of the indexes used by spans.
+const INDEX_WIDTH: u8 = size_of::<Index>() as u8;
+
+/// Event tags.
+const ENTER: u8 = 0;
+const TOKEN: u8 = 1;
+const LEAVE: u8 = 2;
+const END: u8 = 3;
+
+/// Mask for the event tag in the first byte of an event.
+const EVENT: u8 = 0b11;
+/// Set if the event is followed by a gap.
+const HAS_GAP: u8 = 1 << 2;
+/// Set if the event is followed by flags.
+const HAS_FLAGS: u8 = 1 << 3;
+/// Marks are stored shifted by this amount in the first byte of an event.
+const MARKS_SHIFT: u32 = 4;
+/// All bits which can be set in the first byte of an event.
+const KNOWN: u8 = EVENT | HAS_GAP | HAS_FLAGS | (MARK_ERROR | MARK_TRIVIA) << MARKS_SHIFT;
+
+/// Error raised while decoding a tree.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The input is corrupt, with a description of what was wrong.
+    ///
+    /// This includes input which is truncated.
+    Corrupt(&'static str),
+    /// The input was written with a version of the format which is not
+    /// supported.
+    ///
+    /// Older versions might still be supported through
+    /// [`Tree::decode_compat`].
+    UnsupportedVersion(u8),
+    /// The input was written with a different index width than the one in use,
+    /// which happens if `syntree_compact` differs between the writer and the
+    /// reader.
+    IndexWidth {
+        /// The index width in use.
+        expected: u8,
+        /// The index width of the input.
+        actual: u8,
+    },
+    /// The input was written with a different span type than the one being
+    /// decoded.
+    SpanMismatch {
+        /// The tag of the span being decoded.
+        expected: u8,
+        /// The tag of the span in the input.
+        actual: u8,
+    },
+    /// An I/O error occurred while reading the input.
+    Io(io::Error),
+}
+
+impl From<io::Error> for DecodeError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        if error.kind() == io::ErrorKind::UnexpectedEof {
+            return DecodeError::Corrupt("unexpected end of input");
+        }
+
+        DecodeError::Io(error)
+    }
+}
+
+impl From<Error> for DecodeError {
+    #[inline]
+    fn from(error: Error) -> Self {
+        DecodeError::Corrupt(match error {
+            Error::CloseError => "leaving a node which was never entered",
+            Error::BuildError => "nodes left open at end of input",
+            Error::Overflow => "numerical overflow",
+            _ => "invalid tree structure",
+        })
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Corrupt(reason) => {
+                write!(f, "corrupt input: {reason}")
+            }
+            DecodeError::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "unsupported format version {version}, expected version {VERSION}"
+                )
+            }
+            DecodeError::IndexWidth { expected, actual } => {
+                write!(
+                    f,
+                    "input uses {actual} byte indexes, but {expected} byte indexes are in use"
+                )
+            }
+            DecodeError::SpanMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "input uses span type {actual}, but span type {expected} is being decoded"
+                )
+            }
+            DecodeError::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+/// Trait for values which can be encoded in a tree.
+pub trait Encode {
+    /// Encode the value into `out`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing to `out` fails.
+    fn encode<W>(&self, out: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write;
+}
+
+/// Trait for values which can be decoded from a tree.
+pub trait Decode: Sized {
+    /// Decode a value from `input`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if reading from `input` fails or if the input is corrupt.
+    fn decode<R>(input: &mut R) -> Result<Self, DecodeError>
+    where
+        R: ?Sized + io::Read;
+}
+
+/// Trait for spans which can be encoded, which is implemented for [`Span`]
+/// and [`Empty`].
+pub trait EncodeSpan: TreeSpan {
+    #[doc(hidden)]
+    const TAG: u8;
+
+    #[doc(hidden)]
+    fn encode_length<W>(length: Self::Length, out: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write;
+
+    #[doc(hidden)]
+    fn decode_length<R>(input: &mut R) -> Result<Self::Length, DecodeError>
+    where
+        R: ?Sized + io::Read;
+}
+
+impl EncodeSpan for Span {
+    const TAG: u8 = 0;
+
+    #[inline]
+    fn encode_length<W>(length: usize, out: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        length.encode(out)
+    }
+
+    #[inline]
+    fn decode_length<R>(input: &mut R) -> Result<usize, DecodeError>
+    where
+        R: ?Sized + io::Read,
+    {
+        usize::decode(input)
+    }
+}
+
+impl EncodeSpan for Empty {
+    const TAG: u8 = 1;
+
+    #[inline]
+    fn encode_length<W>(_: Empty, _: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        Ok(())
+    }
+
+    #[inline]
+    fn decode_length<R>(_: &mut R) -> Result<Empty, DecodeError>
+    where
+        R: ?Sized + io::Read,
+    {
+        Ok(Empty)
+    }
+}
+
+/// Write `tree` to `out` in the binary format.
+///
+/// This is the same as [`Tree::encode`].
+///
+/// # Errors
+///
+/// Errors if writing to `out` fails, or with [`io::ErrorKind::InvalidInput`]
+/// if the tokens of the tree are not in document order.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::binary;
+///
+/// let tree = syntree::tree! {
+///     1u32 => {
+///         (2u32, 3),
+///     }
+/// };
+///
+/// let mut out = Vec::new();
+/// binary::write(&tree, &mut out)?;
+///
+/// let decoded = binary::read::<u32, syntree::Span, _>(&mut &out[..])?;
+/// assert_eq!(decoded, tree);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn write<T, S, W>(tree: &Tree<T, S>, out: &mut W) -> io::Result<()>
+where
+    T: Encode,
+    S: EncodeSpan,
+    W: ?Sized + io::Write,
+{
+    encode(tree, out)
+}
+
+/// Read a tree from `input` which was written through [`write()`] or
+/// [`Tree::encode`].
+///
+/// This is the same as [`Tree::decode`]. The input is validated as it is
+/// read, so events have to be balanced and spans are recomputed from the
+/// gaps and lengths of tokens, which keeps them monotonic.
+///
+/// # Errors
+///
+/// Errors with [`DecodeError::Corrupt`] if the input is malformed or
+/// truncated, or with one of the other [`DecodeError`] variants if it was
+/// written with an incompatible configuration.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::binary::{self, DecodeError};
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("lit", 3),
+///     }
+/// };
+///
+/// let mut out = Vec::new();
+/// binary::write(&tree, &mut out)?;
+///
+/// let result = binary::read::<String, syntree::Span, _>(&mut &out[..out.len() - 1]);
+/// assert!(matches!(result, Err(DecodeError::Corrupt(..))));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn read<T, S, R>(input: &mut R) -> Result<Tree<T, S>, DecodeError>
+where
+    T: Decode,
+    S: EncodeSpan,
+    R: ?Sized + io::Read,
+{
+    decode(input, false)
+}
+
+/// Encode `tree` into `out`, see [`Tree::encode`].
+pub(crate) fn encode<T, S, W>(tree: &Tree<T, S>, out: &mut W) -> io::Result<()>
+where
+    T: Encode,
+    S: EncodeSpan,
+    W: ?Sized + io::Write,
+{
+    out.write_all(&MAGIC)?;
+    out.write_all(&[VERSION, INDEX_WIDTH, S::TAG])?;
+
+    let mut cursor = 0;
+
+    for (event, node) in tree.walk_events() {
+        if let Event::Up = event {
+            out.write_all(&[LEAVE])?;
+            continue;
+        }
+
+        let span = node.span();
+        let gap = gap::<S>(cursor, span.start())?;
+
+        let tag = match node.kind() {
+            Kind::Token => TOKEN,
+            Kind::Node => ENTER,
+        };
+
+        let mut head = tag | node.marks() << MARKS_SHIFT;
+
+        if cursor != span.start() {
+            head |= HAS_GAP;
+        }
+
+        if node.flags() != 0 {
+            head |= HAS_FLAGS;
+        }
+
+        out.write_all(&[head])?;
+
+        if head & HAS_GAP != 0 {
+            S::encode_length(gap, out)?;
+        }
+
+        if head & HAS_FLAGS != 0 {
+            out.write_all(&[node.flags()])?;
+        }
+
+        if tag == TOKEN {
+            S::encode_length(span.length(), out)?;
+            cursor = span.end();
+        } else {
+            cursor = span.start();
+        }
+
+        node.value().encode(out)?;
+
+        if tag == ENTER && node.is_empty() {
+            out.write_all(&[LEAVE])?;
+        }
+    }
+
+    // Trailing gap at the end of the tree.
+    let end = tree.span().end();
+
+    if cursor == end {
+        return out.write_all(&[END]);
+    }
+
+    out.write_all(&[END | HAS_GAP])?;
+    S::encode_length(gap::<S>(cursor, end)?, out)
+}
+
+/// Calculate the gap between `cursor` and `start`, which errors in case the
+/// tree is not in document order.
+fn gap<S>(cursor: Index, start: Index) -> io::Result<S::Length>
+where
+    S: TreeSpan,
+{
+    if start < cursor {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "tree is not in document order",
+        ));
+    }
+
+    Ok(S::new(cursor, start).length())
+}
+
+/// Decode a tree from `input`, see [`Tree::decode`] and
+/// [`Tree::decode_compat`].
+pub(crate) fn decode<T, S, R>(input: &mut R, compat: bool) -> Result<Tree<T, S>, DecodeError>
+where
+    T: Decode,
+    S: EncodeSpan,
+    R: ?Sized + io::Read,
+{
+    let mut magic = [0; 4];
+    input.read_exact(&mut magic)?;
+
+    if magic != MAGIC {
+        return Err(DecodeError::Corrupt("missing magic bytes"));
+    }
+
+    let [version, width, tag] = read_array(input)?;
+
+    if version != VERSION && !compat {
+        return Err(DecodeError::UnsupportedVersion(version));
+    }
+
+    if width != INDEX_WIDTH {
+        return Err(DecodeError::IndexWidth {
+            expected: INDEX_WIDTH,
+            actual: width,
+        });
+    }
+
+    if tag != S::TAG {
+        return Err(DecodeError::SpanMismatch {
+            expected: S::TAG,
+            actual: tag,
+        });
+    }
+
+    // Readers for every supported version of the format.
+    match version {
+        1 => decode_v1(input),
+        2 => decode_v2(input),
+        version => Err(DecodeError::UnsupportedVersion(version)),
+    }
+}
+
+/// Decode the events of version 1 of the format.
+fn decode_v1<T, S, R>(input: &mut R) -> Result<Tree<T, S>, DecodeError>
+where
+    T: Decode,
+    S: EncodeSpan,
+    R: ?Sized + io::Read,
+{
+    let mut builder = Builder::new_with();
+
+    loop {
+        let [tag] = read_array(input)?;
+
+        match tag {
+            ENTER => {
+                builder.open(T::decode(input)?)?;
+            }
+            TOKEN => {
+                let length = S::decode_length(input)?;
+                builder.token(T::decode(input)?, length)?;
+            }
+            LEAVE => {
+                builder.close()?;
+            }
+            END => break,
+            _ => return Err(DecodeError::Corrupt("unknown event")),
+        }
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Decode the events of version 2 of the format, which in addition to
+/// version 1 stores gaps, flags and marks.
+fn decode_v2<T, S, R>(input: &mut R) -> Result<Tree<T, S>, DecodeError>
+where
+    T: Decode,
+    S: EncodeSpan,
+    R: ?Sized + io::Read,
+{
+    let mut builder = Builder::new_with().with_policy(CursorPolicy::Monotonic);
+
+    loop {
+        let [head] = read_array(input)?;
+
+        if head & !KNOWN != 0 {
+            return Err(DecodeError::Corrupt("unknown event"));
+        }
+
+        if head & HAS_GAP != 0 {
+            builder.advance(S::decode_length(input)?)?;
+        }
+
+        let flags = if head & HAS_FLAGS != 0 {
+            let [flags] = read_array(input)?;
+            flags
+        } else {
+            0
+        };
+
+        let id = match head & EVENT {
+            ENTER => builder.open(T::decode(input)?)?,
+            TOKEN => {
+                let length = S::decode_length(input)?;
+                builder.token(T::decode(input)?, length)?
+            }
+            LEAVE if head == LEAVE => {
+                builder.close()?;
+                continue;
+            }
+            END if head & !HAS_GAP == END => break,
+            _ => return Err(DecodeError::Corrupt("unknown event")),
+        };
+
+        builder.set_flags(id, flags)?;
+        builder.set_marks(id, head >> MARKS_SHIFT)?;
+    }
+
+    Ok(builder.build()?)
+}
+
+fn read_array<R, const N: usize>(input: &mut R) -> io::Result<[u8; N]>
+where
+    R: ?Sized + io::Read,
+{
+    let mut bytes = [0; N];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Write a LEB128 variable-length integer.
+fn write_varint<W>(mut value: u64, out: &mut W) -> io::Result<()>
+where
+    W: ?Sized + io::Write,
+{
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            return out.write_all(&[byte]);
+        }
+
+        out.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Read a LEB128 variable-length integer.
+fn read_varint<R>(input: &mut R) -> Result<u64, DecodeError>
+where
+    R: ?Sized + io::Read,
+{
+    let mut value = 0u64;
+    let mut shift = 0;
+
+    loop {
+        let [byte] = read_array(input)?;
+        let bits = u64::from(byte & 0x7f);
+
+        if shift >= 64 || (shift == 63 && bits > 1) {
+            return Err(DecodeError::Corrupt("integer overflow"));
+        }
+
+        value |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+
+        shift += 7;
+    }
+}
+
+macro_rules! unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                #[inline]
+                fn encode<W>(&self, out: &mut W) -> io::Result<()>
+                where
+                    W: ?Sized + io::Write,
+                {
+                    write_varint(*self as u64, out)
+                }
+            }
+
+            impl Decode for $ty {
+                #[inline]
+                fn decode<R>(input: &mut R) -> Result<Self, DecodeError>
+                where
+                    R: ?Sized + io::Read,
+                {
+                    <$ty>::try_from(read_varint(input)?)
+                        .map_err(|_| DecodeError::Corrupt("integer overflow"))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! signed {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                #[inline]
+                fn encode<W>(&self, out: &mut W) -> io::Result<()>
+                where
+                    W: ?Sized + io::Write,
+                {
+                    // Zigzag encoding, so that small negative numbers are
+                    // small.
+                    let value = *self as i64;
+                    write_varint(((value << 1) ^ (value >> 63)) as u64, out)
+                }
+            }
+
+            impl Decode for $ty {
+                #[inline]
+                fn decode<R>(input: &mut R) -> Result<Self, DecodeError>
+                where
+                    R: ?Sized + io::Read,
+                {
+                    let value = read_varint(input)?;
+                    let value = ((value >> 1) as i64) ^ -((value & 1) as i64);
+                    <$ty>::try_from(value).map_err(|_| DecodeError::Corrupt("integer overflow"))
+                }
+            }
+        )*
+    };
+}
+
+unsigned!(u8, u16, u32, u64, usize);
+signed!(i8, i16, i32, i64, isize);
+
+impl Encode for bool {
+    #[inline]
+    fn encode<W>(&self, out: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        out.write_all(&[u8::from(*self)])
+    }
+}
+
+impl Decode for bool {
+    #[inline]
+    fn decode<R>(input: &mut R) -> Result<Self, DecodeError>
+    where
+        R: ?Sized + io::Read,
+    {
+        match read_array(input)? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(DecodeError::Corrupt("invalid boolean")),
+        }
+    }
+}
+
+impl Encode for () {
+    #[inline]
+    fn encode<W>(&self, _: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        Ok(())
+    }
+}
+
+impl Decode for () {
+    #[inline]
+    fn decode<R>(_: &mut R) -> Result<Self, DecodeError>
+    where
+        R: ?Sized + io::Read,
+    {
+        Ok(())
+    }
+}
+
+impl Encode for str {
+    #[inline]
+    fn encode<W>(&self, out: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.len().encode(out)?;
+        out.write_all(self.as_bytes())
+    }
+}
+
+impl Encode for String {
+    #[inline]
+    fn encode<W>(&self, out: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.as_str().encode(out)
+    }
+}
+
+impl Decode for String {
+    fn decode<R>(input: &mut R) -> Result<Self, DecodeError>
+    where
+        R: ?Sized + io::Read,
+    {
+        let len = usize::decode(input)?;
+
+        // Read through `take` so that a corrupt length doesn't cause a huge
+        // allocation up front.
+        let mut bytes = Vec::new();
+        let read = io::Read::take(&mut *input, len as u64).read_to_end(&mut bytes)?;
+
+        if read != len {
+            return Err(DecodeError::Corrupt("unexpected end of input"));
+        }
+
+        String::from_utf8(bytes).map_err(|_| DecodeError::Corrupt("invalid utf-8"))
+    }
+}
+
+impl<T> Encode for &T
+where
+    T: ?Sized + Encode,
+{
+    #[inline]
+    fn encode<W>(&self, out: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        (**self).encode(out)
+    }
+}
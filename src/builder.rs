@@ -1,18 +1,76 @@
 mod checkpoint;
+mod limits;
+mod open_stack;
+mod policy;
+mod postfix;
+mod recovered;
 
-use core::mem::replace;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter;
+use core::ops::Range;
 
 use crate::error::Error;
-use crate::links::Links;
+use crate::links::{Links, MARK_ERROR, MARK_TRIVIA};
+use crate::node::Node;
 use crate::non_max::NonMax;
 use crate::span::{Index, Indexes, Length, Span, TreeSpan};
 use crate::tree::{Kind, Tree};
 
+use self::limits::Tracker;
+
 pub use self::checkpoint::Checkpoint;
+pub use self::limits::{BuilderLimits, Limit};
+pub use self::open_stack::OpenStack;
+pub use self::policy::CursorPolicy;
+pub use self::postfix::Postfix;
+pub use self::recovered::Recovered;
 
 /// The identifier of a node as returned by functions such as
 /// [`Builder::open`] or [`Builder::token`].
+///
+/// An identifier is the index of the node in the storage of its tree, which is
+/// stored as a 32-bit integer. So a tree can hold at most `u32::MAX` nodes.
+/// Identifiers are ordered by index.
+///
+/// Identifiers are assigned in the order in which nodes are added to the
+/// [`Builder`], starting at zero, and remain valid for as long as the tree
+/// they were taken from is unchanged. This makes [`Id::index`] suitable as a
+/// dense index into side tables of the same size as the tree.
+///
+/// # Examples
+///
+/// Persisting an identifier as a number and resolving it later:
+///
+/// ```
+/// use syntree::Id;
+///
+/// let mut tree = syntree::Builder::new();
+///
+/// tree.open("root")?;
+/// let lit = tree.token("lit", 3)?;
+/// tree.close()?;
+///
+/// let tree = tree.build()?;
+///
+/// let stored: usize = lit.into();
+/// assert_eq!(stored, 1);
+/// assert_eq!(lit.to_string(), "1");
+///
+/// let id = Id::try_from(stored)?;
+/// let node = tree.get(id).ok_or("missing node")?;
+/// assert_eq!(*node.value(), "lit");
+/// assert_eq!(node.range(), 0..3);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "usize", try_from = "usize")
+)]
 #[repr(transparent)]
 pub struct Id(pub(crate) NonMax);
 
@@ -20,6 +78,83 @@ impl Id {
     pub(crate) const fn new(id: NonMax) -> Self {
         Self(id)
     }
+
+    /// Construct an identifier from the index of a node.
+    ///
+    /// Returns `None` if the index is too large to be an identifier. Note
+    /// that the constructed identifier might not refer to a node in any
+    /// particular tree, in which case looking it up with [`Tree::get`]
+    /// returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Id;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(Id::from_index(root.id().index()), Some(root.id()));
+    ///
+    /// assert!(Id::from_index(42).and_then(|id| tree.get(id)).is_none());
+    /// assert!(Id::from_index(usize::MAX).is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub const fn from_index(index: usize) -> Option<Self> {
+        match NonMax::new(index) {
+            Some(id) => Some(Self(id)),
+            None => None,
+        }
+    }
+
+    /// Get the index of the node this identifier refers to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// let root = tree.open("root")?;
+    /// let lit = tree.token("lit", 1)?;
+    /// tree.close()?;
+    ///
+    /// assert_eq!(root.index(), 0);
+    /// assert_eq!(lit.index(), 1);
+    /// assert!(root < lit);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.0.get()
+    }
+}
+
+impl fmt::Display for Id {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.index().fmt(f)
+    }
+}
+
+impl From<Id> for usize {
+    #[inline]
+    fn from(id: Id) -> Self {
+        id.index()
+    }
+}
+
+impl TryFrom<usize> for Id {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        Self::from_index(index).ok_or(Error::Overflow)
+    }
 }
 
 /// A builder for a [Tree].
@@ -62,10 +197,22 @@ where
     parents: Vec<NonMax>,
     /// The last checkpoint that was handed out.
     checkpoint: Option<Checkpoint>,
+    /// The number of times the builder has been flushed through
+    /// [`Builder::flush_completed`], which invalidates checkpoints.
+    generation: u32,
     /// Reference to last sibling inserted.
     sibling: Option<NonMax>,
     /// The current cursor.
     cursor: Index,
+    /// Checkpoints of completed subtrees pushed through
+    /// [`Builder::push_postfix`].
+    postfix: Vec<Checkpoint>,
+    /// Limits being enforced, if any.
+    limits: Option<Tracker>,
+    /// The policy for placing tokens relative to the cursor.
+    policy: CursorPolicy,
+    /// Errors swallowed by [`Builder::open_recover`].
+    recovered: Vec<Recovered>,
 }
 
 impl<T> Builder<T> {
@@ -151,11 +298,253 @@ where
             tree: Tree::new_with(),
             parents: Vec::new(),
             checkpoint: None,
+            generation: 0,
+            sibling: None,
+            cursor: 0,
+            postfix: Vec::new(),
+            limits: None,
+            policy: CursorPolicy::Contiguous,
+            recovered: Vec::new(),
+        }
+    }
+
+    /// Construct a builder which appends to an already built `tree`, reusing
+    /// its allocation.
+    ///
+    /// The builder is positioned after the last node at the top level of the
+    /// tree, so further calls to [`Builder::open`] or [`Builder::token`] add
+    /// new nodes after it. Existing nodes keep their identifiers.
+    ///
+    /// The builder starts out with the default [`CursorPolicy`] and no
+    /// [`BuilderLimits`], with the cursor placed at the end of the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Builder;
+    ///
+    /// let mut tree = Builder::new();
+    /// let first = tree.token("record", 4)?;
+    /// let tree = tree.build()?;
+    ///
+    /// let mut tree = Builder::from_tree(tree);
+    /// let second = tree.token("record", 2)?;
+    /// let tree = tree.build()?;
+    ///
+    /// let expected = syntree::tree! {
+    ///     ("record", 4),
+    ///     ("record", 2),
+    /// };
+    ///
+    /// assert_eq!(tree, expected);
+    /// assert_eq!(tree.get(first).map(|n| n.range()), Some(0..4));
+    /// assert_eq!(tree.get(second).map(|n| n.range()), Some(4..6));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn from_tree(tree: Tree<T, S>) -> Self {
+        let sibling = tree.last().map(|node| node.id().0);
+        let cursor = tree.span().end();
+
+        Builder {
+            tree,
+            parents: Vec::new(),
+            checkpoint: None,
+            generation: 0,
+            sibling,
+            cursor,
+            postfix: Vec::new(),
+            limits: None,
+            policy: CursorPolicy::Contiguous,
+            recovered: Vec::new(),
+        }
+    }
+
+    /// Construct a new builder whose tree has the given capacity.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Builder {
+            tree: Tree::with_capacity(capacity),
+            parents: Vec::new(),
+            checkpoint: None,
+            generation: 0,
             sibling: None,
             cursor: 0,
+            postfix: Vec::new(),
+            limits: None,
+            policy: CursorPolicy::Contiguous,
+            recovered: Vec::new(),
         }
     }
 
+    /// Enforce the given resource `limits` while building the tree.
+    ///
+    /// Limits should be set before anything is added to the builder. Once a
+    /// limit is exceeded, the operation which would have exceeded it fails
+    /// with [`Error::LimitExceeded`] and the builder is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, BuilderLimits, Error, Limit};
+    ///
+    /// let limits = BuilderLimits::new().max_nodes(2);
+    /// let mut tree = Builder::new().with_limits(limits);
+    ///
+    /// let root = tree.open("root")?;
+    /// tree.token("lit", 1)?;
+    ///
+    /// let Err(Error::LimitExceeded { limit, id }) = tree.token("lit", 1) else {
+    ///     return Err("expected limit to be exceeded".into());
+    /// };
+    ///
+    /// assert_eq!(limit, Limit::Nodes);
+    /// assert_ne!(id, root);
+    ///
+    /// tree.close()?;
+    /// let tree = tree.build()?;
+    /// assert_eq!(tree.len(), 2);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// The number of children wrapped through [`Builder::close_at`] is
+    /// limited as well:
+    ///
+    /// ```
+    /// use syntree::{Builder, BuilderLimits, Error, Limit};
+    ///
+    /// let limits = BuilderLimits::new().max_children_per_node(2);
+    /// let mut tree = Builder::new().with_limits(limits);
+    ///
+    /// let c = tree.checkpoint()?;
+    /// tree.token("lit", 1)?;
+    /// tree.token("lit", 1)?;
+    /// tree.token("lit", 1)?;
+    ///
+    /// let Err(Error::LimitExceeded { limit, .. }) = tree.close_at(&c, "root") else {
+    ///     return Err("expected limit to be exceeded".into());
+    /// };
+    ///
+    /// assert_eq!(limit, Limit::Children);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn with_limits(mut self, limits: BuilderLimits) -> Self {
+        self.limits = Some(Tracker::new(limits));
+        self
+    }
+
+    /// Build the tree according to the given cursor `policy`.
+    ///
+    /// The policy should be set before anything is added to the builder. See
+    /// [`CursorPolicy`] for the rules each policy enforces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// tree.open("root")?;
+    /// tree.advance(2)?;
+    /// tree.token("lit", 3)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(root.range(), 2..5);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn with_policy(mut self, policy: CursorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Get the cursor policy of the builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy};
+    ///
+    /// let tree = Builder::<u32>::new();
+    /// assert_eq!(tree.policy(), CursorPolicy::Contiguous);
+    ///
+    /// let tree = tree.with_policy(CursorPolicy::Unordered);
+    /// assert_eq!(tree.policy(), CursorPolicy::Unordered);
+    /// ```
+    #[must_use]
+    pub fn policy(&self) -> CursorPolicy {
+        self.policy
+    }
+
+    /// Get the value and identifier of the innermost node which is currently
+    /// open, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    /// assert_eq!(tree.current(), None);
+    ///
+    /// let root = tree.open("root")?;
+    /// let child = tree.open("child")?;
+    /// assert_eq!(tree.current(), Some((&"child", child)));
+    ///
+    /// tree.close()?;
+    /// assert_eq!(tree.current(), Some((&"root", root)));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn current(&self) -> Option<(&T, Id)> {
+        let id = Id(*self.parents.last()?);
+        Some((self.tree.get(id)?.value(), id))
+    }
+
+    /// Get the number of nodes which are currently open.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    /// assert_eq!(tree.depth(), 0);
+    ///
+    /// tree.open("root")?;
+    /// tree.open("child")?;
+    /// assert_eq!(tree.depth(), 2);
+    ///
+    /// tree.close()?;
+    /// assert_eq!(tree.depth(), 1);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Iterate over the values and identifiers of the nodes which are
+    /// currently open, from the innermost to the outermost.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// let root = tree.open("root")?;
+    /// let block = tree.open("block")?;
+    /// let call = tree.open("call")?;
+    ///
+    /// assert!(tree.open_stack().eq([(&"call", call), (&"block", block), (&"root", root)]));
+    ///
+    /// let in_block = tree.open_stack().any(|(value, _)| *value == "block");
+    /// assert!(in_block);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn open_stack(&self) -> OpenStack<'_, T, S> {
+        OpenStack::new(&self.tree, &self.parents)
+    }
+
     /// Start a node with the given `data`.
     ///
     /// This pushes a new link with the given type onto the stack which links
@@ -184,139 +573,1319 @@ where
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
     pub fn open(&mut self, data: T) -> Result<Id, Error> {
+        self.check_insert()?;
         let id = self.insert(data, Kind::Node, S::point(self.cursor))?;
         self.parents.push(id);
+
+        if let Some(limits) = &mut self.limits {
+            limits.open();
+        }
+
         Ok(Id(id))
     }
 
-    /// End a node being built.
-    ///
-    /// This will pop a value of the stack, and set that value as the next
-    /// sibling which will be used with [`Builder::open`].
-    ///
-    /// # Errors
-    ///
-    /// This call must be balanced with a prior call to [`Builder::open`].
-    /// If not this will result in an [`Error::CloseError`] being raised.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// let mut tree = syntree::Builder::new();
-    ///
-    /// tree.open("root")?;
-    ///
-    /// tree.open("child")?;
-    /// tree.close()?;
-    ///
-    /// tree.open("child")?;
-    /// tree.close()?;
-    ///
-    /// tree.close()?;
-    /// # Ok::<_, Box<dyn std::error::Error>>(())
-    /// ```
-    pub fn close(&mut self) -> Result<(), Error> {
-        let head = self.parents.pop().ok_or(Error::CloseError)?;
-        self.sibling = Some(head);
+    /// Start a node with the given `data`, which is expected to have `children`
+    /// direct children.
+    ///
+    /// This behaves exactly like [`Builder::open`], except that room for the
+    /// children is reserved up front. This avoids repeatedly growing the tree
+    /// when converting from another representation where the number of
+    /// children of each node is known.
+    ///
+    /// The hint is only used to reserve capacity and is never trusted, so
+    /// failing to reserve it isn't an error. The
+    /// number of children reported by [`Node::children`] is always the number
+    /// of children actually added before the node is closed, regardless of
+    /// whether the hint was too low or too high.
+    ///
+    /// [`Node::children`]: crate::Node::children
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] in case we run out of node
+    /// identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open_with_children_hint("array", 3)?;
+    /// tree.token("number", 1)?;
+    /// tree.token("number", 1)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    /// assert!(tree.capacity() >= 4);
+    ///
+    /// let array = tree.first().ok_or("missing array")?;
+    /// assert_eq!(array.children().len(), 2);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn open_with_children_hint(&mut self, data: T, children: usize) -> Result<Id, Error> {
+        let id = self.open(data)?;
+
+        // Don't let a bogus hint reserve room for more nodes than the limits
+        // permit.
+        let children = match &self.limits {
+            Some(limits) => children.min(limits.remaining(self.tree.len())),
+            None => children,
+        };
+
+        self.tree.try_reserve(children);
+        Ok(id)
+    }
+
+    /// End a node being built.
+    ///
+    /// This will pop a value of the stack, and set that value as the next
+    /// sibling which will be used with [`Builder::open`].
+    ///
+    /// # Errors
+    ///
+    /// This call must be balanced with a prior call to [`Builder::open`].
+    /// If not this will result in an [`Error::CloseError`] being raised.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    ///
+    /// tree.open("child")?;
+    /// tree.close()?;
+    ///
+    /// tree.open("child")?;
+    /// tree.close()?;
+    ///
+    /// tree.close()?;
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn close(&mut self) -> Result<(), Error> {
+        let head = self.parents.pop().ok_or(Error::CloseError)?;
+        self.sibling = Some(head);
+
+        if let Some(limits) = &mut self.limits {
+            limits.close(head);
+        }
+
+        if !matches!(self.policy, CursorPolicy::Contiguous) {
+            let node = self
+                .tree
+                .get_mut(head)
+                .ok_or(Error::MissingNode(Id(head)))?;
+
+            if let Some(first) = node.first {
+                let span = self.children_span(first)?;
+
+                if let Some(node) = self.tree.get_mut(head) {
+                    node.span = span;
+                }
+            }
+        }
+
+        if let Some(&parent) = self.parents.last() {
+            let node = self
+                .tree
+                .get_mut(head)
+                .ok_or(Error::MissingNode(Id(head)))?;
+            let span = node.span;
+            let parent = self
+                .tree
+                .get_mut(parent)
+                .ok_or(Error::MissingNode(Id(parent)))?;
+            parent.span = parent.span.join(&span);
+        }
+
+        Ok(())
+    }
+
+    /// Reopen the node identified by `id` so that more children can be
+    /// appended to it.
+    ///
+    /// This is only permitted for the node which was most recently closed
+    /// through [`Builder::close`] or [`Builder::close_at`], as long as nothing
+    /// else has been added after it. The node is pushed back onto the stack of
+    /// nodes being built and its span is extended as new children are added.
+    /// It has to be closed again through [`Builder::close`].
+    ///
+    /// Note that any [`Checkpoint`] created after the node was closed should
+    /// not be used after it has been reopened.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::ReopenError`] if `id` does not refer to the most
+    /// recently closed node, or if the node is a token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// let child = tree.open("child")?;
+    /// tree.token("lit", 3)?;
+    /// tree.close()?;
+    ///
+    /// tree.reopen(child)?;
+    /// tree.token("lit", 2)?;
+    /// tree.close()?;
+    ///
+    /// tree.token("whitespace", 1)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let expected = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("lit", 3),
+    ///             ("lit", 2)
+    ///         },
+    ///         ("whitespace", 1)
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree, expected);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Reopening a node after a sibling has been added is an error:
+    ///
+    /// ```
+    /// use syntree::{Builder, Error};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// let child = tree.open("child")?;
+    /// tree.close()?;
+    /// tree.token("whitespace", 1)?;
+    ///
+    /// assert_eq!(tree.reopen(child), Err(Error::ReopenError));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reopen(&mut self, id: Id) -> Result<(), Error> {
+        if self.sibling != Some(id.0) {
+            return Err(Error::ReopenError);
+        }
+
+        let links = self.tree.get_mut(id.0).ok_or(Error::MissingNode(id))?;
+
+        if !matches!(links.kind, Kind::Node) {
+            return Err(Error::ReopenError);
+        }
+
+        self.sibling = links.last;
+        self.parents.push(id.0);
+
+        if let Some(limits) = &mut self.limits {
+            limits.reopen(id.0);
+        }
+
+        Ok(())
+    }
+
+    /// Set the user flags of the node or token `id`, replacing any flags it
+    /// previously had.
+    ///
+    /// Flags are eight bits which are free for the caller to use, such as to
+    /// mark nodes which should be highlighted differently. They are not
+    /// considered when comparing or hashing trees, and are preserved when a
+    /// tree is modified through a [`ChangeSet`] or a node is copied through
+    /// [`Builder::reuse`].
+    ///
+    /// [`ChangeSet`]: crate::edit::ChangeSet
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::MissingNode`] if `id` is not in the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// const ERROR: u8 = 0b1;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// let lit = tree.token("lit", 3)?;
+    /// tree.set_flags(lit, ERROR)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let lit = tree.get(lit).ok_or("missing lit")?;
+    /// assert_eq!(lit.flags(), ERROR);
+    /// assert_eq!(lit.parent().map(|n| n.flags()), Some(0));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_flags(&mut self, id: Id, flags: u8) -> Result<(), Error> {
+        let links = self.tree.get_mut(id.0).ok_or(Error::MissingNode(id))?;
+        links.flags = flags;
+        Ok(())
+    }
+
+    /// Mark the node or token `id` as an error, meaning that it only exists
+    /// because of error recovery.
+    ///
+    /// The mark is separate from the user flags set through
+    /// [`Builder::set_flags`], and can be tested with [`Node::is_error`]. Like
+    /// flags it is not considered when comparing or hashing trees, and is
+    /// preserved when a tree is modified through a [`ChangeSet`] or a node is
+    /// copied through [`Builder::reuse`].
+    ///
+    /// [`Node::is_error`]: crate::Node::is_error
+    /// [`ChangeSet`]: crate::edit::ChangeSet
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::MissingNode`] if `id` is not in the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// let lit = tree.token("lit", 3)?;
+    /// tree.mark_error(lit)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let lit = tree.get(lit).ok_or("missing lit")?;
+    /// assert!(lit.is_error());
+    /// assert_eq!(lit.flags(), 0);
+    /// assert_eq!(lit.parent().map(|n| n.is_error()), Some(false));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn mark_error(&mut self, id: Id) -> Result<Id, Error> {
+        let links = self.tree.get_mut(id.0).ok_or(Error::MissingNode(id))?;
+        links.marks |= MARK_ERROR;
+        Ok(id)
+    }
+
+    /// Open a node with the given `data` which is marked as an error, see
+    /// [`Builder::open`] and [`Builder::mark_error`].
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] in case we run out of node
+    /// identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.open_error("missing")?;
+    /// tree.token("lit", 1)?;
+    /// tree.close()?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let errors = tree.walk().filter(|n| n.is_error()).map(|n| *n.value());
+    /// assert_eq!(errors.collect::<Vec<_>>(), ["missing"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn open_error(&mut self, data: T) -> Result<Id, Error> {
+        let id = self.open(data)?;
+        self.mark_error(id)
+    }
+
+    /// Declare a token with the specified `value` and `len` which is marked
+    /// as an error, see [`Builder::token`] and [`Builder::mark_error`].
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] in case we run out of node
+    /// identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.token("lit", 1)?;
+    /// tree.error_token("garbage", 3)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let errors = tree.walk().filter(|n| n.is_error()).map(|n| *n.value());
+    /// assert_eq!(errors.collect::<Vec<_>>(), ["garbage"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn error_token(&mut self, value: T, len: S::Length) -> Result<Id, Error> {
+        let id = self.token(value, len)?;
+        self.mark_error(id)
+    }
+
+    /// Declare a trivia token with the specified `value` and `len`, such as
+    /// whitespace or a comment, see [`Builder::token`].
+    ///
+    /// Trivia tokens are part of the tree like any other token, but can be
+    /// tested for with [`Node::is_trivia`] and filtered out by iterators such
+    /// as [`Children::skip_trivia`]. Like the error mark, this is not
+    /// considered when comparing or hashing trees, and is preserved when a
+    /// tree is modified through a [`ChangeSet`] or a node is copied through
+    /// [`Builder::reuse`].
+    ///
+    /// [`Node::is_trivia`]: crate::Node::is_trivia
+    /// [`Children::skip_trivia`]: crate::node::Children::skip_trivia
+    /// [`ChangeSet`]: crate::edit::ChangeSet
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] in case we run out of node
+    /// identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.token("lit", 1)?;
+    /// tree.token_trivia("whitespace", 1)?;
+    /// tree.token("lit", 1)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    /// let root = tree.first().ok_or("missing root")?;
+    ///
+    /// assert_eq!(root.children().count(), 3);
+    ///
+    /// let values = root.children().skip_trivia().map(|n| *n.value());
+    /// assert_eq!(values.collect::<Vec<_>>(), ["lit", "lit"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn token_trivia(&mut self, value: T, len: S::Length) -> Result<Id, Error> {
+        let id = self.token(value, len)?;
+        self.mark_trivia(id)
+    }
+
+    /// Mark the token `id` as trivia.
+    pub(crate) fn mark_trivia(&mut self, id: Id) -> Result<Id, Error> {
+        let links = self.tree.get_mut(id.0).ok_or(Error::MissingNode(id))?;
+        links.marks |= MARK_TRIVIA;
+        Ok(id)
+    }
+
+    /// Set the marks of `id` to `marks`, as returned by [`Node::marks`].
+    pub(crate) fn set_marks(&mut self, id: Id, marks: u8) -> Result<(), Error> {
+        let links = self.tree.get_mut(id.0).ok_or(Error::MissingNode(id))?;
+        links.marks = marks;
+        Ok(())
+    }
+
+    /// Declare a token with the specified `value` and a corresponding `len`.
+    ///
+    /// A token is always a terminating element without children. Like every
+    /// method which adds a single token, this returns the identifier of the
+    /// new token, which can be used to look it up in the built tree through
+    /// [`Tree::get`].
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] in case we run out of node
+    /// identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("child")?;
+    /// tree.token("lit", 4)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let expected = syntree::tree! {
+    ///     "child" => {
+    ///         ("lit", 4)
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree, expected);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Recording side information against tokens as they are lexed, and
+    /// resolving them once the tree is built:
+    ///
+    /// ```
+    /// let source = "let x = 42;";
+    ///
+    /// let mut tree = syntree::Builder::new();
+    /// let mut idents = Vec::new();
+    ///
+    /// tree.open("let")?;
+    ///
+    /// for word in source.split_inclusive(' ') {
+    ///     let (word, ws) = word.split_at(word.trim_end().len());
+    ///
+    ///     match word {
+    ///         "let" => tree.token("kw", word.len())?,
+    ///         "=" => tree.token("eq", word.len())?,
+    ///         "42;" => tree.token("number", word.len())?,
+    ///         _ => {
+    ///             let id = tree.token("ident", word.len())?;
+    ///             idents.push(id);
+    ///             id
+    ///         }
+    ///     };
+    ///
+    ///     if !ws.is_empty() {
+    ///         tree.token("ws", ws.len())?;
+    ///     }
+    /// }
+    ///
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let idents = idents
+    ///     .into_iter()
+    ///     .map(|id| Some(&source[tree.get(id)?.range()]))
+    ///     .collect::<Option<Vec<_>>>();
+    ///
+    /// assert_eq!(idents, Some(vec!["x"]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// [`Tree::get`]: crate::Tree::get
+    pub fn token(&mut self, value: T, len: S::Length) -> Result<Id, Error> {
+        let start = self.cursor;
+        let end = self.add_to(start, len)?;
+        self.push_token(value, S::new(start, end))
+    }
+
+    /// Declare a token with the specified `value` covering an explicit
+    /// `span`.
+    ///
+    /// A token is always a terminating element without children. Where the
+    /// token may be placed relative to the cursor is determined by the
+    /// [`CursorPolicy`] of the builder.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::CursorGap`] or [`Error::CursorBackwards`] if the
+    /// span is not permitted by the cursor policy, with
+    /// [`Error::BackwardsSpan`] if the span ends before it starts, and with
+    /// [`Error::Overflow`] in case we run out of node identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Error, Span};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// tree.open("root")?;
+    /// tree.token_with_span("lit", Span::new(0, 2))?;
+    /// let previous = tree.token_with_span("lit", Span::new(3, 4))?;
+    ///
+    /// assert_eq!(
+    ///     tree.token_with_span("lit", Span::new(2, 3)),
+    ///     Err(Error::CursorBackwards {
+    ///         cursor: 4,
+    ///         start: 2,
+    ///         end: 3,
+    ///         previous: Some(previous),
+    ///         previous_start: 3,
+    ///         previous_end: 4,
+    ///     })
+    /// );
+    ///
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(root.range(), 0..4);
+    ///
+    /// let ranges = root.children().map(|n| n.range()).collect::<Vec<_>>();
+    /// assert_eq!(ranges, [0..2, 3..4]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn token_with_span(&mut self, value: T, span: S) -> Result<Id, Error> {
+        self.push_token(value, span)
+    }
+
+    /// Advance the cursor by `len` without adding a token, leaving a gap in
+    /// the tree.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::CursorGap`] if a non-empty gap is not permitted
+    /// by the [`CursorPolicy`] of the builder, and with [`Error::Overflow`] if
+    /// the cursor overflows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Error};
+    ///
+    /// let mut tree = Builder::<&str>::new();
+    /// assert_eq!(tree.advance(2), Err(Error::CursorGap { cursor: 0, start: 2 }));
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// tree.token("lit", 1)?;
+    /// tree.advance(2)?;
+    /// tree.token("lit", 1)?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let ranges = tree.children().map(|n| n.range()).collect::<Vec<_>>();
+    /// assert_eq!(ranges, [0..1, 3..4]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn advance(&mut self, len: S::Length) -> Result<(), Error> {
+        let end = self.add_to(self.cursor, len)?;
+        self.place(end, end)
+    }
+
+    /// Move the cursor to `index`, checking it against the cursor policy in
+    /// the same way as an empty token placed there.
+    pub(crate) fn advance_to(&mut self, index: Index) -> Result<(), Error> {
+        self.place(index, index)
+    }
+
+    /// Declare a token with the specified `value` and an empty length.
+    ///
+    /// A token is always a terminating element without children.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] in case we run out of node
+    /// identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("child")?;
+    /// tree.token_empty("lit")?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let expected = syntree::tree! {
+    ///     "child" => {
+    ///         "lit"
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree, expected);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn token_empty(&mut self, value: T) -> Result<Id, Error> {
+        self.token(value, S::Length::EMPTY)
+    }
+
+    /// Insert a token with the specified `value` and `span` immediately
+    /// before `anchor`, which must be a child of the node currently being
+    /// built, or a root if no node is being built.
+    ///
+    /// This allows for emitting a token which is only discovered to be
+    /// missing after later siblings have been added, such as a synthetic `;`
+    /// which belongs before trailing trivia. The cursor is not moved.
+    ///
+    /// The span must fit between the end of the sibling preceding `anchor`,
+    /// or the start of the node being built if there is none, and the start
+    /// of `anchor`. With [`CursorPolicy::Unordered`] it only has to be within
+    /// the current span of the node being built. This means that inserting
+    /// never changes the span of any node.
+    ///
+    /// Note that any [`Checkpoint`] created before a token has been inserted
+    /// should not be used afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::InsertBeforeError`] if `anchor` is not a child of
+    /// the node currently being built, with [`Error::BackwardsSpan`] if the
+    /// span ends before it starts, with [`Error::InsertOutOfBounds`] if the
+    /// span doesn't fit before `anchor`, and with [`Error::Overflow`] in case
+    /// we run out of node identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Span};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// tree.open("stmt")?;
+    /// tree.token("ident", 3)?;
+    /// let ws = tree.token_trivia("whitespace", 1)?;
+    /// tree.insert_before(ws, "semi", Span::point(3))?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let expected = syntree::tree! {
+    ///     "stmt" => {
+    ///         ("ident", 3),
+    ///         ("semi", 0),
+    ///         ("whitespace", 1)
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree, expected);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[allow(clippy::unnecessary_cast)]
+    pub fn insert_before(&mut self, anchor: Id, value: T, span: S) -> Result<Id, Error> {
+        let parent = self.parents.last().copied();
+
+        let node = self
+            .tree
+            .node_at(anchor.0)
+            .ok_or(Error::MissingNode(anchor))?;
+
+        if node.parent().map(|n| n.id().0) != parent {
+            return Err(Error::InsertBeforeError);
+        }
+
+        if span.end() < span.start() {
+            return Err(Error::BackwardsSpan {
+                start: span.start() as usize,
+                end: span.end() as usize,
+            });
+        }
+
+        let extent = match parent {
+            Some(parent) => *self
+                .tree
+                .node_at(parent)
+                .ok_or(Error::MissingNode(Id(parent)))?
+                .span(),
+            None => *self.tree.span(),
+        };
+
+        let (start, end) = match self.policy {
+            CursorPolicy::Unordered => (extent.start(), extent.end()),
+            _ => {
+                let start = node.prev().map_or(extent.start(), |n| n.span().end());
+                (start, node.span().start())
+            }
+        };
+
+        if span.start() < start || span.end() > end {
+            return Err(Error::InsertOutOfBounds {
+                start: span.start() as usize,
+                end: span.end() as usize,
+            });
+        }
+
+        let prev = node.prev().map(|n| n.id().0);
+        // The position of the anchor among its siblings is only needed when
+        // enforcing limits.
+        let position = match &self.limits {
+            Some(..) => iter::successors(node.prev(), |n| n.prev()).count(),
+            None => 0,
+        };
+
+        self.check_insert()?;
+
+        let new = NonMax::new(self.tree.len()).ok_or(Error::Overflow)?;
+
+        self.tree.push(Links {
+            data: value,
+            kind: Kind::Token,
+            span,
+            parent,
+            prev,
+            next: Some(anchor.0),
+            first: None,
+            last: None,
+            len: 0,
+            flags: 0,
+            marks: 0,
+        });
+
+        if let Some(links) = self.tree.get_mut(anchor.0) {
+            links.prev = Some(new);
+        }
+
+        if let Some(links) = prev.and_then(|id| self.tree.get_mut(id)) {
+            links.next = Some(new);
+        }
+
+        match parent.and_then(|id| self.tree.get_mut(id)) {
+            Some(links) => {
+                if links.first == Some(anchor.0) {
+                    links.first = Some(new);
+                }
+
+                links.len += 1;
+            }
+            None => {
+                let (first, _, roots) = self.tree.links_mut();
+
+                if *first == Some(anchor.0) {
+                    *first = Some(new);
+                }
+
+                *roots += 1;
+            }
+        }
+
+        if let Some(limits) = &mut self.limits {
+            limits.insert_token(position);
+        }
+
+        let id = Id(new);
+
+        if !span.is_empty() {
+            // Tokens are indexed by where they end, so unless they're sorted
+            // when the tree is built the new index has to be inserted in
+            // place.
+            if matches!(self.policy, CursorPolicy::Unordered) {
+                self.tree.indexes_mut().push(span.end(), id);
+            } else {
+                self.tree.indexes_mut().insert(span.end(), id);
+            }
+        }
+
+        Ok(id)
+    }
+
+    /// Append the tokens produced by a lexer as siblings in the node currently
+    /// being built.
+    ///
+    /// Each item is a token value and the range of source it covers, which is
+    /// what lexers such as [logos] produce. Ranges have to be in order and
+    /// must not overlap with each other or with what has already been added
+    /// to the builder.
+    ///
+    /// Ranges may leave gaps for skipped trivia, which are treated the same
+    /// as gaps left through [`Builder::advance`] so they have to be permitted
+    /// by the [`CursorPolicy`] of the builder. Use
+    /// [`Builder::extend_from_lexer_with_gaps`] to fill them with tokens
+    /// instead.
+    ///
+    /// [logos]: https://docs.rs/logos
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::LexerRange`] naming the position of the first
+    /// item whose range is backwards or out of order, and otherwise with the
+    /// same errors as [`Builder::token_with_span`]. Tokens preceding the
+    /// offending item have already been added when this happens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Error};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// tree.open("root")?;
+    /// tree.extend_from_lexer([("ident", 0..3), ("eq", 4..5), ("number", 6..8)])?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let ranges = root.children().map(|n| (*n.value(), n.range())).collect::<Vec<_>>();
+    /// assert_eq!(ranges, [("ident", 0..3), ("eq", 4..5), ("number", 6..8)]);
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// assert_eq!(
+    ///     tree.extend_from_lexer([("ident", 0..3), ("eq", 2..5)]),
+    ///     Err(Error::LexerRange { index: 1, start: 2, end: 5 })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn extend_from_lexer<I>(&mut self, iter: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (T, Range<usize>)>,
+    {
+        self.extend_from_lexer_inner(iter, || None)
+    }
+
+    /// Append the tokens produced by a lexer as siblings in the node currently
+    /// being built, filling any gaps between them with tokens of the given
+    /// `gap` value.
+    ///
+    /// This works with any [`CursorPolicy`], since the inserted tokens leave
+    /// no gaps. See [`Builder::extend_from_lexer`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::LexerRange`] naming the position of the first
+    /// item whose range is backwards or out of order, and otherwise with the
+    /// same errors as [`Builder::token_with_span`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.extend_from_lexer_with_gaps([("ident", 0..3), ("eq", 4..5), ("number", 6..8)], "ws")?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let expected = syntree::tree! {
+    ///     "root" => {
+    ///         ("ident", 3),
+    ///         ("ws", 1),
+    ///         ("eq", 1),
+    ///         ("ws", 1),
+    ///         ("number", 2),
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree, expected);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn extend_from_lexer_with_gaps<I>(&mut self, iter: I, gap: T) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (T, Range<usize>)>,
+        T: Clone,
+    {
+        self.extend_from_lexer_inner(iter, || Some(gap.clone()))
+    }
+
+    fn extend_from_lexer_inner<I, G>(&mut self, iter: I, mut gap: G) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (T, Range<usize>)>,
+        G: FnMut() -> Option<T>,
+    {
+        let mut previous = self.cursor;
+
+        for (index, (value, range)) in iter.into_iter().enumerate() {
+            let error = Error::LexerRange {
+                index,
+                start: range.start,
+                end: range.end,
+            };
+
+            let start = self.add_to(0, range.start)?;
+            let end = self.add_to(0, range.end)?;
+
+            if start < previous || end < start {
+                return Err(error);
+            }
+
+            if start > previous {
+                if let Some(gap) = gap() {
+                    self.push_token(gap, S::new(previous, start))?;
+                }
+            }
+
+            self.push_token(value, S::new(start, end))?;
+            previous = end;
+        }
+
+        Ok(())
+    }
+
+    /// Copy the subtree rooted at `node` from another tree into the current
+    /// position, rebasing its spans to start at the current cursor.
+    ///
+    /// This is intended for incremental reparsing, where unchanged subtrees of
+    /// a previous tree are reused instead of being replayed token by token.
+    /// See [`Tree::reusable_nodes`] for finding such subtrees. The nodes of a
+    /// subtree are stored contiguously, so they are copied in bulk with their
+    /// links and spans adjusted rather than being replayed node by node.
+    /// Either way gaps between the tokens of the subtree are kept.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] in case we run out of node
+    /// identifiers or the cursor overflows, and with [`Error::CursorGap`] or
+    /// [`Error::CursorBackwards`] if the spans of the subtree are not
+    /// permitted by the [`CursorPolicy`] of the builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let old = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 2),
+    ///         },
+    ///         ("ws", 1),
+    ///         "number" => {
+    ///             ("lit", 3),
+    ///         },
+    ///     }
+    /// };
+    ///
+    /// // Source was edited by inserting a token at the start of the root.
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.token("lit", 4)?;
+    ///
+    /// for node in old.reusable_nodes(0..0) {
+    ///     tree.reuse(node)?;
+    /// }
+    ///
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let expected = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 4),
+    ///         ("ws", 1),
+    ///         "number" => {
+    ///             ("lit", 3),
+    ///         },
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree, expected);
+    ///
+    /// let number = tree.node_with_range(5..8).ok_or("missing number")?;
+    /// assert_eq!(*number.value(), "number");
+    /// assert_eq!(number.range(), 5..8);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reuse(&mut self, node: Node<'_, T, S>) -> Result<Id, Error>
+    where
+        T: Clone,
+    {
+        // Subtrees constructed through a builder are always contiguous, but
+        // fall back to replaying in case they are not.
+        // Limits and the cursor policy are enforced node by node, so replay in
+        // case limits are set or the subtree doesn't fit the policy, which
+        // reports the error.
+        let Some((start, end)) =
+            contiguous(node).filter(|_| self.limits.is_none() && self.fits_policy(node))
+        else {
+            return self.replay(node);
+        };
+
+        let storage = node.storage();
+        let root = node.id().0.get();
+        let base = self.tree.len();
+
+        // Check up front that all identifiers fit.
+        NonMax::new(base + (end - start)).ok_or(Error::Overflow)?;
+
+        let map = |id: Option<NonMax>| -> Option<NonMax> { NonMax::new(id?.get() - start + base) };
+
+        let new_root = map(Some(node.id().0)).ok_or(Error::Overflow)?;
+
+        let from = node.span().start();
+        let shift = |index: Index| -> Result<Index, Error> {
+            (index - from)
+                .checked_add(self.cursor)
+                .ok_or(Error::SpanOverflow { at: Id(new_root) })
+        };
+
+        // The subtree is covered by the span of its root, so check that it fits
+        // before anything is added.
+        shift(node.span().end())?;
+
+        let prev = self.sibling.take();
+        let parent = self.parents.last().copied();
+
+        for (index, links) in storage
+            .get(start..=end)
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+        {
+            let span = S::new(shift(links.span.start())?, shift(links.span.end())?);
+            let is_root = start + index == root;
+
+            self.tree.push(Links {
+                data: links.data.clone(),
+                kind: links.kind,
+                span,
+                parent: if is_root { parent } else { map(links.parent) },
+                prev: if is_root { prev } else { map(links.prev) },
+                next: if is_root { None } else { map(links.next) },
+                first: map(links.first),
+                last: map(links.last),
+                len: links.len,
+                flags: links.flags,
+                marks: links.marks,
+            });
+        }
+
+        // Tokens inserted through `insert_before` are stored after the tokens
+        // following them, so indexes are pushed in document order.
+        for token in iter::once(node).chain(node.walk()) {
+            if matches!(token.kind(), Kind::Token) && !token.span().is_empty() {
+                let id = map(Some(token.id().0)).ok_or(Error::Overflow)?;
+                let cursor = shift(token.span().end())?;
+                self.tree.indexes_mut().push(cursor, Id(id));
+            }
+        }
+
+        let span = node.span();
+        let end = shift(span.end())?;
+
+        if !span.is_empty() {
+            self.cursor = end;
+            self.tree.span_mut().set_end(end);
+        }
+
+        if let Some(id) = parent {
+            if let Some(node) = self.tree.links_at_mut(id) {
+                if node.first.is_none() {
+                    node.first = Some(new_root);
+                }
+
+                node.last = Some(new_root);
+                node.len += 1;
+                node.span.set_end(end);
+            }
+        } else {
+            let (first, last, roots) = self.tree.links_mut();
+
+            if first.is_none() {
+                *first = Some(new_root);
+            }
+
+            *last = Some(new_root);
+            *roots += 1;
+        }
+
+        if let Some(node) = prev.and_then(|id| self.tree.links_at_mut(id)) {
+            node.next = Some(new_root);
+        }
+
+        self.sibling = Some(new_root);
+        Ok(Id(new_root))
+    }
+
+    /// Test if the subtree rooted at `node` can be placed at the cursor
+    /// without violating the cursor policy, in the same way as if it was
+    /// replayed.
+    ///
+    /// Spans which overflow are left for [`Builder::reuse`] to report, since
+    /// it does so before anything is added.
+    fn fits_policy(&self, node: Node<'_, T, S>) -> bool {
+        if let CursorPolicy::Unordered = self.policy {
+            return true;
+        }
+
+        let from = node.span().start();
+        let mut cursor = self.cursor;
+
+        for node in iter::once(node).chain(node.walk()) {
+            let span = node.span();
+
+            let Some(start) = (span.start() - from).checked_add(self.cursor) else {
+                return true;
+            };
+
+            if start < cursor || matches!(self.policy, CursorPolicy::Contiguous) && start > cursor {
+                return false;
+            }
+
+            cursor = match node.kind() {
+                Kind::Token => match span.len().checked_add(start) {
+                    Some(end) => end,
+                    None => return true,
+                },
+                Kind::Node => start,
+            };
+        }
+
+        true
+    }
+
+    /// Replay the subtree rooted at `node` node by node, keeping the spans of
+    /// its tokens relative to the cursor.
+    fn replay(&mut self, node: Node<'_, T, S>) -> Result<Id, Error>
+    where
+        T: Clone,
+    {
+        let from = node.span().start();
+        let base = self.cursor;
+        let id = self.replay_one(node, from, base)?;
 
-        if let Some(&parent) = self.parents.last() {
-            let node = self
-                .tree
-                .get_mut(head)
-                .ok_or(Error::MissingNode(Id(head)))?;
-            let end = node.span.end();
-            let parent = self
-                .tree
-                .get_mut(parent)
-                .ok_or(Error::MissingNode(Id(parent)))?;
-            parent.span.set_end(end);
+        if let Kind::Token = node.kind() {
+            return Ok(id);
+        }
+
+        let mut stack = vec![node.children()];
+
+        while let Some(it) = stack.last_mut() {
+            let Some(node) = it.next() else {
+                stack.pop();
+                self.close()?;
+                continue;
+            };
+
+            self.replay_one(node, from, base)?;
+
+            if let Kind::Node = node.kind() {
+                stack.push(node.children());
+            }
+        }
+
+        Ok(id)
+    }
+
+    /// Replay a single node or token, where `from` is the start of the
+    /// replayed subtree and `base` is where it is being placed.
+    fn replay_one(&mut self, node: Node<'_, T, S>, from: Index, base: Index) -> Result<Id, Error>
+    where
+        T: Clone,
+    {
+        let span = node.span();
+        let start = self.shift(span.start(), from, base)?;
+
+        let id = match node.kind() {
+            Kind::Token => {
+                let end = self.shift(span.end(), from, base)?;
+                self.token_with_span(node.value().clone(), S::new(start, end))?
+            }
+            Kind::Node => {
+                self.advance_to(start)?;
+                self.open(node.value().clone())?
+            }
+        };
+
+        self.copy_marks(id, node)?;
+        Ok(id)
+    }
+
+    /// Move `index` from a subtree starting at `from` to one starting at
+    /// `base`.
+    fn shift(&self, index: Index, from: Index, base: Index) -> Result<Index, Error> {
+        if let Some(index) = (index - from).checked_add(base) {
+            return Ok(index);
         }
 
+        let at = NonMax::new(self.tree.len()).ok_or(Error::Overflow)?;
+        Err(Error::SpanOverflow { at: Id(at) })
+    }
+
+    /// Copy the flags and marks of `node` to `id`.
+    fn copy_marks(&mut self, id: Id, node: Node<'_, T, S>) -> Result<(), Error> {
+        let links = self.tree.get_mut(id.0).ok_or(Error::MissingNode(id))?;
+        links.flags = node.flags();
+        links.marks = node.marks();
         Ok(())
     }
 
-    /// Declare a token with the specified `value` and a corresponding `len`.
+    /// Open a node with the given `value` and build its children with `body`,
+    /// recovering from errors raised by `body`.
     ///
-    /// A token is always a terminating element without children.
+    /// If `body` fails, a zero-width token with `error_value` is appended to
+    /// the node before it is closed, so that the tree stays balanced and the
+    /// error can be pointed to. The token is marked as an error, see
+    /// [`Builder::mark_error`]. The error itself is recorded and can be
+    /// retrieved through [`Builder::take_recovered_errors`].
+    ///
+    /// Children which were added by `body` before it failed are kept, and
+    /// any nodes it left open are closed. Checkpoints created by `body` refer
+    /// to nodes which are now closed, so they can no longer be used with
+    /// [`Builder::close_at`].
     ///
     /// # Errors
     ///
-    /// Errors with [`Error::Overflow`] in case we run out of node
-    /// identifiers.
+    /// Errors if opening or closing the node fails, or with
+    /// [`Error::CloseError`] if `body` closes the node itself.
     ///
     /// # Examples
     ///
     /// ```
     /// let mut tree = syntree::Builder::new();
     ///
-    /// tree.open("child")?;
-    /// tree.token("lit", 4)?;
-    /// tree.close()?;
+    /// tree.open_recover("root", "error", |tree| {
+    ///     tree.token("number", 3)?;
+    ///     tree.open("group")?;
+    ///     tree.token("ident", 2)?;
+    ///     Err::<(), Box<dyn std::error::Error>>("unexpected end of input".into())
+    /// })?;
+    ///
+    /// let errors = tree.take_recovered_errors();
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].message(), "unexpected end of input");
     ///
     /// let tree = tree.build()?;
     ///
     /// let expected = syntree::tree! {
-    ///     "child" => {
-    ///         ("lit", 4)
+    ///     "root" => {
+    ///         ("number", 3),
+    ///         "group" => {
+    ///             ("ident", 2),
+    ///         },
+    ///         "error",
     ///     }
     /// };
     ///
     /// assert_eq!(tree, expected);
+    /// assert_eq!(tree.first().map(|n| n.id()), Some(errors[0].id()));
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
-    pub fn token(&mut self, value: T, len: S::Length) -> Result<Id, Error> {
-        let start = self.cursor;
+    pub fn open_recover<E, F>(&mut self, value: T, error_value: T, body: F) -> Result<Id, Error>
+    where
+        E: fmt::Display,
+        F: FnOnce(&mut Self) -> Result<(), E>,
+    {
+        let id = self.open(value)?;
+        let depth = self.parents.len();
+
+        let result = body(self);
 
-        if !len.is_empty() {
-            self.cursor = len
-                .into_index()
-                .and_then(|len| self.cursor.checked_add(len))
-                .ok_or(Error::Overflow)?;
-            self.tree.span_mut().set_end(self.cursor);
+        if self.parents.get(depth - 1) != Some(&id.0) {
+            return Err(Error::CloseError);
         }
 
-        let id = self.insert(value, Kind::Token, S::new(start, self.cursor))?;
-        self.sibling = Some(id);
-        let id = Id(id);
+        while self.parents.len() > depth {
+            self.close()?;
+        }
 
-        if !len.is_empty() {
-            self.tree.indexes_mut().push(self.cursor, id);
+        if let Err(error) = result {
+            let error_id = self.token_empty(error_value)?;
+            self.mark_error(error_id)?;
+            let message = error.to_string().into_boxed_str();
+            self.recovered.push(Recovered::new(id, message));
         }
 
+        self.close()?;
         Ok(id)
     }
 
-    /// Declare a token with the specified `value` and an empty length.
-    ///
-    /// A token is always a terminating element without children.
-    ///
-    /// # Errors
-    ///
-    /// Errors with [`Error::Overflow`] in case we run out of node
-    /// identifiers.
+    /// Take all errors recovered from by [`Builder::open_recover`] so far, in
+    /// the order in which they occurred.
     ///
     /// # Examples
     ///
     /// ```
     /// let mut tree = syntree::Builder::new();
     ///
-    /// tree.open("child")?;
-    /// tree.token_empty("lit")?;
-    /// tree.close()?;
-    ///
-    /// let tree = tree.build()?;
+    /// let outer = tree.open_recover("outer", "error", |tree| {
+    ///     tree.open_recover("inner", "error", |tree| {
+    ///         tree.token("lit", 1)?;
+    ///         Err::<(), Box<dyn std::error::Error>>("bad inner".into())
+    ///     })?;
     ///
-    /// let expected = syntree::tree! {
-    ///     "child" => {
-    ///         "lit"
-    ///     }
-    /// };
+    ///     Err::<(), _>(syntree::Error::BuildError)
+    /// })?;
     ///
-    /// assert_eq!(tree, expected);
+    /// let errors = tree.take_recovered_errors();
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors[0].message(), "bad inner");
+    /// assert_eq!(errors[1].id(), outer);
+    /// assert!(tree.take_recovered_errors().is_empty());
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
-    pub fn token_empty(&mut self, value: T) -> Result<Id, Error> {
-        self.token(value, S::Length::EMPTY)
+    pub fn take_recovered_errors(&mut self) -> Vec<Recovered> {
+        core::mem::take(&mut self.recovered)
     }
 
     /// Get a checkpoint corresponding to the current position in the tree.
@@ -358,13 +1927,16 @@ where
     pub fn checkpoint(&mut self) -> Result<Checkpoint, Error> {
         let node = NonMax::new(self.tree.len()).ok_or(Error::Overflow)?;
 
+        let parent = self.parents.last().copied();
+
         if let Some(c) = &self.checkpoint {
-            if c.node() == node {
+            if c.get() == (node, parent) {
                 return Ok(c.clone());
             }
         }
 
-        let c = Checkpoint::new(node, self.parents.last().copied());
+        let children = self.limits.as_ref().map_or(0, Tracker::children);
+        let c = Checkpoint::new(node, parent, children, self.generation);
         self.checkpoint = Some(c.clone());
         Ok(c)
     }
@@ -374,7 +1946,9 @@ where
     /// # Errors
     ///
     /// The checkpoint being closed *must* be a sibling. Otherwise a
-    /// [`Error::CloseAtError`] will be raised.
+    /// [`Error::CloseAtError`] will be raised. Checkpoints created before the
+    /// builder was flushed through [`Builder::flush_completed`] raise
+    /// [`Error::StaleCheckpoint`].
     ///
     /// # Examples
     ///
@@ -471,6 +2045,10 @@ where
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
     pub fn close_at(&mut self, c: &Checkpoint, data: T) -> Result<Id, Error> {
+        if c.generation() != self.generation {
+            return Err(Error::StaleCheckpoint);
+        }
+
         let (id, parent) = c.get();
 
         if parent.as_ref() != self.parents.last() {
@@ -479,25 +2057,49 @@ where
 
         let next_id = NonMax::new(self.tree.len()).ok_or(Error::Overflow)?;
 
-        let Some(links) = self.tree.get_mut(id) else {
+        if id.get() >= self.tree.len() {
+            // The checkpoint refers to the next node to be inserted.
+            if id != next_id {
+                return Err(Error::CloseAtError);
+            }
+
+            self.check_insert()?;
             let new_id = self.insert(data, Kind::Node, S::point(self.cursor))?;
             self.sibling = Some(new_id);
-            debug_assert_eq!(new_id, id, "new id should match the expected id");
+
+            if let Some(limits) = &mut self.limits {
+                limits.open();
+                limits.close(new_id);
+            }
+
             return Ok(Id(new_id));
+        }
+
+        if let Some(limits) = &mut self.limits {
+            limits.close_at(next_id, c.children())?;
+        }
+
+        let Some(links) = self.tree.get_mut(id) else {
+            return Err(Error::MissingNode(Id(id)));
         };
 
-        let parent = replace(&mut links.parent, Some(next_id));
-        let prev = replace(&mut links.prev, None);
+        let parent = links.parent.replace(next_id);
+        let prev = links.prev.take();
 
         // Restructuring is necessary to calculate the full span of the newly
         // inserted node and update parent references to point to the newly
         // inserted node.
-        let (last, span) = if let Some(next) = links.next {
+        let (last, len, span) = if let Some(next) = links.next {
             let span = links.span;
-            let (last, end) = restructure_close_at(&mut self.tree, next_id, next)?;
-            (Some(last), S::new(span.start(), end))
+            let (last, len, last_span) = restructure_close_at(&mut self.tree, next_id, next)?;
+            (Some(last), len + 1, span.join(&last_span))
         } else {
-            (Some(id), links.span)
+            (Some(id), 1, links.span)
+        };
+
+        let span = match self.policy {
+            CursorPolicy::Contiguous => span,
+            _ => self.children_span(id)?,
         };
 
         let added = Links {
@@ -509,6 +2111,9 @@ where
             next: None,
             first: Some(id),
             last,
+            len,
+            flags: 0,
+            marks: 0,
         };
 
         if let Some(parent) = parent.and_then(|id| self.tree.get_mut(id)) {
@@ -516,20 +2121,33 @@ where
                 parent.first = Some(next_id);
             }
 
-            if parent.last == Some(id) {
+            // The wrapped nodes are always the trailing children of the
+            // parent, so the last of them is replaced.
+            if parent.last == last {
                 parent.last = Some(next_id);
             }
+
+            parent.len -= len - 1;
         }
 
         if let Some(prev) = prev.and_then(|id| self.tree.get_mut(id)) {
             prev.next = Some(next_id);
         }
 
-        // If we're replacing the first node of the tree, the newly inserted
-        // node should be set as the first node.
-        if self.tree.first_id() == Some(id) {
-            let (first, _) = self.tree.links_mut();
-            *first = Some(next_id);
+        // If we're replacing the first or last node of the tree, the newly
+        // inserted node takes its place.
+        if parent.is_none() {
+            let (first, tree_last, roots) = self.tree.links_mut();
+
+            if *first == Some(id) {
+                *first = Some(next_id);
+            }
+
+            if *tree_last == last {
+                *tree_last = Some(next_id);
+            }
+
+            *roots -= len - 1;
         }
 
         // Do necessary accounting.
@@ -540,6 +2158,135 @@ where
         Ok(Id(next_id))
     }
 
+    /// Push an item from a postfix (reverse polish notation) stream.
+    ///
+    /// An [`Postfix::Operand`] is inserted as a token, and an
+    /// [`Postfix::Operator`] wraps the last `arity` completed operands into a
+    /// new node in source order. The resulting subtree is in turn treated as
+    /// a completed operand. Once the stream is done, call
+    /// [`Builder::finish_postfix`] to verify that exactly one subtree
+    /// remains.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::PostfixUnderflow`] if an operator has a greater
+    /// arity than the number of completed operands, and with
+    /// [`Error::CloseAtError`] if the operands are not siblings of the node
+    /// currently being built.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Postfix};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// // 1 2 + 3 *
+    /// tree.push_postfix(Postfix::Operand { value: "number", len: 1 })?;
+    /// tree.push_postfix(Postfix::Operand { value: "number", len: 1 })?;
+    /// tree.push_postfix(Postfix::Operator { value: "add", arity: 2 })?;
+    /// tree.push_postfix(Postfix::Operand { value: "number", len: 1 })?;
+    /// tree.push_postfix(Postfix::Operator { value: "mul", arity: 2 })?;
+    /// tree.finish_postfix()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let expected = syntree::tree! {
+    ///     "mul" => {
+    ///         "add" => {
+    ///             ("number", 1),
+    ///             ("number", 1),
+    ///         },
+    ///         ("number", 1),
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree, expected);
+    ///
+    /// let mul = tree.first().ok_or("missing mul")?;
+    /// assert_eq!(mul.range(), 0..3);
+    /// let add = mul.first().ok_or("missing add")?;
+    /// assert_eq!(add.range(), 0..2);
+    /// assert_eq!(add.next().map(|n| n.range()), Some(2..3));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Operators with too few operands raise an error:
+    ///
+    /// ```
+    /// use syntree::{Builder, Error, Postfix};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// tree.push_postfix(Postfix::Operand { value: "number", len: 1 })?;
+    ///
+    /// assert_eq!(
+    ///     tree.push_postfix(Postfix::Operator { value: "add", arity: 2 }),
+    ///     Err(Error::PostfixUnderflow { arity: 2, operands: 1 })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn push_postfix(&mut self, item: Postfix<T, S::Length>) -> Result<(), Error> {
+        match item {
+            Postfix::Operand { value, len } => {
+                let c = self.checkpoint()?;
+                self.token(value, len)?;
+                self.postfix.push(c);
+            }
+            Postfix::Operator { value, arity } => {
+                let operands = self.postfix.len();
+
+                let Some(start) = operands.checked_sub(arity) else {
+                    return Err(Error::PostfixUnderflow { arity, operands });
+                };
+
+                let c = match self.postfix.get(start) {
+                    Some(c) => c.clone(),
+                    None => self.checkpoint()?,
+                };
+
+                self.close_at(&c, value)?;
+                self.postfix.truncate(start);
+                self.postfix.push(c);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finish a postfix stream pushed through [`Builder::push_postfix`],
+    /// returning the identifier of the single remaining subtree.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::PostfixUnbalanced`] unless exactly one completed
+    /// subtree remains.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error, Postfix};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// tree.push_postfix(Postfix::Operand { value: "number", len: 1 })?;
+    /// tree.push_postfix(Postfix::Operand { value: "number", len: 1 })?;
+    ///
+    /// assert_eq!(tree.finish_postfix(), Err(Error::PostfixUnbalanced { operands: 2 }));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn finish_postfix(&mut self) -> Result<Id, Error> {
+        let [c] = &self.postfix[..] else {
+            return Err(Error::PostfixUnbalanced {
+                operands: self.postfix.len(),
+            });
+        };
+
+        let id = Id(c.node());
+        self.postfix.clear();
+        Ok(id)
+    }
+
     /// Build a [Tree] from the current state of the builder.
     ///
     /// # Errors
@@ -593,7 +2340,202 @@ where
             return Err(Error::BuildError);
         }
 
-        Ok(self.tree)
+        let mut tree = self.tree;
+
+        // Tokens are indexed by where they end, which is only in order if
+        // they are.
+        if matches!(self.policy, CursorPolicy::Unordered) {
+            tree.indexes_mut().sort();
+        }
+
+        Ok(tree)
+    }
+
+    /// Take the nodes which have been completed at the top level out of the
+    /// builder as a standalone tree, releasing their storage.
+    ///
+    /// This allows a forest of independent roots, like the records of a large
+    /// log file, to be processed as each root is closed instead of holding
+    /// the whole tree in memory until [`Builder::build`] is called. Every root
+    /// completed since the last flush is included in the returned tree, and
+    /// building then continues with an empty tree.
+    ///
+    /// Returns `None` if nothing has been completed since the last flush, or
+    /// if a node is still open or a postfix expression is being built through
+    /// [`Builder::push_postfix`].
+    ///
+    /// Spans are not affected by flushing, so they keep referring to the
+    /// whole source and the span of each flushed tree starts where the
+    /// previous one ended. Identifiers on the other hand start at zero in
+    /// each flushed tree independently, so an [`Id`] returned by the builder
+    /// is only valid for the tree flushed next. For the same reason any
+    /// [`Checkpoint`] created before flushing is invalidated, and using it
+    /// with [`Builder::close_at`] errors with [`Error::StaleCheckpoint`].
+    /// Any [`BuilderLimits`] apply to each flushed tree on its own.
+    ///
+    /// Flushing is never required, and a builder which is never flushed
+    /// behaves exactly as if this method didn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// for (n, len) in [3, 5].into_iter().enumerate() {
+    ///     tree.open("record")?;
+    ///     tree.token("lit", len)?;
+    ///     tree.close()?;
+    ///
+    ///     let record = tree.flush_completed().ok_or("missing record")?;
+    ///     assert_eq!(record.len(), 2);
+    ///
+    ///     let lit = record.first_token().ok_or("missing lit")?;
+    ///     assert_eq!(lit.range(), [0..3, 3..8][n]);
+    /// }
+    ///
+    /// assert!(tree.flush_completed().is_none());
+    /// assert!(tree.build()?.is_empty());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn flush_completed(&mut self) -> Option<Tree<T, S>> {
+        if !self.parents.is_empty() || !self.postfix.is_empty() || self.tree.is_empty() {
+            return None;
+        }
+
+        let mut empty = Tree::new_with();
+        *empty.span_mut() = S::point(self.cursor);
+        let mut tree = core::mem::replace(&mut self.tree, empty);
+
+        if matches!(self.policy, CursorPolicy::Unordered) {
+            tree.indexes_mut().sort();
+        }
+
+        self.checkpoint = None;
+        self.generation = self.generation.wrapping_add(1);
+        self.sibling = None;
+
+        if let Some(limits) = &mut self.limits {
+            limits.reset();
+        }
+
+        Some(tree)
+    }
+
+    /// Add a token covering `span`, checking it against the cursor policy.
+    fn push_token(&mut self, value: T, span: S) -> Result<Id, Error> {
+        self.check_insert()?;
+        self.place(span.start(), span.end())?;
+
+        let id = self.insert(value, Kind::Token, span)?;
+        self.sibling = Some(id);
+
+        if let Some(limits) = &mut self.limits {
+            limits.token();
+        }
+
+        let id = Id(id);
+
+        if !span.is_empty() {
+            self.tree.indexes_mut().push(span.end(), id);
+        }
+
+        Ok(id)
+    }
+
+    /// Check that something covering `start..end` can be placed according to
+    /// the cursor policy, and move the cursor past it.
+    ///
+    /// This is the only place where the cursor policy is enforced.
+    #[allow(clippy::unnecessary_cast)]
+    fn place(&mut self, start: Index, end: Index) -> Result<(), Error> {
+        if end < start {
+            return Err(Error::BackwardsSpan {
+                start: start as usize,
+                end: end as usize,
+            });
+        }
+
+        let cursor = self.cursor;
+
+        match self.policy {
+            CursorPolicy::Contiguous if start > cursor => {
+                return Err(Error::CursorGap {
+                    cursor: cursor as usize,
+                    start: start as usize,
+                });
+            }
+            CursorPolicy::Contiguous | CursorPolicy::Monotonic if start < cursor => {
+                // Tokens are placed in document order under these policies,
+                // so the last non-empty token is the one the span collides
+                // with.
+                let previous = self
+                    .tree
+                    .walk_rev()
+                    .find(|n| n.kind() == Kind::Token && !n.span().is_empty());
+
+                let (previous_start, previous_end) = match &previous {
+                    Some(node) => (node.span().start(), node.span().end()),
+                    None => (cursor, cursor),
+                };
+
+                return Err(Error::CursorBackwards {
+                    cursor: cursor as usize,
+                    start: start as usize,
+                    end: end as usize,
+                    previous: previous.map(|n| n.id()),
+                    previous_start: previous_start as usize,
+                    previous_end: previous_end as usize,
+                });
+            }
+            _ => {}
+        }
+
+        if end > cursor {
+            self.cursor = end;
+            self.tree.span_mut().set_end(end);
+        }
+
+        Ok(())
+    }
+
+    /// Compute the span of a node from its children, starting with `first`,
+    /// according to the cursor policy.
+    fn children_span(&self, first: NonMax) -> Result<S, Error> {
+        let node = self
+            .tree
+            .node_at(first)
+            .ok_or(Error::MissingNode(Id(first)))?;
+
+        let mut span = *node.span();
+
+        for node in node.siblings().skip(1) {
+            span = span.join(node.span());
+        }
+
+        Ok(span)
+    }
+
+    /// Add `len` to `index`, erroring with [`Error::SpanOverflow`] for the
+    /// node which would be added next if it doesn't fit.
+    fn add_to<L>(&self, index: Index, len: L) -> Result<Index, Error>
+    where
+        L: Length,
+    {
+        if let Some(index) = len.add_to(index) {
+            return Ok(index);
+        }
+
+        let at = NonMax::new(self.tree.len()).ok_or(Error::Overflow)?;
+        Err(Error::SpanOverflow { at: Id(at) })
+    }
+
+    /// Check that a new node can be inserted without exceeding any limits.
+    fn check_insert(&self) -> Result<(), Error> {
+        if let Some(limits) = &self.limits {
+            limits.check_insert(self.tree.len(), self.parents.last().copied())?;
+        }
+
+        Ok(())
     }
 
     /// Insert a new node.
@@ -612,6 +2554,9 @@ where
             next: None,
             first: None,
             last: None,
+            len: 0,
+            flags: 0,
+            marks: 0,
         });
 
         if let Some(id) = parent {
@@ -621,16 +2566,18 @@ where
                 }
 
                 node.last = Some(new);
-                node.span.set_end(span.end());
+                node.len += 1;
+                node.span = node.span.join(&span);
             }
         } else {
-            let (first, last) = self.tree.links_mut();
+            let (first, last, roots) = self.tree.links_mut();
 
             if first.is_none() {
                 *first = Some(new);
             }
 
             *last = Some(new);
+            *roots += 1;
         }
 
         if let Some(node) = prev.and_then(|id| self.tree.links_at_mut(id)) {
@@ -653,8 +2600,22 @@ where
             tree: self.tree.clone(),
             parents: self.parents.clone(),
             checkpoint: self.checkpoint.clone(),
+            generation: self.generation,
             sibling: self.sibling,
             cursor: self.cursor,
+            // Checkpoints are shared, so postfix operands have to be copied to
+            // avoid one builder updating the operands of another.
+            postfix: self
+                .postfix
+                .iter()
+                .map(|c| {
+                    let (node, parent) = c.get();
+                    Checkpoint::new(node, parent, c.children(), c.generation())
+                })
+                .collect(),
+            limits: self.limits.clone(),
+            policy: self.policy,
+            recovered: self.recovered.clone(),
         }
     }
 }
@@ -669,24 +2630,41 @@ where
     }
 }
 
+/// Get the inclusive range of identifiers occupied by the subtree rooted at
+/// `node`, if it is stored contiguously.
+fn contiguous<T, S>(node: Node<'_, T, S>) -> Option<(usize, usize)> {
+    let mut start = node.id().0.get();
+    let mut end = start;
+    let mut count = 1;
+
+    for node in node.walk() {
+        let id = node.id().0.get();
+        start = start.min(id);
+        end = end.max(id);
+        count += 1;
+    }
+
+    (end - start + 1 == count).then_some((start, end))
+}
+
 // Adjust span to encapsulate all children and check that we just inserted the
 // checkpointed node in the right location which should be the tail sibling of
-// the replaced node.
+// the replaced node. This also counts the number of restructured siblings.
 fn restructure_close_at<T, S>(
     tree: &mut Tree<T, S>,
     parent_id: NonMax,
     next: NonMax,
-) -> Result<(NonMax, Index), Error>
+) -> Result<(NonMax, u32, S), Error>
 where
     S: TreeSpan,
 {
     let mut links = tree.get_mut(next).ok_or(Error::MissingNode(Id(next)))?;
-    let mut last = (next, links.span.end());
+    let mut last = (next, 1, links.span);
     links.parent = Some(parent_id);
 
     while let Some(next) = links.next {
         links = tree.get_mut(next).ok_or(Error::MissingNode(Id(next)))?;
-        last = (next, links.span.end());
+        last = (next, last.1 + 1, links.span);
         links.parent = Some(parent_id);
     }
 
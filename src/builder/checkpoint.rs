@@ -1,5 +1,6 @@
 use core::cell::Cell;
-use std::rc::Rc;
+
+use alloc::rc::Rc;
 
 use crate::non_max::NonMax;
 
@@ -16,12 +17,33 @@ use crate::non_max::NonMax;
 pub struct Checkpoint(Rc<Cell<Inner>>);
 
 impl Checkpoint {
-    pub(crate) fn new(node: NonMax, parent: Option<NonMax>) -> Self {
-        Self(Rc::new(Cell::new(Inner { node, parent })))
+    pub(crate) fn new(
+        node: NonMax,
+        parent: Option<NonMax>,
+        children: usize,
+        generation: u32,
+    ) -> Self {
+        Self(Rc::new(Cell::new(Inner {
+            node,
+            parent,
+            children,
+            generation,
+        })))
     }
 
     pub(crate) fn set(&self, node: NonMax, parent: Option<NonMax>) {
-        self.0.set(Inner { node, parent });
+        let Inner {
+            children,
+            generation,
+            ..
+        } = self.0.get();
+
+        self.0.set(Inner {
+            node,
+            parent,
+            children,
+            generation,
+        });
     }
 
     pub(crate) fn node(&self) -> NonMax {
@@ -29,9 +51,17 @@ impl Checkpoint {
     }
 
     pub(crate) fn get(&self) -> (NonMax, Option<NonMax>) {
-        let Inner { node, parent } = self.0.get();
+        let Inner { node, parent, .. } = self.0.get();
         (node, parent)
     }
+
+    pub(crate) fn children(&self) -> usize {
+        self.0.get().children
+    }
+
+    pub(crate) fn generation(&self) -> u32 {
+        self.0.get().generation
+    }
 }
 
 /// The parent of the checkpoint.
@@ -41,4 +71,10 @@ struct Inner {
     node: NonMax,
     // The parent node of the context being checkpointed.
     parent: Option<NonMax>,
+    // The number of siblings preceding the checkpoint, used when enforcing
+    // limits.
+    children: usize,
+    // The number of times the builder had been flushed when the checkpoint
+    // was created, since checkpoints don't survive flushing.
+    generation: u32,
 }
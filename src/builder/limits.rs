@@ -0,0 +1,311 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::builder::Id;
+use crate::error::Error;
+use crate::non_max::NonMax;
+
+/// Resource limits enforced by a [`Builder`] while a tree is being built.
+///
+/// This is useful when parsing untrusted input, so that a parser can abort
+/// early instead of building a tree with an absurd size, depth or width.
+/// Limits are set through [`Builder::with_limits`], and all limits are
+/// unbounded by default.
+///
+/// [`Builder`]: crate::Builder
+/// [`Builder::with_limits`]: crate::Builder::with_limits
+///
+/// # Examples
+///
+/// ```
+/// use syntree::{Builder, BuilderLimits, Error, Limit};
+///
+/// let limits = BuilderLimits::new().max_depth(2);
+/// let mut tree = Builder::new().with_limits(limits);
+///
+/// tree.open("root")?;
+/// tree.open("child")?;
+///
+/// let Err(Error::LimitExceeded { limit, .. }) = tree.token("lit", 1) else {
+///     return Err("expected limit to be exceeded".into());
+/// };
+///
+/// assert_eq!(limit, Limit::Depth);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuilderLimits {
+    max_nodes: usize,
+    max_depth: usize,
+    max_children_per_node: usize,
+}
+
+impl BuilderLimits {
+    /// Construct a new set of limits where nothing is limited.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_nodes: usize::MAX,
+            max_depth: usize::MAX,
+            max_children_per_node: usize::MAX,
+        }
+    }
+
+    /// Limit the total number of nodes and tokens in the tree.
+    #[must_use]
+    pub const fn max_nodes(self, max_nodes: usize) -> Self {
+        Self { max_nodes, ..self }
+    }
+
+    /// Limit the depth of the tree, where the roots of the tree are at depth
+    /// one.
+    #[must_use]
+    pub const fn max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
+    /// Limit the number of children, including tokens, of a single node.
+    #[must_use]
+    pub const fn max_children_per_node(self, max_children_per_node: usize) -> Self {
+        Self {
+            max_children_per_node,
+            ..self
+        }
+    }
+}
+
+impl Default for BuilderLimits {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The limit which was exceeded, as reported through
+/// [`Error::LimitExceeded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Limit {
+    /// The limit set through [`BuilderLimits::max_nodes`].
+    Nodes,
+    /// The limit set through [`BuilderLimits::max_depth`].
+    Depth,
+    /// The limit set through [`BuilderLimits::max_children_per_node`].
+    Children,
+}
+
+/// The children of a node being built, or of the roots of the tree.
+#[derive(Debug, Clone, Default)]
+struct Level {
+    /// The number of children.
+    children: usize,
+    /// The heights of the children keyed by their position among their
+    /// siblings, where heights are strictly decreasing. This allows for
+    /// finding the height of the tallest child at or after a given position.
+    heights: Vec<(usize, usize)>,
+}
+
+impl Level {
+    /// The height of the tallest child at or after `key`.
+    fn height(&self, key: usize) -> usize {
+        let index = self.heights.partition_point(|&(k, _)| k < key);
+        self.heights.get(index).map_or(0, |&(_, h)| h)
+    }
+
+    /// Push a child with the given height.
+    fn push(&mut self, height: usize) {
+        while let Some(&(_, h)) = self.heights.last() {
+            if h > height {
+                break;
+            }
+
+            self.heights.pop();
+        }
+
+        self.heights.push((self.children, height));
+        self.children += 1;
+    }
+}
+
+/// Incremental tracking of the size of a tree being built.
+#[derive(Debug, Clone)]
+pub(crate) struct Tracker {
+    limits: BuilderLimits,
+    levels: Vec<Level>,
+    /// The most recently closed node and its children, in case it is
+    /// reopened.
+    closed: Option<(NonMax, Level)>,
+}
+
+impl Tracker {
+    pub(crate) fn new(limits: BuilderLimits) -> Self {
+        Self {
+            limits,
+            levels: vec![Level::default()],
+            closed: None,
+        }
+    }
+
+    /// The number of children currently in the innermost level.
+    pub(crate) fn children(&self) -> usize {
+        self.levels.last().map_or(0, |l| l.children)
+    }
+
+    /// Forget everything which has been tracked, as when the tree being built
+    /// is taken out of the builder.
+    pub(crate) fn reset(&mut self) {
+        *self = Self::new(self.limits);
+    }
+
+    /// The number of nodes which can still be inserted into a tree of `len`
+    /// nodes.
+    pub(crate) fn remaining(&self, len: usize) -> usize {
+        self.limits.max_nodes.saturating_sub(len)
+    }
+
+    /// Check that a node can be inserted at the current position.
+    pub(crate) fn check_insert(&self, len: usize, parent: Option<NonMax>) -> Result<(), Error> {
+        let id = NonMax::new(len).ok_or(Error::Overflow)?;
+
+        if len >= self.limits.max_nodes {
+            return Err(limit(Limit::Nodes, id));
+        }
+
+        if self.levels.len() > self.limits.max_depth {
+            return Err(limit(Limit::Depth, id));
+        }
+
+        if let Some(parent) = parent {
+            if self.children() >= self.limits.max_children_per_node {
+                return Err(limit(Limit::Children, parent));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record that a token has been inserted.
+    pub(crate) fn token(&mut self) {
+        self.closed = None;
+
+        if let Some(level) = self.levels.last_mut() {
+            level.push(1);
+        }
+    }
+
+    /// Record that a token has been inserted at `position` among the children
+    /// of the innermost level.
+    pub(crate) fn insert_token(&mut self, position: usize) {
+        let Some(level) = self.levels.last_mut() else {
+            return;
+        };
+
+        // The inserted token is followed by a sibling which is at least as
+        // tall, so it never needs to be tracked itself.
+        for (key, _) in &mut level.heights {
+            if *key >= position {
+                *key += 1;
+            }
+        }
+
+        level.children += 1;
+    }
+
+    /// Record that a node has been opened.
+    pub(crate) fn open(&mut self) {
+        self.closed = None;
+        self.levels.push(Level::default());
+    }
+
+    /// Record that the node `id` has been closed.
+    pub(crate) fn close(&mut self, id: NonMax) {
+        let Some(level) = self.levels.pop() else {
+            return;
+        };
+
+        let height = 1 + level.height(0);
+
+        if let Some(parent) = self.levels.last_mut() {
+            parent.push(height);
+        }
+
+        self.closed = Some((id, level));
+    }
+
+    /// Check and record that the node `id` wraps all siblings after the first
+    /// `children` ones.
+    pub(crate) fn close_at(&mut self, id: NonMax, children: usize) -> Result<(), Error> {
+        if id.get() >= self.limits.max_nodes {
+            return Err(limit(Limit::Nodes, id));
+        }
+
+        let depth = self.levels.len();
+
+        let Some(level) = self.levels.last_mut() else {
+            return Ok(());
+        };
+
+        let wrapped = level.children.saturating_sub(children);
+
+        if wrapped > self.limits.max_children_per_node {
+            return Err(limit(Limit::Children, id));
+        }
+
+        let height = level.height(children);
+
+        if depth + height > self.limits.max_depth {
+            return Err(limit(Limit::Depth, id));
+        }
+
+        let index = level.heights.partition_point(|&(k, _)| k < children);
+
+        let heights = level
+            .heights
+            .split_off(index)
+            .into_iter()
+            .map(|(k, h)| (k - children, h))
+            .collect();
+
+        level.children = children;
+        level.push(1 + height);
+        self.closed = Some((
+            id,
+            Level {
+                children: wrapped,
+                heights,
+            },
+        ));
+
+        Ok(())
+    }
+
+    /// Record that the node `id` has been reopened.
+    pub(crate) fn reopen(&mut self, id: NonMax) {
+        let Some((closed, level)) = self.closed.take() else {
+            return;
+        };
+
+        if closed != id {
+            return;
+        }
+
+        if let Some(parent) = self.levels.last_mut() {
+            parent.children = parent.children.saturating_sub(1);
+
+            // The reopened node is the last child, so if it is tracked it is
+            // the last entry.
+            if parent.heights.last().map(|&(k, _)| k) == Some(parent.children) {
+                parent.heights.pop();
+            }
+        }
+
+        self.levels.push(level);
+    }
+}
+
+fn limit(limit: Limit, id: NonMax) -> Error {
+    Error::LimitExceeded {
+        limit,
+        id: Id::new(id),
+    }
+}
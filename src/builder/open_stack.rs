@@ -0,0 +1,81 @@
+use core::iter::FusedIterator;
+use core::slice;
+
+use crate::builder::Id;
+use crate::non_max::NonMax;
+use crate::span::TreeSpan;
+use crate::tree::Tree;
+
+/// An iterator over the nodes which are currently open in a [`Builder`], from
+/// the innermost to the outermost.
+///
+/// See [`Builder::open_stack`].
+///
+/// [`Builder`]: crate::Builder
+/// [`Builder::open_stack`]: crate::Builder::open_stack
+pub struct OpenStack<'a, T, S>
+where
+    S: TreeSpan,
+{
+    tree: &'a Tree<T, S>,
+    iter: slice::Iter<'a, NonMax>,
+}
+
+impl<'a, T, S> OpenStack<'a, T, S>
+where
+    S: TreeSpan,
+{
+    #[inline]
+    pub(crate) fn new(tree: &'a Tree<T, S>, parents: &'a [NonMax]) -> Self {
+        Self {
+            tree,
+            iter: parents.iter(),
+        }
+    }
+}
+
+impl<'a, T, S> Iterator for OpenStack<'a, T, S>
+where
+    S: TreeSpan,
+{
+    type Item = (&'a T, Id);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = Id(*self.iter.next_back()?);
+        Some((self.tree.get(id)?.value(), id))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for OpenStack<'_, T, S>
+where
+    S: TreeSpan,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let id = Id(*self.iter.next()?);
+        Some((self.tree.get(id)?.value(), id))
+    }
+}
+
+impl<T, S> ExactSizeIterator for OpenStack<'_, T, S> where S: TreeSpan {}
+
+impl<T, S> FusedIterator for OpenStack<'_, T, S> where S: TreeSpan {}
+
+impl<T, S> Clone for OpenStack<'_, T, S>
+where
+    S: TreeSpan,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            tree: self.tree,
+            iter: self.iter.clone(),
+        }
+    }
+}
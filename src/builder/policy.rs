@@ -0,0 +1,121 @@
+/// The rules a [`Builder`] enforces for where tokens are placed relative to
+/// its cursor.
+///
+/// The cursor is the position right after the last token added. Tokens added
+/// through [`Builder::token`] always start at the cursor, but tokens added
+/// through [`Builder::token_with_span`] and gaps introduced through
+/// [`Builder::advance`] are checked against the policy. Violating it fails
+/// with [`Error::CursorGap`] or [`Error::CursorBackwards`], and the builder
+/// is left unchanged.
+///
+/// The policy also determines the span of a node once it is closed:
+/// * Under [`CursorPolicy::Contiguous`] and [`CursorPolicy::Monotonic`] a
+///   node spans from the start of its first child to the end of its last
+///   child, which since children are ordered is the same as their hull.
+/// * Under [`CursorPolicy::Unordered`] a node spans the hull of its
+///   children, from the smallest start to the largest end.
+///
+/// A node without children is a point at the cursor where it was opened. The
+/// policy is selected through [`Builder::with_policy`] and defaults to
+/// [`CursorPolicy::Contiguous`].
+///
+/// [`Builder`]: crate::Builder
+/// [`Builder::token`]: crate::Builder::token
+/// [`Builder::token_with_span`]: crate::Builder::token_with_span
+/// [`Builder::advance`]: crate::Builder::advance
+/// [`Builder::with_policy`]: crate::Builder::with_policy
+/// [`Error::CursorGap`]: crate::Error::CursorGap
+/// [`Error::CursorBackwards`]: crate::Error::CursorBackwards
+///
+/// # Examples
+///
+/// ```
+/// use syntree::{Builder, CursorPolicy, Error, Span};
+///
+/// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+///
+/// tree.open("root")?;
+/// tree.token("lit", 2)?;
+/// tree.advance(1)?;
+/// let previous = tree.token("lit", 2)?;
+///
+/// assert_eq!(
+///     tree.token_with_span("lit", Span::new(0, 1)),
+///     Err(Error::CursorBackwards {
+///         cursor: 5,
+///         start: 0,
+///         end: 1,
+///         previous: Some(previous),
+///         previous_start: 3,
+///         previous_end: 5,
+///     })
+/// );
+///
+/// tree.close()?;
+///
+/// let tree = tree.build()?;
+/// let root = tree.first().ok_or("missing root")?;
+/// assert_eq!(root.range(), 0..5);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CursorPolicy {
+    /// Every token must start exactly at the cursor, so the tokens of a tree
+    /// cover its source without gaps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error, Span};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// tree.token("lit", 2)?;
+    /// tree.token_with_span("lit", Span::new(2, 4))?;
+    ///
+    /// assert_eq!(tree.advance(1), Err(Error::CursorGap { cursor: 4, start: 5 }));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[default]
+    Contiguous,
+    /// Tokens may leave gaps between them, but must never start before the
+    /// cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Span};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// tree.token("lit", 2)?;
+    /// tree.token_with_span("lit", Span::new(4, 6))?;
+    ///
+    /// let tree = tree.build()?;
+    /// assert_eq!(tree.span(), Span::new(0, 6));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    Monotonic,
+    /// Tokens may be placed anywhere. The cursor is the largest end of any
+    /// token added so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Span};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Unordered);
+    ///
+    /// tree.open("root")?;
+    /// tree.token_with_span("lit", Span::new(4, 6))?;
+    /// tree.token_with_span("lit", Span::new(1, 2))?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(root.range(), 1..6);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    Unordered,
+}
@@ -0,0 +1,46 @@
+/// An item in a postfix (reverse polish notation) stream, as accepted by
+/// [`Builder::push_postfix`].
+///
+/// [`Builder::push_postfix`]: crate::Builder::push_postfix
+///
+/// # Examples
+///
+/// ```
+/// use syntree::{Builder, Postfix};
+///
+/// let mut tree = Builder::new();
+///
+/// tree.push_postfix(Postfix::Operand { value: "number", len: 1 })?;
+/// tree.push_postfix(Postfix::Operator { value: "negate", arity: 1 })?;
+/// tree.finish_postfix()?;
+///
+/// let tree = tree.build()?;
+///
+/// let expected = syntree::tree! {
+///     "negate" => {
+///         ("number", 1)
+///     }
+/// };
+///
+/// assert_eq!(tree, expected);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Postfix<T, L = usize> {
+    /// An operand, which is inserted as a token of the given length.
+    Operand {
+        /// The value of the token.
+        value: T,
+        /// The length of the token.
+        len: L,
+    },
+    /// An operator, which wraps the last `arity` completed operands into a
+    /// node.
+    Operator {
+        /// The value of the node.
+        value: T,
+        /// The number of operands the operator consumes.
+        arity: usize,
+    },
+}
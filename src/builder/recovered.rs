@@ -0,0 +1,33 @@
+use alloc::boxed::Box;
+
+use crate::builder::Id;
+
+/// An error swallowed by [`Builder::open_recover`], as returned by
+/// [`Builder::take_recovered_errors`].
+///
+/// [`Builder::open_recover`]: crate::Builder::open_recover
+/// [`Builder::take_recovered_errors`]: crate::Builder::take_recovered_errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovered {
+    id: Id,
+    message: Box<str>,
+}
+
+impl Recovered {
+    pub(crate) fn new(id: Id, message: Box<str>) -> Self {
+        Self { id, message }
+    }
+
+    /// The identifier of the node which was being built when the error
+    /// occurred.
+    #[must_use]
+    pub const fn id(&self) -> Id {
+        self.id
+    }
+
+    /// The message of the error.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
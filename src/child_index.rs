@@ -0,0 +1,225 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::builder::Id;
+use crate::node::Node;
+use crate::span::TreeSpan;
+use crate::tree::{Kind, Tree};
+
+/// An index of the children of every node in a tree, allowing them to be
+/// accessed by position and looked up by offset in logarithmic time.
+///
+/// Children are linked to each other through their siblings, so finding the
+/// child of a node which covers an offset otherwise requires a linear scan.
+/// This is constructed once for a tree with [`ChildIndex::new`], after which
+/// lookups on wide nodes such as long statement lists or big arrays perform a
+/// binary search over the children instead.
+///
+/// Children are assumed to be ordered and not to overlap, which is always the
+/// case unless the tree was built with [`CursorPolicy::Unordered`].
+///
+/// The index only refers to nodes by identifier, so looking up a node from a
+/// different tree is not meaningful.
+///
+/// [`CursorPolicy::Unordered`]: crate::CursorPolicy::Unordered
+///
+/// # Examples
+///
+/// ```
+/// use syntree::ChildIndex;
+///
+/// let tree = syntree::tree! {
+///     "array" => {
+///         ("open", 1),
+///         "number" => {
+///             ("lit", 3),
+///         },
+///         ("comma", 1),
+///         "number" => {
+///             ("lit", 2),
+///         },
+///         ("close", 1),
+///     }
+/// };
+///
+/// let index = ChildIndex::new(&tree);
+/// let array = tree.first().ok_or("missing array")?;
+///
+/// assert_eq!(index.len(array), 5);
+///
+/// let number = index.child_by_offset(array, 6).ok_or("missing number")?;
+/// assert_eq!(*number.value(), "number");
+/// assert_eq!(number.range(), 5..7);
+///
+/// let comma = index.nth(array, 2).ok_or("missing comma")?;
+/// assert_eq!(comma.range(), 4..5);
+///
+/// assert!(index.child_by_offset(array, 8).is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct ChildIndex<'a, T, S>
+where
+    S: TreeSpan,
+{
+    tree: &'a Tree<T, S>,
+    /// The roots of the tree, followed by the children of every node grouped
+    /// by their parent.
+    children: Vec<Id>,
+    /// The position in `children` where the children of each node start,
+    /// indexed by the identifier of the node.
+    starts: Vec<usize>,
+    /// The number of roots in the tree.
+    roots: usize,
+}
+
+impl<'a, T, S> ChildIndex<'a, T, S>
+where
+    S: TreeSpan,
+{
+    /// Construct an index of the children of every node in `tree`.
+    #[must_use]
+    pub fn new(tree: &'a Tree<T, S>) -> Self {
+        let mut children = Vec::with_capacity(tree.len());
+        let mut starts = vec![0; tree.len()];
+
+        children.extend(tree.children().map(|n| n.id()));
+        let roots = children.len();
+
+        for node in tree.walk() {
+            if node.kind() != Kind::Node {
+                continue;
+            }
+
+            if let Some(start) = starts.get_mut(node.id().index()) {
+                *start = children.len();
+            }
+
+            children.extend(node.children().map(|n| n.id()));
+        }
+
+        Self {
+            tree,
+            children,
+            starts,
+            roots,
+        }
+    }
+
+    /// Get the number of children of `node`.
+    ///
+    /// This is the same as the length of [`Node::children`].
+    #[must_use]
+    pub fn len(&self, node: Node<'_, T, S>) -> usize {
+        self.children_of(node).len()
+    }
+
+    /// Get the child of `node` at position `n`.
+    ///
+    /// This is the same as [`Iterator::nth`] over [`Node::children`], but
+    /// takes constant time.
+    #[must_use]
+    pub fn nth(&self, node: Node<'_, T, S>, n: usize) -> Option<Node<'a, T, S>> {
+        self.tree.get(*self.children_of(node).get(n)?)
+    }
+
+    /// Get the child of `node` which covers `offset`.
+    ///
+    /// A non-empty child covers the offsets from its start up to but not
+    /// including its end. If no such child exists, the first zero-width child
+    /// at `offset` is returned, if any. Offsets in gaps between children have
+    /// no child.
+    ///
+    /// This performs a binary search over the children of `node`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::ChildIndex;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("a", 2),
+    ///         ("empty", 0),
+    ///         ("b", 1),
+    ///         ("eof", 0),
+    ///     }
+    /// };
+    ///
+    /// let index = ChildIndex::new(&tree);
+    /// let root = tree.first().ok_or("missing root")?;
+    ///
+    /// assert_eq!(index.child_by_offset(root, 1).map(|n| *n.value()), Some("a"));
+    /// assert_eq!(index.child_by_offset(root, 2).map(|n| *n.value()), Some("b"));
+    /// assert_eq!(index.child_by_offset(root, 3).map(|n| *n.value()), Some("eof"));
+    /// assert_eq!(index.child_by_offset(root, 4), None);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn child_by_offset(&self, node: Node<'_, T, S>, offset: usize) -> Option<Node<'a, T, S>> {
+        self.find(self.children_of(node), offset)
+    }
+
+    /// Get the root of the tree which covers `offset`, see
+    /// [`ChildIndex::child_by_offset`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::ChildIndex;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "fn" => {
+    ///         ("ident", 3),
+    ///     },
+    ///     "struct" => {
+    ///         ("ident", 2),
+    ///     },
+    /// };
+    ///
+    /// let index = ChildIndex::new(&tree);
+    ///
+    /// assert_eq!(index.root_by_offset(3).map(|n| *n.value()), Some("struct"));
+    /// assert_eq!(index.root_by_offset(5), None);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn root_by_offset(&self, offset: usize) -> Option<Node<'a, T, S>> {
+        self.find(self.children.get(..self.roots)?, offset)
+    }
+
+    /// Get the identifiers of the children of `node`.
+    fn children_of(&self, node: Node<'_, T, S>) -> &[Id] {
+        let Some(&start) = self.starts.get(node.id().index()) else {
+            return &[];
+        };
+
+        let len = match node.kind() {
+            Kind::Node => node.children().len(),
+            Kind::Token => 0,
+        };
+
+        self.children.get(start..start + len).unwrap_or_default()
+    }
+
+    /// Find the node among `children` which covers `offset`.
+    fn find(&self, children: &[Id], offset: usize) -> Option<Node<'a, T, S>> {
+        let range = |id: &Id| self.tree.get(*id).map(|n| n.span().range());
+
+        let end = children.partition_point(|id| range(id).map_or(false, |r| r.start <= offset));
+        let last = self.tree.get(*children.get(end.checked_sub(1)?)?)?;
+        let last_range = last.span().range();
+
+        if last_range.end > offset {
+            return Some(last);
+        }
+
+        if last_range.start != offset {
+            return None;
+        }
+
+        // Every child starting at `offset` is zero-width, since the last one
+        // is.
+        let start = children.partition_point(|id| range(id).map_or(false, |r| r.start < offset));
+        self.tree.get(*children.get(start)?)
+    }
+}
@@ -0,0 +1,97 @@
+//! Indented rendering of trees, used by the [`Debug`][fmt::Debug]
+//! implementations of [`Tree`] and [`Node`].
+
+use core::fmt;
+
+use crate::node::{Node, WithDepths};
+use crate::span::TreeSpan;
+use crate::tree::{Kind, Tree};
+
+/// The depth below which nodes are elided when not using the alternate
+/// (`{:#?}`) format.
+const MAX_DEPTH: usize = 8;
+
+/// Format `tree` in the same format as [`print::print`].
+///
+/// [`print::print`]: crate::print::print
+pub(crate) fn fmt_tree<T, S>(f: &mut fmt::Formatter<'_>, tree: &Tree<T, S>) -> fmt::Result
+where
+    T: fmt::Debug,
+    S: TreeSpan,
+{
+    let limit = (!f.alternate()).then_some(MAX_DEPTH);
+    fmt_walk(f, tree.walk().with_depths(), 0, limit, true)
+}
+
+/// Format `node` and its subtree in the same format as [`print::print`].
+///
+/// [`print::print`]: crate::print::print
+pub(crate) fn fmt_subtree<T, S>(f: &mut fmt::Formatter<'_>, node: Node<'_, T, S>) -> fmt::Result
+where
+    T: fmt::Debug,
+    S: TreeSpan,
+{
+    fmt_line(f, 0, node)?;
+    fmt_walk(f, node.walk().with_depths(), 1, None, false)
+}
+
+fn fmt_walk<T, S>(
+    f: &mut fmt::Formatter<'_>,
+    walk: WithDepths<'_, T, S>,
+    base: usize,
+    limit: Option<usize>,
+    mut first: bool,
+) -> fmt::Result
+where
+    T: fmt::Debug,
+    S: TreeSpan,
+{
+    let mut eliding = false;
+
+    for (depth, node) in walk {
+        let depth = base + depth;
+        let elide = limit.map_or(false, |limit| depth > limit);
+
+        if elide && eliding {
+            continue;
+        }
+
+        if !first {
+            f.write_str("\n")?;
+        }
+
+        first = false;
+        eliding = elide;
+
+        if elide {
+            write!(f, "{:n$}…", "", n = depth * 2)?;
+        } else {
+            fmt_line(f, depth, node)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn fmt_line<T, S>(f: &mut fmt::Formatter<'_>, depth: usize, node: Node<'_, T, S>) -> fmt::Result
+where
+    T: fmt::Debug,
+    S: TreeSpan,
+{
+    write!(f, "{:n$}{:?}@", "", node.value(), n = depth * 2)?;
+    node.span().fmt_span(f)?;
+
+    if node.is_error() {
+        f.write_str("!")?;
+    }
+
+    if node.is_trivia() {
+        f.write_str("~")?;
+    }
+
+    if let Kind::Token = node.kind() {
+        f.write_str(" +")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,599 @@
+//! Utilities for finding and printing the differences between two trees.
+
+use core::fmt::{self, Write};
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::builder::Id;
+use crate::node::{Children, Node};
+use crate::span::{FmtSpan, TreeSpan};
+use crate::tree::Tree;
+
+/// The kind of a difference found by [`diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Difference {
+    /// The values of the two nodes differ.
+    Value,
+    /// The [`Kind`][crate::Kind] of the two nodes differ.
+    Kind,
+    /// The spans of the two nodes differ.
+    Span,
+    /// The subtree is present in the first tree but missing in the second.
+    Missing,
+    /// The subtree is missing in the first tree but present in the second.
+    Extra,
+}
+
+/// A single difference between two trees as reported by [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    difference: Difference,
+    a: Option<Id>,
+    b: Option<Id>,
+    path: Vec<usize>,
+}
+
+impl DiffEntry {
+    /// The kind of difference.
+    #[must_use]
+    pub const fn difference(&self) -> Difference {
+        self.difference
+    }
+
+    /// The identifier of the node in the first tree, if present.
+    #[must_use]
+    pub const fn a(&self) -> Option<Id> {
+        self.a
+    }
+
+    /// The identifier of the node in the second tree, if present.
+    #[must_use]
+    pub const fn b(&self) -> Option<Id> {
+        self.b
+    }
+
+    /// The path to the node, as a sequence of child indexes starting from the
+    /// roots of the tree. This is the same in both trees.
+    #[must_use]
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+}
+
+/// Options for [`print_diff`].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct DiffOptions {
+    context: usize,
+}
+
+impl DiffOptions {
+    /// Construct the default diff options.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { context: 3 }
+    }
+
+    /// Set the number of lines of context to include around each difference.
+    /// Defaults to `3`.
+    #[must_use]
+    pub const fn context(self, context: usize) -> Self {
+        Self { context }
+    }
+}
+
+impl Default for DiffOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Walk two trees in lockstep and report where they diverge.
+///
+/// Nodes at the same position are compared by value, kind and span, each of
+/// which is reported separately. The children of nodes at the same position
+/// are then compared, and any children which are only present in one of the
+/// trees are reported as a single [`Difference::Missing`] or
+/// [`Difference::Extra`] entry for the whole subtree.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::diff::{self, Difference};
+///
+/// let a = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///         ("ws", 1),
+///     }
+/// };
+///
+/// let b = syntree::tree! {
+///     "root" => {
+///         ("ident", 3),
+///     },
+///     "extra" => {}
+/// };
+///
+/// let entries = diff::diff(&a, &b);
+/// let entries = entries.iter().map(|e| (e.difference(), e.path())).collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     entries,
+///     [
+///         (Difference::Span, &[0][..]),
+///         (Difference::Value, &[0, 0][..]),
+///         (Difference::Missing, &[0, 1][..]),
+///         (Difference::Extra, &[1][..]),
+///     ]
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[must_use]
+pub fn diff<T, S>(a: &Tree<T, S>, b: &Tree<T, S>) -> Vec<DiffEntry>
+where
+    T: PartialEq,
+    S: TreeSpan + PartialEq,
+{
+    let mut entries = Vec::new();
+
+    lockstep(a, b, |step, path| {
+        let (difference, a, b) = match step {
+            Step::Both(a, b) => {
+                if a.value() != b.value() {
+                    entries.push(entry(Difference::Value, Some(a), Some(b), path));
+                }
+
+                if a.kind() != b.kind() {
+                    entries.push(entry(Difference::Kind, Some(a), Some(b), path));
+                }
+
+                if a.span() != b.span() {
+                    entries.push(entry(Difference::Span, Some(a), Some(b), path));
+                }
+
+                return;
+            }
+            Step::Missing(a) => (Difference::Missing, Some(a), None),
+            Step::Extra(b) => (Difference::Extra, None, Some(b)),
+        };
+
+        entries.push(entry(difference, a, b, path));
+    });
+
+    entries
+}
+
+/// Print a unified-style rendering of the regions in which two trees differ
+/// to the given [`io::Write`][std::io::Write].
+///
+/// This is the same as [`write_diff`], except that it writes to an I/O
+/// stream.
+///
+/// # Errors
+///
+/// Errors if writing to the output fails.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::diff::{self, DiffOptions};
+///
+/// let a = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///         ("ws", 1),
+///         ("number", 2),
+///     }
+/// };
+///
+/// let b = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///         ("ws", 1),
+///         ("ident", 2),
+///     }
+/// };
+///
+/// let mut s = Vec::new();
+/// diff::print_diff(&mut s, &a, &b, &DiffOptions::new().context(1))?;
+/// let s = String::from_utf8(s)?;
+///
+/// let expected = r#"@@ -3,2 +3,2 @@
+///    "ws"@3..4
+/// -  "number"@4..6
+/// +  "ident"@4..6
+/// "#;
+///
+/// assert_eq!(s, expected);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn print_diff<O, T, S>(
+    mut o: O,
+    a: &Tree<T, S>,
+    b: &Tree<T, S>,
+    options: &DiffOptions,
+) -> std::io::Result<()>
+where
+    O: std::io::Write,
+    T: fmt::Debug + PartialEq,
+    S: TreeSpan + PartialEq,
+{
+    let mut s = String::new();
+    // Writing to a string never fails.
+    _ = write_diff(&mut s, a, b, options);
+    o.write_all(s.as_bytes())
+}
+
+/// Write a unified-style rendering of the regions in which two trees differ.
+///
+/// Lines are rendered in the same format as [`print::print`], prefixed with
+/// `-` if they only belong to the first tree, `+` if they only belong to the
+/// second and a space if they are the same in both. Each hunk of differing
+/// lines is preceeded by a header indicating which lines in each rendered tree
+/// it covers, and is surrounded by a number of context lines as specified in
+/// [`DiffOptions`].
+///
+/// Nothing is written if the trees are equal.
+///
+/// [`print::print`]: crate::print::print
+///
+/// # Examples
+///
+/// ```
+/// use syntree::diff::{self, DiffOptions};
+///
+/// let a = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///         ("ws", 1),
+///         ("number", 2),
+///     }
+/// };
+///
+/// let b = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///         ("ws", 1),
+///         ("ident", 2),
+///     }
+/// };
+///
+/// let mut s = String::new();
+/// diff::write_diff(&mut s, &a, &b, &DiffOptions::new().context(1))?;
+///
+/// let expected = r#"@@ -3,2 +3,2 @@
+///    "ws"@3..4
+/// -  "number"@4..6
+/// +  "ident"@4..6
+/// "#;
+///
+/// assert_eq!(s, expected);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn write_diff<O, T, S>(
+    mut o: O,
+    a: &Tree<T, S>,
+    b: &Tree<T, S>,
+    options: &DiffOptions,
+) -> fmt::Result
+where
+    O: Write,
+    T: fmt::Debug + PartialEq,
+    S: TreeSpan + PartialEq,
+{
+    let mut lines = Vec::new();
+
+    lockstep(a, b, |step, path| {
+        let depth = path.len().saturating_sub(1);
+
+        match step {
+            Step::Both(a, b) => {
+                if a.value() == b.value() && a.kind() == b.kind() && a.span() == b.span() {
+                    lines.push((Tag::Same, render(depth, a)));
+                } else {
+                    lines.push((Tag::Removed, render(depth, a)));
+                    lines.push((Tag::Added, render(depth, b)));
+                }
+            }
+            Step::Missing(a) => {
+                lines.push((Tag::Removed, render(depth, a)));
+
+                for (d, n) in a.walk().with_depths() {
+                    lines.push((Tag::Removed, render(depth + d + 1, n)));
+                }
+            }
+            Step::Extra(b) => {
+                lines.push((Tag::Added, render(depth, b)));
+
+                for (d, n) in b.walk().with_depths() {
+                    lines.push((Tag::Added, render(depth + d + 1, n)));
+                }
+            }
+        }
+    });
+
+    // Mark every line which should be included in the output.
+    let mut included = vec![false; lines.len()];
+
+    for (n, (tag, _)) in lines.iter().enumerate() {
+        if !matches!(tag, Tag::Same) {
+            let start = n.saturating_sub(options.context);
+            let end = n
+                .saturating_add(options.context)
+                .saturating_add(1)
+                .min(lines.len());
+
+            for included in &mut included[start..end] {
+                *included = true;
+            }
+        }
+    }
+
+    let mut a_line = 1usize;
+    let mut b_line = 1usize;
+    let mut n = 0;
+
+    while n < lines.len() {
+        if !included[n] {
+            let (a_step, b_step) = lines[n].0.steps();
+            a_line += a_step;
+            b_line += b_step;
+            n += 1;
+            continue;
+        }
+
+        let start = n;
+
+        while n < lines.len() && included[n] {
+            n += 1;
+        }
+
+        let hunk = &lines[start..n];
+        let a_count = hunk.iter().map(|(tag, _)| tag.steps().0).sum::<usize>();
+        let b_count = hunk.iter().map(|(tag, _)| tag.steps().1).sum::<usize>();
+
+        writeln!(o, "@@ -{},{} +{},{} @@", a_line, a_count, b_line, b_count)?;
+
+        for (tag, line) in hunk {
+            writeln!(o, "{}{}", tag.prefix(), line)?;
+        }
+
+        a_line += a_count;
+        b_line += b_count;
+    }
+
+    Ok(())
+}
+
+/// Panic with a diff of two trees which are not equal, used by
+/// [`assert_tree_eq!`][crate::assert_tree_eq].
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_tree_eq_failed<T, S>(a: &Tree<T, S>, b: &Tree<T, S>) -> !
+where
+    T: fmt::Debug + PartialEq,
+    S: TreeSpan + PartialEq,
+{
+    let mut out = String::new();
+    // Writing to a string never fails.
+    _ = write_diff(&mut out, a, b, &DiffOptions::new());
+    panic!("assertion failed: trees are not equal\n{out}");
+}
+
+/// Compare a tree against an expected tree built by
+/// [`assert_tree!`][crate::assert_tree], panicking with a rendering of both
+/// trees and the first node at which they diverge if they differ.
+///
+/// Spans are ignored if the expected tree spans nothing.
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_tree<T, S>(actual: &Tree<T, S>, expected: &Tree<T, S>)
+where
+    T: fmt::Debug + PartialEq,
+    S: TreeSpan + PartialEq,
+{
+    let spans = !expected.span().is_empty();
+    let mut first = None;
+
+    lockstep(actual, expected, |step, path| {
+        if first.is_some() {
+            return;
+        }
+
+        let diverges = match &step {
+            Step::Both(a, b) => {
+                a.value() != b.value() || a.kind() != b.kind() || (spans && a.span() != b.span())
+            }
+            Step::Missing(..) | Step::Extra(..) => true,
+        };
+
+        if diverges {
+            first = Some((step, path.to_vec()));
+        }
+    });
+
+    let Some((step, path)) = first else {
+        return;
+    };
+
+    let (a, b) = match step {
+        Step::Both(a, b) => (Some(a), Some(b)),
+        Step::Missing(a) => (Some(a), None),
+        Step::Extra(b) => (None, Some(b)),
+    };
+
+    let mut out = String::new();
+    // Writing to a string never fails.
+    _ = write_assert_tree(&mut out, actual, expected, a, b, &path, spans);
+    panic!("{out}");
+}
+
+fn write_assert_tree<O, T, S>(
+    mut o: O,
+    actual: &Tree<T, S>,
+    expected: &Tree<T, S>,
+    a: Option<Node<'_, T, S>>,
+    b: Option<Node<'_, T, S>>,
+    path: &[usize],
+    spans: bool,
+) -> fmt::Result
+where
+    O: Write,
+    T: fmt::Debug,
+    S: TreeSpan,
+{
+    writeln!(o, "assertion failed: tree does not match")?;
+
+    if !spans {
+        writeln!(
+            o,
+            "note: spans are ignored since the expected tree has none"
+        )?;
+    }
+
+    writeln!(o, "actual:")?;
+
+    for (depth, node) in actual.walk().with_depths() {
+        writeln!(o, "  {}", render(depth, node))?;
+    }
+
+    writeln!(o, "expected:")?;
+
+    for (depth, node) in expected.walk().with_depths() {
+        if spans {
+            writeln!(o, "  {}", render(depth, node))?;
+        } else {
+            writeln!(o, "  {:n$}{:?}", "", node.value(), n = depth * 2)?;
+        }
+    }
+
+    writeln!(o, "first difference at {:?}:", path)?;
+
+    for (label, node) in [("actual", a), ("expected", b)] {
+        match node {
+            Some(node) => writeln!(
+                o,
+                "  {}: {:?} ({:?}) @ {}",
+                label,
+                node.value(),
+                node.kind(),
+                FmtSpan(node.span())
+            )?,
+            None => writeln!(o, "  {}: <missing>", label)?,
+        }
+    }
+
+    Ok(())
+}
+
+enum Step<'a, T, S> {
+    Both(Node<'a, T, S>, Node<'a, T, S>),
+    Missing(Node<'a, T, S>),
+    Extra(Node<'a, T, S>),
+}
+
+enum Tag {
+    Same,
+    Removed,
+    Added,
+}
+
+impl Tag {
+    /// The number of lines this tag occupies in the first and second tree.
+    fn steps(&self) -> (usize, usize) {
+        match self {
+            Tag::Same => (1, 1),
+            Tag::Removed => (1, 0),
+            Tag::Added => (0, 1),
+        }
+    }
+
+    fn prefix(&self) -> char {
+        match self {
+            Tag::Same => ' ',
+            Tag::Removed => '-',
+            Tag::Added => '+',
+        }
+    }
+}
+
+fn render<T, S>(depth: usize, node: Node<'_, T, S>) -> String
+where
+    T: fmt::Debug,
+    S: TreeSpan,
+{
+    format!(
+        "{:n$}{:?}@{}",
+        "",
+        node.value(),
+        FmtSpan(node.span()),
+        n = depth * 2
+    )
+}
+
+fn entry<T, S>(
+    difference: Difference,
+    a: Option<Node<'_, T, S>>,
+    b: Option<Node<'_, T, S>>,
+    path: &[usize],
+) -> DiffEntry {
+    DiffEntry {
+        difference,
+        a: a.map(|n| n.id()),
+        b: b.map(|n| n.id()),
+        path: path.to_vec(),
+    }
+}
+
+/// Walk two trees in lockstep, calling `f` with each step and the path to the
+/// node(s) being visited. Subtrees which are only present in one of the trees
+/// are not descended into.
+fn lockstep<'a, T, S, F>(a: &'a Tree<T, S>, b: &'a Tree<T, S>, mut f: F)
+where
+    S: TreeSpan,
+    F: FnMut(Step<'a, T, S>, &[usize]),
+{
+    let mut stack: Vec<(Children<'a, T, S>, Children<'a, T, S>)> =
+        vec![(a.children(), b.children())];
+    let mut path = vec![0usize];
+
+    while let Some((a, b)) = stack.last_mut() {
+        let step = match (a.next(), b.next()) {
+            (Some(a), Some(b)) => Step::Both(a, b),
+            (Some(a), None) => Step::Missing(a),
+            (None, Some(b)) => Step::Extra(b),
+            (None, None) => {
+                stack.pop();
+                path.pop();
+
+                if let Some(last) = path.last_mut() {
+                    *last += 1;
+                }
+
+                continue;
+            }
+        };
+
+        let descend = match &step {
+            Step::Both(a, b) => Some((a.children(), b.children())),
+            _ => None,
+        };
+
+        f(step, &path);
+
+        if let Some(children) = descend {
+            stack.push(children);
+            path.push(0);
+        } else if let Some(last) = path.last_mut() {
+            *last += 1;
+        }
+    }
+}
@@ -1,19 +1,336 @@
 //! Types associated with performing immutable editing of a tree.
 
-use std::collections::HashMap;
+mod undo;
 
-use crate::builder::Id;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::{self, FusedIterator};
+use core::ops::Range;
+use core::slice;
+
+use crate::builder::{Builder, CursorPolicy, Id};
 use crate::error::Error;
-use crate::links::Links;
-use crate::node::Node;
+use crate::node::{Children, Node};
 use crate::non_max::NonMax;
-use crate::span::{Index, Indexes, TreeSpan};
+use crate::span::{Span, TreeSpan};
 use crate::tree::{Kind, Tree};
 
+pub use self::undo::UndoLog;
+
 #[derive(Debug)]
-pub(crate) enum Change {
+pub(crate) enum Change<T> {
     /// Delete the given node.
     Delete,
+    /// Replace the node and its subtree with the tree at the given index.
+    Replace(usize),
+    /// Replace the value of the given node.
+    Value(T),
+    /// Wrap the given node in a new node with the given value.
+    Wrap(T),
+    /// Replace the given node with its children.
+    Unwrap,
+}
+
+/// Where an inserted subtree is placed relative to its anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    /// Immediately before the anchor.
+    Before,
+    /// Immediately after the anchor.
+    After,
+    /// As a child of the anchor, before the child at the given index.
+    Child(usize),
+}
+
+/// A handle to a subtree which has been registered for insertion in a
+/// [`ChangeSet`].
+///
+/// This is returned by functions such as [`ChangeSet::insert_before`], and
+/// can be used together with [`IdMapping::inserted`] to find out where the
+/// nodes of the inserted subtree ended up in the modified tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Insertion(usize);
+
+/// Material which can be inserted into a tree through a [`ChangeSet`].
+///
+/// This is implemented for a prebuilt [`Tree`], and for closures which
+/// receive a [`Builder`] to construct the inserted subtree on the fly.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::edit::ChangeSet;
+/// use syntree::{Builder, Error};
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("lit", 1),
+///     }
+/// };
+///
+/// let lit = tree.first().and_then(|n| n.first()).ok_or("missing lit")?;
+///
+/// let mut change_set = ChangeSet::new();
+///
+/// change_set.insert_after(lit.id(), |b: &mut Builder<_>| {
+///     b.token("lit", 2)?;
+///     Ok::<_, Error>(())
+/// })?;
+///
+/// assert_eq!(
+///     change_set.modify(&tree)?,
+///     syntree::tree! {
+///         "root" => {
+///             ("lit", 1),
+///             ("lit", 2),
+///         }
+///     }
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub trait IntoSubtree<T, S>
+where
+    S: TreeSpan,
+{
+    /// Convert the material into a tree.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the subtree couldn't be built.
+    fn into_subtree(self) -> Result<Tree<T, S>, Error>;
+}
+
+impl<T, S> IntoSubtree<T, S> for Tree<T, S>
+where
+    S: TreeSpan,
+{
+    #[inline]
+    fn into_subtree(self) -> Result<Tree<T, S>, Error> {
+        Ok(self)
+    }
+}
+
+impl<T, S, F> IntoSubtree<T, S> for F
+where
+    S: TreeSpan,
+    F: FnOnce(&mut Builder<T, S>) -> Result<(), Error>,
+{
+    #[inline]
+    fn into_subtree(self) -> Result<Tree<T, S>, Error> {
+        let mut builder = Builder::new_with();
+        self(&mut builder)?;
+        builder.build()
+    }
+}
+
+/// A mapping of identifiers produced when a [`ChangeSet`] is applied through
+/// [`ChangeSet::modify_with_ids`].
+///
+/// Node identifiers in a modified tree generally don't match the ones in the
+/// original tree, so this can be used to translate side tables which are
+/// keyed by [`Id`].
+///
+/// # Examples
+///
+/// ```
+/// use syntree::edit::ChangeSet;
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("lit", 1),
+///     }
+/// };
+///
+/// let lit = tree.first().and_then(|n| n.first()).ok_or("missing lit")?;
+///
+/// let inserted = syntree::tree! {
+///     "number" => {
+///         ("lit", 2),
+///     }
+/// };
+///
+/// let number = inserted.first().ok_or("missing number")?.id();
+///
+/// let mut change_set = ChangeSet::new();
+/// let insertion = change_set.insert_before(lit.id(), inserted)?;
+///
+/// let (tree, mapping) = change_set.modify_with_ids(&tree)?;
+///
+/// let number = mapping.inserted(insertion, number).ok_or("missing number")?;
+/// let number = tree.get(number).ok_or("missing number")?;
+/// assert_eq!(*number.value(), "number");
+/// assert_eq!(number.range(), 0..2);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IdMapping {
+    old: Vec<Option<Id>>,
+    inserted: BTreeMap<(usize, NonMax), Id>,
+}
+
+impl IdMapping {
+    /// Get the identifier in the modified tree of the node `id` from the
+    /// original tree.
+    ///
+    /// Returns [`None`] if the node didn't survive the modification, such as
+    /// when it has been removed or replaced. Inserted nodes have no
+    /// identifier in the original tree, see [`IdMapping::inserted`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 1),
+    ///         },
+    ///         ("whitespace", 1),
+    ///         "number" => {
+    ///             ("lit", 2),
+    ///         },
+    ///     },
+    ///     ("eof", 0),
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let first = root.first().ok_or("missing first")?;
+    /// let whitespace = first.next().ok_or("missing whitespace")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(first.id());
+    /// change_set.insert_before(whitespace.id(), syntree::tree!(("lit", 4)))?;
+    ///
+    /// let (new_tree, mapping) = change_set.modify_with_ids(&tree)?;
+    ///
+    /// assert_eq!(mapping.old_to_new(first.id()), None);
+    ///
+    /// for old in tree.walk() {
+    ///     if first.walk().chain([first]).any(|n| n == old) {
+    ///         continue;
+    ///     }
+    ///
+    ///     let new = mapping.old_to_new(old.id()).ok_or("missing node")?;
+    ///     let new = new_tree.get(new).ok_or("missing node")?;
+    ///     assert_eq!(old.value(), new.value());
+    /// }
+    ///
+    /// let second = root.last().and_then(|n| n.first()).ok_or("missing second")?;
+    /// let new = mapping.old_to_new(second.id()).and_then(|id| new_tree.get(id)).ok_or("missing second")?;
+    /// assert_eq!(second.range(), 2..4);
+    /// assert_eq!(new.range(), 5..7);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn old_to_new(&self, id: Id) -> Option<Id> {
+        self.old.get(id.0.get()).copied().flatten()
+    }
+
+    /// Iterate over the identifiers of all nodes which survived the
+    /// modification as `(old, new)` pairs, ordered by the old identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         ("lit", 2),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let first = root.first().ok_or("missing first")?;
+    /// let second = first.next().ok_or("missing second")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(first.id());
+    ///
+    /// let (new_tree, mapping) = change_set.modify_with_ids(&tree)?;
+    ///
+    /// let mut it = mapping.iter();
+    ///
+    /// let (old, new) = it.next().ok_or("missing root")?;
+    /// assert_eq!(old, root.id());
+    /// assert_eq!(new_tree.get(new).map(|n| *n.value()), Some("root"));
+    ///
+    /// let (old, new) = it.next().ok_or("missing second")?;
+    /// assert_eq!(old, second.id());
+    /// assert_eq!(new_tree.get(new).map(|n| n.range()), Some(0..2));
+    ///
+    /// assert!(it.next().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn iter(&self) -> IdMappingIter<'_> {
+        IdMappingIter {
+            iter: self.old.iter().enumerate(),
+        }
+    }
+
+    /// Get the identifier in the modified tree of the node `id` from the
+    /// subtree registered through `insertion`.
+    ///
+    /// Returns [`None`] if no such node was inserted, which is the case if
+    /// the anchor of the insertion was itself removed.
+    #[must_use]
+    pub fn inserted(&self, insertion: Insertion, id: Id) -> Option<Id> {
+        self.inserted.get(&(insertion.0, id.0)).copied()
+    }
+}
+
+impl<'a> IntoIterator for &'a IdMapping {
+    type Item = (Id, Id);
+    type IntoIter = IdMappingIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over `(old, new)` identifier pairs in an [`IdMapping`].
+///
+/// See [`IdMapping::iter`].
+#[derive(Debug, Clone)]
+pub struct IdMappingIter<'a> {
+    iter: iter::Enumerate<slice::Iter<'a, Option<Id>>>,
+}
+
+impl Iterator for IdMappingIter<'_> {
+    type Item = (Id, Id);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (index, new) = self.iter.next()?;
+
+            if let Some(new) = *new {
+                return Some((Id::new(NonMax::new(index)?), new));
+            }
+        }
+    }
+}
+
+impl FusedIterator for IdMappingIter<'_> {}
+
+/// A frame of nodes being visited while a change set is applied.
+struct Frame<'a, T, S> {
+    /// Children being visited.
+    children: Children<'a, T, S>,
+    /// The node whose children are being visited, or `None` for the roots of
+    /// the tree.
+    parent: Option<Node<'a, T, S>>,
+    /// Index of the next child.
+    index: usize,
+    /// The number of nodes to close once all children have been visited.
+    close: usize,
 }
 
 /// A recorded set of tree modifications.
@@ -72,9 +389,12 @@ pub struct ChangeSet<T, S>
 where
     S: TreeSpan,
 {
-    changes: HashMap<NonMax, Change>,
-    #[allow(unused)]
+    changes: BTreeMap<NonMax, Change<T>>,
+    inserts: BTreeMap<NonMax, Vec<(Position, usize)>>,
     trees: Vec<Tree<T, S>>,
+    texts: BTreeMap<usize, Box<str>>,
+    /// Flags added to every inserted node.
+    flags: u8,
 }
 
 impl<T, S> ChangeSet<T, S>
@@ -87,6 +407,47 @@ where
         Self::default()
     }
 
+    /// Tag every node inserted when the change set is applied with the given
+    /// user `flags`, in addition to any flags the inserted material already
+    /// has.
+    ///
+    /// This covers subtrees inserted through [`ChangeSet::insert_before`],
+    /// [`ChangeSet::insert_after`], [`ChangeSet::insert_child`] and
+    /// [`ChangeSet::replace`], as well as the nodes added through
+    /// [`ChangeSet::wrap`]. Nodes which are kept retain their flags, so tags
+    /// from earlier edits survive later ones. The tagged regions can be
+    /// queried through [`Tree::synthesized_ranges`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// const SYNTHESIZED: u8 = 0b1;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let lit = tree.first().and_then(|n| n.first()).ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new().with_inserted_flags(SYNTHESIZED);
+    /// change_set.insert_after(lit.id(), syntree::tree!(("semi", 1)))?;
+    ///
+    /// let tree = change_set.modify(&tree)?;
+    ///
+    /// let flags = tree.walk().map(|n| (*n.value(), n.flags())).collect::<Vec<_>>();
+    /// assert_eq!(flags, [("root", 0), ("lit", 0), ("semi", SYNTHESIZED)]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn with_inserted_flags(mut self, flags: u8) -> Self {
+        self.flags = flags;
+        self
+    }
+
     /// Register a node removal in the changeset. Only one kind of modification
     /// for a given node will be preserved.
     ///
@@ -124,13 +485,25 @@ where
         self.changes.insert(id.0, Change::Delete);
     }
 
-    /// Construct a modified tree where the recorded modifications have been
-    /// applied.
+    /// Register a subtree to be inserted immediately before the node `id`.
+    ///
+    /// The material can either be a prebuilt [`Tree`], or a closure which
+    /// receives a [`Builder`] to construct it (see [`IntoSubtree`]). Multiple
+    /// insertions anchored to the same node are applied in the order in which
+    /// they were registered. The spans of all nodes following the insertion
+    /// are shifted, and the spans of its ancestors are widened accordingly.
+    /// Subtrees inserted before or after a removed node are still inserted,
+    /// while anchoring an insertion inside of a removed or replaced subtree
+    /// causes applying the change set to error with
+    /// [`Error::ConflictingChange`].
+    ///
+    /// The returned [`Insertion`] can be used with
+    /// [`ChangeSet::modify_with_ids`] to map the identifiers of the inserted
+    /// subtree to the ones in the modified tree.
     ///
     /// # Errors
     ///
-    /// Errors with [`Error::Overflow`] in case we run out of node
-    /// identifiers.
+    /// Errors if the material couldn't be built into a tree.
     ///
     /// # Examples
     ///
@@ -139,187 +512,1239 @@ where
     ///
     /// let tree = syntree::tree! {
     ///     "root" => {
-    ///         "child" => {
+    ///         ("lit", 1),
+    ///     },
+    ///     ("whitespace", 3),
+    /// };
+    ///
+    /// let lit = tree.first().and_then(|n| n.first()).ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.insert_before(lit.id(), syntree::tree!(("a", 2)))?;
+    /// change_set.insert_before(lit.id(), syntree::tree!(("b", 4)))?;
+    ///
+    /// let tree = change_set.modify(&tree)?;
+    ///
+    /// assert_eq!(
+    ///     tree,
+    ///     syntree::tree! {
+    ///         "root" => {
+    ///             ("a", 2),
+    ///             ("b", 4),
     ///             ("lit", 1),
-    ///             ("lit", 2),
     ///         },
     ///         ("whitespace", 3),
     ///     }
+    /// );
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(root.range(), 0..7);
+    /// let whitespace = root.next().ok_or("missing whitespace")?;
+    /// assert_eq!(whitespace.range(), 7..10);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn insert_before<M>(&mut self, id: Id, material: M) -> Result<Insertion, Error>
+    where
+        M: IntoSubtree<T, S>,
+    {
+        self.insert(id, Position::Before, material)
+    }
+
+    /// Register a subtree to be inserted immediately after the node `id`.
+    ///
+    /// See [`ChangeSet::insert_before`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the material couldn't be built into a tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     },
     /// };
     ///
-    /// let child = tree.first().and_then(|n| n.first()).ok_or("missing child")?;
+    /// let root = tree.first().ok_or("missing root")?;
+    ///
     /// let mut change_set = ChangeSet::new();
-    /// change_set.remove(child.id());
+    /// change_set.insert_after(root.id(), syntree::tree!("root2" => { ("lit", 2) }))?;
     ///
     /// assert_eq!(
     ///     change_set.modify(&tree)?,
     ///     syntree::tree! {
     ///         "root" => {
-    ///             ("whitespace", 3)
-    ///         }
+    ///             ("lit", 1),
+    ///         },
+    ///         "root2" => {
+    ///             ("lit", 2),
+    ///         },
     ///     }
     /// );
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
-    pub fn modify(&mut self, tree: &Tree<T, S>) -> Result<Tree<T, S>, Error>
+    pub fn insert_after<M>(&mut self, id: Id, material: M) -> Result<Insertion, Error>
     where
-        T: Clone,
-        S: TreeSpan,
+        M: IntoSubtree<T, S>,
     {
-        let mut output = Tree::<T, S>::with_capacity(tree.capacity());
-
-        let mut refactor = RefactorWalk {
-            parents: Vec::new(),
-            prev: None,
-        };
-
-        let mut cursor = Index::default();
-
-        // The specified sub-tree depth is being deleted.
-        let mut current = tree.first().map(|node| (node, false));
-
-        while let Some((mut node, mut first)) = current.take() {
-            let node_id = NonMax::new(output.len()).ok_or(Error::Overflow)?;
-
-            if let Some(change) = self.changes.get(&node_id) {
-                match change {
-                    Change::Delete => {
-                        let Some(skipped) = refactor.skip_subtree(node, first) else {
-                            continue;
-                        };
-
-                        node = skipped.node;
-                        first = skipped.first;
-                    }
-                }
-            }
-
-            if refactor.parents.is_empty() {
-                let (first, last) = output.links_mut();
-
-                if first.is_none() {
-                    *first = Some(node_id);
-                }
-
-                *last = Some(node_id);
-            }
-
-            // Since we are the first node in the sequence we're obligated to
-            // set the first child of the parent.
-            let prev = if first {
-                None
-            } else {
-                let prev = refactor.prev.take();
-
-                if let Some(prev) = prev.and_then(|id| output.get_mut(id)) {
-                    prev.next = Some(node_id);
-                }
-
-                prev
-            };
-
-            let span = match node.kind() {
-                Kind::Node => S::point(cursor),
-                Kind::Token => {
-                    let len = node.span().len();
-
-                    if len > 0 {
-                        output.indexes_mut().push(cursor, Id(node_id));
-                        let start = cursor;
-                        cursor = cursor
-                            .checked_add(node.span().len())
-                            .ok_or(Error::Overflow)?;
-                        S::new(start, cursor)
-                    } else {
-                        S::point(cursor)
-                    }
-                }
-            };
-
-            let parent = refactor.parents.last().map(|n| n.1);
-
-            if let Some(parent) = parent.and_then(|id| output.get_mut(id)) {
-                if parent.first.is_none() {
-                    parent.first = Some(node_id);
-                }
-
-                parent.last = Some(node_id);
-                parent.span.set_end(span.end());
-            }
+        self.insert(id, Position::After, material)
+    }
 
-            output.push(Links {
-                data: node.value().clone(),
-                kind: node.kind(),
-                span,
-                parent,
-                prev,
-                next: None,
-                first: None,
-                last: None,
-            });
+    /// Register a subtree to be inserted as a child of the node `id`, before
+    /// the child currently at `index`. If `index` is greater than or equal to
+    /// the number of children the subtree is appended as the last child.
+    ///
+    /// Indexes refer to the children of the node in the original tree, so
+    /// they are not affected by other registered modifications. If the node
+    /// is removed or replaced, or is inside of a removed or replaced subtree,
+    /// applying the change set errors with [`Error::ConflictingChange`].
+    ///
+    /// See [`ChangeSet::insert_before`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the material couldn't be built into a tree. Since only nodes
+    /// can have children, registering a child for a token causes
+    /// [`ChangeSet::modify`] and [`ChangeSet::text_edits`] to error with
+    /// [`Error::InsertChildOfToken`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    /// use syntree::{Builder, Error};
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         ("lit", 2),
+    ///     },
+    ///     "empty" => {},
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let empty = root.next().ok_or("missing empty")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.insert_child(root.id(), 1, syntree::tree!(("a", 3)))?;
+    /// change_set.insert_child(root.id(), 100, syntree::tree!(("b", 4)))?;
+    /// change_set.insert_child(empty.id(), 0, |b: &mut Builder<_>| {
+    ///     b.token("c", 5)?;
+    ///     Ok::<_, Error>(())
+    /// })?;
+    ///
+    /// assert_eq!(
+    ///     change_set.modify(&tree)?,
+    ///     syntree::tree! {
+    ///         "root" => {
+    ///             ("lit", 1),
+    ///             ("a", 3),
+    ///             ("lit", 2),
+    ///             ("b", 4),
+    ///         },
+    ///         "empty" => {
+    ///             ("c", 5),
+    ///         },
+    ///     }
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn insert_child<M>(&mut self, id: Id, index: usize, material: M) -> Result<Insertion, Error>
+    where
+        M: IntoSubtree<T, S>,
+    {
+        self.insert(id, Position::Child(index), material)
+    }
+
+    fn insert<M>(&mut self, id: Id, position: Position, material: M) -> Result<Insertion, Error>
+    where
+        M: IntoSubtree<T, S>,
+    {
+        let index = self.push_tree(material)?;
+        self.inserts
+            .entry(id.0)
+            .or_default()
+            .push((position, index));
+        Ok(Insertion(index))
+    }
+
+    /// Register a replacement of the value of the node `id`. The structure
+    /// and spans of the tree are left untouched. Only one kind of
+    /// modification for a given node will be preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "fn" => {
+    ///         ("ident", 3),
+    ///         "body" => {
+    ///             ("ident", 3),
+    ///         },
+    ///     }
+    /// };
+    ///
+    /// let mut change_set = ChangeSet::new();
+    ///
+    /// for node in tree.walk().filter(|n| *n.value() == "ident") {
+    ///     change_set.replace_value(node.id(), "renamed");
+    /// }
+    ///
+    /// assert_eq!(
+    ///     change_set.modify(&tree)?,
+    ///     syntree::tree! {
+    ///         "fn" => {
+    ///             ("renamed", 3),
+    ///             "body" => {
+    ///                 ("renamed", 3),
+    ///             },
+    ///         }
+    ///     }
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn replace_value(&mut self, id: Id, value: T) {
+        self.changes.insert(id.0, Change::Value(value));
+    }
+
+    /// Register a replacement of the node `id` and its entire subtree with
+    /// new material. Only one kind of modification for a given node will be
+    /// preserved.
+    ///
+    /// If the replacement has a different width than the node it replaces,
+    /// the spans of all following nodes are shifted and the spans of its
+    /// ancestors are adjusted accordingly. The returned [`Insertion`] can be
+    /// used with [`ChangeSet::modify_with_ids`] to map the identifiers of the
+    /// replacement to the ones in the modified tree.
+    ///
+    /// Replacing, or changing the value of, a node which is inside of another
+    /// replaced or removed subtree is a conflict which causes
+    /// [`ChangeSet::modify`] to error with [`Error::ConflictingChange`]. The
+    /// same is true for removing a node inside of a replaced subtree.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the material couldn't be built into a tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 4),
+    ///         },
+    ///         ("whitespace", 1),
+    ///     },
+    ///     ("eof", 0),
+    /// };
+    ///
+    /// let number = tree.first().and_then(|n| n.first()).ok_or("missing number")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.replace(number.id(), syntree::tree!(("lit", 1)))?;
+    ///
+    /// let tree = change_set.modify(&tree)?;
+    ///
+    /// assert_eq!(
+    ///     tree,
+    ///     syntree::tree! {
+    ///         "root" => {
+    ///             ("lit", 1),
+    ///             ("whitespace", 1),
+    ///         },
+    ///         ("eof", 0),
+    ///     }
+    /// );
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(root.range(), 0..2);
+    /// assert_eq!(root.next().map(|n| n.range()), Some(2..2));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Conflicting changes are reported when the change set is applied:
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 4),
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// let number = tree.first().and_then(|n| n.first()).ok_or("missing number")?;
+    /// let lit = number.first().ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.replace(number.id(), syntree::tree!(("lit", 1)))?;
+    /// change_set.replace_value(lit.id(), "other");
+    ///
+    /// assert_eq!(change_set.modify(&tree), Err(Error::ConflictingChange(lit.id())));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn replace<M>(&mut self, id: Id, material: M) -> Result<Insertion, Error>
+    where
+        M: IntoSubtree<T, S>,
+    {
+        let index = self.push_tree(material)?;
+        self.changes.insert(id.0, Change::Replace(index));
+        Ok(Insertion(index))
+    }
+
+    /// Register a wrapping of the node `id` in a new node with the given
+    /// `value`, which is interposed between the node and its current parent.
+    /// The span of the wrapping node is equal to the span of the wrapped
+    /// node. Only one kind of modification for a given node will be
+    /// preserved.
+    ///
+    /// Subtrees inserted before or after the wrapped node are placed outside
+    /// of the wrapping node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "expr" => {
+    ///         ("lit", 1),
+    ///         ("plus", 1),
+    ///         ("lit", 1),
+    ///     },
+    ///     ("eof", 0),
+    /// };
+    ///
+    /// let expr = tree.first().ok_or("missing expr")?;
+    /// let lit = expr.first().ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.wrap(expr.id(), "group");
+    /// change_set.wrap(lit.id(), "number");
+    ///
+    /// let tree = change_set.modify(&tree)?;
+    ///
+    /// assert_eq!(
+    ///     tree,
+    ///     syntree::tree! {
+    ///         "group" => {
+    ///             "expr" => {
+    ///                 "number" => {
+    ///                     ("lit", 1),
+    ///                 },
+    ///                 ("plus", 1),
+    ///                 ("lit", 1),
+    ///             },
+    ///         },
+    ///         ("eof", 0),
+    ///     }
+    /// );
+    ///
+    /// let group = tree.first().ok_or("missing group")?;
+    /// assert_eq!(group.range(), 0..3);
+    /// let number = group.first().and_then(|n| n.first()).ok_or("missing number")?;
+    /// assert_eq!(number.range(), 0..1);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn wrap(&mut self, id: Id, value: T) {
+        self.changes.insert(id.0, Change::Wrap(value));
+    }
+
+    /// Register an unwrapping of the node `id`, which removes the node and
+    /// splices its children into its place among its siblings. Unwrapping a
+    /// node without children is the same as removing it. Only one kind of
+    /// modification for a given node will be preserved.
+    ///
+    /// Unwrapping a token causes [`ChangeSet::modify`] to error with
+    /// [`Error::UnwrapToken`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "group" => {
+    ///             ("lit", 1),
+    ///             ("lit", 2),
+    ///         },
+    ///         "empty" => {},
+    ///         ("lit", 3),
+    ///     }
+    /// };
+    ///
+    /// let group = tree.first().and_then(|n| n.first()).ok_or("missing group")?;
+    /// let empty = group.next().ok_or("missing empty")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.unwrap(group.id());
+    /// change_set.unwrap(empty.id());
+    ///
+    /// assert_eq!(
+    ///     change_set.modify(&tree)?,
+    ///     syntree::tree! {
+    ///         "root" => {
+    ///             ("lit", 1),
+    ///             ("lit", 2),
+    ///             ("lit", 3),
+    ///         }
+    ///     }
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Unwrapping a token is an error:
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let lit = tree.first().and_then(|n| n.first()).ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.unwrap(lit.id());
+    ///
+    /// assert_eq!(change_set.modify(&tree), Err(Error::UnwrapToken(lit.id())));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn unwrap(&mut self, id: Id) {
+        self.changes.insert(id.0, Change::Unwrap);
+    }
+
+    /// Attach the source text corresponding to an inserted or replacing
+    /// subtree, which is used by [`ChangeSet::text_edits`] and
+    /// [`ChangeSet::apply_to_source`]. Subtrees without attached text are
+    /// treated as if their text is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let source = "let a = 1;";
+    ///
+    /// let tree = syntree::tree! {
+    ///     "let" => {
+    ///         ("kw", 3),
+    ///         ("ws", 1),
+    ///         ("ident", 1),
+    ///         ("ws", 1),
+    ///         ("eq", 1),
+    ///         ("ws", 1),
+    ///         ("number", 1),
+    ///         ("semi", 1),
+    ///     }
+    /// };
+    ///
+    /// let ident = tree.first().and_then(|n| n.children().nth(2)).ok_or("missing ident")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// let insertion = change_set.replace(ident.id(), syntree::tree!(("ident", 5)))?;
+    /// change_set.set_text(insertion, "value");
+    ///
+    /// assert_eq!(change_set.apply_to_source(&tree, source)?, "let value = 1;");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_text<N>(&mut self, insertion: Insertion, text: N)
+    where
+        N: Into<Box<str>>,
+    {
+        self.texts.insert(insertion.0, text.into());
+    }
+
+    fn push_tree<M>(&mut self, material: M) -> Result<usize, Error>
+    where
+        M: IntoSubtree<T, S>,
+    {
+        let tree = material.into_subtree()?;
+        let index = self.trees.len();
+        self.trees.push(tree);
+        Ok(index)
+    }
+
+    /// Construct a modified tree where the recorded modifications have been
+    /// applied.
+    ///
+    /// Removed nodes are dropped together with their entire subtree. The
+    /// spans of the remaining nodes are recomputed so that they no longer
+    /// include the removed ranges. Registering a removal for a node which is
+    /// inside of another removed subtree has no additional effect. Gaps
+    /// between the tokens of inserted trees are kept.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] in case we run out of node
+    /// identifiers, or with [`Error::ConflictingChange`] if a node inside of a
+    /// replaced or removed subtree has been replaced, or a node inside of a
+    /// replaced subtree has been removed. Errors with
+    /// [`Error::InsertChildOfToken`] if a child has been registered for
+    /// insertion into a token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("lit", 1),
+    ///             ("lit", 2),
+    ///         },
+    ///         ("whitespace", 3),
+    ///     }
+    /// };
+    ///
+    /// let child = tree.first().and_then(|n| n.first()).ok_or("missing child")?;
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(child.id());
+    ///
+    /// assert_eq!(
+    ///     change_set.modify(&tree)?,
+    ///     syntree::tree! {
+    ///         "root" => {
+    ///             ("whitespace", 3)
+    ///         }
+    ///     }
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Removing a root, or the only child of a node which leaves it empty:
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     },
+    ///     "root2" => {
+    ///         "child" => {
+    ///             ("lit", 2),
+    ///         }
+    ///     },
+    ///     ("whitespace", 3),
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let child = root.next().and_then(|n| n.first()).ok_or("missing child")?;
+    /// let lit = child.first().ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(root.id());
+    /// change_set.remove(lit.id());
+    ///
+    /// assert_eq!(
+    ///     change_set.modify(&tree)?,
+    ///     syntree::tree! {
+    ///         "root2" => {
+    ///             "child" => {}
+    ///         },
+    ///         ("whitespace", 3),
+    ///     }
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Removing a node inside of an already removed subtree:
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("lit", 1),
+    ///         },
+    ///         ("whitespace", 3),
+    ///     }
+    /// };
+    ///
+    /// let child = tree.first().and_then(|n| n.first()).ok_or("missing child")?;
+    /// let lit = child.first().ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(child.id());
+    /// change_set.remove(lit.id());
+    ///
+    /// assert_eq!(
+    ///     change_set.modify(&tree)?,
+    ///     syntree::tree! {
+    ///         "root" => {
+    ///             ("whitespace", 3)
+    ///         }
+    ///     }
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn modify(&mut self, tree: &Tree<T, S>) -> Result<Tree<T, S>, Error>
+    where
+        T: Clone,
+    {
+        let (tree, _) = self.modify_with_ids(tree)?;
+        Ok(tree)
+    }
+
+    /// Construct a modified tree where the recorded modifications have been
+    /// applied, together with an [`IdMapping`] which describes where nodes
+    /// ended up in the modified tree.
+    ///
+    /// See [`ChangeSet::modify`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] in case we run out of node
+    /// identifiers, with [`Error::ConflictingChange`] if conflicting changes
+    /// have been registered, or with [`Error::InsertChildOfToken`] if a child
+    /// has been registered for insertion into a token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    ///
+    /// let inserted = syntree::tree!(("lit", 2));
+    /// let lit = inserted.first().ok_or("missing lit")?.id();
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// let a = change_set.insert_child(root.id(), 0, inserted.clone())?;
+    /// let b = change_set.insert_after(root.id(), inserted)?;
+    ///
+    /// let (tree, mapping) = change_set.modify_with_ids(&tree)?;
+    ///
+    /// let a = tree.get(mapping.inserted(a, lit).ok_or("missing a")?).ok_or("missing a")?;
+    /// let b = tree.get(mapping.inserted(b, lit).ok_or("missing b")?).ok_or("missing b")?;
+    ///
+    /// assert_eq!(a.range(), 0..2);
+    /// assert_eq!(b.range(), 3..5);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn modify_with_ids(&mut self, tree: &Tree<T, S>) -> Result<(Tree<T, S>, IdMapping), Error>
+    where
+        T: Clone,
+    {
+        let mut output =
+            Builder::<T, S>::with_capacity(tree.capacity()).with_policy(CursorPolicy::Monotonic);
+        let mut mapping = IdMapping {
+            old: vec![None; tree.len()],
+            inserted: BTreeMap::new(),
+        };
+
+        let mut stack = vec![Frame {
+            children: tree.children(),
+            parent: None,
+            index: 0,
+            close: 0,
+        }];
+
+        while let Some(frame) = stack.last_mut() {
+            let index = frame.index;
+            let parent = frame.parent;
+            let close = frame.close;
+
+            let Some(node) = frame.children.next() else {
+                stack.pop();
+
+                if let Some(parent) = parent {
+                    self.insert_at(
+                        &mut output,
+                        &mut mapping,
+                        parent,
+                        |p| matches!(p, Position::Child(n) if n >= index),
+                    )?;
+
+                    for _ in 0..close {
+                        output.close()?;
+                    }
+
+                    self.insert_at(&mut output, &mut mapping, parent, |p| p == Position::After)?;
+                }
+
+                continue;
+            };
+
+            frame.index += 1;
+            self.check_children(node)?;
+
+            if let Some(parent) = parent {
+                self.insert_at(&mut output, &mut mapping, parent, |p| {
+                    p == Position::Child(index)
+                })?;
+            }
+
+            self.insert_at(&mut output, &mut mapping, node, |p| p == Position::Before)?;
+
+            let value = match self.changes.get(&node.id().0) {
+                Some(Change::Delete) => {
+                    self.check_conflicts(node, false)?;
+                    self.insert_at(&mut output, &mut mapping, node, |p| p == Position::After)?;
+                    continue;
+                }
+                Some(&Change::Replace(index)) => {
+                    self.check_conflicts(node, true)?;
+                    self.copy(&mut output, &mut mapping, index)?;
+                    self.insert_at(&mut output, &mut mapping, node, |p| p == Position::After)?;
+                    continue;
+                }
+                Some(Change::Unwrap) => {
+                    if let Kind::Token = node.kind() {
+                        return Err(Error::UnwrapToken(node.id()));
+                    }
+
+                    stack.push(Frame {
+                        children: node.children(),
+                        parent: Some(node),
+                        index: 0,
+                        close: 0,
+                    });
+
+                    continue;
+                }
+                Some(Change::Value(value)) => value,
+                Some(Change::Wrap(..)) | None => node.value(),
+            };
+
+            let wrapped = if let Some(Change::Wrap(wrapper)) = self.changes.get(&node.id().0) {
+                let id = output.open(wrapper.clone())?;
+                set_flags(&mut output, id, self.flags)?;
+                1
+            } else {
+                0
+            };
 
-            current = refactor.step(node, node_id);
+            let id = match node.kind() {
+                Kind::Node => {
+                    let id = output.open(value.clone())?;
+
+                    stack.push(Frame {
+                        children: node.children(),
+                        parent: Some(node),
+                        index: 0,
+                        close: 1 + wrapped,
+                    });
+
+                    id
+                }
+                Kind::Token => {
+                    let id = output.token(value.clone(), node.span().length())?;
+
+                    for _ in 0..wrapped {
+                        output.close()?;
+                    }
+
+                    self.insert_at(&mut output, &mut mapping, node, |p| p == Position::After)?;
+                    id
+                }
+            };
+
+            set_flags(&mut output, id, node.flags())?;
+            output.set_marks(id, node.marks())?;
+
+            if let Some(old) = mapping.old.get_mut(node.id().0.get()) {
+                *old = Some(id);
+            }
         }
 
-        output.span_mut().set_end(cursor);
-        Ok(output)
+        Ok((output.build()?, mapping))
     }
-}
 
-impl<T, S> Default for ChangeSet<T, S>
-where
-    S: TreeSpan,
-{
-    #[inline]
-    fn default() -> Self {
-        Self {
-            changes: HashMap::new(),
-            trees: Vec::new(),
+    /// Check that no children have been registered for insertion into a
+    /// token.
+    fn check_children(&self, node: Node<'_, T, S>) -> Result<(), Error> {
+        if node.kind() != Kind::Token {
+            return Ok(());
+        }
+
+        let Some(inserts) = self.inserts.get(&node.id().0) else {
+            return Ok(());
+        };
+
+        if inserts
+            .iter()
+            .any(|(p, _)| matches!(p, Position::Child(..)))
+        {
+            return Err(Error::InsertChildOfToken(node.id()));
         }
+
+        Ok(())
     }
-}
 
-/// The state of the skipped subtree.
-struct Skipped<'a, T, S> {
-    node: Node<'a, T, S>,
-    first: bool,
-}
+    /// Check that no conflicting changes have been registered inside of the
+    /// subtree of `node`, which is being removed or replaced.
+    ///
+    /// Insertions anchored inside of the subtree, or as children of `node`,
+    /// conflict since they have nowhere to go.
+    fn check_conflicts(&self, node: Node<'_, T, S>, replaced: bool) -> Result<(), Error> {
+        if let Some(inserts) = self.inserts.get(&node.id().0) {
+            if inserts
+                .iter()
+                .any(|(p, _)| matches!(p, Position::Child(..)))
+            {
+                return Err(Error::ConflictingChange(node.id()));
+            }
+        }
 
-struct RefactorWalk<'a, T, S> {
-    parents: Vec<(Node<'a, T, S>, NonMax)>,
-    prev: Option<NonMax>,
-}
+        for node in node.walk() {
+            if self.inserts.contains_key(&node.id().0) {
+                return Err(Error::ConflictingChange(node.id()));
+            }
+
+            match self.changes.get(&node.id().0) {
+                Some(Change::Delete) if !replaced => {}
+                Some(..) => return Err(Error::ConflictingChange(node.id())),
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copy the registered tree at `index` into `output`.
+    fn copy(
+        &self,
+        output: &mut Builder<T, S>,
+        mapping: &mut IdMapping,
+        index: usize,
+    ) -> Result<(), Error>
+    where
+        T: Clone,
+    {
+        let Some(tree) = self.trees.get(index) else {
+            return Ok(());
+        };
+
+        let mut stack = vec![tree.children()];
+        let mut last = None;
+
+        while let Some(it) = stack.last_mut() {
+            let Some(node) = it.next() else {
+                stack.pop();
+
+                if !stack.is_empty() {
+                    output.close()?;
+                }
+
+                continue;
+            };
+
+            let id = match node.kind() {
+                Kind::Node => {
+                    let id = output.open(node.value().clone())?;
+                    stack.push(node.children());
+                    id
+                }
+                Kind::Token => {
+                    let span = node.span();
+
+                    // Keep the gap to the previous token in the inserted tree.
+                    if let Some(last) = last.filter(|&last| last < span.start()) {
+                        output.advance(S::new(last, span.start()).length())?;
+                    }
+
+                    last = Some(span.end());
+                    output.token(node.value().clone(), span.length())?
+                }
+            };
+
+            set_flags(output, id, node.flags() | self.flags)?;
+            output.set_marks(id, node.marks())?;
+
+            mapping.inserted.insert((index, node.id().0), id);
+        }
+
+        Ok(())
+    }
+
+    /// Copy all subtrees anchored to `node` whose position matches `filter`
+    /// into `output`, in the order in which they were registered.
+    fn insert_at(
+        &self,
+        output: &mut Builder<T, S>,
+        mapping: &mut IdMapping,
+        node: Node<'_, T, S>,
+        filter: impl Fn(Position) -> bool,
+    ) -> Result<(), Error>
+    where
+        T: Clone,
+    {
+        let Some(inserts) = self.inserts.get(&node.id().0) else {
+            return Ok(());
+        };
+
+        for &(position, index) in inserts {
+            if !filter(position) {
+                continue;
+            }
+
+            self.copy(output, mapping, index)?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the identifiers of all the nodes in `tree` which will be removed
+    /// when the change set is applied through [`ChangeSet::modify`]. This
+    /// includes all nodes in removed or replaced subtrees as well as unwrapped
+    /// nodes, in the order in which they appear in the tree.
+    ///
+    /// This is useful to clean up side tables which are keyed by [`Id`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("lit", 1),
+    ///             ("lit", 2),
+    ///         },
+    ///         ("whitespace", 3),
+    ///     }
+    /// };
+    ///
+    /// let child = tree.first().and_then(|n| n.first()).ok_or("missing child")?;
+    /// let lit = child.first().ok_or("missing lit")?;
+    /// let lit2 = lit.next().ok_or("missing lit2")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(child.id());
+    /// change_set.remove(lit.id());
+    ///
+    /// assert_eq!(change_set.removed(&tree), [child.id(), lit.id(), lit2.id()]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn removed(&self, tree: &Tree<T, S>) -> Vec<Id> {
+        let mut removed = Vec::new();
+        let mut stack = vec![tree.children()];
+
+        while let Some(it) = stack.last_mut() {
+            let Some(node) = it.next() else {
+                stack.pop();
+                continue;
+            };
+
+            match self.changes.get(&node.id().0) {
+                Some(Change::Delete | Change::Replace(..)) => {
+                    removed.push(node.id());
+                    removed.extend(node.walk().map(|n| n.id()));
+                    continue;
+                }
+                Some(Change::Unwrap) => {
+                    removed.push(node.id());
+                }
+                _ => {}
+            }
 
-impl<'a, T, S> RefactorWalk<'a, T, S> {
-    fn skip_subtree(&mut self, node: Node<'a, T, S>, first: bool) -> Option<Skipped<'a, T, S>> {
-        if let Some(next) = node.next() {
-            return Some(Skipped { node: next, first });
+            stack.push(node.children());
         }
 
-        let (node, parent_id) = self.parents.pop()?;
-        self.prev = Some(parent_id);
-        Some(Skipped { node, first: false })
+        removed
     }
+}
+
+impl<T> ChangeSet<T, Span> {
+    /// Compute the text edits to the source of `tree` which correspond to the
+    /// registered changes, as `(range, replacement)` pairs sorted by position
+    /// and guaranteed not to overlap.
+    ///
+    /// Removing a node deletes its range, replacing a node replaces its range
+    /// with the text attached through [`ChangeSet::set_text`], and inserting
+    /// a subtree inserts its attached text at the corresponding position.
+    /// Wrapping, unwrapping and replacing values don't affect the source.
+    /// Edits at the same position are ordered so that insertions come before
+    /// removals and replacements, and otherwise in registration order.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::ConflictingChange`] in the same way as
+    /// [`ChangeSet::modify`], such as for an insertion anchored inside of a
+    /// removed or replaced subtree. Removing a node inside of an already
+    /// removed node doesn't conflict, since it has no additional effect.
+    /// Errors with [`Error::OverlappingEdits`] naming the anchors of two
+    /// edits which overlap, which can happen for trees with overlapping
+    /// siblings. Errors with [`Error::MissingNode`] if a change refers to a
+    /// node which is not in `tree`, and with [`Error::InsertChildOfToken`] if
+    /// a child has been registered for insertion into a token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("a", 1),
+    ///         ("ws", 1),
+    ///         ("b", 1),
+    ///     }
+    /// };
+    ///
+    /// let a = tree.first().and_then(|n| n.first()).ok_or("missing a")?;
+    /// let ws = a.next().ok_or("missing ws")?;
+    /// let b = ws.next().ok_or("missing b")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(ws.id());
+    /// change_set.remove(b.id());
+    /// let insertion = change_set.insert_after(a.id(), syntree::tree!(("c", 2)))?;
+    /// change_set.set_text(insertion, "cc");
+    ///
+    /// let edits = change_set.text_edits(&tree)?;
+    /// assert_eq!(edits, [(1..1, "cc"), (1..2, ""), (2..3, "")]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// Insertions inside of removed subtrees are rejected:
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("a", 1),
+    ///         ("b", 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let a = root.first().ok_or("missing a")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(root.id());
+    /// let insertion = change_set.insert_after(a.id(), syntree::tree!(("c", 1)))?;
+    /// change_set.set_text(insertion, "c");
+    ///
+    /// assert_eq!(change_set.text_edits(&tree), Err(Error::ConflictingChange(a.id())));
+    /// assert_eq!(change_set.modify(&tree), Err(Error::ConflictingChange(a.id())));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn text_edits(&self, tree: &Tree<T, Span>) -> Result<Vec<(Range<usize>, &str)>, Error> {
+        struct Edit<'a> {
+            range: Range<usize>,
+            text: &'a str,
+            id: Id,
+            order: usize,
+        }
 
-    /// Advance the iteration.
-    fn step(&mut self, node: Node<'a, T, S>, node_id: NonMax) -> Option<(Node<'a, T, S>, bool)> {
-        if let Some(next) = node.first() {
-            self.parents.push((node, node_id));
-            return Some((next, true));
+        let mut edits = Vec::new();
+
+        let node = |id: NonMax| tree.get(Id(id)).ok_or(Error::MissingNode(Id(id)));
+
+        for (&id, change) in &self.changes {
+            let (text, order, replaced) = match *change {
+                Change::Delete => ("", usize::MAX, false),
+                Change::Replace(index) => (self.text(index), index, true),
+                _ => continue,
+            };
+
+            let node = node(id)?;
+
+            // Changes inside of a removed or replaced subtree are checked
+            // together with it, in the same way as when modifying the tree.
+            let nested = node.ancestors().skip(1).any(|n| {
+                matches!(
+                    self.changes.get(&n.id().0),
+                    Some(Change::Delete | Change::Replace(..))
+                )
+            });
+
+            if !nested {
+                self.check_conflicts(node, replaced)?;
+            }
+
+            edits.push(Edit {
+                range: node.range(),
+                text,
+                id: Id(id),
+                order,
+            });
         }
 
-        if let Some(next) = node.next() {
-            self.prev = Some(node_id);
-            return Some((next, false));
+        for (&id, inserts) in &self.inserts {
+            let node = node(id)?;
+            self.check_children(node)?;
+
+            for &(position, index) in inserts {
+                let at = match position {
+                    Position::Before => node.range().start,
+                    Position::After => node.range().end,
+                    Position::Child(n) => match node.children().nth(n) {
+                        Some(child) => child.range().start,
+                        None => node.range().end,
+                    },
+                };
+
+                edits.push(Edit {
+                    range: at..at,
+                    text: self.text(index),
+                    id: Id(id),
+                    order: index,
+                });
+            }
         }
 
-        while let Some((parent, prev_id)) = self.parents.pop() {
-            if let Some(next) = parent.next() {
-                self.prev = Some(prev_id);
-                return Some((next, false));
+        edits.sort_by_key(|e| (e.range.start, !e.range.is_empty(), e.order, e.range.end));
+
+        let mut output = Vec::<(Range<usize>, &str)>::with_capacity(edits.len());
+        let mut last: Option<Edit<'_>> = None;
+
+        for edit in edits {
+            if edit.range.is_empty() && edit.text.is_empty() {
+                continue;
+            }
+
+            if let Some(last) = &last {
+                if edit.range.start < last.range.end {
+                    let nested_removal = edit.text.is_empty()
+                        && last.text.is_empty()
+                        && edit.range.end <= last.range.end;
+
+                    if nested_removal {
+                        continue;
+                    }
+
+                    return Err(Error::OverlappingEdits(last.id, edit.id));
+                }
+            }
+
+            output.push((edit.range.clone(), edit.text));
+
+            if !edit.range.is_empty() {
+                last = Some(edit);
             }
         }
 
-        None
+        Ok(output)
+    }
+
+    /// Apply the text edits computed by [`ChangeSet::text_edits`] to
+    /// `source`, which is the source of `tree`.
+    ///
+    /// # Errors
+    ///
+    /// Errors in the same way as [`ChangeSet::text_edits`], or with
+    /// [`Error::SourceMismatch`] if an edit falls outside of `source` or
+    /// doesn't fall on a character boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// let source = "a b";
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("a", 1),
+    ///         ("ws", 1),
+    ///         ("b", 1),
+    ///     }
+    /// };
+    ///
+    /// let a = tree.first().and_then(|n| n.first()).ok_or("missing a")?;
+    /// let ws = a.next().ok_or("missing ws")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(ws.id());
+    /// let insertion = change_set.insert_before(a.id(), syntree::tree!(("c", 2)))?;
+    /// change_set.set_text(insertion, "cc");
+    ///
+    /// assert_eq!(change_set.apply_to_source(&tree, source)?, "ccab");
+    ///
+    /// let new_tree = change_set.modify(&tree)?;
+    /// assert_eq!(new_tree.span().range(), 0..4);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn apply_to_source(&self, tree: &Tree<T, Span>, source: &str) -> Result<String, Error> {
+        let edits = self.text_edits(tree)?;
+        let mut output = String::with_capacity(source.len());
+        let mut cursor = 0;
+
+        for (range, text) in edits {
+            let (Some(before), true) = (
+                source.get(cursor..range.start),
+                source.is_char_boundary(range.end),
+            ) else {
+                return Err(Error::SourceMismatch);
+            };
+
+            output.push_str(before);
+            output.push_str(text);
+            cursor = range.end;
+        }
+
+        output.push_str(source.get(cursor..).unwrap_or_default());
+        Ok(output)
+    }
+}
+
+impl<T, S> ChangeSet<T, S>
+where
+    S: TreeSpan,
+{
+    /// Get the text attached to the tree at the given index.
+    fn text(&self, index: usize) -> &str {
+        self.texts.get(&index).map(|s| &**s).unwrap_or_default()
+    }
+}
+
+impl<T, S> Default for ChangeSet<T, S>
+where
+    S: TreeSpan,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            changes: BTreeMap::new(),
+            inserts: BTreeMap::new(),
+            trees: Vec::new(),
+            texts: BTreeMap::new(),
+            flags: 0,
+        }
+    }
+}
+
+/// Set the flags of a node in `output`, skipping nodes without flags since
+/// they are initialized without any.
+fn set_flags<T, S>(output: &mut Builder<T, S>, id: Id, flags: u8) -> Result<(), Error>
+where
+    S: TreeSpan,
+{
+    if flags != 0 {
+        output.set_flags(id, flags)?;
     }
+
+    Ok(())
 }
@@ -0,0 +1,362 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::builder::{Builder, CursorPolicy, Id};
+use crate::edit::{Change, ChangeSet, IdMapping};
+use crate::error::Error;
+use crate::node::Node;
+use crate::span::Span;
+use crate::tree::Tree;
+
+/// A tree together with its source.
+type State<T> = (Tree<T, Span>, String);
+
+/// A single step recorded in an [`UndoLog`].
+enum Step<T> {
+    /// Apply a change set to the current tree.
+    Edit(ChangeSet<T, Span>),
+    /// Restore a tree and its source in full. This is used when an edit
+    /// removes every node in the tree, so there is nothing left for a change
+    /// set to anchor to.
+    Restore(Tree<T, Span>, Box<str>),
+}
+
+/// A log of edits applied to a tree and its source, which allows them to be
+/// undone and redone.
+///
+/// Rather than storing copies of the tree for every step, the log stores the
+/// inverse of every applied [`ChangeSet`] as another change set. The inverse
+/// holds owned copies of the subtrees which were removed or replaced, the
+/// values which were overwritten, and the source text of all of them. Nodes
+/// which were inserted, wrapped or unwrapped are identified by their
+/// identifiers in the modified tree.
+///
+/// Since undoing and redoing are themselves applications of change sets,
+/// each of them records its own inverse for the opposite direction.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::edit::{ChangeSet, UndoLog};
+///
+/// let source = "a b";
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("ident", 1),
+///         ("ws", 1),
+///         ("ident", 1),
+///     }
+/// };
+///
+/// let ws = tree.first().and_then(|n| n.first()).and_then(|n| n.next()).ok_or("missing ws")?;
+///
+/// let mut log = UndoLog::new();
+///
+/// let mut change_set = ChangeSet::new();
+/// change_set.remove(ws.id());
+///
+/// let (edited, edited_source) = log.apply(&mut change_set, &tree, source)?;
+/// assert_eq!(edited_source, "ab");
+///
+/// let (undone, undone_source) = log.undo(&edited, &edited_source)?.ok_or("nothing to undo")?;
+/// assert_eq!(undone, tree);
+/// assert_eq!(undone_source, source);
+///
+/// let (redone, redone_source) = log.redo(&undone, &undone_source)?.ok_or("nothing to redo")?;
+/// assert_eq!(redone, edited);
+/// assert_eq!(redone_source, edited_source);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct UndoLog<T> {
+    undo: Vec<Step<T>>,
+    redo: Vec<Step<T>>,
+}
+
+impl<T> UndoLog<T> {
+    /// Construct a new empty undo log.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Test if there are any steps which can be undone.
+    #[must_use]
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Test if there are any steps which can be redone.
+    #[must_use]
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Clear all recorded steps.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+impl<T> UndoLog<T>
+where
+    T: Clone,
+{
+    /// Apply `change_set` to `tree` and its `source`, returning the modified
+    /// tree and source. The inverse of the change set is recorded so that
+    /// the step can be undone with [`UndoLog::undo`].
+    ///
+    /// Applying a new change set discards all steps which could be redone.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the change set can't be applied to the tree through
+    /// [`ChangeSet::modify`] or to the source through
+    /// [`ChangeSet::apply_to_source`], in which case the log is left
+    /// unchanged.
+    pub fn apply(
+        &mut self,
+        change_set: &mut ChangeSet<T, Span>,
+        tree: &Tree<T, Span>,
+        source: &str,
+    ) -> Result<(Tree<T, Span>, String), Error> {
+        let ((tree, source), inverse) = edit(change_set, tree, source)?;
+        self.undo.push(inverse);
+        self.redo.clear();
+        Ok((tree, source))
+    }
+
+    /// Undo the last applied or redone step, where `tree` and `source` are
+    /// the current state resulting from it.
+    ///
+    /// Returns `None` if there is nothing to undo.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `tree` or `source` don't correspond to the state produced by
+    /// the last step, in which case the step is kept.
+    pub fn undo(&mut self, tree: &Tree<T, Span>, source: &str) -> Result<Option<State<T>>, Error> {
+        step(&mut self.undo, &mut self.redo, tree, source)
+    }
+
+    /// Redo the last undone step, where `tree` and `source` are the current
+    /// state resulting from undoing it.
+    ///
+    /// Returns `None` if there is nothing to redo.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `tree` or `source` don't correspond to the state produced by
+    /// the last undo, in which case the step is kept.
+    pub fn redo(&mut self, tree: &Tree<T, Span>, source: &str) -> Result<Option<State<T>>, Error> {
+        step(&mut self.redo, &mut self.undo, tree, source)
+    }
+}
+
+impl<T> Default for UndoLog<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Apply the last step in `from` and record its inverse in `to`.
+fn step<T>(
+    from: &mut Vec<Step<T>>,
+    to: &mut Vec<Step<T>>,
+    tree: &Tree<T, Span>,
+    source: &str,
+) -> Result<Option<State<T>>, Error>
+where
+    T: Clone,
+{
+    let Some(last) = from.last_mut() else {
+        return Ok(None);
+    };
+
+    let (state, inverse) = match last {
+        Step::Edit(change_set) => edit(change_set, tree, source)?,
+        Step::Restore(restored, restored_source) => {
+            let mut inverse = ChangeSet::new();
+
+            for node in restored.children() {
+                inverse.remove(node.id());
+            }
+
+            (
+                (restored.clone(), String::from(&**restored_source)),
+                Step::Edit(inverse),
+            )
+        }
+    };
+
+    from.pop();
+    to.push(inverse);
+    Ok(Some(state))
+}
+
+/// Apply `change_set`, returning the modified tree and source together with
+/// the step which reverts it.
+fn edit<T>(
+    change_set: &mut ChangeSet<T, Span>,
+    tree: &Tree<T, Span>,
+    source: &str,
+) -> Result<(State<T>, Step<T>), Error>
+where
+    T: Clone,
+{
+    let new_source = change_set.apply_to_source(tree, source)?;
+    let (new_tree, mapping) = change_set.modify_with_ids(tree)?;
+
+    let inverse = if new_tree.is_empty() && !tree.is_empty() {
+        Step::Restore(tree.clone(), source.into())
+    } else {
+        Step::Edit(invert(change_set, tree, source, &new_tree, &mapping)?)
+    };
+
+    Ok(((new_tree, new_source), inverse))
+}
+
+/// Construct the change set which turns `new` back into `old`, where `new`
+/// is the result of applying `change_set` to `old`.
+///
+/// Every node in `old` whose ancestors all survived the modification is
+/// *kept*. The inverse reverts value changes and wrappings of kept nodes, and
+/// re-inserts owned copies of all other top-most nodes from `old` next to
+/// their closest kept sibling. Everything else in `new` came from inserted
+/// material or from the subtrees of re-inserted nodes, so it is removed.
+fn invert<T>(
+    change_set: &ChangeSet<T, Span>,
+    old: &Tree<T, Span>,
+    source: &str,
+    new: &Tree<T, Span>,
+    mapping: &IdMapping,
+) -> Result<ChangeSet<T, Span>, Error>
+where
+    T: Clone,
+{
+    let mut inverse = ChangeSet::new();
+    let mut kept = vec![false; new.len()];
+    let mut stack = vec![old.children()];
+
+    while let Some(it) = stack.last_mut() {
+        let Some(node) = it.next() else {
+            stack.pop();
+            continue;
+        };
+
+        let Some(id) = mapping.old_to_new(node.id()) else {
+            let insertion = match anchor(node, mapping, new)? {
+                Anchor::After(id) => inverse.insert_after(id, subtree(node)?)?,
+                Anchor::Before(id) => inverse.insert_before(id, subtree(node)?)?,
+                Anchor::Child(id) => inverse.insert_child(id, usize::MAX, subtree(node)?)?,
+            };
+
+            let text = source.get(node.range()).ok_or(Error::SourceMismatch)?;
+            inverse.set_text(insertion, text);
+            continue;
+        };
+
+        kept[id.index()] = true;
+
+        match change_set.changes.get(&node.id().0) {
+            Some(Change::Value(..)) => {
+                inverse.replace_value(id, node.value().clone());
+            }
+            Some(Change::Wrap(..)) => {
+                let wrapper = new
+                    .get(id)
+                    .and_then(|n| n.parent())
+                    .ok_or(Error::MissingNode(id))?;
+
+                kept[wrapper.id().index()] = true;
+                inverse.unwrap(wrapper.id());
+            }
+            _ => {}
+        }
+
+        stack.push(node.children());
+    }
+
+    let mut stack = vec![new.children()];
+
+    while let Some(it) = stack.last_mut() {
+        let Some(node) = it.next() else {
+            stack.pop();
+            continue;
+        };
+
+        if kept[node.id().index()] {
+            stack.push(node.children());
+        } else {
+            inverse.remove(node.id());
+        }
+    }
+
+    Ok(inverse)
+}
+
+/// Where a node which didn't survive a modification is re-inserted.
+enum Anchor {
+    After(Id),
+    Before(Id),
+    Child(Id),
+}
+
+/// Find where to re-insert `node` from the original tree into the modified
+/// tree `new`.
+fn anchor<T>(
+    node: Node<'_, T, Span>,
+    mapping: &IdMapping,
+    new: &Tree<T, Span>,
+) -> Result<Anchor, Error> {
+    let mut prev = node.prev();
+
+    while let Some(n) = prev {
+        if let Some(id) = mapping.old_to_new(n.id()) {
+            return Ok(Anchor::After(id));
+        }
+
+        prev = n.prev();
+    }
+
+    let mut next = node.next();
+
+    while let Some(n) = next {
+        if let Some(id) = mapping.old_to_new(n.id()) {
+            return Ok(Anchor::Before(id));
+        }
+
+        next = n.next();
+    }
+
+    if let Some(parent) = node.parent() {
+        let id = mapping
+            .old_to_new(parent.id())
+            .ok_or(Error::MissingNode(parent.id()))?;
+        return Ok(Anchor::Child(id));
+    }
+
+    // None of the roots survived, but the new roots are all removed by the
+    // inverse so we can anchor to any of them.
+    let first = new.first().ok_or(Error::MissingNode(node.id()))?;
+    Ok(Anchor::Before(first.id()))
+}
+
+/// Construct an owned copy of the subtree rooted at `node`, which keeps the
+/// flags, marks and spans of all of its nodes.
+fn subtree<T>(node: Node<'_, T, Span>) -> Result<Tree<T, Span>, Error>
+where
+    T: Clone,
+{
+    let mut builder = Builder::new().with_policy(CursorPolicy::Monotonic);
+    builder.advance_to(node.span().start)?;
+    builder.reuse(node)?;
+    builder.build()
+}
@@ -1,6 +1,6 @@
 use core::fmt;
 
-use crate::Id;
+use crate::{Id, Limit};
 
 /// Errors raised while building a tree.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -65,11 +65,542 @@ pub enum Error {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
     CloseAtError,
+    /// Error raised by [Builder::close_at][crate::Builder::close_at] if the
+    /// checkpoint was created before the builder was flushed through
+    /// [Builder::flush_completed][crate::Builder::flush_completed].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// let c = tree.checkpoint()?;
+    /// tree.token("lit", 3)?;
+    /// assert!(tree.flush_completed().is_some());
+    ///
+    /// tree.token("lit", 3)?;
+    /// assert_eq!(tree.close_at(&c, "operation"), Err(Error::StaleCheckpoint));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    StaleCheckpoint,
+    /// Error raised by [Builder::reopen][crate::Builder::reopen] if the node
+    /// being reopened is not the most recently closed node, or if something
+    /// else has been added after it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// let a = tree.open("a")?;
+    /// tree.close()?;
+    ///
+    /// tree.open("b")?;
+    /// tree.close()?;
+    ///
+    /// assert_eq!(tree.reopen(a), Err(Error::ReopenError));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ReopenError,
+    /// Error raised by [Builder::insert_before][crate::Builder::insert_before]
+    /// if the anchor is not a child of the node currently being built, or a
+    /// root if no node is being built.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error, Span};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// let lit = tree.token("lit", 1)?;
+    /// tree.close()?;
+    ///
+    /// assert_eq!(
+    ///     tree.insert_before(lit, "semi", Span::point(0)),
+    ///     Err(Error::InsertBeforeError)
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    InsertBeforeError,
+    /// Error raised by [ChangeSet::modify][crate::edit::ChangeSet::modify]
+    /// and [ChangeSet::text_edits][crate::edit::ChangeSet::text_edits] if a
+    /// change has been registered for the node with the given id which
+    /// conflicts with a change registered for one of its ancestors, such as
+    /// replacing a node or anchoring an insertion inside of a subtree which
+    /// has been removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let lit = root.first().ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(root.id());
+    /// change_set.replace_value(lit.id(), "other");
+    ///
+    /// assert_eq!(change_set.modify(&tree), Err(Error::ConflictingChange(lit.id())));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ConflictingChange(Id),
+    /// Error raised by [ChangeSet::modify][crate::edit::ChangeSet::modify]
+    /// if the token with the given id has been registered for unwrapping.
+    /// Only nodes can be unwrapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree!(("lit", 1));
+    /// let lit = tree.first().ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.unwrap(lit.id());
+    ///
+    /// assert_eq!(change_set.modify(&tree), Err(Error::UnwrapToken(lit.id())));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    UnwrapToken(Id),
+    /// Error raised by [ChangeSet::modify][crate::edit::ChangeSet::modify] or
+    /// [ChangeSet::text_edits][crate::edit::ChangeSet::text_edits] if a child
+    /// has been registered for insertion into the token with the given id.
+    /// Only nodes can have children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree!(("lit", 1));
+    /// let lit = tree.first().ok_or("missing lit")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.insert_child(lit.id(), 0, syntree::tree!(("a", 3)))?;
+    ///
+    /// assert_eq!(change_set.modify(&tree), Err(Error::InsertChildOfToken(lit.id())));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    InsertChildOfToken(Id),
+    /// Error raised by
+    /// [ChangeSet::text_edits][crate::edit::ChangeSet::text_edits] if the
+    /// text edits corresponding to changes registered for the two nodes with
+    /// the given ids overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    /// use syntree::{Builder, CursorPolicy, Error, Span};
+    ///
+    /// // Siblings can only overlap when placed without ordering.
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Unordered);
+    /// let a = tree.token_with_span("a", Span::new(0, 4))?;
+    /// let b = tree.token_with_span("b", Span::new(2, 6))?;
+    /// let tree = tree.build()?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(a);
+    /// change_set.remove(b);
+    ///
+    /// assert_eq!(change_set.text_edits(&tree), Err(Error::OverlappingEdits(a, b)));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    OverlappingEdits(Id, Id),
+    /// Error raised by
+    /// [ChangeSet::apply_to_source][crate::edit::ChangeSet::apply_to_source]
+    /// or [Tree::with_source][crate::Tree::with_source] if the source doesn't
+    /// match the tree, such as when it's too short.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("a", 4),
+    ///     }
+    /// };
+    ///
+    /// let a = tree.first().and_then(|n| n.first()).ok_or("missing a")?;
+    ///
+    /// let mut change_set = ChangeSet::new();
+    /// change_set.remove(a.id());
+    ///
+    /// assert_eq!(change_set.apply_to_source(&tree, "ab"), Err(Error::SourceMismatch));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    SourceMismatch,
+    /// Error raised by [SourceTree::token_at][crate::SourceTree::token_at]
+    /// if the offset is past the end of the source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree!(("lit", 2)).with_source("ab")?;
+    ///
+    /// assert_eq!(
+    ///     tree.token_at(3).err(),
+    ///     Some(Error::OffsetOutOfBounds { offset: 3, len: 2 })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    OffsetOutOfBounds {
+        /// The offset which was out of bounds.
+        offset: usize,
+        /// The length of the source.
+        len: usize,
+    },
+    /// Error raised by [Builder::push_postfix][crate::Builder::push_postfix]
+    /// if an operator has a greater arity than the number of completed
+    /// operands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error, Postfix};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// let result = tree.push_postfix(Postfix::Operator { value: "negate", arity: 1 });
+    /// assert_eq!(result, Err(Error::PostfixUnderflow { arity: 1, operands: 0 }));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    PostfixUnderflow {
+        /// The arity of the operator.
+        arity: usize,
+        /// The number of completed operands available.
+        operands: usize,
+    },
+    /// Error raised by
+    /// [Builder::finish_postfix][crate::Builder::finish_postfix] if anything
+    /// but exactly one completed subtree remains.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error};
+    ///
+    /// let mut tree = Builder::<&str>::new();
+    ///
+    /// assert_eq!(tree.finish_postfix(), Err(Error::PostfixUnbalanced { operands: 0 }));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    PostfixUnbalanced {
+        /// The number of completed subtrees which remain.
+        operands: usize,
+    },
+    /// Error raised when a limit set through
+    /// [Builder::with_limits][crate::Builder::with_limits] would be exceeded.
+    ///
+    /// For [`Limit::Children`] the identifier is the node which would have too
+    /// many children, and otherwise it is the node which would have been
+    /// added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, BuilderLimits, Error, Limit};
+    ///
+    /// let limits = BuilderLimits::new().max_children_per_node(1);
+    /// let mut tree = Builder::new().with_limits(limits);
+    ///
+    /// let root = tree.open("root")?;
+    /// tree.token("lit", 1)?;
+    ///
+    /// assert_eq!(
+    ///     tree.token("lit", 1),
+    ///     Err(Error::LimitExceeded { limit: Limit::Children, id: root })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    LimitExceeded {
+        /// The limit which would be exceeded.
+        limit: Limit,
+        /// The node responsible.
+        id: Id,
+    },
+    /// Error raised by [Builder::advance][crate::Builder::advance] or
+    /// [Builder::token_with_span][crate::Builder::token_with_span] if a gap
+    /// would be left after the cursor, which isn't permitted by
+    /// [CursorPolicy::Contiguous][crate::CursorPolicy::Contiguous].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error, Span};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// tree.token("lit", 1)?;
+    ///
+    /// assert_eq!(
+    ///     tree.token_with_span("lit", Span::new(2, 3)),
+    ///     Err(Error::CursorGap { cursor: 1, start: 2 })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    CursorGap {
+        /// The position of the cursor.
+        cursor: usize,
+        /// The position something was placed at.
+        start: usize,
+    },
+    /// Error raised by
+    /// [Builder::token_with_span][crate::Builder::token_with_span] if a
+    /// token would start before the cursor, which is only permitted by
+    /// [CursorPolicy::Unordered][crate::CursorPolicy::Unordered].
+    ///
+    /// This includes the span of the offending token and of the last
+    /// non-empty token placed before it. Since `Error` is not generic over the values stored
+    /// in the tree, the value of the offending token can't be included.
+    /// Callers which need it to report the error have to hold on to it
+    /// themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Error, Span};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// let lit = tree.token("lit", 2)?;
+    ///
+    /// assert_eq!(
+    ///     tree.token_with_span("lit", Span::new(1, 3)),
+    ///     Err(Error::CursorBackwards {
+    ///         cursor: 2,
+    ///         start: 1,
+    ///         end: 3,
+    ///         previous: Some(lit),
+    ///         previous_start: 0,
+    ///         previous_end: 2,
+    ///     })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    CursorBackwards {
+        /// The position of the cursor.
+        cursor: usize,
+        /// The start of the span of the offending token.
+        start: usize,
+        /// The end of the span of the offending token.
+        end: usize,
+        /// The identifier of the last non-empty token placed before the
+        /// cursor, or `None` if the cursor was only moved past empty tokens or
+        /// through [Builder::advance][crate::Builder::advance].
+        previous: Option<Id>,
+        /// The start of the span of the previous token, or the cursor if
+        /// there is none.
+        previous_start: usize,
+        /// The end of the span of the previous token, or the cursor if there
+        /// is none.
+        previous_end: usize,
+    },
+    /// Error raised by
+    /// [Builder::token_with_span][crate::Builder::token_with_span] if the
+    /// span of a token ends before it starts. This is checked regardless of
+    /// [CursorPolicy][crate::CursorPolicy].
+    ///
+    /// Since `Error` is not generic over the values stored in the tree, the
+    /// value of the offending token can't be included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Error, Span};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Unordered);
+    ///
+    /// let mut span = Span::point(3);
+    /// span.end = 1;
+    ///
+    /// assert_eq!(
+    ///     tree.token_with_span("lit", span),
+    ///     Err(Error::BackwardsSpan { start: 3, end: 1 })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    BackwardsSpan {
+        /// The start of the offending span.
+        start: usize,
+        /// The end of the offending span.
+        end: usize,
+    },
+    /// Error raised by [Builder::insert_before][crate::Builder::insert_before]
+    /// if the span of the inserted token doesn't fit between the anchor and
+    /// its preceding sibling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error, Span};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.token("ident", 2)?;
+    /// let lit = tree.token("lit", 1)?;
+    ///
+    /// assert_eq!(
+    ///     tree.insert_before(lit, "semi", Span::new(1, 2)),
+    ///     Err(Error::InsertOutOfBounds { start: 1, end: 2 })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    InsertOutOfBounds {
+        /// The start of the offending span.
+        start: usize,
+        /// The end of the offending span.
+        end: usize,
+    },
+    /// Error raised by [Tree::concat_ordered][crate::Tree::concat_ordered] if
+    /// the roots of the tree at `index` start at `start`, before the end of
+    /// the trees preceding it at `end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Error, Tree};
+    ///
+    /// let a = syntree::tree!(("lit", 2));
+    /// let b = syntree::tree!(("lit", 1));
+    ///
+    /// assert_eq!(
+    ///     Tree::concat_ordered([a, b]),
+    ///     Err(Error::ConcatOverlap { index: 1, start: 0, end: 2 })
+    /// );
+    /// ```
+    ConcatOverlap {
+        /// The position of the offending tree.
+        index: usize,
+        /// The start of the roots of the offending tree.
+        start: usize,
+        /// The end of the trees preceding it.
+        end: usize,
+    },
+    /// Error raised by
+    /// [Builder::extend_from_lexer][crate::Builder::extend_from_lexer] if the
+    /// range of the item at `index` is backwards, or starts before the end of
+    /// the preceding token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// assert_eq!(
+    ///     tree.extend_from_lexer([("lit", 0..2), ("lit", 2..4), ("lit", 4..3)]),
+    ///     Err(Error::LexerRange { index: 2, start: 4, end: 3 })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    LexerRange {
+        /// The position of the item in the lexer.
+        index: usize,
+        /// The start of the offending range.
+        start: usize,
+        /// The end of the offending range.
+        end: usize,
+    },
+    /// Error raised by
+    /// [Tree::from_owned_nodes][crate::Tree::from_owned_nodes] if a token has
+    /// children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::owned::TreeNode;
+    /// use syntree::{Error, Span, Tree};
+    ///
+    /// let mut token = TreeNode::token("lit", Span::new(0, 1));
+    /// token.children.push(TreeNode::token("lit", Span::new(0, 1)));
+    ///
+    /// assert_eq!(Tree::from_owned_nodes(vec![token]), Err(Error::TokenWithChildren));
+    /// ```
+    TokenWithChildren,
+    /// Error raised when combining [`FlagSet`]s which were constructed for
+    /// trees of different sizes.
+    ///
+    /// [`FlagSet`]: crate::FlagSet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Error, FlagSet};
+    ///
+    /// let a = syntree::tree! { ("lit", 1) };
+    /// let b = syntree::tree! { ("lit", 1), ("lit", 1) };
+    ///
+    /// let mut flags = FlagSet::new(&a);
+    ///
+    /// assert_eq!(
+    ///     flags.union_with(&FlagSet::new(&b)),
+    ///     Err(Error::FlagSetMismatch { len: 1, other: 2 })
+    /// );
+    /// ```
+    FlagSetMismatch {
+        /// The number of nodes the flag set being modified was constructed for.
+        len: usize,
+        /// The number of nodes the other flag set was constructed for.
+        other: usize,
+    },
+    /// Error raised by the [Builder][crate::Builder] if the cursor would move
+    /// past the largest offset representable by a span, which is
+    /// [`Index::MAX`][crate::span::Index]. `at` is the identifier of the node
+    /// which was being added when this happened, or which would be added next
+    /// when advancing the cursor.
+    ///
+    /// Since the span of a node is the union of the spans of its children, it
+    /// never overflows on its own, so nodes added before the error keep their
+    /// spans.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, Error};
+    ///
+    /// let mut tree = Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.token("lit", 1)?;
+    ///
+    /// let at = syntree::Id::from_index(2).ok_or("bad id")?;
+    /// assert_eq!(tree.token("lit", usize::MAX), Err(Error::SpanOverflow { at }));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    SpanOverflow {
+        /// The node being added.
+        at: Id,
+    },
     /// Numerical overflow.
     ///
-    /// This only happens under extreme circumstances or if a feature is enabled
-    /// which narrows the width of an identifier to the degree that this error
-    /// is easier to accomplish.
+    /// This happens if a tree would hold more than `u32::MAX` nodes, since
+    /// node identifiers are 32 bits wide. Overflowing spans while building a
+    /// tree is reported as [`Error::SpanOverflow`].
     Overflow,
     /// The node of the given id is missing.
     MissingNode(Id),
@@ -79,8 +610,14 @@ pub enum Error {
     MissingCloseAtSibling,
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+// `core::error::Error` is only stable since Rust 1.81, so it is opt-in.
+#[cfg(all(not(feature = "std"), feature = "core-error"))]
+#[allow(clippy::incompatible_msrv)]
+impl core::error::Error for Error {}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -96,6 +633,142 @@ impl fmt::Display for Error {
                     "trying to close a node which is not a sibling of the checkpoint being closed"
                 )
             }
+            Error::StaleCheckpoint => {
+                write!(f, "checkpoint was created before the builder was flushed")
+            }
+            Error::ReopenError => {
+                write!(
+                    f,
+                    "can only reopen the most recently closed node if nothing has been added after it"
+                )
+            }
+            Error::InsertBeforeError => {
+                write!(
+                    f,
+                    "can only insert before a child of the node currently being built"
+                )
+            }
+            Error::ConflictingChange(id) => {
+                write!(
+                    f,
+                    "change to node with id `{}` conflicts with a change to one of its ancestors",
+                    id.0.get()
+                )
+            }
+            Error::UnwrapToken(id) => {
+                write!(f, "cannot unwrap token with id `{}`", id.0.get())
+            }
+            Error::InsertChildOfToken(id) => {
+                write!(
+                    f,
+                    "cannot insert a child into token with id `{}`",
+                    id.0.get()
+                )
+            }
+            Error::OverlappingEdits(a, b) => {
+                write!(
+                    f,
+                    "text edits for nodes with ids `{}` and `{}` overlap",
+                    a.0.get(),
+                    b.0.get()
+                )
+            }
+            Error::CursorGap { cursor, start } => {
+                write!(
+                    f,
+                    "cannot leave a gap between the cursor at {cursor} and {start}"
+                )
+            }
+            Error::CursorBackwards {
+                cursor,
+                start,
+                end,
+                previous,
+                previous_start,
+                previous_end,
+            } => {
+                write!(
+                    f,
+                    "cannot place token spanning {start}..{end} before the cursor at {cursor}"
+                )?;
+
+                if let Some(id) = previous {
+                    write!(
+                        f,
+                        ", the previous token with id `{}` spans {previous_start}..{previous_end}",
+                        id.0.get()
+                    )?;
+                }
+
+                Ok(())
+            }
+            Error::BackwardsSpan { start, end } => {
+                write!(f, "span {start}..{end} of token ends before it starts")
+            }
+            Error::InsertOutOfBounds { start, end } => {
+                write!(
+                    f,
+                    "span {start}..{end} of inserted token doesn't fit before the anchor"
+                )
+            }
+            Error::ConcatOverlap { index, start, end } => {
+                write!(
+                    f,
+                    "tree {index} starts at {start} before the preceding trees end at {end}"
+                )
+            }
+            Error::LexerRange { index, start, end } => {
+                write!(
+                    f,
+                    "range {start}..{end} of lexer item {index} is backwards or overlaps the preceding token"
+                )
+            }
+            Error::TokenWithChildren => {
+                write!(f, "token has children")
+            }
+            Error::SourceMismatch => {
+                write!(f, "source doesn't match the tree")
+            }
+            Error::OffsetOutOfBounds { offset, len } => {
+                write!(
+                    f,
+                    "offset {offset} is out of bounds of source with length {len}"
+                )
+            }
+            Error::PostfixUnderflow { arity, operands } => {
+                write!(
+                    f,
+                    "operator with arity {arity} applied to {operands} completed operands"
+                )
+            }
+            Error::PostfixUnbalanced { operands } => {
+                write!(
+                    f,
+                    "expected exactly one completed subtree in postfix stream, but found {operands}"
+                )
+            }
+            Error::LimitExceeded { limit, id } => {
+                let what = match limit {
+                    Limit::Nodes => "maximum number of nodes",
+                    Limit::Depth => "maximum depth",
+                    Limit::Children => "maximum number of children",
+                };
+
+                write!(f, "{what} exceeded by node with id `{}`", id.0.get())
+            }
+            Error::FlagSetMismatch { len, other } => {
+                write!(
+                    f,
+                    "cannot combine flag set for {len} nodes with flag set for {other} nodes"
+                )
+            }
+            Error::SpanOverflow { at } => {
+                write!(
+                    f,
+                    "span of node with id `{}` overflows the largest supported offset",
+                    at.0.get()
+                )
+            }
             Error::Overflow => {
                 write!(f, "numerical overflow")
             }
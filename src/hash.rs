@@ -0,0 +1,329 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use crate::builder::Id;
+use crate::node::{Children, Node};
+use crate::span::TreeSpan;
+use crate::tree::{Kind, Tree};
+
+/// Tags mixed into hashes to distinguish different kinds of nodes.
+const TOKEN: u8 = 0;
+const NODE: u8 = 1;
+const SKIPPED: u8 = 2;
+
+/// Precomputed content hashes for every node in a tree.
+///
+/// The content hash of a node covers its value, its kind, the length of the
+/// tokens it contains and the structure of its subtree, but not its absolute
+/// position. So two structurally identical subtrees in different places hash
+/// to the same value.
+///
+/// The hashes stored here are equal to the ones produced by
+/// [`Node::content_hash`] and [`Node::content_hash_filtered`], but they are
+/// computed for every node in the tree in a single bottom-up pass.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::hash_map::RandomState;
+///
+/// use syntree::TreeHashes;
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "number" => {
+///             ("lit", 2),
+///         },
+///         ("whitespace", 1),
+///         "number" => {
+///             ("lit", 2),
+///         },
+///     }
+/// };
+///
+/// let state = RandomState::new();
+/// let hashes = TreeHashes::new(&tree, &state);
+///
+/// let first = tree.first().and_then(|n| n.first()).ok_or("missing first")?;
+/// let second = first.next().and_then(|n| n.next()).ok_or("missing second")?;
+///
+/// assert_eq!(hashes.get(first.id()), hashes.get(second.id()));
+/// assert_eq!(hashes.get(first.id()), Some(first.content_hash(&state)));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct TreeHashes {
+    hashes: Vec<u64>,
+}
+
+impl TreeHashes {
+    /// Compute the content hash of every node in `tree` using hashers
+    /// constructed from `hasher`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// use syntree::TreeHashes;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         ("lit", 2),
+    ///     }
+    /// };
+    ///
+    /// let state = RandomState::new();
+    /// let hashes = TreeHashes::new(&tree, &state);
+    ///
+    /// let first = tree.first().and_then(|n| n.first()).ok_or("missing first")?;
+    /// let second = first.next().ok_or("missing second")?;
+    ///
+    /// // The lengths of the tokens differ.
+    /// assert_ne!(hashes.get(first.id()), hashes.get(second.id()));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new<T, S, B>(tree: &Tree<T, S>, hasher: &B) -> Self
+    where
+        T: Hash,
+        S: TreeSpan,
+        B: BuildHasher,
+    {
+        Self::new_filtered(tree, hasher, |_| false)
+    }
+
+    /// Compute the content hash of every node in `tree` using hashers
+    /// constructed from `hasher`, where subtrees for which `skip` returns
+    /// `true` only contribute a fixed marker to the hash of their ancestors.
+    ///
+    /// The hash of a skipped node itself is the fixed marker. Nodes inside of
+    /// skipped subtrees are still hashed as usual, so every stored hash is
+    /// equal to the one produced by [`Node::content_hash_filtered`] for the
+    /// same node and predicate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// use syntree::TreeHashes;
+    ///
+    /// let a = syntree::tree! {
+    ///     "fn" => {
+    ///         "doc" => {
+    ///             ("comment", 10),
+    ///         },
+    ///         ("ident", 3),
+    ///     }
+    /// };
+    ///
+    /// let b = syntree::tree! {
+    ///     "fn" => {
+    ///         "doc" => {
+    ///             ("comment", 24),
+    ///         },
+    ///         ("ident", 3),
+    ///     }
+    /// };
+    ///
+    /// let state = RandomState::new();
+    ///
+    /// let a_hashes = TreeHashes::new_filtered(&a, &state, |n| *n.value() == "doc");
+    /// let b_hashes = TreeHashes::new_filtered(&b, &state, |n| *n.value() == "doc");
+    ///
+    /// let a_fn = a.first().ok_or("missing fn")?;
+    /// let b_fn = b.first().ok_or("missing fn")?;
+    ///
+    /// assert_eq!(a_hashes.get(a_fn.id()), b_hashes.get(b_fn.id()));
+    /// assert_ne!(TreeHashes::new(&a, &state).get(a_fn.id()), TreeHashes::new(&b, &state).get(b_fn.id()));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new_filtered<T, S, B, P>(tree: &Tree<T, S>, hasher: &B, mut skip: P) -> Self
+    where
+        T: Hash,
+        S: TreeSpan,
+        B: BuildHasher,
+        P: FnMut(&Node<'_, T, S>) -> bool,
+    {
+        let mut hashes = vec![0; tree.len()];
+
+        // Each frame is the node being hashed, its hasher in progress, its
+        // remaining children and whether it is skipped.
+        let mut stack = Vec::<(Node<'_, T, S>, B::Hasher, Children<'_, T, S>, bool)>::new();
+        let mut roots = tree.children();
+
+        loop {
+            let next = match stack.last_mut() {
+                Some((_, _, children, _)) => children.next(),
+                None => match roots.next() {
+                    Some(node) => Some(node),
+                    None => break,
+                },
+            };
+
+            let Some(node) = next else {
+                let Some((node, state, _, skipped)) = stack.pop() else {
+                    break;
+                };
+
+                let hash = if skipped {
+                    marker(hasher)
+                } else {
+                    state.finish()
+                };
+
+                hashes[node.id().0.get()] = hash;
+
+                if let Some((_, state, _, _)) = stack.last_mut() {
+                    state.write_u64(hash);
+                }
+
+                continue;
+            };
+
+            let skipped = skip(&node);
+
+            match node.kind() {
+                Kind::Node => {
+                    let state = open(hasher, &node);
+                    stack.push((node, state, node.children(), skipped));
+                }
+                Kind::Token => {
+                    let hash = if skipped {
+                        marker(hasher)
+                    } else {
+                        token(hasher, &node)
+                    };
+
+                    hashes[node.id().0.get()] = hash;
+
+                    if let Some((_, state, _, _)) = stack.last_mut() {
+                        state.write_u64(hash);
+                    }
+                }
+            }
+        }
+
+        Self { hashes }
+    }
+
+    /// Get the precomputed content hash of the node with the given `id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// use syntree::TreeHashes;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let state = RandomState::new();
+    /// let hashes = TreeHashes::new(&tree, &state);
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(hashes.get(root.id()), Some(root.content_hash(&state)));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn get(&self, id: Id) -> Option<u64> {
+        self.hashes.get(id.0.get()).copied()
+    }
+}
+
+/// Compute the content hash of a single node, see
+/// [`Node::content_hash_filtered`].
+pub(crate) fn content_hash<T, S, B, P>(node: &Node<'_, T, S>, hasher: &B, mut skip: P) -> u64
+where
+    T: Hash,
+    S: TreeSpan,
+    B: BuildHasher,
+    P: FnMut(&Node<'_, T, S>) -> bool,
+{
+    if skip(node) {
+        return marker(hasher);
+    }
+
+    if let Kind::Token = node.kind() {
+        return token(hasher, node);
+    }
+
+    let mut stack = vec![(open(hasher, node), node.children())];
+
+    while let Some((_, children)) = stack.last_mut() {
+        let Some(node) = children.next() else {
+            let Some((state, _)) = stack.pop() else {
+                break;
+            };
+
+            let hash = state.finish();
+
+            let Some((state, _)) = stack.last_mut() else {
+                return hash;
+            };
+
+            state.write_u64(hash);
+            continue;
+        };
+
+        let hash = if skip(&node) {
+            marker(hasher)
+        } else if let Kind::Token = node.kind() {
+            token(hasher, &node)
+        } else {
+            stack.push((open(hasher, &node), node.children()));
+            continue;
+        };
+
+        if let Some((state, _)) = stack.last_mut() {
+            state.write_u64(hash);
+        }
+    }
+
+    // The node being hashed is at the bottom of the stack, so its hash is
+    // always returned above.
+    marker(hasher)
+}
+
+/// The fixed hash of a skipped subtree.
+fn marker<B>(hasher: &B) -> u64
+where
+    B: BuildHasher,
+{
+    let mut state = hasher.build_hasher();
+    SKIPPED.hash(&mut state);
+    state.finish()
+}
+
+/// The hash of a token.
+fn token<T, S, B>(hasher: &B, node: &Node<'_, T, S>) -> u64
+where
+    T: Hash,
+    S: TreeSpan,
+    B: BuildHasher,
+{
+    let mut state = hasher.build_hasher();
+    TOKEN.hash(&mut state);
+    node.value().hash(&mut state);
+    node.span().len().hash(&mut state);
+    state.finish()
+}
+
+/// Start hashing a node, the hashes of its children are written to the
+/// returned hasher.
+fn open<T, S, B>(hasher: &B, node: &Node<'_, T, S>) -> B::Hasher
+where
+    T: Hash,
+    B: BuildHasher,
+{
+    let mut state = hasher.build_hasher();
+    NODE.hash(&mut state);
+    node.value().hash(&mut state);
+    state
+}
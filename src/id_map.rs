@@ -0,0 +1,987 @@
+//! Collections keyed by node [`Id`].
+//!
+//! Since identifiers are dense indexes into the storage of a tree, these can be
+//! used instead of hash maps to associate data with nodes without paying for
+//! hashing.
+//!
+//! Identifiers from a different tree are simply treated as other indexes, so
+//! looking them up never panics but might not be meaningful. Identifiers which
+//! are much larger than the number of entries in a collection are stored
+//! sparsely, so that they can't cause large allocations.
+
+use alloc::collections::{btree_map, BTreeMap};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem;
+use core::slice;
+
+use crate::builder::Id;
+use crate::error::Error;
+use crate::node::Node;
+use crate::span::TreeSpan;
+use crate::tree::Tree;
+
+/// A map from [`Id`] to values, stored densely by the index of the node.
+///
+/// Values for identifiers which are far beyond the number of values in the
+/// map are stored sparsely until the map has grown enough to cover them.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::IdMap;
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///         ("ident", 2),
+///     }
+/// };
+///
+/// let mut depths = IdMap::with_capacity_for(&tree);
+///
+/// for (depth, node) in tree.walk().with_depths() {
+///     depths.insert(node.id(), depth);
+/// }
+///
+/// let number = tree.first().and_then(|n| n.first()).ok_or("missing number")?;
+/// assert_eq!(depths.get(number.id()), Some(&1));
+/// assert_eq!(depths.len(), 3);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone)]
+pub struct IdMap<V> {
+    values: Vec<Option<V>>,
+    /// Values whose index is past the end of `values`.
+    sparse: BTreeMap<usize, V>,
+    len: usize,
+}
+
+impl<V> IdMap<V> {
+    /// Construct a new empty map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::IdMap;
+    ///
+    /// let map = IdMap::<u32>::new();
+    /// assert!(map.is_empty());
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            sparse: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Construct a new empty map with room for every node in the given
+    /// `tree`, so that inserting values for its nodes does not reallocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::IdMap;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("number", 3),
+    ///     }
+    /// };
+    ///
+    /// let map = IdMap::<u32>::with_capacity_for(&tree);
+    /// assert!(map.is_empty());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn with_capacity_for<T, S>(tree: &Tree<T, S>) -> Self
+    where
+        S: TreeSpan,
+    {
+        let mut values = Vec::with_capacity(tree.len());
+        values.resize_with(tree.len(), || None);
+
+        Self {
+            values,
+            sparse: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// The number of values in the map.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Test if the map is empty.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert a value for the given `id`, returning the previous value if
+    /// there was one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Id, IdMap};
+    ///
+    /// let id = Id::from_index(4).ok_or("bad id")?;
+    ///
+    /// let mut map = IdMap::new();
+    /// assert_eq!(map.insert(id, "a"), None);
+    /// assert_eq!(map.insert(id, "b"), Some("a"));
+    /// assert_eq!(map.get(id), Some(&"b"));
+    /// assert_eq!(map.len(), 1);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn insert(&mut self, id: Id, value: V) -> Option<V> {
+        let index = id.index();
+
+        let old = if self.reserve(index) {
+            self.values[index].replace(value)
+        } else {
+            self.sparse.insert(index, value)
+        };
+
+        if old.is_none() {
+            self.len += 1;
+        }
+
+        old
+    }
+
+    /// Get the value associated with the given `id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Id, IdMap};
+    ///
+    /// let mut map = IdMap::new();
+    /// map.insert(Id::from_index(1).ok_or("bad id")?, 42);
+    ///
+    /// assert_eq!(map.get(Id::from_index(1).ok_or("bad id")?), Some(&42));
+    /// assert_eq!(map.get(Id::from_index(0).ok_or("bad id")?), None);
+    /// assert_eq!(map.get(Id::from_index(100).ok_or("bad id")?), None);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn get(&self, id: Id) -> Option<&V> {
+        let index = id.index();
+
+        match self.values.get(index) {
+            Some(value) => value.as_ref(),
+            None => self.sparse.get(&index),
+        }
+    }
+
+    /// Get the value associated with the given `id` mutably.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Id, IdMap};
+    ///
+    /// let id = Id::from_index(1).ok_or("bad id")?;
+    ///
+    /// let mut map = IdMap::new();
+    /// map.insert(id, 1);
+    ///
+    /// if let Some(value) = map.get_mut(id) {
+    ///     *value += 1;
+    /// }
+    ///
+    /// assert_eq!(map.get(id), Some(&2));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut V> {
+        let index = id.index();
+
+        match self.values.get_mut(index) {
+            Some(value) => value.as_mut(),
+            None => self.sparse.get_mut(&index),
+        }
+    }
+
+    /// Test if the map contains a value for the given `id`.
+    #[must_use]
+    pub fn contains(&self, id: Id) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Get the value associated with the given `id`, inserting the value
+    /// returned by `f` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Id, IdMap};
+    ///
+    /// let id = Id::from_index(2).ok_or("bad id")?;
+    ///
+    /// let mut map = IdMap::<Vec<u32>>::new();
+    /// map.get_or_insert_with(id, Vec::new).push(1);
+    /// map.get_or_insert_with(id, Vec::new).push(2);
+    ///
+    /// assert_eq!(map.get(id), Some(&vec![1, 2]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get_or_insert_with<F>(&mut self, id: Id, f: F) -> &mut V
+    where
+        F: FnOnce() -> V,
+    {
+        let index = id.index();
+
+        if !self.reserve(index) {
+            return match self.sparse.entry(index) {
+                btree_map::Entry::Occupied(e) => e.into_mut(),
+                btree_map::Entry::Vacant(e) => {
+                    self.len += 1;
+                    e.insert(f())
+                }
+            };
+        }
+
+        let slot = &mut self.values[index];
+
+        if slot.is_none() {
+            self.len += 1;
+        }
+
+        slot.get_or_insert_with(f)
+    }
+
+    /// Remove the value associated with the given `id`.
+    pub fn remove(&mut self, id: Id) -> Option<V> {
+        let index = id.index();
+
+        let value = match self.values.get_mut(index) {
+            Some(value) => value.take()?,
+            None => self.sparse.remove(&index)?,
+        };
+
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Remove all values from the map.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.sparse.clear();
+        self.len = 0;
+    }
+
+    /// Iterate over identifiers and values in the map, in order of
+    /// identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Id, IdMap};
+    ///
+    /// let mut map = IdMap::new();
+    /// map.insert(Id::from_index(3).ok_or("bad id")?, 'c');
+    /// map.insert(Id::from_index(1).ok_or("bad id")?, 'a');
+    ///
+    /// let entries = map.iter().map(|(id, c)| (id.index(), *c)).collect::<Vec<_>>();
+    /// assert_eq!(entries, [(1, 'a'), (3, 'c')]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            iter: self.values.iter().enumerate(),
+            sparse: self.sparse.iter(),
+        }
+    }
+
+    /// Iterate mutably over identifiers and values in the map, in order of
+    /// identifier.
+    #[must_use]
+    pub fn iter_mut(&mut self) -> IterMut<'_, V> {
+        IterMut {
+            iter: self.values.iter_mut().enumerate(),
+            sparse: self.sparse.iter_mut(),
+        }
+    }
+
+    /// Grow the dense storage to cover `index` if it is close enough to the
+    /// number of values in the map, returning `true` if it is covered.
+    fn reserve(&mut self, index: usize) -> bool {
+        if index < self.values.len() {
+            return true;
+        }
+
+        if index >= self.len.saturating_mul(2).saturating_add(DENSE_SLACK) {
+            return false;
+        }
+
+        let len = index + 1;
+        self.values.resize_with(len, || None);
+
+        // Move sparse values which are now covered into dense storage.
+        let rest = self.sparse.split_off(&len);
+
+        for (index, value) in mem::replace(&mut self.sparse, rest) {
+            self.values[index] = Some(value);
+        }
+
+        true
+    }
+}
+
+impl<V> PartialEq for IdMap<V>
+where
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<V> Eq for IdMap<V> where V: Eq {}
+
+impl<V> Default for IdMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> fmt::Debug for IdMap<V>
+where
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<V> FromIterator<(Id, V)> for IdMap<V> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Id, V)>,
+    {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<V> Extend<(Id, V)> for IdMap<V> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Id, V)>,
+    {
+        for (id, value) in iter {
+            self.insert(id, value);
+        }
+    }
+}
+
+impl<'a, V> IntoIterator for &'a IdMap<V> {
+    type Item = (Id, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a mut IdMap<V> {
+    type Item = (Id, &'a mut V);
+    type IntoIter = IterMut<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Iterator over the entries of an [`IdMap`], as returned by [`IdMap::iter`].
+pub struct Iter<'a, V> {
+    iter: core::iter::Enumerate<slice::Iter<'a, Option<V>>>,
+    sparse: btree_map::Iter<'a, usize, V>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Id, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, value) in self.iter.by_ref() {
+            if let Some(value) = value {
+                return Some((Id::from_index(index)?, value));
+            }
+        }
+
+        let (&index, value) = self.sparse.next()?;
+        Some((Id::from_index(index)?, value))
+    }
+}
+
+impl<V> FusedIterator for Iter<'_, V> {}
+
+/// Mutable iterator over the entries of an [`IdMap`], as returned by
+/// [`IdMap::iter_mut`].
+pub struct IterMut<'a, V> {
+    iter: core::iter::Enumerate<slice::IterMut<'a, Option<V>>>,
+    sparse: btree_map::IterMut<'a, usize, V>,
+}
+
+impl<'a, V> Iterator for IterMut<'a, V> {
+    type Item = (Id, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, value) in self.iter.by_ref() {
+            if let Some(value) = value {
+                return Some((Id::from_index(index)?, value));
+            }
+        }
+
+        let (&index, value) = self.sparse.next()?;
+        Some((Id::from_index(index)?, value))
+    }
+}
+
+impl<V> FusedIterator for IterMut<'_, V> {}
+
+/// A set of [`Id`]s, stored as a bitset by the index of the node.
+///
+/// Membership tests are a single bit lookup. The bitset only grows to cover
+/// identifiers which are close to the number of identifiers in the set, the
+/// words of identifiers beyond that are stored sparsely.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::IdSet;
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///         ("ident", 2),
+///     }
+/// };
+///
+/// let mut tokens = IdSet::new();
+///
+/// for node in tree.walk() {
+///     if node.is_empty() {
+///         tokens.insert(node.id());
+///     }
+/// }
+///
+/// let root = tree.first().ok_or("missing root")?;
+/// assert!(!tokens.contains(root.id()));
+/// assert_eq!(tokens.len(), 2);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct IdSet {
+    words: Vec<u64>,
+    /// Non-empty words whose index is past the end of `words`.
+    sparse: BTreeMap<usize, u64>,
+    len: usize,
+}
+
+impl IdSet {
+    /// Construct a new empty set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            words: Vec::new(),
+            sparse: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// The number of identifiers in the set.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Test if the set is empty.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert the given `id`, returning `true` if it was not already present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Id, IdSet};
+    ///
+    /// let id = Id::from_index(70).ok_or("bad id")?;
+    ///
+    /// let mut set = IdSet::new();
+    /// assert!(set.insert(id));
+    /// assert!(!set.insert(id));
+    /// assert!(set.contains(id));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn insert(&mut self, id: Id) -> bool {
+        let (word, bit) = split(id);
+
+        let w = if self.reserve(word) {
+            &mut self.words[word]
+        } else {
+            self.sparse.entry(word).or_insert(0)
+        };
+
+        let inserted = *w & bit == 0;
+        *w |= bit;
+        self.len += usize::from(inserted);
+        inserted
+    }
+
+    /// Test if the set contains the given `id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Id, IdSet};
+    ///
+    /// let mut set = IdSet::new();
+    /// set.insert(Id::from_index(1).ok_or("bad id")?);
+    ///
+    /// assert!(set.contains(Id::from_index(1).ok_or("bad id")?));
+    /// assert!(!set.contains(Id::from_index(2).ok_or("bad id")?));
+    /// assert!(!set.contains(Id::from_index(1000).ok_or("bad id")?));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn contains(&self, id: Id) -> bool {
+        let (word, bit) = split(id);
+
+        let w = match self.words.get(word) {
+            Some(w) => w,
+            None => self.sparse.get(&word).unwrap_or(&0),
+        };
+
+        w & bit != 0
+    }
+
+    /// Remove the given `id`, returning `true` if it was present.
+    pub fn remove(&mut self, id: Id) -> bool {
+        let (word, bit) = split(id);
+
+        let Some(w) = self.words.get_mut(word) else {
+            let Some(w) = self.sparse.get_mut(&word) else {
+                return false;
+            };
+
+            let removed = *w & bit != 0;
+            *w &= !bit;
+
+            if *w == 0 {
+                self.sparse.remove(&word);
+            }
+
+            self.len -= usize::from(removed);
+            return removed;
+        };
+
+        let removed = *w & bit != 0;
+        *w &= !bit;
+        self.len -= usize::from(removed);
+        removed
+    }
+
+    /// Remove all identifiers from the set.
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.sparse.clear();
+        self.len = 0;
+    }
+
+    /// Iterate over identifiers in the set, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Id, IdSet};
+    ///
+    /// let set = [65, 3, 1]
+    ///     .into_iter()
+    ///     .flat_map(Id::from_index)
+    ///     .collect::<IdSet>();
+    ///
+    /// let indexes = set.iter().map(|id| id.index()).collect::<Vec<_>>();
+    /// assert_eq!(indexes, [1, 3, 65]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn iter(&self) -> Ids<'_> {
+        Ids {
+            words: self.words.iter(),
+            sparse: self.sparse.iter(),
+            base: 0,
+            current: 0,
+        }
+    }
+
+    /// Grow the bitset to cover `word` if it is close enough to the number of
+    /// identifiers in the set, returning `true` if it is covered.
+    fn reserve(&mut self, word: usize) -> bool {
+        if word < self.words.len() {
+            return true;
+        }
+
+        if word >= self.len.saturating_add(DENSE_SLACK) {
+            return false;
+        }
+
+        let len = word + 1;
+        self.words.resize(len, 0);
+
+        // Move sparse words which are now covered into the bitset.
+        let rest = self.sparse.split_off(&len);
+
+        for (word, bits) in mem::replace(&mut self.sparse, rest) {
+            self.words[word] = bits;
+        }
+
+        true
+    }
+}
+
+impl PartialEq for IdSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for IdSet {}
+
+impl fmt::Debug for IdSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<Id> for IdSet {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Id>,
+    {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Id> for IdSet {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Id>,
+    {
+        for id in iter {
+            self.insert(id);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a IdSet {
+    type Item = Id;
+    type IntoIter = Ids<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the identifiers in an [`IdSet`], as returned by
+/// [`IdSet::iter`].
+pub struct Ids<'a> {
+    words: slice::Iter<'a, u64>,
+    sparse: btree_map::Iter<'a, usize, u64>,
+    /// The index of the first identifier after the current word.
+    base: usize,
+    current: u64,
+}
+
+impl Iterator for Ids<'_> {
+    type Item = Id;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bits = u64::BITS as usize;
+
+        while self.current == 0 {
+            self.current = if let Some(&w) = self.words.next() {
+                self.base += bits;
+                w
+            } else {
+                let (&word, &w) = self.sparse.next()?;
+                self.base = (word + 1) * bits;
+                w
+            };
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Id::from_index(self.base - bits + bit)
+    }
+}
+
+impl FusedIterator for Ids<'_> {}
+
+/// A set of flags for the nodes of a particular tree, stored as a bitset
+/// sized to the tree.
+///
+/// This is a companion to a built [`Tree`] for marking nodes during analysis
+/// passes, such as which nodes have been type checked. Unlike [`IdSet`], it is
+/// constructed for a tree up front and rejects identifiers which are out of
+/// range for it. Note that identifiers from a different tree with at least as
+/// many nodes can't be detected.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::FlagSet;
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///         "error" => {},
+///     }
+/// };
+///
+/// let mut checked = FlagSet::new(&tree);
+/// let mut errors = FlagSet::new(&tree);
+///
+/// for node in tree.walk() {
+///     checked.set(node.id())?;
+///
+///     if *node.value() == "error" {
+///         errors.set(node.id())?;
+///
+///         for ancestor in node.ancestors() {
+///             errors.set(ancestor.id())?;
+///         }
+///     }
+/// }
+///
+/// let values = errors.nodes(&tree).map(|n| *n.value()).collect::<Vec<_>>();
+/// assert_eq!(values, ["root", "error"]);
+///
+/// checked.intersect_with(&errors)?;
+/// assert_eq!(checked, errors);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagSet {
+    set: IdSet,
+    capacity: usize,
+}
+
+impl FlagSet {
+    /// Construct an empty flag set for the nodes of `tree`.
+    #[must_use]
+    pub fn new<T, S>(tree: &Tree<T, S>) -> Self
+    where
+        S: TreeSpan,
+    {
+        let capacity = tree.len();
+        let bits = u64::BITS as usize;
+
+        let set = IdSet {
+            words: vec![0; (capacity + bits - 1) / bits],
+            sparse: BTreeMap::new(),
+            len: 0,
+        };
+
+        Self { set, capacity }
+    }
+
+    /// The number of nodes the flag set was constructed for.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of nodes which are flagged.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Test if no nodes are flagged.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Flag the node `id`, returning `true` if it was not already flagged.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::MissingNode`] if `id` is out of range for the tree
+    /// the flag set was constructed for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Error, FlagSet, Id};
+    ///
+    /// let tree = syntree::tree! { ("lit", 1) };
+    /// let lit = tree.first().ok_or("missing lit")?;
+    ///
+    /// let mut flags = FlagSet::new(&tree);
+    /// assert_eq!(flags.set(lit.id()), Ok(true));
+    /// assert_eq!(flags.set(lit.id()), Ok(false));
+    /// assert!(flags.get(lit.id()));
+    ///
+    /// let other = Id::from_index(1).ok_or("bad id")?;
+    /// assert_eq!(flags.set(other), Err(Error::MissingNode(other)));
+    /// assert!(!flags.get(other));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set(&mut self, id: Id) -> Result<bool, Error> {
+        self.check(id)?;
+        Ok(self.set.insert(id))
+    }
+
+    /// Test if the node `id` is flagged. Identifiers which are out of range
+    /// are never flagged.
+    #[must_use]
+    pub fn get(&self, id: Id) -> bool {
+        self.set.contains(id)
+    }
+
+    /// Clear the flag of the node `id`, returning `true` if it was flagged.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::MissingNode`] if `id` is out of range for the tree
+    /// the flag set was constructed for.
+    pub fn clear(&mut self, id: Id) -> Result<bool, Error> {
+        self.check(id)?;
+        Ok(self.set.remove(id))
+    }
+
+    /// Flag every node which is flagged in `other`.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::FlagSetMismatch`] if `other` was constructed for a
+    /// tree of a different size.
+    pub fn union_with(&mut self, other: &FlagSet) -> Result<(), Error> {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Clear the flag of every node which is not flagged in `other`.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::FlagSetMismatch`] if `other` was constructed for a
+    /// tree of a different size.
+    pub fn intersect_with(&mut self, other: &FlagSet) -> Result<(), Error> {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// Iterate over the identifiers of flagged nodes, in order.
+    #[must_use]
+    pub fn iter(&self) -> Ids<'_> {
+        self.set.iter()
+    }
+
+    /// Iterate over the flagged nodes of `tree`, which should be the tree the
+    /// flag set was constructed for.
+    #[must_use]
+    pub fn nodes<'a, T, S>(&'a self, tree: &'a Tree<T, S>) -> FlaggedNodes<'a, T, S>
+    where
+        S: TreeSpan,
+    {
+        FlaggedNodes {
+            ids: self.set.iter(),
+            tree,
+        }
+    }
+
+    fn check(&self, id: Id) -> Result<(), Error> {
+        if id.index() >= self.capacity {
+            return Err(Error::MissingNode(id));
+        }
+
+        Ok(())
+    }
+
+    fn combine(&mut self, other: &FlagSet, f: impl Fn(u64, u64) -> u64) -> Result<(), Error> {
+        if self.capacity != other.capacity {
+            return Err(Error::FlagSetMismatch {
+                len: self.capacity,
+                other: other.capacity,
+            });
+        }
+
+        let mut len = 0;
+
+        for (a, b) in self.set.words.iter_mut().zip(&other.set.words) {
+            *a = f(*a, *b);
+            len += a.count_ones() as usize;
+        }
+
+        self.set.len = len;
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a FlagSet {
+    type Item = Id;
+    type IntoIter = Ids<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the flagged nodes in a [`FlagSet`], as returned by
+/// [`FlagSet::nodes`].
+pub struct FlaggedNodes<'a, T, S>
+where
+    S: TreeSpan,
+{
+    ids: Ids<'a>,
+    tree: &'a Tree<T, S>,
+}
+
+impl<'a, T, S> Iterator for FlaggedNodes<'a, T, S>
+where
+    S: TreeSpan,
+{
+    type Item = Node<'a, T, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tree.get(self.ids.next()?)
+    }
+}
+
+impl<T, S> FusedIterator for FlaggedNodes<'_, T, S> where S: TreeSpan {}
+
+/// How far past the number of entries in a collection its dense storage is
+/// allowed to grow, in entries for [`IdMap`] and in words for [`IdSet`].
+const DENSE_SLACK: usize = 64;
+
+/// Split an identifier into the index of its word and its bit in that word.
+#[inline]
+fn split(id: Id) -> (usize, u64) {
+    let index = id.index();
+    let bits = u64::BITS as usize;
+    (index / bits, 1 << (index % bits))
+}
@@ -0,0 +1,72 @@
+/// A stable textual name for the kind of a tree value.
+///
+/// The printers and exporters normally render values through their
+/// [`Debug`][core::fmt::Debug] implementation, which ties textual output such
+/// as golden files to the names of Rust identifiers. Implementing this trait
+/// decouples the two, and is used by the `*_named` family of functions:
+///
+/// * [`print::print_named`] and [`print::print_named_with_source`].
+/// * [`print::to_dot_named`].
+/// * [`print::to_sexpr_named`] and its inverse [`sexpr::from_sexpr_named`].
+#[cfg_attr(
+    feature = "serde",
+    doc = " * [`Tree::write_events_named`] with the `serde` feature."
+)]
+#[cfg_attr(
+    not(feature = "serde"),
+    doc = " * `Tree::write_events_named` with the `serde` feature."
+)]
+///
+/// This is most conveniently implemented using the [`kind_names!`] macro.
+///
+/// [`print::print_named`]: crate::print::print_named
+/// [`print::print_named_with_source`]: crate::print::print_named_with_source
+/// [`print::to_dot_named`]: crate::print::to_dot_named
+/// [`print::to_sexpr_named`]: crate::print::to_sexpr_named
+/// [`sexpr::from_sexpr_named`]: crate::sexpr::from_sexpr_named
+#[cfg_attr(
+    feature = "serde",
+    doc = " [`Tree::write_events_named`]: crate::Tree::write_events_named"
+)]
+/// [`kind_names!`]: crate::kind_names
+///
+/// # Examples
+///
+/// ```
+/// use syntree::KindName;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Syntax {
+///     Number,
+///     Whitespace,
+/// }
+///
+/// impl KindName for Syntax {
+///     fn kind_name(&self) -> &str {
+///         match self {
+///             Syntax::Number => "number",
+///             Syntax::Whitespace => "ws",
+///         }
+///     }
+/// }
+///
+/// assert_eq!(Syntax::Whitespace.kind_name(), "ws");
+/// assert_eq!(Syntax::from_kind_name("ws"), None);
+/// ```
+pub trait KindName {
+    /// Get the name of this kind.
+    fn kind_name(&self) -> &str;
+
+    /// Look up a kind by its name, which is used when parsing textual formats
+    /// back into trees.
+    ///
+    /// The default implementation doesn't recognize any names.
+    #[inline]
+    fn from_kind_name(name: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = name;
+        None
+    }
+}
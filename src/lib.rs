@@ -26,9 +26,10 @@
 //!
 //! ## Enabling `syntree_compact`
 //!
-//! We support a configuration option to reduce the size of the tree in memory.
-//! It changes the tree from using `usize` as indexes to use `u32` which saves 4
-//! bytes per reference on 64-bit platforms.
+//! References between nodes are always stored as `u32`, which limits a tree to
+//! `u32::MAX` nodes. We support a configuration option to further reduce the
+//! size of the tree in memory. It changes spans from using `usize` as indexes
+//! to use `u32` which saves 8 bytes per node on 64-bit platforms.
 //!
 //! This can be enabled by setting `--cfg syntree_compact` while building and
 //! might improve performance due to allowing nodes to fit neatly on individual
@@ -40,6 +41,17 @@
 //!
 //! <br>
 //!
+//! ## `no_std` support
+//!
+//! The crate supports `no_std` environments with an allocator by disabling
+//! the default `std` feature. This disables the functions in the [`print`]
+//! module which write to an `std::io::Write` and other functionality which
+//! relies on `std::io`. Error types implement `std::error::Error` with the
+//! `std` feature, and `core::error::Error` without it if the `core-error`
+//! feature is enabled, which requires Rust 1.81.
+//!
+//! <br>
+//!
 //! ## Syntax trees
 //!
 //! This crate provides a way to efficiently model [abstract syntax trees]. The
@@ -283,21 +295,67 @@
 
 #![deny(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
 
 #[macro_use]
 mod macros;
+#[cfg(feature = "arbitrary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+pub mod arbitrary;
+#[cfg(feature = "binary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "binary")))]
+pub mod binary;
 mod builder;
+mod child_index;
+mod debug;
+pub mod diff;
 pub mod edit;
 mod error;
+mod hash;
+pub mod id_map;
+mod kind_name;
+mod line_index;
 mod links;
 pub mod node;
 mod non_max;
+pub mod owned;
+mod owned_event;
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub mod par;
 pub mod print;
+pub mod query;
+pub mod sexpr;
+pub mod shape;
+mod source;
+mod source_tree;
 pub mod span;
+mod token_index;
+pub mod transform;
 mod tree;
 
-pub use self::builder::{Builder, Checkpoint, Id};
+pub use self::builder::{
+    Builder, BuilderLimits, Checkpoint, CursorPolicy, Id, Limit, OpenStack, Postfix, Recovered,
+};
+pub use self::child_index::ChildIndex;
 pub use self::error::Error;
-pub use self::node::Node;
+pub use self::hash::TreeHashes;
+pub use self::id_map::{FlagSet, IdMap, IdSet};
+pub use self::kind_name::KindName;
+pub use self::line_index::{LineColumn, LineIndex};
+pub use self::node::{Node, TypedNode, VisitControl, Visitor};
+pub use self::owned_event::OwnedEvent;
+pub use self::sexpr::from_sexpr;
+pub use self::source::Source;
+pub use self::source_tree::{SourceNode, SourceTree, WithSource};
 pub use self::span::Span;
-pub use self::tree::{Kind, Tree};
+pub use self::token_index::{TokenIndex, TokensIn};
+pub use self::tree::{
+    CorruptTree, Entries, IntoValues, Invariant, Kind, Kinds, Link, Page, PageItem, RawEntry,
+    RawIter, RawTree, Spans, Tree, TreeId, Values, ValuesMut,
+};
@@ -0,0 +1,173 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+/// A zero-based line and column in a source, as returned by
+/// [`LineIndex::line_column`].
+///
+/// The column is counted in bytes from the start of the line. The [Display]
+/// implementation is one-based, as is conventional for diagnostics.
+///
+/// [Display]: core::fmt::Display
+///
+/// # Examples
+///
+/// ```
+/// use syntree::LineColumn;
+///
+/// let position = LineColumn { line: 1, column: 4 };
+/// assert_eq!(position.to_string(), "2:5");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LineColumn {
+    /// The zero-based line.
+    pub line: usize,
+    /// The zero-based column in bytes.
+    pub column: usize,
+}
+
+impl fmt::Display for LineColumn {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line + 1, self.column + 1)
+    }
+}
+
+/// An index of where lines start in a source, used to translate the byte
+/// offsets in spans into lines and columns.
+///
+/// Lines are separated by `\n`, so a line ending in `\r\n` includes the `\r`.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::{LineColumn, LineIndex};
+///
+/// let source = "fn main\n  ret 42\n";
+///
+/// let tree = syntree::tree! {
+///     "fn" => {
+///         ("ident", 7),
+///         ("nl", 1),
+///     },
+///     "ret" => {
+///         ("ws", 2),
+///         ("ident", 3),
+///         ("ws", 1),
+///         ("number", 2),
+///         ("nl", 1),
+///     }
+/// };
+///
+/// let lines = LineIndex::new(source);
+///
+/// let number = tree.walk().find(|n| *n.value() == "number").ok_or("missing number")?;
+/// let position = lines.line_column(number.range().start).ok_or("out of bounds")?;
+///
+/// assert_eq!(position, LineColumn { line: 1, column: 6 });
+/// assert_eq!(position.to_string(), "2:7");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// The offset at which each line starts.
+    starts: Vec<usize>,
+    /// The length of the indexed source.
+    len: usize,
+}
+
+impl LineIndex {
+    /// Index the lines of the given `source`.
+    #[must_use]
+    pub fn new(source: &str) -> Self {
+        let mut starts = vec![0];
+
+        for (n, b) in source.bytes().enumerate() {
+            if b == b'\n' {
+                starts.push(n + 1);
+            }
+        }
+
+        Self {
+            starts,
+            len: source.len(),
+        }
+    }
+
+    /// Get the number of lines in the indexed source.
+    ///
+    /// A source always has at least one line, and a trailing `\n` starts an
+    /// empty last line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::LineIndex;
+    ///
+    /// assert_eq!(LineIndex::new("").line_count(), 1);
+    /// assert_eq!(LineIndex::new("a\nb").line_count(), 2);
+    /// assert_eq!(LineIndex::new("a\nb\n").line_count(), 3);
+    /// ```
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Translate a byte `offset` into a line and column.
+    ///
+    /// Returns `None` if the offset is past the end of the indexed source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{LineColumn, LineIndex};
+    ///
+    /// let lines = LineIndex::new("a\nbc\n");
+    ///
+    /// assert_eq!(lines.line_column(0), Some(LineColumn { line: 0, column: 0 }));
+    /// assert_eq!(lines.line_column(1), Some(LineColumn { line: 0, column: 1 }));
+    /// assert_eq!(lines.line_column(3), Some(LineColumn { line: 1, column: 1 }));
+    /// assert_eq!(lines.line_column(5), Some(LineColumn { line: 2, column: 0 }));
+    /// assert_eq!(lines.line_column(6), None);
+    /// ```
+    #[must_use]
+    pub fn line_column(&self, offset: usize) -> Option<LineColumn> {
+        if offset > self.len {
+            return None;
+        }
+
+        let line = self.starts.partition_point(|&start| start <= offset) - 1;
+        let column = offset - self.starts[line];
+        Some(LineColumn { line, column })
+    }
+
+    /// Get the byte range of the given zero-based `line`, excluding its
+    /// terminating `\n`.
+    ///
+    /// Returns `None` if there is no such line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::LineIndex;
+    ///
+    /// let source = "first\nsecond\n";
+    /// let lines = LineIndex::new(source);
+    ///
+    /// assert_eq!(lines.line_range(1).map(|r| &source[r]), Some("second"));
+    /// assert_eq!(lines.line_range(2).map(|r| &source[r]), Some(""));
+    /// assert_eq!(lines.line_range(3), None);
+    /// ```
+    #[must_use]
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.starts.get(line)?;
+
+        let end = match self.starts.get(line + 1) {
+            Some(&next) => next - 1,
+            None => self.len,
+        };
+
+        Some(start..end)
+    }
+}
@@ -3,6 +3,11 @@
 use crate::non_max::NonMax;
 use crate::Kind;
 
+/// Mark of a node produced by error recovery.
+pub(crate) const MARK_ERROR: u8 = 1 << 0;
+/// Mark of a trivia token.
+pub(crate) const MARK_TRIVIA: u8 = 1 << 1;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Links<T, S> {
     /// The data in the node.
@@ -22,4 +27,19 @@ pub struct Links<T, S> {
     pub(crate) first: Option<NonMax>,
     /// Last child node.
     pub(crate) last: Option<NonMax>,
+    /// The number of children. This is stored as 32 bits just like references
+    /// since it can't exceed the number of nodes in the tree.
+    pub(crate) len: u32,
+    /// User flags, which fit in what would otherwise be padding.
+    pub(crate) flags: u8,
+    /// Marks such as [`MARK_ERROR`] and [`MARK_TRIVIA`], which also fit in
+    /// what would otherwise be padding.
+    pub(crate) marks: u8,
 }
+
+// Make sure that links don't grow by accident. Five 32-bit references, the
+// number of children, the kind, user flags, marks and two span indexes.
+#[cfg(not(syntree_compact))]
+const _: () = assert!(core::mem::size_of::<Links<(), crate::Span>>() <= 48);
+#[cfg(syntree_compact)]
+const _: () = assert!(core::mem::size_of::<Links<(), crate::Span>>() <= 36);
@@ -1,5 +1,13 @@
 /// Helper macro for building a tree in place.
 ///
+/// # Panics
+///
+/// Panics if the literal doesn't describe a valid tree, such as when a token
+/// is given a range which starts before the end of the previous token. Use
+/// [`try_tree!`] to propagate the error instead.
+///
+/// [`try_tree!`]: crate::try_tree
+///
 /// # Examples
 ///
 /// ```
@@ -46,10 +54,51 @@
 /// assert_eq!(expected, tree);
 /// # Ok::<_,  Box<dyn std::error::Error>>(())
 /// ```
+///
+/// Tokens can also be given an explicit range instead of a length, which
+/// moves the cursor to the end of the range. The whole tree can be offset by
+/// prefixing it with `offset = <length>;`, which is useful for describing
+/// fragments which start in the middle of a source:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     offset = 10;
+///     "root" => {
+///         ("number", 3),
+///         ("string", 15..20),
+///         ("ws", 1),
+///     }
+/// };
+///
+/// let root = tree.first().ok_or("missing root")?;
+/// assert_eq!(root.range(), 10..21);
+///
+/// let ranges = root.children().map(|n| n.range()).collect::<Vec<_>>();
+/// assert_eq!(ranges, [10..13, 15..20, 20..21]);
+/// # Ok::<_,  Box<dyn std::error::Error>>(())
+/// ```
 #[macro_export]
 macro_rules! tree {
     (@o $b:ident,) => {};
 
+    (@o $b:ident, ($expr:expr, span = $span:expr) $(,)?) => {{
+        $b.token_with_span($expr, $span)?;
+    }};
+
+    (@o $b:ident, ($expr:expr, span = $span:expr), $($rest:tt)*) => {{
+        $b.token_with_span($expr, $span)?;
+        $crate::tree!(@o $b, $($rest)*);
+    }};
+
+    (@o $b:ident, ($expr:expr, $start:tt .. $end:tt) $(,)?) => {{
+        $b.token_with_span($expr, $crate::span::TreeSpan::new($start, $end))?;
+    }};
+
+    (@o $b:ident, ($expr:expr, $start:tt .. $end:tt), $($rest:tt)*) => {{
+        $b.token_with_span($expr, $crate::span::TreeSpan::new($start, $end))?;
+        $crate::tree!(@o $b, $($rest)*);
+    }};
+
     (@o $b:ident, ($expr:expr, $len:expr) $(,)?) => {{
         $b.token($expr, $len)?;
     }};
@@ -81,15 +130,73 @@ macro_rules! tree {
         $crate::tree!(@o $b, $($rest)*);
     }};
 
-    ($($tt:tt)*) => {{
-        let mut b = $crate::Builder::new();
+    (@try [$($builder:tt)*] offset = $offset:expr; $($tt:tt)*) => {{
+        let mut b = $($builder)*.with_policy($crate::CursorPolicy::Monotonic);
+        b.advance($offset)?;
+        $crate::tree!(@o b, $($tt)*);
+        b.build()?
+    }};
+
+    (@try [$($builder:tt)*] $($tt:tt)*) => {{
+        let mut b = $($builder)*.with_policy($crate::CursorPolicy::Monotonic);
         $crate::tree!(@o b, $($tt)*);
         b.build()?
     }};
+
+    (@build [$($builder:tt)*] $($tt:tt)*) => {{
+        let result = (|| -> ::core::result::Result<_, $crate::Error> {
+            ::core::result::Result::Ok($crate::tree!(@try [$($builder)*] $($tt)*))
+        })();
+
+        match result {
+            ::core::result::Result::Ok(tree) => tree,
+            ::core::result::Result::Err(error) => {
+                ::core::panic!("failed to build tree from literal: {}", error)
+            }
+        }
+    }};
+
+    ($($tt:tt)*) => {
+        $crate::tree!(@build [$crate::Builder::new()] $($tt)*)
+    };
+}
+
+/// Helper macro for building a tree in place, which propagates errors using
+/// `?` instead of panicking.
+///
+/// This accepts the same syntax as [`tree!`], and can only be used in
+/// functions returning a [`Result`] whose error can be converted from
+/// [`Error`][crate::Error].
+///
+/// # Examples
+///
+/// ```
+/// use syntree::{Error, Span, Tree};
+///
+/// fn fragment() -> Result<Tree<&'static str, Span>, Error> {
+///     Ok(syntree::try_tree! {
+///         ("a", 4),
+///         ("b", 2..3),
+///     })
+/// }
+///
+/// assert!(matches!(
+///     fragment(),
+///     Err(Error::CursorBackwards { cursor: 4, start: 2, .. })
+/// ));
+/// ```
+#[macro_export]
+macro_rules! try_tree {
+    ($($tt:tt)*) => {
+        $crate::tree!(@try [$crate::Builder::new()] $($tt)*)
+    };
 }
 
 /// Helper macro for building a tree in place with a custom span.
 ///
+/// This accepts the same syntax as [`tree!`]. Tokens can also be given an
+/// arbitrary span expression through `(value, span = <expr>)`.
+///
 /// # Examples
 ///
 /// ```
@@ -118,9 +225,285 @@ macro_rules! tree {
 /// ```
 #[macro_export]
 macro_rules! tree_with {
-    ($($tt:tt)*) => {{
-        let mut b = $crate::Builder::new_with();
-        $crate::tree!(@o b, $($tt)*);
-        b.build()?
+    ($($tt:tt)*) => {
+        $crate::tree!(@build [$crate::Builder::new_with()] $($tt)*)
+    };
+}
+
+/// Assert that two trees are equal, panicking with a rendering of their
+/// differences as produced by [`diff::print_diff`] if they are not.
+///
+/// [`diff::print_diff`]: crate::diff::print_diff
+///
+/// # Examples
+///
+/// ```
+/// let a = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///     }
+/// };
+///
+/// let b = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///     }
+/// };
+///
+/// syntree::assert_tree_eq!(a, b);
+/// # Ok::<_,  Box<dyn std::error::Error>>(())
+/// ```
+///
+/// ```should_panic
+/// let a = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///     }
+/// };
+///
+/// let b = syntree::tree! {
+///     "root" => {
+///         ("ident", 3),
+///     }
+/// };
+///
+/// syntree::assert_tree_eq!(a, b);
+/// # Ok::<_,  Box<dyn std::error::Error>>(())
+/// ```
+#[macro_export]
+macro_rules! assert_tree_eq {
+    ($a:expr, $b:expr $(,)?) => {{
+        match (&$a, &$b) {
+            (a, b) => {
+                if a != b {
+                    $crate::diff::__assert_tree_eq_failed(a, b);
+                }
+            }
+        }
+    }};
+}
+
+/// Assert that a tree matches an expected tree, which is written using the
+/// same syntax as [`tree!`].
+///
+/// If the expected tree spans nothing, which is the case when all of its
+/// tokens are written without a length, spans are ignored and only the
+/// values, kinds and structure of the trees are compared. This allows for
+/// writing tests which don't hardcode offsets.
+///
+/// On mismatch this panics with a rendering of both trees and the value, kind
+/// and span of the first node at which they diverge.
+///
+/// # Examples
+///
+/// ```
+/// let mut tree = syntree::Builder::new();
+///
+/// tree.open("number")?;
+/// tree.token("lit", 3)?;
+/// tree.close()?;
+/// tree.token("ws", 1)?;
+///
+/// let tree = tree.build()?;
+///
+/// syntree::assert_tree!(tree, {
+///     "number" => {
+///         ("lit", 3),
+///     },
+///     ("ws", 1),
+/// });
+///
+/// // Spans are ignored if none are specified.
+/// syntree::assert_tree!(tree, {
+///     "number" => {
+///         "lit",
+///     },
+///     "ws",
+/// });
+/// # Ok::<_,  Box<dyn std::error::Error>>(())
+/// ```
+///
+/// ```should_panic
+/// let tree = syntree::tree! {
+///     "number" => {
+///         ("lit", 3),
+///     }
+/// };
+///
+/// syntree::assert_tree!(tree, {
+///     "number" => {
+///         "ident",
+///     }
+/// });
+/// # Ok::<_,  Box<dyn std::error::Error>>(())
+/// ```
+#[macro_export]
+macro_rules! assert_tree {
+    ($actual:expr, { $($tt:tt)* } $(,)?) => {{
+        let expected = $crate::tree!($($tt)*);
+
+        match (&$actual, &expected) {
+            (actual, expected) => {
+                $crate::diff::__assert_tree(actual, expected);
+            }
+        }
     }};
 }
+
+/// Helper macro for declaring typed wrappers around nodes, which implement
+/// [`TypedNode`].
+///
+/// Each wrapper is declared with the type of the values in the tree, and
+/// either an expression which the value of a node has to be equal to, or a
+/// predicate over the value of a node. The generated wrapper is generic over
+/// the span of the tree, which defaults to [`Span`].
+///
+/// [`TypedNode`]: crate::node::TypedNode
+/// [`Span`]: crate::Span
+///
+/// # Examples
+///
+/// ```
+/// use syntree::node::TypedNode;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Syntax {
+///     FnDef,
+///     Ident,
+///     Number,
+///     Whitespace,
+/// }
+///
+/// syntree::typed_node! {
+///     /// A function definition.
+///     pub struct FnDef(Syntax) where value == Syntax::FnDef;
+///     /// An atom.
+///     pub struct Atom(Syntax) where |value| matches!(value, Syntax::Ident | Syntax::Number);
+/// }
+///
+/// let tree = syntree::tree! {
+///     Syntax::FnDef => {
+///         (Syntax::Ident, 2),
+///         (Syntax::Whitespace, 1),
+///         (Syntax::Number, 1),
+///     }
+/// };
+///
+/// let fn_def = tree.first().and_then(FnDef::cast).ok_or("missing fn")?;
+/// assert_eq!(fn_def.node().children_of_type::<Atom>().count(), 2);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[macro_export]
+macro_rules! typed_node {
+    (@items) => {};
+
+    (@items $(#[$meta:meta])* $vis:vis struct $name:ident($ty:ty) where value == $expr:expr; $($rest:tt)*) => {
+        $crate::typed_node!(@item $(#[$meta])* $vis struct $name($ty), |value| *value == $expr);
+        $crate::typed_node!(@items $($rest)*);
+    };
+
+    (@items $(#[$meta:meta])* $vis:vis struct $name:ident($ty:ty) where |$value:ident| $pred:expr; $($rest:tt)*) => {
+        $crate::typed_node!(@item $(#[$meta])* $vis struct $name($ty), |$value| $pred);
+        $crate::typed_node!(@items $($rest)*);
+    };
+
+    (@item $(#[$meta:meta])* $vis:vis struct $name:ident($ty:ty), |$value:ident| $pred:expr) => {
+        $(#[$meta])*
+        $vis struct $name<'a, S = $crate::Span>($crate::Node<'a, $ty, S>);
+
+        impl<'a, S> $crate::node::TypedNode<'a, $ty, S> for $name<'a, S> {
+            #[inline]
+            fn cast(node: $crate::Node<'a, $ty, S>) -> ::core::option::Option<Self> {
+                let $value: &$ty = node.value();
+
+                if $pred {
+                    ::core::option::Option::Some(Self(node))
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+
+            #[inline]
+            fn node(&self) -> $crate::Node<'a, $ty, S> {
+                self.0
+            }
+        }
+
+        impl<S> ::core::clone::Clone for $name<'_, S> {
+            #[inline]
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<S> ::core::marker::Copy for $name<'_, S> {}
+
+        impl<'a, S> ::core::fmt::Debug for $name<'a, S>
+        where
+            $crate::Node<'a, $ty, S>: ::core::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_tuple(::core::stringify!($name)).field(&self.0).finish()
+            }
+        }
+    };
+    ($($tt:tt)*) => {
+        $crate::typed_node!(@items $($tt)*);
+    };
+}
+
+/// Helper macro for implementing [`KindName`] for an enum from a mapping of
+/// its unit variants to names.
+///
+/// Both [`KindName::kind_name`] and [`KindName::from_kind_name`] are
+/// implemented, so the names can be parsed back using
+/// [`sexpr::from_sexpr_named`].
+///
+/// [`KindName`]: crate::KindName
+/// [`KindName::kind_name`]: crate::KindName::kind_name
+/// [`KindName::from_kind_name`]: crate::KindName::from_kind_name
+/// [`sexpr::from_sexpr_named`]: crate::sexpr::from_sexpr_named
+///
+/// # Examples
+///
+/// ```
+/// use syntree::KindName;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Syntax {
+///     Number,
+///     Whitespace,
+/// }
+///
+/// syntree::kind_names! {
+///     Syntax {
+///         Number => "number",
+///         Whitespace => "ws",
+///     }
+/// }
+///
+/// assert_eq!(Syntax::Whitespace.kind_name(), "ws");
+/// assert_eq!(Syntax::from_kind_name("number"), Some(Syntax::Number));
+/// assert_eq!(Syntax::from_kind_name("Number"), None);
+/// ```
+#[macro_export]
+macro_rules! kind_names {
+    ($ty:ty { $($variant:ident => $name:literal),* $(,)? }) => {
+        impl $crate::KindName for $ty {
+            #[inline]
+            fn kind_name(&self) -> &str {
+                match self {
+                    $(Self::$variant => $name,)*
+                }
+            }
+
+            #[inline]
+            fn from_kind_name(name: &str) -> ::core::option::Option<Self> {
+                match name {
+                    $($name => ::core::option::Option::Some(Self::$variant),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    };
+}
@@ -2,27 +2,70 @@
 
 mod ancestors;
 mod children;
+mod cursor;
+mod descendants_with_value;
+mod fold;
+mod gaps;
+mod identity;
+mod matching;
+mod nodes_with_range;
+mod only_tokens;
+mod reusable_nodes;
 mod siblings;
 mod skip_tokens;
+mod skip_trivia;
+mod tree_cursor;
+mod typed;
+mod visit;
 mod walk;
+mod walk_context;
 mod walk_events;
+mod walk_rev;
+mod walk_with_source;
 
+use alloc::vec::{Drain, Vec};
+use core::cmp::Ordering;
 use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::FusedIterator;
 use core::mem::size_of;
 use core::ops::Range;
 
 use crate::builder::Id;
-use crate::links::Links;
+use crate::debug;
+use crate::hash;
+use crate::links::{Links, MARK_ERROR, MARK_TRIVIA};
 use crate::non_max::NonMax;
-use crate::span::Span;
+#[cfg(feature = "rayon")]
+use crate::par::ParChildren;
+use crate::shape::{self, ShapeResult, ShapeRules};
+use crate::span::{Span, TreeSpan};
 use crate::tree::Kind;
 
 pub use self::ancestors::Ancestors;
 pub use self::children::Children;
+pub use self::cursor::{ChildrenCursor, CursorError, Expected};
+pub use self::descendants_with_value::DescendantsWithValue;
+pub use self::gaps::Gaps;
+pub use self::identity::Identity;
+pub use self::matching::Matching;
+pub use self::nodes_with_range::NodesWithRange;
+pub use self::only_tokens::OnlyTokens;
+pub use self::reusable_nodes::ReusableNodes;
 pub use self::siblings::Siblings;
 pub use self::skip_tokens::SkipTokens;
-pub use self::walk::{Walk, WithDepths};
+pub use self::skip_trivia::SkipTrivia;
+pub use self::tree_cursor::Cursor;
+pub use self::typed::{OfType, TypedNode};
+pub use self::visit::{VisitControl, Visitor};
+
+pub(crate) use self::fold::fold_subtree;
+pub(crate) use self::visit::visit_subtree;
+pub use self::walk::{Walk, WithDepths, WithParents, WithPositions};
+pub use self::walk_context::{ContextAncestors, WalkContext, WithContext};
 pub use self::walk_events::{Event, WalkEvents};
+pub use self::walk_rev::{WalkRev, WalkRevWithDepths};
+pub use self::walk_with_source::WalkWithSource;
 
 /// A node in the tree.
 pub struct Node<'a, T, S = Span> {
@@ -35,6 +78,11 @@ impl<'a, T, S> Node<'a, T, S> {
         Self { links, tree }
     }
 
+    /// Access the links of the tree this node belongs to.
+    pub(crate) const fn storage(&self) -> &'a [Links<T, S>] {
+        self.tree
+    }
+
     /// Get the identifier of the current node.
     ///
     /// This can be used to register a change in a [`ChangeSet`] later.
@@ -98,6 +146,107 @@ impl<'a, T, S> Node<'a, T, S> {
         &self.links.data
     }
 
+    /// Access the user flags of the node, as set through
+    /// [`Builder::set_flags`][crate::Builder::set_flags].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// let root = tree.open("root")?;
+    /// tree.set_flags(root, 0b101)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(root.flags(), 0b101);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub const fn flags(&self) -> u8 {
+        self.links.flags
+    }
+
+    /// Access the marks of the node, such as whether it is an error or
+    /// trivia.
+    pub(crate) const fn marks(&self) -> u8 {
+        self.links.marks
+    }
+
+    /// Test if the node is marked as an error, meaning that it only exists
+    /// because of error recovery.
+    ///
+    /// Nodes are marked through [`Builder::mark_error`], and nodes and tokens
+    /// added with [`Builder::open_error`], [`Builder::error_token`] or as the
+    /// error token of [`Builder::open_recover`] are marked automatically.
+    /// Marked nodes otherwise behave like any other node of the same
+    /// [`Kind`], so for example [`SkipTokens`] skips tokens which are marked
+    /// as errors.
+    ///
+    /// [`Builder::mark_error`]: crate::Builder::mark_error
+    /// [`Builder::open_error`]: crate::Builder::open_error
+    /// [`Builder::error_token`]: crate::Builder::error_token
+    /// [`Builder::open_recover`]: crate::Builder::open_recover
+    /// [`SkipTokens`]: crate::node::SkipTokens
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.token("lit", 1)?;
+    /// tree.error_token("garbage", 2)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert!(!root.is_error());
+    ///
+    /// let errors = root.children().filter(|n| n.is_error()).map(|n| *n.value());
+    /// assert_eq!(errors.collect::<Vec<_>>(), ["garbage"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub const fn is_error(&self) -> bool {
+        self.links.marks & MARK_ERROR != 0
+    }
+
+    /// Test if this is a trivia token, such as whitespace or a comment.
+    ///
+    /// Tokens are declared as trivia through [`Builder::token_trivia`], and
+    /// can be filtered out of iterators such as [`Children`] through
+    /// [`Children::skip_trivia`]. Nodes are never trivia.
+    ///
+    /// [`Builder::token_trivia`]: crate::Builder::token_trivia
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.token("lit", 1)?;
+    /// tree.token_trivia("ws", 1)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    /// let root = tree.first().ok_or("missing root")?;
+    ///
+    /// assert!(!root.is_trivia());
+    ///
+    /// let trivia = root.children().filter(|n| n.is_trivia()).map(|n| *n.value());
+    /// assert!(trivia.eq(["ws"]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn is_trivia(&self) -> bool {
+        self.links.marks & MARK_TRIVIA != 0
+    }
+
     /// Access the kind of the node.
     ///
     /// Terminating nodes are [`Kind::Token`] and intermediary nodes are
@@ -156,10 +305,46 @@ impl<'a, T, S> Node<'a, T, S> {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
     #[must_use]
-    pub const fn span(&self) -> &S {
+    pub const fn span(&self) -> &'a S {
         &self.links.span
     }
 
+    /// Check if the current node is the first among its siblings, in that it
+    /// has no previous sibling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("a", 1),
+    ///         ("b", 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let a = root.first().ok_or("missing a")?;
+    /// let b = root.last().ok_or("missing b")?;
+    ///
+    /// assert!(root.is_first() && root.is_last());
+    /// assert!(a.is_first() && !a.is_last());
+    /// assert!(!b.is_first() && b.is_last());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub const fn is_first(&self) -> bool {
+        self.links.prev.is_none()
+    }
+
+    /// Check if the current node is the last among its siblings, in that it
+    /// has no next sibling.
+    ///
+    /// See [`Node::is_first`] for an example.
+    #[must_use]
+    pub const fn is_last(&self) -> bool {
+        self.links.next.is_none()
+    }
+
     /// Check if the current node is empty. In that it doesn't have any
     /// children.
     ///
@@ -193,6 +378,45 @@ impl<'a, T, S> Node<'a, T, S> {
         Ancestors::new(Some(*self))
     }
 
+    /// Get the chain of nested nodes in the subtree of this node which all
+    /// cover exactly `range`, from the outermost to the innermost, including
+    /// this node if it matches.
+    ///
+    /// See [NodesWithRange] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "paren" => {
+    ///         "expr" => {
+    ///             "lit" => {
+    ///                 ("number", 2),
+    ///             }
+    ///         },
+    ///     }
+    /// };
+    ///
+    /// let paren = tree.first().ok_or("missing paren")?;
+    /// let expr = paren.first().ok_or("missing expr")?;
+    ///
+    /// let nodes = paren.nodes_with_range(0..2).map(|n| *n.value()).collect::<Vec<_>>();
+    /// assert_eq!(nodes, ["paren", "expr", "lit", "number"]);
+    ///
+    /// let nodes = expr.nodes_with_range(0..2).map(|n| *n.value()).collect::<Vec<_>>();
+    /// assert_eq!(nodes, ["expr", "lit", "number"]);
+    ///
+    /// assert_eq!(expr.nodes_with_range(0..1).count(), 0);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn nodes_with_range(&self, range: Range<usize>) -> NodesWithRange<'a, T, S>
+    where
+        S: TreeSpan,
+    {
+        NodesWithRange::from_node(*self, range)
+    }
+
     /// Get an iterator over the siblings of this node, including itself.
     ///
     /// See [Siblings] for documentation.
@@ -206,193 +430,1083 @@ impl<'a, T, S> Node<'a, T, S> {
     /// See [Children] for documentation.
     #[must_use]
     pub fn children(&self) -> Children<'a, T, S> {
-        Children::new(self.tree, self.links.first, self.links.last)
+        Children::new(self.tree, self.links.first, self.links.last, self.links.len)
     }
 
-    /// Walk the subtree forward starting with the first child of the current
-    /// node.
+    /// Get a parallel iterator over the children of this node, in no
+    /// particular order.
     ///
-    /// See [Walk] for documentation.
+    /// Children are split into work items by halving the list of children,
+    /// which takes time linear in the number of children being split.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rayon::prelude::*;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "fn" => { ("lit", 1) },
+    ///         "fn" => { ("lit", 1), ("lit", 1) },
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let count = root.par_children().map(|n| n.walk().count()).sum::<usize>();
+    /// assert_eq!(count, 3);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
     #[must_use]
-    pub fn walk(&self) -> Walk<'a, T, S> {
-        Walk::new(self.tree, self.links.first)
+    pub fn par_children(&self) -> ParChildren<'a, T, S>
+    where
+        T: Sync,
+        S: Sync,
+    {
+        ParChildren::new(self.children())
     }
 
-    /// Walk the node forwards in a depth-first fashion emitting events
-    /// indicating how the rest of the tree is being traversed.
+    /// Get a cursor over the children of this node which supports peeking.
     ///
-    /// See [`WalkEvents`] for documentation.
+    /// See [ChildrenCursor] for documentation.
     #[must_use]
-    pub fn walk_events(&self) -> WalkEvents<'a, T, S> {
-        WalkEvents::new(self.tree, self.links.first)
+    pub fn children_cursor(&self) -> ChildrenCursor<'a, T, S> {
+        ChildrenCursor::new(self.id(), self.children())
     }
 
-    /// Get immediate parent to this node.
+    /// Get a cursor for navigating the subtree of this node, starting at
+    /// this node.
+    ///
+    /// See [Cursor] for documentation.
+    #[must_use]
+    pub fn cursor(&self) -> Cursor<'a, T, S> {
+        Cursor::new(*self, None)
+    }
+
+    /// Get an iterator over the children of this node whose value is equal
+    /// to `value`.
+    ///
+    /// This is a shorthand for using [`Children::matching`].
     ///
     /// # Examples
     ///
     /// ```
     /// let tree = syntree::tree! {
-    ///     "root" => {
-    ///         "number" => {
-    ///             ("lit", 5)
-    ///         },
-    ///         "ident" => {
-    ///             ("lit", 3)
-    ///         }
-    ///     },
-    ///     "root2" => {
-    ///         ("whitespace", 5)
+    ///     "fn" => {
+    ///         "param" => {},
+    ///         ("comma", 1),
+    ///         "param" => {},
     ///     }
     /// };
     ///
     /// let root = tree.first().ok_or("missing root")?;
-    /// assert_eq!(*root.value(), "root");
-    /// assert!(root.parent().is_none());
-    ///
-    /// let number = root.first().ok_or("missing number")?;
-    /// assert_eq!(*number.value(), "number");
-    ///
-    /// let root = number.parent().ok_or("missing parent")?;
-    /// assert_eq!(*root.value(), "root");
+    /// assert_eq!(root.children_with_value(&"param").count(), 2);
+    /// assert_eq!(root.children_with_value(&"body").count(), 0);
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
-    #[must_use]
-    pub fn parent(&self) -> Option<Node<'a, T, S>> {
-        self.node_at(self.links.parent?)
+    pub fn children_with_value<'v>(
+        &self,
+        value: &'v T,
+    ) -> impl DoubleEndedIterator<Item = Node<'a, T, S>> + FusedIterator + Clone + 'v
+    where
+        'a: 'v,
+        T: PartialEq,
+    {
+        self.children().matching(move |v| v == value)
     }
 
-    /// Get the previous sibling.
+    /// Get an iterator over the children of this node which can be cast to
+    /// the typed node `N`.
+    ///
+    /// See [`TypedNode`] for documentation.
     ///
     /// # Examples
     ///
     /// ```
+    /// syntree::typed_node! {
+    ///     struct Number(&'static str) where value == "number";
+    /// }
+    ///
     /// let tree = syntree::tree! {
     ///     "root" => {
-    ///         "number" => {
-    ///             ("lit", 5)
-    ///         },
-    ///         "ident" => {
-    ///             ("lit", 3)
-    ///         }
+    ///         "number" => { ("lit", 1) },
+    ///         ("ws", 1),
+    ///         "number" => { ("lit", 2) },
     ///     }
     /// };
     ///
-    /// let number = tree.first().and_then(|n| n.first()).ok_or("missing number")?;
-    /// assert_eq!(*number.value(), "number");
-    /// assert!(number.prev().is_none());
-    ///
-    /// let ident = number.next().ok_or("missing ident")?;
-    /// assert_eq!(*ident.value(), "ident");
-    ///
-    /// let number = ident.prev().ok_or("missing number")?;
-    /// assert_eq!(*number.value(), "number");
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(root.children_of_type::<Number>().count(), 2);
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
     #[must_use]
-    pub fn prev(self) -> Option<Node<'a, T, S>> {
-        self.node_at(self.links.prev?)
+    pub fn children_of_type<N>(&self) -> OfType<Children<'a, T, S>, N>
+    where
+        N: TypedNode<'a, T, S>,
+    {
+        self.children().of_type()
     }
 
-    /// Get the next sibling.
+    /// Get the first child of this node which can be cast to the typed node
+    /// `N`.
+    ///
+    /// See [`TypedNode`] for documentation.
     ///
     /// # Examples
     ///
     /// ```
+    /// use syntree::node::TypedNode;
+    ///
+    /// syntree::typed_node! {
+    ///     struct Ident(&'static str) where value == "ident";
+    /// }
+    ///
     /// let tree = syntree::tree! {
     ///     "root" => {
-    ///         "number" => {
-    ///             ("lit", 5)
-    ///         },
-    ///         "ident" => {
-    ///             ("lit", 3)
-    ///         }
+    ///         ("number", 1),
+    ///         ("ident", 2),
     ///     }
     /// };
     ///
     /// let root = tree.first().ok_or("missing root")?;
-    /// assert_eq!(*root.value(), "root");
-    ///
-    /// let number = root.first().ok_or("missing second root")?;
-    /// assert_eq!(*number.value(), "number");
-    ///
-    /// let ident = number.next().ok_or("missing second root")?;
-    /// assert_eq!(*ident.value(), "ident");
+    /// let ident = root.first_of_type::<Ident>().ok_or("missing ident")?;
+    /// assert_eq!(ident.node().span().range(), 1..3);
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
     #[must_use]
-    pub fn next(self) -> Option<Node<'a, T, S>> {
-        self.node_at(self.links.next?)
+    pub fn first_of_type<N>(&self) -> Option<N>
+    where
+        N: TypedNode<'a, T, S>,
+    {
+        self.children().find_map(N::cast)
     }
 
-    /// Get the first child node.
+    /// Cast this node into the typed node `N`.
+    ///
+    /// This is the same as [`TypedNode::cast`].
+    #[must_use]
+    pub fn cast<N>(self) -> Option<N>
+    where
+        N: TypedNode<'a, T, S>,
+    {
+        N::cast(self)
+    }
+
+    /// Walk the subtree forward starting with the first child of the current
+    /// node.
+    ///
+    /// See [Walk] for documentation.
+    #[must_use]
+    pub fn walk(&self) -> Walk<'a, T, S> {
+        Walk::new(
+            self.tree,
+            self.links.first,
+            self.tree.len().saturating_sub(1),
+            false,
+        )
+    }
+
+    /// Walk the subtree forward like [`Node::walk`], pairing every token with
+    /// the text it covers in `source`.
+    ///
+    /// See [`WalkWithSource`] for documentation.
     ///
     /// # Examples
     ///
     /// ```
     /// let tree = syntree::tree! {
-    ///     "root" => {
-    ///         "number" => {
-    ///             ("lit", 5)
+    ///     "call" => {
+    ///         ("ident", 3),
+    ///         "args" => {
+    ///             ("open", 1),
+    ///             ("close", 1),
     ///         },
-    ///         "ident" => {
-    ///             ("lit", 3)
-    ///         }
     ///     },
-    ///     "root2" => {
-    ///         ("whitespace", 5)
-    ///     }
     /// };
     ///
-    /// let root = tree.first().ok_or("missing root")?;
-    /// assert_eq!(*root.value(), "root");
+    /// let call = tree.first().ok_or("missing call")?;
     ///
-    /// let number = root.first().ok_or("missing number")?;
-    /// assert_eq!(*number.value(), "number");
+    /// let text = call
+    ///     .walk_with_source("foo()")
+    ///     .filter_map(|(_, text)| text)
+    ///     .collect::<String>();
+    ///
+    /// assert_eq!(text, "foo()");
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
     #[must_use]
-    pub fn first(&self) -> Option<Node<'a, T, S>> {
-        self.node_at(self.links.first?)
+    pub fn walk_with_source<'s>(&self, source: &'s str) -> WalkWithSource<'a, 's, T, S>
+    where
+        S: TreeSpan,
+    {
+        WalkWithSource::new(self.walk(), source)
     }
 
-    /// Get the last child node.
+    /// Iterate over the descendants of this node whose value is equal to
+    /// `value`, in the order they are visited by [`Node::walk`]. The node
+    /// itself is not included.
+    ///
+    /// See [`DescendantsWithValue`] for documentation.
     ///
     /// # Examples
     ///
     /// ```
     /// let tree = syntree::tree! {
-    ///     "root" => {
-    ///         "number" => {
-    ///             ("lit", 5)
+    ///     "fn" => {
+    ///         ("ident", 1),
+    ///         "block" => {
+    ///             "fn" => {
+    ///                 ("ident", 2),
+    ///             },
     ///         },
-    ///         "ident" => {
-    ///             ("lit", 3)
-    ///         }
     ///     },
-    ///     "root2" => {
-    ///         ("whitespace", 5)
-    ///     }
     /// };
     ///
-    /// let root2 = tree.last().ok_or("missing root2")?;
-    /// assert_eq!(*root2.value(), "root2");
+    /// let root = tree.first().ok_or("missing root")?;
     ///
-    /// let whitespace = root2.last().ok_or("missing whitespace")?;
-    /// assert_eq!(*whitespace.value(), "whitespace");
+    /// let ranges = root.descendants_with_value(&"fn").map(|n| n.range());
+    /// assert_eq!(ranges.collect::<Vec<_>>(), [1..3]);
+    ///
+    /// let ranges = root.descendants_with_value(&"ident").map(|n| n.range());
+    /// assert_eq!(ranges.collect::<Vec<_>>(), [0..1, 1..3]);
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
     #[must_use]
-    pub fn last(&self) -> Option<Node<'a, T, S>> {
-        self.node_at(self.links.last?)
+    pub fn descendants_with_value(&self, value: &'a T) -> DescendantsWithValue<'a, T, S>
+    where
+        T: PartialEq,
+    {
+        DescendantsWithValue::new(self.walk(), value)
     }
 
-    /// Find a preceeding node which matches the given predicate.
+    /// Walk the subtree backwards starting with the last descendant of the
+    /// current node, in the exact reverse order of [`Node::walk`].
     ///
-    /// A "preceeding node" is one which constitutes tokens the immediately
-    /// preceedes the ones of the current node, so this function scans first the
-    /// parents of the current node for a matching [`Node::prev`] sibling, and
-    /// then traverses that matches [`Node::last`].
+    /// See [`WalkRev`] for documentation.
+    #[must_use]
+    pub fn walk_rev(&self) -> WalkRev<'a, T, S> {
+        WalkRev::new(self.tree, self.links.last)
+    }
+
+    /// Walk the node forwards in a depth-first fashion emitting events
+    /// indicating how the rest of the tree is being traversed.
+    ///
+    /// See [`WalkEvents`] for documentation.
+    #[must_use]
+    pub fn walk_events(&self) -> WalkEvents<'a, T, S> {
+        WalkEvents::new(self.tree, self.links.first)
+    }
+
+    /// Visit the subtree rooted at this node with the given `visitor`.
+    ///
+    /// Unlike [`Node::walk`], this includes the current node. See [`Visitor`]
+    /// for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Node, Span, Visitor};
+    ///
+    /// struct Tokens(Vec<&'static str>);
+    ///
+    /// impl<'a> Visitor<'a, &'static str, Span> for Tokens {
+    ///     fn token(&mut self, node: Node<'a, &'static str, Span>) {
+    ///         self.0.push(*node.value());
+    ///     }
+    /// }
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 1)
+    ///         },
+    ///         ("ws", 1)
+    ///     },
+    ///     ("eof", 0)
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    ///
+    /// let mut tokens = Tokens(Vec::new());
+    /// root.visit(&mut tokens);
+    /// assert_eq!(tokens.0, ["lit", "ws"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn visit<V>(&self, visitor: &mut V)
+    where
+        V: ?Sized + Visitor<'a, T, S>,
+    {
+        visit_subtree(*self, visitor);
+    }
+
+    /// Reduce the subtree rooted at this node bottom-up.
+    ///
+    /// The closure `f` is called once for every node in the subtree, after it
+    /// has been called for all of the node's children. It receives the node
+    /// together with the results computed for its children, in order. Tokens
+    /// and nodes without children receive no results.
+    ///
+    /// Results are drained from a single buffer shared by the whole fold, and
+    /// the fold is not recursive, so arbitrarily deep trees can be folded
+    /// without risking a stack overflow.
+    ///
+    /// # Examples
+    ///
+    /// Evaluating a tiny arithmetic tree:
+    ///
+    /// ```
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    /// enum Syntax {
+    ///     Add,
+    ///     Mul,
+    ///     Number,
+    ///     Operator,
+    /// }
+    ///
+    /// use Syntax::*;
+    ///
+    /// let source = "2*3+4";
+    ///
+    /// let tree = syntree::tree! {
+    ///     Add => {
+    ///         Mul => {
+    ///             (Number, 1),
+    ///             (Operator, 1),
+    ///             (Number, 1),
+    ///         },
+    ///         (Operator, 1),
+    ///         (Number, 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    ///
+    /// let value = root.fold(|node, children| match node.value() {
+    ///     Add => Some(children.flatten().sum()),
+    ///     Mul => Some(children.flatten().product()),
+    ///     Number => source[node.range()].parse::<u32>().ok(),
+    ///     Operator => None,
+    /// });
+    ///
+    /// assert_eq!(value, Some(10));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn fold<R, F>(&self, mut f: F) -> R
+    where
+        F: FnMut(Node<'a, T, S>, Drain<'_, R>) -> R,
+    {
+        fold_subtree(*self, &mut f, &mut Vec::new(), &mut Vec::new())
+    }
+
+    /// Get immediate parent to this node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 5)
+    ///         },
+    ///         "ident" => {
+    ///             ("lit", 3)
+    ///         }
+    ///     },
+    ///     "root2" => {
+    ///         ("whitespace", 5)
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(*root.value(), "root");
+    /// assert!(root.parent().is_none());
+    ///
+    /// let number = root.first().ok_or("missing number")?;
+    /// assert_eq!(*number.value(), "number");
+    ///
+    /// let root = number.parent().ok_or("missing parent")?;
+    /// assert_eq!(*root.value(), "root");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn parent(&self) -> Option<Node<'a, T, S>> {
+        self.node_at(self.links.parent?)
+    }
+
+    /// Get the parent node if it matches the given predicate.
+    ///
+    /// Nodes at the top level of a tree have no parent, so this always
+    /// returns `None` for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "call" => {
+    ///         "args" => {
+    ///             ("lit", 1),
+    ///         },
+    ///     }
+    /// };
+    ///
+    /// let lit = tree.first().and_then(|n| n.first()).and_then(|n| n.first()).ok_or("missing lit")?;
+    /// assert!(lit.parent_with(|n| *n.value() == "args").is_some());
+    /// assert!(lit.parent_with(|n| *n.value() == "call").is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn parent_with<P>(&self, predicate: P) -> Option<Node<'a, T, S>>
+    where
+        P: FnOnce(Node<'a, T, S>) -> bool,
+    {
+        self.parent().filter(|n| predicate(*n))
+    }
+
+    /// Find the nearest ancestor which matches the given predicate, excluding
+    /// the node itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "fn" => {
+    ///         "block" => {
+    ///             "block" => {
+    ///                 ("lit", 1),
+    ///             },
+    ///         },
+    ///     }
+    /// };
+    ///
+    /// let inner = tree.walk().nth(2).ok_or("missing inner block")?;
+    ///
+    /// let block = inner.ancestor_with(|n| *n.value() == "block").ok_or("missing block")?;
+    /// assert_eq!(block.id(), inner.parent().ok_or("missing parent")?.id());
+    /// assert!(inner.ancestor_with(|n| *n.value() == "struct").is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn ancestor_with<P>(&self, mut predicate: P) -> Option<Node<'a, T, S>>
+    where
+        P: FnMut(Node<'a, T, S>) -> bool,
+    {
+        let mut current = self.parent();
+
+        while let Some(node) = current {
+            if predicate(node) {
+                return Some(node);
+            }
+
+            current = node.parent();
+        }
+
+        None
+    }
+
+    /// Test if the parent node has the given `value`.
+    ///
+    /// Nodes at the top level of a tree have no parent, so this always
+    /// returns `false` for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "args" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let args = tree.first().ok_or("missing args")?;
+    /// let lit = args.first().ok_or("missing lit")?;
+    ///
+    /// assert!(lit.parent_is(&"args"));
+    /// assert!(!args.parent_is(&"args"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn parent_is(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.parent_with(|n| n.value() == value).is_some()
+    }
+
+    /// Find the nearest ancestor with the given `value`, excluding the node
+    /// itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "block" => {
+    ///         "block" => {
+    ///             ("lit", 1),
+    ///         },
+    ///     }
+    /// };
+    ///
+    /// let outer = tree.first().ok_or("missing outer block")?;
+    /// let inner = outer.first().ok_or("missing inner block")?;
+    ///
+    /// assert_eq!(inner.nearest_ancestor(&"block").map(|n| n.id()), Some(outer.id()));
+    /// assert!(outer.nearest_ancestor(&"block").is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn nearest_ancestor(&self, value: &T) -> Option<Node<'a, T, S>>
+    where
+        T: PartialEq,
+    {
+        self.ancestor_with(|n| n.value() == value)
+    }
+
+    /// Get the previous sibling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 5)
+    ///         },
+    ///         "ident" => {
+    ///             ("lit", 3)
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let number = tree.first().and_then(|n| n.first()).ok_or("missing number")?;
+    /// assert_eq!(*number.value(), "number");
+    /// assert!(number.prev().is_none());
+    ///
+    /// let ident = number.next().ok_or("missing ident")?;
+    /// assert_eq!(*ident.value(), "ident");
+    ///
+    /// let number = ident.prev().ok_or("missing number")?;
+    /// assert_eq!(*number.value(), "number");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn prev(self) -> Option<Node<'a, T, S>> {
+        self.node_at(self.links.prev?)
+    }
+
+    /// Get the next sibling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 5)
+    ///         },
+    ///         "ident" => {
+    ///             ("lit", 3)
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(*root.value(), "root");
+    ///
+    /// let number = root.first().ok_or("missing second root")?;
+    /// assert_eq!(*number.value(), "number");
+    ///
+    /// let ident = number.next().ok_or("missing second root")?;
+    /// assert_eq!(*ident.value(), "ident");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn next(self) -> Option<Node<'a, T, S>> {
+        self.node_at(self.links.next?)
+    }
+
+    /// Get the next sibling which is a [`Kind::Node`], skipping over any
+    /// tokens in between.
+    ///
+    /// The node itself is never returned, even if it is a node. This differs
+    /// from [`Siblings::next_node`], since [`Node::siblings`] starts with the
+    /// node it was constructed from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "a" => {},
+    ///         ("ws", 1),
+    ///         "b" => {},
+    ///         ("comma", 1),
+    ///         ("ws", 1),
+    ///     }
+    /// };
+    ///
+    /// let a = tree.first().and_then(|n| n.first()).ok_or("missing a")?;
+    ///
+    /// let b = a.next_node_sibling().ok_or("missing b")?;
+    /// assert_eq!(*b.value(), "b");
+    /// assert!(b.next_node_sibling().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn next_node_sibling(self) -> Option<Node<'a, T, S>> {
+        self.next_with_kind(Kind::Node)
+    }
+
+    /// Get the previous sibling which is a [`Kind::Node`], skipping over any
+    /// tokens in between.
+    ///
+    /// The node itself is never returned, even if it is a node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "a" => {},
+    ///         ("ws", 1),
+    ///         "b" => {},
+    ///         ("comma", 1),
+    ///         ("ws", 1),
+    ///     }
+    /// };
+    ///
+    /// let ws = tree.first().and_then(|n| n.last()).ok_or("missing ws")?;
+    ///
+    /// let b = ws.prev_node_sibling().ok_or("missing b")?;
+    /// assert_eq!(*b.value(), "b");
+    ///
+    /// let a = b.prev_node_sibling().ok_or("missing a")?;
+    /// assert_eq!(*a.value(), "a");
+    /// assert!(a.prev_node_sibling().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn prev_node_sibling(self) -> Option<Node<'a, T, S>> {
+        self.prev_with_kind(Kind::Node)
+    }
+
+    /// Get the next sibling which is a [`Kind::Token`], skipping over any
+    /// nodes in between.
+    ///
+    /// The node itself is never returned, even if it is a token. This differs
+    /// from [`Siblings::next_token`], since [`Node::siblings`] starts with the
+    /// node it was constructed from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "a" => {},
+    ///         ("ws", 1),
+    ///         "b" => {},
+    ///         ("comma", 1),
+    ///         ("ws", 1),
+    ///     }
+    /// };
+    ///
+    /// let a = tree.first().and_then(|n| n.first()).ok_or("missing a")?;
+    ///
+    /// let ws = a.next_token_sibling().ok_or("missing ws")?;
+    /// assert_eq!(ws.range(), 0..1);
+    ///
+    /// let comma = ws.next_token_sibling().ok_or("missing comma")?;
+    /// assert_eq!(*comma.value(), "comma");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn next_token_sibling(self) -> Option<Node<'a, T, S>> {
+        self.next_with_kind(Kind::Token)
+    }
+
+    /// Get the previous sibling which is a [`Kind::Token`], skipping over any
+    /// nodes in between.
+    ///
+    /// The node itself is never returned, even if it is a token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "a" => {},
+    ///         ("ws", 1),
+    ///         "b" => {},
+    ///         ("comma", 1),
+    ///         ("ws", 1),
+    ///     }
+    /// };
+    ///
+    /// let comma = tree.walk().find(|n| *n.value() == "comma").ok_or("missing comma")?;
+    ///
+    /// let ws = comma.prev_token_sibling().ok_or("missing ws")?;
+    /// assert_eq!(ws.range(), 0..1);
+    /// assert!(ws.prev_token_sibling().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn prev_token_sibling(self) -> Option<Node<'a, T, S>> {
+        self.prev_with_kind(Kind::Token)
+    }
+
+    /// Follow `next` links until a sibling of the given `kind` is found.
+    fn next_with_kind(self, kind: Kind) -> Option<Node<'a, T, S>> {
+        let mut node = self.next()?;
+
+        while node.kind() != kind {
+            node = node.next()?;
+        }
+
+        Some(node)
+    }
+
+    /// Follow `prev` links until a sibling of the given `kind` is found.
+    fn prev_with_kind(self, kind: Kind) -> Option<Node<'a, T, S>> {
+        let mut node = self.prev()?;
+
+        while node.kind() != kind {
+            node = node.prev()?;
+        }
+
+        Some(node)
+    }
+
+    /// Get the first child node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 5)
+    ///         },
+    ///         "ident" => {
+    ///             ("lit", 3)
+    ///         }
+    ///     },
+    ///     "root2" => {
+    ///         ("whitespace", 5)
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(*root.value(), "root");
+    ///
+    /// let number = root.first().ok_or("missing number")?;
+    /// assert_eq!(*number.value(), "number");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn first(&self) -> Option<Node<'a, T, S>> {
+        self.node_at(self.links.first?)
+    }
+
+    /// Get the last child node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 5)
+    ///         },
+    ///         "ident" => {
+    ///             ("lit", 3)
+    ///         }
+    ///     },
+    ///     "root2" => {
+    ///         ("whitespace", 5)
+    ///     }
+    /// };
+    ///
+    /// let root2 = tree.last().ok_or("missing root2")?;
+    /// assert_eq!(*root2.value(), "root2");
+    ///
+    /// let whitespace = root2.last().ok_or("missing whitespace")?;
+    /// assert_eq!(*whitespace.value(), "whitespace");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn last(&self) -> Option<Node<'a, T, S>> {
+        self.node_at(self.links.last?)
+    }
+
+    /// Get the first child which is a [`Kind::Node`], skipping over any
+    /// leading tokens.
+    ///
+    /// This only visits the children up until the first node, unlike
+    /// searching through [`Children::skip_tokens`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "call" => {
+    ///         ("ws", 1),
+    ///         ("open", 1),
+    ///         "arg" => {},
+    ///         ("comma", 1),
+    ///         "arg" => {},
+    ///         ("close", 1),
+    ///     }
+    /// };
+    ///
+    /// let call = tree.first().ok_or("missing call")?;
+    ///
+    /// let arg = call.first_node_child().ok_or("missing arg")?;
+    /// assert_eq!(*arg.value(), "arg");
+    /// assert_eq!(arg.range(), 2..2);
+    /// assert!(arg.first_node_child().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn first_node_child(&self) -> Option<Node<'a, T, S>> {
+        self.first_with_kind(Kind::Node)
+    }
+
+    /// Get the last child which is a [`Kind::Node`], skipping over any
+    /// trailing tokens.
+    ///
+    /// This only visits the children from the end back to the last node,
+    /// unlike searching through [`Children::skip_tokens`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "call" => {
+    ///         ("open", 1),
+    ///         "arg" => {},
+    ///         ("comma", 1),
+    ///         "arg" => {},
+    ///         ("close", 1),
+    ///     }
+    /// };
+    ///
+    /// let call = tree.first().ok_or("missing call")?;
+    ///
+    /// let arg = call.last_node_child().ok_or("missing arg")?;
+    /// assert_eq!(arg.range(), 2..2);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn last_node_child(&self) -> Option<Node<'a, T, S>> {
+        self.last_with_kind(Kind::Node)
+    }
+
+    /// Get the first child which is a [`Kind::Token`], skipping over any
+    /// leading nodes.
+    ///
+    /// Unlike [`Node::first_token`] this doesn't look inside of the children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "call" => {
+    ///         "callee" => {
+    ///             ("ident", 3),
+    ///         },
+    ///         ("open", 1),
+    ///         ("close", 1),
+    ///     }
+    /// };
+    ///
+    /// let call = tree.first().ok_or("missing call")?;
+    ///
+    /// let open = call.first_token_child().ok_or("missing open")?;
+    /// assert_eq!(*open.value(), "open");
+    ///
+    /// let ident = call.first_token().ok_or("missing ident")?;
+    /// assert_eq!(*ident.value(), "ident");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn first_token_child(&self) -> Option<Node<'a, T, S>> {
+        self.first_with_kind(Kind::Token)
+    }
+
+    /// Get the last child which is a [`Kind::Token`], skipping over any
+    /// trailing nodes.
+    ///
+    /// Unlike [`Node::last_token`] this doesn't look inside of the children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "block" => {
+    ///         ("open", 1),
+    ///         "stmt" => {
+    ///             ("ident", 3),
+    ///         },
+    ///     }
+    /// };
+    ///
+    /// let block = tree.first().ok_or("missing block")?;
+    ///
+    /// let open = block.last_token_child().ok_or("missing open")?;
+    /// assert_eq!(*open.value(), "open");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn last_token_child(&self) -> Option<Node<'a, T, S>> {
+        self.last_with_kind(Kind::Token)
+    }
+
+    /// Get the first child of the given `kind`.
+    fn first_with_kind(&self, kind: Kind) -> Option<Node<'a, T, S>> {
+        let first = self.first()?;
+
+        if first.kind() == kind {
+            return Some(first);
+        }
+
+        first.next_with_kind(kind)
+    }
+
+    /// Get the last child of the given `kind`.
+    fn last_with_kind(&self, kind: Kind) -> Option<Node<'a, T, S>> {
+        let last = self.last()?;
+
+        if last.kind() == kind {
+            return Some(last);
+        }
+
+        last.prev_with_kind(kind)
+    }
+
+    /// Get the first token in the subtree of this node, which is the node
+    /// itself if it is a token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "empty" => {},
+    ///         "number" => {
+    ///             ("lit", 5)
+    ///         },
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let token = root.first_token().ok_or("missing token")?;
+    /// assert_eq!(*token.value(), "lit");
+    ///
+    /// let empty = root.first().ok_or("missing empty")?;
+    /// assert!(empty.first_token().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn first_token(&self) -> Option<Node<'a, T, S>> {
+        if self.links.kind == Kind::Token {
+            return Some(*self);
+        }
+
+        self.walk().find(|n| n.kind() == Kind::Token)
+    }
+
+    /// Get the last token in the subtree of this node, which is the node
+    /// itself if it is a token.
+    ///
+    /// This walks the subtree backwards, so it only has to visit the nodes
+    /// along the trailing edge of the subtree unless it ends in empty nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 5)
+    ///         },
+    ///         "ident" => {
+    ///             ("lit", 3),
+    ///             "empty" => {}
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let token = root.last_token().ok_or("missing token")?;
+    /// assert_eq!(token.span(), &syntree::Span::new(5, 8));
+    ///
+    /// let empty = root.last().and_then(|n| n.last()).ok_or("missing empty")?;
+    /// assert_eq!(*empty.value(), "empty");
+    /// assert!(empty.last_token().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn last_token(&self) -> Option<Node<'a, T, S>> {
+        if self.links.kind == Kind::Token {
+            return Some(*self);
+        }
+
+        self.walk_rev().find(|n| n.kind() == Kind::Token)
+    }
+
+    /// Iterate over the ranges within the span of this node which are not
+    /// covered by any of its tokens, in order.
+    ///
+    /// Since the span of a node starts at its first token and ends with its
+    /// last, these are always between tokens. A token has no gaps. See
+    /// [`Gaps`] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Span};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// tree.open("list")?;
+    /// tree.token("number", 1)?;
+    /// tree.advance(2)?;
+    /// tree.open("pair")?;
+    /// tree.token("number", 1)?;
+    /// tree.token("eof", 0)?;
+    /// tree.advance(1)?;
+    /// tree.token("number", 1)?;
+    /// tree.close()?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let list = tree.first().ok_or("missing list")?;
+    /// assert!(list.gaps().eq([Span::new(1, 3), Span::new(4, 5)]));
+    ///
+    /// let number = list.first().ok_or("missing number")?;
+    /// assert!(number.gaps().next().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn gaps(&self) -> Gaps<'a, T, S>
+    where
+        S: TreeSpan,
+    {
+        let span = self.span();
+
+        match self.links.kind {
+            Kind::Node => Gaps::new(self.walk(), span.start(), span.end()),
+            Kind::Token => Gaps::default(),
+        }
+    }
+
+    /// Find a preceeding node which matches the given predicate.
+    ///
+    /// A "preceeding node" is one which constitutes tokens the immediately
+    /// preceedes the ones of the current node, so this function scans first the
+    /// parents of the current node for a matching [`Node::prev`] sibling, and
+    /// then traverses that matches [`Node::last`].
     ///
     /// # Examples
     ///
@@ -457,6 +1571,354 @@ impl<'a, T, S> Node<'a, T, S> {
         }
     }
 
+    /// Compute the content hash of this node using a hasher constructed from
+    /// `hasher`.
+    ///
+    /// The content hash covers the value and kind of this node, the length of
+    /// the tokens it contains and the structure of its subtree, but not its
+    /// absolute position. To compute the hashes of all nodes in a tree at
+    /// once, use [`TreeHashes`][crate::TreeHashes].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "number" => {
+    ///         ("lit", 2),
+    ///     },
+    ///     ("whitespace", 1),
+    ///     "number" => {
+    ///         ("lit", 2),
+    ///     },
+    ///     "number" => {
+    ///         ("lit", 3),
+    ///     },
+    /// };
+    ///
+    /// let state = RandomState::new();
+    /// let hashes = tree.children().skip_tokens().map(|n| n.content_hash(&state)).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(hashes[0], hashes[1]);
+    /// assert_ne!(hashes[0], hashes[2]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn content_hash<B>(&self, hasher: &B) -> u64
+    where
+        T: Hash,
+        S: TreeSpan,
+        B: BuildHasher,
+    {
+        hash::content_hash(self, hasher, |_| false)
+    }
+
+    /// Compute the content hash of this node like [`Node::content_hash`],
+    /// except that subtrees for which `skip` returns `true` only contribute a
+    /// fixed marker to the hash instead of their contents.
+    ///
+    /// This is useful to compute hashes which are stable across changes to
+    /// volatile regions of a tree, such as comments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "fn" => {
+    ///         "doc" => {
+    ///             ("comment", 10),
+    ///         },
+    ///         ("ident", 3),
+    ///     },
+    ///     "fn" => {
+    ///         "doc" => {
+    ///             ("comment", 24),
+    ///             ("comment", 2),
+    ///         },
+    ///         ("ident", 3),
+    ///     },
+    /// };
+    ///
+    /// let a = tree.first().ok_or("missing first")?;
+    /// let b = a.next().ok_or("missing second")?;
+    ///
+    /// let state = RandomState::new();
+    ///
+    /// assert_ne!(a.content_hash(&state), b.content_hash(&state));
+    ///
+    /// let is_doc = |n: &syntree::Node<_>| *n.value() == "doc";
+    /// assert_eq!(a.content_hash_filtered(&state, is_doc), b.content_hash_filtered(&state, is_doc));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn content_hash_filtered<B, P>(&self, hasher: &B, skip: P) -> u64
+    where
+        T: Hash,
+        S: TreeSpan,
+        B: BuildHasher,
+        P: FnMut(&Node<'_, T, S>) -> bool,
+    {
+        hash::content_hash(self, hasher, skip)
+    }
+
+    /// Classify this node against a table of declared shapes, returning the
+    /// shape which matched together with the node children bound to each of
+    /// its child patterns. Tokens are skipped when matching children.
+    ///
+    /// See the [`shape`] module for more.
+    ///
+    /// [`shape`]: crate::shape
+    /// [`ShapeError::NoMatch`]: crate::shape::ShapeError::NoMatch
+    /// [`ShapeError::Ambiguous`]: crate::shape::ShapeError::Ambiguous
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`ShapeError::NoMatch`] listing near misses if no shape
+    /// matched, or [`ShapeError::Ambiguous`] if more than one shape matched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::shape::{Child, ShapeError, ShapeRules};
+    ///
+    /// let mut rules = ShapeRules::new();
+    /// let unary = rules.insert("unary", "expr", [Child::One("op"), Child::One("number")]);
+    /// let list = rules.insert("list", "expr", [Child::Many("number")]);
+    /// let pair = rules.insert("pair", "expr", [Child::One("number"), Child::Optional("number")]);
+    ///
+    /// let tree = syntree::tree! {
+    ///     "expr" => {
+    ///         "op" => { ("minus", 1) },
+    ///         "number" => { ("lit", 1) },
+    ///     },
+    ///     "expr" => {
+    ///         "number" => { ("lit", 1) },
+    ///         ("whitespace", 1),
+    ///         "number" => { ("lit", 1) },
+    ///     },
+    ///     "expr" => {
+    ///         "op" => { ("minus", 1) },
+    ///         ("whitespace", 1),
+    ///         "op" => { ("minus", 1) },
+    ///     },
+    /// };
+    ///
+    /// let mut it = tree.children();
+    ///
+    /// let m = it.next().ok_or("missing unary")?.classify(&rules)?;
+    /// assert_eq!(m.shape(), unary);
+    /// assert_eq!(m.name(), "unary");
+    /// assert_eq!(m.get(1).map(|n| n.range()), Some(1..2));
+    ///
+    /// let node = it.next().ok_or("missing list")?;
+    /// assert_eq!(node.classify(&rules).map(|m| m.shape()), Err(ShapeError::Ambiguous(vec![list, pair])));
+    ///
+    /// let node = it.next().ok_or("missing invalid")?;
+    ///
+    /// let Err(ShapeError::NoMatch(near_misses)) = node.classify(&rules) else {
+    ///     return Err("expected no match".into());
+    /// };
+    ///
+    /// let second_op = node.children().nth(2).ok_or("missing op")?;
+    ///
+    /// assert_eq!(near_misses[0].shape(), unary);
+    /// assert_eq!(near_misses[0].matched(), 1);
+    /// assert_eq!(near_misses[0].found(), Some(second_op.id()));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn classify(&self, rules: &'a ShapeRules<T>) -> ShapeResult<'a, T, S>
+    where
+        T: PartialEq,
+    {
+        shape::classify(*self, rules)
+    }
+
+    /// Compare this node to `other` by their position in the document, which
+    /// is the order in which they are visited by a pre-order walk such as
+    /// [`Tree::walk`].
+    ///
+    /// An ancestor sorts before its descendants, and an earlier sibling sorts
+    /// before later siblings and all of their descendants. This does not
+    /// depend on spans, so empty nodes and zero-width tokens which share an
+    /// offset are still ordered by their position.
+    ///
+    /// Note that the order of identifiers does *not* correspond to the
+    /// position of nodes, since nodes created through [`Builder::close_at`]
+    /// are allocated after the children they wrap.
+    ///
+    /// This takes time proportional to the depth of the nodes and the number
+    /// of siblings between their diverging ancestors. Comparing nodes from
+    /// different trees gives an order which is consistent but otherwise
+    /// unspecified.
+    ///
+    /// [`Tree::walk`]: crate::Tree::walk
+    /// [`Builder::close_at`]: crate::Builder::close_at
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// let c = tree.checkpoint()?;
+    /// tree.token("number", 1)?;
+    /// tree.token_empty("error")?;
+    /// tree.close_at(&c, "expr")?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let expr = tree.first().ok_or("missing expr")?;
+    /// let number = expr.first().ok_or("missing number")?;
+    /// let error = expr.last().ok_or("missing error")?;
+    ///
+    /// // The wrapping node was allocated last.
+    /// assert!(expr.id() > number.id());
+    ///
+    /// assert_eq!(expr.cmp_position(&number), Ordering::Less);
+    /// assert_eq!(error.cmp_position(&number), Ordering::Greater);
+    ///
+    /// let mut nodes = vec![error, number, expr];
+    /// nodes.sort_by(|a, b| a.cmp_position(b));
+    /// assert!(nodes.iter().map(|n| *n.value()).eq(["expr", "number", "error"]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn cmp_position(&self, other: &Self) -> Ordering {
+        if !core::ptr::eq(self.tree, other.tree) {
+            return self.tree.as_ptr().cmp(&other.tree.as_ptr());
+        }
+
+        if core::ptr::eq(self.links, other.links) {
+            return Ordering::Equal;
+        }
+
+        let mut a = *self;
+        let mut b = *other;
+        let mut a_depth = a.ancestors().count();
+        let mut b_depth = b.ancestors().count();
+
+        // Lift the deepest node until both are at the same depth. If they then
+        // coincide, the deepest node is a descendant of the other.
+        while a_depth > b_depth {
+            let Some(parent) = a.parent() else {
+                break;
+            };
+
+            if core::ptr::eq(parent.links, b.links) {
+                return Ordering::Greater;
+            }
+
+            a = parent;
+            a_depth -= 1;
+        }
+
+        while b_depth > a_depth {
+            let Some(parent) = b.parent() else {
+                break;
+            };
+
+            if core::ptr::eq(parent.links, a.links) {
+                return Ordering::Less;
+            }
+
+            b = parent;
+            b_depth -= 1;
+        }
+
+        // Lift both nodes until they are siblings.
+        while a.links.parent != b.links.parent {
+            let (Some(pa), Some(pb)) = (a.parent(), b.parent()) else {
+                break;
+            };
+
+            a = pa;
+            b = pb;
+        }
+
+        let mut current = a;
+
+        while let Some(next) = current.next() {
+            if core::ptr::eq(next.links, b.links) {
+                return Ordering::Less;
+            }
+
+            current = next;
+        }
+
+        Ordering::Greater
+    }
+
+    /// Test if this node is the same node as `other`, which is the case if
+    /// they belong to the same tree and have the same identifier.
+    ///
+    /// This differs from the [`PartialEq`] implementation, which compares
+    /// nodes by their value, kind and span.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("error", 0),
+    ///         ("error", 0),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let a = root.first().ok_or("missing first")?;
+    /// let b = root.last().ok_or("missing last")?;
+    ///
+    /// assert!(a.same(&a));
+    /// assert!(!a.same(&b));
+    /// assert_eq!(a, b);
+    ///
+    /// let c = syntree::tree!(("error", 0));
+    /// let c = c.first().ok_or("missing lit")?;
+    ///
+    /// assert_eq!(*a.value(), *c.value());
+    /// assert!(!a.same(&c));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn same(&self, other: &Self) -> bool {
+        core::ptr::eq(self.tree, other.tree) && core::ptr::eq(self.links, other.links)
+    }
+
+    /// Get a key for this node which compares and hashes by identity, so that
+    /// distinct nodes with equal values can be told apart in collections such
+    /// as a `HashSet`.
+    ///
+    /// See [`Node::same`] and [`Identity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("error", 0),
+    ///         ("error", 0),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    ///
+    /// let by_value = root.children().collect::<HashSet<_>>();
+    /// assert_eq!(by_value.len(), 1);
+    ///
+    /// let by_identity = root.children().map(|n| n.identity()).collect::<HashSet<_>>();
+    /// assert_eq!(by_identity.len(), 2);
+    /// assert!(root.children().all(|n| by_identity.contains(&n.identity())));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub const fn identity(self) -> Identity<'a, T, S> {
+        Identity::new(self)
+    }
+
     fn node_at(&self, id: NonMax) -> Option<Node<'a, T, S>> {
         let cur = self.tree.get(id.get())?;
 
@@ -467,7 +1929,7 @@ impl<'a, T, S> Node<'a, T, S> {
     }
 }
 
-impl<T> Node<'_, T, Span> {
+impl<'a, T> Node<'a, T, Span> {
     /// Access the [Span] of the node as a [Range].
     ///
     /// # Examples
@@ -498,14 +1960,92 @@ impl<T> Node<'_, T, Span> {
     pub const fn range(&self) -> Range<usize> {
         self.links.span.range()
     }
+
+    /// Get the range which folding this node would hide, which spans from the
+    /// end of its first line to its end.
+    ///
+    /// The first line ends at the first token in document order for which
+    /// `is_newline` returns `true`, and the range ends with the
+    /// [last token][Node::last_token] of the node. Only tokens are passed to
+    /// `is_newline`, and scanning for the line break stops as soon as it is
+    /// found, so the cost of this is proportional to the length of the first
+    /// line rather than the size of the node.
+    ///
+    /// Returns `None` if the node doesn't span multiple lines, which is the
+    /// case when it contains no line break or nothing follows it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "block" => {
+    ///         ("open", 1),
+    ///         ("nl", 1),
+    ///         "stmt" => {
+    ///             ("ident", 3),
+    ///             ("nl", 1),
+    ///         },
+    ///         ("close", 1),
+    ///     },
+    ///     ("nl", 1),
+    /// };
+    ///
+    /// let block = tree.first().ok_or("missing block")?;
+    /// assert_eq!(block.fold_range(|n| *n.value() == "nl"), Some(1..7));
+    ///
+    /// let stmt = block.children().find(|n| *n.value() == "stmt").ok_or("missing stmt")?;
+    /// assert_eq!(stmt.fold_range(|n| *n.value() == "nl"), None);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn fold_range<F>(&self, mut is_newline: F) -> Option<Range<usize>>
+    where
+        F: FnMut(&Node<'a, T, Span>) -> bool,
+    {
+        let newline = self
+            .walk()
+            .find(|n| n.kind() == Kind::Token && is_newline(n))?;
+
+        let last = self.last_token()?;
+
+        if newline.same(&last) {
+            return None;
+        }
+
+        Some(newline.range().start..last.range().end)
+    }
 }
 
+/// Nodes are formatted as a struct with their value, kind and span. With the
+/// alternate (`{:#?}`) format the node and its subtree is instead formatted
+/// in the same indented format as [`print::print`].
+///
+/// [`print::print`]: crate::print::print
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("lit", 3),
+///     }
+/// };
+///
+/// let root = tree.first().ok_or("missing root")?;
+///
+/// assert_eq!(format!("{root:?}"), "Node { data: \"root\", kind: Node, span: (0, 3) }");
+/// assert_eq!(format!("{root:#?}"), "\"root\"@0..3\n  \"lit\"@0..3 +");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
 impl<T, S> fmt::Debug for Node<'_, T, S>
 where
     T: fmt::Debug,
-    S: fmt::Debug,
+    S: TreeSpan + fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return debug::fmt_subtree(f, *self);
+        }
+
         f.debug_struct("Node")
             .field("data", &self.links.data)
             .field("kind", &self.links.kind)
@@ -541,3 +2081,22 @@ where
     S: Eq,
 {
 }
+
+/// Nodes are hashed by their value, kind and span, consistently with their
+/// [`PartialEq`] implementation.
+///
+/// To hash nodes by identity instead, use [`Node::identity`].
+impl<T, S> Hash for Node<'_, T, S>
+where
+    T: Hash,
+    S: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.links.data.hash(state);
+        self.links.kind.hash(state);
+        self.links.span.hash(state);
+    }
+}
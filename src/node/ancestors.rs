@@ -57,6 +57,51 @@ impl<'a, T, S> Ancestors<'a, T, S> {
         Self { node }
     }
 
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::Ancestors;
+    ///
+    /// let mut it = Ancestors::<u32, syntree::Span>::empty();
+    /// assert!(it.is_empty_hint());
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self { node: None }
+    }
+
+    /// Test if the iterator is known to produce no more elements.
+    ///
+    /// This is cheap to compute, but can only be relied on when it returns
+    /// `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {}
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut it = root.ancestors();
+    /// assert!(!it.is_empty_hint());
+    /// assert!(it.next().is_some());
+    /// assert!(it.is_empty_hint());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_empty_hint(&self) -> bool {
+        self.node.is_none()
+    }
+
     /// Construct a [`SkipTokens`] iterator from the remainder of this
     /// iterator. This filters out [`Kind::Token`] elements.
     ///
@@ -129,6 +174,6 @@ impl<T, S> Clone for Ancestors<'_, T, S> {
 impl<T, S> Default for Ancestors<'_, T, S> {
     #[inline]
     fn default() -> Self {
-        Self { node: None }
+        Self::empty()
     }
 }
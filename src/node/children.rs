@@ -1,7 +1,7 @@
 use core::iter::FusedIterator;
 
 use crate::links::Links;
-use crate::node::{Node, SkipTokens};
+use crate::node::{Matching, Node, OfType, OnlyTokens, SkipTokens, SkipTrivia, TypedNode};
 use crate::non_max::NonMax;
 use crate::tree::Kind;
 
@@ -67,10 +67,38 @@ use crate::tree::Kind;
 /// );
 /// # Ok::<_, Box<dyn std::error::Error>>(())
 /// ```
+///
+/// The number of children is tracked in the tree, so this iterator knows its
+/// exact length. Note that this doesn't hold once it is filtered through
+/// [`Children::skip_tokens`] or [`Children::of_type`], which can only report
+/// an upper bound.
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("t1", 1),
+///         "child1" => {},
+///         ("t2", 1),
+///     }
+/// };
+///
+/// let root = tree.first().ok_or("missing root")?;
+///
+/// let mut it = root.children();
+/// assert_eq!(it.len(), 3);
+/// it.next();
+/// it.next_back();
+/// assert_eq!(it.len(), 1);
+///
+/// assert_eq!(root.children().skip_tokens().size_hint(), (0, Some(3)));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
 pub struct Children<'a, T, S> {
     tree: &'a [Links<T, S>],
     first: Option<NonMax>,
     last: Option<NonMax>,
+    /// The number of remaining children.
+    len: usize,
 }
 
 impl<'a, T, S> Children<'a, T, S> {
@@ -80,8 +108,64 @@ impl<'a, T, S> Children<'a, T, S> {
         tree: &'a [Links<T, S>],
         first: Option<NonMax>,
         last: Option<NonMax>,
+        len: u32,
     ) -> Self {
-        Self { tree, first, last }
+        Self {
+            tree,
+            first,
+            last,
+            len: len as usize,
+        }
+    }
+
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::Children;
+    ///
+    /// let mut it = Children::<u32, syntree::Span>::empty();
+    /// assert!(it.is_empty_hint());
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            tree: &[],
+            first: None,
+            last: None,
+            len: 0,
+        }
+    }
+
+    /// Test if the iterator is known to produce no more elements.
+    ///
+    /// This is cheap to compute, but can only be relied on when it returns
+    /// `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {}
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut it = root.children();
+    /// assert!(!it.is_empty_hint());
+    /// assert!(it.next().is_some());
+    /// assert!(it.is_empty_hint());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_empty_hint(&self) -> bool {
+        self.first.is_none()
     }
 
     /// Construct a [`SkipTokens`] iterator from the remainder of this
@@ -93,8 +177,52 @@ impl<'a, T, S> Children<'a, T, S> {
         SkipTokens::new(self)
     }
 
+    /// Construct an [`OnlyTokens`] iterator from the remainder of this
+    /// iterator. This filters out [`Kind::Node`] elements.
+    ///
+    /// See [`OnlyTokens`] for documentation.
+    #[must_use]
+    pub const fn only_tokens(self) -> OnlyTokens<Self> {
+        OnlyTokens::new(self)
+    }
+
+    /// Construct a [`SkipTrivia`] iterator from the remainder of this
+    /// iterator. This filters out trivia tokens.
+    ///
+    /// See [`SkipTrivia`] for documentation.
+    #[must_use]
+    pub const fn skip_trivia(self) -> SkipTrivia<Self> {
+        SkipTrivia::new(self)
+    }
+
+    /// Construct a [`Matching`] iterator from the remainder of this iterator.
+    /// This only yields children whose value matches `predicate`.
+    ///
+    /// See [`Matching`] for documentation.
+    #[must_use]
+    pub const fn matching<P>(self, predicate: P) -> Matching<Self, P>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        Matching::new(self, predicate)
+    }
+
+    /// Construct an [`OfType`] iterator from the remainder of this iterator.
+    /// This only yields children which can be cast to the typed node `N`.
+    ///
+    /// See [`TypedNode`] for documentation.
+    #[must_use]
+    pub const fn of_type<N>(self) -> OfType<Self, N>
+    where
+        N: TypedNode<'a, T, S>,
+    {
+        OfType::new(self)
+    }
+
     /// Get the next node from the iterator. This advances past all non-node
-    /// data.
+    /// data, which is consumed from the iterator.
+    ///
+    /// Returns `None` if there are no more nodes.
     ///
     /// # Examples
     ///
@@ -129,6 +257,75 @@ impl<'a, T, S> Children<'a, T, S> {
             }
         }
     }
+
+    /// Get the next token from the iterator. This advances past all non-token
+    /// data, which is consumed from the iterator.
+    ///
+    /// Returns `None` if there are no more tokens.
+    ///
+    /// # Examples
+    ///
+    /// Finding the separators between the elements of a list:
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "element" => {},
+    ///     ("comma", 1),
+    ///     "element" => {},
+    ///     ("comma", 1),
+    ///     "element" => {},
+    /// };
+    ///
+    /// let mut it = tree.children();
+    ///
+    /// assert_eq!(it.next_token().map(|n| n.range()), Some(0..1));
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("element"));
+    /// assert_eq!(it.next_token().map(|n| n.range()), Some(1..2));
+    /// assert!(it.next_token().is_none());
+    /// assert!(it.next().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn next_token(&mut self) -> Option<Node<'a, T, S>> {
+        loop {
+            let node = self.next()?;
+
+            if matches!(node.kind(), Kind::Token) {
+                return Some(node);
+            }
+        }
+    }
+
+    /// Split the remaining children into the first `n` and the rest, which
+    /// takes time linear in `n`.
+    ///
+    /// Requires that `0 < n < len`.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn split_at(self, n: usize) -> (Self, Self) {
+        let mut mid = self.first;
+
+        for _ in 1..n {
+            mid = mid.and_then(|id| self.tree.get(id.get())?.next);
+        }
+
+        let next = mid.and_then(|id| self.tree.get(id.get())?.next);
+
+        let first = Self {
+            tree: self.tree,
+            first: self.first,
+            last: mid,
+            len: n,
+        };
+
+        let rest = Self {
+            tree: self.tree,
+            first: next,
+            last: self.last,
+            len: self.len - n,
+        };
+
+        (first, rest)
+    }
 }
 
 impl<'a, T, S> Iterator for Children<'a, T, S> {
@@ -143,8 +340,14 @@ impl<'a, T, S> Iterator for Children<'a, T, S> {
             self.first = node.next;
         }
 
+        self.len = self.len.saturating_sub(1);
         Some(Node::new(node, self.tree))
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
 impl<T, S> DoubleEndedIterator for Children<'_, T, S> {
@@ -157,10 +360,13 @@ impl<T, S> DoubleEndedIterator for Children<'_, T, S> {
             self.last = node.prev;
         }
 
+        self.len = self.len.saturating_sub(1);
         Some(Node::new(node, self.tree))
     }
 }
 
+impl<T, S> ExactSizeIterator for Children<'_, T, S> {}
+
 impl<T, S> FusedIterator for Children<'_, T, S> {}
 
 impl<T, S> Clone for Children<'_, T, S> {
@@ -170,6 +376,7 @@ impl<T, S> Clone for Children<'_, T, S> {
             tree: self.tree,
             first: self.first,
             last: self.last,
+            len: self.len,
         }
     }
 }
@@ -177,10 +384,6 @@ impl<T, S> Clone for Children<'_, T, S> {
 impl<T, S> Default for Children<'_, T, S> {
     #[inline]
     fn default() -> Self {
-        Self {
-            tree: &[],
-            first: None,
-            last: None,
-        }
+        Self::empty()
     }
 }
@@ -0,0 +1,310 @@
+use core::fmt;
+
+use crate::node::{Children, Node};
+use crate::tree::Kind;
+use crate::Id;
+
+/// A cursor over the children of a node which supports peeking, constructed
+/// through [`Node::children_cursor`].
+///
+/// This is intended for consuming list-like nodes where element nodes are
+/// interleaved with separator tokens, such as when writing typed accessors or
+/// validators. Methods which expect something in particular don't consume
+/// anything if they fail.
+///
+/// # Examples
+///
+/// Consuming a comma-separated list which permits a trailing comma:
+///
+/// ```
+/// use syntree::node::{ChildrenCursor, CursorError, Expected};
+/// use syntree::Node;
+///
+/// fn elements<'a>(node: Node<'a, &'static str, syntree::Span>) -> Result<Vec<Node<'a, &'static str, syntree::Span>>, CursorError> {
+///     let mut cursor = node.children_cursor();
+///     let mut out = Vec::new();
+///
+///     while cursor.peek().is_some() {
+///         out.push(cursor.expect_node()?);
+///
+///         if cursor.peek().is_none() {
+///             break;
+///         }
+///
+///         cursor.expect_token_with(&"comma")?;
+///     }
+///
+///     cursor.finish()?;
+///     Ok(out)
+/// }
+///
+/// let tree = syntree::tree! {
+///     "list" => {
+///         "element" => { ("number", 1) },
+///         ("comma", 1),
+///         "element" => { ("number", 1) },
+///         ("comma", 1),
+///     }
+/// };
+///
+/// let list = tree.first().ok_or("missing list")?;
+/// assert_eq!(elements(list)?.len(), 2);
+///
+/// // The second separator is missing.
+/// let tree = syntree::tree! {
+///     "list" => {
+///         "element" => { ("number", 1) },
+///         ("comma", 1),
+///         "element" => { ("number", 1) },
+///         "element" => { ("number", 1) },
+///     }
+/// };
+///
+/// let list = tree.first().ok_or("missing list")?;
+/// let error = elements(list).unwrap_err();
+/// assert_eq!(error.parent(), list.id());
+/// assert_eq!(error.expected(), Expected::Token);
+/// assert_eq!(error.found().map(|n| n.index()), Some(6));
+/// assert_eq!(error.to_string(), "expected token in node 0, but found node 6");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct ChildrenCursor<'a, T, S> {
+    parent: Id,
+    children: Children<'a, T, S>,
+}
+
+impl<'a, T, S> ChildrenCursor<'a, T, S> {
+    #[inline]
+    pub(crate) const fn new(parent: Id, children: Children<'a, T, S>) -> Self {
+        Self { parent, children }
+    }
+
+    /// Peek at the next child without consuming it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut cursor = root.children_cursor();
+    ///
+    /// assert_eq!(cursor.peek().map(|n| *n.value()), Some("lit"));
+    /// assert_eq!(cursor.peek().map(|n| *n.value()), Some("lit"));
+    /// assert_eq!(cursor.bump().map(|n| *n.value()), Some("lit"));
+    /// assert!(cursor.peek().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn peek(&self) -> Option<Node<'a, T, S>> {
+        self.children.clone().next()
+    }
+
+    /// Consume and return the next child, regardless of its kind.
+    pub fn bump(&mut self) -> Option<Node<'a, T, S>> {
+        self.children.next()
+    }
+
+    /// Consume the next child, which is expected to be a [`Kind::Node`].
+    ///
+    /// # Errors
+    ///
+    /// Errors without consuming anything if the next child is a token, or if
+    /// there are no more children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::Expected;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut cursor = root.children_cursor();
+    ///
+    /// let error = cursor.expect_node().unwrap_err();
+    /// assert_eq!(error.expected(), Expected::Node);
+    /// assert_eq!(cursor.peek().map(|n| *n.value()), Some("lit"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn expect_node(&mut self) -> Result<Node<'a, T, S>, CursorError> {
+        self.expect(Expected::Node, |node| node.kind() == Kind::Node)
+    }
+
+    /// Consume the next child, which is expected to be a [`Kind::Token`] with
+    /// the given `value`.
+    ///
+    /// # Errors
+    ///
+    /// Errors without consuming anything if the next child is not a token
+    /// with the given value, or if there are no more children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("comma", 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut cursor = root.children_cursor();
+    ///
+    /// assert!(cursor.expect_token_with(&"semi").is_err());
+    /// assert!(cursor.expect_token_with(&"comma").is_ok());
+    /// assert!(cursor.finish().is_ok());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn expect_token_with(&mut self, value: &T) -> Result<Node<'a, T, S>, CursorError>
+    where
+        T: PartialEq,
+    {
+        self.expect(Expected::Token, |node| {
+            node.kind() == Kind::Token && node.value() == value
+        })
+    }
+
+    /// Verify that all children have been consumed.
+    ///
+    /// # Errors
+    ///
+    /// Errors if there are children remaining.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::Expected;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let cursor = root.children_cursor();
+    ///
+    /// let error = cursor.finish().unwrap_err();
+    /// assert_eq!(error.expected(), Expected::End);
+    /// assert_eq!(error.to_string(), "expected end in node 0, but found token 1");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn finish(self) -> Result<(), CursorError> {
+        match self.peek() {
+            Some(node) => Err(CursorError::new(self.parent, Expected::End, Some(node))),
+            None => Ok(()),
+        }
+    }
+
+    fn expect(
+        &mut self,
+        expected: Expected,
+        f: impl FnOnce(&Node<'a, T, S>) -> bool,
+    ) -> Result<Node<'a, T, S>, CursorError> {
+        match self.peek() {
+            Some(node) if f(&node) => {
+                self.children.next();
+                Ok(node)
+            }
+            found => Err(CursorError::new(self.parent, expected, found)),
+        }
+    }
+}
+
+impl<T, S> Clone for ChildrenCursor<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            parent: self.parent,
+            children: self.children.clone(),
+        }
+    }
+}
+
+/// What a [`ChildrenCursor`] expected to find when it raised a
+/// [`CursorError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Expected {
+    /// A [`Kind::Node`].
+    Node,
+    /// A [`Kind::Token`], with a particular value.
+    Token,
+    /// No more children.
+    End,
+}
+
+/// Error raised by [`ChildrenCursor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorError {
+    parent: Id,
+    expected: Expected,
+    found: Option<(Id, Kind)>,
+}
+
+impl CursorError {
+    fn new<T, S>(parent: Id, expected: Expected, found: Option<Node<'_, T, S>>) -> Self {
+        Self {
+            parent,
+            expected,
+            found: found.map(|node| (node.id(), node.kind())),
+        }
+    }
+
+    /// The identifier of the node whose children were being consumed.
+    #[must_use]
+    pub const fn parent(&self) -> Id {
+        self.parent
+    }
+
+    /// What was expected.
+    #[must_use]
+    pub const fn expected(&self) -> Expected {
+        self.expected
+    }
+
+    /// The identifier of the child which was found instead, or `None` if there
+    /// were no more children.
+    #[must_use]
+    pub const fn found(&self) -> Option<Id> {
+        match self.found {
+            Some((id, _)) => Some(id),
+            None => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CursorError {}
+
+// `core::error::Error` is only stable since Rust 1.81, so it is opt-in.
+#[cfg(all(not(feature = "std"), feature = "core-error"))]
+#[allow(clippy::incompatible_msrv)]
+impl core::error::Error for CursorError {}
+
+impl fmt::Display for CursorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expected = match self.expected {
+            Expected::Node => "node",
+            Expected::Token => "token",
+            Expected::End => "end",
+        };
+
+        write!(f, "expected {expected} in node {}, but found ", self.parent)?;
+
+        match self.found {
+            Some((id, Kind::Node)) => write!(f, "node {id}"),
+            Some((id, Kind::Token)) => write!(f, "token {id}"),
+            None => write!(f, "end"),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use core::iter::FusedIterator;
+
+use crate::node::{Node, Walk};
+
+/// An iterator over the descendants of a node whose value is equal to a given
+/// value, in the order they are visited by [`Walk`].
+///
+/// Unlike filtering a [`Walk`] with a closure the type of this iterator can
+/// be written out, which makes it possible to store it in other types.
+///
+/// See [`Node::descendants_with_value`] or
+/// [`Tree::descendants_with_value`][crate::Tree::descendants_with_value].
+///
+/// # Examples
+///
+/// ```
+/// use syntree::node::DescendantsWithValue;
+/// use syntree::Span;
+///
+/// struct Functions<'a> {
+///     iter: DescendantsWithValue<'a, &'static str, Span>,
+/// }
+///
+/// let tree = syntree::tree! {
+///     "file" => {
+///         "fn" => {
+///             ("ident", 1),
+///             "block" => {
+///                 "fn" => {
+///                     ("ident", 1),
+///                 },
+///             },
+///         },
+///         "struct" => {
+///             ("ident", 1),
+///         },
+///     }
+/// };
+///
+/// let file = tree.first().ok_or("missing file")?;
+///
+/// let functions = Functions {
+///     iter: file.descendants_with_value(&"fn"),
+/// };
+///
+/// let ranges = functions.iter.map(|n| n.range()).collect::<Vec<_>>();
+/// assert_eq!(ranges, [0..2, 1..2]);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct DescendantsWithValue<'a, T, S> {
+    walk: Walk<'a, T, S>,
+    value: &'a T,
+}
+
+impl<'a, T, S> DescendantsWithValue<'a, T, S> {
+    #[inline]
+    pub(crate) const fn new(walk: Walk<'a, T, S>, value: &'a T) -> Self {
+        Self { walk, value }
+    }
+
+    /// Get the value being searched for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let it = tree.descendants_with_value(&"lit");
+    /// assert_eq!(*it.value(), "lit");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn value(&self) -> &'a T {
+        self.value
+    }
+}
+
+impl<'a, T, S> Iterator for DescendantsWithValue<'a, T, S>
+where
+    T: PartialEq,
+{
+    type Item = Node<'a, T, S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.value;
+        self.walk.find(|n| n.value() == value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = self.walk.size_hint();
+        (0, upper)
+    }
+}
+
+impl<T, S> FusedIterator for DescendantsWithValue<'_, T, S> where T: PartialEq {}
+
+impl<T, S> Clone for DescendantsWithValue<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            walk: self.walk.clone(),
+            value: self.value,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use alloc::vec::{Drain, Vec};
+
+use crate::node::Node;
+
+/// Fold the subtree rooted at `root` bottom-up, using `results` and `starts`
+/// as scratch buffers which are left empty.
+pub(crate) fn fold_subtree<'a, T, S, R, F>(
+    root: Node<'a, T, S>,
+    f: &mut F,
+    results: &mut Vec<R>,
+    starts: &mut Vec<usize>,
+) -> R
+where
+    F: FnMut(Node<'a, T, S>, Drain<'_, R>) -> R,
+{
+    let mut current = root;
+
+    loop {
+        // Results of children are pushed after this offset.
+        starts.push(results.len());
+
+        if let Some(first) = current.first() {
+            current = first;
+            continue;
+        }
+
+        loop {
+            let start = starts.pop().unwrap_or_default();
+            let result = f(current, results.drain(start..));
+
+            if current.same(&root) {
+                return result;
+            }
+
+            results.push(result);
+
+            if let Some(next) = current.next() {
+                current = next;
+                break;
+            }
+
+            // Every node in the subtree other than the root has a parent.
+            current = current.parent().unwrap_or(root);
+        }
+    }
+}
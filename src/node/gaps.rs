@@ -0,0 +1,116 @@
+use core::iter::FusedIterator;
+
+use crate::node::Walk;
+use crate::span::{Index, TreeSpan};
+use crate::tree::Kind;
+
+/// An iterator over the ranges of a span which are not covered by any token,
+/// constructed through [`Node::gaps`] or [`Tree::gaps`].
+///
+/// Gaps are left by [`Builder::advance`] or lexers which skip over trivia, so
+/// the span of a node can cover offsets which no token owns. Each gap is as
+/// large as possible and they are yielded in order, so zero-width tokens
+/// never split a gap.
+///
+/// Tokens are assumed to be ordered, which is always the case unless the tree
+/// was built with [`CursorPolicy::Unordered`].
+///
+/// [`Node::gaps`]: crate::Node::gaps
+/// [`Tree::gaps`]: crate::Tree::gaps
+/// [`Builder::advance`]: crate::Builder::advance
+/// [`CursorPolicy::Unordered`]: crate::CursorPolicy::Unordered
+///
+/// # Examples
+///
+/// ```
+/// use syntree::{Builder, CursorPolicy, Span};
+///
+/// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+///
+/// tree.open("call")?;
+/// tree.token("ident", 3)?;
+/// tree.advance(1)?;
+/// tree.token("open", 1)?;
+/// tree.token("close", 1)?;
+/// tree.close()?;
+///
+/// let tree = tree.build()?;
+/// let call = tree.first().ok_or("missing call")?;
+///
+/// assert!(call.gaps().eq([Span::new(3, 4)]));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Gaps<'a, T, S> {
+    iter: Walk<'a, T, S>,
+    /// The end of the tokens visited so far.
+    cursor: Index,
+    /// The end of the span gaps are searched for in.
+    end: Index,
+}
+
+impl<'a, T, S> Gaps<'a, T, S> {
+    #[inline]
+    pub(crate) const fn new(iter: Walk<'a, T, S>, start: Index, end: Index) -> Self {
+        Self {
+            iter,
+            cursor: start,
+            end,
+        }
+    }
+}
+
+impl<T, S> Iterator for Gaps<'_, T, S>
+where
+    S: TreeSpan,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for node in self.iter.by_ref() {
+            let span = node.span();
+
+            if node.kind() != Kind::Token || span.is_empty() {
+                continue;
+            }
+
+            let start = self.cursor;
+            self.cursor = self.cursor.max(span.end());
+
+            if span.start() > start {
+                return Some(S::new(start, span.start()));
+            }
+        }
+
+        if self.cursor < self.end {
+            let start = self.cursor;
+            self.cursor = self.end;
+            return Some(S::new(start, self.end));
+        }
+
+        None
+    }
+}
+
+impl<T, S> FusedIterator for Gaps<'_, T, S> where S: TreeSpan {}
+
+impl<T, S> Clone for Gaps<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            cursor: self.cursor,
+            end: self.end,
+        }
+    }
+}
+
+impl<T, S> Default for Gaps<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            iter: Walk::empty(),
+            cursor: 0,
+            end: 0,
+        }
+    }
+}
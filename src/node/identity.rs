@@ -0,0 +1,63 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use crate::node::Node;
+
+/// A [`Node`] which compares and hashes by identity, as returned by
+/// [`Node::identity`].
+///
+/// Two identities are equal if they refer to the same node in the same tree,
+/// as determined by [`Node::same`]. They are hashed by the address of the tree
+/// and the identifier of the node, so they are only meaningful for as long as
+/// the tree is borrowed.
+pub struct Identity<'a, T, S> {
+    node: Node<'a, T, S>,
+}
+
+impl<'a, T, S> Identity<'a, T, S> {
+    #[inline]
+    pub(crate) const fn new(node: Node<'a, T, S>) -> Self {
+        Self { node }
+    }
+
+    /// Access the node.
+    #[must_use]
+    pub const fn node(&self) -> Node<'a, T, S> {
+        self.node
+    }
+}
+
+impl<T, S> PartialEq for Identity<'_, T, S> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.node.same(&other.node)
+    }
+}
+
+impl<T, S> Eq for Identity<'_, T, S> {}
+
+impl<T, S> Hash for Identity<'_, T, S> {
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.node.storage().as_ptr().hash(state);
+        self.node.id().hash(state);
+    }
+}
+
+impl<T, S> Clone for Identity<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, S> Copy for Identity<'_, T, S> {}
+
+impl<T, S> fmt::Debug for Identity<'_, T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Identity").field(&self.node.id()).finish()
+    }
+}
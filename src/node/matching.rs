@@ -0,0 +1,218 @@
+use core::iter::FusedIterator;
+
+use crate::node::Node;
+
+/// Wrapped around an iterator that only yields nodes whose values match a
+/// predicate.
+///
+/// Unlike [`Iterator::filter`] the type of this iterator can be written out,
+/// so using a function pointer as the predicate makes it possible to store it
+/// in other types:
+///
+/// ```
+/// use syntree::node::{Children, Matching};
+/// use syntree::{Node, Span};
+///
+/// type Value = &'static str;
+///
+/// struct Params<'a> {
+///     iter: Matching<Children<'a, Value, Span>, fn(&Value) -> bool>,
+/// }
+///
+/// impl<'a> Iterator for Params<'a> {
+///     type Item = Node<'a, Value, Span>;
+///
+///     fn next(&mut self) -> Option<Self::Item> {
+///         self.iter.next()
+///     }
+/// }
+///
+/// let tree = syntree::tree! {
+///     "fn" => {
+///         "param" => {},
+///         ("comma", 1),
+///         "param" => {},
+///     }
+/// };
+///
+/// let root = tree.first().ok_or("missing root")?;
+///
+/// let params = Params {
+///     iter: root.children().matching(|value| *value == "param"),
+/// };
+///
+/// assert_eq!(params.count(), 2);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// Note that this iterator also implements [Default] if the wrapped iterator
+/// does, which produces an empty iterator without a predicate:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("token", 1)
+///     }
+/// };
+///
+/// let mut it = tree
+///     .first()
+///     .and_then(|n| n.next())
+///     .map(|n| n.children().matching(|value| *value == "token"))
+///     .unwrap_or_default();
+///
+/// assert!(it.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// See [`Children::matching`] or [`Walk::matching`].
+///
+/// Since it's not known up front how many nodes will match, this only reports
+/// an upper bound through [`size_hint`][Iterator::size_hint] even if the
+/// wrapped iterator is exact.
+///
+/// [`Children::matching`]: crate::node::Children::matching
+/// [`Walk::matching`]: crate::node::Walk::matching
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "number" => {
+///             ("lit", 1),
+///         },
+///         ("ws", 1),
+///         "number" => {
+///             ("lit", 2),
+///         },
+///     }
+/// };
+///
+/// assert_eq!(tree.walk().matching(|value| *value == "lit").count(), 2);
+///
+/// let root = tree.first().ok_or("missing root")?;
+/// let mut it = root.children().matching(|value| *value == "number");
+///
+/// assert_eq!(it.next_back().map(|n| n.range()), Some(2..4));
+/// assert_eq!(it.next_back().map(|n| n.range()), Some(0..1));
+/// assert!(it.next_back().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Matching<I, P> {
+    iter: I,
+    predicate: Option<P>,
+}
+
+impl<I, P> Matching<I, P> {
+    #[inline]
+    pub(crate) const fn new(iter: I, predicate: P) -> Self {
+        Self {
+            iter,
+            predicate: Some(predicate),
+        }
+    }
+
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::{Matching, Walk};
+    ///
+    /// let mut it = Matching::<Walk<u32, syntree::Span>, fn(&u32) -> bool>::empty();
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn empty() -> Self
+    where
+        I: Default,
+    {
+        Self::default()
+    }
+}
+
+impl<'a, I, P, T: 'a, S: 'a> Iterator for Matching<I, P>
+where
+    I: Iterator<Item = Node<'a, T, S>>,
+    P: FnMut(&T) -> bool,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let predicate = self.predicate.as_mut()?;
+
+        loop {
+            let node = self.iter.next()?;
+
+            if predicate(node.value()) {
+                return Some(node);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.predicate.is_none() {
+            return (0, Some(0));
+        }
+
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<'a, I, P, T: 'a, S: 'a> DoubleEndedIterator for Matching<I, P>
+where
+    I: DoubleEndedIterator<Item = Node<'a, T, S>>,
+    P: FnMut(&T) -> bool,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let predicate = self.predicate.as_mut()?;
+
+        loop {
+            let node = self.iter.next_back()?;
+
+            if predicate(node.value()) {
+                return Some(node);
+            }
+        }
+    }
+}
+
+impl<'a, I, P, T: 'a, S: 'a> FusedIterator for Matching<I, P>
+where
+    I: FusedIterator<Item = Node<'a, T, S>>,
+    P: FnMut(&T) -> bool,
+{
+}
+
+impl<I, P> Clone for Matching<I, P>
+where
+    I: Clone,
+    P: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            predicate: self.predicate.clone(),
+        }
+    }
+}
+
+impl<I, P> Default for Matching<I, P>
+where
+    I: Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            iter: Default::default(),
+            predicate: None,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use core::iter::FusedIterator;
+use core::ops::Range;
+
+use crate::node::{Children, Node};
+use crate::span::TreeSpan;
+
+/// An iterator over a chain of nested nodes which all cover exactly the same
+/// range, from the outermost to the innermost node.
+///
+/// See [`Tree::nodes_with_range`][crate::Tree::nodes_with_range] or
+/// [`Node::nodes_with_range`].
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("ws", 4),
+///         "paren" => {
+///             "expr" => {
+///                 ("lit", 5),
+///             },
+///         },
+///     }
+/// };
+///
+/// let nodes = tree.nodes_with_range(4..9).map(|n| *n.value()).collect::<Vec<_>>();
+/// assert_eq!(nodes, ["paren", "expr", "lit"]);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct NodesWithRange<'a, T, S> {
+    node: Option<Node<'a, T, S>>,
+    range: Range<usize>,
+}
+
+impl<'a, T, S> NodesWithRange<'a, T, S>
+where
+    S: TreeSpan,
+{
+    /// Construct an iterator by descending through `children` towards the
+    /// outermost node covering exactly `range`.
+    pub(crate) fn new(children: Children<'a, T, S>, range: Range<usize>) -> Self {
+        let node = descend(children, &range);
+        Self { node, range }
+    }
+
+    /// Construct an iterator starting at `node`, which may itself be the
+    /// outermost node covering exactly `range`.
+    pub(crate) fn from_node(node: Node<'a, T, S>, range: Range<usize>) -> Self {
+        let r = node.span().range();
+
+        let node = if r == range {
+            Some(node)
+        } else if r.start <= range.start && r.end >= range.end {
+            descend(node.children(), &range)
+        } else {
+            None
+        };
+
+        Self { node, range }
+    }
+}
+
+impl<T, S> NodesWithRange<'_, T, S> {
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::NodesWithRange;
+    ///
+    /// let mut it = NodesWithRange::<u32, syntree::Span>::empty();
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            node: None,
+            range: 0..0,
+        }
+    }
+}
+
+/// Descend through `children` while some child covers `range`, preferring a
+/// child which covers it exactly.
+fn descend<'a, T, S>(
+    mut children: Children<'a, T, S>,
+    range: &Range<usize>,
+) -> Option<Node<'a, T, S>>
+where
+    S: TreeSpan,
+{
+    loop {
+        let mut covering = None;
+
+        for child in children {
+            let r = child.span().range();
+
+            if r == *range {
+                return Some(child);
+            }
+
+            if covering.is_none() && r.start <= range.start && r.end >= range.end {
+                covering = Some(child);
+            }
+        }
+
+        children = covering?.children();
+    }
+}
+
+impl<'a, T, S> Iterator for NodesWithRange<'a, T, S>
+where
+    S: TreeSpan,
+{
+    type Item = Node<'a, T, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.node.take()?;
+        self.node = node.children().find(|n| n.span().range() == self.range);
+        Some(node)
+    }
+}
+
+impl<T, S> FusedIterator for NodesWithRange<'_, T, S> where S: TreeSpan {}
+
+impl<T, S> Clone for NodesWithRange<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            node: self.node,
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<T, S> Default for NodesWithRange<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
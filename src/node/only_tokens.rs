@@ -0,0 +1,214 @@
+use core::iter::FusedIterator;
+
+use crate::node::Node;
+use crate::tree::Kind;
+
+/// Wrapped around an iterator that only includes [`Kind::Token`] nodes. This is
+/// the mirror of [`SkipTokens`].
+///
+/// Note that this iterator also implements [Default] if the wrapped iterator
+/// does, allowing it to effectively create an empty iterator in case a
+/// particular node is not available:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("token", 1)
+///     }
+/// };
+///
+/// let mut it = tree.first().and_then(|n| n.next()).map(|n| n.children().only_tokens()).unwrap_or_default();
+/// assert!(it.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// See [`Children::only_tokens`], [`Siblings::only_tokens`] or
+/// [`Walk::only_tokens`].
+///
+/// Since it's not known up front how many nodes will be skipped, this only
+/// reports an upper bound through [`size_hint`][Iterator::size_hint] even if
+/// the wrapped iterator is exact.
+///
+/// [`SkipTokens`]: crate::node::SkipTokens
+/// [`Children::only_tokens`]: crate::node::Children::only_tokens
+/// [`Siblings::only_tokens`]: crate::node::Siblings::only_tokens
+/// [`Walk::only_tokens`]: crate::node::Walk::only_tokens
+///
+/// # Examples
+///
+/// Filtering [`Kind::Node`] elements from a [`Walk`] iterator:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "child1" => {
+///         "child2" => {},
+///         ("token1", 1),
+///         "child3" => {},
+///     },
+///     ("token2", 1),
+///     "child4" => {
+///         ("token3", 1)
+///     }
+/// };
+///
+/// let mut it = tree.walk().only_tokens();
+///
+/// assert_eq!(
+///     it.map(|n| *n.value()).collect::<Vec<_>>(),
+///     ["token1", "token2", "token3"]
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`Walk`]: crate::node::Walk
+pub struct OnlyTokens<I> {
+    iter: I,
+}
+
+impl<I> OnlyTokens<I> {
+    #[inline]
+    pub(crate) const fn new(iter: I) -> Self {
+        Self { iter }
+    }
+
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::{OnlyTokens, Walk};
+    ///
+    /// let mut it = OnlyTokens::<Walk<u32, syntree::Span>>::empty();
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn empty() -> Self
+    where
+        I: Default,
+    {
+        Self::default()
+    }
+
+    /// Convert this iterator back into the iterator it wraps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     ("t1", 1),
+    ///     "n1" => {},
+    ///     ("t2", 1),
+    ///     "n2" => {},
+    /// };
+    ///
+    /// let mut it = tree.children().only_tokens();
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("t1"));
+    ///
+    /// let mut it = it.into_inner();
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("n1"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<'a, I, T: 'a, S: 'a> Iterator for OnlyTokens<I>
+where
+    I: Iterator<Item = Node<'a, T, S>>,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.iter.next()?;
+
+            if matches!(node.kind(), Kind::Token) {
+                return Some(node);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.fold(init, move |acc, node| {
+            if matches!(node.kind(), Kind::Token) {
+                f(acc, node)
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+impl<'a, I, T: 'a, S: 'a> DoubleEndedIterator for OnlyTokens<I>
+where
+    I: DoubleEndedIterator<Item = Node<'a, T, S>>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.iter.next_back()?;
+
+            if matches!(node.kind(), Kind::Token) {
+                return Some(node);
+            }
+        }
+    }
+
+    #[inline]
+    fn rfold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.rfold(init, move |acc, node| {
+            if matches!(node.kind(), Kind::Token) {
+                f(acc, node)
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+impl<'a, I, T: 'a, S: 'a> FusedIterator for OnlyTokens<I> where
+    I: FusedIterator<Item = Node<'a, T, S>>
+{
+}
+
+impl<I> Clone for OnlyTokens<I>
+where
+    I: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<I> Default for OnlyTokens<I>
+where
+    I: Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            iter: Default::default(),
+        }
+    }
+}
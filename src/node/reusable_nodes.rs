@@ -0,0 +1,93 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::ops::Range;
+
+use crate::node::{Children, Node};
+use crate::span::TreeSpan;
+
+/// An iterator over the maximal subtrees which don't touch an edited range.
+///
+/// See [`Tree::reusable_nodes`][crate::Tree::reusable_nodes].
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "a" => {
+///             ("lit", 2),
+///         },
+///         ("ws", 1),
+///         "b" => {
+///             ("lit", 2),
+///             ("lit", 2),
+///         },
+///     }
+/// };
+///
+/// let nodes = tree.reusable_nodes(6..6).map(|n| *n.value()).collect::<Vec<_>>();
+/// assert_eq!(nodes, ["a", "ws", "lit"]);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct ReusableNodes<'a, T, S> {
+    stack: Vec<Children<'a, T, S>>,
+    edited: Range<usize>,
+}
+
+impl<'a, T, S> ReusableNodes<'a, T, S> {
+    pub(crate) fn new(children: Children<'a, T, S>, edited: Range<usize>) -> Self {
+        Self {
+            stack: vec![children],
+            edited,
+        }
+    }
+}
+
+impl<'a, T, S> Iterator for ReusableNodes<'a, T, S>
+where
+    S: TreeSpan,
+{
+    type Item = Node<'a, T, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(children) = self.stack.last_mut() {
+            let Some(node) = children.next() else {
+                self.stack.pop();
+                continue;
+            };
+
+            let range = node.span().range();
+
+            if range.end < self.edited.start || range.start > self.edited.end {
+                return Some(node);
+            }
+
+            self.stack.push(node.children());
+        }
+
+        None
+    }
+}
+
+impl<T, S> FusedIterator for ReusableNodes<'_, T, S> where S: TreeSpan {}
+
+impl<T, S> Clone for ReusableNodes<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            stack: self.stack.clone(),
+            edited: self.edited.clone(),
+        }
+    }
+}
+
+impl<T, S> Default for ReusableNodes<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            stack: Vec::new(),
+            edited: 0..0,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use core::iter::FusedIterator;
 
 use crate::links::Links;
-use crate::node::{Node, SkipTokens};
+use crate::node::{Node, OnlyTokens, SkipTokens, SkipTrivia};
 use crate::tree::Kind;
 
 /// An iterator that iterates over the [`Node::next`] elements of a node. This is
@@ -66,6 +66,54 @@ impl<'a, T, S> Siblings<'a, T, S> {
         }
     }
 
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::Siblings;
+    ///
+    /// let mut it = Siblings::<u32, syntree::Span>::empty();
+    /// assert!(it.is_empty_hint());
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            tree: &[],
+            links: None,
+        }
+    }
+
+    /// Test if the iterator is known to produce no more elements.
+    ///
+    /// This is cheap to compute, but can only be relied on when it returns
+    /// `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {}
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut it = root.siblings();
+    /// assert!(!it.is_empty_hint());
+    /// assert!(it.next().is_some());
+    /// assert!(it.is_empty_hint());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_empty_hint(&self) -> bool {
+        self.links.is_none()
+    }
+
     /// Construct a [`SkipTokens`] iterator from the remainder of this
     /// iterator. This filters out [`Kind::Token`] elements.
     ///
@@ -75,8 +123,28 @@ impl<'a, T, S> Siblings<'a, T, S> {
         SkipTokens::new(self)
     }
 
+    /// Construct an [`OnlyTokens`] iterator from the remainder of this
+    /// iterator. This filters out [`Kind::Node`] elements.
+    ///
+    /// See [`OnlyTokens`] for documentation.
+    #[must_use]
+    pub const fn only_tokens(self) -> OnlyTokens<Self> {
+        OnlyTokens::new(self)
+    }
+
+    /// Construct a [`SkipTrivia`] iterator from the remainder of this
+    /// iterator. This filters out trivia tokens.
+    ///
+    /// See [`SkipTrivia`] for documentation.
+    #[must_use]
+    pub const fn skip_trivia(self) -> SkipTrivia<Self> {
+        SkipTrivia::new(self)
+    }
+
     /// Get the next node from the iterator. This advances past all non-node
-    /// data.
+    /// data, which is consumed from the iterator.
+    ///
+    /// Returns `None` if there are no more nodes.
     ///
     /// # Examples
     ///
@@ -113,6 +181,46 @@ impl<'a, T, S> Siblings<'a, T, S> {
             }
         }
     }
+
+    /// Get the next token from the iterator. This advances past all non-token
+    /// data, which is consumed from the iterator.
+    ///
+    /// Returns `None` if there are no more tokens.
+    ///
+    /// # Examples
+    ///
+    /// Finding the separators between the elements of a list:
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "element" => {},
+    ///     ("comma", 1),
+    ///     "element" => {},
+    ///     ("comma", 1),
+    ///     "element" => {},
+    /// };
+    ///
+    /// let first = tree.first().ok_or("missing first")?;
+    ///
+    /// let mut it = first.siblings();
+    ///
+    /// assert_eq!(it.next_token().map(|n| n.range()), Some(0..1));
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("element"));
+    /// assert_eq!(it.next_token().map(|n| n.range()), Some(1..2));
+    /// assert!(it.next_token().is_none());
+    /// assert!(it.next().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn next_token(&mut self) -> Option<Node<'a, T, S>> {
+        loop {
+            let node = self.next()?;
+
+            if matches!(node.kind(), Kind::Token) {
+                return Some(node);
+            }
+        }
+    }
 }
 
 impl<'a, T, S> Iterator for Siblings<'a, T, S> {
@@ -124,6 +232,22 @@ impl<'a, T, S> Iterator for Siblings<'a, T, S> {
         self.links = links.next.and_then(|id| self.tree.get(id.get()));
         Some(Node::new(links, self.tree))
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let Some(links) = self.links else {
+            return (0, Some(0));
+        };
+
+        // The position among siblings isn't stored, so the number of children
+        // in the parent is the best known bound.
+        let upper = match links.parent.and_then(|id| self.tree.get(id.get())) {
+            Some(parent) => parent.len as usize,
+            None => self.tree.len(),
+        };
+
+        (1, Some(upper))
+    }
 }
 
 impl<T, S> FusedIterator for Siblings<'_, T, S> {}
@@ -141,9 +265,6 @@ impl<T, S> Clone for Siblings<'_, T, S> {
 impl<T, S> Default for Siblings<'_, T, S> {
     #[inline]
     fn default() -> Self {
-        Self {
-            tree: &[],
-            links: None,
-        }
+        Self::empty()
     }
 }
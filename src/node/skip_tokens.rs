@@ -5,8 +5,32 @@ use crate::tree::Kind;
 
 /// Wrapped around an iterator that excludes [`Kind::Token`] nodes.
 ///
+/// Note that this iterator also implements [Default] if the wrapped iterator
+/// does, allowing it to effectively create an empty iterator in case a
+/// particular node is not available:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("token", 1)
+///     }
+/// };
+///
+/// let mut it = tree.first().and_then(|n| n.next()).map(|n| n.children().skip_tokens()).unwrap_or_default();
+/// assert!(it.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
 /// See [`Siblings::skip_tokens`] or [`Walk::skip_tokens`].
 ///
+/// Tokens which are marked as errors through
+/// [`Builder::mark_error`][crate::Builder::mark_error] are skipped like any
+/// other token, while nodes marked as errors are kept.
+///
+/// Since it's not known up front how many tokens will be skipped, this only
+/// reports an upper bound through [`size_hint`][Iterator::size_hint] even if
+/// the wrapped iterator is exact.
+///
 /// [`Siblings::skip_tokens`]: crate::node::Siblings::skip_tokens
 /// [`Walk::skip_tokens`]: crate::node::Walk::skip_tokens
 ///
@@ -68,6 +92,57 @@ impl<I> SkipTokens<I> {
     pub(crate) const fn new(iter: I) -> Self {
         Self { iter }
     }
+
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::{SkipTokens, Walk};
+    ///
+    /// let mut it = SkipTokens::<Walk<u32, syntree::Span>>::empty();
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn empty() -> Self
+    where
+        I: Default,
+    {
+        Self::default()
+    }
+
+    /// Convert this iterator back into the iterator it wraps.
+    ///
+    /// This allows for switching between iterating over only nodes and
+    /// iterating over both nodes and tokens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "n1" => {},
+    ///     ("t1", 1),
+    ///     "n2" => {},
+    ///     ("t2", 1),
+    ///     "n3" => {},
+    /// };
+    ///
+    /// let mut it = tree.children().skip_tokens();
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("n1"));
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("n2"));
+    ///
+    /// let mut it = it.into_inner();
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("t2"));
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("n3"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
 }
 
 impl<'a, I, T: 'a, S: 'a> Iterator for SkipTokens<I>
@@ -86,6 +161,25 @@ where
             }
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.fold(init, move |acc, node| {
+            if matches!(node.kind(), Kind::Token) {
+                acc
+            } else {
+                f(acc, node)
+            }
+        })
+    }
 }
 
 impl<'a, I, T: 'a, S: 'a> DoubleEndedIterator for SkipTokens<I>
@@ -102,6 +196,20 @@ where
             }
         }
     }
+
+    #[inline]
+    fn rfold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.rfold(init, move |acc, node| {
+            if matches!(node.kind(), Kind::Token) {
+                acc
+            } else {
+                f(acc, node)
+            }
+        })
+    }
 }
 
 impl<'a, I, T: 'a, S: 'a> FusedIterator for SkipTokens<I> where
@@ -0,0 +1,214 @@
+use core::iter::FusedIterator;
+
+use crate::node::Node;
+
+/// Wrapped around an iterator that excludes trivia tokens, such as whitespace
+/// and comments declared through
+/// [`Builder::token_trivia`][crate::Builder::token_trivia]. Nodes and
+/// significant tokens are kept.
+///
+/// Note that this iterator also implements [Default] if the wrapped iterator
+/// does, allowing it to effectively create an empty iterator in case a
+/// particular node is not available:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("token", 1)
+///     }
+/// };
+///
+/// let mut it = tree.first().and_then(|n| n.next()).map(|n| n.children().skip_trivia()).unwrap_or_default();
+/// assert!(it.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// See [`Children::skip_trivia`], [`Siblings::skip_trivia`] or
+/// [`Walk::skip_trivia`].
+///
+/// Since it's not known up front how many nodes will be skipped, this only
+/// reports an upper bound through [`size_hint`][Iterator::size_hint] even if
+/// the wrapped iterator is exact.
+///
+/// [`Children::skip_trivia`]: crate::node::Children::skip_trivia
+/// [`Siblings::skip_trivia`]: crate::node::Siblings::skip_trivia
+/// [`Walk::skip_trivia`]: crate::node::Walk::skip_trivia
+///
+/// # Examples
+///
+/// ```
+/// let mut tree = syntree::Builder::new();
+///
+/// tree.open("call")?;
+/// tree.token("ident", 3)?;
+/// tree.token_trivia("ws", 1)?;
+/// tree.open("args")?;
+/// tree.token("lit", 1)?;
+/// tree.token_trivia("comment", 5)?;
+/// tree.close()?;
+/// tree.close()?;
+///
+/// let tree = tree.build()?;
+///
+/// let values = tree.walk().skip_trivia().map(|n| *n.value()).collect::<Vec<_>>();
+/// assert_eq!(values, ["call", "ident", "args", "lit"]);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct SkipTrivia<I> {
+    iter: I,
+}
+
+impl<I> SkipTrivia<I> {
+    #[inline]
+    pub(crate) const fn new(iter: I) -> Self {
+        Self { iter }
+    }
+
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::{SkipTrivia, Walk};
+    ///
+    /// let mut it = SkipTrivia::<Walk<u32, syntree::Span>>::empty();
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn empty() -> Self
+    where
+        I: Default,
+    {
+        Self::default()
+    }
+
+    /// Convert this iterator back into the iterator it wraps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.token_trivia("ws", 1)?;
+    /// tree.token("lit", 1)?;
+    /// tree.token_trivia("ws", 1)?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// let mut it = tree.children().skip_trivia();
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("lit"));
+    ///
+    /// let mut it = it.into_inner();
+    /// assert_eq!(it.next().map(|n| *n.value()), Some("ws"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<'a, I, T: 'a, S: 'a> Iterator for SkipTrivia<I>
+where
+    I: Iterator<Item = Node<'a, T, S>>,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.iter.next()?;
+
+            if !node.is_trivia() {
+                return Some(node);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.fold(
+            init,
+            move |acc, node| {
+                if !node.is_trivia() {
+                    f(acc, node)
+                } else {
+                    acc
+                }
+            },
+        )
+    }
+}
+
+impl<'a, I, T: 'a, S: 'a> DoubleEndedIterator for SkipTrivia<I>
+where
+    I: DoubleEndedIterator<Item = Node<'a, T, S>>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.iter.next_back()?;
+
+            if !node.is_trivia() {
+                return Some(node);
+            }
+        }
+    }
+
+    #[inline]
+    fn rfold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.rfold(
+            init,
+            move |acc, node| {
+                if !node.is_trivia() {
+                    f(acc, node)
+                } else {
+                    acc
+                }
+            },
+        )
+    }
+}
+
+impl<'a, I, T: 'a, S: 'a> FusedIterator for SkipTrivia<I> where
+    I: FusedIterator<Item = Node<'a, T, S>>
+{
+}
+
+impl<I> Clone for SkipTrivia<I>
+where
+    I: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<I> Default for SkipTrivia<I>
+where
+    I: Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            iter: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use alloc::vec::Vec;
+
+use crate::node::{Children, Node};
+use crate::span::TreeSpan;
+use crate::tree::Kind;
+
+/// A cursor for navigating a tree one step at a time, constructed through
+/// [`Tree::cursor`] or [`Node::cursor`].
+///
+/// The cursor remembers the nodes it descended through, so moving back to the
+/// parent doesn't have to look anything up. Every method which moves the
+/// cursor returns `true` if it succeeded, and leaves the cursor where it was
+/// otherwise.
+///
+/// A cursor constructed from a [`Node`] is confined to the subtree of that
+/// node, while one constructed from a [`Tree`] can move between its roots.
+///
+/// [`Tree`]: crate::Tree
+/// [`Tree::cursor`]: crate::Tree::cursor
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "fn" => {
+///         ("ident", 3),
+///         "block" => {
+///             ("open", 1),
+///             ("close", 1),
+///         },
+///     },
+///     ("eof", 0),
+/// };
+///
+/// let mut cursor = tree.cursor().ok_or("missing cursor")?;
+/// assert_eq!(*cursor.node().value(), "fn");
+///
+/// assert!(cursor.goto_first_child());
+/// assert!(cursor.goto_next_sibling());
+/// assert!(cursor.goto_first_child());
+/// assert_eq!(*cursor.node().value(), "open");
+/// assert_eq!(cursor.depth(), 2);
+///
+/// let path = cursor.path().iter().map(|n| *n.value()).collect::<Vec<_>>();
+/// assert_eq!(path, ["fn", "block"]);
+///
+/// assert!(cursor.goto_parent());
+/// assert!(cursor.goto_parent());
+/// assert!(!cursor.goto_parent());
+///
+/// assert!(cursor.goto_next_sibling());
+/// assert_eq!(*cursor.node().value(), "eof");
+/// assert!(!cursor.goto_next_sibling());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct Cursor<'a, T, S> {
+    /// The node the cursor is currently at.
+    node: Node<'a, T, S>,
+    /// The nodes the cursor descended through to reach the current node.
+    parents: Vec<Node<'a, T, S>>,
+    /// The roots of the tree, if the cursor can move between them.
+    roots: Option<Children<'a, T, S>>,
+}
+
+impl<'a, T, S> Cursor<'a, T, S> {
+    #[inline]
+    pub(crate) const fn new(node: Node<'a, T, S>, roots: Option<Children<'a, T, S>>) -> Self {
+        Self {
+            node,
+            parents: Vec::new(),
+            roots,
+        }
+    }
+
+    /// Get the node the cursor is currently at.
+    #[must_use]
+    #[inline]
+    pub fn node(&self) -> Node<'a, T, S> {
+        self.node
+    }
+
+    /// Get the depth of the current node, relative to the node the cursor was
+    /// constructed at or the roots of the tree.
+    #[must_use]
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Get the ancestors of the current node which the cursor descended
+    /// through, from the outermost to the parent of the current node.
+    ///
+    /// This is empty when the cursor is at depth zero.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &[Node<'a, T, S>] {
+        &self.parents
+    }
+
+    /// Move the cursor to the first child of the current node.
+    ///
+    /// Returns `false` if the current node has no children.
+    pub fn goto_first_child(&mut self) -> bool {
+        let Some(first) = self.node.first() else {
+            return false;
+        };
+
+        self.parents.push(self.node);
+        self.node = first;
+        true
+    }
+
+    /// Move the cursor to the next sibling of the current node.
+    ///
+    /// Returns `false` if the current node is the last child of its parent. A
+    /// cursor constructed from a [`Node`] never moves past the siblings of
+    /// that node.
+    pub fn goto_next_sibling(&mut self) -> bool {
+        if self.parents.is_empty() && self.roots.is_none() {
+            return false;
+        }
+
+        let Some(next) = self.node.next() else {
+            return false;
+        };
+
+        self.node = next;
+        true
+    }
+
+    /// Move the cursor to the parent of the current node.
+    ///
+    /// This takes constant time. Returns `false` if the cursor is at depth
+    /// zero.
+    pub fn goto_parent(&mut self) -> bool {
+        let Some(parent) = self.parents.pop() else {
+            return false;
+        };
+
+        self.node = parent;
+        true
+    }
+}
+
+impl<'a, T, S> Cursor<'a, T, S>
+where
+    S: TreeSpan,
+{
+    /// Move the cursor to the token which covers `offset`, starting from depth
+    /// zero.
+    ///
+    /// A non-empty token covers the offsets from its start up to but not
+    /// including its end. Where no such token exists, a zero-width token at
+    /// `offset` is used instead. Returns `false` if no token covers `offset`,
+    /// in which case the cursor is not moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "fn" => {
+    ///         ("ident", 3),
+    ///         "block" => {
+    ///             ("open", 1),
+    ///             ("close", 1),
+    ///         },
+    ///     },
+    ///     ("eof", 0),
+    /// };
+    ///
+    /// let mut cursor = tree.cursor().ok_or("missing cursor")?;
+    ///
+    /// assert!(cursor.goto_token_at(4));
+    /// assert_eq!(*cursor.node().value(), "close");
+    /// assert_eq!(cursor.depth(), 2);
+    ///
+    /// assert!(cursor.goto_token_at(5));
+    /// assert_eq!(*cursor.node().value(), "eof");
+    /// assert_eq!(cursor.depth(), 0);
+    ///
+    /// assert!(!cursor.goto_token_at(6));
+    /// assert_eq!(*cursor.node().value(), "eof");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn goto_token_at(&mut self, offset: usize) -> bool {
+        let found = match &self.roots {
+            Some(roots) => covering(roots.clone(), offset),
+            None => {
+                let top = self.parents.first().copied().unwrap_or(self.node);
+                covering(Some(top), offset)
+            }
+        };
+
+        let Some(mut node) = found else {
+            return false;
+        };
+
+        let mut parents = Vec::new();
+
+        while node.kind() == Kind::Node {
+            let Some(child) = covering(node.children(), offset) else {
+                return false;
+            };
+
+            parents.push(node);
+            node = child;
+        }
+
+        self.node = node;
+        self.parents = parents;
+        true
+    }
+}
+
+impl<T, S> Clone for Cursor<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            node: self.node,
+            parents: self.parents.clone(),
+            roots: self.roots.clone(),
+        }
+    }
+}
+
+/// Find the first node in `nodes` which covers `offset`, or the first
+/// zero-width node at `offset` if there is none.
+fn covering<'a, T, S, I>(nodes: I, offset: usize) -> Option<Node<'a, T, S>>
+where
+    S: TreeSpan,
+    I: IntoIterator<Item = Node<'a, T, S>>,
+{
+    let mut empty = None;
+
+    for node in nodes {
+        let range = node.span().range();
+
+        if range.contains(&offset) {
+            return Some(node);
+        }
+
+        if empty.is_none() && range.start == offset && range.end == offset {
+            empty = Some(node);
+        }
+    }
+
+    empty
+}
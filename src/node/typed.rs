@@ -0,0 +1,138 @@
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+use crate::node::Node;
+
+/// A typed wrapper around a [`Node`], such as the nodes of an abstract syntax
+/// tree layered on top of a concrete one.
+///
+/// Wrappers can be written by hand, or generated with the
+/// [`typed_node!`][crate::typed_node] macro.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::node::TypedNode;
+/// use syntree::{Node, Span};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Syntax {
+///     Number,
+///     Lit,
+/// }
+///
+/// struct Number<'a>(Node<'a, Syntax, Span>);
+///
+/// impl<'a> TypedNode<'a, Syntax, Span> for Number<'a> {
+///     fn cast(node: Node<'a, Syntax, Span>) -> Option<Self> {
+///         (*node.value() == Syntax::Number).then_some(Self(node))
+///     }
+///
+///     fn node(&self) -> Node<'a, Syntax, Span> {
+///         self.0
+///     }
+/// }
+///
+/// let tree = syntree::tree! {
+///     Syntax::Number => {
+///         (Syntax::Lit, 2)
+///     },
+///     (Syntax::Lit, 1)
+/// };
+///
+/// let numbers = tree.children().of_type::<Number>().collect::<Vec<_>>();
+/// assert_eq!(numbers.len(), 1);
+/// assert_eq!(numbers[0].node().span().range(), 0..2);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub trait TypedNode<'a, T, S>: Sized {
+    /// Cast the given `node` into this type, returning `None` if it is not of
+    /// the expected type.
+    fn cast(node: Node<'a, T, S>) -> Option<Self>;
+
+    /// Access the wrapped node.
+    fn node(&self) -> Node<'a, T, S>;
+}
+
+/// Wrapped around an iterator that only yields nodes which can be cast to the
+/// typed node `N`.
+///
+/// See [`Children::of_type`], [`Walk::of_type`] or
+/// [`Node::children_of_type`].
+///
+/// [`Children::of_type`]: crate::node::Children::of_type
+/// [`Walk::of_type`]: crate::node::Walk::of_type
+pub struct OfType<I, N> {
+    iter: I,
+    _marker: PhantomData<fn() -> N>,
+}
+
+impl<I, N> OfType<I, N> {
+    #[inline]
+    pub(crate) const fn new(iter: I) -> Self {
+        Self {
+            iter,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, I, N, T: 'a, S: 'a> Iterator for OfType<I, N>
+where
+    I: Iterator<Item = Node<'a, T, S>>,
+    N: TypedNode<'a, T, S>,
+{
+    type Item = N;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find_map(N::cast)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<'a, I, N, T: 'a, S: 'a> DoubleEndedIterator for OfType<I, N>
+where
+    I: DoubleEndedIterator<Item = Node<'a, T, S>>,
+    N: TypedNode<'a, T, S>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(node) = N::cast(self.iter.next_back()?) {
+                return Some(node);
+            }
+        }
+    }
+}
+
+impl<'a, I, N, T: 'a, S: 'a> FusedIterator for OfType<I, N>
+where
+    I: FusedIterator<Item = Node<'a, T, S>>,
+    N: TypedNode<'a, T, S>,
+{
+}
+
+impl<I, N> Clone for OfType<I, N>
+where
+    I: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.iter.clone())
+    }
+}
+
+impl<I, N> Default for OfType<I, N>
+where
+    I: Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(I::default())
+    }
+}
@@ -0,0 +1,197 @@
+use crate::node::Node;
+use crate::tree::Kind;
+
+/// Controls how traversal proceeds after a [`Visitor`] has entered a node.
+///
+/// See [`Visitor::enter_node`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VisitControl {
+    /// Continue by visiting the children of the node.
+    Continue,
+    /// Skip the children of the node, continuing with
+    /// [`Visitor::leave_node`] for it.
+    SkipSubtree,
+    /// Stop the traversal.
+    ///
+    /// Before the traversal returns, [`Visitor::leave_node`] is called for the
+    /// node which was just entered and then for each of its ancestors which
+    /// were entered during the traversal, innermost first. So every call to
+    /// [`Visitor::enter_node`] is always balanced by a call to
+    /// [`Visitor::leave_node`].
+    Stop,
+}
+
+/// A visitor over the nodes of a tree, which is driven by
+/// [`Tree::visit`][crate::Tree::visit] or [`Node::visit`].
+///
+/// Nodes of kind [`Kind::Node`] are passed to [`Visitor::enter_node`] before
+/// their children are visited and to [`Visitor::leave_node`] after, while
+/// nodes of kind [`Kind::Token`] are passed to [`Visitor::token`]. All
+/// methods have default implementations which do nothing, so only the ones
+/// of interest have to be implemented.
+///
+/// The traversal is not recursive, so arbitrarily deep trees can be visited
+/// without risking a stack overflow.
+///
+/// # Examples
+///
+/// A visitor which pretty-prints a tree in the same way as
+/// [`print::print`][crate::print::print]:
+///
+/// ```
+/// use std::fmt::Write;
+///
+/// use syntree::{Node, Span, Visitor, VisitControl};
+///
+/// #[derive(Default)]
+/// struct Printer {
+///     out: String,
+///     depth: usize,
+/// }
+///
+/// impl Printer {
+///     fn line(&mut self, node: Node<'_, &str, Span>, suffix: &str) {
+///         let _ = writeln!(
+///             self.out,
+///             "{:indent$}{:?}@{}{suffix}",
+///             "",
+///             node.value(),
+///             node.span(),
+///             indent = self.depth * 2
+///         );
+///     }
+/// }
+///
+/// impl<'a> Visitor<'a, &'a str, Span> for Printer {
+///     fn enter_node(&mut self, node: Node<'a, &'a str, Span>) -> VisitControl {
+///         self.line(node, "");
+///         self.depth += 1;
+///         VisitControl::Continue
+///     }
+///
+///     fn leave_node(&mut self, _: Node<'a, &'a str, Span>) {
+///         self.depth -= 1;
+///     }
+///
+///     fn token(&mut self, node: Node<'a, &'a str, Span>) {
+///         self.line(node, " +");
+///     }
+/// }
+///
+/// let tree = syntree::tree! {
+///     "number" => {
+///         ("lit", 3),
+///     },
+///     ("whitespace", 1),
+///     "operator" => {
+///         ("plus", 1)
+///     },
+/// };
+///
+/// let mut printer = Printer::default();
+/// tree.visit(&mut printer);
+///
+/// let mut expected = Vec::new();
+/// syntree::print::print(&mut expected, &tree)?;
+///
+/// assert_eq!(printer.out, String::from_utf8(expected)?);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub trait Visitor<'a, T, S> {
+    /// Called when entering a node of kind [`Kind::Node`], before its
+    /// children are visited. The returned [`VisitControl`] determines how the
+    /// traversal proceeds.
+    #[inline]
+    fn enter_node(&mut self, node: Node<'a, T, S>) -> VisitControl {
+        _ = node;
+        VisitControl::Continue
+    }
+
+    /// Called when leaving a node of kind [`Kind::Node`], after its children
+    /// have been visited or skipped.
+    #[inline]
+    fn leave_node(&mut self, node: Node<'a, T, S>) {
+        _ = node;
+    }
+
+    /// Called for each node of kind [`Kind::Token`].
+    #[inline]
+    fn token(&mut self, node: Node<'a, T, S>) {
+        _ = node;
+    }
+}
+
+/// Visit the subtree rooted at `root`, returning `false` if the traversal
+/// was stopped.
+pub(crate) fn visit_subtree<'a, T, S, V>(root: Node<'a, T, S>, visitor: &mut V) -> bool
+where
+    V: ?Sized + Visitor<'a, T, S>,
+{
+    let mut current = root;
+
+    loop {
+        let descend = match current.kind() {
+            Kind::Token => {
+                visitor.token(current);
+                false
+            }
+            Kind::Node => match visitor.enter_node(current) {
+                VisitControl::Continue => true,
+                VisitControl::SkipSubtree => false,
+                VisitControl::Stop => {
+                    unwind(root, current, visitor);
+                    return false;
+                }
+            },
+        };
+
+        if descend {
+            if let Some(first) = current.first() {
+                current = first;
+                continue;
+            }
+        }
+
+        // Leave nodes until one with a next sibling is found.
+        loop {
+            if let Kind::Node = current.kind() {
+                visitor.leave_node(current);
+            }
+
+            if current.same(&root) {
+                return true;
+            }
+
+            if let Some(next) = current.next() {
+                current = next;
+                break;
+            }
+
+            let Some(parent) = current.parent() else {
+                return true;
+            };
+
+            current = parent;
+        }
+    }
+}
+
+/// Leave `current` and all of its ancestors up to and including `root`.
+fn unwind<'a, T, S, V>(root: Node<'a, T, S>, mut current: Node<'a, T, S>, visitor: &mut V)
+where
+    V: ?Sized + Visitor<'a, T, S>,
+{
+    loop {
+        visitor.leave_node(current);
+
+        if current.same(&root) {
+            return;
+        }
+
+        let Some(parent) = current.parent() else {
+            return;
+        };
+
+        current = parent;
+    }
+}
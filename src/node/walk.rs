@@ -1,13 +1,32 @@
-use std::iter::FusedIterator;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
 
 use crate::links::Links;
-use crate::node::{Event, SkipTokens, WalkEvents};
+use crate::node::{
+    Event, Matching, OfType, OnlyTokens, SkipTokens, SkipTrivia, TypedNode, WalkEvents, WithContext,
+};
 use crate::non_max::NonMax;
 use crate::Node;
 
 /// An iterator that walks over the entire tree, visiting every node exactly
 /// once.
 ///
+/// Note that this iterator also implements [Default], allowing it to
+/// effectively create an empty iterator in case a particular node is not
+/// available:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "child" => {}
+///     }
+/// };
+///
+/// let mut it = tree.first().and_then(|n| n.next()).map(|n| n.walk()).unwrap_or_default();
+/// assert!(it.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
 /// See [`Tree::walk`][crate::Tree::walk] or [`Node::walk`].
 ///
 /// # Examples
@@ -41,19 +60,106 @@ use crate::Node;
 /// assert_eq!(c5.walk().map(|n| *n.value()).collect::<Vec<_>>(), Vec::<&str>::new());
 /// # Ok::<_, Box<dyn std::error::Error>>(())
 /// ```
+///
+/// Walking a whole tree through [`Tree::walk`][crate::Tree::walk] visits every
+/// node in it, so its [`size_hint`][Iterator::size_hint] is exact. Walking
+/// from a node only reports an upper bound, since the size of its subtree is
+/// not stored.
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "c1" => {
+///             "c2" => {},
+///         },
+///         "c3" => {}
+///     }
+/// };
+///
+/// let mut it = tree.walk();
+/// assert_eq!(it.size_hint(), (4, Some(4)));
+/// it.next();
+/// assert_eq!(it.size_hint(), (3, Some(3)));
+///
+/// let root = tree.first().ok_or("missing root node")?;
+/// let (lower, upper) = root.walk().size_hint();
+/// assert!(lower <= 3 && upper >= Some(3));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
 pub struct Walk<'a, T, S> {
     iter: WalkEvents<'a, T, S>,
+    /// An upper bound on the number of remaining nodes.
+    remaining: usize,
+    /// If `remaining` is exact.
+    exact: bool,
 }
 
 impl<'a, T, S> Walk<'a, T, S> {
-    /// Construct a new walk.
+    /// Construct a new walk, which visits at most `remaining` nodes or exactly
+    /// that many if `exact` is set.
     #[inline]
-    pub(crate) const fn new(tree: &'a [Links<T, S>], node: Option<NonMax>) -> Self {
+    pub(crate) const fn new(
+        tree: &'a [Links<T, S>],
+        node: Option<NonMax>,
+        remaining: usize,
+        exact: bool,
+    ) -> Self {
         Self {
             iter: WalkEvents::new(tree, node),
+            remaining,
+            exact,
+        }
+    }
+
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::Walk;
+    ///
+    /// let mut it = Walk::<u32, syntree::Span>::empty();
+    /// assert!(it.is_empty_hint());
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            iter: WalkEvents::empty(),
+            remaining: 0,
+            exact: true,
         }
     }
 
+    /// Test if the iterator is known to produce no more elements.
+    ///
+    /// This is cheap to compute, but can only be relied on when it returns
+    /// `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {}
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut it = root.walk();
+    /// assert!(!it.is_empty_hint());
+    /// assert!(it.next().is_some());
+    /// assert!(it.is_empty_hint());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_empty_hint(&self) -> bool {
+        self.iter.is_empty_hint()
+    }
+
     /// Get the next element with a corresponding depth.
     ///
     /// Alternatively you can use [`WithDepths`] through [`Walk::with_depths`].
@@ -88,11 +194,25 @@ impl<'a, T, S> Walk<'a, T, S> {
             let (event, node) = self.iter.next()?;
 
             if !matches!(event, Event::Up) {
+                self.remaining = self.remaining.saturating_sub(1);
                 return Some((depth, node));
             }
         }
     }
 
+    /// Get the next event from the underlying [`WalkEvents`], keeping track
+    /// of the number of remaining nodes.
+    #[inline]
+    pub(crate) fn next_event(&mut self) -> Option<(Event, Node<'a, T, S>)> {
+        let (event, node) = self.iter.next()?;
+
+        if !matches!(event, Event::Up) {
+            self.remaining = self.remaining.saturating_sub(1);
+        }
+
+        Some((event, node))
+    }
+
     /// Convert this iterator into one which includes depths.
     ///
     /// # Examples
@@ -118,6 +238,65 @@ impl<'a, T, S> Walk<'a, T, S> {
         WithDepths { iter: self }
     }
 
+    /// Convert this iterator into one which includes depths and whether each
+    /// node is the last among its siblings.
+    ///
+    /// This is what is needed to draw the guides of a tree, since whether a
+    /// node is last decides if the guide for its depth continues below it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "c1" => {
+    ///             "c2" => {},
+    ///             "c3" => {},
+    ///         },
+    ///         "c4" => {},
+    ///     }
+    /// };
+    ///
+    /// let mut it = tree.walk().with_positions().map(|(d, last, n)| (d, last, *n.value()));
+    ///
+    /// assert!(it.eq([
+    ///     (0, true, "root"),
+    ///     (1, false, "c1"),
+    ///     (2, false, "c2"),
+    ///     (2, true, "c3"),
+    ///     (1, true, "c4"),
+    /// ]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_positions(self) -> WithPositions<'a, T, S> {
+        WithPositions { iter: self }
+    }
+
+    /// Convert this iterator into one which includes the parent of each node.
+    ///
+    /// See [`WithParents`] for documentation.
+    #[inline]
+    #[must_use]
+    pub fn with_parents(self) -> WithParents<'a, T, S> {
+        WithParents {
+            iter: self,
+            parents: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// Convert this iterator into one which keeps track of the ancestors of
+    /// each node.
+    ///
+    /// See [`WithContext`] for documentation.
+    #[inline]
+    #[must_use]
+    pub fn with_context(self) -> WithContext<'a, T, S> {
+        WithContext::new(self)
+    }
+
     /// Construct a [`SkipTokens`] iterator from the remainder of this
     /// iterator. This filters out [`Kind::Token`][crate::Kind::Token] elements.
     ///
@@ -127,6 +306,71 @@ impl<'a, T, S> Walk<'a, T, S> {
     pub fn skip_tokens(self) -> SkipTokens<Self> {
         SkipTokens::new(self)
     }
+
+    /// Construct an [`OnlyTokens`] iterator from the remainder of this
+    /// iterator. This filters out [`Kind::Node`][crate::Kind::Node] elements.
+    ///
+    /// See [`OnlyTokens`] for documentation.
+    #[inline]
+    #[must_use]
+    pub fn only_tokens(self) -> OnlyTokens<Self> {
+        OnlyTokens::new(self)
+    }
+
+    /// Construct a [`SkipTrivia`] iterator from the remainder of this
+    /// iterator. This filters out trivia tokens.
+    ///
+    /// See [`SkipTrivia`] for documentation.
+    #[inline]
+    #[must_use]
+    pub fn skip_trivia(self) -> SkipTrivia<Self> {
+        SkipTrivia::new(self)
+    }
+
+    /// Construct a [`Matching`] iterator from the remainder of this iterator.
+    /// This only yields nodes whose value matches `predicate`.
+    ///
+    /// See [`Matching`] for documentation.
+    #[inline]
+    #[must_use]
+    pub fn matching<P>(self, predicate: P) -> Matching<Self, P>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        Matching::new(self, predicate)
+    }
+
+    /// Construct an [`OfType`] iterator from the remainder of this iterator.
+    /// This only yields nodes which can be cast to the typed node `N`.
+    ///
+    /// See [`TypedNode`] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// syntree::typed_node! {
+    ///     struct Lit(&'static str) where |value| value.starts_with("lit");
+    /// }
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => { ("lit1", 1) },
+    ///         ("ws", 1),
+    ///         ("lit2", 2),
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree.walk().of_type::<Lit>().count(), 2);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn of_type<N>(self) -> OfType<Self, N>
+    where
+        N: TypedNode<'a, T, S>,
+    {
+        OfType::new(self)
+    }
 }
 
 impl<T, S> Clone for Walk<'_, T, S> {
@@ -134,6 +378,8 @@ impl<T, S> Clone for Walk<'_, T, S> {
     fn clone(&self) -> Self {
         Self {
             iter: self.iter.clone(),
+            remaining: self.remaining,
+            exact: self.exact,
         }
     }
 }
@@ -141,9 +387,7 @@ impl<T, S> Clone for Walk<'_, T, S> {
 impl<T, S> Default for Walk<'_, T, S> {
     #[inline]
     fn default() -> Self {
-        Self {
-            iter: WalkEvents::default(),
-        }
+        Self::empty()
     }
 }
 
@@ -156,10 +400,22 @@ impl<'a, T, S> Iterator for Walk<'a, T, S> {
             let (e, node) = self.iter.next()?;
 
             if !matches!(e, Event::Up) {
+                self.remaining = self.remaining.saturating_sub(1);
                 return Some(node);
             }
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.exact {
+            (self.remaining, Some(self.remaining))
+        } else if self.iter.is_empty_hint() {
+            (0, Some(0))
+        } else {
+            (1, Some(self.remaining))
+        }
+    }
 }
 
 impl<T, S> FusedIterator for Walk<'_, T, S> {}
@@ -167,6 +423,22 @@ impl<T, S> FusedIterator for Walk<'_, T, S> {}
 /// An iterator that walks over the entire tree, visiting every node exactly
 /// once. This is constructed with [`Walk::with_depths`].
 ///
+/// Note that this iterator also implements [Default], allowing it to
+/// effectively create an empty iterator in case a particular node is not
+/// available:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "child" => {}
+///     }
+/// };
+///
+/// let mut it = tree.first().and_then(|n| n.next()).map(|n| n.walk().with_depths()).unwrap_or_default();
+/// assert!(it.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
 /// # Examples
 ///
 /// ```
@@ -199,6 +471,55 @@ pub struct WithDepths<'a, T, S> {
     iter: Walk<'a, T, S>,
 }
 
+impl<T, S> WithDepths<'_, T, S> {
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::WithDepths;
+    ///
+    /// let mut it = WithDepths::<u32, syntree::Span>::empty();
+    /// assert!(it.is_empty_hint());
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            iter: Walk::empty(),
+        }
+    }
+
+    /// Test if the iterator is known to produce no more elements.
+    ///
+    /// This is cheap to compute, but can only be relied on when it returns
+    /// `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {}
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut it = root.walk().with_depths();
+    /// assert!(!it.is_empty_hint());
+    /// assert!(it.next().is_some());
+    /// assert!(it.is_empty_hint());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_empty_hint(&self) -> bool {
+        self.iter.is_empty_hint()
+    }
+}
+
 impl<'a, T, S> Iterator for WithDepths<'a, T, S> {
     type Item = (usize, Node<'a, T, S>);
 
@@ -206,6 +527,11 @@ impl<'a, T, S> Iterator for WithDepths<'a, T, S> {
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next_with_depth()
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
 impl<T, S> FusedIterator for WithDepths<'_, T, S> {}
@@ -220,10 +546,156 @@ impl<T, S> Clone for WithDepths<'_, T, S> {
 }
 
 impl<T, S> Default for WithDepths<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// An iterator that walks over the entire tree, visiting every node exactly
+/// once. This is constructed with [`Walk::with_positions`].
+///
+/// Each node is yielded with its depth and whether it is the last among its
+/// siblings, as reported by [`Node::is_last`].
+pub struct WithPositions<'a, T, S> {
+    iter: Walk<'a, T, S>,
+}
+
+impl<'a, T, S> Iterator for WithPositions<'a, T, S> {
+    type Item = (usize, bool, Node<'a, T, S>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, node) = self.iter.next_with_depth()?;
+        Some((depth, node.is_last(), node))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> FusedIterator for WithPositions<'_, T, S> {}
+
+impl<T, S> Clone for WithPositions<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T, S> Default for WithPositions<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            iter: Walk::empty(),
+        }
+    }
+}
+
+/// An iterator that walks over the entire tree, visiting every node exactly
+/// once. This is constructed with [`Walk::with_parents`].
+///
+/// Each node is yielded together with its parent, which is `None` for nodes
+/// whose parent is not part of the walk. So when walking from a node, its
+/// children have no parent since the node itself is not visited.
+///
+/// Parents are tracked with a stack as the walk moves up and down the tree,
+/// rather than being looked up through [`Node::parent`] for every node.
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "fn" => {
+///         "body" => {
+///             "return" => {},
+///         },
+///         "return" => {},
+///     },
+/// };
+///
+/// let misplaced = tree
+///     .walk()
+///     .with_parents()
+///     .filter(|(parent, n)| *n.value() == "return" && parent.map(|p| *p.value()) != Some("body"))
+///     .count();
+///
+/// assert_eq!(misplaced, 1);
+///
+/// let root = tree.first().ok_or("missing root")?;
+///
+/// let pairs = root
+///     .walk()
+///     .with_parents()
+///     .map(|(parent, n)| (parent.map(|p| *p.value()), *n.value()))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(pairs, [(None, "body"), (Some("body"), "return"), (None, "return")]);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct WithParents<'a, T, S> {
+    iter: Walk<'a, T, S>,
+    /// The parents of the nodes being visited, from the outermost to the
+    /// innermost.
+    parents: Vec<Node<'a, T, S>>,
+    /// The last node visited.
+    last: Option<Node<'a, T, S>>,
+}
+
+impl<'a, T, S> Iterator for WithParents<'a, T, S> {
+    type Item = (Option<Node<'a, T, S>>, Node<'a, T, S>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (event, node) = self.iter.next_event()?;
+
+            match event {
+                Event::Up => {
+                    self.parents.pop();
+                    continue;
+                }
+                Event::Down => {
+                    self.parents.extend(self.last);
+                }
+                Event::Next => {}
+            }
+
+            self.last = Some(node);
+            return Some((self.parents.last().copied(), node));
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> FusedIterator for WithParents<'_, T, S> {}
+
+impl<T, S> Clone for WithParents<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            parents: self.parents.clone(),
+            last: self.last,
+        }
+    }
+}
+
+impl<T, S> Default for WithParents<'_, T, S> {
     #[inline]
     fn default() -> Self {
         Self {
-            iter: Walk::default(),
+            iter: Walk::empty(),
+            parents: Vec::new(),
+            last: None,
         }
     }
 }
@@ -0,0 +1,231 @@
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::slice;
+
+use crate::node::{Event, Node, Walk};
+
+/// A walk which keeps track of the ancestors of every node it visits, as
+/// returned by [`Walk::with_context`].
+///
+/// Since the [`WalkContext`] is borrowed from the walk, this isn't an
+/// [`Iterator`]. Nodes are instead visited by calling [`WithContext::next`] in
+/// a loop.
+///
+/// The ancestors of the first node visited are collected when it is reached,
+/// after which they are maintained as the walk moves up and down the tree.
+/// So the context always covers all ancestors of a node, even when walking
+/// from a node which isn't a root.
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "fn" => {
+///         "block" => {
+///             "let" => {
+///                 ("ident", 1),
+///             },
+///         },
+///     },
+///     ("ident", 1),
+/// };
+///
+/// let mut it = tree.walk().with_context();
+/// let mut in_block = Vec::new();
+///
+/// while let Some((node, cx)) = it.next() {
+///     if *node.value() == "ident" {
+///         in_block.push(cx.nearest(|n| *n.value() == "block").is_some());
+///     }
+/// }
+///
+/// assert_eq!(in_block, [true, false]);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct WithContext<'a, T, S> {
+    iter: Walk<'a, T, S>,
+    context: WalkContext<'a, T, S>,
+    /// The last node visited.
+    last: Option<Node<'a, T, S>>,
+    /// If the ancestors of the first node have been collected.
+    seeded: bool,
+}
+
+impl<'a, T, S> WithContext<'a, T, S> {
+    #[inline]
+    pub(crate) const fn new(iter: Walk<'a, T, S>) -> Self {
+        Self {
+            iter,
+            context: WalkContext { stack: Vec::new() },
+            last: None,
+            seeded: false,
+        }
+    }
+
+    /// Get the next node together with the context it's in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("lit", 1),
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// let mut it = tree.walk().with_context();
+    /// let mut depths = Vec::new();
+    ///
+    /// while let Some((node, cx)) = it.next() {
+    ///     depths.push((*node.value(), cx.depth()));
+    /// }
+    ///
+    /// assert_eq!(depths, [("root", 0), ("child", 1), ("lit", 2)]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[allow(clippy::should_implement_trait, clippy::type_complexity)]
+    pub fn next(&mut self) -> Option<(Node<'a, T, S>, &WalkContext<'a, T, S>)> {
+        loop {
+            let (event, node) = self.iter.next_event()?;
+
+            if self.seeded {
+                match event {
+                    Event::Up => {
+                        self.context.stack.pop();
+                        continue;
+                    }
+                    Event::Down => {
+                        self.context.stack.extend(self.last);
+                    }
+                    Event::Next => {}
+                }
+            } else {
+                if let Event::Up = event {
+                    continue;
+                }
+
+                self.context.stack.extend(node.ancestors().skip(1));
+                self.context.stack.reverse();
+                self.seeded = true;
+            }
+
+            self.last = Some(node);
+            return Some((node, &self.context));
+        }
+    }
+}
+
+/// The ancestors of a node being visited by [`WithContext`].
+pub struct WalkContext<'a, T, S> {
+    /// Ancestors from the outermost to the innermost.
+    stack: Vec<Node<'a, T, S>>,
+}
+
+impl<'a, T, S> WalkContext<'a, T, S> {
+    /// The depth of the node in the tree, which is the number of its
+    /// ancestors.
+    ///
+    /// Note that this differs from [`Walk::with_depths`] when walking from a
+    /// node which isn't a root, since that is relative to where the walk
+    /// started.
+    #[inline]
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Get the parent of the node.
+    #[inline]
+    #[must_use]
+    pub fn parent(&self) -> Option<Node<'a, T, S>> {
+        self.stack.last().copied()
+    }
+
+    /// Iterate over the ancestors of the node, from the innermost to the
+    /// outermost, excluding the node itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("lit", 1),
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// let mut it = tree.walk().with_context();
+    /// let mut paths = Vec::new();
+    ///
+    /// while let Some((node, cx)) = it.next() {
+    ///     paths.push(cx.ancestors().map(|n| *n.value()).collect::<Vec<_>>());
+    /// }
+    ///
+    /// assert_eq!(paths, [vec![], vec!["root"], vec!["child", "root"]]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn ancestors(&self) -> ContextAncestors<'_, 'a, T, S> {
+        ContextAncestors {
+            iter: self.stack.iter(),
+        }
+    }
+
+    /// Find the innermost ancestor of the node which matches `predicate`,
+    /// excluding the node itself.
+    ///
+    /// See [`WithContext`] for an example.
+    #[inline]
+    pub fn nearest<P>(&self, mut predicate: P) -> Option<Node<'a, T, S>>
+    where
+        P: FnMut(Node<'a, T, S>) -> bool,
+    {
+        self.ancestors().find(|n| predicate(*n))
+    }
+}
+
+/// An iterator over the ancestors in a [`WalkContext`], from the innermost to
+/// the outermost.
+///
+/// See [`WalkContext::ancestors`].
+pub struct ContextAncestors<'b, 'a, T, S> {
+    iter: slice::Iter<'b, Node<'a, T, S>>,
+}
+
+impl<'a, T, S> Iterator for ContextAncestors<'_, 'a, T, S> {
+    type Item = Node<'a, T, S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().copied()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for ContextAncestors<'_, '_, T, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next().copied()
+    }
+}
+
+impl<T, S> ExactSizeIterator for ContextAncestors<'_, '_, T, S> {}
+
+impl<T, S> FusedIterator for ContextAncestors<'_, '_, T, S> {}
+
+impl<T, S> Clone for ContextAncestors<'_, '_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::iter::FusedIterator;
+use core::iter::FusedIterator;
 
 use crate::links::Links;
 use crate::non_max::NonMax;
@@ -6,6 +6,13 @@ use crate::Node;
 
 /// An event indicating how a tree is being walked with [`WalkEvents`].
 ///
+/// Every node is entered exactly once through either [`Event::Next`] or
+/// [`Event::Down`]. A node which has children is also exited exactly once
+/// through [`Event::Up`] after all of its children have been walked, while
+/// tokens and other empty nodes are never exited. So to tell whether an
+/// [`Event::Up`] will follow for an entered node it is enough to check
+/// [`Node::is_empty`].
+///
 /// See [`WalkEvents`] for documentation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Event {
@@ -18,9 +25,133 @@ pub enum Event {
     Up,
 }
 
+impl Event {
+    /// Test if this event enters a node, which is the case for
+    /// [`Event::Next`] and [`Event::Down`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         "empty" => {},
+    ///     }
+    /// };
+    ///
+    /// let entered = tree
+    ///     .walk_events()
+    ///     .filter(|(e, _)| e.is_enter())
+    ///     .map(|(_, n)| *n.value())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(entered, ["root", "lit", "empty"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_enter(&self) -> bool {
+        matches!(self, Event::Next | Event::Down)
+    }
+
+    /// Test if this event exits a node, which is the case for [`Event::Up`].
+    ///
+    /// Only nodes which have children are exited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         "empty" => {},
+    ///     }
+    /// };
+    ///
+    /// let exited = tree
+    ///     .walk_events()
+    ///     .filter(|(e, _)| e.is_exit())
+    ///     .map(|(_, n)| *n.value())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(exited, ["root"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_exit(&self) -> bool {
+        matches!(self, Event::Up)
+    }
+
+    /// The change in depth caused by this event, which is `1` for
+    /// [`Event::Down`], `-1` for [`Event::Up`] and `0` for [`Event::Next`].
+    ///
+    /// Summing the deltas of the events emitted so far gives the depth of the
+    /// node which the last event is about.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("lit", 1),
+    ///         },
+    ///         ("ws", 1),
+    ///     }
+    /// };
+    ///
+    /// let mut depth = 0;
+    /// let mut depths = Vec::new();
+    ///
+    /// for (event, node) in tree.walk_events() {
+    ///     depth += event.depth_delta();
+    ///     depths.push((depth, *node.value()));
+    /// }
+    ///
+    /// assert_eq!(
+    ///     depths,
+    ///     [(0, "root"), (1, "child"), (2, "lit"), (1, "child"), (1, "ws"), (0, "root")]
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn depth_delta(&self) -> isize {
+        match self {
+            Event::Next => 0,
+            Event::Down => 1,
+            Event::Up => -1,
+        }
+    }
+}
+
 /// A low-level iterator which walks the tree while emitting [Event] instances
 /// indicating *how* the structure is being navigated.
 ///
+/// The node emitted with each event is always the node the event is about:
+/// the node being entered for [`Event::Next`] and [`Event::Down`], and the
+/// node being exited for [`Event::Up`]. This means that the events can be
+/// replayed into a [`Builder`] by opening entered nodes, closing them once
+/// exited, and closing entered nodes with no children immediately.
+///
+/// [`Builder`]: crate::Builder
+///
+/// Note that this iterator also implements [Default], allowing it to
+/// effectively create an empty iterator in case a particular node is not
+/// available:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "child" => {}
+///     }
+/// };
+///
+/// let mut it = tree.first().and_then(|n| n.next()).map(|n| n.walk_events()).unwrap_or_default();
+/// assert!(it.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
 /// See [`Tree::walk_events`][crate::Tree::walk_events] or
 /// [`Node::walk_events`][crate::Node::walk_events].
 ///
@@ -147,6 +278,55 @@ impl<'a, T, S> WalkEvents<'a, T, S> {
         }
     }
 
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::WalkEvents;
+    ///
+    /// let mut it = WalkEvents::<u32, syntree::Span>::empty();
+    /// assert!(it.is_empty_hint());
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            tree: &[],
+            node: None,
+            depth: 0,
+        }
+    }
+
+    /// Test if the iterator is known to produce no more elements.
+    ///
+    /// This is cheap to compute, but can only be relied on when it returns
+    /// `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {}
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut it = root.walk_events();
+    /// assert!(!it.is_empty_hint());
+    /// assert!(it.next().is_some());
+    /// assert!(it.is_empty_hint());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_empty_hint(&self) -> bool {
+        self.node.is_none()
+    }
+
     /// Get current depth.
     pub(crate) fn depth(&self) -> usize {
         self.depth
@@ -187,11 +367,7 @@ impl<T, S> Clone for WalkEvents<'_, T, S> {
 impl<T, S> Default for WalkEvents<'_, T, S> {
     #[inline]
     fn default() -> Self {
-        Self {
-            tree: &[],
-            node: None,
-            depth: 0,
-        }
+        Self::empty()
     }
 }
 
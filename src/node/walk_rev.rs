@@ -0,0 +1,398 @@
+use core::iter::FusedIterator;
+
+use crate::links::Links;
+use crate::node::{OfType, SkipTokens, TypedNode};
+use crate::non_max::NonMax;
+use crate::Node;
+
+/// An iterator that walks over the entire tree in reverse, visiting every
+/// node exactly once in the exact reverse order of [`Walk`].
+///
+/// This is useful to find the last node of some kind which precedes a
+/// particular position, such as the closest preceding definition.
+///
+/// Note that this iterator also implements [Default], allowing it to
+/// effectively create an empty iterator in case a particular node is not
+/// available:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "child" => {}
+///     }
+/// };
+///
+/// let mut it = tree.first().and_then(|n| n.next()).map(|n| n.walk_rev()).unwrap_or_default();
+/// assert!(it.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// See [`Tree::walk_rev`][crate::Tree::walk_rev] or [`Node::walk_rev`].
+///
+/// [`Walk`]: crate::node::Walk
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "c1" => {
+///             "c2" => {},
+///             "c3" => {},
+///             "c4" => {},
+///         },
+///         "c5" => {},
+///         "c6" => {}
+///     }
+/// };
+///
+/// assert!(
+///     tree.walk_rev().map(|n| *n.value()).eq(["c6", "c5", "c4", "c3", "c2", "c1", "root"])
+/// );
+///
+/// let root = tree.first().ok_or("missing root node")?;
+///
+/// assert!(
+///     root.walk_rev().map(|n| *n.value()).eq(["c6", "c5", "c4", "c3", "c2", "c1"])
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// Finding the last token which ends before an offset:
+///
+/// ```
+/// use syntree::Kind;
+///
+/// let tree = syntree::tree! {
+///     "fn" => {
+///         ("ident", 3),
+///         ("ws", 1),
+///         "block" => {
+///             ("open", 1),
+///             ("close", 1),
+///         }
+///     },
+///     ("ws", 1),
+/// };
+///
+/// let token = tree
+///     .walk_rev()
+///     .find(|n| n.kind() == Kind::Token && n.range().end <= 5)
+///     .ok_or("missing token")?;
+///
+/// assert_eq!(*token.value(), "open");
+/// assert_eq!(token.range(), 4..5);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct WalkRev<'a, T, S> {
+    /// The tree being iterated over.
+    tree: &'a [Links<T, S>],
+    /// The next node to yield.
+    node: Option<NonMax>,
+    /// The depth of the next node to yield.
+    depth: usize,
+}
+
+impl<'a, T, S> WalkRev<'a, T, S> {
+    /// Construct a new reverse walk, starting with the subtree of the last
+    /// node to walk at depth zero.
+    #[inline]
+    pub(crate) fn new(tree: &'a [Links<T, S>], last: Option<NonMax>) -> Self {
+        let mut this = Self {
+            tree,
+            node: None,
+            depth: 0,
+        };
+
+        this.node = last.and_then(|id| this.descend(id));
+        this
+    }
+
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::WalkRev;
+    ///
+    /// let mut it = WalkRev::<u32, syntree::Span>::empty();
+    /// assert!(it.is_empty_hint());
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            tree: &[],
+            node: None,
+            depth: 0,
+        }
+    }
+
+    /// Test if the iterator is known to produce no more elements.
+    ///
+    /// This is cheap to compute, but can only be relied on when it returns
+    /// `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {}
+    ///     }
+    /// };
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let mut it = root.walk_rev();
+    /// assert!(!it.is_empty_hint());
+    /// assert!(it.next().is_some());
+    /// assert!(it.is_empty_hint());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_empty_hint(&self) -> bool {
+        self.node.is_none()
+    }
+
+    /// Get the next element with a corresponding depth, which is the same
+    /// depth as [`Walk::next_with_depth`] reports for the same node.
+    ///
+    /// Alternatively you can use [`WalkRevWithDepths`] through
+    /// [`WalkRev::with_depths`].
+    ///
+    /// [`Walk::next_with_depth`]: crate::node::Walk::next_with_depth
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::iter;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "c1" => {
+    ///             "c2" => {},
+    ///             "c3" => {},
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let mut it = tree.walk_rev();
+    /// let it = iter::from_fn(move || it.next_with_depth());
+    /// let it = it.map(|(d, n)| (d, *n.value()));
+    ///
+    /// assert!(it.eq([(2, "c3"), (2, "c2"), (1, "c1"), (0, "root")]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn next_with_depth(&mut self) -> Option<(usize, Node<'a, T, S>)> {
+        let id = self.node.take()?;
+        let links = self.tree.get(id.get())?;
+        let depth = self.depth;
+
+        if let Some(prev) = links.prev {
+            self.node = self.descend(prev);
+        } else if let Some(depth) = self.depth.checked_sub(1) {
+            self.depth = depth;
+            self.node = links.parent;
+        }
+
+        Some((depth, Node::new(links, self.tree)))
+    }
+
+    /// Convert this iterator into one which includes depths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "c1" => {
+    ///             "c2" => {},
+    ///             "c3" => {},
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let mut it = tree.walk_rev().with_depths().map(|(d, n)| (d, *n.value()));
+    /// assert!(it.eq([(2, "c3"), (2, "c2"), (1, "c1"), (0, "root")]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_depths(self) -> WalkRevWithDepths<'a, T, S> {
+        WalkRevWithDepths { iter: self }
+    }
+
+    /// Construct a [`SkipTokens`] iterator from the remainder of this
+    /// iterator. This filters out [`Kind::Token`][crate::Kind::Token] elements.
+    ///
+    /// See [`SkipTokens`] for documentation.
+    #[inline]
+    #[must_use]
+    pub fn skip_tokens(self) -> SkipTokens<Self> {
+        SkipTokens::new(self)
+    }
+
+    /// Construct an [`OfType`] iterator from the remainder of this iterator.
+    /// This only yields nodes which can be cast to the typed node `N`.
+    ///
+    /// See [`TypedNode`] for documentation.
+    #[inline]
+    #[must_use]
+    pub fn of_type<N>(self) -> OfType<Self, N>
+    where
+        N: TypedNode<'a, T, S>,
+    {
+        OfType::new(self)
+    }
+
+    /// Descend to the last node in the subtree rooted at `id`, which is the
+    /// first node of that subtree to visit in reverse.
+    fn descend(&mut self, mut id: NonMax) -> Option<NonMax> {
+        loop {
+            let links = self.tree.get(id.get())?;
+
+            let Some(last) = links.last else {
+                return Some(id);
+            };
+
+            self.depth = self.depth.checked_add(1)?;
+            id = last;
+        }
+    }
+}
+
+impl<T, S> Clone for WalkRev<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            tree: self.tree,
+            node: self.node,
+            depth: self.depth,
+        }
+    }
+}
+
+impl<T, S> Default for WalkRev<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<'a, T, S> Iterator for WalkRev<'a, T, S> {
+    type Item = Node<'a, T, S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_with_depth()?.1)
+    }
+}
+
+impl<T, S> FusedIterator for WalkRev<'_, T, S> {}
+
+/// An iterator that walks over the entire tree in reverse, visiting every
+/// node exactly once. This is constructed with [`WalkRev::with_depths`].
+///
+/// Note that this iterator also implements [Default], allowing it to
+/// effectively create an empty iterator in case a particular node is not
+/// available:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "child" => {}
+///     }
+/// };
+///
+/// let mut it = tree.first().and_then(|n| n.next()).map(|n| n.walk_rev().with_depths()).unwrap_or_default();
+/// assert!(it.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "c1" => {
+///             "c2" => {},
+///             "c3" => {},
+///         },
+///         "c4" => {}
+///     }
+/// };
+///
+/// let root = tree.first().ok_or("missing root node")?;
+///
+/// assert_eq!(
+///     root.walk_rev().with_depths().map(|(d, n)| (d, *n.value())).collect::<Vec<_>>(),
+///     [(0, "c4"), (1, "c3"), (1, "c2"), (0, "c1")]
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct WalkRevWithDepths<'a, T, S> {
+    iter: WalkRev<'a, T, S>,
+}
+
+impl<T, S> WalkRevWithDepths<'_, T, S> {
+    /// Construct an empty iterator, which is the same as its [Default]
+    /// implementation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::WalkRevWithDepths;
+    ///
+    /// let mut it = WalkRevWithDepths::<u32, syntree::Span>::empty();
+    /// assert!(it.is_empty_hint());
+    /// assert!(it.next().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self {
+            iter: WalkRev::empty(),
+        }
+    }
+
+    /// Test if the iterator is known to produce no more elements.
+    ///
+    /// This is cheap to compute, but can only be relied on when it returns
+    /// `true`.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty_hint(&self) -> bool {
+        self.iter.is_empty_hint()
+    }
+}
+
+impl<'a, T, S> Iterator for WalkRevWithDepths<'a, T, S> {
+    type Item = (usize, Node<'a, T, S>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_with_depth()
+    }
+}
+
+impl<T, S> FusedIterator for WalkRevWithDepths<'_, T, S> {}
+
+impl<T, S> Clone for WalkRevWithDepths<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T, S> Default for WalkRevWithDepths<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
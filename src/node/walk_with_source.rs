@@ -0,0 +1,94 @@
+use core::iter::FusedIterator;
+
+use crate::node::{Node, Walk};
+use crate::span::TreeSpan;
+use crate::tree::Kind;
+
+/// A walk which pairs every node with the text it covers in a source, as
+/// returned by [`Tree::walk_with_source`] or [`Node::walk_with_source`].
+///
+/// The text is only looked up for tokens, so it is always `None` for nodes.
+/// It is also `None` for tokens whose span extends past the end of the
+/// source or doesn't fall on character boundaries, rather than panicking.
+///
+/// [`Tree::walk_with_source`]: crate::Tree::walk_with_source
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "number" => {
+///         ("lit", 3),
+///     },
+///     ("ws", 1),
+///     ("ident", 5),
+/// };
+///
+/// let texts = tree
+///     .walk_with_source("123 hi")
+///     .map(|(n, text)| (*n.value(), text))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(
+///     texts,
+///     [("number", None), ("lit", Some("123")), ("ws", Some(" ")), ("ident", None)]
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct WalkWithSource<'a, 's, T, S> {
+    iter: Walk<'a, T, S>,
+    source: &'s str,
+}
+
+impl<'a, 's, T, S> WalkWithSource<'a, 's, T, S> {
+    #[inline]
+    pub(crate) const fn new(iter: Walk<'a, T, S>, source: &'s str) -> Self {
+        Self { iter, source }
+    }
+}
+
+impl<'a, 's, T, S> Iterator for WalkWithSource<'a, 's, T, S>
+where
+    S: TreeSpan,
+{
+    type Item = (Node<'a, T, S>, Option<&'s str>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.iter.next()?;
+
+        let text = match node.kind() {
+            Kind::Token => self.source.get(node.span().range()),
+            Kind::Node => None,
+        };
+
+        Some((node, text))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> FusedIterator for WalkWithSource<'_, '_, T, S> where S: TreeSpan {}
+
+impl<T, S> Clone for WalkWithSource<'_, '_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            source: self.source,
+        }
+    }
+}
+
+impl<T, S> Default for WalkWithSource<'_, '_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            iter: Walk::empty(),
+            source: "",
+        }
+    }
+}
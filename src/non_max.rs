@@ -1,95 +1,67 @@
-/// Get the configured nonmax type.
-#[cfg(not(syntree_compact))]
-pub(crate) type NonMax = self::imp::NonMaxUsize;
-#[cfg(syntree_compact)]
-pub(crate) type NonMax = self::imp::NonMaxU32;
+use core::cmp::Ordering;
+use core::fmt;
+use core::num::NonZeroU32;
 
-#[cfg(not(syntree_compact))]
-mod imp {
-    use core::fmt;
-    use core::num::NonZeroUsize;
+/// The largest index which can be stored in a [`NonMax`].
+pub(crate) const MAX: usize = (u32::MAX - 1) as usize;
 
-    /// Helper struct which behaves exactly like `NonZeroUsize` except that it
-    /// rejects max values.
-    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-    #[repr(transparent)]
-    pub(crate) struct NonMaxUsize(NonZeroUsize);
+/// Helper struct which behaves exactly like `NonZeroU32` except that it rejects
+/// max values.
+///
+/// This is used for all internal references between nodes, which limits the
+/// number of nodes in a tree to [`MAX`] + 1 but keeps each reference at four
+/// bytes regardless of platform.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub(crate) struct NonMax(NonZeroU32);
 
-    impl NonMaxUsize {
-        /// Unchecked constructor.
-        #[inline]
-        pub(crate) unsafe fn new_unchecked(value: usize) -> Self {
-            Self(NonZeroUsize::new_unchecked(value ^ usize::MAX))
-        }
+impl NonMax {
+    /// Unchecked constructor.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `value` is no larger than [`MAX`].
+    #[inline]
+    pub(crate) unsafe fn new_unchecked(value: usize) -> Self {
+        Self(NonZeroU32::new_unchecked((value as u32) ^ u32::MAX))
+    }
 
-        #[inline]
-        pub(crate) const fn new(value: usize) -> Option<Self> {
-            match NonZeroUsize::new(value ^ usize::MAX) {
-                None => None,
-                Some(value) => Some(Self(value)),
-            }
+    #[inline]
+    pub(crate) const fn new(value: usize) -> Option<Self> {
+        if value > MAX {
+            return None;
         }
 
-        #[inline]
-        pub(crate) const fn get(self) -> usize {
-            self.0.get() ^ usize::MAX
+        match NonZeroU32::new((value as u32) ^ u32::MAX) {
+            None => None,
+            Some(value) => Some(Self(value)),
         }
     }
 
-    impl fmt::Debug for NonMaxUsize {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            self.get().fmt(f)
-        }
+    #[inline]
+    pub(crate) const fn get(self) -> usize {
+        (self.0.get() ^ u32::MAX) as usize
     }
 }
 
-#[cfg(syntree_compact)]
-mod imp {
-    use core::fmt;
-    use core::mem::size_of;
-    use core::num::NonZeroU32;
-
-    /// Helper struct which behaves exactly like `NonZeroU32` except that it rejects
-    /// max values.
-    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-    #[repr(transparent)]
-    pub(crate) struct NonMaxU32(NonZeroU32);
-
-    impl NonMaxU32 {
-        /// Unchecked constructor.
-        #[inline]
-        pub(crate) unsafe fn new_unchecked(value: usize) -> Self {
-            let value = value as u32;
-            Self(NonZeroU32::new_unchecked(value ^ u32::MAX))
-        }
-
-        #[inline]
-        pub(crate) const fn new(value: usize) -> Option<Self> {
-            let value = if size_of::<usize>() <= size_of::<u32>() {
-                value as u32
-            } else {
-                if value > u32::MAX as usize {
-                    return None;
-                }
-
-                value as u32
-            };
-
-            match NonZeroU32::new((value as u32) ^ u32::MAX) {
-                None => None,
-                Some(value) => Some(Self(value)),
-            }
-        }
+// Ordering has to be implemented manually, since the stored representation is
+// inverted.
+impl PartialOrd for NonMax {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-        #[inline]
-        pub(crate) const fn get(self) -> usize {
-            (self.0.get() ^ u32::MAX) as usize
-        }
+impl Ord for NonMax {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.get().cmp(&other.get())
     }
+}
 
-    impl fmt::Debug for NonMaxU32 {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            self.get().fmt(f)
-        }
+impl fmt::Debug for NonMax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
     }
 }
@@ -0,0 +1,121 @@
+//! An owned, recursive representation of trees.
+//!
+//! This is useful for interoperating with code which expects a plain
+//! recursive tree, and for writing fixtures by hand. Trees are converted into
+//! it with [`Tree::to_owned_nodes`] and back with [`Tree::from_owned_nodes`].
+//!
+//! Note that comparing or formatting a [`TreeNode`] recurses through its
+//! children, so very deep trees are better kept as a [`Tree`]. Dropping and
+//! the conversions themselves do not recurse.
+//!
+//! [`Tree`]: crate::Tree
+//! [`Tree::to_owned_nodes`]: crate::Tree::to_owned_nodes
+//! [`Tree::from_owned_nodes`]: crate::Tree::from_owned_nodes
+
+use alloc::vec::Vec;
+use core::mem::{self, ManuallyDrop};
+use core::ptr;
+
+use crate::span::Span;
+use crate::tree::Kind;
+
+/// A node in an owned, recursive tree.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::owned::TreeNode;
+/// use syntree::{Kind, Span, Tree};
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("lit", 3),
+///         "empty" => {}
+///     }
+/// };
+///
+/// let nodes = tree.to_owned_nodes();
+///
+/// assert_eq!(
+///     nodes,
+///     [TreeNode {
+///         value: "root",
+///         kind: Kind::Node,
+///         span: Span::new(0, 3),
+///         children: vec![
+///             TreeNode::token("lit", Span::new(0, 3)),
+///             TreeNode::node("empty", Span::point(3), Vec::new()),
+///         ],
+///     }]
+/// );
+///
+/// assert_eq!(Tree::from_owned_nodes(nodes)?, tree);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeNode<T, S = Span> {
+    /// The value of the node.
+    pub value: T,
+    /// The kind of the node.
+    pub kind: Kind,
+    /// The span of the node.
+    pub span: S,
+    /// The children of the node, which must be empty for tokens.
+    pub children: Vec<TreeNode<T, S>>,
+}
+
+impl<T, S> TreeNode<T, S> {
+    /// Construct a node with the given children.
+    #[must_use]
+    pub const fn node(value: T, span: S, children: Vec<TreeNode<T, S>>) -> Self {
+        Self {
+            value,
+            kind: Kind::Node,
+            span,
+            children,
+        }
+    }
+
+    /// Construct a token.
+    #[must_use]
+    pub const fn token(value: T, span: S) -> Self {
+        Self {
+            value,
+            kind: Kind::Token,
+            span,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<T, S> TreeNode<T, S> {
+    /// Take the node apart without dropping it.
+    pub(crate) fn into_parts(self) -> (T, Kind, S, Vec<TreeNode<T, S>>)
+    where
+        S: Copy,
+    {
+        let mut this = ManuallyDrop::new(self);
+        let children = mem::take(&mut this.children);
+        // SAFETY: The value is read exactly once and the node is never used
+        // or dropped again. The remaining fields don't need to be dropped,
+        // since the span is `Copy` and the children have been taken.
+        let value = unsafe { ptr::read(&this.value) };
+        (value, this.kind, this.span, children)
+    }
+}
+
+/// Nodes are dropped without recursing, so trees of any depth can be dropped.
+impl<T, S> Drop for TreeNode<T, S> {
+    fn drop(&mut self) {
+        if self.children.is_empty() {
+            return;
+        }
+
+        let mut stack = mem::take(&mut self.children);
+
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
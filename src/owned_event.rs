@@ -0,0 +1,44 @@
+/// An owned event describing a tree in depth-first order.
+///
+/// A sequence of these can be produced through [`Tree::to_owned_events`] and
+/// turned back into a tree through [`Tree::from_owned_events`]. When the
+/// `serde` feature is enabled they can be serialized, which makes them
+/// suitable for streaming a tree between processes.
+///
+/// [`Tree::to_owned_events`]: crate::Tree::to_owned_events
+/// [`Tree::from_owned_events`]: crate::Tree::from_owned_events
+///
+/// # Examples
+///
+/// ```
+/// use syntree::{OwnedEvent, Span};
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("lit", 3),
+///         "empty" => {}
+///     }
+/// };
+///
+/// assert_eq!(
+///     tree.to_owned_events(),
+///     [
+///         OwnedEvent::Enter("root", Span::new(0, 3)),
+///         OwnedEvent::Token("lit", Span::new(0, 3)),
+///         OwnedEvent::Enter("empty", Span::point(3)),
+///         OwnedEvent::Leave,
+///         OwnedEvent::Leave,
+///     ]
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OwnedEvent<T, S> {
+    /// Enter a node with the given value and span.
+    Enter(T, S),
+    /// A token with the given value and span.
+    Token(T, S),
+    /// Leave the most recently entered node.
+    Leave,
+}
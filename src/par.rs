@@ -0,0 +1,121 @@
+//! Parallel iterators over trees, powered by [`rayon`].
+//!
+//! These visit every node exactly once, but not in document order.
+
+use alloc::vec::Vec;
+
+use rayon::iter::plumbing::{bridge_unindexed, Folder, UnindexedConsumer, UnindexedProducer};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::links::Links;
+use crate::node::{Children, Node};
+
+/// A parallel iterator over every node in a tree.
+///
+/// See [`Tree::par_walk`][crate::Tree::par_walk].
+pub struct ParWalk<'a, T, S> {
+    tree: &'a [Links<T, S>],
+}
+
+impl<'a, T, S> ParWalk<'a, T, S> {
+    #[inline]
+    pub(crate) const fn new(tree: &'a [Links<T, S>]) -> Self {
+        Self { tree }
+    }
+}
+
+impl<'a, T, S> ParallelIterator for ParWalk<'a, T, S>
+where
+    T: Sync,
+    S: Sync,
+{
+    type Item = Node<'a, T, S>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        // Every node is stored exactly once, so the storage can be split
+        // freely without regard for structure.
+        let tree = self.tree;
+
+        tree.into_par_iter()
+            .map(move |links| Node::new(links, tree))
+            .drive_unindexed(consumer)
+    }
+
+    #[inline]
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.tree.len())
+    }
+}
+
+/// A parallel iterator over the children of a node.
+///
+/// See [`Node::par_children`][crate::Node::par_children].
+pub struct ParChildren<'a, T, S> {
+    children: Children<'a, T, S>,
+}
+
+impl<'a, T, S> ParChildren<'a, T, S> {
+    #[inline]
+    pub(crate) const fn new(children: Children<'a, T, S>) -> Self {
+        Self { children }
+    }
+}
+
+impl<'a, T, S> ParallelIterator for ParChildren<'a, T, S>
+where
+    T: Sync,
+    S: Sync,
+{
+    type Item = Node<'a, T, S>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(ChildrenProducer(self.children), consumer)
+    }
+}
+
+/// Splits a list of children in half.
+struct ChildrenProducer<'a, T, S>(Children<'a, T, S>);
+
+impl<'a, T, S> UnindexedProducer for ChildrenProducer<'a, T, S>
+where
+    T: Sync,
+    S: Sync,
+{
+    type Item = Node<'a, T, S>;
+
+    fn split(self) -> (Self, Option<Self>) {
+        let len = self.0.len();
+
+        if len < 2 {
+            return (self, None);
+        }
+
+        let (a, b) = self.0.split_at(len / 2);
+        (Self(a), Some(Self(b)))
+    }
+
+    fn fold_with<F>(self, folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        folder.consume_iter(self.0)
+    }
+}
+
+/// Collect the nodes at `depth` below `roots`, see
+/// [`Tree::par_fold_subtrees`][crate::Tree::par_fold_subtrees].
+pub(crate) fn subtrees<'a, T, S>(roots: Children<'a, T, S>, depth: usize) -> Vec<Node<'a, T, S>> {
+    let mut level = roots.collect::<Vec<_>>();
+
+    for _ in 0..depth {
+        level = level.iter().flat_map(|n| n.children()).collect();
+    }
+
+    level
+}
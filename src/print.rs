@@ -1,157 +1,20 @@
 //! Helper utilities for pretty-printing trees.
+//!
+//! The functions which write to an `std::io::Write` require the `std`
+//! feature, while [`Labels`] and the options are always available.
 
-use core::fmt;
-use std::io::{Error, Write};
+mod labels;
+mod options;
+#[cfg(feature = "std")]
+mod write;
 
-use crate::span::{self, Span};
-use crate::tree::{Kind, Tree};
-
-/// Pretty-print a tree without a source.
-///
-/// This will replace all source references with `+`. If you have a source
-/// available you can use [`print_with_source`] instead.
-///
-/// # Examples
-///
-/// ```
-/// #[derive(Debug)]
-/// enum Syntax {
-///     NUMBER,
-///     WHITESPACE,
-///     OPERATOR,
-///     PLUS,
-/// }
-///
-/// use Syntax::*;
-///
-/// let tree = syntree::tree! {
-///     NUMBER => {
-///         (NUMBER, 3),
-///     },
-///     (WHITESPACE, 1),
-///     OPERATOR => {
-///         (PLUS, 1)
-///     },
-///     (WHITESPACE, 1),
-///     NUMBER => {
-///         (NUMBER, 2),
-///     },
-/// };
-///
-/// let mut s = Vec::new();
-/// syntree::print::print(&mut s, &tree)?;
-/// # let s = String::from_utf8(s)?;
-/// # assert_eq!(s, "NUMBER@0..3\n  NUMBER@0..3 +\nWHITESPACE@3..4 +\nOPERATOR@4..5\n  PLUS@4..5 +\nWHITESPACE@5..6 +\nNUMBER@6..8\n  NUMBER@6..8 +\n");
-/// # Ok::<_, Box<dyn std::error::Error>>(())
-/// ```
-///
-/// This would write:
-///
-/// ```text
-/// NUMBER@0..3
-///   NUMBER@0..3 +
-/// WHITESPACE@3..4 +
-/// OPERATOR@4..5
-///   PLUS@4..5 +
-/// WHITESPACE@5..6 +
-/// NUMBER@6..8
-///   NUMBER@6..8 +
-/// ```
-pub fn print<O, T, S>(o: O, tree: &Tree<T, S>) -> Result<(), Error>
-where
-    O: Write,
-    T: fmt::Debug,
-    S: span::TreeSpan + fmt::Display,
-{
-    print_with_lookup(o, tree, |_| None)
-}
-
-/// Pretty-print a tree with the source spans printed.
-///
-/// # Examples
-///
-/// ```
-/// #[derive(Debug)]
-/// enum Syntax {
-///     NUMBER,
-///     WHITESPACE,
-///     OPERATOR,
-///     PLUS,
-/// }
-///
-/// use Syntax::*;
-///
-/// let source = "128 + 64";
-///
-/// let tree = syntree::tree! {
-///     NUMBER => {
-///         (NUMBER, 3),
-///     },
-///     (WHITESPACE, 1),
-///     OPERATOR => {
-///         (PLUS, 1)
-///     },
-///     (WHITESPACE, 1),
-///     NUMBER => {
-///         (NUMBER, 2),
-///     },
-/// };
-///
-/// let mut s = Vec::new();
-/// syntree::print::print_with_source(&mut s, &tree, source)?;
-/// # let s = String::from_utf8(s)?;
-/// # assert_eq!(s, "NUMBER@0..3\n  NUMBER@0..3 \"128\"\nWHITESPACE@3..4 \" \"\nOPERATOR@4..5\n  PLUS@4..5 \"+\"\nWHITESPACE@5..6 \" \"\nNUMBER@6..8\n  NUMBER@6..8 \"64\"\n");
-/// # Ok::<_, Box<dyn std::error::Error>>(())
-/// ```
-///
-/// This would write:
-///
-/// ```text
-/// NUMBER@0..3
-///   NUMBER@0..3 "128"
-/// WHITESPACE@3..4 " "
-/// OPERATOR@4..5
-///   PLUS@4..5 "+"
-/// WHITESPACE@5..6 " "
-/// NUMBER@6..8
-///   NUMBER@6..8 "64"
-/// ```
-pub fn print_with_source<O, T>(o: O, tree: &Tree<T, Span>, source: &str) -> Result<(), Error>
-where
-    O: Write,
-    T: fmt::Debug,
-{
-    print_with_lookup(o, tree, |span| source.get(span.range()))
-}
-
-fn print_with_lookup<'a, O, T, S>(
-    mut o: O,
-    tree: &Tree<T, S>,
-    source: impl Fn(&S) -> Option<&'a str>,
-) -> Result<(), Error>
-where
-    O: Write,
-    T: fmt::Debug,
-    S: span::TreeSpan + fmt::Display,
-{
-    for (depth, node) in tree.walk().with_depths() {
-        let n = depth * 2;
-        let data = node.value();
-        let span = node.span();
-
-        match node.kind() {
-            Kind::Token => {
-                if let Some(source) = source(span) {
-                    writeln!(o, "{:n$}{:?}@{} {:?}", "", data, span, source, n = n)?;
-                } else {
-                    writeln!(o, "{:n$}{:?}@{} +", "", data, span, n = n)?;
-                }
-            }
-            Kind::Node => {
-                writeln!(o, "{:n$}{:?}@{}", "", data, span, n = n)?;
-            }
-        }
-    }
-
-    Ok(())
-}
+pub use self::labels::Labels;
+pub use self::options::{HtmlOptions, SourceOptions};
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use self::write::{
+    print, print_labeled, print_labeled_with_source, print_named, print_named_with_source,
+    print_spanless, print_with_source, print_with_source_options, to_dot, to_dot_labeled,
+    to_dot_named, to_html, to_json, to_json_labeled, to_json_named, to_json_with_source, to_sexpr,
+    to_sexpr_labeled, to_sexpr_named,
+};
@@ -0,0 +1,149 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use core::fmt;
+
+/// A table of human readable labels for tree values.
+///
+/// This is useful when the values stored in a tree don't have a meaningful
+/// [`Debug`][core::fmt::Debug] implementation, such as raw numerical kinds produced
+/// by a generated lexer. The same table can be reused across many trees and is
+/// accepted by the `*_labeled` family of functions in this module. Values
+/// which are missing from the table fall back to their debug representation.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::Labels;
+///
+/// let mut labels = Labels::new();
+/// labels.insert(14u16, "IDENT");
+/// labels.insert(15u16, "WHITESPACE");
+///
+/// assert_eq!(labels.label(&14), Some("IDENT"));
+/// assert_eq!(labels.value("WHITESPACE"), Some(&15));
+/// assert_eq!(labels.label(&16), None);
+/// ```
+///
+/// Labels can also be collected from an iterator of pairs:
+///
+/// ```
+/// use syntree::print::Labels;
+///
+/// let labels = [(1u32, "NUMBER"), (2u32, "OPERATOR")].into_iter().collect::<Labels<_>>();
+/// assert_eq!(labels.label(&2), Some("OPERATOR"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Labels<T> {
+    labels: BTreeMap<T, Box<str>>,
+    values: BTreeMap<Box<str>, T>,
+}
+
+impl<T> Labels<T> {
+    /// Construct a new empty label table.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            labels: BTreeMap::new(),
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> Labels<T>
+where
+    T: Ord,
+{
+    /// Associate `value` with the given `label`, returning the label that was
+    /// previously associated with it if any.
+    ///
+    /// Labels are one-to-one, so if `label` was associated with another value
+    /// that value loses its label.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::print::Labels;
+    ///
+    /// let mut labels = Labels::new();
+    /// assert_eq!(labels.insert(1u16, "A"), None);
+    /// assert_eq!(labels.insert(1u16, "B").as_deref(), Some("A"));
+    /// assert_eq!(labels.label(&1), Some("B"));
+    /// assert_eq!(labels.value("A"), None);
+    ///
+    /// assert_eq!(labels.insert(2u16, "B"), None);
+    /// assert_eq!(labels.value("B"), Some(&2));
+    /// assert_eq!(labels.label(&1), None);
+    /// ```
+    pub fn insert(&mut self, value: T, label: impl Into<Box<str>>) -> Option<Box<str>>
+    where
+        T: Clone,
+    {
+        let label = label.into();
+
+        if let Some(previous) = self.values.insert(label.clone(), value.clone()) {
+            if previous != value {
+                self.labels.remove(&previous);
+            }
+        }
+
+        let old = self.labels.insert(value, label.clone())?;
+
+        if old != label {
+            self.values.remove(&old);
+        }
+
+        Some(old)
+    }
+
+    /// Get the label associated with the given `value`.
+    #[must_use]
+    pub fn label(&self, value: &T) -> Option<&str> {
+        Some(self.labels.get(value)?)
+    }
+
+    /// Get the value associated with the given `label`.
+    #[must_use]
+    pub fn value(&self, label: &str) -> Option<&T> {
+        self.values.get(label)
+    }
+
+    /// Format the given value, falling back to its debug representation if it
+    /// doesn't have a label.
+    #[cfg(feature = "std")]
+    pub(crate) fn fmt_value(&self, value: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    where
+        T: fmt::Debug,
+    {
+        match self.label(value) {
+            Some(label) => f.write_str(label),
+            None => fmt::Debug::fmt(value, f),
+        }
+    }
+}
+
+impl<T> Default for Labels<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, L> FromIterator<(T, L)> for Labels<T>
+where
+    T: Clone + Ord,
+    L: Into<Box<str>>,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (T, L)>,
+    {
+        let mut labels = Self::new();
+
+        for (value, label) in iter {
+            labels.insert(value, label);
+        }
+
+        labels
+    }
+}
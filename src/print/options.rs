@@ -0,0 +1,222 @@
+use alloc::boxed::Box;
+use core::fmt;
+
+/// Options controlling how trees and source snippets are rendered by
+/// [`print_with_source_options`].
+///
+/// Snippets are always quoted and escaped, so newlines and tabs inside of a
+/// token never break up the output.
+///
+/// The output can also be aligned into columns with
+/// [`SourceOptions::with_aligned_spans`] and
+/// [`SourceOptions::with_padded_values`]. Widths are measured in characters,
+/// and truncating snippets with [`SourceOptions::with_max_len`] doesn't
+/// affect alignment since snippets are always the last column.
+///
+/// [`print_with_source_options`]: crate::print::print_with_source_options
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::SourceOptions;
+///
+/// let options = SourceOptions::new().with_max_len(16);
+/// assert_eq!(options.max_len(), Some(16));
+/// assert_eq!(SourceOptions::new().max_len(), None);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SourceOptions {
+    max_len: Option<usize>,
+    align_spans: bool,
+    pad_values: bool,
+}
+
+impl SourceOptions {
+    /// Construct the default options, which renders snippets in full.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_len: None,
+            align_spans: false,
+            pad_values: false,
+        }
+    }
+
+    /// Truncate snippets to at most `max_len` characters, marking truncated
+    /// snippets with a trailing `…`.
+    #[must_use]
+    pub const fn with_max_len(self, max_len: usize) -> Self {
+        Self {
+            max_len: Some(max_len),
+            ..self
+        }
+    }
+
+    /// The maximum number of characters of a snippet to render, if any.
+    #[must_use]
+    pub const fn max_len(&self) -> Option<usize> {
+        self.max_len
+    }
+
+    /// Align the spans of every node into a single column following the
+    /// widest indented value in the tree, and right-align the spans within
+    /// that column.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::print::{self, SourceOptions};
+    ///
+    /// let tree = syntree::tree! {
+    ///     "NUMBER" => {
+    ///         ("LIT", 2),
+    ///     },
+    ///     ("WHITESPACE", 10),
+    /// };
+    ///
+    /// let options = SourceOptions::new().with_aligned_spans();
+    ///
+    /// let mut s = Vec::new();
+    /// print::print_with_source_options(&mut s, &tree, "42          ", &options)?;
+    /// # let s = String::from_utf8(s)?;
+    /// # assert_eq!(s, "\"NUMBER\"    @ 0..2\n  \"LIT\"     @ 0..2 \"42\"\n\"WHITESPACE\"@2..12 \"          \"\n");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// This outputs:
+    ///
+    /// ```text
+    /// "NUMBER"    @ 0..2
+    ///   "LIT"     @ 0..2 "42"
+    /// "WHITESPACE"@2..12 "          "
+    /// ```
+    #[must_use]
+    pub const fn with_aligned_spans(self) -> Self {
+        Self {
+            align_spans: true,
+            ..self
+        }
+    }
+
+    /// If spans are aligned into a single column.
+    #[must_use]
+    pub const fn aligned_spans(&self) -> bool {
+        self.align_spans
+    }
+
+    /// Pad every value to the width of the widest value at the same depth, so
+    /// that the spans of siblings line up.
+    ///
+    /// This has no effect if [`SourceOptions::with_aligned_spans`] is also
+    /// used, since that aligns every span.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::print::{self, SourceOptions};
+    ///
+    /// let tree = syntree::tree! {
+    ///     "NUMBER" => {
+    ///         ("LIT", 2),
+    ///     },
+    ///     ("WHITESPACE", 1),
+    /// };
+    ///
+    /// let options = SourceOptions::new().with_padded_values();
+    ///
+    /// let mut s = Vec::new();
+    /// print::print_with_source_options(&mut s, &tree, "42 ", &options)?;
+    /// # let s = String::from_utf8(s)?;
+    /// # assert_eq!(s, "\"NUMBER\"    @0..2\n  \"LIT\"@0..2 \"42\"\n\"WHITESPACE\"@2..3 \" \"\n");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// This outputs:
+    ///
+    /// ```text
+    /// "NUMBER"    @0..2
+    ///   "LIT"@0..2 "42"
+    /// "WHITESPACE"@2..3 " "
+    /// ```
+    #[must_use]
+    pub const fn with_padded_values(self) -> Self {
+        Self {
+            pad_values: true,
+            ..self
+        }
+    }
+
+    /// If values are padded to the widest value at the same depth.
+    #[must_use]
+    pub const fn padded_values(&self) -> bool {
+        self.pad_values
+    }
+}
+
+/// A function determining the class of an element in [`HtmlOptions`].
+type Classifier<'a, T> = dyn Fn(&T) -> Option<&str> + 'a;
+
+/// Options controlling how trees are rendered by [`to_html`].
+///
+/// [`to_html`]: crate::print::to_html
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::HtmlOptions;
+///
+/// let options = HtmlOptions::new().with_classifier(|value: &&str| match *value {
+///     "NUMBER" => Some("number"),
+///     _ => None,
+/// });
+///
+/// assert_eq!(options.class(&"NUMBER"), Some("number"));
+/// assert_eq!(options.class(&"WS"), None);
+/// ```
+pub struct HtmlOptions<'a, T> {
+    classifier: Option<Box<Classifier<'a, T>>>,
+}
+
+impl<'a, T> HtmlOptions<'a, T> {
+    /// Construct the default options, which doesn't add a class to any
+    /// element.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { classifier: None }
+    }
+
+    /// Use the given `classifier` to determine the `class` attribute of the
+    /// element for each value. Elements for which it returns `None` don't get
+    /// a `class` attribute.
+    #[must_use]
+    pub fn with_classifier<F>(self, classifier: F) -> Self
+    where
+        F: Fn(&T) -> Option<&str> + 'a,
+    {
+        Self {
+            classifier: Some(Box::new(classifier)),
+        }
+    }
+
+    /// Get the class of the element for the given value, if any.
+    #[must_use]
+    pub fn class<'v>(&self, value: &'v T) -> Option<&'v str> {
+        (self.classifier.as_ref()?)(value)
+    }
+}
+
+impl<T> Default for HtmlOptions<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for HtmlOptions<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HtmlOptions")
+            .field("classifier", &self.classifier.is_some())
+            .finish()
+    }
+}
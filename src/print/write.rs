@@ -0,0 +1,1609 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use std::io::{Error, ErrorKind, Write};
+
+use crate::node::Event;
+use crate::sexpr;
+use crate::source::Source;
+use crate::span::{self, FmtSpan, Span};
+use crate::tree::{Kind, Tree};
+use crate::KindName;
+
+use super::{HtmlOptions, Labels, SourceOptions};
+
+/// Pretty-print a tree without a source.
+///
+/// This will replace all source references with `+`. If you have a source
+/// available you can use [`print_with_source`] instead.
+///
+/// Nodes which are marked as errors through
+/// [`Builder::mark_error`][crate::Builder::mark_error] are annotated with a
+/// trailing `!` after their span, like `ERROR@3..3! +`.
+/// Trivia tokens declared through
+/// [`Builder::token_trivia`][crate::Builder::token_trivia] are annotated with a
+/// trailing `~`, like `WHITESPACE@3..4~ +`.
+///
+/// Spans are rendered through [`TreeSpan::fmt_span`], so a custom span type
+/// doesn't need to implement [`Display`][fmt::Display]. To leave out spans
+/// entirely, use [`print_spanless`].
+///
+/// [`TreeSpan::fmt_span`]: crate::span::TreeSpan::fmt_span
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug)]
+/// enum Syntax {
+///     NUMBER,
+///     WHITESPACE,
+///     OPERATOR,
+///     PLUS,
+/// }
+///
+/// use Syntax::*;
+///
+/// let tree = syntree::tree! {
+///     NUMBER => {
+///         (NUMBER, 3),
+///     },
+///     (WHITESPACE, 1),
+///     OPERATOR => {
+///         (PLUS, 1)
+///     },
+///     (WHITESPACE, 1),
+///     NUMBER => {
+///         (NUMBER, 2),
+///     },
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::print(&mut s, &tree)?;
+/// # let s = String::from_utf8(s)?;
+/// # assert_eq!(s, "NUMBER@0..3\n  NUMBER@0..3 +\nWHITESPACE@3..4 +\nOPERATOR@4..5\n  PLUS@4..5 +\nWHITESPACE@5..6 +\nNUMBER@6..8\n  NUMBER@6..8 +\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// This would write:
+///
+/// ```text
+/// NUMBER@0..3
+///   NUMBER@0..3 +
+/// WHITESPACE@3..4 +
+/// OPERATOR@4..5
+///   PLUS@4..5 +
+/// WHITESPACE@5..6 +
+/// NUMBER@6..8
+///   NUMBER@6..8 +
+/// ```
+pub fn print<O, T, S>(o: O, tree: &Tree<T, S>) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug,
+    S: span::TreeSpan,
+{
+    print_with_lookup(
+        o,
+        tree,
+        true,
+        &SourceOptions::new(),
+        |_| Snippet::Missing,
+        fmt::Debug::fmt,
+    )
+}
+
+/// Pretty-print a tree without a source or spans.
+///
+/// This is like [`print`], except that the `@span` part of each line is
+/// omitted.
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("lit", 3),
+///         ("ws", 1),
+///     },
+///     ("eof", 0),
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::print_spanless(&mut s, &tree)?;
+///
+/// assert_eq!(
+///     String::from_utf8(s)?,
+///     "\"root\"\n  \"lit\" +\n  \"ws\" +\n\"eof\" +\n"
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn print_spanless<O, T, S>(o: O, tree: &Tree<T, S>) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug,
+    S: span::TreeSpan,
+{
+    print_with_lookup(
+        o,
+        tree,
+        false,
+        &SourceOptions::new(),
+        |_| Snippet::Missing,
+        fmt::Debug::fmt,
+    )
+}
+
+/// Pretty-print a tree with the source spans printed.
+///
+/// The source can be anything which implements [`Source`], like a `&str` or a
+/// source which doesn't store its text contiguously.
+///
+/// Tokens whose span can't be looked up in the source are rendered as
+/// `<invalid span>`. To truncate long snippets, use
+/// [`print_with_source_options`] instead.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug)]
+/// enum Syntax {
+///     NUMBER,
+///     WHITESPACE,
+///     OPERATOR,
+///     PLUS,
+/// }
+///
+/// use Syntax::*;
+///
+/// let source = "128 + 64";
+///
+/// let tree = syntree::tree! {
+///     NUMBER => {
+///         (NUMBER, 3),
+///     },
+///     (WHITESPACE, 1),
+///     OPERATOR => {
+///         (PLUS, 1)
+///     },
+///     (WHITESPACE, 1),
+///     NUMBER => {
+///         (NUMBER, 2),
+///     },
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::print_with_source(&mut s, &tree, source)?;
+/// # let s = String::from_utf8(s)?;
+/// # assert_eq!(s, "NUMBER@0..3\n  NUMBER@0..3 \"128\"\nWHITESPACE@3..4 \" \"\nOPERATOR@4..5\n  PLUS@4..5 \"+\"\nWHITESPACE@5..6 \" \"\nNUMBER@6..8\n  NUMBER@6..8 \"64\"\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// This would write:
+///
+/// ```text
+/// NUMBER@0..3
+///   NUMBER@0..3 "128"
+/// WHITESPACE@3..4 " "
+/// OPERATOR@4..5
+///   PLUS@4..5 "+"
+/// WHITESPACE@5..6 " "
+/// NUMBER@6..8
+///   NUMBER@6..8 "64"
+/// ```
+pub fn print_with_source<O, T>(o: O, tree: &Tree<T, Span>, source: impl Source) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug,
+{
+    print_with_source_options(o, tree, source, &SourceOptions::new())
+}
+
+/// Pretty-print a tree with the source spans printed, using the given
+/// [`SourceOptions`] to control how source snippets are rendered.
+///
+/// Tokens whose span can't be looked up in the source, because it is out of
+/// bounds or doesn't fall on a character boundary, are rendered as
+/// `<invalid span>`.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::SourceOptions;
+///
+/// let source = "\"a long string\"\né";
+///
+/// let tree = syntree::tree! {
+///     "STRING" => {
+///         ("LIT", 15),
+///         ("NEWLINE", 1),
+///     },
+///     ("E", 2),
+///     ("PAST", 3),
+/// };
+///
+/// let options = SourceOptions::new().with_max_len(8);
+///
+/// let mut s = Vec::new();
+/// syntree::print::print_with_source_options(&mut s, &tree, source, &options)?;
+/// # let s = String::from_utf8(s)?;
+/// # assert_eq!(s, "\"STRING\"@0..16\n  \"LIT\"@0..15 \"\\\"a long \"…\n  \"NEWLINE\"@15..16 \"\\n\"\n\"E\"@16..18 \"é\"\n\"PAST\"@18..21 <invalid span>\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// This would write:
+///
+/// ```text
+/// "STRING"@0..16
+///   "LIT"@0..15 "\"a long "…
+///   "NEWLINE"@15..16 "\n"
+/// "E"@16..18 "é"
+/// "PAST"@18..21 <invalid span>
+/// ```
+pub fn print_with_source_options<O, T>(
+    o: O,
+    tree: &Tree<T, Span>,
+    source: impl Source,
+    options: &SourceOptions,
+) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug,
+{
+    print_with_lookup(
+        o,
+        tree,
+        true,
+        options,
+        |span| Snippet::lookup(&source, span, options),
+        fmt::Debug::fmt,
+    )
+}
+
+/// Pretty-print a tree without a source, using the given [`Labels`] to
+/// render values.
+///
+/// Values which are missing from `labels` are rendered using their
+/// [`Debug`][fmt::Debug] implementation.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::Labels;
+///
+/// let labels = [(1u16, "NUMBER"), (2u16, "LIT")].into_iter().collect::<Labels<_>>();
+///
+/// let tree = syntree::tree! {
+///     1u16 => {
+///         (2u16, 3),
+///     },
+///     (14u16, 1),
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::print_labeled(&mut s, &tree, &labels)?;
+/// # let s = String::from_utf8(s)?;
+/// # assert_eq!(s, "NUMBER@0..3\n  LIT@0..3 +\n14@3..4 +\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// This would write:
+///
+/// ```text
+/// NUMBER@0..3
+///   LIT@0..3 +
+/// 14@3..4 +
+/// ```
+pub fn print_labeled<O, T, S>(o: O, tree: &Tree<T, S>, labels: &Labels<T>) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug + Ord,
+    S: span::TreeSpan,
+{
+    print_with_lookup(
+        o,
+        tree,
+        true,
+        &SourceOptions::new(),
+        |_| Snippet::Missing,
+        |value, f| labels.fmt_value(value, f),
+    )
+}
+
+/// Pretty-print a tree with the source spans printed, using the given
+/// [`Labels`] to render values.
+///
+/// Values which are missing from `labels` are rendered using their
+/// [`Debug`][fmt::Debug] implementation.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::Labels;
+///
+/// let labels = [(1u16, "NUMBER"), (2u16, "LIT")].into_iter().collect::<Labels<_>>();
+///
+/// let source = "128 ";
+///
+/// let tree = syntree::tree! {
+///     1u16 => {
+///         (2u16, 3),
+///     },
+///     (14u16, 1),
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::print_labeled_with_source(&mut s, &tree, source, &labels)?;
+/// # let s = String::from_utf8(s)?;
+/// # assert_eq!(s, "NUMBER@0..3\n  LIT@0..3 \"128\"\n14@3..4 \" \"\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// This would write:
+///
+/// ```text
+/// NUMBER@0..3
+///   LIT@0..3 "128"
+/// 14@3..4 " "
+/// ```
+pub fn print_labeled_with_source<O, T>(
+    o: O,
+    tree: &Tree<T, Span>,
+    source: impl Source,
+    labels: &Labels<T>,
+) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug + Ord,
+{
+    print_with_lookup(
+        o,
+        tree,
+        true,
+        &SourceOptions::new(),
+        |span| Snippet::lookup(&source, span, &SourceOptions::new()),
+        |value, f| labels.fmt_value(value, f),
+    )
+}
+
+/// Pretty-print a tree without a source, using the [`KindName`] of each value.
+///
+/// Unlike [`print`], the output doesn't depend on the [`Debug`][fmt::Debug]
+/// implementation of values, so renaming or reordering enum variants doesn't
+/// change it.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug, Clone, Copy)]
+/// enum Syntax {
+///     Number,
+///     Lit,
+/// }
+///
+/// syntree::kind_names! {
+///     Syntax {
+///         Number => "NUMBER",
+///         Lit => "LIT",
+///     }
+/// }
+///
+/// let tree = syntree::tree! {
+///     Syntax::Number => {
+///         (Syntax::Lit, 3),
+///     },
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::print_named(&mut s, &tree)?;
+/// # let s = String::from_utf8(s)?;
+/// # assert_eq!(s, "NUMBER@0..3\n  LIT@0..3 +\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// This would write:
+///
+/// ```text
+/// NUMBER@0..3
+///   LIT@0..3 +
+/// ```
+pub fn print_named<O, T, S>(o: O, tree: &Tree<T, S>) -> Result<(), Error>
+where
+    O: Write,
+    T: KindName,
+    S: span::TreeSpan,
+{
+    print_with_lookup(
+        o,
+        tree,
+        true,
+        &SourceOptions::new(),
+        |_| Snippet::Missing,
+        fmt_kind_name,
+    )
+}
+
+/// Pretty-print a tree with the source spans printed, using the [`KindName`]
+/// of each value.
+///
+/// See [`print_named`] for details.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug, Clone, Copy)]
+/// enum Syntax {
+///     Number,
+///     Lit,
+/// }
+///
+/// syntree::kind_names! {
+///     Syntax {
+///         Number => "NUMBER",
+///         Lit => "LIT",
+///     }
+/// }
+///
+/// let tree = syntree::tree! {
+///     Syntax::Number => {
+///         (Syntax::Lit, 3),
+///     },
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::print_named_with_source(&mut s, &tree, "128")?;
+/// # let s = String::from_utf8(s)?;
+/// # assert_eq!(s, "NUMBER@0..3\n  LIT@0..3 \"128\"\n");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// This would write:
+///
+/// ```text
+/// NUMBER@0..3
+///   LIT@0..3 "128"
+/// ```
+pub fn print_named_with_source<O, T>(
+    o: O,
+    tree: &Tree<T, Span>,
+    source: impl Source,
+) -> Result<(), Error>
+where
+    O: Write,
+    T: KindName,
+{
+    print_with_lookup(
+        o,
+        tree,
+        true,
+        &SourceOptions::new(),
+        |span| Snippet::lookup(&source, span, &SourceOptions::new()),
+        fmt_kind_name,
+    )
+}
+
+/// Write a tree as a [Graphviz] digraph.
+///
+/// Every element in the tree is emitted as a graph node identified by its
+/// [`Id`][crate::Id] and labelled with the [`Debug`][fmt::Debug]
+/// representation of its value followed by its span. Nodes are drawn as boxes
+/// and tokens as ellipses. Edges go from parent to child in child order, and
+/// the graph is marked with `ordering=out` so that layout preserves the order
+/// of siblings.
+///
+/// [Graphviz]: https://graphviz.org
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("number", 3),
+///         ("ws", 1),
+///     },
+///     ("eof", 0)
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_dot(&mut s, &tree)?;
+/// let s = String::from_utf8(s)?;
+///
+/// let expected = r#"digraph {
+///   ordering=out;
+///   0 [label="\"root\"@0..4", shape=box];
+///   1 [label="\"number\"@0..3", shape=ellipse];
+///   0 -> 1;
+///   2 [label="\"ws\"@3..4", shape=ellipse];
+///   0 -> 2;
+///   3 [label="\"eof\"@4..4", shape=ellipse];
+/// }
+/// "#;
+///
+/// assert_eq!(s, expected);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// Quotes, backslashes and newlines in the debug representation are escaped:
+///
+/// ```
+/// let tree = syntree::tree! {
+///     ("a\"b\nc", 1)
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_dot(&mut s, &tree)?;
+/// let s = String::from_utf8(s)?;
+///
+/// let lines = s.lines().collect::<Vec<_>>();
+/// assert_eq!(lines[0], "digraph {");
+/// assert_eq!(lines[2], r#"  0 [label="\"a\\\"b\\nc\"@0..1", shape=ellipse];"#);
+/// assert_eq!(lines[3], "}");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_dot<O, T, S>(o: O, tree: &Tree<T, S>) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug,
+    S: span::TreeSpan,
+{
+    to_dot_with(o, tree, fmt::Debug::fmt)
+}
+
+/// Write a tree as a [Graphviz] digraph, using the given [`Labels`] to render
+/// values.
+///
+/// See [`to_dot`] for details on the output.
+///
+/// [Graphviz]: https://graphviz.org
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::Labels;
+///
+/// let labels = [(1u16, "NUMBER")].into_iter().collect::<Labels<_>>();
+///
+/// let tree = syntree::tree! {
+///     1u16 => {
+///         (2u16, 3),
+///     }
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_dot_labeled(&mut s, &tree, &labels)?;
+/// let s = String::from_utf8(s)?;
+///
+/// let lines = s.lines().collect::<Vec<_>>();
+/// assert_eq!(lines[2], r#"  0 [label="NUMBER@0..3", shape=box];"#);
+/// assert_eq!(lines[3], r#"  1 [label="2@0..3", shape=ellipse];"#);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_dot_labeled<O, T, S>(o: O, tree: &Tree<T, S>, labels: &Labels<T>) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug + Ord,
+    S: span::TreeSpan,
+{
+    to_dot_with(o, tree, |value, f| labels.fmt_value(value, f))
+}
+
+/// Write a tree as a [Graphviz] digraph, using the [`KindName`] of each value.
+///
+/// See [`to_dot`] for details on the output.
+///
+/// [Graphviz]: https://graphviz.org
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug, Clone, Copy)]
+/// enum Syntax {
+///     Number,
+///     Lit,
+/// }
+///
+/// syntree::kind_names! {
+///     Syntax {
+///         Number => "NUMBER",
+///         Lit => "LIT",
+///     }
+/// }
+///
+/// let tree = syntree::tree! {
+///     Syntax::Number => {
+///         (Syntax::Lit, 3),
+///     }
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_dot_named(&mut s, &tree)?;
+/// let s = String::from_utf8(s)?;
+///
+/// let lines = s.lines().collect::<Vec<_>>();
+/// assert_eq!(lines[2], r#"  0 [label="NUMBER@0..3", shape=box];"#);
+/// assert_eq!(lines[3], r#"  1 [label="LIT@0..3", shape=ellipse];"#);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_dot_named<O, T, S>(o: O, tree: &Tree<T, S>) -> Result<(), Error>
+where
+    O: Write,
+    T: KindName,
+    S: span::TreeSpan,
+{
+    to_dot_with(o, tree, fmt_kind_name)
+}
+
+fn to_dot_with<O, T, S, F>(mut o: O, tree: &Tree<T, S>, value: F) -> Result<(), Error>
+where
+    O: Write,
+    S: span::TreeSpan,
+    F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    writeln!(o, "digraph {{")?;
+    writeln!(o, "  ordering=out;")?;
+
+    for node in tree.walk() {
+        let id = node.id().0.get();
+
+        let shape = match node.kind() {
+            Kind::Node => "box",
+            Kind::Token => "ellipse",
+        };
+
+        let label = format!(
+            "{}{}",
+            Value(node.value(), &value),
+            SpanAt(Some(node.span()), 0)
+        );
+
+        writeln!(
+            o,
+            "  {} [label=\"{}\", shape={}];",
+            id,
+            EscapeDot(&label),
+            shape
+        )?;
+
+        if let Some(parent) = node.parent() {
+            writeln!(o, "  {} -> {};", parent.id().0.get(), id)?;
+        }
+    }
+
+    writeln!(o, "}}")?;
+    Ok(())
+}
+
+/// Write a tree as a single-line S-expression.
+///
+/// Every node is written as a list starting with the
+/// [`Debug`][fmt::Debug] representation of its value followed by its
+/// children, and every token is written as a list containing its value
+/// followed by the quoted source it spans. If the representation of a value
+/// is neither a bare symbol nor a quoted string it is written as a quoted
+/// string.
+///
+/// The output can be parsed back into a tree using [`from_sexpr`].
+///
+/// [`from_sexpr`]: crate::from_sexpr
+///
+/// # Errors
+///
+/// Errors if writing to the output fails, or with
+/// [`ErrorKind::InvalidInput`] if a token
+/// spans outside of the provided source.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug)]
+/// enum Syntax {
+///     ROOT,
+///     NUMBER,
+///     LIT,
+///     WS,
+/// }
+///
+/// use Syntax::*;
+///
+/// let source = "128 64";
+///
+/// let tree = syntree::tree! {
+///     ROOT => {
+///         NUMBER => {
+///             (LIT, 3)
+///         },
+///         (WS, 1),
+///         NUMBER => {
+///             (LIT, 2)
+///         }
+///     }
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_sexpr(&mut s, &tree, source)?;
+/// let s = String::from_utf8(s)?;
+///
+/// assert_eq!(s, r#"(ROOT (NUMBER (LIT "128")) (WS " ") (NUMBER (LIT "64")))"#);
+///
+/// let parsed = syntree::from_sexpr(&s)?;
+/// assert!(parsed.walk().map(|n| *n.span()).eq(tree.walk().map(|n| *n.span())));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_sexpr<O, T>(o: O, tree: &Tree<T, Span>, source: impl Source) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug,
+{
+    to_sexpr_with(o, tree, source, fmt::Debug::fmt)
+}
+
+/// Write a tree as a single-line S-expression, using the given [`Labels`] to
+/// render values.
+///
+/// The output can be parsed back into a tree using [`from_sexpr_labeled`]
+/// with the same table. See [`to_sexpr`] for details on the output.
+///
+/// [`from_sexpr_labeled`]: crate::sexpr::from_sexpr_labeled
+///
+/// # Errors
+///
+/// Errors if writing to the output fails, or with
+/// [`ErrorKind::InvalidInput`] if a token
+/// spans outside of the provided source.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::Labels;
+///
+/// let labels = [(1u16, "NUMBER"), (2u16, "LIT")].into_iter().collect::<Labels<_>>();
+///
+/// let tree = syntree::tree! {
+///     1u16 => {
+///         (2u16, 3)
+///     }
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_sexpr_labeled(&mut s, &tree, "128", &labels)?;
+/// let s = String::from_utf8(s)?;
+///
+/// assert_eq!(s, r#"(NUMBER (LIT "128"))"#);
+/// assert_eq!(syntree::sexpr::from_sexpr_labeled(&s, &labels)?, tree);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_sexpr_labeled<O, T>(
+    o: O,
+    tree: &Tree<T, Span>,
+    source: impl Source,
+    labels: &Labels<T>,
+) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug + Ord,
+{
+    to_sexpr_with(o, tree, source, |value, f| labels.fmt_value(value, f))
+}
+
+/// Write a tree as a single-line S-expression, using the [`KindName`] of each
+/// value.
+///
+/// The output can be parsed back into a tree using [`from_sexpr_named`]. See
+/// [`to_sexpr`] for details on the output.
+///
+/// [`from_sexpr_named`]: crate::sexpr::from_sexpr_named
+///
+/// # Errors
+///
+/// Errors if writing to the output fails, or with
+/// [`ErrorKind::InvalidInput`] if a token
+/// spans outside of the provided source.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Syntax {
+///     Number,
+///     Lit,
+/// }
+///
+/// syntree::kind_names! {
+///     Syntax {
+///         Number => "NUMBER",
+///         Lit => "LIT",
+///     }
+/// }
+///
+/// let tree = syntree::tree! {
+///     Syntax::Number => {
+///         (Syntax::Lit, 3)
+///     }
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_sexpr_named(&mut s, &tree, "128")?;
+/// let s = String::from_utf8(s)?;
+///
+/// assert_eq!(s, r#"(NUMBER (LIT "128"))"#);
+/// assert_eq!(syntree::sexpr::from_sexpr_named::<Syntax>(&s)?, tree);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_sexpr_named<O, T>(o: O, tree: &Tree<T, Span>, source: impl Source) -> Result<(), Error>
+where
+    O: Write,
+    T: KindName,
+{
+    to_sexpr_with(o, tree, source, fmt_kind_name)
+}
+
+fn to_sexpr_with<O, T, F>(
+    mut o: O,
+    tree: &Tree<T, Span>,
+    source: impl Source,
+    value: F,
+) -> Result<(), Error>
+where
+    O: Write,
+    F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    let mut first = true;
+
+    for (event, node) in tree.walk_events() {
+        if let Event::Up = event {
+            write!(o, ")")?;
+            continue;
+        }
+
+        if !first {
+            write!(o, " ")?;
+        }
+
+        first = false;
+
+        let name = Value(node.value(), &value).to_string();
+
+        if sexpr::is_symbol(&name)
+            || (name.len() >= 2 && name.starts_with('"') && name.ends_with('"'))
+        {
+            write!(o, "({}", name)?;
+        } else {
+            write!(o, "({:?}", name)?;
+        }
+
+        match node.kind() {
+            Kind::Token => {
+                let Some(text) = source.slice(node.range()) else {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "token span is out of bounds of the source",
+                    ));
+                };
+
+                write!(o, " {:?})", text)?;
+            }
+            Kind::Node => {
+                if node.is_empty() {
+                    write!(o, ")")?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Write a tree as a JSON document.
+///
+/// The document is an array containing one object for every root in the tree.
+/// Every object has the following fields:
+/// * `"value"` - the [`Debug`][fmt::Debug] representation of the value as a
+///   string.
+/// * `"kind"` - either `"node"` or `"token"`.
+/// * `"span"` - an object with the numerical `"start"` and `"end"` of the
+///   span.
+/// * `"children"` - an array of objects for each child, which is always empty
+///   for tokens.
+///
+/// If a source is provided through [`to_json_with_source`] tokens also have a
+/// `"text"` field containing the source they span.
+///
+/// The document is written on a single line, and deep trees are written
+/// without recursion.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug)]
+/// enum Syntax {
+///     NUMBER,
+///     WHITESPACE,
+/// }
+///
+/// use Syntax::*;
+///
+/// let tree = syntree::tree! {
+///     NUMBER => {
+///         (NUMBER, 3),
+///     },
+///     (WHITESPACE, 1),
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_json(&mut s, &tree)?;
+/// let s = String::from_utf8(s)?;
+///
+/// let expected = concat!(
+///     r#"[{"value":"NUMBER","kind":"node","span":{"start":0,"end":3},"children":["#,
+///     r#"{"value":"NUMBER","kind":"token","span":{"start":0,"end":3},"children":[]}]},"#,
+///     r#"{"value":"WHITESPACE","kind":"token","span":{"start":3,"end":4},"children":[]}]"#,
+/// );
+///
+/// assert_eq!(s, expected);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_json<O, T, S>(o: O, tree: &Tree<T, S>) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug,
+    S: span::TreeSpan,
+{
+    to_json_with(o, tree, |_| Ok(None), fmt::Debug::fmt)
+}
+
+/// Write a tree as a JSON document, including the source spanned by each
+/// token in a `"text"` field.
+///
+/// See [`to_json`] for details on the output.
+///
+/// # Errors
+///
+/// Errors if writing to the output fails, or with
+/// [`ErrorKind::InvalidInput`] if a token
+/// spans outside of the provided source.
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "STRING" => {
+///         ("LIT", 5),
+///     }
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_json_with_source(&mut s, &tree, "\"a\tb\"")?;
+/// let s = String::from_utf8(s)?;
+///
+/// let expected = concat!(
+///     r#"[{"value":"\"STRING\"","kind":"node","span":{"start":0,"end":5},"children":["#,
+///     r#"{"value":"\"LIT\"","kind":"token","span":{"start":0,"end":5},"text":"\"a\tb\"","children":[]}]}]"#,
+/// );
+///
+/// assert_eq!(s, expected);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_json_with_source<O, T>(
+    o: O,
+    tree: &Tree<T, Span>,
+    source: impl Source,
+) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug,
+{
+    to_json_with(
+        o,
+        tree,
+        |span| lookup_source(&source, span),
+        fmt::Debug::fmt,
+    )
+}
+
+/// Write a tree as a JSON document, using the given [`Labels`] to render
+/// values.
+///
+/// See [`to_json`] for details on the output.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::Labels;
+///
+/// let labels = [(1u16, "NUMBER")].into_iter().collect::<Labels<_>>();
+///
+/// let tree = syntree::tree! {
+///     (1u16, 3),
+///     (2u16, 1),
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_json_labeled(&mut s, &tree, &labels)?;
+/// let s = String::from_utf8(s)?;
+///
+/// assert!(s.starts_with(r#"[{"value":"NUMBER","#));
+/// assert!(s.contains(r#"{"value":"2","#));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_json_labeled<O, T, S>(o: O, tree: &Tree<T, S>, labels: &Labels<T>) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug + Ord,
+    S: span::TreeSpan,
+{
+    to_json_with(o, tree, |_| Ok(None), |value, f| labels.fmt_value(value, f))
+}
+
+/// Write a tree as a JSON document, using the [`KindName`] of each value.
+///
+/// See [`to_json`] for details on the output.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(Debug, Clone, Copy)]
+/// enum Syntax {
+///     Number,
+/// }
+///
+/// syntree::kind_names! {
+///     Syntax {
+///         Number => "NUMBER",
+///     }
+/// }
+///
+/// let tree = syntree::tree! {
+///     (Syntax::Number, 3),
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_json_named(&mut s, &tree)?;
+/// let s = String::from_utf8(s)?;
+///
+/// assert!(s.starts_with(r#"[{"value":"NUMBER","#));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_json_named<O, T, S>(o: O, tree: &Tree<T, S>) -> Result<(), Error>
+where
+    O: Write,
+    T: KindName,
+    S: span::TreeSpan,
+{
+    to_json_with(o, tree, |_| Ok(None), fmt_kind_name)
+}
+
+fn to_json_with<'a, O, T, S, F>(
+    mut o: O,
+    tree: &Tree<T, S>,
+    source: impl Fn(&S) -> Result<Option<Cow<'a, str>>, Error>,
+    value: F,
+) -> Result<(), Error>
+where
+    O: Write,
+    S: span::TreeSpan,
+    F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    write!(o, "[")?;
+
+    // Whether the next element is the first in its enclosing array. Since
+    // `walk_events` walks the tree iteratively this is the only state needed.
+    let mut first = true;
+
+    for (event, node) in tree.walk_events() {
+        if let Event::Up = event {
+            write!(o, "]}}")?;
+            first = false;
+            continue;
+        }
+
+        if !first {
+            write!(o, ",")?;
+        }
+
+        let name = Value(node.value(), &value).to_string();
+        let span = node.span();
+
+        let kind = match node.kind() {
+            Kind::Node => "node",
+            Kind::Token => "token",
+        };
+
+        write!(
+            o,
+            "{{\"value\":\"{}\",\"kind\":\"{}\",\"span\":{{\"start\":{},\"end\":{}}}",
+            EscapeJson(&name),
+            kind,
+            span.start(),
+            span.end()
+        )?;
+
+        if node.kind() == Kind::Token {
+            if let Some(text) = source(span)? {
+                write!(o, ",\"text\":\"{}\"", EscapeJson(&text))?;
+            }
+        }
+
+        if node.is_empty() {
+            write!(o, ",\"children\":[]}}")?;
+            first = false;
+        } else {
+            write!(o, ",\"children\":[")?;
+            first = true;
+        }
+    }
+
+    write!(o, "]")?;
+    Ok(())
+}
+
+/// Look up the source of a token, erroring if its span is out of bounds.
+fn lookup_source<'a>(source: &'a impl Source, span: &Span) -> Result<Option<Cow<'a, str>>, Error> {
+    match source.slice(span.range()) {
+        Some(text) => Ok(Some(text)),
+        None => Err(Error::new(
+            ErrorKind::InvalidInput,
+            "token span is out of bounds of the source",
+        )),
+    }
+}
+
+/// Render the source of a tree as HTML, wrapping it in nested `<span>`
+/// elements which mirror the structure of the tree.
+///
+/// Every element in the tree becomes a `<span>` with the following
+/// attributes:
+/// * `data-kind` - either `node` or `token`.
+/// * `data-value` - the [`Debug`][fmt::Debug] representation of the value.
+/// * `data-range` - the range of the source the element spans, like `0..3`.
+/// * `class` - the class returned by the classifier in [`HtmlOptions`], if
+///   any.
+///
+/// Tokens contain the source they span, and nodes contain the elements of
+/// their children. Source which isn't covered by any token is written as plain
+/// text, so that the text content of the output is exactly the source. Both
+/// text and attribute values are escaped.
+///
+/// # Errors
+///
+/// Errors if writing to the output fails, or with
+/// [`ErrorKind::InvalidInput`] if an element
+/// spans outside of the provided source.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::HtmlOptions;
+///
+/// let source = "1<2";
+///
+/// let tree = syntree::tree! {
+///     "EXPR" => {
+///         ("NUMBER", 1),
+///         ("LT", 1),
+///         ("NUMBER", 1),
+///     }
+/// };
+///
+/// let options = HtmlOptions::new().with_classifier(|value: &&str| match *value {
+///     "NUMBER" => Some("number"),
+///     _ => None,
+/// });
+///
+/// let mut s = Vec::new();
+/// syntree::print::to_html(&mut s, &tree, source, &options)?;
+/// let s = String::from_utf8(s)?;
+///
+/// let expected = concat!(
+///     r#"<span data-kind="node" data-value="&quot;EXPR&quot;" data-range="0..3">"#,
+///     r#"<span data-kind="token" data-value="&quot;NUMBER&quot;" data-range="0..1" class="number">1</span>"#,
+///     r#"<span data-kind="token" data-value="&quot;LT&quot;" data-range="1..2">&lt;</span>"#,
+///     r#"<span data-kind="token" data-value="&quot;NUMBER&quot;" data-range="2..3" class="number">2</span>"#,
+///     r#"</span>"#,
+/// );
+///
+/// assert_eq!(s, expected);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_html<O, T>(
+    mut o: O,
+    tree: &Tree<T, Span>,
+    source: &str,
+    options: &HtmlOptions<'_, T>,
+) -> Result<(), Error>
+where
+    O: Write,
+    T: fmt::Debug,
+{
+    // Byte offset into the source up until which text has been written.
+    let mut cursor = 0;
+
+    for (event, node) in tree.walk_events() {
+        let range = node.range();
+
+        let Some(text) = source.get(range.clone()) else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "span is out of bounds of the source",
+            ));
+        };
+
+        if let Event::Up = event {
+            write_html_gap(&mut o, source, &mut cursor, range.end)?;
+            write!(o, "</span>")?;
+            continue;
+        }
+
+        write_html_gap(&mut o, source, &mut cursor, range.start)?;
+
+        let kind = match node.kind() {
+            Kind::Node => "node",
+            Kind::Token => "token",
+        };
+
+        let value = format!("{:?}", node.value());
+
+        write!(
+            o,
+            "<span data-kind=\"{}\" data-value=\"{}\" data-range=\"{}..{}\"",
+            kind,
+            EscapeHtml(&value),
+            range.start,
+            range.end
+        )?;
+
+        if let Some(class) = options.class(node.value()) {
+            write!(o, " class=\"{}\"", EscapeHtml(class))?;
+        }
+
+        write!(o, ">")?;
+
+        if node.kind() == Kind::Token {
+            write!(o, "{}", EscapeHtml(text))?;
+            cursor = cursor.max(range.end);
+        }
+
+        if node.is_empty() {
+            write_html_gap(&mut o, source, &mut cursor, range.end)?;
+            write!(o, "</span>")?;
+        }
+    }
+
+    write_html_gap(&mut o, source, &mut cursor, source.len())?;
+    Ok(())
+}
+
+/// Write the source which hasn't been written yet up until `end` as escaped
+/// text.
+fn write_html_gap<O>(o: &mut O, source: &str, cursor: &mut usize, end: usize) -> Result<(), Error>
+where
+    O: Write,
+{
+    if end <= *cursor {
+        return Ok(());
+    }
+
+    let Some(text) = source.get(*cursor..end) else {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "span is out of bounds of the source",
+        ));
+    };
+
+    write!(o, "{}", EscapeHtml(text))?;
+    *cursor = end;
+    Ok(())
+}
+
+/// Format a value using its [`KindName`].
+fn fmt_kind_name<T>(value: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+    T: KindName,
+{
+    f.write_str(value.kind_name())
+}
+
+/// Escape a string so that it can be used inside of a quoted DOT string.
+struct EscapeDot<'a>(&'a str);
+
+impl fmt::Display for EscapeDot<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use fmt::Write as _;
+
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Escape a string so that it can be used inside of a quoted JSON string.
+struct EscapeJson<'a>(&'a str);
+
+impl fmt::Display for EscapeJson<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use fmt::Write as _;
+
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Escape a string so that it can be used as HTML text or inside of a quoted
+/// attribute value.
+struct EscapeHtml<'a>(&'a str);
+
+impl fmt::Display for EscapeHtml<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use fmt::Write as _;
+
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&#39;")?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Helper to display a value using the given formatting function.
+struct Value<'a, T, F>(&'a T, &'a F);
+
+impl<T, F> fmt::Display for Value<'_, T, F>
+where
+    F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
+
+/// Helper to display a span prefixed with `@` and padded by the given number
+/// of spaces, or nothing.
+struct SpanAt<'a, S>(Option<&'a S>, usize);
+
+impl<S> fmt::Display for SpanAt<'_, S>
+where
+    S: span::TreeSpan,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(span) => write!(f, "@{:pad$}{}", "", FmtSpan(span), pad = self.1),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Count the number of characters something is displayed as.
+fn width<D>(value: D) -> usize
+where
+    D: fmt::Display,
+{
+    struct Width(usize);
+
+    impl fmt::Write for Width {
+        #[inline]
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 += s.chars().count();
+            Ok(())
+        }
+    }
+
+    let mut w = Width(0);
+    _ = fmt::Write::write_fmt(&mut w, format_args!("{value}"));
+    w.0
+}
+
+/// Column widths measured in a first pass over a tree, which are used to
+/// align the output of the second pass.
+struct Layout {
+    /// If spans are aligned into a single column.
+    aligned: bool,
+    /// The width of the widest indented value.
+    prefix: usize,
+    /// The width of the widest span.
+    span: usize,
+    /// The width of the widest value at each depth.
+    depths: Vec<usize>,
+}
+
+impl Layout {
+    fn measure<T, S, F>(tree: &Tree<T, S>, options: &SourceOptions, value: &F) -> Option<Self>
+    where
+        S: span::TreeSpan,
+        F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+    {
+        let aligned = options.aligned_spans();
+
+        if !aligned && !options.padded_values() {
+            return None;
+        }
+
+        let mut layout = Self {
+            aligned,
+            prefix: 0,
+            span: 0,
+            depths: Vec::new(),
+        };
+
+        for (depth, node) in tree.walk().with_depths() {
+            let value = width(Value(node.value(), value));
+            layout.prefix = layout.prefix.max(depth * 2 + value);
+            layout.span = layout.span.max(width(FmtSpan(node.span())));
+
+            if layout.depths.len() <= depth {
+                layout.depths.resize(depth + 1, 0);
+            }
+
+            if let Some(widest) = layout.depths.get_mut(depth) {
+                *widest = (*widest).max(value);
+            }
+        }
+
+        Some(layout)
+    }
+
+    /// The padding following a value of the given `width` at `depth`.
+    fn value_padding(&self, depth: usize, width: usize) -> usize {
+        if self.aligned {
+            return self.prefix.saturating_sub(depth * 2 + width);
+        }
+
+        let widest = self.depths.get(depth).copied().unwrap_or_default();
+        widest.saturating_sub(width)
+    }
+
+    /// The padding preceding a span of the given `width`.
+    fn span_padding(&self, width: usize) -> usize {
+        if self.aligned {
+            self.span.saturating_sub(width)
+        } else {
+            0
+        }
+    }
+}
+
+fn print_with_lookup<'a, O, T, S, F>(
+    mut o: O,
+    tree: &Tree<T, S>,
+    spans: bool,
+    options: &SourceOptions,
+    source: impl Fn(&S) -> Snippet<'a>,
+    value: F,
+) -> Result<(), Error>
+where
+    O: Write,
+    S: span::TreeSpan,
+    F: Fn(&T, &mut fmt::Formatter<'_>) -> fmt::Result,
+{
+    // Measuring requires a separate pass over the tree, so it's only done if
+    // the output is aligned.
+    let layout = Layout::measure(tree, options, &value);
+
+    for (depth, node) in tree.walk().with_depths() {
+        let n = depth * 2;
+        let data = Value(node.value(), &value);
+        let span = node.span();
+
+        let (pad, span_pad) = match &layout {
+            Some(layout) => (
+                layout.value_padding(depth, width(&data)),
+                layout.span_padding(width(FmtSpan(span))),
+            ),
+            None => (0, 0),
+        };
+
+        let at = SpanAt(spans.then_some(span), span_pad);
+        let error = if node.is_error() { "!" } else { "" };
+        let trivia = if node.is_trivia() { "~" } else { "" };
+
+        match node.kind() {
+            Kind::Token => {
+                writeln!(
+                    o,
+                    "{:n$}{}{:pad$}{}{}{} {}",
+                    "",
+                    data,
+                    "",
+                    at,
+                    error,
+                    trivia,
+                    source(span),
+                    n = n,
+                    pad = pad
+                )?;
+            }
+            Kind::Node => {
+                writeln!(
+                    o,
+                    "{:n$}{}{:pad$}{}{}",
+                    "",
+                    data,
+                    "",
+                    at,
+                    error,
+                    n = n,
+                    pad = pad
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The source snippet of a token being printed.
+enum Snippet<'a> {
+    /// No source is available.
+    Missing,
+    /// The span of the token doesn't refer to a valid range in the source.
+    Invalid,
+    /// The source of the token, and whether it has been truncated.
+    Text(Cow<'a, str>, bool),
+}
+
+impl<'a> Snippet<'a> {
+    fn lookup(source: &'a impl Source, span: &Span, options: &SourceOptions) -> Self {
+        let Some(mut text) = source.slice(span.range()) else {
+            return Snippet::Invalid;
+        };
+
+        let Some(max_len) = options.max_len() else {
+            return Snippet::Text(text, false);
+        };
+
+        let Some((end, _)) = text.char_indices().nth(max_len) else {
+            return Snippet::Text(text, false);
+        };
+
+        match &mut text {
+            Cow::Borrowed(text) => *text = &text[..end],
+            Cow::Owned(text) => text.truncate(end),
+        }
+
+        Snippet::Text(text, true)
+    }
+}
+
+impl fmt::Display for Snippet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Snippet::Missing => f.write_str("+"),
+            Snippet::Invalid => f.write_str("<invalid span>"),
+            Snippet::Text(text, truncated) => {
+                write!(f, "{:?}", text)?;
+
+                if *truncated {
+                    f.write_str("…")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Composable queries for finding nodes in a tree.
+//!
+//! A [`Query`] is a conjunction of filters on a node, such as its
+//! [`Kind`], its value, or that it has children or descendants matching other
+//! queries. Queries are executed through [`Tree::query`][crate::Tree::query], which yields the
+//! matching nodes lazily in document order.
+//!
+//! # Examples
+//!
+//! ```
+//! use syntree::query::Query;
+//!
+//! let tree = syntree::tree! {
+//!     "fn" => {
+//!         "call" => {
+//!             "ident" => { ("name", 3) },
+//!             "args" => { ("number", 1) },
+//!         },
+//!         "call" => {
+//!             "ident" => { ("name", 3) },
+//!             "args" => {},
+//!         },
+//!     }
+//! };
+//!
+//! let query = Query::new()
+//!     .value("call")
+//!     .child(Query::new().value("args").has_token("number"));
+//!
+//! let calls = tree.query(&query).map(|n| n.range()).collect::<Vec<_>>();
+//! assert_eq!(calls, [0..4]);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::rc::Rc;
+use core::fmt;
+use core::iter::FusedIterator;
+
+use crate::node::{Node, Walk};
+use crate::tree::Kind;
+
+/// A query matching nodes in a tree, see the [module level
+/// documentation][self].
+///
+/// Queries are immutable once constructed and can be used with any number of
+/// trees. Cloning a query is cheap, since filters are shared between clones.
+pub struct Query<T> {
+    last: Option<Rc<Link<T>>>,
+}
+
+impl<T> Query<T> {
+    /// Construct a new query which matches every node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::query::Query;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree.query(&Query::new()).count(), 2);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { last: None }
+    }
+
+    /// Only match nodes of the given [`Kind`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::query::Query;
+    /// use syntree::Kind;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         "group" => {},
+    ///     }
+    /// };
+    ///
+    /// let tokens = tree.query(&Query::new().kind(Kind::Token)).map(|n| *n.value());
+    /// assert_eq!(tokens.collect::<Vec<_>>(), ["lit"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn kind(self, kind: Kind) -> Self {
+        self.with(Filter::Kind(kind))
+    }
+
+    /// Only match nodes with the given `value`.
+    #[must_use]
+    pub fn value(self, value: T) -> Self {
+        self.with(Filter::Value(value))
+    }
+
+    /// Only match nodes which have a direct child matching `query`.
+    #[must_use]
+    pub fn child(self, query: Query<T>) -> Self {
+        self.with(Filter::Child(query))
+    }
+
+    /// Only match nodes which have a descendant matching `query`, not
+    /// including the node itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::query::Query;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "block" => {
+    ///         "block" => {
+    ///             "return" => {},
+    ///         },
+    ///     },
+    ///     "block" => {},
+    /// };
+    ///
+    /// let query = Query::new().value("block").descendant(Query::new().value("return"));
+    /// let blocks = tree.query(&query).map(|n| n.id().index());
+    /// assert_eq!(blocks.collect::<Vec<_>>(), [0, 1]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn descendant(self, query: Query<T>) -> Self {
+        self.with(Filter::Descendant(query))
+    }
+
+    /// Only match nodes which contain a token with the given `value`, at any
+    /// depth.
+    #[must_use]
+    pub fn has_token(self, value: T) -> Self {
+        self.with(Filter::HasToken(value))
+    }
+
+    /// Test if `node` matches the query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::query::Query;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "number" => {
+    ///         ("lit", 1),
+    ///     }
+    /// };
+    ///
+    /// let number = tree.first().ok_or("missing number")?;
+    ///
+    /// assert!(Query::new().value("number").has_token("lit").matches(number));
+    /// assert!(!Query::new().value("number").has_token("ident").matches(number));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn matches<S>(&self, node: Node<'_, T, S>) -> bool
+    where
+        T: PartialEq,
+    {
+        match &self.last {
+            Some(link) => link.matches(node),
+            None => true,
+        }
+    }
+
+    fn with(self, filter: Filter<T>) -> Self {
+        Self {
+            last: Some(Rc::new(Link {
+                filter,
+                prev: self.last,
+            })),
+        }
+    }
+}
+
+impl<T> Clone for Query<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            last: self.last.clone(),
+        }
+    }
+}
+
+impl<T> Default for Query<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Query<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        let mut filters = alloc::vec::Vec::new();
+        let mut current = self.last.as_deref();
+
+        while let Some(link) = current {
+            filters.push(&link.filter);
+            current = link.prev.as_deref();
+        }
+
+        list.entries(filters.iter().rev());
+        list.finish()
+    }
+}
+
+/// A filter in a query, linked to the filters added before it.
+struct Link<T> {
+    filter: Filter<T>,
+    prev: Option<Rc<Link<T>>>,
+}
+
+impl<T> Link<T> {
+    /// Test the filters in the order in which they were added, so that cheap
+    /// filters added early prune expensive ones added later.
+    fn matches<S>(&self, node: Node<'_, T, S>) -> bool
+    where
+        T: PartialEq,
+    {
+        if let Some(prev) = &self.prev {
+            if !prev.matches(node) {
+                return false;
+            }
+        }
+
+        self.filter.matches(node)
+    }
+}
+
+#[derive(Debug)]
+enum Filter<T> {
+    Kind(Kind),
+    Value(T),
+    Child(Query<T>),
+    Descendant(Query<T>),
+    HasToken(T),
+}
+
+impl<T> Filter<T> {
+    fn matches<S>(&self, node: Node<'_, T, S>) -> bool
+    where
+        T: PartialEq,
+    {
+        match self {
+            Filter::Kind(kind) => node.kind() == *kind,
+            Filter::Value(value) => node.value() == value,
+            Filter::Child(query) => node.children().any(|n| query.matches(n)),
+            Filter::Descendant(query) => node.walk().any(|n| query.matches(n)),
+            Filter::HasToken(value) => node
+                .walk()
+                .any(|n| n.kind() == Kind::Token && n.value() == value),
+        }
+    }
+}
+
+/// An iterator over the nodes matching a [`Query`], in document order.
+///
+/// See [`Tree::query`][crate::Tree::query].
+pub struct Matches<'a, T, S> {
+    walk: Walk<'a, T, S>,
+    query: Query<T>,
+}
+
+impl<'a, T, S> Matches<'a, T, S> {
+    #[inline]
+    pub(crate) fn new(walk: Walk<'a, T, S>, query: Query<T>) -> Self {
+        Self { walk, query }
+    }
+}
+
+impl<'a, T, S> Iterator for Matches<'a, T, S>
+where
+    T: PartialEq,
+{
+    type Item = Node<'a, T, S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let query = &self.query;
+        self.walk.find(|n| query.matches(*n))
+    }
+}
+
+impl<T, S> FusedIterator for Matches<'_, T, S> where T: PartialEq {}
+
+impl<T, S> Clone for Matches<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            walk: self.walk.clone(),
+            query: self.query.clone(),
+        }
+    }
+}
@@ -0,0 +1,442 @@
+//! Parsing of trees from their S-expression representation.
+//!
+//! This is the inverse of [`print::to_sexpr`], and is primarily intended for
+//! writing expected trees as readable strings in tests.
+//!
+//! [`print::to_sexpr`]: crate::print::to_sexpr
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::builder::Builder;
+use crate::error::Error;
+use crate::kind_name::KindName;
+use crate::print::Labels;
+use crate::span::Span;
+use crate::tree::Tree;
+
+/// Error raised when parsing a tree from an S-expression.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::sexpr::ParseErrorKind;
+///
+/// let error = syntree::from_sexpr("(root (lit \"a\")").unwrap_err();
+/// assert_eq!(error.offset(), 0);
+/// assert_eq!(*error.kind(), ParseErrorKind::UnclosedList);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    offset: usize,
+    kind: ParseErrorKind,
+}
+
+impl ParseError {
+    const fn new(offset: usize, kind: ParseErrorKind) -> Self {
+        Self { offset, kind }
+    }
+
+    /// The byte offset in the input at which the error occured.
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The kind of the error.
+    #[must_use]
+    pub const fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+}
+
+/// The kind of a [`ParseError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// A list was opened but never closed.
+    UnclosedList,
+    /// A list was closed without being opened.
+    UnexpectedClose,
+    /// A list is missing its leading name.
+    ExpectedName,
+    /// A token is expected to be closed after its string.
+    ExpectedClose,
+    /// A string literal or name is not in a legal position.
+    UnexpectedValue,
+    /// A string literal is missing its closing quote.
+    UnterminatedString,
+    /// An illegal escape sequence in a string literal.
+    BadEscape,
+    /// A name which is not present in the [`Labels`] table being used, or
+    /// which isn't recognized by [`KindName::from_kind_name`].
+    UnknownLabel(Box<str>),
+    /// An error raised while building the tree.
+    Build(Error),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+// `core::error::Error` is only stable since Rust 1.81, so it is opt-in.
+#[cfg(all(not(feature = "std"), feature = "core-error"))]
+#[allow(clippy::incompatible_msrv)]
+impl core::error::Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnclosedList => write!(f, "unclosed list")?,
+            ParseErrorKind::UnexpectedClose => write!(f, "unexpected `)`")?,
+            ParseErrorKind::ExpectedName => write!(f, "expected name")?,
+            ParseErrorKind::ExpectedClose => write!(f, "expected `)`")?,
+            ParseErrorKind::UnexpectedValue => write!(f, "unexpected value")?,
+            ParseErrorKind::UnterminatedString => write!(f, "unterminated string")?,
+            ParseErrorKind::BadEscape => write!(f, "bad escape sequence")?,
+            ParseErrorKind::UnknownLabel(label) => write!(f, "unknown label `{}`", label)?,
+            ParseErrorKind::Build(error) => error.fmt(f)?,
+        }
+
+        write!(f, " at byte {}", self.offset)
+    }
+}
+
+/// Parse a tree from its S-expression representation as produced by
+/// [`print::to_sexpr`].
+///
+/// Every list starts with a name, which is either a bare symbol or a quoted
+/// string. A list whose name is followed by a single string literal is a
+/// token, whose span is computed from the length of that string. Otherwise
+/// the list is a node whose children are the lists that follow.
+///
+/// [`print::to_sexpr`]: crate::print::to_sexpr
+///
+/// # Errors
+///
+/// Errors with a [`ParseError`] carrying the byte offset of the problem in
+/// case the input is malformed.
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::from_sexpr(r#"(root (number (lit "128")) (ws " ") (number (lit "64")))"#)?;
+///
+/// let expected = syntree::tree! {
+///     "root" => {
+///         "number" => {
+///             ("lit", 3)
+///         },
+///         ("ws", 1),
+///         "number" => {
+///             ("lit", 2)
+///         }
+///     }
+/// };
+///
+/// assert!(tree.walk().map(|n| (n.value().as_str(), *n.span())).eq(expected.walk().map(|n| (*n.value(), *n.span()))));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// Errors include the byte offset of the problem:
+///
+/// ```
+/// use syntree::sexpr::ParseErrorKind;
+///
+/// let error = syntree::from_sexpr(r#"(root (lit "a\q"))"#).unwrap_err();
+/// assert_eq!(error.offset(), 13);
+/// assert_eq!(*error.kind(), ParseErrorKind::BadEscape);
+///
+/// let error = syntree::from_sexpr("(root))").unwrap_err();
+/// assert_eq!(error.offset(), 6);
+/// assert_eq!(*error.kind(), ParseErrorKind::UnexpectedClose);
+/// ```
+pub fn from_sexpr(input: &str) -> Result<Tree<String, Span>, ParseError> {
+    parse(input, |name, _| Ok(name))
+}
+
+/// Parse a tree from its S-expression representation, using the given
+/// [`Labels`] to resolve names back into values.
+///
+/// This is the inverse of [`print::to_sexpr_labeled`].
+///
+/// [`print::to_sexpr_labeled`]: crate::print::to_sexpr_labeled
+///
+/// # Errors
+///
+/// Errors with a [`ParseError`] carrying the byte offset of the problem in
+/// case the input is malformed, or if a name is missing from `labels`.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::print::Labels;
+/// use syntree::sexpr::ParseErrorKind;
+///
+/// let labels = [(1u16, "NUMBER"), (2u16, "LIT")].into_iter().collect::<Labels<_>>();
+///
+/// let tree = syntree::sexpr::from_sexpr_labeled(r#"(NUMBER (LIT "128"))"#, &labels)?;
+///
+/// let expected = syntree::tree! {
+///     1u16 => {
+///         (2u16, 3)
+///     }
+/// };
+///
+/// assert_eq!(tree, expected);
+///
+/// let error = syntree::sexpr::from_sexpr_labeled(r#"(NUMBER (IDENT "a"))"#, &labels).unwrap_err();
+/// assert_eq!(error.offset(), 9);
+/// assert_eq!(*error.kind(), ParseErrorKind::UnknownLabel("IDENT".into()));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn from_sexpr_labeled<T>(input: &str, labels: &Labels<T>) -> Result<Tree<T, Span>, ParseError>
+where
+    T: Clone + Ord,
+{
+    parse(input, |name, offset| match labels.value(&name) {
+        Some(value) => Ok(value.clone()),
+        None => Err(ParseError::new(
+            offset,
+            ParseErrorKind::UnknownLabel(name.into()),
+        )),
+    })
+}
+
+/// Parse a tree from its S-expression representation, using
+/// [`KindName::from_kind_name`] to resolve names back into values.
+///
+/// This is the inverse of [`print::to_sexpr_named`].
+///
+/// [`print::to_sexpr_named`]: crate::print::to_sexpr_named
+///
+/// # Errors
+///
+/// Errors with a [`ParseError`] carrying the byte offset of the problem in
+/// case the input is malformed, or if a name isn't recognized.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::sexpr::ParseErrorKind;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Syntax {
+///     Number,
+///     Lit,
+/// }
+///
+/// syntree::kind_names! {
+///     Syntax {
+///         Number => "NUMBER",
+///         Lit => "LIT",
+///     }
+/// }
+///
+/// let tree = syntree::sexpr::from_sexpr_named::<Syntax>(r#"(NUMBER (LIT "128"))"#)?;
+///
+/// let expected = syntree::tree! {
+///     Syntax::Number => {
+///         (Syntax::Lit, 3)
+///     }
+/// };
+///
+/// assert_eq!(tree, expected);
+///
+/// let error = syntree::sexpr::from_sexpr_named::<Syntax>(r#"(Number (Lit "a"))"#).unwrap_err();
+/// assert_eq!(error.offset(), 1);
+/// assert_eq!(*error.kind(), ParseErrorKind::UnknownLabel("Number".into()));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn from_sexpr_named<T>(input: &str) -> Result<Tree<T, Span>, ParseError>
+where
+    T: KindName,
+{
+    parse(input, |name, offset| match T::from_kind_name(&name) {
+        Some(value) => Ok(value),
+        None => Err(ParseError::new(
+            offset,
+            ParseErrorKind::UnknownLabel(name.into()),
+        )),
+    })
+}
+
+/// Test if the given string can be written as a bare symbol.
+#[cfg(feature = "std")]
+pub(crate) fn is_symbol(s: &str) -> bool {
+    !s.is_empty()
+        && !s
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '"'))
+}
+
+fn parse<T, F>(input: &str, mut value: F) -> Result<Tree<T, Span>, ParseError>
+where
+    F: FnMut(String, usize) -> Result<T, ParseError>,
+{
+    let mut p = Parser { input, pos: 0 };
+    let mut builder = Builder::new();
+    let mut opens = Vec::new();
+
+    loop {
+        p.skip_whitespace();
+
+        let start = p.pos;
+
+        let Some(c) = p.peek() else {
+            break;
+        };
+
+        match c {
+            '(' => {
+                p.pos += 1;
+                p.skip_whitespace();
+                let name_offset = p.pos;
+                let name = p.name()?;
+                let data = value(name, name_offset)?;
+                p.skip_whitespace();
+
+                if let Some('"') = p.peek() {
+                    let text = p.string()?;
+                    p.skip_whitespace();
+
+                    if p.peek() != Some(')') {
+                        return Err(ParseError::new(p.pos, ParseErrorKind::ExpectedClose));
+                    }
+
+                    p.pos += 1;
+                    builder.token(data, text.len()).map_err(|e| p.build(e))?;
+                } else {
+                    builder.open(data).map_err(|e| p.build(e))?;
+                    opens.push(start);
+                }
+            }
+            ')' => {
+                if opens.pop().is_none() {
+                    return Err(ParseError::new(start, ParseErrorKind::UnexpectedClose));
+                }
+
+                p.pos += 1;
+                builder.close().map_err(|e| p.build(e))?;
+            }
+            _ => {
+                return Err(ParseError::new(start, ParseErrorKind::UnexpectedValue));
+            }
+        }
+    }
+
+    if let Some(&offset) = opens.last() {
+        return Err(ParseError::new(offset, ParseErrorKind::UnclosedList));
+    }
+
+    builder.build().map_err(|e| p.build(e))
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input.get(self.pos..)?.chars().next()
+    }
+
+    fn build(&self, error: Error) -> ParseError {
+        ParseError::new(self.pos, ParseErrorKind::Build(error))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+
+            self.pos += c.len_utf8();
+        }
+    }
+
+    /// Parse a name, which is either a bare symbol or a quoted string.
+    fn name(&mut self) -> Result<String, ParseError> {
+        if let Some('"') = self.peek() {
+            return self.string();
+        }
+
+        let start = self.pos;
+
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || matches!(c, '(' | ')' | '"') {
+                break;
+            }
+
+            self.pos += c.len_utf8();
+        }
+
+        if start == self.pos {
+            return Err(ParseError::new(start, ParseErrorKind::ExpectedName));
+        }
+
+        Ok(self.input[start..self.pos].to_owned())
+    }
+
+    /// Parse a quoted string literal, using the same escapes as produced by
+    /// the [`Debug`][fmt::Debug] implementation of [`str`].
+    fn string(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut out = String::new();
+
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(ParseError::new(start, ParseErrorKind::UnterminatedString));
+            };
+
+            let at = self.pos;
+            self.pos += c.len_utf8();
+
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escaped = match self.peek() {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some('\\') => '\\',
+                        Some('"') => '"',
+                        Some('\'') => '\'',
+                        Some('u') => {
+                            self.pos += 1;
+                            out.push(self.unicode(at)?);
+                            continue;
+                        }
+                        _ => return Err(ParseError::new(at, ParseErrorKind::BadEscape)),
+                    };
+
+                    self.pos += 1;
+                    out.push(escaped);
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// Parse the `{XXXX}` part of a unicode escape.
+    fn unicode(&mut self, at: usize) -> Result<char, ParseError> {
+        let error = ParseError::new(at, ParseErrorKind::BadEscape);
+
+        let rest = self.input.get(self.pos..).ok_or_else(|| error.clone())?;
+        let rest = rest.strip_prefix('{').ok_or_else(|| error.clone())?;
+        let end = rest.find('}').ok_or_else(|| error.clone())?;
+
+        let c = u32::from_str_radix(&rest[..end], 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(error)?;
+
+        self.pos += end + 2;
+        Ok(c)
+    }
+}
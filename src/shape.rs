@@ -0,0 +1,431 @@
+//! Classifying nodes according to declared shapes.
+//!
+//! A shape describes the value of a node together with the values of its
+//! node children, ignoring tokens. A set of shapes is collected in
+//! [`ShapeRules`] and nodes are classified against them with
+//! [`Node::classify`], which reports which shape matched and which children
+//! were bound to each part of it.
+//!
+//! # Examples
+//!
+//! ```
+//! use syntree::shape::{Child, ShapeError, ShapeRules};
+//!
+//! let mut rules = ShapeRules::new();
+//! let binary = rules.insert("binary", "expr", [Child::One("number"), Child::One("op"), Child::One("number")]);
+//! let call = rules.insert("call", "expr", [Child::One("ident"), Child::Many("arg")]);
+//! rules.insert("unit", "expr", []);
+//!
+//! let tree = syntree::tree! {
+//!     "expr" => {
+//!         "number" => { ("lit", 1) },
+//!         ("whitespace", 1),
+//!         "op" => { ("plus", 1) },
+//!         ("whitespace", 1),
+//!         "number" => { ("lit", 1) },
+//!     },
+//!     "expr" => {
+//!         "ident" => { ("lit", 3) },
+//!         "arg" => { ("lit", 1) },
+//!         "arg" => { ("lit", 1) },
+//!     },
+//!     "expr" => {
+//!         "ident" => { ("lit", 3) },
+//!         "number" => { ("lit", 1) },
+//!     },
+//! };
+//!
+//! let mut it = tree.children();
+//!
+//! let m = it.next().ok_or("missing binary")?.classify(&rules)?;
+//! assert_eq!(m.shape(), binary);
+//! assert_eq!(m.get(1).map(|n| *n.value()), Some("op"));
+//!
+//! let m = it.next().ok_or("missing call")?.classify(&rules)?;
+//! assert_eq!(m.shape(), call);
+//! assert_eq!(m.get_all(1).len(), 2);
+//!
+//! let node = it.next().ok_or("missing invalid call")?;
+//!
+//! let Err(ShapeError::NoMatch(near_misses)) = node.classify(&rules) else {
+//!     return Err("expected no match".into());
+//! };
+//!
+//! assert_eq!(near_misses.len(), 3);
+//! assert_eq!(near_misses[0].shape(), call);
+//! assert_eq!(near_misses[0].matched(), 1);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::fmt;
+
+use crate::builder::Id;
+use crate::node::Node;
+use crate::span::TreeSpan;
+use crate::tree::Kind;
+
+/// The identifier of a shape in [`ShapeRules`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ShapeId(usize);
+
+/// A pattern matching the node children of a shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Child<T> {
+    /// Matches exactly one node child with the given value.
+    One(T),
+    /// Matches zero or one node children with the given value.
+    Optional(T),
+    /// Matches any number of node children with the given value.
+    Many(T),
+}
+
+impl<T> Child<T> {
+    fn value(&self) -> &T {
+        match self {
+            Child::One(value) | Child::Optional(value) | Child::Many(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Shape<T> {
+    name: Box<str>,
+    value: T,
+    children: Vec<Child<T>>,
+}
+
+/// A table of shapes used by [`Node::classify`].
+///
+/// See the [module level documentation][self] for more.
+#[derive(Debug, Clone)]
+pub struct ShapeRules<T> {
+    shapes: Vec<Shape<T>>,
+}
+
+impl<T> ShapeRules<T> {
+    /// Construct a new empty set of shape rules.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { shapes: Vec::new() }
+    }
+
+    /// Declare a shape with the given `name`, matching a node with the given
+    /// `value` whose node children match the `children` patterns in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::shape::{Child, ShapeRules};
+    ///
+    /// let mut rules = ShapeRules::new();
+    /// let item = rules.insert("item", "item", [Child::One("ident"), Child::Optional("body")]);
+    ///
+    /// let tree = syntree::tree! {
+    ///     "item" => {
+    ///         "ident" => { ("lit", 3) },
+    ///     }
+    /// };
+    ///
+    /// let m = tree.first().ok_or("missing item")?.classify(&rules)?;
+    /// assert_eq!(m.shape(), item);
+    /// assert_eq!(rules.name(item), Some("item"));
+    /// assert!(m.get(1).is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn insert<N, C>(&mut self, name: N, value: T, children: C) -> ShapeId
+    where
+        N: Into<Box<str>>,
+        C: IntoIterator<Item = Child<T>>,
+    {
+        let id = ShapeId(self.shapes.len());
+
+        self.shapes.push(Shape {
+            name: name.into(),
+            value,
+            children: children.into_iter().collect(),
+        });
+
+        id
+    }
+
+    /// Get the name of the given shape.
+    #[must_use]
+    pub fn name(&self, shape: ShapeId) -> Option<&str> {
+        Some(&self.shapes.get(shape.0)?.name)
+    }
+}
+
+impl<T> Default for ShapeRules<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The result of [`Node::classify`].
+pub type ShapeResult<'a, T, S> = Result<ShapeMatch<'a, T, S>, ShapeError>;
+
+/// A successful match of a node against a shape.
+pub struct ShapeMatch<'a, T, S> {
+    shape: ShapeId,
+    name: &'a str,
+    node: Node<'a, T, S>,
+    children: Vec<Node<'a, T, S>>,
+    bindings: Vec<(usize, usize)>,
+}
+
+impl<'a, T, S> ShapeMatch<'a, T, S> {
+    /// The shape which matched.
+    #[must_use]
+    pub const fn shape(&self) -> ShapeId {
+        self.shape
+    }
+
+    /// The name of the shape which matched.
+    #[must_use]
+    pub const fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The node which was classified.
+    #[must_use]
+    pub const fn node(&self) -> Node<'a, T, S> {
+        self.node
+    }
+
+    /// Get the first node bound to the child pattern at `index` of the
+    /// shape.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<Node<'a, T, S>> {
+        self.get_all(index).first().copied()
+    }
+
+    /// Get all nodes bound to the child pattern at `index` of the shape.
+    #[must_use]
+    pub fn get_all(&self, index: usize) -> &[Node<'a, T, S>] {
+        let Some(&(start, end)) = self.bindings.get(index) else {
+            return &[];
+        };
+
+        self.children.get(start..end).unwrap_or_default()
+    }
+}
+
+impl<T, S> fmt::Debug for ShapeMatch<'_, T, S>
+where
+    T: fmt::Debug,
+    S: TreeSpan + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShapeMatch")
+            .field("shape", &self.shape)
+            .field("name", &self.name)
+            .field("node", &self.node)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A shape whose value matched but whose children did not, as reported by
+/// [`ShapeError::NoMatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearMiss {
+    shape: ShapeId,
+    name: Box<str>,
+    matched: usize,
+    found: Option<Id>,
+}
+
+impl NearMiss {
+    /// The shape which almost matched.
+    #[must_use]
+    pub const fn shape(&self) -> ShapeId {
+        self.shape
+    }
+
+    /// The name of the shape which almost matched.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of node children which matched before the first mismatch.
+    #[must_use]
+    pub const fn matched(&self) -> usize {
+        self.matched
+    }
+
+    /// The node child which didn't match, or `None` if the node ran out of
+    /// children.
+    #[must_use]
+    pub const fn found(&self) -> Option<Id> {
+        self.found
+    }
+}
+
+/// Error raised by [`Node::classify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShapeError {
+    /// No shape matched. Contains shapes whose value matched but whose
+    /// children did not, ordered by how far matching got before failing.
+    NoMatch(Vec<NearMiss>),
+    /// More than one shape matched.
+    Ambiguous(Vec<ShapeId>),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ShapeError {}
+
+// `core::error::Error` is only stable since Rust 1.81, so it is opt-in.
+#[cfg(all(not(feature = "std"), feature = "core-error"))]
+#[allow(clippy::incompatible_msrv)]
+impl core::error::Error for ShapeError {}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::NoMatch(near_misses) => {
+                write!(f, "no shape matched")?;
+
+                for (n, miss) in near_misses.iter().enumerate() {
+                    let sep = if n == 0 { ", near misses: " } else { ", " };
+                    write!(f, "{sep}`{}` after {} children", miss.name, miss.matched)?;
+                }
+
+                Ok(())
+            }
+            ShapeError::Ambiguous(shapes) => {
+                write!(f, "node matched {} shapes", shapes.len())
+            }
+        }
+    }
+}
+
+/// Classify `node` against `rules`, see [`Node::classify`].
+pub(crate) fn classify<'a, T, S>(
+    node: Node<'a, T, S>,
+    rules: &'a ShapeRules<T>,
+) -> ShapeResult<'a, T, S>
+where
+    T: PartialEq,
+{
+    let children = node
+        .children()
+        .filter(|n| n.kind() == Kind::Node)
+        .collect::<Vec<_>>();
+
+    let mut matched = None;
+    let mut ambiguous = Vec::new();
+    let mut near_misses = Vec::new();
+
+    for (index, shape) in rules.shapes.iter().enumerate() {
+        if shape.value != *node.value() {
+            continue;
+        }
+
+        let mut bindings = vec![(0, 0); shape.children.len()];
+        let mut furthest = 0;
+
+        if match_children(
+            &shape.children,
+            &children,
+            0,
+            0,
+            &mut bindings,
+            &mut furthest,
+        ) {
+            if matched.is_some() {
+                ambiguous.push(ShapeId(index));
+            } else {
+                matched = Some((ShapeId(index), shape, bindings));
+            }
+        } else {
+            near_misses.push(NearMiss {
+                shape: ShapeId(index),
+                name: shape.name.clone(),
+                matched: furthest,
+                found: children.get(furthest).map(|n| n.id()),
+            });
+        }
+    }
+
+    let Some((id, shape, bindings)) = matched else {
+        near_misses.sort_by_key(|miss| Reverse(miss.matched));
+        return Err(ShapeError::NoMatch(near_misses));
+    };
+
+    if !ambiguous.is_empty() {
+        ambiguous.insert(0, id);
+        return Err(ShapeError::Ambiguous(ambiguous));
+    }
+
+    Ok(ShapeMatch {
+        shape: id,
+        name: &shape.name,
+        node,
+        children,
+        bindings,
+    })
+}
+
+/// Match `patterns[p..]` against `children[c..]` with backtracking, recording
+/// the range bound to each pattern in `bindings` and the furthest child index
+/// at which matching failed in `furthest`.
+fn match_children<T, S>(
+    patterns: &[Child<T>],
+    children: &[Node<'_, T, S>],
+    p: usize,
+    c: usize,
+    bindings: &mut [(usize, usize)],
+    furthest: &mut usize,
+) -> bool
+where
+    T: PartialEq,
+{
+    let Some(pattern) = patterns.get(p) else {
+        if c == children.len() {
+            return true;
+        }
+
+        *furthest = (*furthest).max(c);
+        return false;
+    };
+
+    let value = pattern.value();
+
+    // The number of consecutive children matching the pattern value.
+    let run = children
+        .get(c..)
+        .unwrap_or_default()
+        .iter()
+        .take_while(|n| n.value() == value)
+        .count();
+
+    let (min, max) = match pattern {
+        Child::One(..) => (1, 1),
+        Child::Optional(..) => (0, 1),
+        Child::Many(..) => (0, run),
+    };
+
+    if run < min {
+        *furthest = (*furthest).max(c);
+        return false;
+    }
+
+    for n in (min..=max.min(run)).rev() {
+        if let Some(binding) = bindings.get_mut(p) {
+            *binding = (c, c + n);
+        }
+
+        if match_children(patterns, children, p + 1, c + n, bindings, furthest) {
+            return true;
+        }
+    }
+
+    false
+}
@@ -0,0 +1,93 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::ops::Range;
+
+/// A source which text can be extracted from by byte range, such as when
+/// printing a tree through [`print_with_source`].
+///
+/// This is implemented for [`str`] and [`String`], and for [`Rope`] if the
+/// `ropey` feature is enabled. Sources which don't store their text
+/// contiguously can implement it by returning an owned string assembled from
+/// their pieces.
+///
+/// [`print_with_source`]: crate::print::print_with_source
+/// [`Rope`]: https://docs.rs/ropey/latest/ropey/struct.Rope.html
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use std::ops::Range;
+///
+/// use syntree::Source;
+///
+/// /// A source stored as a sequence of lines.
+/// struct Lines(Vec<String>);
+///
+/// impl Source for Lines {
+///     fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+///         self.0.concat().get(range).map(|s| Cow::Owned(s.to_owned()))
+///     }
+/// }
+///
+/// let source = Lines(vec![String::from("128\n"), String::from("64")]);
+///
+/// let tree = syntree::tree! {
+///     "NUMBER" => {
+///         ("LIT", 3),
+///     },
+///     ("NEWLINE", 1),
+///     "NUMBER" => {
+///         ("LIT", 2),
+///     },
+/// };
+///
+/// let mut s = Vec::new();
+/// syntree::print::print_with_source(&mut s, &tree, &source)?;
+/// let s = String::from_utf8(s)?;
+///
+/// let mut expected = Vec::new();
+/// syntree::print::print_with_source(&mut expected, &tree, "128\n64")?;
+/// assert_eq!(s, String::from_utf8(expected)?);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub trait Source {
+    /// Get the text in the given byte `range` of the source.
+    ///
+    /// Returns `None` if the range is out of bounds or doesn't fall on
+    /// character boundaries.
+    fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>>;
+}
+
+impl Source for str {
+    #[inline]
+    fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+        self.get(range).map(Cow::Borrowed)
+    }
+}
+
+impl Source for String {
+    #[inline]
+    fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+        self.as_str().slice(range)
+    }
+}
+
+impl<T> Source for &T
+where
+    T: ?Sized + Source,
+{
+    #[inline]
+    fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+        (**self).slice(range)
+    }
+}
+
+#[cfg(feature = "ropey")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ropey")))]
+impl Source for ropey::Rope {
+    #[inline]
+    fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+        self.get_byte_slice(range).map(Cow::from)
+    }
+}
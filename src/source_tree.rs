@@ -0,0 +1,492 @@
+use alloc::string::String;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::{Deref, Range};
+
+use crate::builder::Id;
+use crate::error::Error;
+use crate::node::{Children, Node, Walk};
+use crate::span::Span;
+use crate::tree::{Kind, Tree};
+
+/// A tree bundled together with the source it was parsed from, as constructed
+/// through [`Tree::with_source`].
+///
+/// Nodes are accessed as [`SourceNode`] which can produce their text without
+/// being passed the source. The underlying tree is available through
+/// [`SourceTree::tree`] for everything else.
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "number" => {
+///         ("lit", 3),
+///     },
+///     ("ws", 1),
+///     "ident" => {
+///         ("lit", 5),
+///     },
+/// };
+///
+/// let tree = tree.with_source("123 hello")?;
+///
+/// let texts = tree.children().map(|n| n.text()).collect::<Vec<_>>();
+/// assert_eq!(texts, ["123", " ", "hello"]);
+///
+/// let lit = tree.token_at(5)?.ok_or("missing token")?;
+/// assert_eq!(lit.text(), "hello");
+/// assert_eq!(lit.parent().map(|n| *n.value()), Some("ident"));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct SourceTree<T> {
+    tree: Tree<T, Span>,
+    source: String,
+}
+
+impl<T> Tree<T, Span> {
+    /// Bundle the tree together with the `source` it was parsed from.
+    ///
+    /// See [`SourceTree`] for documentation.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::SourceMismatch`] if the span of any node doesn't
+    /// refer to a valid range in `source`, such as if the source is too short
+    /// or a span splits a character.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 2),
+    ///     }
+    /// };
+    ///
+    /// assert!(tree.clone().with_source("ab").is_ok());
+    /// assert!(tree.clone().with_source("é").is_ok());
+    /// assert!(matches!(tree.with_source("a"), Err(Error::SourceMismatch)));
+    ///
+    /// // The tokens would split the `é`.
+    /// let tree = syntree::tree! {
+    ///     ("a", 1),
+    ///     ("b", 1),
+    /// };
+    ///
+    /// assert!(matches!(tree.with_source("é"), Err(Error::SourceMismatch)));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_source<I>(self, source: I) -> Result<SourceTree<T>, Error>
+    where
+        I: Into<String>,
+    {
+        let source = source.into();
+
+        if source.get(self.range()).is_none() {
+            return Err(Error::SourceMismatch);
+        }
+
+        for node in self.walk() {
+            if source.get(node.range()).is_none() {
+                return Err(Error::SourceMismatch);
+            }
+        }
+
+        Ok(SourceTree { tree: self, source })
+    }
+}
+
+impl<T> SourceTree<T> {
+    /// Get the underlying tree.
+    #[must_use]
+    pub fn tree(&self) -> &Tree<T, Span> {
+        &self.tree
+    }
+
+    /// Get the source of the tree.
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Split into the tree and its source.
+    #[must_use]
+    pub fn into_parts(self) -> (Tree<T, Span>, String) {
+        (self.tree, self.source)
+    }
+
+    /// Associate a node of the underlying tree with the source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     ("lit", 2),
+    ///     ("lit", 3),
+    /// };
+    ///
+    /// let tree = tree.with_source("ab123")?;
+    /// let last = tree.tree().last().ok_or("missing last")?;
+    /// assert_eq!(tree.node(last).text(), "123");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn node<'a>(&'a self, node: Node<'a, T, Span>) -> SourceNode<'a, T> {
+        SourceNode {
+            node,
+            source: &self.source,
+        }
+    }
+
+    /// Get the node with the given identifier.
+    ///
+    /// See [`Tree::get`].
+    #[must_use]
+    pub fn get(&self, id: Id) -> Option<SourceNode<'_, T>> {
+        Some(self.node(self.tree.get(id)?))
+    }
+
+    /// Get the first root node in the tree.
+    ///
+    /// See [`Tree::first`].
+    #[must_use]
+    pub fn first(&self) -> Option<SourceNode<'_, T>> {
+        Some(self.node(self.tree.first()?))
+    }
+
+    /// Get the last root node in the tree.
+    ///
+    /// See [`Tree::last`].
+    #[must_use]
+    pub fn last(&self) -> Option<SourceNode<'_, T>> {
+        Some(self.node(self.tree.last()?))
+    }
+
+    /// Iterate over the root nodes of the tree.
+    ///
+    /// See [`Tree::children`].
+    #[must_use]
+    pub fn children(&self) -> WithSource<'_, Children<'_, T, Span>> {
+        WithSource::new(self.tree.children(), &self.source)
+    }
+
+    /// Walk every node in the tree.
+    ///
+    /// See [`Tree::walk`].
+    #[must_use]
+    pub fn walk(&self) -> WithSource<'_, Walk<'_, T, Span>> {
+        WithSource::new(self.tree.walk(), &self.source)
+    }
+
+    /// Query for the node that matches the given range.
+    ///
+    /// See [`Tree::node_with_range`].
+    #[must_use]
+    pub fn node_with_range(&self, range: Range<usize>) -> Option<SourceNode<'_, T>> {
+        Some(self.node(self.tree.node_with_range(range)?))
+    }
+
+    /// Get the token which covers the byte at `offset` in the source.
+    ///
+    /// Returns `None` if no token covers `offset`, which is always the case
+    /// at the end of the source.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::OffsetOutOfBounds`] if `offset` is past the end
+    /// of the source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Error;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 2),
+    ///         ("ws", 1),
+    ///     }
+    /// };
+    ///
+    /// let tree = tree.with_source("12 ")?;
+    ///
+    /// assert_eq!(tree.token_at(1)?.map(|n| n.text()), Some("12"));
+    /// assert_eq!(tree.token_at(2)?.map(|n| n.text()), Some(" "));
+    /// assert!(tree.token_at(3)?.is_none());
+    /// assert_eq!(tree.token_at(4).err(), Some(Error::OffsetOutOfBounds { offset: 4, len: 3 }));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn token_at(&self, offset: usize) -> Result<Option<SourceNode<'_, T>>, Error> {
+        if offset > self.source.len() {
+            return Err(Error::OffsetOutOfBounds {
+                offset,
+                len: self.source.len(),
+            });
+        }
+
+        // The node found is the parent of the first token which ends after
+        // `offset`.
+        let Some(parent) = self.tree.node_with_range(offset..offset) else {
+            return Ok(None);
+        };
+
+        let token = parent
+            .children()
+            .find(|n| n.kind() == Kind::Token && n.range().contains(&offset));
+
+        Ok(token.map(|n| self.node(n)))
+    }
+
+    /// Pretty-print the tree with the source, as with
+    /// [`print::print_with_source`].
+    ///
+    /// [`print::print_with_source`]: crate::print::print_with_source
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing to `o` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 2),
+    ///     }
+    /// };
+    ///
+    /// let tree = tree.with_source("12")?;
+    ///
+    /// let mut s = Vec::new();
+    /// tree.print(&mut s)?;
+    /// assert_eq!(String::from_utf8(s)?, "\"root\"@0..2\n  \"lit\"@0..2 \"12\"\n");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn print<O>(&self, o: O) -> std::io::Result<()>
+    where
+        O: std::io::Write,
+        T: fmt::Debug,
+    {
+        crate::print::print_with_source(o, &self.tree, &self.source)
+    }
+}
+
+impl<T> Clone for SourceTree<T>
+where
+    T: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            tree: self.tree.clone(),
+            source: self.source.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for SourceTree<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SourceTree")
+            .field("tree", &self.tree)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+/// A node together with the source of its tree, as returned by
+/// [`SourceTree`].
+///
+/// This dereferences to [`Node`], so every method of a node is available. The
+/// methods defined here return related nodes which keep the source.
+pub struct SourceNode<'a, T> {
+    node: Node<'a, T, Span>,
+    source: &'a str,
+}
+
+impl<'a, T> SourceNode<'a, T> {
+    /// Get the underlying node.
+    #[must_use]
+    pub const fn node(&self) -> Node<'a, T, Span> {
+        self.node
+    }
+
+    /// Get the text of the node in the source.
+    #[must_use]
+    pub fn text(&self) -> &'a str {
+        // The spans of all nodes are checked in `Tree::with_source`.
+        self.source.get(self.node.range()).unwrap_or_default()
+    }
+
+    /// Get the parent of the node.
+    #[must_use]
+    pub fn parent(&self) -> Option<Self> {
+        Some(self.with(self.node.parent()?))
+    }
+
+    /// Get the first child of the node.
+    #[must_use]
+    pub fn first(&self) -> Option<Self> {
+        Some(self.with(self.node.first()?))
+    }
+
+    /// Get the last child of the node.
+    #[must_use]
+    pub fn last(&self) -> Option<Self> {
+        Some(self.with(self.node.last()?))
+    }
+
+    /// Get the next sibling of the node.
+    #[must_use]
+    pub fn next(&self) -> Option<Self> {
+        Some(self.with(self.node.next()?))
+    }
+
+    /// Get the previous sibling of the node.
+    #[must_use]
+    pub fn prev(&self) -> Option<Self> {
+        Some(self.with(self.node.prev()?))
+    }
+
+    /// Iterate over the children of the node.
+    #[must_use]
+    pub fn children(&self) -> WithSource<'a, Children<'a, T, Span>> {
+        WithSource::new(self.node.children(), self.source)
+    }
+
+    /// Walk the subtree of the node.
+    #[must_use]
+    pub fn walk(&self) -> WithSource<'a, Walk<'a, T, Span>> {
+        WithSource::new(self.node.walk(), self.source)
+    }
+
+    #[inline]
+    fn with(&self, node: Node<'a, T, Span>) -> Self {
+        Self {
+            node,
+            source: self.source,
+        }
+    }
+}
+
+impl<'a, T> Deref for SourceNode<'a, T> {
+    type Target = Node<'a, T, Span>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl<T> Clone for SourceNode<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SourceNode<'_, T> {}
+
+impl<T> PartialEq for SourceNode<'_, T>
+where
+    T: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+}
+
+impl<T> Eq for SourceNode<'_, T> where T: Eq {}
+
+impl<T> fmt::Debug for SourceNode<'_, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SourceNode")
+            .field("value", self.node.value())
+            .field("kind", &self.node.kind())
+            .field("span", self.node.span())
+            .field("text", &self.text())
+            .finish()
+    }
+}
+
+/// An iterator which associates the nodes of another iterator with the source
+/// of their tree.
+///
+/// See [`SourceTree`].
+pub struct WithSource<'a, I> {
+    iter: I,
+    source: &'a str,
+}
+
+impl<'a, I> WithSource<'a, I> {
+    #[inline]
+    const fn new(iter: I, source: &'a str) -> Self {
+        Self { iter, source }
+    }
+}
+
+impl<'a, I, T> Iterator for WithSource<'a, I>
+where
+    I: Iterator<Item = Node<'a, T, Span>>,
+    T: 'a,
+{
+    type Item = SourceNode<'a, T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(SourceNode {
+            node: self.iter.next()?,
+            source: self.source,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, I, T> DoubleEndedIterator for WithSource<'a, I>
+where
+    I: DoubleEndedIterator<Item = Node<'a, T, Span>>,
+    T: 'a,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some(SourceNode {
+            node: self.iter.next_back()?,
+            source: self.source,
+        })
+    }
+}
+
+impl<'a, I, T> FusedIterator for WithSource<'a, I>
+where
+    I: FusedIterator<Item = Node<'a, T, Span>>,
+    T: 'a,
+{
+}
+
+impl<I> Clone for WithSource<'_, I>
+where
+    I: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            source: self.source,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Types to deal with spans in syntax trees.
 
+use alloc::vec::Vec;
 use core::fmt;
 use core::mem::size_of;
 use core::ops;
@@ -9,8 +10,17 @@ use crate::builder::Id;
 use crate::non_max::NonMax;
 
 /// The index used in a span.
+///
+/// This is `usize`, unless the crate is built with `--cfg syntree_compact` in
+/// which case it is `u32`.
+///
+/// This limits the size of the source that spans can represent to
+/// `Index::MAX` bytes, which is `u32::MAX` or just under 4 GiB with
+/// `syntree_compact`. Trying to build a tree past this limit results in an
+/// [`Error::SpanOverflow`][crate::Error::SpanOverflow] rather than wrapping
+/// around.
 #[cfg(syntree_compact)]
-pub(crate) type Index = u32;
+pub type Index = u32;
 
 #[cfg(syntree_compact)]
 #[inline]
@@ -18,8 +28,18 @@ pub(crate) fn usize_to_index(value: usize) -> Option<u32> {
     u32::try_from(value).ok()
 }
 
+/// The index used in a span.
+///
+/// This is `usize`, unless the crate is built with `--cfg syntree_compact` in
+/// which case it is `u32`.
+///
+/// This limits the size of the source that spans can represent to
+/// `Index::MAX` bytes, which is `u32::MAX` or just under 4 GiB with
+/// `syntree_compact`. Trying to build a tree past this limit results in an
+/// [`Error::SpanOverflow`][crate::Error::SpanOverflow] rather than wrapping
+/// around.
 #[cfg(not(syntree_compact))]
-pub(crate) type Index = usize;
+pub type Index = usize;
 
 #[cfg(not(syntree_compact))]
 #[inline]
@@ -33,7 +53,20 @@ const _: () = assert!(size_of::<Index>() <= size_of::<usize>());
 
 /// A span in the source code, akin to `start..end` so the end of the span is
 /// exclusive.
+///
+/// Spans are ordered by their start, and then by their end. So a span comes
+/// before any span which starts after it, and an empty span comes before
+/// every other span starting at the same offset.
+///
+/// ```
+/// use syntree::Span;
+///
+/// assert!(Span::new(2, 8) < Span::new(4, 5));
+/// assert!(Span::point(4) < Span::new(4, 5));
+/// assert!(Span::new(4, 5) < Span::new(4, 6));
+/// ```
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub struct Span {
     /// The start of the span.
@@ -85,6 +118,92 @@ impl Span {
         Self { start: at, end: at }
     }
 
+    /// Construct a span which starts at `start` and is `len` long.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end of the span doesn't fit in an [`Index`].
+    ///
+    /// ```should_panic
+    /// use syntree::span::{Index, Span};
+    ///
+    /// Span::with_len(Index::MAX, 1);
+    /// ```
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Span;
+    ///
+    /// const KEYWORD: Span = Span::with_len(4, 3);
+    ///
+    /// assert_eq!(KEYWORD, Span::new(4, 7));
+    /// assert_eq!(Span::with_len(4, 0), Span::point(4));
+    /// ```
+    #[must_use]
+    pub const fn with_len(start: Index, len: Index) -> Self {
+        let Some(end) = start.checked_add(len) else {
+            panic!("end of the span must fit in an index");
+        };
+
+        Self { start, end }
+    }
+
+    /// Move the span `delta` bytes forward, or backward if `delta` is
+    /// negative.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the moved span doesn't fit in an [`Index`], see
+    /// [`Span::checked_shift`] for a non-panicking alternative.
+    ///
+    /// ```should_panic
+    /// use syntree::Span;
+    ///
+    /// Span::new(2, 4).shift(-3);
+    /// ```
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Span;
+    ///
+    /// const SHIFTED: Span = Span::new(4, 8).shift(-2);
+    ///
+    /// assert_eq!(SHIFTED, Span::new(2, 6));
+    /// assert_eq!(Span::new(4, 8).shift(2), Span::new(6, 10));
+    /// ```
+    #[must_use]
+    pub const fn shift(self, delta: isize) -> Self {
+        let Some(span) = self.checked_shift(delta) else {
+            panic!("shifted span must fit in an index");
+        };
+
+        span
+    }
+
+    /// Move the span `delta` bytes forward, or backward if `delta` is
+    /// negative, or `None` if the moved span doesn't fit in an [`Index`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Span;
+    /// use syntree::span::Index;
+    ///
+    /// assert_eq!(Span::new(4, 8).checked_shift(-4), Some(Span::new(0, 4)));
+    /// assert_eq!(Span::new(4, 8).checked_shift(-5), None);
+    /// assert_eq!(Span::point(Index::MAX).checked_shift(1), None);
+    /// ```
+    #[must_use]
+    pub const fn checked_shift(self, delta: isize) -> Option<Self> {
+        let (Some(start), Some(end)) = (shift(self.start, delta), shift(self.end, delta)) else {
+            return None;
+        };
+
+        Some(Self { start, end })
+    }
+
     /// Join the current span with another.
     ///
     /// # Examples
@@ -117,6 +236,92 @@ impl Span {
         }
     }
 
+    /// Construct a span covering both the current span and `other`, which is
+    /// the same as [`Span::join`].
+    ///
+    /// This reads naturally when computing the span of a construct from its
+    /// first and last parts, such as `open.to(close)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Span;
+    ///
+    /// let open = Span::new(4, 5);
+    /// let close = Span::new(9, 10);
+    ///
+    /// assert_eq!(open.to(close), Span::new(4, 10));
+    /// assert_eq!(close.to(open), Span::new(4, 10));
+    /// ```
+    #[must_use]
+    pub const fn to(self, other: Self) -> Self {
+        self.join(&other)
+    }
+
+    /// Get the span of the gap between the current span and `other`,
+    /// regardless of which order they come in.
+    ///
+    /// The gap between adjacent spans is empty, and if the spans overlap there
+    /// is no gap so this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Span;
+    ///
+    /// let a = Span::new(4, 5);
+    /// let b = Span::new(9, 10);
+    ///
+    /// assert_eq!(a.between(b), Some(Span::new(5, 9)));
+    /// assert_eq!(b.between(a), Some(Span::new(5, 9)));
+    /// assert_eq!(a.between(Span::new(5, 6)), Some(Span::point(5)));
+    /// assert_eq!(a.between(Span::new(2, 10)), None);
+    /// ```
+    #[must_use]
+    pub const fn between(self, other: Self) -> Option<Self> {
+        let (first, second) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        if first.end > second.start {
+            return None;
+        }
+
+        Some(Self {
+            start: first.end,
+            end: second.start,
+        })
+    }
+
+    /// Construct a span from a range of offsets, or `None` if the range is
+    /// backwards or can't be represented.
+    ///
+    /// This is the non-panicking counterpart of converting from a [`Range`]
+    /// using [`From`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Span;
+    ///
+    /// assert_eq!(Span::try_from_range(4..8), Some(Span::new(4, 8)));
+    /// assert_eq!(Span::try_from_range(4..4), Some(Span::point(4)));
+    /// assert_eq!(Span::try_from_range(8..4), None);
+    /// ```
+    #[must_use]
+    pub fn try_from_range(range: Range<usize>) -> Option<Self> {
+        let start = usize_to_index(range.start)?;
+        let end = usize_to_index(range.end)?;
+
+        if start > end {
+            return None;
+        }
+
+        Some(Self { start, end })
+    }
+
     /// Coerce into a [`ops::Range`] which is useful for slicing.
     ///
     /// # Examples
@@ -181,6 +386,23 @@ impl Span {
     }
 }
 
+/// Move `index` by `delta`, or `None` if the result doesn't fit in an
+/// [`Index`].
+#[allow(clippy::unnecessary_cast)]
+const fn shift(index: Index, delta: isize) -> Option<Index> {
+    let abs = delta.unsigned_abs();
+
+    if abs > Index::MAX as usize {
+        return None;
+    }
+
+    if delta < 0 {
+        index.checked_sub(abs as Index)
+    } else {
+        index.checked_add(abs as Index)
+    }
+}
+
 impl fmt::Display for Span {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}..{}", self.start, self.end)
@@ -207,60 +429,294 @@ impl PartialEq<Span> for &Span {
     }
 }
 
-use std::ops::Range as StdRange;
-impl From<StdRange<usize>> for Span {
-    fn from(range: StdRange<usize>) -> Self {
+/// Convert a range into a span.
+///
+/// # Panics
+///
+/// Panics if the range is backwards, see [`Span::try_from_range`] for a
+/// non-panicking alternative.
+impl From<Range<Index>> for Span {
+    fn from(range: Range<Index>) -> Self {
         Self::new(range.start, range.end)
     }
 }
 
+/// Convert a range into a span.
+///
+/// # Panics
+///
+/// Panics if the range is backwards or its offsets don't fit in 32 bits, see
+/// [`Span::try_from_range`] for a non-panicking alternative.
+#[cfg(syntree_compact)]
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        let Some(span) = Self::try_from_range(range) else {
+            panic!("range must be forwards and fit in a span");
+        };
+
+        span
+    }
+}
+
+impl From<Span> for Range<usize> {
+    #[inline]
+    fn from(span: Span) -> Self {
+        span.range()
+    }
+}
+
+/// Iterate over the offsets covered by a span, the same as iterating over the
+/// [`Range`] it converts into.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::Span;
+///
+/// let offsets = Span::new(4, 7).into_iter().collect::<Vec<_>>();
+/// assert_eq!(offsets, [4, 5, 6]);
+/// assert!(Span::point(4).into_iter().next().is_none());
+/// ```
+impl IntoIterator for Span {
+    type Item = usize;
+    type IntoIter = Range<usize>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.range()
+    }
+}
+
+/// Convert a span into a pair of its start and end offsets.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::Span;
+///
+/// let (start, end) = Span::new(4, 8).into();
+/// assert_eq!((start, end), (4usize, 8usize));
+/// ```
+impl From<Span> for (usize, usize) {
+    #[inline]
+    #[allow(clippy::unnecessary_cast)]
+    fn from(span: Span) -> Self {
+        (span.start as usize, span.end as usize)
+    }
+}
+
+/// Convert a span into a [`miette::SourceSpan`] for use in diagnostics.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::Span;
+///
+/// let span = miette::SourceSpan::from(&Span::new(4, 8));
+/// assert_eq!(span.offset(), 4);
+/// assert_eq!(span.len(), 4);
+/// ```
+#[cfg(feature = "miette")]
+#[cfg_attr(docsrs, doc(cfg(feature = "miette")))]
+impl From<&Span> for miette::SourceSpan {
+    #[inline]
+    fn from(span: &Span) -> Self {
+        miette::SourceSpan::from(span.range())
+    }
+}
+
+#[cfg(feature = "miette")]
+#[cfg_attr(docsrs, doc(cfg(feature = "miette")))]
+impl From<Span> for miette::SourceSpan {
+    #[inline]
+    fn from(span: Span) -> Self {
+        miette::SourceSpan::from(span.range())
+    }
+}
+
 mod sealed {
     pub trait Sealed {}
 
-    impl Sealed for super::Span {}
     impl Sealed for super::Empty {}
     impl Sealed for usize {}
-    impl Sealed for Vec<super::TreeIndex> {}
+    impl Sealed for alloc::vec::Vec<super::TreeIndex> {}
 }
 
-/// Trait governing the behavior of a span, allowing it to either use the real
-/// [`Span`] or the zero-cost [`Empty`] span.
-pub trait TreeSpan: self::sealed::Sealed + Copy {
-    #[doc(hidden)]
+/// Trait governing the behavior of a span.
+///
+/// This is implemented by the real [`Span`] and the zero-cost [`Empty`] span,
+/// but can also be implemented for custom spans which carry additional data,
+/// such as the file a span belongs to. Trees with custom spans are built
+/// using [`Builder::new_with`] and [`Builder::token_with_span`].
+///
+/// The builder starts every node as a [`TreeSpan::point`] at the current
+/// cursor and combines it with the spans of its children through
+/// [`TreeSpan::join`]. So implementations which carry additional data should
+/// take it from the span being joined in if the current span is empty.
+///
+/// [`Builder::new_with`]: crate::Builder::new_with
+/// [`Builder::token_with_span`]: crate::Builder::token_with_span
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt;
+/// use std::ops::Range;
+///
+/// use syntree::span::{Index, TreeIndex, TreeSpan};
+/// use syntree::Builder;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// struct FileSpan {
+///     file: u32,
+///     start: Index,
+///     end: Index,
+/// }
+///
+/// impl TreeSpan for FileSpan {
+///     const EMPTY: Self = FileSpan { file: 0, start: 0, end: 0 };
+///     const INDEXES: Self::Indexes = Vec::new();
+///
+///     type Length = usize;
+///     type Indexes = Vec<TreeIndex>;
+///
+///     fn point(index: Index) -> Self {
+///         FileSpan { file: 0, start: index, end: index }
+///     }
+///
+///     fn new(start: Index, end: Index) -> Self {
+///         FileSpan { file: 0, start, end }
+///     }
+///
+///     fn start(&self) -> Index {
+///         self.start
+///     }
+///
+///     fn end(&self) -> Index {
+///         self.end
+///     }
+///
+///     fn set_end(&mut self, end: Index) {
+///         self.end = end;
+///     }
+///
+///     fn length(&self) -> usize {
+///         (self.end - self.start) as usize
+///     }
+///
+///     fn join(&self, other: &Self) -> Self {
+///         FileSpan {
+///             file: if self.is_empty() { other.file } else { self.file },
+///             start: self.start.min(other.start),
+///             end: self.end.max(other.end),
+///         }
+///     }
+/// }
+///
+/// let mut tree = Builder::<_, FileSpan>::new_with();
+///
+/// tree.open("root")?;
+/// tree.token_with_span("lit", FileSpan { file: 7, start: 0, end: 3 })?;
+/// tree.close()?;
+///
+/// let tree = tree.build()?;
+///
+/// let root = tree.first().ok_or("missing root")?;
+/// assert_eq!(*root.span(), FileSpan { file: 7, start: 0, end: 3 });
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub trait TreeSpan: Copy {
+    /// The span of an empty tree.
     const EMPTY: Self;
 
-    #[doc(hidden)]
+    /// The initial value of the token indexes used for range searches.
     const INDEXES: Self::Indexes;
 
-    #[doc(hidden)]
+    /// The length of a span, which is `usize` for spans that track offsets.
     type Length: Length;
 
-    #[doc(hidden)]
+    /// The token indexes used for range searches, which is
+    /// `Vec<TreeIndex>` for spans that track offsets.
     type Indexes: Indexes;
 
-    #[doc(hidden)]
+    /// Construct an empty span at the given index.
     fn point(index: Index) -> Self;
 
-    #[doc(hidden)]
+    /// Construct a span from `start` to `end`.
     fn new(start: Index, end: Index) -> Self;
 
-    #[doc(hidden)]
+    /// The start of the span.
     fn start(&self) -> Index;
 
-    #[doc(hidden)]
+    /// The end of the span.
     fn end(&self) -> Index;
 
-    #[doc(hidden)]
+    /// Modify the end of the span.
     fn set_end(&mut self, end: Index);
 
-    #[doc(hidden)]
-    fn len(&self) -> Index;
+    /// The length of the span.
+    #[inline]
+    fn len(&self) -> Index {
+        self.end().saturating_sub(self.start())
+    }
 
-    #[doc(hidden)]
-    fn range(self) -> Range<usize>;
+    /// Test if the span is empty.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The length of the span.
+    fn length(&self) -> Self::Length;
+
+    /// The range of the source covered by the span.
+    #[inline]
+    #[allow(clippy::unnecessary_cast)]
+    fn range(self) -> Range<usize> {
+        self.start() as usize..self.end() as usize
+    }
+
+    /// Construct a span covering both this span and `other`.
+    ///
+    /// This is used by the builder to compute the span of a node from the
+    /// spans of its children.
+    #[inline]
+    #[must_use]
+    fn join(&self, other: &Self) -> Self {
+        Self::new(self.start().min(other.start()), self.end().max(other.end()))
+    }
+
+    /// Format the span when printing a tree, such as through
+    /// [`print::print`] or the [`Debug`] implementation of [`Tree`].
+    ///
+    /// The default implementation writes `start..end`, override it to render
+    /// any additional information carried by the span.
+    ///
+    /// [`print::print`]: crate::print::print
+    /// [`Debug`]: core::fmt::Debug
+    /// [`Tree`]: crate::Tree
+    #[inline]
+    fn fmt_span(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start(), self.end())
+    }
 }
 
-#[doc(hidden)]
+/// Helper to display a span through [`TreeSpan::fmt_span`].
+pub(crate) struct FmtSpan<'a, S>(pub(crate) &'a S);
+
+impl<S> fmt::Display for FmtSpan<'_, S>
+where
+    S: TreeSpan,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_span(f)
+    }
+}
+
+/// The length of a [`TreeSpan`].
+///
+/// This trait is sealed and implemented for `usize` and [`Empty`].
 pub trait Length: self::sealed::Sealed + Copy {
     #[doc(hidden)]
     const EMPTY: Self;
@@ -270,27 +726,51 @@ pub trait Length: self::sealed::Sealed + Copy {
 
     #[doc(hidden)]
     fn into_index(self) -> Option<Index>;
+
+    /// Add this length to `index`, returning `None` if the result can't be
+    /// represented as an [`Index`]. All cursor arithmetic in the builder goes
+    /// through here.
+    #[doc(hidden)]
+    #[inline]
+    fn add_to(self, index: Index) -> Option<Index> {
+        index.checked_add(self.into_index()?)
+    }
 }
 
-#[doc(hidden)]
+/// The token indexes of a [`TreeSpan`], used for range searches.
+///
+/// This trait is sealed and implemented for `Vec<TreeIndex>` and [`Empty`].
 pub trait Indexes: self::sealed::Sealed {
     #[doc(hidden)]
     fn push(&mut self, cursor: Index, id: Id);
 
+    #[doc(hidden)]
+    fn insert(&mut self, cursor: Index, id: Id);
+
     #[doc(hidden)]
     fn binary_search(&self, index: Index) -> Result<usize, usize>;
 
     #[doc(hidden)]
     fn get(&self, index: usize) -> Option<Id>;
+
+    #[doc(hidden)]
+    fn sort(&mut self);
 }
 
+/// A single token index used for range searches in [`Indexes`].
 #[derive(Debug, Clone, Copy)]
-#[doc(hidden)]
 pub struct TreeIndex {
     pub(crate) index: Index,
     pub(crate) id: NonMax,
 }
 
+impl From<Span> for Empty {
+    #[inline]
+    fn from(_: Span) -> Self {
+        Empty
+    }
+}
+
 impl From<Empty> for usize {
     #[inline]
     fn from(Empty: Empty) -> Self {
@@ -304,6 +784,14 @@ impl Indexes for Vec<TreeIndex> {
         Vec::push(self, TreeIndex { index, id })
     }
 
+    #[inline]
+    fn insert(&mut self, index: Index, Id(id): Id) {
+        // Insert after any equal indexes, which is where sorting would have
+        // placed it.
+        let at = self.partition_point(|f| f.index <= index);
+        Vec::insert(self, at, TreeIndex { index, id });
+    }
+
     #[inline]
     fn binary_search(&self, index: Index) -> Result<usize, usize> {
         self.binary_search_by(|f| f.index.cmp(&index))
@@ -313,6 +801,11 @@ impl Indexes for Vec<TreeIndex> {
     fn get(&self, index: usize) -> Option<Id> {
         Some(Id(<[_]>::get(self, index)?.id))
     }
+
+    #[inline]
+    fn sort(&mut self) {
+        self.sort_by_key(|f| f.index);
+    }
 }
 
 impl Length for usize {
@@ -366,6 +859,12 @@ impl TreeSpan for Span {
         Span::len(self)
     }
 
+    #[inline]
+    #[allow(clippy::unnecessary_cast)]
+    fn length(&self) -> Self::Length {
+        Span::len(self) as usize
+    }
+
     #[inline]
     fn range(self) -> Range<usize> {
         Span::range(self)
@@ -378,6 +877,7 @@ impl TreeSpan for Span {
 ///
 /// [`Builder::new_with`]: crate::Builder::new_with
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 pub struct Empty;
 
@@ -416,6 +916,11 @@ impl TreeSpan for Empty {
         0
     }
 
+    #[inline]
+    fn length(&self) -> Self::Length {
+        Empty
+    }
+
     #[inline]
     fn range(self) -> Range<usize> {
         0..0
@@ -440,6 +945,9 @@ impl Indexes for Empty {
     #[inline]
     fn push(&mut self, _: Index, _: Id) {}
 
+    #[inline]
+    fn insert(&mut self, _: Index, _: Id) {}
+
     #[inline]
     fn binary_search(&self, _: Index) -> Result<usize, usize> {
         Err(0)
@@ -449,4 +957,7 @@ impl Indexes for Empty {
     fn get(&self, _: usize) -> Option<Id> {
         None
     }
+
+    #[inline]
+    fn sort(&mut self) {}
 }
@@ -0,0 +1,299 @@
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::ops::Range;
+use core::slice;
+
+use crate::builder::Id;
+use crate::node::Node;
+use crate::span::TreeSpan;
+use crate::tree::{Kind, Tree};
+
+/// A sorted index of the tokens in a tree, for repeated position lookups.
+///
+/// This is constructed once for a tree with [`TokenIndex::new`], and answers
+/// lookups through a binary search over the starting offsets of tokens.
+///
+/// Tokens are assumed not to overlap, which is always the case unless the tree
+/// was built with [`CursorPolicy::Unordered`].
+///
+/// # Zero-width tokens and gaps
+///
+/// Zero-width tokens are considered to sit *before* any non-empty token
+/// starting at the same offset. [`TokenIndex::token_at`] prefers a non-empty
+/// token covering an offset, and only returns a zero-width token if nothing
+/// else covers it. Offsets not covered by any token, such as in gaps left by
+/// [`CursorPolicy::Monotonic`], have no token. [`TokenIndex::node_covering`]
+/// on the other hand returns the interior node which covers a gap.
+///
+/// [`CursorPolicy::Unordered`]: crate::CursorPolicy::Unordered
+/// [`CursorPolicy::Monotonic`]: crate::CursorPolicy::Monotonic
+///
+/// # Examples
+///
+/// ```
+/// use syntree::TokenIndex;
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         "number" => {
+///             ("lit", 3),
+///         },
+///         ("ws", 1),
+///         "ident" => {
+///             ("lit", 2),
+///         },
+///     }
+/// };
+///
+/// let index = TokenIndex::new(&tree);
+///
+/// assert_eq!(index.token_at(4).map(|n| n.range()), Some(4..6));
+/// assert_eq!(index.token_at(6), None);
+///
+/// let tokens = index.tokens_in(2..5).map(|n| *n.value()).collect::<Vec<_>>();
+/// assert_eq!(tokens, ["lit", "ws", "lit"]);
+///
+/// let node = index.node_covering(4..5).ok_or("missing node")?;
+/// assert_eq!(*node.value(), "lit");
+/// assert_eq!(node.parent().map(|n| *n.value()), Some("ident"));
+///
+/// let node = index.node_covering(2..5).ok_or("missing node")?;
+/// assert_eq!(*node.value(), "root");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct TokenIndex<'a, T, S>
+where
+    S: TreeSpan,
+{
+    tree: &'a Tree<T, S>,
+    entries: Vec<Entry>,
+}
+
+/// A token in the index.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    start: usize,
+    end: usize,
+    id: Id,
+}
+
+impl Entry {
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl<'a, T, S> TokenIndex<'a, T, S>
+where
+    S: TreeSpan,
+{
+    /// Construct an index of the tokens in `tree`.
+    #[must_use]
+    pub fn new(tree: &'a Tree<T, S>) -> Self {
+        let mut entries = tree
+            .walk()
+            .filter(|n| n.kind() == Kind::Token)
+            .map(|n| {
+                let range = n.span().range();
+
+                Entry {
+                    start: range.start,
+                    end: range.end,
+                    id: n.id(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Stable, so tokens with equal spans stay in document order.
+        entries.sort_by_key(|e| (e.start, e.end));
+        Self { tree, entries }
+    }
+
+    /// The number of tokens in the index.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Test if the index has no tokens.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the token covering `offset`.
+    ///
+    /// A non-empty token covers the offsets from its start up to but not
+    /// including its end. If no such token exists, the first zero-width token
+    /// at `offset` is returned, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::TokenIndex;
+    ///
+    /// let tree = syntree::tree! {
+    ///     ("a", 2),
+    ///     ("empty1", 0),
+    ///     ("empty2", 0),
+    ///     ("b", 1),
+    ///     ("empty3", 0),
+    /// };
+    ///
+    /// let index = TokenIndex::new(&tree);
+    ///
+    /// assert_eq!(index.token_at(1).map(|n| *n.value()), Some("a"));
+    /// assert_eq!(index.token_at(2).map(|n| *n.value()), Some("b"));
+    /// assert_eq!(index.token_at(3).map(|n| *n.value()), Some("empty3"));
+    /// assert_eq!(index.token_at(4), None);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn token_at(&self, offset: usize) -> Option<Node<'a, T, S>> {
+        let end = self.entries.partition_point(|e| e.start <= offset);
+        let last = self.entries.get(end.checked_sub(1)?)?;
+
+        if last.end > offset {
+            return self.tree.get(last.id);
+        }
+
+        if last.start != offset {
+            return None;
+        }
+
+        // Every token starting at `offset` is zero-width, since the one with
+        // the greatest end is.
+        let start = self.entries.partition_point(|e| e.start < offset);
+        self.tree.get(self.entries.get(start)?.id)
+    }
+
+    /// Iterate over the tokens overlapping `range`, in order.
+    ///
+    /// Zero-width tokens are included if they are positioned within `range`,
+    /// including at its end. An empty `range` only matches zero-width tokens at
+    /// its offset, or a token which strictly contains it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::TokenIndex;
+    ///
+    /// let tree = syntree::tree! {
+    ///     ("a", 2),
+    ///     ("empty", 0),
+    ///     ("b", 1),
+    ///     ("c", 1),
+    /// };
+    ///
+    /// let index = TokenIndex::new(&tree);
+    ///
+    /// let values = |range| index.tokens_in(range).map(|n| *n.value()).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(values(1..3), ["a", "empty", "b"]);
+    /// assert_eq!(values(2..2), ["empty"]);
+    /// assert_eq!(values(1..1), ["a"]);
+    /// assert!(values(3..3).is_empty());
+    /// assert!(values(4..10).is_empty());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn tokens_in(&self, range: Range<usize>) -> TokensIn<'a, '_, T, S> {
+        let start = self
+            .entries
+            .partition_point(|e| e.end < range.start || (e.end == range.start && !e.is_empty()));
+
+        let end = self
+            .entries
+            .partition_point(|e| e.start < range.end || (e.start == range.end && e.is_empty()));
+
+        let entries = self.entries.get(start..end).unwrap_or_default();
+
+        TokensIn {
+            tree: self.tree,
+            iter: entries.iter(),
+        }
+    }
+
+    /// Get the smallest node whose span covers `range`.
+    ///
+    /// This looks up the token at the start of `range` and walks its
+    /// ancestors, starting with the token itself. If `range` starts in a gap
+    /// between tokens, the walk starts at the token preceding the gap, so the
+    /// interior node covering the gap is found. Since the walk starts at a
+    /// token, nodes which contain no tokens are never returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, TokenIndex};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// tree.open("root")?;
+    /// tree.token("a", 2)?;
+    /// tree.advance(2)?;
+    /// tree.token("b", 2)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    /// let index = TokenIndex::new(&tree);
+    ///
+    /// assert_eq!(index.node_covering(0..1).map(|n| *n.value()), Some("a"));
+    /// assert_eq!(index.node_covering(2..3).map(|n| *n.value()), Some("root"));
+    /// assert_eq!(index.node_covering(5..7), None);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn node_covering(&self, range: Range<usize>) -> Option<Node<'a, T, S>> {
+        let end = self.entries.partition_point(|e| e.start <= range.start);
+        let entry = self.entries.get(end.saturating_sub(1))?;
+        let token = self.tree.get(entry.id)?;
+
+        token.ancestors().find(|n| {
+            let span = n.span().range();
+            span.start <= range.start && span.end >= range.end
+        })
+    }
+}
+
+/// An iterator over the tokens overlapping a range, as returned by
+/// [`TokenIndex::tokens_in`].
+pub struct TokensIn<'a, 'b, T, S>
+where
+    S: TreeSpan,
+{
+    tree: &'a Tree<T, S>,
+    iter: slice::Iter<'b, Entry>,
+}
+
+impl<'a, T, S> Iterator for TokensIn<'a, '_, T, S>
+where
+    S: TreeSpan,
+{
+    type Item = Node<'a, T, S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tree.get(self.iter.next()?.id)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for TokensIn<'_, '_, T, S>
+where
+    S: TreeSpan,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.tree.get(self.iter.next_back()?.id)
+    }
+}
+
+impl<T, S> ExactSizeIterator for TokensIn<'_, '_, T, S> where S: TreeSpan {}
+
+impl<T, S> FusedIterator for TokensIn<'_, '_, T, S> where S: TreeSpan {}
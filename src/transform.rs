@@ -0,0 +1,207 @@
+//! Transforms which produce modified trees along with their source.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::builder::Builder;
+use crate::edit::ChangeSet;
+use crate::error::Error;
+use crate::node::Node;
+use crate::span::Span;
+use crate::tree::{Kind, Tree};
+
+/// The classification of a token used by [`normalize_whitespace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WsClass {
+    /// Horizontal whitespace, such as spaces and tabs.
+    Whitespace,
+    /// A token containing a line break.
+    Newline,
+    /// Any other token.
+    Other,
+}
+
+/// Normalize the whitespace in a tree and its source.
+///
+/// Tokens are classified using `classify`, and whitespace is rewritten
+/// according to the following rules:
+/// * Whitespace which is followed by a newline or the end of the source is
+///   removed.
+/// * Whitespace at the start of a line is preserved as indentation.
+/// * Any other run of whitespace is collapsed into a single space.
+///
+/// The text of every token which isn't whitespace is preserved. This returns
+/// the modified tree along with the regenerated source, with all spans
+/// recomputed to be consistent with the new source. Whitespace tokens which
+/// are removed are retained in the tree as empty tokens.
+///
+/// This is built on a [`ChangeSet`], which replaces every whitespace token
+/// whose text changes. The new source is produced through
+/// [`ChangeSet::apply_to_source`], the spans of the new tree are recomputed
+/// through [`ChangeSet::modify`], and the result is checked against the new
+/// source in the same way as [`Tree::with_source`].
+///
+/// [`ChangeSet`]: crate::edit::ChangeSet
+/// [`ChangeSet::apply_to_source`]: crate::edit::ChangeSet::apply_to_source
+/// [`ChangeSet::modify`]: crate::edit::ChangeSet::modify
+///
+/// # Errors
+///
+/// Errors with [`Error::SourceMismatch`] if the span of a token doesn't refer
+/// to a valid range in `source`, or if the tokens of the tree leave gaps
+/// since they can't be preserved. Errors with [`Error::Overflow`] in case we
+/// run out of node identifiers.
+///
+/// # Examples
+///
+/// ```
+/// use syntree::transform::{self, WsClass};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Syntax {
+///     ROOT,
+///     WORD,
+///     WS,
+///     NL,
+/// }
+///
+/// use Syntax::*;
+///
+/// let source = "a  \t b   \n  c \t ";
+///
+/// let tree = syntree::tree! {
+///     ROOT => {
+///         (WORD, 1),
+///         (WS, 2),
+///         (WS, 2),
+///         (WORD, 1),
+///         (WS, 3),
+///         (NL, 1),
+///         (WS, 2),
+///         (WORD, 1),
+///         (WS, 3),
+///     }
+/// };
+///
+/// let (tree, output) = transform::normalize_whitespace(&tree, source, |syntax| match syntax {
+///     WS => WsClass::Whitespace,
+///     NL => WsClass::Newline,
+///     _ => WsClass::Other,
+/// })?;
+///
+/// assert_eq!(output, "a b\n  c");
+/// assert_eq!(tree.span().range().end, output.len());
+///
+/// let text = tree.walk().filter(|n| n.kind() == syntree::Kind::Token).map(|n| &output[n.range()]).collect::<String>();
+/// assert_eq!(text, output);
+///
+/// let expected = syntree::tree! {
+///     ROOT => {
+///         (WORD, 1),
+///         (WS, 1),
+///         (WS, 0),
+///         (WORD, 1),
+///         (WS, 0),
+///         (NL, 1),
+///         (WS, 2),
+///         (WORD, 1),
+///         (WS, 0),
+///     }
+/// };
+///
+/// assert_eq!(tree, expected);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn normalize_whitespace<T, C>(
+    tree: &Tree<T, Span>,
+    source: &str,
+    classify: C,
+) -> Result<(Tree<T, Span>, String), Error>
+where
+    T: Clone,
+    C: Fn(&T) -> WsClass,
+{
+    if tree.gaps().next().is_some() {
+        return Err(Error::SourceMismatch);
+    }
+
+    let mut tokens = Vec::new();
+
+    for node in tree.walk() {
+        if matches!(node.kind(), Kind::Token) {
+            let text = source.get(node.range()).ok_or(Error::SourceMismatch)?;
+            tokens.push((node, classify(node.value()), text));
+        }
+    }
+
+    // Whitespace is trailing if the next token which isn't whitespace is a
+    // newline, or if there is none.
+    let mut trailing = vec![false; tokens.len()];
+    let mut before_newline = true;
+
+    for (&(_, class, _), trailing) in tokens.iter().zip(&mut trailing).rev() {
+        match class {
+            WsClass::Whitespace => *trailing = before_newline,
+            WsClass::Newline => before_newline = true,
+            WsClass::Other => before_newline = false,
+        }
+    }
+
+    let mut change_set = ChangeSet::new();
+    let mut line_start = true;
+    let mut in_run = false;
+
+    for (&(node, class, text), trailing) in tokens.iter().zip(trailing) {
+        match class {
+            WsClass::Whitespace => {
+                let replacement = if trailing || (in_run && !line_start) {
+                    ""
+                } else if line_start {
+                    text
+                } else {
+                    in_run = true;
+                    " "
+                };
+
+                if replacement != text {
+                    let insertion = change_set.replace(node.id(), token(node, replacement)?)?;
+                    change_set.set_text(insertion, replacement);
+                }
+            }
+            WsClass::Newline => {
+                line_start = true;
+                in_run = false;
+            }
+            WsClass::Other => {
+                if !text.is_empty() {
+                    line_start = false;
+                    in_run = false;
+                }
+            }
+        }
+    }
+
+    let output = change_set.apply_to_source(tree, source)?;
+    let tree = change_set.modify(tree)?;
+    debug_assert!(tree.validate().is_ok(), "modified tree is corrupt");
+    Ok(tree.with_source(output)?.into_parts())
+}
+
+/// Construct a replacement for the token `node` with the given text, which
+/// keeps its marks.
+fn token<T>(node: Node<'_, T, Span>, text: &str) -> Result<Tree<T, Span>, Error>
+where
+    T: Clone,
+{
+    let mut builder = Builder::new();
+    let id = builder.token(node.value().clone(), text.len())?;
+
+    if node.flags() != 0 {
+        builder.set_flags(id, node.flags())?;
+    }
+
+    builder.set_marks(id, node.marks())?;
+    builder.build()
+}
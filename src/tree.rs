@@ -1,14 +1,45 @@
+mod checked;
+mod concat;
+mod page;
+mod raw;
+mod validate;
+
+use alloc::vec::{Drain, Vec};
 use core::fmt;
+use core::hash::{Hash, Hasher};
 use core::ops::Range;
 
+#[cfg(feature = "binary")]
+use crate::binary::{self, Decode, DecodeError, Encode, EncodeSpan};
+use crate::builder::{Builder, CursorPolicy, Id};
+use crate::debug;
+use crate::error::Error;
+#[cfg(feature = "serde")]
+use crate::kind_name::KindName;
 use crate::links::Links;
 use crate::node::Node;
-use crate::node::{Children, Walk, WalkEvents};
+use crate::node::{
+    fold_subtree, visit_subtree, Ancestors, Children, Cursor, DescendantsWithValue, Event, Gaps,
+    NodesWithRange, ReusableNodes, Siblings, Visitor, Walk, WalkEvents, WalkRev, WalkWithSource,
+};
 use crate::non_max::NonMax;
+use crate::owned::TreeNode;
+use crate::owned_event::OwnedEvent;
+#[cfg(feature = "rayon")]
+use crate::par::{self, ParWalk};
+use crate::query::{Matches, Query};
 use crate::span::{usize_to_index, Index, Indexes, Span, TreeSpan};
 
+pub use self::checked::TreeId;
+pub use self::page::{Page, PageItem};
+pub use self::raw::{
+    Entries, IntoValues, Kinds, RawEntry, RawIter, RawTree, Spans, Values, ValuesMut,
+};
+pub use self::validate::{CorruptTree, Invariant, Link};
+
 /// The kind of a node in the [Tree].
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Kind {
     /// A node.
@@ -34,6 +65,8 @@ where
     first: Option<NonMax>,
     /// The last element in the tree.
     last: Option<NonMax>,
+    /// The number of root nodes in the tree.
+    roots: u32,
 }
 
 impl<T, S> Tree<T, S>
@@ -51,6 +84,7 @@ where
             indexes: S::INDEXES,
             first: None,
             last: None,
+            roots: 0,
         }
     }
 
@@ -65,17 +99,31 @@ where
             indexes: S::INDEXES,
             first: None,
             last: None,
+            roots: 0,
         }
     }
 
-    /// Get the span of the current node. The span of a node is the complete
-    /// span of all its children.
+    /// Try to reserve capacity for at least `additional` more nodes, doing
+    /// nothing if the allocation fails.
+    pub(crate) fn try_reserve(&mut self, additional: usize) {
+        _ = self.tree.try_reserve(additional);
+    }
+
+    /// Get the span of the whole tree, which covers all of its roots.
+    ///
+    /// This starts at the beginning of the source and ends after the last
+    /// token or gap added through the [`Builder`], so it is the empty span at
+    /// zero for a tree without tokens. The end of the span is the end of the
+    /// source, which is useful for reporting an unexpected end of input.
     ///
     /// # Examples
     ///
     /// ```
     /// use syntree::Span;
     ///
+    /// let empty = syntree::Builder::<()>::new().build()?;
+    /// assert_eq!(empty.span(), Span::point(0));
+    ///
     /// let tree = syntree::tree! {
     ///     "root" => {
     ///         "number" => {
@@ -110,87 +158,1548 @@ where
     /// let mut tree = syntree::Builder::<()>::new();
     /// let tree = tree.build()?;
     ///
-    /// assert_eq!(tree.len(), 0);
+    /// assert_eq!(tree.len(), 0);
+    ///
+    /// let mut tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("token", 2)
+    ///         },
+    ///         ("whitespace", 1),
+    ///         "child2" => {}
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree.len(), 5);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Check if the current tree is empty. In that it doesn't have any
+    /// childrens at the root of the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::<()>::new();
+    /// let tree = tree.build()?;
+    /// assert!(tree.is_empty());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Get the capacity of the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::<()>::new();
+    /// let tree = tree.build()?;
+    ///
+    /// assert_eq!(tree.capacity(), 0);
+    ///
+    /// let mut tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("token", 2)
+    ///         },
+    ///         ("whitespace", 1),
+    ///         "child2" => {}
+    ///     }
+    /// };
+    ///
+    /// assert!(tree.capacity() >= 5);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.tree.capacity()
+    }
+
+    /// Get all root nodes in the tree.
+    ///
+    /// See [Children] for documentation.
+    pub fn children(&self) -> Children<'_, T, S> {
+        Children::new(&self.tree, self.first, self.last, self.roots)
+    }
+
+    /// Get a cursor for navigating the tree, starting at its first root.
+    ///
+    /// Returns `None` if the tree is empty. See [`Cursor`] for documentation.
+    pub fn cursor(&self) -> Option<Cursor<'_, T, S>> {
+        let first = self.first()?;
+        Some(Cursor::new(first, Some(self.children())))
+    }
+
+    /// Walk the tree forwards in a depth-first fashion visiting every node once.
+    ///
+    /// See [`Walk`] for documentation.
+    pub fn walk(&self) -> Walk<'_, T, S> {
+        Walk::new(self.tree.as_slice(), self.first, self.tree.len(), true)
+    }
+
+    /// Walk the tree forwards like [`Tree::walk`], pairing every token with
+    /// the text it covers in `source`.
+    ///
+    /// See [`WalkWithSource`] for documentation.
+    pub fn walk_with_source<'s>(&self, source: &'s str) -> WalkWithSource<'_, 's, T, S> {
+        WalkWithSource::new(self.walk(), source)
+    }
+
+    /// Visit every node in the tree in parallel, in no particular order.
+    ///
+    /// Each node is visited exactly once, since the storage of the tree is
+    /// split between threads regardless of structure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rayon::prelude::*;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         ("lit", 2),
+    ///     }
+    /// };
+    ///
+    /// let count = tree.par_walk().filter(|n| *n.value() == "lit").count();
+    /// assert_eq!(count, 2);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub fn par_walk(&self) -> ParWalk<'_, T, S>
+    where
+        T: Sync,
+        S: Sync,
+    {
+        ParWalk::new(self.tree.as_slice())
+    }
+
+    /// Map every subtree rooted at `depth` in parallel with `map`, and reduce
+    /// the results with `reduce`.
+    ///
+    /// Nodes at the top level of the tree are at depth zero. Nodes above
+    /// `depth` are not passed to `map`, and `None` is returned if there are
+    /// no nodes at `depth`. Since results are reduced in no particular order,
+    /// `reduce` should be associative and commutative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "module" => {
+    ///         "fn" => { ("lit", 1), ("lit", 1) },
+    ///         "fn" => { ("lit", 1) },
+    ///         "fn" => {},
+    ///     }
+    /// };
+    ///
+    /// let tokens = tree.par_fold_subtrees(1, |n| n.walk().count(), |a, b| a + b);
+    /// assert_eq!(tokens, Some(3));
+    ///
+    /// assert_eq!(tree.par_fold_subtrees(3, |n| n.walk().count(), |a, b| a + b), None);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub fn par_fold_subtrees<R, M, F>(&self, depth: usize, map: M, reduce: F) -> Option<R>
+    where
+        T: Sync,
+        S: Sync,
+        R: Send,
+        M: Fn(Node<'_, T, S>) -> R + Sync + Send,
+        F: Fn(R, R) -> R + Sync + Send,
+    {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        par::subtrees(self.children(), depth)
+            .into_par_iter()
+            .map(map)
+            .reduce_with(reduce)
+    }
+
+    /// Find all nodes matching `query`, lazily and in document order.
+    ///
+    /// See [`Query`] and the [`query`][crate::query] module for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::query::Query;
+    /// use syntree::Kind;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "call" => {
+    ///         "ident" => { ("name", 3) },
+    ///         ("open", 1),
+    ///         ("close", 1),
+    ///     },
+    ///     "call" => {
+    ///         "path" => { ("name", 3) },
+    ///         ("open", 1),
+    ///         ("close", 1),
+    ///     },
+    /// };
+    ///
+    /// let query = Query::new()
+    ///     .kind(Kind::Node)
+    ///     .value("call")
+    ///     .child(Query::new().value("ident"));
+    ///
+    /// let calls = tree.query(&query).map(|n| n.range()).collect::<Vec<_>>();
+    /// assert_eq!(calls, [0..5]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn query(&self, query: &Query<T>) -> Matches<'_, T, S>
+    where
+        T: PartialEq,
+    {
+        Matches::new(self.walk(), query.clone())
+    }
+
+    /// Iterate over every node in the tree whose value is equal to `value`,
+    /// in the order they are visited by [`Tree::walk`].
+    ///
+    /// See [`DescendantsWithValue`] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "fn" => {
+    ///         ("ident", 1),
+    ///     },
+    ///     "fn" => {
+    ///         ("ident", 2),
+    ///     },
+    /// };
+    ///
+    /// let ranges = tree.descendants_with_value(&"fn").map(|n| n.range());
+    /// assert_eq!(ranges.collect::<Vec<_>>(), [0..1, 1..3]);
+    /// ```
+    pub fn descendants_with_value<'a>(&'a self, value: &'a T) -> DescendantsWithValue<'a, T, S>
+    where
+        T: PartialEq,
+    {
+        DescendantsWithValue::new(self.walk(), value)
+    }
+
+    /// Group the identifiers of every node in the tree by their value in a
+    /// single pass.
+    ///
+    /// The identifiers in each group are in the order they are visited by
+    /// [`Tree::walk`]. This is useful when looking up nodes by many different
+    /// values, where [`Tree::descendants_with_value`] would walk the tree once
+    /// for each value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "fn" => {
+    ///         ("ident", 1),
+    ///         "block" => {
+    ///             "fn" => {
+    ///                 ("ident", 1),
+    ///             },
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// let groups = tree.group_by_value();
+    ///
+    /// let functions = groups.get(&"fn").map_or(&[][..], Vec::as_slice);
+    /// let ranges = functions.iter().flat_map(|&id| tree.get(id)).map(|n| n.range());
+    /// assert_eq!(ranges.collect::<Vec<_>>(), [0..2, 1..2]);
+    ///
+    /// assert_eq!(groups.get(&"ident").map(Vec::len), Some(2));
+    /// assert!(groups.get(&"struct").is_none());
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[must_use]
+    pub fn group_by_value(&self) -> std::collections::HashMap<&T, Vec<Id>>
+    where
+        T: Eq + Hash,
+    {
+        let mut groups = std::collections::HashMap::<_, Vec<Id>>::new();
+
+        for node in self.walk() {
+            groups.entry(node.value()).or_default().push(node.id());
+        }
+
+        groups
+    }
+
+    /// Walk the tree backwards in a depth-first fashion visiting every node
+    /// once, in the exact reverse order of [`Tree::walk`].
+    ///
+    /// See [`WalkRev`] for documentation.
+    pub fn walk_rev(&self) -> WalkRev<'_, T, S> {
+        WalkRev::new(self.tree.as_slice(), self.last)
+    }
+
+    /// Iterate over every node in the tree in storage order.
+    ///
+    /// Storage order is the order in which nodes were added to the tree
+    /// during construction, which is *not* generally the same as document
+    /// order. For example, a node created through [`Builder::close_at`] is
+    /// stored after its children. No structural navigation takes place, which
+    /// makes this considerably faster than [`Tree::walk`] for bulk scans over
+    /// large trees which don't care about structure, such as counting the
+    /// number of nodes of a particular kind or summing up token widths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Kind;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// let c = tree.checkpoint()?;
+    /// tree.token("lit", 1)?;
+    /// tree.token("whitespace", 3)?;
+    /// tree.token("lit", 2)?;
+    /// tree.close_at(&c, "root")?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// assert_eq!(
+    ///     tree.raw_iter().map(|e| *e.value()).collect::<Vec<_>>(),
+    ///     ["lit", "whitespace", "lit", "root"]
+    /// );
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// let lit = tree.raw_iter().next().ok_or("missing lit")?;
+    /// assert_eq!(lit.parent(), Some(root.id()));
+    ///
+    /// let count = |kind| tree.raw_iter().filter(|e| e.kind() == kind).count();
+    /// assert_eq!(count(Kind::Token), tree.walk().filter(|n| n.kind() == Kind::Token).count());
+    /// assert_eq!(count(Kind::Node), tree.walk().filter(|n| n.kind() == Kind::Node).count());
+    ///
+    /// let width = tree
+    ///     .raw_iter()
+    ///     .filter(|e| e.kind() == Kind::Token)
+    ///     .map(|e| e.span().range().len())
+    ///     .sum::<usize>();
+    ///
+    /// assert_eq!(width, 6);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn raw_iter(&self) -> RawIter<'_, T, S> {
+        RawIter::new(self.tree.as_slice())
+    }
+
+    /// Get a read-only view of the storage of the tree, which is accessed by
+    /// identifier without constructing any [`Node`].
+    ///
+    /// Nodes are stored in the order in which they were allocated by the
+    /// builder, see [`RawTree`] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         ("ws", 1),
+    ///     }
+    /// };
+    ///
+    /// let raw = tree.as_raw();
+    ///
+    /// for node in tree.walk() {
+    ///     assert_eq!(raw.value_of(node.id()), Some(node.value()));
+    ///     assert_eq!(raw.parent_of(node.id()), node.parent().map(|n| n.id()));
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn as_raw(&self) -> RawTree<'_, T, S> {
+        RawTree::new(self.tree.as_slice(), self.first, self.last)
+    }
+
+    /// Iterate over the values of all nodes in the tree.
+    ///
+    /// Like [`Tree::raw_iter`], this iterates in storage order, which is the
+    /// order of node identifiers and not document order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         ("ws", 1),
+    ///         ("lit", 2),
+    ///     }
+    /// };
+    ///
+    /// let mut histogram = HashMap::<_, usize>::new();
+    ///
+    /// for value in tree.values() {
+    ///     *histogram.entry(*value).or_default() += 1;
+    /// }
+    ///
+    /// assert_eq!(tree.values().len(), 4);
+    /// assert_eq!(histogram.get("lit"), Some(&2));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn values(&self) -> Values<'_, T, S> {
+        Values::new(self.tree.as_slice())
+    }
+
+    /// Iterate mutably over the values of all nodes in the tree.
+    ///
+    /// This iterates in storage order like [`Tree::values`]. Only the values
+    /// are exposed, so the structure and spans of the tree are unaffected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         ("ws", 1),
+    ///     }
+    /// };
+    ///
+    /// for value in tree.values_mut() {
+    ///     if *value == "lit" {
+    ///         *value = "number";
+    ///     }
+    /// }
+    ///
+    /// let values = tree.walk().map(|n| *n.value()).collect::<Vec<_>>();
+    /// assert_eq!(values, ["root", "number", "ws"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, T, S> {
+        ValuesMut::new(self.tree.as_mut_slice())
+    }
+
+    /// Iterate over the spans of all nodes in the tree.
+    ///
+    /// Like [`Tree::raw_iter`], this iterates in storage order, which is the
+    /// order of node identifiers and not document order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         ("ws", 3),
+    ///     }
+    /// };
+    ///
+    /// let spans = tree.spans().map(|s| s.range()).collect::<Vec<_>>();
+    /// assert_eq!(spans, [0..4, 0..1, 1..4]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn spans(&self) -> Spans<'_, T, S> {
+        Spans::new(self.tree.as_slice())
+    }
+
+    /// Iterate over the identifiers, values, kinds and spans of all nodes in
+    /// the tree.
+    ///
+    /// Like [`Tree::raw_iter`], this iterates in storage order, which is the
+    /// order of node identifiers and not document order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::Kind;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 1),
+    ///         ("ws", 3),
+    ///     }
+    /// };
+    ///
+    /// let width = tree
+    ///     .entries()
+    ///     .filter(|(_, _, kind, _)| *kind == Kind::Token)
+    ///     .map(|(_, _, _, span)| span.range().len())
+    ///     .sum::<usize>();
+    ///
+    /// assert_eq!(width, 4);
+    ///
+    /// let (id, value, kind, _) = tree.entries().next().ok_or("missing root")?;
+    /// assert_eq!(tree.get(id).map(|n| *n.value()), Some(*value));
+    /// assert_eq!(kind, Kind::Node);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn entries(&self) -> Entries<'_, T, S> {
+        Entries::new(self.tree.as_slice())
+    }
+
+    /// Consume the tree and iterate over the values of all its nodes, which
+    /// recovers the owned data once the structure is no longer needed.
+    ///
+    /// Like [`Tree::raw_iter`], this iterates in storage order, which is the
+    /// order of node identifiers and not document order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     String::from("root") => {
+    ///         (String::from("lit"), 1),
+    ///     }
+    /// };
+    ///
+    /// let values = tree.into_values().collect::<Vec<String>>();
+    /// assert_eq!(values, ["root", "lit"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn into_values(self) -> IntoValues<T, S> {
+        IntoValues::new(self.tree)
+    }
+
+    /// Convert the tree into one with a different span type, by mapping every
+    /// span with `f`.
+    ///
+    /// The structure and node identifiers of the tree are preserved, and the
+    /// token indexes used for range queries are rebuilt from the mapped spans.
+    /// The function is called with the span of every node in storage order,
+    /// followed by the span of the whole tree.
+    ///
+    /// Mapped spans should nest the same way the original ones do, or range
+    /// queries such as [`Tree::node_with_range`] might not find what they're
+    /// looking for.
+    ///
+    /// # Examples
+    ///
+    /// Re-anchoring a tree which was parsed from the middle of a file:
+    ///
+    /// ```
+    /// use syntree::Span;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 3),
+    ///         ("ws", 1),
+    ///     }
+    /// };
+    ///
+    /// let lit = tree.first().and_then(|n| n.first()).ok_or("missing lit")?.id();
+    ///
+    /// let tree = tree.map_spans(|s| Span::new(s.start + 10, s.end + 10));
+    ///
+    /// assert_eq!(tree.span(), Span::new(10, 14));
+    /// assert_eq!(tree.get(lit).map(|n| n.range()), Some(10..13));
+    /// assert_eq!(tree.node_with_range(13..14).map(|n| *n.value()), Some("root"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn map_spans<S2, F>(self, mut f: F) -> Tree<T, S2>
+    where
+        S2: TreeSpan,
+        F: FnMut(S) -> S2,
+    {
+        let mut indexes = S2::INDEXES;
+        let mut tree = Vec::with_capacity(self.tree.len());
+
+        for (index, links) in self.tree.into_iter().enumerate() {
+            let span = f(links.span);
+
+            if links.kind == Kind::Token && !span.is_empty() {
+                if let Some(id) = NonMax::new(index) {
+                    indexes.push(span.end(), Id(id));
+                }
+            }
+
+            tree.push(Links {
+                data: links.data,
+                kind: links.kind,
+                span,
+                parent: links.parent,
+                prev: links.prev,
+                next: links.next,
+                first: links.first,
+                last: links.last,
+                len: links.len,
+                flags: links.flags,
+                marks: links.marks,
+            });
+        }
+
+        // The mapped spans of tokens aren't necessarily in storage order.
+        indexes.sort();
+
+        Tree {
+            tree,
+            span: f(self.span),
+            indexes,
+            first: self.first,
+            last: self.last,
+            roots: self.roots,
+        }
+    }
+
+    /// Convert the tree into one with a different span type through its
+    /// [`From`] implementation.
+    ///
+    /// See [`Tree::map_spans`] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::span::Empty;
+    /// use syntree::Tree;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 3),
+    ///     }
+    /// };
+    ///
+    /// let tree: Tree<_, Empty> = tree.cast_spans();
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(*root.span(), Empty);
+    /// assert_eq!(root.first().map(|n| *n.value()), Some("lit"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn cast_spans<S2>(self) -> Tree<T, S2>
+    where
+        S2: TreeSpan + From<S>,
+    {
+        self.map_spans(S2::from)
+    }
+
+    /// Walk the tree forwards in a depth-first fashion emitting events
+    /// indicating how the tree is being traversed.
+    ///
+    /// See [`WalkEvents`] for documentation.
+    pub fn walk_events(&self) -> WalkEvents<'_, T, S> {
+        WalkEvents::new(self.tree.as_slice(), self.first)
+    }
+
+    /// Visit every node in the tree with the given `visitor`.
+    ///
+    /// See [`Visitor`] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Node, Span, VisitControl, Visitor};
+    ///
+    /// #[derive(Default)]
+    /// struct Find {
+    ///     entered: Vec<&'static str>,
+    ///     found: Option<&'static str>,
+    /// }
+    ///
+    /// impl<'a> Visitor<'a, &'static str, Span> for Find {
+    ///     fn enter_node(&mut self, node: Node<'a, &'static str, Span>) -> VisitControl {
+    ///         self.entered.push(*node.value());
+    ///
+    ///         match *node.value() {
+    ///             "comment" => VisitControl::SkipSubtree,
+    ///             _ => VisitControl::Continue,
+    ///         }
+    ///     }
+    ///
+    ///     fn token(&mut self, node: Node<'a, &'static str, Span>) {
+    ///         self.found = self.found.or(Some(*node.value()));
+    ///     }
+    /// }
+    ///
+    /// let tree = syntree::tree! {
+    ///     "comment" => {
+    ///         ("text", 4)
+    ///     },
+    ///     "item" => {
+    ///         ("ident", 3)
+    ///     }
+    /// };
+    ///
+    /// let mut find = Find::default();
+    /// tree.visit(&mut find);
+    ///
+    /// assert_eq!(find.entered, ["comment", "item"]);
+    /// assert_eq!(find.found, Some("ident"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn visit<'a, V>(&'a self, visitor: &mut V)
+    where
+        V: ?Sized + Visitor<'a, T, S>,
+    {
+        for node in self.children() {
+            if !visit_subtree(node, visitor) {
+                return;
+            }
+        }
+    }
+
+    /// Reduce every root in the tree bottom-up, returning one result per
+    /// root.
+    ///
+    /// See [`Node::fold`] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "child" => {
+    ///             ("token", 1),
+    ///             ("token", 2),
+    ///         },
+    ///         ("token", 3),
+    ///     },
+    ///     ("token", 4),
+    /// };
+    ///
+    /// // Count the number of nodes in each root.
+    /// let counts = tree.fold(|_, children| 1 + children.sum::<usize>());
+    /// assert_eq!(counts, [5, 1]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn fold<'a, R, F>(&'a self, mut f: F) -> Vec<R>
+    where
+        F: FnMut(Node<'a, T, S>, Drain<'_, R>) -> R,
+    {
+        let mut output = Vec::new();
+        let mut results = Vec::new();
+        let mut starts = Vec::new();
+
+        for node in self.children() {
+            output.push(fold_subtree(node, &mut f, &mut results, &mut starts));
+        }
+
+        output
+    }
+
+    /// Get the node with the given identifier.
+    ///
+    /// Returns `None` if the identifier doesn't refer to a node in this tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// let root = tree.open("root")?;
+    /// let lit = tree.token("lit", 3)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// assert_eq!(tree.get(root).map(|n| *n.value()), Some("root"));
+    /// assert_eq!(tree.get(lit).map(|n| *n.value()), Some("lit"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn get(&self, id: Id) -> Option<Node<'_, T, S>> {
+        self.node_at(id.0)
+    }
+
+    /// Get a mutable reference to the value of the node with the given
+    /// identifier.
+    ///
+    /// Only the value is exposed, so the structure and spans of the tree are
+    /// unaffected. Returns `None` if the identifier doesn't refer to a node in
+    /// this tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// let lit = tree.token("placeholder", 3)?;
+    /// tree.close()?;
+    ///
+    /// let mut tree = tree.build()?;
+    ///
+    /// if let Some(value) = tree.value_mut(lit) {
+    ///     *value = "lit";
+    /// }
+    ///
+    /// assert_eq!(tree.get(lit).map(|n| *n.value()), Some("lit"));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn value_mut(&mut self, id: Id) -> Option<&mut T> {
+        Some(&mut self.tree.get_mut(id.0.get())?.data)
+    }
+
+    /// Construct a [`TreeId`] for the given node, which can only be resolved
+    /// against this tree through [`Tree::get_checked`].
+    ///
+    /// Returns `None` if the node doesn't belong to this tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let a = syntree::tree! {
+    ///     "root" => {}
+    /// };
+    ///
+    /// let b = syntree::tree! {
+    ///     "root" => {}
+    /// };
+    ///
+    /// let node = a.first().ok_or("missing root")?;
+    ///
+    /// assert!(a.checked_id(node).is_some());
+    /// assert!(b.checked_id(node).is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn checked_id(&self, node: Node<'_, T, S>) -> Option<TreeId<'_>> {
+        let storage = self.tree.as_ptr() as usize;
+
+        if node.storage().as_ptr() as usize != storage {
+            return None;
+        }
+
+        Some(TreeId::new(node.id(), storage))
+    }
+
+    /// Get the node with the given checked identifier.
+    ///
+    /// Unlike [`Tree::get`] this returns `None` if the identifier was taken
+    /// from a different tree.
+    ///
+    /// See [`TreeId`] for documentation.
+    #[must_use]
+    pub fn get_checked(&self, id: TreeId<'_>) -> Option<Node<'_, T, S>> {
+        if id.storage() != self.tree.as_ptr() as usize {
+            return None;
+        }
+
+        self.get(id.id())
+    }
+
+    /// Get an iterator over the children of the node with the given
+    /// identifier.
+    ///
+    /// This is the same as calling [`Node::children`] on the node returned by
+    /// [`Tree::get`], except that it produces an empty iterator if the
+    /// identifier doesn't refer to a node in this tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// let root = tree.open("root")?;
+    /// tree.token("a", 1)?;
+    /// tree.token("b", 1)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// assert!(tree.children_of(root).map(|n| *n.value()).eq(["a", "b"]));
+    ///
+    /// let mut other = syntree::Builder::new();
+    /// other.token("a", 1)?;
+    /// other.token("b", 1)?;
+    /// other.token("c", 1)?;
+    /// let invalid = other.token("d", 1)?;
+    ///
+    /// assert_eq!(tree.children_of(invalid).count(), 0);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn children_of(&self, id: Id) -> Children<'_, T, S> {
+        self.get(id).map(|n| n.children()).unwrap_or_default()
+    }
+
+    /// Get an iterator over the siblings of the node with the given
+    /// identifier, including the node itself.
+    ///
+    /// This is the same as calling [`Node::siblings`] on the node returned by
+    /// [`Tree::get`], except that it produces an empty iterator if the
+    /// identifier doesn't refer to a node in this tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.token("a", 1)?;
+    /// let b = tree.token("b", 1)?;
+    /// tree.token("c", 1)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// assert!(tree.siblings_of(b).map(|n| *n.value()).eq(["b", "c"]));
+    ///
+    /// let mut other = syntree::Builder::new();
+    /// other.token("a", 1)?;
+    /// other.token("b", 1)?;
+    /// other.token("c", 1)?;
+    /// other.token("d", 1)?;
+    /// let invalid = other.token("e", 1)?;
+    ///
+    /// assert_eq!(tree.siblings_of(invalid).count(), 0);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn siblings_of(&self, id: Id) -> Siblings<'_, T, S> {
+        self.get(id).map(|n| n.siblings()).unwrap_or_default()
+    }
+
+    /// Get an iterator over the ancestors of the node with the given
+    /// identifier, including the node itself.
+    ///
+    /// This is the same as calling [`Node::ancestors`] on the node returned
+    /// by [`Tree::get`], except that it produces an empty iterator if the
+    /// identifier doesn't refer to a node in this tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// tree.open("root")?;
+    /// tree.open("child")?;
+    /// let lit = tree.token("lit", 1)?;
+    /// tree.close()?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// assert!(tree.ancestors_of(lit).map(|n| *n.value()).eq(["lit", "child", "root"]));
+    ///
+    /// let mut other = syntree::Builder::new();
+    /// other.token("a", 1)?;
+    /// other.token("b", 1)?;
+    /// other.token("c", 1)?;
+    /// let invalid = other.token("d", 1)?;
+    ///
+    /// assert_eq!(tree.ancestors_of(invalid).count(), 0);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn ancestors_of(&self, id: Id) -> Ancestors<'_, T, S> {
+        self.get(id).map(|n| n.ancestors()).unwrap_or_default()
+    }
+
+    /// Walk the subtree of the node with the given identifier, starting with
+    /// its first child.
+    ///
+    /// This is the same as calling [`Node::walk`] on the node returned by
+    /// [`Tree::get`], except that it produces an empty iterator if the
+    /// identifier doesn't refer to a node in this tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// let root = tree.open("root")?;
+    /// tree.open("child")?;
+    /// tree.token("lit", 1)?;
+    /// tree.close()?;
+    /// tree.token("ws", 1)?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// assert!(tree.walk_from_id(root).map(|n| *n.value()).eq(["child", "lit", "ws"]));
+    ///
+    /// let mut other = syntree::Builder::new();
+    /// other.token("a", 1)?;
+    /// other.token("b", 1)?;
+    /// other.token("c", 1)?;
+    /// other.token("d", 1)?;
+    /// let invalid = other.token("e", 1)?;
+    ///
+    /// assert_eq!(tree.walk_from_id(invalid).count(), 0);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn walk_from_id(&self, id: Id) -> Walk<'_, T, S> {
+        self.get(id).map(|n| n.walk()).unwrap_or_default()
+    }
+
+    /// Walk the subtree of the node with the given identifier while emitting
+    /// events, starting with its first child.
+    ///
+    /// This is the same as calling [`Node::walk_events`] on the node returned
+    /// by [`Tree::get`], except that it produces an empty iterator if the
+    /// identifier doesn't refer to a node in this tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::node::Event::*;
+    ///
+    /// let mut tree = syntree::Builder::new();
+    ///
+    /// let root = tree.open("root")?;
+    /// tree.open("child")?;
+    /// tree.token("lit", 1)?;
+    /// tree.close()?;
+    /// tree.close()?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// assert!(tree.walk_events_from_id(root).map(|(e, n)| (e, *n.value())).eq([(Next, "child"), (Down, "lit"), (Up, "child")]));
+    ///
+    /// let mut other = syntree::Builder::new();
+    /// other.token("a", 1)?;
+    /// other.token("b", 1)?;
+    /// other.token("c", 1)?;
+    /// let invalid = other.token("d", 1)?;
+    ///
+    /// assert_eq!(tree.walk_events_from_id(invalid).count(), 0);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn walk_events_from_id(&self, id: Id) -> WalkEvents<'_, T, S> {
+        self.get(id).map(|n| n.walk_events()).unwrap_or_default()
+    }
+
+    /// Convert the tree into a sequence of [`OwnedEvent`]s.
+    ///
+    /// The tree can be reconstructed from these events using
+    /// [`Tree::from_owned_events`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{OwnedEvent, Span};
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 3)
+    ///     },
+    ///     ("ws", 1)
+    /// };
+    ///
+    /// assert_eq!(
+    ///     tree.to_owned_events(),
+    ///     [
+    ///         OwnedEvent::Enter("root", Span::new(0, 3)),
+    ///         OwnedEvent::Token("lit", Span::new(0, 3)),
+    ///         OwnedEvent::Leave,
+    ///         OwnedEvent::Token("ws", Span::new(3, 4)),
+    ///     ]
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn to_owned_events(&self) -> Vec<OwnedEvent<T, S>>
+    where
+        T: Clone,
+    {
+        let mut events = Vec::with_capacity(self.tree.len());
+
+        self.for_each_event(|event| {
+            events.push(match event {
+                OwnedEvent::Enter(value, span) => OwnedEvent::Enter(value.clone(), *span),
+                OwnedEvent::Token(value, span) => OwnedEvent::Token(value.clone(), *span),
+                OwnedEvent::Leave => OwnedEvent::Leave,
+            });
+        });
+
+        events
+    }
+
+    /// Serialize the tree as a sequence of [`OwnedEvent`]s without first
+    /// collecting them.
+    ///
+    /// This produces the same output as serializing the result of
+    /// [`Tree::to_owned_events`].
+    ///
+    /// # Errors
+    ///
+    /// Errors if serialization fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{OwnedEvent, Span, Tree};
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 3)
+    ///     }
+    /// };
+    ///
+    /// let mut out = Vec::new();
+    /// tree.write_events(&mut serde_json::Serializer::new(&mut out))?;
+    ///
+    /// let events: Vec<OwnedEvent<String, Span>> = serde_json::from_slice(&out)?;
+    /// let tree2 = Tree::from_owned_events(events)?;
+    ///
+    /// assert!(tree.walk().map(|n| *n.value()).eq(tree2.walk().map(|n| n.value().as_str())));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn write_events<O>(&self, serializer: O) -> Result<O::Ok, O::Error>
+    where
+        O: serde::Serializer,
+        T: serde::Serialize,
+        S: serde::Serialize,
+    {
+        use serde::ser::SerializeSeq;
+
+        let mut seq = serializer.serialize_seq(None)?;
+        let mut result = Ok(());
+
+        self.for_each_event(|event| {
+            if result.is_ok() {
+                result = seq.serialize_element(&event);
+            }
+        });
+
+        result?;
+        seq.end()
+    }
+
+    /// Serialize the tree as a sequence of [`OwnedEvent`]s where every value
+    /// is replaced with its [`KindName`].
+    ///
+    /// This produces the same output as [`Tree::write_events`] would for a
+    /// tree of strings, so it can be read back as
+    /// `OwnedEvent<String, S>`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if serialization fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #[derive(Debug, Clone, Copy)]
+    /// enum Syntax {
+    ///     Number,
+    ///     Lit,
+    /// }
+    ///
+    /// syntree::kind_names! {
+    ///     Syntax {
+    ///         Number => "NUMBER",
+    ///         Lit => "LIT",
+    ///     }
+    /// }
+    ///
+    /// let tree = syntree::tree! {
+    ///     Syntax::Number => {
+    ///         (Syntax::Lit, 3)
+    ///     }
+    /// };
+    ///
+    /// let mut out = Vec::new();
+    /// tree.write_events_named(&mut serde_json::Serializer::new(&mut out))?;
+    ///
+    /// assert_eq!(
+    ///     std::str::from_utf8(&out)?,
+    ///     r#"[{"Enter":["NUMBER",{"start":0,"end":3}]},{"Token":["LIT",{"start":0,"end":3}]},"Leave"]"#
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn write_events_named<O>(&self, serializer: O) -> Result<O::Ok, O::Error>
+    where
+        O: serde::Serializer,
+        T: KindName,
+        S: serde::Serialize,
+    {
+        use serde::ser::SerializeSeq;
+
+        let mut seq = serializer.serialize_seq(None)?;
+        let mut result = Ok(());
+
+        self.for_each_event(|event| {
+            if result.is_ok() {
+                let event = match event {
+                    OwnedEvent::Enter(value, span) => OwnedEvent::Enter(value.kind_name(), span),
+                    OwnedEvent::Token(value, span) => OwnedEvent::Token(value.kind_name(), span),
+                    OwnedEvent::Leave => OwnedEvent::Leave,
+                };
+
+                result = seq.serialize_element(&event);
+            }
+        });
+
+        result?;
+        seq.end()
+    }
+
+    /// Reconstruct a tree from a sequence of [`OwnedEvent`]s, such as those
+    /// produced by [`Tree::to_owned_events`].
+    ///
+    /// Tokens are placed at their spans and empty nodes at the start of
+    /// theirs, so gaps between tokens as permitted by
+    /// [`CursorPolicy::Monotonic`] are preserved. The spans of other nodes are
+    /// recomputed from their children in the same manner as when using a
+    /// [`Builder`].
+    ///
+    /// # Errors
+    ///
+    /// Since the events might come from an untrusted source, they are checked
+    /// to be balanced. A [`OwnedEvent::Leave`] without a matching
+    /// [`OwnedEvent::Enter`] errors with [`Error::CloseError`] and events
+    /// which leave nodes open error with [`Error::BuildError`]. A token or
+    /// empty node which starts before the end of the preceding token errors
+    /// with [`Error::CursorBackwards`], and a token whose span ends before it
+    /// starts with [`Error::BackwardsSpan`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Error, OwnedEvent, Span, Tree};
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 3),
+    ///         "empty" => {}
+    ///     }
+    /// };
+    ///
+    /// let events = tree.to_owned_events();
+    /// assert_eq!(Tree::from_owned_events(events)?, tree);
+    ///
+    /// let events = [
+    ///     OwnedEvent::Token("a", Span::new(0, 1)),
+    ///     OwnedEvent::Token("b", Span::new(5, 6)),
+    /// ];
+    ///
+    /// let tree = Tree::from_owned_events(events)?;
+    /// let ranges = tree.children().map(|n| n.range()).collect::<Vec<_>>();
+    /// assert_eq!(ranges, [0..1, 5..6]);
+    ///
+    /// let events = [OwnedEvent::Enter("root", Span::point(0))];
+    /// assert_eq!(Tree::from_owned_events(events), Err(Error::BuildError));
+    ///
+    /// let events = [OwnedEvent::Token("lit", Span::new(0, 1)), OwnedEvent::Leave];
+    /// assert_eq!(Tree::from_owned_events(events), Err(Error::CloseError));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_owned_events<I>(events: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = OwnedEvent<T, S>>,
+    {
+        let mut builder = Builder::new_with().with_policy(CursorPolicy::Monotonic);
+        let mut events = events.into_iter().peekable();
+
+        while let Some(event) = events.next() {
+            match event {
+                OwnedEvent::Enter(value, span) => {
+                    if let Some(OwnedEvent::Leave) = events.peek() {
+                        builder.advance_to(span.start())?;
+                    }
+
+                    builder.open(value)?;
+                }
+                OwnedEvent::Token(value, span) => {
+                    builder.token_with_span(value, span)?;
+                }
+                OwnedEvent::Leave => {
+                    builder.close()?;
+                }
+            }
+        }
+
+        builder.build()
+    }
+
+    /// Convert the tree into an owned, recursive representation.
+    ///
+    /// This is the inverse of [`Tree::from_owned_nodes`], and doesn't recurse
+    /// so it can convert trees of any depth.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::owned::TreeNode;
+    /// use syntree::Span;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 3),
+    ///     },
+    ///     ("eof", 0),
+    /// };
+    ///
+    /// assert_eq!(
+    ///     tree.to_owned_nodes(),
+    ///     [
+    ///         TreeNode::node("root", Span::new(0, 3), vec![TreeNode::token("lit", Span::new(0, 3))]),
+    ///         TreeNode::token("eof", Span::point(3)),
+    ///     ]
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_owned_nodes(&self) -> Vec<TreeNode<T, S>>
+    where
+        T: Clone,
+    {
+        let mut roots = Vec::with_capacity(self.roots as usize);
+        let mut stack = Vec::<TreeNode<T, S>>::new();
+
+        let mut finish = |stack: &mut Vec<TreeNode<T, S>>| {
+            let Some(node) = stack.pop() else {
+                return;
+            };
+
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => roots.push(node),
+            }
+        };
+
+        for (depth, node) in self.walk().with_depths() {
+            while stack.len() > depth {
+                finish(&mut stack);
+            }
+
+            stack.push(TreeNode {
+                value: node.value().clone(),
+                kind: node.kind(),
+                span: *node.span(),
+                children: Vec::with_capacity(node.children().len()),
+            });
+        }
+
+        while !stack.is_empty() {
+            finish(&mut stack);
+        }
+
+        roots
+    }
+
+    /// Reconstruct a tree from its owned, recursive representation, such as
+    /// the one produced by [`Tree::to_owned_nodes`].
+    ///
+    /// Tokens are placed at their spans and empty nodes at the start of
+    /// theirs, so gaps between tokens as permitted by
+    /// [`CursorPolicy::Monotonic`] are preserved. The spans of other nodes are
+    /// recomputed from their children in the same manner as when using a
+    /// [`Builder`]. This doesn't recurse so it can convert trees of any depth.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::TokenWithChildren`] if a node of kind
+    /// [`Kind::Token`] has children. A token or empty node which starts
+    /// before the end of the preceding token errors with
+    /// [`Error::CursorBackwards`], and a token whose span ends before it
+    /// starts with [`Error::BackwardsSpan`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::owned::TreeNode;
+    /// use syntree::{Error, Span, Tree};
+    ///
+    /// let nodes = vec![TreeNode::node(
+    ///     "root",
+    ///     Span::point(0),
+    ///     vec![TreeNode::token("lit", Span::new(4, 7))],
+    /// )];
+    ///
+    /// let tree = Tree::from_owned_nodes(nodes)?;
+    ///
+    /// let root = tree.first().ok_or("missing root")?;
+    /// assert_eq!(root.range(), 4..7);
+    /// assert_eq!(tree.to_owned_nodes()[0].children[0].span, Span::new(4, 7));
+    ///
+    /// let mut token = TreeNode::token("lit", Span::new(0, 3));
+    /// token.children.push(TreeNode::token("lit", Span::new(0, 3)));
+    ///
+    /// assert_eq!(Tree::from_owned_nodes(vec![token]), Err(Error::TokenWithChildren));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_owned_nodes(nodes: Vec<TreeNode<T, S>>) -> Result<Self, Error> {
+        let mut builder = Builder::new_with().with_policy(CursorPolicy::Monotonic);
+        let mut stack = Vec::from([nodes.into_iter()]);
+
+        while let Some(iter) = stack.last_mut() {
+            let Some(node) = iter.next() else {
+                stack.pop();
+
+                if !stack.is_empty() {
+                    builder.close()?;
+                }
+
+                continue;
+            };
+
+            let (value, kind, span, children) = node.into_parts();
+
+            match kind {
+                Kind::Token => {
+                    if !children.is_empty() {
+                        return Err(Error::TokenWithChildren);
+                    }
+
+                    builder.token_with_span(value, span)?;
+                }
+                Kind::Node => {
+                    if children.is_empty() {
+                        builder.advance_to(span.start())?;
+                    }
+
+                    builder.open(value)?;
+                    stack.push(children.into_iter());
+                }
+            }
+        }
+
+        builder.build()
+    }
+
+    /// Encode the tree into `out` using the [binary encoding][crate::binary].
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing to `out` fails, or with
+    /// [`std::io::ErrorKind::InvalidInput`] if the tokens of the tree are not
+    /// in document order.
     ///
-    /// let mut tree = syntree::tree! {
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
     ///     "root" => {
-    ///         "child" => {
-    ///             ("token", 2)
-    ///         },
-    ///         ("whitespace", 1),
-    ///         "child2" => {}
+    ///         ("lit", 3),
     ///     }
     /// };
     ///
-    /// assert_eq!(tree.len(), 5);
+    /// let mut out = Vec::new();
+    /// tree.encode(&mut out)?;
+    /// assert!(out.starts_with(b"SYNT"));
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
-    pub fn len(&self) -> usize {
-        self.tree.len()
+    #[cfg(feature = "binary")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "binary")))]
+    pub fn encode<W>(&self, mut out: W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+        T: Encode,
+        S: EncodeSpan,
+    {
+        binary::encode(self, &mut out)
     }
 
-    /// Check if the current tree is empty. In that it doesn't have any
-    /// childrens at the root of the tree.
+    /// Decode a tree from `input` which was encoded with [`Tree::encode`].
+    ///
+    /// This only supports the current version of the format, see
+    /// [`Tree::decode_compat`] for reading input written by older versions.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`DecodeError::Corrupt`] if the input is corrupt or
+    /// truncated, with [`DecodeError::UnsupportedVersion`] if it was written
+    /// with another version of the format, and with
+    /// [`DecodeError::IndexWidth`] or [`DecodeError::SpanMismatch`] if it was
+    /// written with a different configuration.
     ///
     /// # Examples
     ///
     /// ```
-    /// let mut tree = syntree::Builder::<()>::new();
-    /// let tree = tree.build()?;
-    /// assert!(tree.is_empty());
+    /// use syntree::binary::DecodeError;
+    /// use syntree::Tree;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("lit", 3),
+    ///     }
+    /// };
+    ///
+    /// let mut out = Vec::new();
+    /// tree.encode(&mut out)?;
+    ///
+    /// let decoded = Tree::<String>::decode(&out[..])?;
+    /// assert_eq!(decoded.first().map(|n| n.value().as_str()), Some("root"));
+    ///
+    /// let result = Tree::<String>::decode(&out[..out.len() - 1]);
+    /// assert!(matches!(result, Err(DecodeError::Corrupt(..))));
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
-    pub fn is_empty(&self) -> bool {
-        self.tree.is_empty()
+    #[cfg(feature = "binary")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "binary")))]
+    pub fn decode<R>(mut input: R) -> Result<Self, DecodeError>
+    where
+        R: std::io::Read,
+        T: Decode,
+        S: EncodeSpan,
+    {
+        binary::decode(&mut input, false)
     }
 
-    /// Get the capacity of the tree.
+    /// Decode a tree from `input` which was encoded with [`Tree::encode`] by
+    /// the current or any earlier version of the format.
+    ///
+    /// # Errors
+    ///
+    /// Errors in the same way as [`Tree::decode`], except that
+    /// [`DecodeError::UnsupportedVersion`] is only raised for versions which
+    /// are newer than [`binary::VERSION`].
     ///
     /// # Examples
     ///
     /// ```
-    /// let mut tree = syntree::Builder::<()>::new();
-    /// let tree = tree.build()?;
-    ///
-    /// assert_eq!(tree.capacity(), 0);
+    /// use syntree::Tree;
     ///
-    /// let mut tree = syntree::tree! {
+    /// let tree = syntree::tree! {
     ///     "root" => {
-    ///         "child" => {
-    ///             ("token", 2)
-    ///         },
-    ///         ("whitespace", 1),
-    ///         "child2" => {}
+    ///         ("lit", 3),
     ///     }
     /// };
     ///
-    /// assert!(tree.capacity() >= 5);
+    /// let mut out = Vec::new();
+    /// tree.encode(&mut out)?;
+    ///
+    /// let decoded = Tree::<String>::decode_compat(&out[..])?;
+    /// assert_eq!(decoded.range(), 0..3);
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
-    pub fn capacity(&self) -> usize {
-        self.tree.capacity()
+    #[cfg(feature = "binary")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "binary")))]
+    pub fn decode_compat<R>(mut input: R) -> Result<Self, DecodeError>
+    where
+        R: std::io::Read,
+        T: Decode,
+        S: EncodeSpan,
+    {
+        binary::decode(&mut input, true)
     }
 
-    /// Get all root nodes in the tree.
-    ///
-    /// See [Children] for documentation.
-    pub fn children(&self) -> Children<'_, T, S> {
-        Children::new(&self.tree, self.first, self.last)
-    }
+    /// Call `f` with every event of the tree in depth-first order.
+    pub(crate) fn for_each_event<'a, F>(&'a self, mut f: F)
+    where
+        F: FnMut(OwnedEvent<&'a T, &'a S>),
+    {
+        for (event, node) in self.walk_events() {
+            if let Event::Up = event {
+                f(OwnedEvent::Leave);
+                continue;
+            }
 
-    /// Walk the tree forwards in a depth-first fashion visiting every node once.
-    ///
-    /// See [`Walk`] for documentation.
-    pub fn walk(&self) -> Walk<'_, T, S> {
-        Walk::new(self.tree.as_slice(), self.first)
-    }
+            match node.kind() {
+                Kind::Token => {
+                    f(OwnedEvent::Token(node.value(), node.span()));
+                }
+                Kind::Node => {
+                    f(OwnedEvent::Enter(node.value(), node.span()));
 
-    /// Walk the tree forwards in a depth-first fashion emitting events
-    /// indicating how the tree is being traversed.
-    ///
-    /// See [`WalkEvents`] for documentation.
-    pub fn walk_events(&self) -> WalkEvents<'_, T, S> {
-        WalkEvents::new(self.tree.as_slice(), self.first)
+                    if node.is_empty() {
+                        f(OwnedEvent::Leave);
+                    }
+                }
+            }
+        }
     }
 
     /// Get the first child node in the tree.
@@ -229,14 +1738,141 @@ where
         self.node_at(self.last?)
     }
 
-    /// The first id currently being set.
-    pub(crate) fn first_id(&self) -> Option<NonMax> {
-        self.first
+    /// Get the first token in the tree in document order, across all of its
+    /// roots.
+    ///
+    /// Nodes without tokens are skipped, so this is `None` if the tree only
+    /// consists of empty nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "empty" => {},
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 3),
+    ///         },
+    ///         ("ws", 1),
+    ///     },
+    ///     ("eof", 0),
+    ///     "empty" => {},
+    /// };
+    ///
+    /// let first = tree.first_token().ok_or("missing first token")?;
+    /// assert_eq!(*first.value(), "lit");
+    ///
+    /// let last = tree.last_token().ok_or("missing last token")?;
+    /// assert_eq!(*last.value(), "eof");
+    /// assert_eq!(last.range(), 4..4);
+    ///
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "empty" => {},
+    ///     },
+    /// };
+    ///
+    /// assert!(tree.first_token().is_none());
+    /// assert!(tree.last_token().is_none());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn first_token(&self) -> Option<Node<'_, T, S>> {
+        self.walk().find(|n| n.kind() == Kind::Token)
+    }
+
+    /// Get the last token in the tree in document order, across all of its
+    /// roots.
+    ///
+    /// This walks the tree backwards so it only has to visit the nodes along
+    /// its trailing edge, unless the tree ends with nodes without tokens which
+    /// are skipped. See [`Tree::first_token`].
+    #[must_use]
+    pub fn last_token(&self) -> Option<Node<'_, T, S>> {
+        self.walk_rev().find(|n| n.kind() == Kind::Token)
+    }
+
+    /// Iterate over the ranges within the [span][Tree::span] of the tree which
+    /// are not covered by any token, in order.
+    ///
+    /// Unlike [`Node::gaps`] this includes ranges before the first and after
+    /// the last token. See [`Gaps`] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Span};
+    ///
+    /// let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    ///
+    /// tree.advance(2)?;
+    /// tree.open("number")?;
+    /// tree.token("lit", 3)?;
+    /// tree.close()?;
+    /// tree.advance(1)?;
+    ///
+    /// let tree = tree.build()?;
+    ///
+    /// assert_eq!(tree.span(), &Span::new(0, 6));
+    /// assert!(tree.gaps().eq([Span::new(0, 2), Span::new(5, 6)]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn gaps(&self) -> Gaps<'_, T, S> {
+        Gaps::new(self.walk(), self.span.start(), self.span.end())
+    }
+
+    /// Check that the tree upholds the invariants of trees constructed
+    /// through a [`Builder`].
+    ///
+    /// This checks that:
+    /// * All links between nodes are in bounds.
+    /// * Every node is reachable exactly once from the roots of the tree.
+    /// * Parent and sibling links agree with each other, the first and last
+    ///   children of each node agree with the chain of siblings between them,
+    ///   and so does the number of children.
+    /// * Tokens have no children.
+    /// * The span of every node with children is the union of the spans of
+    ///   its children, and the spans of siblings are ordered and don't
+    ///   overlap.
+    ///
+    /// Trees built with [`CursorPolicy::Unordered`] might legitimately have
+    /// siblings whose spans are out of order, which is reported as
+    /// [`Invariant::SpanOrder`].
+    ///
+    /// This is intended for testing code which constructs or modifies trees,
+    /// so it checks everything even though it is more expensive than walking
+    /// the tree.
+    ///
+    /// [`CursorPolicy::Unordered`]: crate::CursorPolicy::Unordered
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`CorruptTree`] naming the first node and invariant found
+    /// to be violated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 3),
+    ///         },
+    ///         ("ws", 1),
+    ///         "empty" => {},
+    ///     },
+    /// };
+    ///
+    /// tree.validate()?;
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn validate(&self) -> Result<(), CorruptTree> {
+        validate::validate(&self.tree, self.first, self.last, self.roots)
     }
 
-    /// Get the tree links mutably.
-    pub(crate) fn links_mut(&mut self) -> (&mut Option<NonMax>, &mut Option<NonMax>) {
-        (&mut self.first, &mut self.last)
+    /// Get the tree links and the number of roots mutably.
+    pub(crate) fn links_mut(&mut self) -> (&mut Option<NonMax>, &mut Option<NonMax>, &mut u32) {
+        (&mut self.first, &mut self.last, &mut self.roots)
     }
 
     /// Get a mutable reference to an element in the tree.
@@ -265,6 +1901,142 @@ where
         Some(Node::new(cur, &self.tree))
     }
 
+    /// Iterate over the maximal subtrees which neither intersect nor are
+    /// adjacent to the `edited` range of the source, in document order.
+    ///
+    /// These are the subtrees which can be reused when reparsing the source
+    /// after it has been edited, typically through [`Builder::reuse`]. Nodes
+    /// which are adjacent to the edit are excluded, since an edit right next
+    /// to a token might change how it is lexed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "number" => {
+    ///             ("lit", 2),
+    ///         },
+    ///         ("ws", 1),
+    ///         "number" => {
+    ///             ("lit", 2),
+    ///         },
+    ///         ("ws", 1),
+    ///         "number" => {
+    ///             ("lit", 2),
+    ///         },
+    ///     },
+    ///     ("eof", 0),
+    /// };
+    ///
+    /// let nodes = tree.reusable_nodes(3..4).map(|n| n.range()).collect::<Vec<_>>();
+    /// assert_eq!(nodes, [0..2, 5..6, 6..8, 8..8]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reusable_nodes(&self, edited: Range<usize>) -> ReusableNodes<'_, T, S> {
+        ReusableNodes::new(self.children(), edited)
+    }
+
+    /// Get a page of at most `limit` children of `parent`, or of the roots of
+    /// the tree if `parent` is `None`, with values rendered through `render`.
+    ///
+    /// Paging starts at the first child if `after` is `None`, and otherwise
+    /// continues with the child following `after`. Subsequent pages are
+    /// fetched by passing [`Page::after`] as `after` until
+    /// [`Page::has_more`] returns `false`.
+    ///
+    /// Since identifiers are assigned deterministically during construction,
+    /// cursors are stable across identical trees, so a page can be resumed in
+    /// a rebuilt tree as long as it is identical.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::MissingNode`] if `parent` is not a node in the
+    /// tree, or if `after` is not a child of `parent`. This is the case if a
+    /// cursor is used with a tree which has been changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         "a" => {
+    ///             ("lit", 1),
+    ///         },
+    ///         ("b", 1),
+    ///         ("c", 1),
+    ///     },
+    /// };
+    ///
+    /// let roots = tree.children_page(None, None, 10, |v| v.to_string())?;
+    /// let root = &roots.items()[0];
+    /// assert_eq!(root.value(), "root");
+    /// assert!(root.has_children());
+    /// assert!(!roots.has_more());
+    ///
+    /// let page = tree.children_page(Some(root.id()), None, 2, |v| v.to_string())?;
+    /// assert!(page.items().iter().map(|n| n.value()).eq(["a", "b"]));
+    /// assert!(page.has_more());
+    ///
+    /// let page = tree.children_page(Some(root.id()), page.after(), 2, |v| v.to_string())?;
+    /// assert!(page.items().iter().map(|n| n.value()).eq(["c"]));
+    /// assert_eq!(page.items()[0].span().range(), 2..3);
+    /// assert!(!page.has_more());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn children_page<F, V>(
+        &self,
+        parent: Option<Id>,
+        after: Option<Id>,
+        limit: usize,
+        mut render: F,
+    ) -> Result<Page<V, S>, Error>
+    where
+        F: FnMut(&T) -> V,
+    {
+        let parent = match parent {
+            Some(id) => Some(self.get(id).ok_or(Error::MissingNode(id))?),
+            None => None,
+        };
+
+        let mut current = match after {
+            Some(id) => {
+                let node = self
+                    .get(id)
+                    .filter(|n| n.parent().map(|p| p.id()) == parent.map(|p| p.id()))
+                    .ok_or(Error::MissingNode(id))?;
+
+                node.next()
+            }
+            None => match parent {
+                Some(parent) => parent.first(),
+                None => self.first(),
+            },
+        };
+
+        let mut items = Vec::new();
+        let mut last = after;
+
+        while items.len() < limit {
+            let Some(node) = current else {
+                break;
+            };
+
+            items.push(PageItem::new(
+                node.id(),
+                node.kind(),
+                *node.span(),
+                node.first().is_some(),
+                render(node.value()),
+            ));
+
+            last = Some(node.id());
+            current = node.next();
+        }
+
+        Ok(Page::new(items, last, current.is_some()))
+    }
+
     /// Access the [Span] of the node as a [Range].
     ///
     /// # Examples
@@ -295,7 +2067,11 @@ where
     /// Query for the node that matches the given range.
     ///
     /// This query finds the node which contains the entirety of the given
-    /// [Range].
+    /// [Range]. It starts from the token at the start of the range and walks
+    /// up its ancestors, so the *innermost* such node is returned, and tokens
+    /// themselves are never returned. If several nested nodes share the same
+    /// span, the innermost one is returned, use [`Tree::nodes_with_range`] to
+    /// get all of them.
     ///
     /// # Examples
     ///
@@ -372,10 +2148,121 @@ where
         self.node_with_span_internal(start, end)
     }
 
+    /// Get the chain of nested nodes which all cover exactly `range`, from
+    /// the outermost to the innermost.
+    ///
+    /// Unlike [`Tree::node_with_range`], which returns the innermost node
+    /// containing a range, this only yields nodes whose span is equal to
+    /// `range`, and includes tokens. The chain is found through a single
+    /// descent from the roots, after which children are followed for as long
+    /// as one covers the same range.
+    ///
+    /// See [`NodesWithRange`] for documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tree = syntree::tree! {
+    ///     "root" => {
+    ///         ("open", 1),
+    ///         "expr" => {
+    ///             "lit" => {
+    ///                 ("number", 2),
+    ///             },
+    ///         },
+    ///         ("close", 1),
+    ///     }
+    /// };
+    ///
+    /// let nodes = tree.nodes_with_range(1..3).map(|n| *n.value()).collect::<Vec<_>>();
+    /// assert_eq!(nodes, ["expr", "lit", "number"]);
+    ///
+    /// let nodes = tree.nodes_with_range(0..4).map(|n| *n.value()).collect::<Vec<_>>();
+    /// assert_eq!(nodes, ["root"]);
+    ///
+    /// assert_eq!(tree.nodes_with_range(1..2).count(), 0);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn nodes_with_range(&self, range: Range<usize>) -> NodesWithRange<'_, T, S> {
+        NodesWithRange::new(self.children(), range)
+    }
+
+    /// Get the ranges covered by nodes which have any of the given user `flag`
+    /// bits set, such as the nodes tagged through
+    /// [`ChangeSet::with_inserted_flags`].
+    ///
+    /// The spans of flagged tokens are merged into maximal ranges so that
+    /// adjacent regions are reported once, and are returned in order. Since
+    /// only tokens cover any source, a flagged node such as one added through
+    /// [`ChangeSet::wrap`] doesn't by itself mark the material it wraps.
+    ///
+    /// [`ChangeSet::with_inserted_flags`]: crate::edit::ChangeSet::with_inserted_flags
+    /// [`ChangeSet::wrap`]: crate::edit::ChangeSet::wrap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::edit::ChangeSet;
+    ///
+    /// const SYNTHESIZED: u8 = 0b1;
+    ///
+    /// let tree = syntree::tree! {
+    ///     "call" => {
+    ///         ("ident", 3),
+    ///         ("arg", 1),
+    ///     }
+    /// };
+    ///
+    /// let arg = tree.first().and_then(|n| n.last()).ok_or("missing arg")?;
+    ///
+    /// let mut change_set = ChangeSet::new().with_inserted_flags(SYNTHESIZED);
+    /// change_set.insert_before(arg.id(), syntree::tree!(("open", 1)))?;
+    /// change_set.insert_after(arg.id(), syntree::tree!(("close", 1)))?;
+    /// change_set.insert_after(arg.id(), syntree::tree!(("semi", 1)))?;
+    ///
+    /// let tree = change_set.modify(&tree)?;
+    ///
+    /// assert!(tree.synthesized_ranges(SYNTHESIZED).eq([3..4, 5..7]));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn synthesized_ranges(&self, flag: u8) -> impl Iterator<Item = Range<usize>> {
+        let mut ranges = Vec::new();
+
+        for node in self.walk() {
+            if node.kind() != Kind::Token || node.flags() & flag == 0 {
+                continue;
+            }
+
+            let range = node.span().range();
+
+            if !range.is_empty() {
+                ranges.push(range);
+            }
+        }
+
+        // Nodes are visited in document order, but spans might not be sorted
+        // depending on the cursor policy they were built with.
+        ranges.sort_by_key(|range| range.start);
+
+        let mut merged = Vec::<Range<usize>>::with_capacity(ranges.len());
+
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => merged.push(range),
+            }
+        }
+
+        merged.into_iter()
+    }
+
     /// Query the tree for the first node which encapsulates the whole `span`.
     ///
     /// This query finds the node which contains the entirety of the given
-    /// [Span].
+    /// [Span]. Like [`Tree::node_with_range`] this returns the innermost such
+    /// node, and never a token.
     ///
     /// # Examples
     ///
@@ -513,6 +2400,7 @@ where
             indexes: self.indexes.clone(),
             first: self.first,
             last: self.last,
+            roots: self.roots,
         }
     }
 }
@@ -544,26 +2432,76 @@ where
 {
 }
 
+/// Trees are hashed structurally, consistently with their [`PartialEq`]
+/// implementation. So they can be used as keys in caches.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// let a = syntree::tree! {
+///     "root" => {
+///         ("lit", 1),
+///     }
+/// };
+///
+/// let b = syntree::tree! {
+///     "root" => {
+///         ("lit", 1),
+///     }
+/// };
+///
+/// let c = syntree::tree! {
+///     "root" => {
+///         ("lit", 2),
+///     }
+/// };
+///
+/// let set = HashSet::from([a, b, c]);
+/// assert_eq!(set.len(), 2);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+impl<T, S> Hash for Tree<T, S>
+where
+    T: Hash,
+    S: TreeSpan + Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        for (depth, node) in self.walk().with_depths() {
+            depth.hash(state);
+            node.hash(state);
+        }
+    }
+}
+
+/// Trees are formatted in the same indented format as [`print::print`], where
+/// nodes nested deeper than eight levels are elided unless the alternate
+/// (`{:#?}`) format is used.
+///
+/// [`print::print`]: crate::print::print
+///
+/// # Examples
+///
+/// ```
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("lit", 3),
+///     }
+/// };
+///
+/// assert_eq!(format!("{tree:?}"), "\"root\"@0..3\n  \"lit\"@0..3 +");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
 impl<T, S> fmt::Debug for Tree<T, S>
 where
     T: fmt::Debug,
-    S: TreeSpan + fmt::Debug,
+    S: TreeSpan,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        struct List<'a, T, S>(&'a Tree<T, S>)
-        where
-            S: TreeSpan;
-
-        impl<T, S> fmt::Debug for List<'_, T, S>
-        where
-            T: fmt::Debug,
-            S: TreeSpan + fmt::Debug,
-        {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                f.debug_list().entries(self.0.walk().with_depths()).finish()
-            }
-        }
-
-        f.debug_tuple("Tree").field(&List(self)).finish()
+        debug::fmt_tree(f, self)
     }
 }
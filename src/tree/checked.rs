@@ -0,0 +1,76 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::builder::Id;
+
+/// An identifier which is checked to belong to a particular tree, as returned
+/// by [`Tree::checked_id`].
+///
+/// A plain [`Id`] is just an index, so resolving it against a tree other than
+/// the one it was taken from silently produces an unrelated node. A `TreeId`
+/// borrows the tree it was taken from and remembers where its nodes are
+/// stored, so [`Tree::get_checked`] returns `None` instead of resolving it
+/// against a different tree.
+///
+/// [`Tree::checked_id`]: crate::Tree::checked_id
+/// [`Tree::get_checked`]: crate::Tree::get_checked
+///
+/// # Examples
+///
+/// ```
+/// let a = syntree::tree! {
+///     "a" => {
+///         ("lit", 1),
+///     }
+/// };
+///
+/// let b = syntree::tree! {
+///     "b" => {
+///         ("lit", 1),
+///     }
+/// };
+///
+/// let node = a.first().ok_or("missing root")?;
+/// let id = a.checked_id(node).ok_or("node not in tree")?;
+///
+/// assert_eq!(a.get_checked(id).map(|n| *n.value()), Some("a"));
+/// assert!(b.get_checked(id).is_none());
+///
+/// // An unchecked identifier resolves against either tree.
+/// assert_eq!(b.get(id.id()).map(|n| *n.value()), Some("b"));
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TreeId<'a> {
+    id: Id,
+    storage: usize,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl TreeId<'_> {
+    pub(crate) const fn new(id: Id, storage: usize) -> Self {
+        Self {
+            id,
+            storage,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the unchecked identifier.
+    #[must_use]
+    pub const fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Address of the storage of the tree this identifier belongs to.
+    pub(crate) const fn storage(&self) -> usize {
+        self.storage
+    }
+}
+
+impl fmt::Debug for TreeId<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TreeId").field(&self.id).finish()
+    }
+}
@@ -0,0 +1,235 @@
+use crate::error::Error;
+use crate::links::Links;
+use crate::non_max::NonMax;
+use crate::span::{Index, Span, TreeIndex};
+use crate::tree::Tree;
+
+impl<T> Tree<T, Span> {
+    /// Concatenate a sequence of `trees` into a single forest, where the
+    /// roots of each tree are appended after the roots of the trees before
+    /// it.
+    ///
+    /// The spans of each tree are shifted so that it starts where the span of
+    /// the preceding tree ends, so trees parsed from consecutive chunks of a
+    /// source with spans relative to their chunk refer to the whole source
+    /// once concatenated. Use [`Tree::concat_ordered`] to keep the original
+    /// spans instead.
+    ///
+    /// Nodes keep their relative order in storage, so the identifier of a
+    /// node from one of the `trees` is its [`Id::index`] offset by the
+    /// combined [`Tree::len`] of the trees before it. Empty trees contribute
+    /// no nodes.
+    ///
+    /// [`Id::index`]: crate::Id::index
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::Overflow`] if the concatenated tree has too many
+    /// nodes, or if its spans no longer fit in an [`Index`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Id, Tree};
+    ///
+    /// let a = syntree::tree! {
+    ///     "fn" => {
+    ///         ("ident", 3),
+    ///     }
+    /// };
+    ///
+    /// let b = syntree::tree! {
+    ///     "struct" => {
+    ///         ("ident", 2),
+    ///     }
+    /// };
+    ///
+    /// let ident = b.first().and_then(|n| n.first()).ok_or("missing ident")?.id();
+    /// let offset = a.len();
+    ///
+    /// let tree = Tree::concat([a, b])?;
+    ///
+    /// let expected = syntree::tree! {
+    ///     "fn" => {
+    ///         ("ident", 3),
+    ///     },
+    ///     "struct" => {
+    ///         ("ident", 2),
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(tree, expected);
+    ///
+    /// let ident = Id::from_index(ident.index() + offset).and_then(|id| tree.get(id));
+    /// assert_eq!(ident.map(|n| n.range()), Some(3..5));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn concat<I>(trees: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        concat(trees, true)
+    }
+
+    /// Concatenate a sequence of `trees` into a single forest while keeping
+    /// their original spans, see [`Tree::concat`].
+    ///
+    /// This is useful when each tree has been parsed with spans referring to
+    /// the whole source. The roots of each tree must start at or after the
+    /// end of the span of the trees before it.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`Error::ConcatOverlap`] if a tree starts before the
+    /// preceding trees end, and with [`Error::Overflow`] if the concatenated
+    /// tree has too many nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use syntree::{Builder, CursorPolicy, Error, Span, Tree};
+    ///
+    /// let a = syntree::tree! {
+    ///     "fn" => {
+    ///         ("ident", 3),
+    ///     }
+    /// };
+    ///
+    /// let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+    /// b.open("struct")?;
+    /// b.token_with_span("ident", Span::new(4, 6))?;
+    /// b.close()?;
+    /// let b = b.build()?;
+    ///
+    /// let tree = Tree::concat_ordered([a.clone(), b.clone()])?;
+    ///
+    /// let ranges = tree.children().map(|n| n.range()).collect::<Vec<_>>();
+    /// assert_eq!(ranges, [0..3, 4..6]);
+    /// assert_eq!(tree.span(), Span::new(0, 6));
+    ///
+    /// assert_eq!(
+    ///     Tree::concat_ordered([b, a]),
+    ///     Err(Error::ConcatOverlap { index: 1, start: 0, end: 6 })
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn concat_ordered<I>(trees: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        concat(trees, false)
+    }
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn concat<T, I>(trees: I, shift: bool) -> Result<Tree<T, Span>, Error>
+where
+    I: IntoIterator<Item = Tree<T, Span>>,
+{
+    let mut out = Tree::<T, Span>::new_with();
+    let mut span = None::<Span>;
+
+    for (index, tree) in trees.into_iter().enumerate() {
+        let Tree {
+            tree: links,
+            span: tree_span,
+            indexes,
+            first,
+            last,
+            roots,
+        } = tree;
+
+        // Spans are moved from `from` to `to`.
+        let (from, to) = match span {
+            Some(span) if shift => (tree_span.start, span.end),
+            Some(span) => {
+                let start = first
+                    .and_then(|id| links.get(id.get()))
+                    .map(|links| links.span.start);
+
+                if let Some(start) = start.filter(|&start| start < span.end) {
+                    return Err(Error::ConcatOverlap {
+                        index,
+                        start: start as usize,
+                        end: span.end as usize,
+                    });
+                }
+
+                (0, 0)
+            }
+            None => (0, 0),
+        };
+
+        let move_span = |s: Span| -> Result<Span, Error> {
+            let start = move_index(s.start, from, to)?;
+            let end = move_index(s.end, from, to)?;
+            Ok(Span::new(start, end))
+        };
+
+        let tree_span = move_span(tree_span)?;
+
+        span = Some(match span {
+            Some(span) => span.join(&tree_span),
+            None => tree_span,
+        });
+
+        let offset = out.tree.len();
+        let move_id = |id: NonMax| NonMax::new(id.get() + offset).ok_or(Error::Overflow);
+        let move_opt = |id: Option<NonMax>| id.map(move_id).transpose();
+
+        out.tree.reserve(links.len());
+
+        for links in links {
+            out.tree.push(Links {
+                span: move_span(links.span)?,
+                parent: move_opt(links.parent)?,
+                prev: move_opt(links.prev)?,
+                next: move_opt(links.next)?,
+                first: move_opt(links.first)?,
+                last: move_opt(links.last)?,
+                ..links
+            });
+        }
+
+        for TreeIndex { index, id } in indexes {
+            out.indexes.push(TreeIndex {
+                index: move_index(index, from, to)?,
+                id: move_id(id)?,
+            });
+        }
+
+        let (Some(first), Some(last)) = (move_opt(first)?, move_opt(last)?) else {
+            continue;
+        };
+
+        // Link the first root of this tree to the last root of the forest.
+        if let Some(prev) = out.last {
+            if let Some(links) = out.tree.get_mut(first.get()) {
+                links.prev = Some(prev);
+            }
+
+            if let Some(links) = out.tree.get_mut(prev.get()) {
+                links.next = Some(first);
+            }
+        } else {
+            out.first = Some(first);
+        }
+
+        out.last = Some(last);
+        out.roots = out.roots.checked_add(roots).ok_or(Error::Overflow)?;
+    }
+
+    if let Some(span) = span {
+        out.span = span;
+    }
+
+    Ok(out)
+}
+
+/// Move `index` so that `from` ends up at `to`.
+fn move_index(index: Index, from: Index, to: Index) -> Result<Index, Error> {
+    index
+        .checked_sub(from)
+        .and_then(|index| index.checked_add(to))
+        .ok_or(Error::Overflow)
+}
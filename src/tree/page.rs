@@ -0,0 +1,104 @@
+use alloc::vec::Vec;
+
+use crate::builder::Id;
+use crate::tree::Kind;
+
+/// A page of children produced by
+/// [`Tree::children_page`][crate::Tree::children_page].
+///
+/// If the `serde` feature is enabled pages can be serialized, which makes them
+/// suitable for exposing large trees incrementally over a remote protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Page<V, S> {
+    items: Vec<PageItem<V, S>>,
+    after: Option<Id>,
+    more: bool,
+}
+
+impl<V, S> Page<V, S> {
+    pub(crate) const fn new(items: Vec<PageItem<V, S>>, after: Option<Id>, more: bool) -> Self {
+        Self { items, after, more }
+    }
+
+    /// The children in this page.
+    #[must_use]
+    pub fn items(&self) -> &[PageItem<V, S>] {
+        &self.items
+    }
+
+    /// Coerce into the children in this page.
+    #[must_use]
+    pub fn into_items(self) -> Vec<PageItem<V, S>> {
+        self.items
+    }
+
+    /// The cursor to pass as `after` to fetch the next page.
+    ///
+    /// This is the identifier of the last child in this page, or the cursor
+    /// this page was fetched with if the page is empty.
+    #[must_use]
+    pub const fn after(&self) -> Option<Id> {
+        self.after
+    }
+
+    /// Test if more children follow this page.
+    #[must_use]
+    pub const fn has_more(&self) -> bool {
+        self.more
+    }
+}
+
+/// A summary of a single child in a [`Page`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PageItem<V, S> {
+    id: Id,
+    kind: Kind,
+    span: S,
+    has_children: bool,
+    value: V,
+}
+
+impl<V, S> PageItem<V, S> {
+    pub(crate) const fn new(id: Id, kind: Kind, span: S, has_children: bool, value: V) -> Self {
+        Self {
+            id,
+            kind,
+            span,
+            has_children,
+            value,
+        }
+    }
+
+    /// The identifier of the child.
+    #[must_use]
+    pub const fn id(&self) -> Id {
+        self.id
+    }
+
+    /// The kind of the child.
+    #[must_use]
+    pub const fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// The span of the child.
+    #[must_use]
+    pub const fn span(&self) -> &S {
+        &self.span
+    }
+
+    /// Test if the child has children of its own, which can be fetched by
+    /// passing its identifier as `parent`.
+    #[must_use]
+    pub const fn has_children(&self) -> bool {
+        self.has_children
+    }
+
+    /// The rendered value of the child.
+    #[must_use]
+    pub const fn value(&self) -> &V {
+        &self.value
+    }
+}
@@ -0,0 +1,669 @@
+use alloc::vec::{self, Vec};
+use core::fmt;
+use core::iter::FusedIterator;
+use core::slice;
+
+use crate::builder::Id;
+use crate::links::Links;
+use crate::non_max::NonMax;
+use crate::tree::Kind;
+
+/// An entry in the storage of a tree, as produced by [`RawIter`].
+///
+/// See [`Tree::raw_iter`][crate::Tree::raw_iter].
+pub struct RawEntry<'a, T, S> {
+    id: NonMax,
+    links: &'a Links<T, S>,
+}
+
+impl<'a, T, S> RawEntry<'a, T, S> {
+    /// Get the identifier of the node.
+    #[must_use]
+    #[inline]
+    pub const fn id(&self) -> Id {
+        Id::new(self.id)
+    }
+
+    /// Get the kind of the node.
+    #[must_use]
+    #[inline]
+    pub const fn kind(&self) -> Kind {
+        self.links.kind
+    }
+
+    /// Access the data associated with the node.
+    #[must_use]
+    #[inline]
+    pub const fn value(&self) -> &'a T {
+        &self.links.data
+    }
+
+    /// Get the span of the node.
+    #[must_use]
+    #[inline]
+    pub const fn span(&self) -> &'a S {
+        &self.links.span
+    }
+
+    /// Get the identifier of the parent of the node, if any.
+    #[must_use]
+    #[inline]
+    pub fn parent(&self) -> Option<Id> {
+        self.links.parent.map(Id::new)
+    }
+}
+
+impl<T, S> fmt::Debug for RawEntry<'_, T, S>
+where
+    T: fmt::Debug,
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawEntry")
+            .field("id", &self.id())
+            .field("kind", &self.kind())
+            .field("value", self.value())
+            .field("span", self.span())
+            .field("parent", &self.parent())
+            .finish()
+    }
+}
+
+impl<T, S> Clone for RawEntry<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, S> Copy for RawEntry<'_, T, S> {}
+
+/// An iterator over the storage of a tree, in storage order.
+///
+/// See [`Tree::raw_iter`][crate::Tree::raw_iter].
+pub struct RawIter<'a, T, S> {
+    iter: slice::Iter<'a, Links<T, S>>,
+    index: usize,
+}
+
+impl<'a, T, S> RawIter<'a, T, S> {
+    pub(crate) fn new(tree: &'a [Links<T, S>]) -> Self {
+        Self {
+            iter: tree.iter(),
+            index: 0,
+        }
+    }
+}
+
+impl<'a, T, S> Iterator for RawIter<'a, T, S> {
+    type Item = RawEntry<'a, T, S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let links = self.iter.next()?;
+        let id = NonMax::new(self.index)?;
+        self.index += 1;
+        Some(RawEntry { id, links })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for RawIter<'_, T, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let links = self.iter.next_back()?;
+        let id = NonMax::new(self.index + self.iter.len())?;
+        Some(RawEntry { id, links })
+    }
+}
+
+impl<T, S> ExactSizeIterator for RawIter<'_, T, S> {}
+
+impl<T, S> FusedIterator for RawIter<'_, T, S> {}
+
+impl<T, S> Clone for RawIter<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            index: self.index,
+        }
+    }
+}
+
+impl<T, S> Default for RawIter<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+/// An iterator over the values of a tree, in storage order.
+///
+/// See [`Tree::values`][crate::Tree::values].
+pub struct Values<'a, T, S> {
+    iter: slice::Iter<'a, Links<T, S>>,
+}
+
+impl<'a, T, S> Values<'a, T, S> {
+    pub(crate) fn new(tree: &'a [Links<T, S>]) -> Self {
+        Self { iter: tree.iter() }
+    }
+}
+
+impl<'a, T, S> Iterator for Values<'a, T, S> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(&self.iter.next()?.data)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for Values<'_, T, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some(&self.iter.next_back()?.data)
+    }
+}
+
+impl<T, S> ExactSizeIterator for Values<'_, T, S> {}
+
+impl<T, S> FusedIterator for Values<'_, T, S> {}
+
+impl<T, S> Clone for Values<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T, S> Default for Values<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+/// A mutable iterator over the values of a tree, in storage order.
+///
+/// See [`Tree::values_mut`][crate::Tree::values_mut].
+pub struct ValuesMut<'a, T, S> {
+    iter: slice::IterMut<'a, Links<T, S>>,
+}
+
+impl<'a, T, S> ValuesMut<'a, T, S> {
+    pub(crate) fn new(tree: &'a mut [Links<T, S>]) -> Self {
+        Self {
+            iter: tree.iter_mut(),
+        }
+    }
+}
+
+impl<'a, T, S> Iterator for ValuesMut<'a, T, S> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(&mut self.iter.next()?.data)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for ValuesMut<'_, T, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some(&mut self.iter.next_back()?.data)
+    }
+}
+
+impl<T, S> ExactSizeIterator for ValuesMut<'_, T, S> {}
+
+impl<T, S> FusedIterator for ValuesMut<'_, T, S> {}
+
+impl<T, S> Default for ValuesMut<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(&mut [])
+    }
+}
+
+/// An iterator over the spans of a tree, in storage order.
+///
+/// See [`Tree::spans`][crate::Tree::spans].
+pub struct Spans<'a, T, S> {
+    iter: slice::Iter<'a, Links<T, S>>,
+}
+
+impl<'a, T, S> Spans<'a, T, S> {
+    pub(crate) fn new(tree: &'a [Links<T, S>]) -> Self {
+        Self { iter: tree.iter() }
+    }
+}
+
+impl<'a, T, S> Iterator for Spans<'a, T, S> {
+    type Item = &'a S;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(&self.iter.next()?.span)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for Spans<'_, T, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some(&self.iter.next_back()?.span)
+    }
+}
+
+impl<T, S> ExactSizeIterator for Spans<'_, T, S> {}
+
+impl<T, S> FusedIterator for Spans<'_, T, S> {}
+
+impl<T, S> Clone for Spans<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T, S> Default for Spans<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+/// An iterator over the kinds of a tree, in storage order.
+///
+/// See [`RawTree::kinds`].
+pub struct Kinds<'a, T, S> {
+    iter: slice::Iter<'a, Links<T, S>>,
+}
+
+impl<'a, T, S> Kinds<'a, T, S> {
+    pub(crate) fn new(tree: &'a [Links<T, S>]) -> Self {
+        Self { iter: tree.iter() }
+    }
+}
+
+impl<T, S> Iterator for Kinds<'_, T, S> {
+    type Item = Kind;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.iter.next()?.kind)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for Kinds<'_, T, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some(self.iter.next_back()?.kind)
+    }
+}
+
+impl<T, S> ExactSizeIterator for Kinds<'_, T, S> {}
+
+impl<T, S> FusedIterator for Kinds<'_, T, S> {}
+
+impl<T, S> Clone for Kinds<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T, S> Default for Kinds<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+/// An iterator over the identifiers, values, kinds and spans of a tree, in
+/// storage order.
+///
+/// See [`Tree::entries`][crate::Tree::entries].
+pub struct Entries<'a, T, S> {
+    iter: RawIter<'a, T, S>,
+}
+
+impl<'a, T, S> Entries<'a, T, S> {
+    pub(crate) fn new(tree: &'a [Links<T, S>]) -> Self {
+        Self {
+            iter: RawIter::new(tree),
+        }
+    }
+}
+
+impl<'a, T, S> Iterator for Entries<'a, T, S> {
+    type Item = (Id, &'a T, Kind, &'a S);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let e = self.iter.next()?;
+        Some((e.id(), e.value(), e.kind(), e.span()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for Entries<'_, T, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let e = self.iter.next_back()?;
+        Some((e.id(), e.value(), e.kind(), e.span()))
+    }
+}
+
+impl<T, S> ExactSizeIterator for Entries<'_, T, S> {}
+
+impl<T, S> FusedIterator for Entries<'_, T, S> {}
+
+impl<T, S> Clone for Entries<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T, S> Default for Entries<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+/// An owning iterator over the values of a tree, in storage order.
+///
+/// See [`Tree::into_values`][crate::Tree::into_values].
+pub struct IntoValues<T, S> {
+    iter: vec::IntoIter<Links<T, S>>,
+}
+
+impl<T, S> IntoValues<T, S> {
+    pub(crate) fn new(tree: Vec<Links<T, S>>) -> Self {
+        Self {
+            iter: tree.into_iter(),
+        }
+    }
+}
+
+impl<T, S> Iterator for IntoValues<T, S> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.iter.next()?.data)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S> DoubleEndedIterator for IntoValues<T, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some(self.iter.next_back()?.data)
+    }
+}
+
+impl<T, S> ExactSizeIterator for IntoValues<T, S> {}
+
+impl<T, S> FusedIterator for IntoValues<T, S> {}
+
+/// A read-only view of the storage of a tree, which is accessed by identifier
+/// without constructing any [`Node`].
+///
+/// This is constructed through [`Tree::as_raw`].
+///
+/// Nodes are stored in the order in which they were allocated by the
+/// [`Builder`], and the [`index`] of an identifier is the position of its node
+/// in storage. So the `n`th element of [`RawTree::values`],
+/// [`RawTree::kinds`] and [`RawTree::spans`] all belong to the node whose
+/// identifier has index `n`. Allocation order is *not* generally the same as
+/// document order, since for example a node created through
+/// [`Builder::close_at`] is stored after its children.
+///
+/// Every node is stored as a single entry, so these columns are exposed as
+/// exact-size iterators over the storage rather than as slices.
+///
+/// Since the view only borrows the tree, the structure of the tree can't be
+/// modified through it.
+///
+/// [`Node`]: crate::Node
+/// [`Tree::as_raw`]: crate::Tree::as_raw
+/// [`Builder`]: crate::Builder
+/// [`Builder::close_at`]: crate::Builder::close_at
+/// [`index`]: crate::Id::index
+///
+/// # Examples
+///
+/// ```
+/// use syntree::{Id, Kind};
+///
+/// let tree = syntree::tree! {
+///     "root" => {
+///         ("lit", 1),
+///         ("ws", 3),
+///     },
+///     ("eof", 0),
+/// };
+///
+/// let raw = tree.as_raw();
+/// assert_eq!(raw.len(), 4);
+///
+/// let width = raw
+///     .kinds()
+///     .zip(raw.spans())
+///     .filter(|(kind, _)| *kind == Kind::Token)
+///     .map(|(_, span)| span.range().len())
+///     .sum::<usize>();
+///
+/// assert_eq!(width, 4);
+///
+/// let root = raw.first().ok_or("missing root")?;
+/// assert_eq!(raw.value_of(root), Some(&"root"));
+///
+/// let lit = raw.first_of(root).ok_or("missing lit")?;
+/// assert_eq!(raw.parent_of(lit), Some(root));
+/// assert_eq!(raw.next_of(lit).and_then(|id| raw.value_of(id)), Some(&"ws"));
+/// assert_eq!(raw.next_of(root).and_then(|id| raw.value_of(id)), Some(&"eof"));
+/// assert_eq!(raw.kind_of(lit), Some(Kind::Token));
+///
+/// assert_eq!(Id::from_index(3), raw.last());
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct RawTree<'a, T, S> {
+    tree: &'a [Links<T, S>],
+    first: Option<NonMax>,
+    last: Option<NonMax>,
+}
+
+impl<'a, T, S> RawTree<'a, T, S> {
+    pub(crate) const fn new(
+        tree: &'a [Links<T, S>],
+        first: Option<NonMax>,
+        last: Option<NonMax>,
+    ) -> Self {
+        Self { tree, first, last }
+    }
+
+    /// Get the number of nodes in the tree.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Test if the tree is empty.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Get the identifier of the first root of the tree.
+    #[must_use]
+    #[inline]
+    pub fn first(&self) -> Option<Id> {
+        self.first.map(Id::new)
+    }
+
+    /// Get the identifier of the last root of the tree.
+    #[must_use]
+    #[inline]
+    pub fn last(&self) -> Option<Id> {
+        self.last.map(Id::new)
+    }
+
+    /// Iterate over the values of all nodes, in storage order.
+    #[must_use]
+    #[inline]
+    pub fn values(&self) -> Values<'a, T, S> {
+        Values::new(self.tree)
+    }
+
+    /// Iterate over the kinds of all nodes, in storage order.
+    #[must_use]
+    #[inline]
+    pub fn kinds(&self) -> Kinds<'a, T, S> {
+        Kinds::new(self.tree)
+    }
+
+    /// Iterate over the spans of all nodes, in storage order.
+    #[must_use]
+    #[inline]
+    pub fn spans(&self) -> Spans<'a, T, S> {
+        Spans::new(self.tree)
+    }
+
+    /// Get the value of the node with the given identifier.
+    #[must_use]
+    #[inline]
+    pub fn value_of(&self, id: Id) -> Option<&'a T> {
+        Some(&self.links(id)?.data)
+    }
+
+    /// Get the kind of the node with the given identifier.
+    #[must_use]
+    #[inline]
+    pub fn kind_of(&self, id: Id) -> Option<Kind> {
+        Some(self.links(id)?.kind)
+    }
+
+    /// Get the span of the node with the given identifier.
+    #[must_use]
+    #[inline]
+    pub fn span_of(&self, id: Id) -> Option<&'a S> {
+        Some(&self.links(id)?.span)
+    }
+
+    /// Get the identifier of the parent of the node with the given identifier.
+    #[must_use]
+    #[inline]
+    pub fn parent_of(&self, id: Id) -> Option<Id> {
+        self.links(id)?.parent.map(Id::new)
+    }
+
+    /// Get the identifier of the previous sibling of the node with the given
+    /// identifier.
+    #[must_use]
+    #[inline]
+    pub fn prev_of(&self, id: Id) -> Option<Id> {
+        self.links(id)?.prev.map(Id::new)
+    }
+
+    /// Get the identifier of the next sibling of the node with the given
+    /// identifier.
+    #[must_use]
+    #[inline]
+    pub fn next_of(&self, id: Id) -> Option<Id> {
+        self.links(id)?.next.map(Id::new)
+    }
+
+    /// Get the identifier of the first child of the node with the given
+    /// identifier.
+    #[must_use]
+    #[inline]
+    pub fn first_of(&self, id: Id) -> Option<Id> {
+        self.links(id)?.first.map(Id::new)
+    }
+
+    /// Get the identifier of the last child of the node with the given
+    /// identifier.
+    #[must_use]
+    #[inline]
+    pub fn last_of(&self, id: Id) -> Option<Id> {
+        self.links(id)?.last.map(Id::new)
+    }
+
+    /// Get the number of children of the node with the given identifier.
+    #[must_use]
+    #[inline]
+    pub fn children_len_of(&self, id: Id) -> Option<usize> {
+        Some(self.links(id)?.len as usize)
+    }
+
+    #[inline]
+    fn links(&self, id: Id) -> Option<&'a Links<T, S>> {
+        self.tree.get(id.0.get())
+    }
+}
+
+impl<T, S> fmt::Debug for RawTree<'_, T, S>
+where
+    T: fmt::Debug,
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(RawIter::new(self.tree)).finish()
+    }
+}
+
+impl<T, S> Clone for RawTree<'_, T, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, S> Copy for RawTree<'_, T, S> {}
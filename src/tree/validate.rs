@@ -0,0 +1,233 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::builder::Id;
+use crate::links::Links;
+use crate::non_max::NonMax;
+use crate::span::TreeSpan;
+use crate::tree::Kind;
+
+/// An invariant of a tree, as reported through [`CorruptTree::invariant`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Invariant {
+    /// A link of the node refers to something outside of the tree.
+    OutOfBounds(Link),
+    /// The node was reached more than once, so the links of the tree contain
+    /// a cycle or a node is shared between parents.
+    Cycle,
+    /// The node can't be reached from the roots of the tree.
+    Unreachable,
+    /// The parent of the node isn't the node whose children it is linked
+    /// among.
+    Parent,
+    /// The previous sibling of the node isn't the node whose next sibling it
+    /// is.
+    Prev,
+    /// The last child, or the last root of the tree, isn't the end of the
+    /// chain of siblings starting at the first one.
+    Last,
+    /// The number of children of the node, or the number of roots of the
+    /// tree, doesn't match its chain of siblings.
+    Len,
+    /// The node is a token with children.
+    TokenWithChildren,
+    /// The span of the node isn't the union of the spans of its children.
+    Span,
+    /// The span of the node starts before its previous sibling ends.
+    SpanOrder,
+}
+
+/// A link between nodes, as reported through [`Invariant::OutOfBounds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Link {
+    /// The parent of a node.
+    Parent,
+    /// The previous sibling of a node.
+    Prev,
+    /// The next sibling of a node.
+    Next,
+    /// The first child of a node, or the first root of the tree.
+    First,
+    /// The last child of a node, or the last root of the tree.
+    Last,
+}
+
+/// Error raised by [`Tree::validate`] if a tree is corrupt.
+///
+/// [`Tree::validate`]: crate::Tree::validate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CorruptTree {
+    id: Option<Id>,
+    invariant: Invariant,
+}
+
+impl CorruptTree {
+    const fn new(id: Option<NonMax>, invariant: Invariant) -> Self {
+        Self {
+            id: match id {
+                Some(id) => Some(Id::new(id)),
+                None => None,
+            },
+            invariant,
+        }
+    }
+
+    /// The node which violates the invariant, or `None` if it is violated
+    /// by the roots of the tree.
+    #[must_use]
+    pub const fn id(&self) -> Option<Id> {
+        self.id
+    }
+
+    /// The invariant which is violated.
+    #[must_use]
+    pub const fn invariant(&self) -> Invariant {
+        self.invariant
+    }
+}
+
+impl fmt::Display for CorruptTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.invariant {
+            Invariant::OutOfBounds(link) => match link {
+                Link::Parent => "parent is out of bounds",
+                Link::Prev => "previous sibling is out of bounds",
+                Link::Next => "next sibling is out of bounds",
+                Link::First => "first child is out of bounds",
+                Link::Last => "last child is out of bounds",
+            },
+            Invariant::Cycle => "is reachable more than once",
+            Invariant::Unreachable => "is unreachable",
+            Invariant::Parent => "has an inconsistent parent",
+            Invariant::Prev => "has an inconsistent previous sibling",
+            Invariant::Last => "has an inconsistent last child",
+            Invariant::Len => "has an inconsistent number of children",
+            Invariant::TokenWithChildren => "is a token with children",
+            Invariant::Span => "has a span which isn't the union of its children",
+            Invariant::SpanOrder => "has a span which overlaps its previous sibling",
+        };
+
+        match self.id {
+            Some(id) => write!(f, "node with id `{}` {what}", id.0.get()),
+            None => write!(f, "root of tree {what}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CorruptTree {}
+
+// `core::error::Error` is only stable since Rust 1.81, so it is opt-in.
+#[cfg(all(not(feature = "std"), feature = "core-error"))]
+#[allow(clippy::incompatible_msrv)]
+impl core::error::Error for CorruptTree {}
+
+/// Validate the links and spans of a tree, see [`Tree::validate`].
+///
+/// [`Tree::validate`]: crate::Tree::validate
+pub(crate) fn validate<T, S>(
+    tree: &[Links<T, S>],
+    first: Option<NonMax>,
+    last: Option<NonMax>,
+    roots: u32,
+) -> Result<(), CorruptTree>
+where
+    S: TreeSpan,
+{
+    let links = |id: NonMax| &tree[id.get()];
+
+    for (index, node) in tree.iter().enumerate() {
+        let id = NonMax::new(index);
+
+        for (link, target) in [
+            (Link::Parent, node.parent),
+            (Link::Prev, node.prev),
+            (Link::Next, node.next),
+            (Link::First, node.first),
+            (Link::Last, node.last),
+        ] {
+            if target.map_or(false, |target| target.get() >= tree.len()) {
+                return Err(CorruptTree::new(id, Invariant::OutOfBounds(link)));
+            }
+        }
+
+        if node.kind == Kind::Token && (node.first.is_some() || node.last.is_some()) {
+            return Err(CorruptTree::new(id, Invariant::TokenWithChildren));
+        }
+    }
+
+    for (link, target) in [(Link::First, first), (Link::Last, last)] {
+        if target.map_or(false, |target| target.get() >= tree.len()) {
+            return Err(CorruptTree::new(None, Invariant::OutOfBounds(link)));
+        }
+    }
+
+    let mut visited = vec![false; tree.len()];
+    let mut queue = Vec::from([(None, first, last, roots)]);
+
+    while let Some((parent, first, last, len)) = queue.pop() {
+        let mut count = 0u32;
+        let mut prev = None::<NonMax>;
+        let mut current = first;
+        let mut hull = None::<(S, S)>;
+
+        while let Some(id) = current {
+            // All links have been checked to be in bounds above.
+            if visited[id.get()] {
+                return Err(CorruptTree::new(Some(id), Invariant::Cycle));
+            }
+
+            visited[id.get()] = true;
+            let node = links(id);
+
+            if node.parent != parent {
+                return Err(CorruptTree::new(Some(id), Invariant::Parent));
+            }
+
+            if node.prev != prev {
+                return Err(CorruptTree::new(Some(id), Invariant::Prev));
+            }
+
+            if let Some(prev) = prev {
+                if links(prev).span.end() > node.span.start() {
+                    return Err(CorruptTree::new(Some(id), Invariant::SpanOrder));
+                }
+            }
+
+            hull = Some(match hull {
+                Some((start, _)) => (start, node.span),
+                None => (node.span, node.span),
+            });
+
+            queue.push((Some(id), node.first, node.last, node.len));
+            count = count.saturating_add(1);
+            prev = Some(id);
+            current = node.next;
+        }
+
+        if prev != last {
+            return Err(CorruptTree::new(parent, Invariant::Last));
+        }
+
+        if count != len {
+            return Err(CorruptTree::new(parent, Invariant::Len));
+        }
+
+        if let (Some(parent), Some((first, last))) = (parent, hull) {
+            let span = &links(parent).span;
+
+            if span.start() != first.start() || span.end() != last.end() {
+                return Err(CorruptTree::new(Some(parent), Invariant::Span));
+            }
+        }
+    }
+
+    if let Some(index) = visited.iter().position(|seen| !seen) {
+        return Err(CorruptTree::new(NonMax::new(index), Invariant::Unreachable));
+    }
+
+    Ok(())
+}
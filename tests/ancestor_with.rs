@@ -0,0 +1,64 @@
+use anyhow::{anyhow, Result};
+
+#[test]
+fn ancestor_is_root() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            "a" => {
+                "b" => {
+                    ("lit", 1),
+                },
+            },
+        },
+        "other" => {},
+    };
+
+    let root = tree.first().ok_or(anyhow!("missing root"))?;
+    let lit = tree
+        .walk()
+        .find(|n| *n.value() == "lit")
+        .ok_or(anyhow!("missing lit"))?;
+
+    let found = lit
+        .nearest_ancestor(&"root")
+        .ok_or(anyhow!("missing root"))?;
+    assert_eq!(found.id(), root.id());
+
+    let found = lit
+        .ancestor_with(|n| n.parent().is_none())
+        .ok_or(anyhow!("missing root"))?;
+    assert_eq!(found.id(), root.id());
+
+    let a = root.first().ok_or(anyhow!("missing a"))?;
+    assert!(a.parent_is(&"root"));
+    assert_eq!(a.parent_with(|_| true).map(|n| n.id()), Some(root.id()));
+    Ok(())
+}
+
+#[test]
+fn nothing_matches() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            "a" => {
+                ("lit", 1),
+            },
+        },
+    };
+
+    let root = tree.first().ok_or(anyhow!("missing root"))?;
+    let lit = tree
+        .walk()
+        .find(|n| *n.value() == "lit")
+        .ok_or(anyhow!("missing lit"))?;
+
+    // The node itself is never considered.
+    assert!(lit.nearest_ancestor(&"lit").is_none());
+    assert!(lit.nearest_ancestor(&"missing").is_none());
+    assert!(!lit.parent_is(&"root"));
+
+    // The root has no parent.
+    assert!(root.parent_with(|_| true).is_none());
+    assert!(root.ancestor_with(|_| true).is_none());
+    assert!(!root.parent_is(&"root"));
+    Ok(())
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "arbitrary")]
+
+use anyhow::Result;
+use arbitrary::{Arbitrary, Unstructured};
+use syntree::arbitrary::Generator;
+use syntree::{print, span, Kind, Node, Span, Tree};
+
+mod common;
+
+/// Generate unstructured data of a varying size for the given seed.
+fn data(seed: u64) -> Vec<u8> {
+    let mut rng = common::Rng::new(seed);
+    let len = (rng.next() % 4096) as usize;
+    (0..len).map(|_| rng.next() as u8).collect()
+}
+
+/// Run `f` over trees generated by `generator` from 128 different seeds.
+fn for_each_tree<F>(generator: &Generator, mut f: F) -> Result<()>
+where
+    F: FnMut(&Tree<u32, Span>) -> Result<()>,
+{
+    for seed in 1..=128 {
+        let data = data(seed);
+        let mut u = Unstructured::new(&data);
+        let tree = generator.generate(&mut u, u32::arbitrary)?;
+        f(&tree)?;
+    }
+
+    Ok(())
+}
+
+/// Check that the children of a node are linked to it and cover its span
+/// without gaps.
+fn check_node(node: Node<'_, u32, Span>, max_children: usize) {
+    if node.kind() == Kind::Token {
+        return;
+    }
+
+    let mut cursor = node.span().start;
+
+    for child in node.children() {
+        assert_eq!(child.parent(), Some(node));
+        assert_eq!(child.span().start, cursor);
+        cursor = child.span().end;
+    }
+
+    assert_eq!(cursor, node.span().end);
+    assert!(node.children().len() <= max_children);
+}
+
+#[test]
+fn generated_invariants() -> Result<()> {
+    let generator = Generator::new()
+        .max_depth(4)
+        .max_children(5)
+        .token_len(1..=3);
+
+    for_each_tree(&generator, |tree| {
+        assert!(tree.children().len() <= 5);
+
+        let mut cursor = 0;
+
+        for (depth, node) in tree.walk().with_depths() {
+            assert!(depth < 4);
+            check_node(node, 5);
+
+            if node.kind() == Kind::Token {
+                assert!((1..=3).contains(&node.range().len()));
+                assert_eq!(node.span().start, cursor);
+                cursor = node.span().end;
+            }
+        }
+
+        assert_eq!(tree.span().end, cursor);
+        Ok(())
+    })
+}
+
+#[test]
+fn generated_walk() -> Result<()> {
+    for_each_tree(&Generator::new(), |tree| {
+        assert_eq!(tree.walk().count(), tree.len());
+        assert_eq!(tree.walk().size_hint(), (tree.len(), Some(tree.len())));
+
+        // Walking in reverse visits the same nodes.
+        let mut forward = tree.walk().map(|n| n.id()).collect::<Vec<_>>();
+        forward.reverse();
+        assert!(forward.into_iter().eq(tree.walk_rev().map(|n| n.id())));
+
+        // Depths agree with the number of ancestors of each node.
+        for (depth, node) in tree.walk().with_depths() {
+            let mut parents = 0;
+            let mut current = node;
+
+            while let Some(parent) = current.parent() {
+                parents += 1;
+                current = parent;
+            }
+
+            assert_eq!(depth, parents);
+        }
+
+        Ok(())
+    })
+}
+
+#[test]
+fn generated_node_with_range() -> Result<()> {
+    let generator = Generator::new().token_len(0..=4);
+
+    for_each_tree(&generator, |tree| {
+        for token in tree
+            .walk()
+            .filter(|n| n.kind() == Kind::Token && !n.span().is_empty())
+        {
+            let range = token.range();
+
+            let Some(node) = tree.node_with_range(range.clone()) else {
+                // Tokens outside of any node have no containing node.
+                assert!(token.parent().is_none());
+                continue;
+            };
+
+            assert!(node.range().start <= range.start && range.end <= node.range().end);
+        }
+
+        Ok(())
+    })
+}
+
+#[test]
+fn generated_print() -> Result<()> {
+    for_each_tree(&Generator::new(), |tree| {
+        let mut out = Vec::new();
+        print::print(&mut out, tree)?;
+        let out = String::from_utf8(out)?;
+
+        assert_eq!(out.lines().count(), tree.len());
+
+        for (line, (depth, node)) in out.lines().zip(tree.walk().with_depths()) {
+            let expected = format!(
+                "{:indent$}{:?}@{:?}",
+                "",
+                node.value(),
+                node.range(),
+                indent = depth * 2
+            );
+            assert!(
+                line.starts_with(&expected),
+                "{line:?} should start with {expected:?}"
+            );
+        }
+
+        Ok(())
+    })
+}
+
+#[test]
+fn arbitrary_tree() -> Result<()> {
+    for seed in 1..=16 {
+        let data = data(seed);
+
+        let tree = Tree::<u8, Span>::arbitrary(&mut Unstructured::new(&data))?;
+        assert_eq!(tree.walk().count(), tree.len());
+
+        let tree = Tree::<u8, span::Empty>::arbitrary(&mut Unstructured::new(&data))?;
+        assert_eq!(tree.walk().count(), tree.len());
+    }
+
+    // Exhausted input produces an empty tree.
+    let tree = Tree::<u8, Span>::arbitrary(&mut Unstructured::new(&[]))?;
+    assert!(tree.is_empty());
+
+    // Zero depth never produces anything.
+    let tree: Tree<u8, Span> = Generator::new()
+        .max_depth(0)
+        .generate(&mut Unstructured::new(&data(1)), u8::arbitrary)?;
+    assert!(tree.is_empty());
+    Ok(())
+}
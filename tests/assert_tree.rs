@@ -0,0 +1,117 @@
+use std::panic;
+
+use anyhow::Result;
+
+fn panic_message<F>(f: F) -> String
+where
+    F: FnOnce() + panic::UnwindSafe,
+{
+    let error = panic::catch_unwind(f).expect_err("expected a panic");
+
+    match error.downcast::<String>() {
+        Ok(message) => *message,
+        Err(error) => (*error.downcast::<&str>().expect("string panic")).to_owned(),
+    }
+}
+
+#[test]
+fn assert_tree_matches() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("lit", 3),
+            "empty" => {},
+        },
+        ("ws", 1),
+    };
+
+    syntree::assert_tree!(tree, {
+        "root" => {
+            ("lit", 3),
+            "empty" => {},
+        },
+        ("ws", 1),
+    });
+
+    syntree::assert_tree!(&tree, {
+        "root" => {
+            "lit",
+            "empty" => {},
+        },
+        "ws",
+    });
+
+    Ok(())
+}
+
+#[test]
+fn assert_tree_mismatch() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("lit", 3),
+            ("ws", 1),
+        },
+    };
+
+    let message = panic_message(move || {
+        syntree::assert_tree!(tree, {
+            "root" => {
+                ("lit", 3),
+                ("ident", 1),
+            },
+        });
+    });
+
+    let expected = [
+        "assertion failed: tree does not match",
+        "actual:",
+        "  \"root\"@0..4",
+        "    \"lit\"@0..3",
+        "    \"ws\"@3..4",
+        "expected:",
+        "  \"root\"@0..4",
+        "    \"lit\"@0..3",
+        "    \"ident\"@3..4",
+        "first difference at [0, 1]:",
+        "  actual: \"ws\" (Token) @ 3..4",
+        "  expected: \"ident\" (Token) @ 3..4",
+    ];
+
+    assert_eq!(message.lines().collect::<Vec<_>>(), expected);
+    Ok(())
+}
+
+#[test]
+fn assert_tree_mismatch_without_spans() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("lit", 3),
+        },
+        "extra" => {},
+    };
+
+    let message = panic_message(move || {
+        syntree::assert_tree!(tree, {
+            "root" => {
+                "lit",
+            },
+        });
+    });
+
+    let expected = [
+        "assertion failed: tree does not match",
+        "note: spans are ignored since the expected tree has none",
+        "actual:",
+        "  \"root\"@0..3",
+        "    \"lit\"@0..3",
+        "  \"extra\"@3..3",
+        "expected:",
+        "  \"root\"",
+        "    \"lit\"",
+        "first difference at [1]:",
+        "  actual: \"extra\" (Node) @ 3..3",
+        "  expected: <missing>",
+    ];
+
+    assert_eq!(message.lines().collect::<Vec<_>>(), expected);
+    Ok(())
+}
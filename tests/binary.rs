@@ -0,0 +1,311 @@
+#![cfg(feature = "binary")]
+
+use anyhow::Result;
+use syntree::binary::{self, DecodeError, VERSION};
+use syntree::{span, Builder, CursorPolicy, Span, Tree};
+
+mod common;
+
+/// The encoding of [`fixture_tree`] with version 1 of the format, which must
+/// not change.
+#[cfg(all(not(syntree_compact), target_pointer_width = "64"))]
+const V1: &[u8] = include_bytes!("fixtures/binary-v1.bin");
+
+/// The encoding of [`gapped_tree`] with version 2 of the format, which must
+/// not change.
+#[cfg(all(not(syntree_compact), target_pointer_width = "64"))]
+const V2: &[u8] = include_bytes!("fixtures/binary-v2.bin");
+
+fn fixture_tree() -> Result<Tree<&'static str>> {
+    Ok(syntree::tree! {
+        "root" => {
+            "number" => {
+                ("lit", 300),
+            },
+            ("whitespace", 1),
+            "empty" => {},
+            "lit",
+        },
+        ("eof", 0),
+    })
+}
+
+/// A tree which starts at a non-zero offset, has gaps between its tokens and
+/// at its end, and has flags and marks.
+fn gapped_tree() -> Result<Tree<&'static str>> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+    b.advance(2)?;
+    let root = b.open("root")?;
+    b.token("lit", 3)?;
+    b.advance(1)?;
+    b.token_trivia("whitespace", 1)?;
+    b.open_error("error")?;
+    b.advance(4)?;
+    b.token("lit", 300)?;
+    b.close()?;
+    b.advance(2)?;
+    b.open("empty")?;
+    b.close()?;
+    b.close()?;
+    b.advance(3)?;
+    b.set_flags(root, 0b1001)?;
+    Ok(b.build()?)
+}
+
+fn encode<T, S>(tree: &Tree<T, S>) -> Result<Vec<u8>>
+where
+    T: syntree::binary::Encode,
+    S: syntree::binary::EncodeSpan,
+{
+    let mut out = Vec::new();
+    tree.encode(&mut out)?;
+    Ok(out)
+}
+
+fn assert_same(a: &Tree<&str>, b: &Tree<String>) {
+    assert_eq!(a.len(), b.len());
+    assert_eq!(a.span(), b.span());
+
+    for (a, b) in a.walk().zip(b.walk()) {
+        assert_eq!(*a.value(), b.value().as_str());
+        assert_eq!(a.kind(), b.kind());
+        assert_eq!(a.span(), b.span());
+        assert_eq!(a.flags(), b.flags());
+        assert_eq!(a.is_error(), b.is_error());
+        assert_eq!(a.is_trivia(), b.is_trivia());
+    }
+}
+
+#[test]
+#[cfg(all(not(syntree_compact), target_pointer_width = "64"))]
+fn fixture_v1() -> Result<()> {
+    let tree = fixture_tree()?;
+    assert_same(&tree, &Tree::decode_compat(V1)?);
+    assert!(matches!(
+        Tree::<String>::decode(V1),
+        Err(DecodeError::UnsupportedVersion(1))
+    ));
+    Ok(())
+}
+
+#[test]
+#[cfg(all(not(syntree_compact), target_pointer_width = "64"))]
+fn fixture_v2() -> Result<()> {
+    let tree = gapped_tree()?;
+    assert_eq!(encode(&tree)?, V2, "encoding of version 2 changed");
+    assert_same(&tree, &Tree::decode(V2)?);
+    assert_same(&tree, &Tree::decode_compat(V2)?);
+
+    // Trees without gaps, flags or marks are encoded like in version 1.
+    let mut v1 = encode(&fixture_tree()?)?;
+    v1[4] = 1;
+    assert_eq!(v1, V1);
+    Ok(())
+}
+
+#[test]
+fn roundtrip_gaps_flags_and_marks() -> Result<()> {
+    let tree = gapped_tree()?;
+    let decoded = Tree::<String>::decode(&encode(&tree)?[..])?;
+    assert_same(&tree, &decoded);
+    assert_eq!(decoded.range(), 0..316);
+
+    let root = decoded.first().ok_or(anyhow::anyhow!("missing root"))?;
+    assert_eq!(root.range(), 2..313);
+    assert_eq!(root.flags(), 0b1001);
+    Ok(())
+}
+
+#[test]
+fn unordered() -> Result<()> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Unordered);
+    b.token_with_span("b", Span::new(2, 3))?;
+    b.token_with_span("a", Span::new(0, 1))?;
+    let tree = b.build()?;
+
+    let error = encode(&tree)
+        .err()
+        .and_then(|e| e.downcast::<std::io::Error>().ok());
+    assert_eq!(
+        error.map(|e| e.kind()),
+        Some(std::io::ErrorKind::InvalidInput)
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip() -> Result<()> {
+    let tree = fixture_tree()?;
+    let decoded = Tree::<String>::decode(&encode(&tree)?[..])?;
+    assert_same(&tree, &decoded);
+
+    let mut b = syntree::Builder::<i64, span::Empty>::new_with();
+    b.open(-1)?;
+    b.token_empty(i64::MIN)?;
+    b.token_empty(i64::MAX)?;
+    b.close()?;
+    b.token_empty(0)?;
+    let tree = b.build()?;
+
+    let decoded = Tree::<i64, span::Empty>::decode(&encode(&tree)?[..])?;
+    assert_eq!(tree, decoded);
+    Ok(())
+}
+
+#[test]
+fn corrupt() -> Result<()> {
+    let bytes = encode(&fixture_tree()?)?;
+
+    // Every truncation is detected.
+    for n in 0..bytes.len() {
+        let result = Tree::<String>::decode(&bytes[..n]);
+        assert!(
+            matches!(result, Err(DecodeError::Corrupt(..))),
+            "{n}: {result:?}"
+        );
+    }
+
+    let mut bad = bytes.clone();
+    bad[0] = b'X';
+    assert!(matches!(
+        Tree::<String>::decode(&bad[..]),
+        Err(DecodeError::Corrupt(..))
+    ));
+
+    // Leaving the root before its children.
+    let mut bad = bytes[..7].to_vec();
+    bad.extend([0, 4, b'r', b'o', b'o', b't', 2, 2, 3]);
+    assert!(matches!(
+        Tree::<String>::decode(&bad[..]),
+        Err(DecodeError::Corrupt(..))
+    ));
+
+    // Unknown marks.
+    let mut bad = bytes[..7].to_vec();
+    bad.extend([0b0100_0000, 4, b'r', b'o', b'o', b't', 2, 3]);
+    assert!(matches!(
+        Tree::<String>::decode(&bad[..]),
+        Err(DecodeError::Corrupt(..))
+    ));
+
+    // A leave with a gap.
+    let mut bad = bytes[..7].to_vec();
+    bad.extend([0, 4, b'r', b'o', b'o', b't', 2 | 0b100, 1, 3]);
+    assert!(matches!(
+        Tree::<String>::decode(&bad[..]),
+        Err(DecodeError::Corrupt(..))
+    ));
+
+    // Unknown event.
+    let mut bad = bytes[..7].to_vec();
+    bad.push(42);
+    assert!(matches!(
+        Tree::<String>::decode(&bad[..]),
+        Err(DecodeError::Corrupt(..))
+    ));
+    Ok(())
+}
+
+#[test]
+fn unsupported_version() -> Result<()> {
+    let mut bytes = encode(&fixture_tree()?)?;
+    bytes[4] = VERSION + 1;
+
+    assert!(matches!(
+        Tree::<String>::decode(&bytes[..]),
+        Err(DecodeError::UnsupportedVersion(v)) if v == VERSION + 1
+    ));
+    assert!(matches!(
+        Tree::<String>::decode_compat(&bytes[..]),
+        Err(DecodeError::UnsupportedVersion(v)) if v == VERSION + 1
+    ));
+    Ok(())
+}
+
+#[test]
+fn configuration_mismatch() -> Result<()> {
+    let mut bytes = encode(&fixture_tree()?)?;
+    let width = bytes[5];
+    bytes[5] = if width == 4 { 8 } else { 4 };
+
+    let Err(DecodeError::IndexWidth { expected, actual }) = Tree::<String>::decode(&bytes[..])
+    else {
+        panic!("expected index width mismatch");
+    };
+
+    assert_eq!((expected, actual), (width, bytes[5]));
+
+    let bytes = encode(&fixture_tree()?)?;
+
+    assert!(matches!(
+        Tree::<String, span::Empty>::decode(&bytes[..]),
+        Err(DecodeError::SpanMismatch {
+            expected: 1,
+            actual: 0
+        })
+    ));
+
+    let empty: Tree<&str, span::Empty> = syntree::tree_with! { "root" => { "lit" } };
+
+    assert!(matches!(
+        Tree::<String, Span>::decode(&encode(&empty)?[..]),
+        Err(DecodeError::SpanMismatch {
+            expected: 0,
+            actual: 1
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn roundtrip_generated() -> Result<()> {
+    for seed in 1..=64 {
+        let tree = common::Fixture::new()
+            .max_steps(500)
+            .wide()
+            .generate(seed)?;
+
+        let mut out = Vec::new();
+        binary::write(&tree, &mut out)?;
+        let decoded = binary::read::<u32, Span, _>(&mut &out[..])?;
+        assert_eq!(tree, decoded, "seed {seed}");
+
+        // Every truncation of a non-trivial tree fails gracefully.
+        if seed <= 4 {
+            for n in 0..out.len() {
+                let result = binary::read::<u32, Span, _>(&mut &out[..n]);
+                assert!(
+                    matches!(result, Err(DecodeError::Corrupt(..))),
+                    "{seed}: {n}"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn compact_size() -> Result<()> {
+    // A lower bound on the in-memory footprint of every node, which has at
+    // least five 32-bit references to other nodes and a span of two indexes
+    // in addition to its value.
+    let links = 5 * std::mem::size_of::<u32>() + std::mem::size_of::<Span>();
+
+    for seed in 1..=16 {
+        let tree = common::Fixture::new()
+            .max_steps(500)
+            .wide()
+            .generate(seed)?;
+        let encoded = encode(&tree)?;
+        let memory = tree.len() * (links + std::mem::size_of::<u32>());
+
+        assert!(
+            tree.is_empty() || encoded.len() * 4 < memory,
+            "seed {seed}: {} bytes encoded versus {memory} in memory",
+            encoded.len()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anyhow::{anyhow, Result};
+
+#[test]
+fn checked_id() -> Result<()> {
+    let a = syntree::tree! {
+        "root" => {
+            ("a1", 1),
+            "inner" => {
+                ("a2", 2),
+            },
+        }
+    };
+
+    let b = a.clone();
+    let empty = syntree::Tree::<&str, syntree::Span>::default();
+
+    for node in a.walk() {
+        let id = a.checked_id(node).ok_or(anyhow!("node not in tree"))?;
+        assert_eq!(id.id(), node.id());
+        assert_eq!(a.get_checked(id), Some(node));
+
+        // The clone has identical contents but separate storage.
+        assert!(b.get_checked(id).is_none());
+        assert!(b.checked_id(node).is_none());
+        assert!(empty.get_checked(id).is_none());
+        assert_eq!(b.get(id.id()).map(|n| *n.value()), Some(*node.value()));
+    }
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
 enum Syntax {
     ROOT,
     NUMBER,
@@ -123,3 +124,34 @@ fn test_nested_checkpoints2() -> Result<()> {
     assert_eq!(tree, expected);
     Ok(())
 }
+
+#[test]
+fn test_close_at_updates_last() -> Result<()> {
+    let mut tree = syntree::Builder::new();
+
+    tree.open("root")?;
+    let c = tree.checkpoint()?;
+    tree.token("a", 1)?;
+    tree.token("b", 1)?;
+    tree.close_at(&c, "wrap")?;
+    tree.close()?;
+
+    let c = tree.checkpoint()?;
+    tree.token("c", 1)?;
+    tree.token("d", 1)?;
+    tree.close_at(&c, "wrap2")?;
+
+    let tree = tree.build()?;
+
+    assert_eq!(tree.last().map(|n| *n.value()), Some("wrap2"));
+    assert!(tree
+        .children()
+        .rev()
+        .map(|n| *n.value())
+        .eq(["wrap2", "root"]));
+
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    assert_eq!(root.last().map(|n| *n.value()), Some("wrap"));
+    assert!(root.children().rev().map(|n| *n.value()).eq(["wrap"]));
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use syntree::{Builder, ChildIndex, CursorPolicy, Span};
+
+#[test]
+fn child_by_offset_matches_scan() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open("array")?;
+
+    for i in 0..100 {
+        tree.open("number")?;
+        tree.token("lit", 1 + i % 3)?;
+        tree.close()?;
+
+        if i % 10 == 0 {
+            tree.token("empty", 0)?;
+        }
+
+        tree.token("comma", 1)?;
+    }
+
+    tree.close()?;
+    tree.token("eof", 0)?;
+
+    let tree = tree.build()?;
+    let index = ChildIndex::new(&tree);
+    let array = tree.first().ok_or(anyhow::anyhow!("missing array"))?;
+
+    assert_eq!(index.len(array), array.children().count());
+
+    for (n, child) in array.children().enumerate() {
+        assert_eq!(index.nth(array, n), Some(child));
+    }
+
+    assert!(index.nth(array, array.children().count()).is_none());
+
+    for offset in 0..array.range().end {
+        let expected = array.children().find(|n| n.range().contains(&offset));
+        assert_eq!(index.child_by_offset(array, offset), expected);
+    }
+
+    // Tokens and childless nodes have no children.
+    let number = index
+        .nth(array, 0)
+        .ok_or(anyhow::anyhow!("missing number"))?;
+    let lit = index.nth(number, 0).ok_or(anyhow::anyhow!("missing lit"))?;
+    assert_eq!(index.len(lit), 0);
+    assert!(index.child_by_offset(lit, 0).is_none());
+
+    let end = tree.span().range().end;
+    assert_eq!(index.root_by_offset(0), Some(array));
+    assert_eq!(index.root_by_offset(end).map(|n| *n.value()), Some("eof"));
+    assert!(index.root_by_offset(end + 1).is_none());
+    Ok(())
+}
+
+#[test]
+fn child_by_offset_gaps() -> Result<()> {
+    let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    tree.open("root")?;
+    tree.token("a", 2)?;
+    tree.token_with_span("b", Span::new(4, 5))?;
+    tree.token_with_span("c", Span::new(5, 7))?;
+    tree.close()?;
+
+    let tree = tree.build()?;
+    let index = ChildIndex::new(&tree);
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+
+    let values = (0..8)
+        .map(|o| index.child_by_offset(root, o).map(|n| *n.value()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        values,
+        [
+            Some("a"),
+            Some("a"),
+            None,
+            None,
+            Some("b"),
+            Some("c"),
+            Some("c"),
+            None
+        ]
+    );
+
+    Ok(())
+}
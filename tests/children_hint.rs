@@ -0,0 +1,86 @@
+use anyhow::Result;
+use syntree::{Builder, BuilderLimits, Span, Tree};
+
+/// Build a tree with three arrays of two children each, where the arrays are
+/// opened with the given children hint.
+fn build(hint: Option<usize>) -> Result<Tree<&'static str, Span>> {
+    let mut b = Builder::new();
+
+    b.open("root")?;
+
+    for _ in 0..3 {
+        match hint {
+            Some(hint) => b.open_with_children_hint("array", hint)?,
+            None => b.open("array")?,
+        };
+
+        b.token("number", 1)?;
+        b.open("nested")?;
+        b.token("number", 2)?;
+        b.close()?;
+        b.close()?;
+    }
+
+    b.close()?;
+    Ok(b.build()?)
+}
+
+#[test]
+fn children_hint_is_reconciled() -> Result<()> {
+    let expected = build(None)?;
+
+    for hint in [0, 1, 2, 3, 100] {
+        let tree = build(Some(hint))?;
+        tree.validate()?;
+        assert_eq!(tree, expected, "hint {hint}");
+
+        for array in tree.walk().filter(|n| *n.value() == "array") {
+            let children = array.children();
+            assert_eq!(children.len(), 2);
+            assert_eq!(children.size_hint(), (2, Some(2)));
+            assert_eq!(children.count(), 2);
+        }
+
+        let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+        assert_eq!(root.children().len(), 3);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn children_hint_reserves() -> Result<()> {
+    let mut b = Builder::<_, Span>::new();
+    b.open_with_children_hint("root", 1000)?;
+
+    for _ in 0..1000 {
+        b.token("number", 1)?;
+    }
+
+    b.close()?;
+    let tree = b.build()?;
+
+    assert!(tree.capacity() >= 1001);
+    assert_eq!(tree.first().map(|n| n.children().len()), Some(1000));
+    Ok(())
+}
+
+#[test]
+fn children_hint_bogus() -> Result<()> {
+    // An absurd hint is ignored rather than failing or aborting.
+    let mut b = Builder::<_, Span>::new();
+    b.open_with_children_hint("root", usize::MAX)?;
+    b.token("number", 1)?;
+    b.close()?;
+    let tree = b.build()?;
+    assert_eq!(tree.first().map(|n| n.children().len()), Some(1));
+
+    // Reservations are bounded by the node limit.
+    let mut b = Builder::<_, Span>::new().with_limits(BuilderLimits::new().max_nodes(4));
+    b.open_with_children_hint("root", 1 << 30)?;
+    b.token("number", 1)?;
+    b.close()?;
+    let tree = b.build()?;
+    assert!(tree.capacity() < 1 << 30);
+    Ok(())
+}
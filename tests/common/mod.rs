@@ -0,0 +1,157 @@
+//! Fixtures shared between integration tests.
+//!
+//! Every test binary only uses some of these, so unused items are allowed.
+
+#![allow(dead_code)]
+
+use anyhow::{anyhow, Result};
+use syntree::{Builder, Span, Tree};
+
+/// A small deterministic xorshift generator so that failures are
+/// reproducible.
+pub struct Rng(u64);
+
+impl Rng {
+    /// Construct a generator from a non-zero `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Generates trees with empty nodes, tokens and nodes wrapped through
+/// checkpoints by driving a [`Builder`] with random operations.
+pub struct Fixture {
+    max_steps: u64,
+    max_value: Option<u64>,
+    wide: bool,
+    reuse: bool,
+}
+
+impl Fixture {
+    pub const fn new() -> Self {
+        Self {
+            max_steps: 200,
+            max_value: None,
+            wide: false,
+            reuse: false,
+        }
+    }
+
+    /// Perform fewer than `max_steps` builder operations.
+    pub fn max_steps(self, max_steps: u64) -> Self {
+        Self { max_steps, ..self }
+    }
+
+    /// Pick values below `max_value` instead of numbering them by step, so
+    /// that equal subtrees are likely.
+    pub fn max_value(self, max_value: u64) -> Self {
+        Self {
+            max_value: Some(max_value),
+            ..self
+        }
+    }
+
+    /// Use values and token lengths of widely varying widths.
+    pub fn wide(self) -> Self {
+        Self { wide: true, ..self }
+    }
+
+    /// Also reuse a small subtree from another tree.
+    pub fn reuse(self) -> Self {
+        Self {
+            reuse: true,
+            ..self
+        }
+    }
+
+    /// Generate a tree for the given `seed`.
+    pub fn generate(&self, seed: u64) -> Result<Tree<u32, Span>> {
+        self.generate_with(&mut Rng::new(seed))
+    }
+
+    /// Generate a tree using `rng`.
+    pub fn generate_with(&self, rng: &mut Rng) -> Result<Tree<u32, Span>> {
+        let source = syntree::tree! {
+            0 => {
+                (1, 2),
+                2 => {
+                    (3, 1),
+                    4 => {},
+                },
+            }
+        };
+
+        let reused = source.first().ok_or(anyhow!("missing reused"))?;
+
+        let mut b = Builder::new();
+        let mut parents = Vec::new();
+        let mut checkpoints = Vec::new();
+
+        let ops = if self.reuse { 7 } else { 6 };
+
+        for n in 0..rng.below(self.max_steps) {
+            let value = if self.wide {
+                rng.next() as u32 >> (rng.below(4) * 8)
+            } else if let Some(max_value) = self.max_value {
+                rng.below(max_value) as u32
+            } else {
+                n as u32
+            };
+
+            match rng.below(ops) {
+                0 => {
+                    parents.push(b.open(value)?);
+                }
+                1 if !parents.is_empty() => {
+                    b.close()?;
+                    parents.pop();
+                }
+                2 => {
+                    checkpoints.push((parents.last().copied(), b.checkpoint()?));
+                }
+                3 => {
+                    // Only close checkpoints created under the current parent.
+                    if let Some((parent, c)) = checkpoints.pop() {
+                        if parent == parents.last().copied() {
+                            b.close_at(&c, value)?;
+                        }
+                    }
+                }
+                6 => {
+                    b.reuse(reused)?;
+                }
+                _ => {
+                    let len = if self.wide {
+                        (rng.next() as usize >> (rng.below(8) * 8)) % 100_000
+                    } else {
+                        rng.below(3) as usize
+                    };
+
+                    b.token(value, len)?;
+                }
+            }
+        }
+
+        for _ in parents {
+            b.close()?;
+        }
+
+        Ok(b.build()?)
+    }
+}
+
+/// Generate a tree with the default [`Fixture`] for the given `seed`.
+pub fn generate(seed: u64) -> Result<Tree<u32, Span>> {
+    Fixture::new().generate(seed)
+}
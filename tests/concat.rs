@@ -0,0 +1,122 @@
+use anyhow::Result;
+use syntree::{Builder, CursorPolicy, Error, Id, Span, Tree};
+
+#[test]
+fn concat_shifts_spans() -> Result<()> {
+    let a = syntree::tree! {
+        "fn" => {
+            ("ident", 2),
+            ("eof", 0),
+        }
+    };
+
+    let b = syntree::tree! {
+        "fn" => {
+            ("ident", 3),
+        },
+        ("eof", 0),
+    };
+
+    let c = syntree::tree! {
+        "struct" => {
+            ("ident", 1),
+        }
+    };
+
+    let offsets = [0, a.len(), a.len() + b.len()];
+    let inputs = [a.clone(), b.clone(), c.clone()];
+
+    let tree = Tree::concat([a, Tree::default(), b, Tree::default(), c])?;
+    tree.validate()?;
+
+    let expected = syntree::tree! {
+        "fn" => {
+            ("ident", 2),
+            ("eof", 0),
+        },
+        "fn" => {
+            ("ident", 3),
+        },
+        ("eof", 0),
+        "struct" => {
+            ("ident", 1),
+        }
+    };
+
+    assert_eq!(tree, expected);
+    assert_eq!(tree.span(), &Span::new(0, 6));
+
+    let ranges = tree.children().map(|n| n.range()).collect::<Vec<_>>();
+    assert_eq!(ranges, [0..2, 2..5, 5..5, 5..6]);
+
+    let ranges = tree.children().rev().map(|n| n.range()).collect::<Vec<_>>();
+    assert_eq!(ranges, [5..6, 5..5, 2..5, 0..2]);
+
+    // Identifiers from the inputs are offset by the length of the preceding
+    // trees.
+    for (input, offset) in inputs.iter().zip(offsets) {
+        for node in input.walk() {
+            let id = Id::from_index(node.id().index() + offset);
+            let new = id.and_then(|id| tree.get(id));
+            let new = new.ok_or(anyhow::anyhow!("missing node"))?;
+            assert_eq!(node.value(), new.value());
+        }
+    }
+
+    // Tokens are indexed in their new location.
+    let node = tree.node_with_range(2..5);
+    assert_eq!(node.map(|n| *n.value()), Some("fn"));
+    Ok(())
+}
+
+#[test]
+fn concat_empty() -> Result<()> {
+    let tree = Tree::<&str, Span>::concat([])?;
+    assert!(tree.is_empty());
+    assert_eq!(tree.span(), &Span::point(0));
+
+    let tree = Tree::<&str, Span>::concat([Tree::default(), Tree::default()])?;
+    assert!(tree.is_empty());
+    assert!(tree.first().is_none());
+
+    let a = syntree::tree!(("eof", 0));
+    let tree = Tree::concat([Tree::default(), a.clone(), a])?;
+    tree.validate()?;
+
+    assert_eq!(tree.children().count(), 2);
+    assert_eq!(tree.span(), &Span::point(0));
+    Ok(())
+}
+
+#[test]
+fn concat_ordered() -> Result<()> {
+    let mut a = Builder::new().with_policy(CursorPolicy::Monotonic);
+    a.token("ident", 2)?;
+    a.token("eof", 0)?;
+    let a = a.build()?;
+
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+    b.advance(2)?;
+    b.open("fn")?;
+    b.token("ident", 2)?;
+    b.close()?;
+    let b = b.build()?;
+
+    let tree = Tree::concat_ordered([a.clone(), Tree::default(), b.clone()])?;
+    tree.validate()?;
+
+    let ranges = tree.children().map(|n| n.range()).collect::<Vec<_>>();
+    assert_eq!(ranges, [0..2, 2..2, 2..4]);
+    assert_eq!(tree.span(), &Span::new(0, 4));
+
+    assert_eq!(
+        Tree::concat_ordered([b, a]),
+        Err(Error::ConcatOverlap {
+            index: 1,
+            start: 0,
+            end: 4
+        })
+    );
+
+    Ok(())
+}
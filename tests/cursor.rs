@@ -0,0 +1,178 @@
+use anyhow::Result;
+use syntree::node::Cursor;
+use syntree::{Span, TokenIndex, Tree};
+
+fn fixture() -> Tree<&'static str, Span> {
+    syntree::tree! {
+        "fn" => {
+            ("ident", 3),
+            "block" => {
+                ("open", 1),
+                "stmt" => {
+                    ("ident", 2),
+                    ("semi", 1),
+                },
+                "empty" => {},
+                ("close", 1),
+            },
+        },
+        ("ws", 1),
+        "struct" => {
+            ("ident", 2),
+        },
+        ("eof", 0),
+    }
+}
+
+/// Visit every node reachable from the cursor in depth-first order, returning
+/// the cursor to where it started.
+fn visit<'a>(
+    cursor: &mut Cursor<'a, &'static str, Span>,
+    out: &mut Vec<(syntree::Node<'a, &'static str, Span>, usize)>,
+) {
+    let depth = cursor.depth();
+
+    loop {
+        out.push((cursor.node(), cursor.depth()));
+
+        if cursor.goto_first_child() {
+            continue;
+        }
+
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+
+            if cursor.depth() == depth || !cursor.goto_parent() {
+                return;
+            }
+        }
+    }
+}
+
+#[test]
+fn cursor_visits_every_node() -> Result<()> {
+    let tree = fixture();
+    let mut cursor = tree.cursor().ok_or(anyhow::anyhow!("missing cursor"))?;
+
+    let mut visited = Vec::new();
+    visit(&mut cursor, &mut visited);
+
+    let visited = visited.iter().map(|(n, d)| (n.id(), *d));
+    let expected = tree.walk().with_depths().map(|(d, n)| (n.id(), d));
+    assert!(visited.eq(expected));
+
+    // The cursor ends at the last root, from where it can't go anywhere.
+    assert_eq!(*cursor.node().value(), "eof");
+    assert!(!cursor.goto_next_sibling());
+    assert!(!cursor.goto_parent());
+    assert!(!cursor.goto_first_child());
+    Ok(())
+}
+
+#[test]
+fn cursor_path_and_back() -> Result<()> {
+    let tree = fixture();
+
+    for node in tree.walk() {
+        let mut cursor = tree.cursor().ok_or(anyhow::anyhow!("missing cursor"))?;
+
+        // Navigate to `node` by following its ancestors from the root.
+        let mut ancestors = node.ancestors().collect::<Vec<_>>();
+        ancestors.reverse();
+
+        for (depth, target) in ancestors.iter().enumerate() {
+            if depth > 0 {
+                assert!(cursor.goto_first_child());
+            }
+
+            while !cursor.node().same(target) {
+                assert!(cursor.goto_next_sibling());
+            }
+        }
+
+        assert!(cursor.node().same(&node));
+        assert_eq!(cursor.depth(), ancestors.len() - 1);
+        let path = cursor.path().iter().map(|n| n.id());
+        assert!(path.eq(ancestors[..ancestors.len() - 1].iter().map(|n| n.id())));
+
+        // And back to the root.
+        for target in ancestors.iter().rev().skip(1) {
+            assert!(cursor.goto_parent());
+            assert!(cursor.node().same(target));
+        }
+
+        assert!(!cursor.goto_parent());
+        assert_eq!(cursor.depth(), 0);
+        assert!(cursor.path().is_empty());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn node_cursor_is_confined() -> Result<()> {
+    let tree = fixture();
+    let block = tree
+        .walk()
+        .find(|n| *n.value() == "block")
+        .ok_or(anyhow::anyhow!("missing block"))?;
+
+    let mut cursor = block.cursor();
+    assert!(!cursor.goto_next_sibling());
+    assert!(!cursor.goto_parent());
+
+    let mut visited = Vec::new();
+    visit(&mut cursor, &mut visited);
+
+    let visited = visited.iter().map(|(n, d)| (n.id(), *d));
+    let expected = block.walk().with_depths().map(|(d, n)| (n.id(), d + 1));
+    assert!(visited.eq(std::iter::once((block.id(), 0)).chain(expected)));
+    assert!(cursor.node().same(&block));
+
+    // Tokens outside of the node can't be reached.
+    assert!(!cursor.goto_token_at(0));
+    assert!(cursor.goto_token_at(6));
+    assert_eq!(*cursor.node().value(), "semi");
+    assert_eq!(cursor.depth(), 2);
+    assert!(!cursor.goto_token_at(0));
+    assert_eq!(*cursor.node().value(), "semi");
+
+    // A token cursor has nowhere to go.
+    let mut cursor = tree
+        .first_token()
+        .ok_or(anyhow::anyhow!("missing token"))?
+        .cursor();
+
+    assert!(!cursor.goto_first_child());
+    assert!(!cursor.goto_next_sibling());
+    assert!(cursor.goto_token_at(1));
+    assert_eq!(cursor.depth(), 0);
+    Ok(())
+}
+
+#[test]
+fn cursor_token_at() -> Result<()> {
+    let tree = fixture();
+    let index = TokenIndex::new(&tree);
+    let mut cursor = tree.cursor().ok_or(anyhow::anyhow!("missing cursor"))?;
+
+    for offset in 0..=tree.span().range().end + 1 {
+        let expected = index.token_at(offset);
+        assert_eq!(cursor.goto_token_at(offset), expected.is_some());
+
+        let Some(expected) = expected else {
+            continue;
+        };
+
+        assert!(cursor.node().same(&expected));
+
+        let mut ancestors = expected.ancestors().skip(1).collect::<Vec<_>>();
+        ancestors.reverse();
+        let path = cursor.path().iter().map(|n| n.id());
+        assert!(path.eq(ancestors.iter().map(|n| n.id())));
+    }
+
+    Ok(())
+}
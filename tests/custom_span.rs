@@ -0,0 +1,154 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use syntree::span::{Index, TreeIndex, TreeSpan};
+use syntree::{Builder, CursorPolicy, Tree};
+
+/// A span which also tracks the file it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileSpan {
+    file: u32,
+    start: Index,
+    end: Index,
+}
+
+const fn fs(file: u32, start: Index, end: Index) -> FileSpan {
+    FileSpan { file, start, end }
+}
+
+impl TreeSpan for FileSpan {
+    const EMPTY: Self = fs(0, 0, 0);
+    const INDEXES: Self::Indexes = Vec::new();
+
+    type Length = usize;
+    type Indexes = Vec<TreeIndex>;
+
+    fn point(index: Index) -> Self {
+        fs(0, index, index)
+    }
+
+    fn new(start: Index, end: Index) -> Self {
+        fs(0, start, end)
+    }
+
+    fn start(&self) -> Index {
+        self.start
+    }
+
+    fn end(&self) -> Index {
+        self.end
+    }
+
+    fn set_end(&mut self, end: Index) {
+        self.end = end;
+    }
+
+    fn length(&self) -> usize {
+        self.range().len()
+    }
+
+    fn join(&self, other: &Self) -> Self {
+        let file = if self.is_empty() {
+            other.file
+        } else {
+            self.file
+        };
+        fs(file, self.start.min(other.start), self.end.max(other.end))
+    }
+
+    fn fmt_span(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}..{}", self.file, self.start, self.end)
+    }
+}
+
+#[test]
+fn custom_span_build() -> Result<()> {
+    let mut b = Builder::<_, FileSpan>::new_with();
+
+    b.open("root")?;
+    b.open("number")?;
+    b.token_with_span("lit", fs(3, 0, 2))?;
+    b.close()?;
+    b.token_with_span("ws", fs(3, 2, 3))?;
+    b.open("empty")?;
+    b.close()?;
+    b.close()?;
+
+    let tree = b.build()?;
+
+    let spans = tree
+        .walk()
+        .map(|n| (*n.value(), *n.span()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        spans,
+        [
+            ("root", fs(3, 0, 3)),
+            ("number", fs(3, 0, 2)),
+            ("lit", fs(3, 0, 2)),
+            ("ws", fs(3, 2, 3)),
+            ("empty", fs(0, 3, 3)),
+        ]
+    );
+
+    let number = tree
+        .node_with_range(0..2)
+        .ok_or(anyhow!("missing number"))?;
+    assert_eq!(*number.value(), "number");
+    Ok(())
+}
+
+#[test]
+fn custom_span_policies() -> Result<()> {
+    for policy in [CursorPolicy::Monotonic, CursorPolicy::Unordered] {
+        let mut b = Builder::<_, FileSpan>::new_with().with_policy(policy);
+
+        b.open("root")?;
+        let c = b.checkpoint()?;
+        b.token_with_span("a", fs(1, 2, 4))?;
+        b.token_with_span("b", fs(1, 6, 8))?;
+        b.close_at(&c, "wrapped")?;
+        b.close()?;
+
+        let tree = b.build()?;
+
+        let spans = tree
+            .walk()
+            .map(|n| (*n.value(), *n.span()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            spans,
+            [
+                ("root", fs(1, 2, 8)),
+                ("wrapped", fs(1, 2, 8)),
+                ("a", fs(1, 2, 4)),
+                ("b", fs(1, 6, 8)),
+            ]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn custom_span_macro_and_print() -> Result<()> {
+    let tree: Tree<_, FileSpan> = syntree::tree_with! {
+        "root" => {
+            ("lit", span = fs(2, 0, 3)),
+            ("ws", span = fs(2, 3, 4)),
+        },
+        ("eof", span = fs(2, 4, 4)),
+    };
+
+    let mut s = Vec::new();
+    syntree::print::print(&mut s, &tree)?;
+
+    assert_eq!(
+        String::from_utf8(s)?,
+        "\"root\"@2:0..4\n  \"lit\"@2:0..3 +\n  \"ws\"@2:3..4 +\n\"eof\"@2:4..4 +\n"
+    );
+
+    Ok(())
+}
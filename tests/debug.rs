@@ -0,0 +1,95 @@
+use anyhow::{anyhow, Result};
+use syntree::{print, Builder};
+
+#[test]
+fn tree_debug_matches_print() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    tree.open("group")?;
+    tree.token("ident", 3)?;
+    tree.close()?;
+    tree.error_token("garbage", 2)?;
+    tree.close()?;
+    tree.open("empty")?;
+    tree.close()?;
+
+    let tree = tree.build()?;
+
+    let expected = [
+        "\"root\"@0..5",
+        "  \"group\"@0..3",
+        "    \"ident\"@0..3 +",
+        "  \"garbage\"@3..5! +",
+        "\"empty\"@5..5",
+    ]
+    .join("\n");
+
+    assert_eq!(format!("{tree:?}"), expected);
+    assert_eq!(format!("{tree:#?}"), expected);
+
+    let mut s = Vec::new();
+    print::print(&mut s, &tree)?;
+    assert_eq!(String::from_utf8(s)?.trim_end(), expected);
+    Ok(())
+}
+
+#[test]
+fn tree_debug_elides_deep_nodes() -> Result<()> {
+    let mut tree = Builder::new();
+
+    for depth in 0..10 {
+        tree.open(depth)?;
+    }
+
+    tree.token(10, 1)?;
+
+    for _ in 0..10 {
+        tree.close()?;
+    }
+
+    tree.token(11, 1)?;
+    let tree = tree.build()?;
+
+    let mut expected = (0..=8)
+        .map(|d| format!("{:n$}{d}@0..1", "", n = d * 2))
+        .collect::<Vec<_>>();
+    expected.push(format!("{:18}…", ""));
+    expected.push("11@1..2 +".to_string());
+
+    assert_eq!(format!("{tree:?}"), expected.join("\n"));
+
+    // The alternate format includes everything.
+    let alternate = format!("{tree:#?}");
+    assert_eq!(alternate.lines().count(), 12);
+    assert!(alternate.contains(&format!("{:20}10@0..1 +", "")));
+    Ok(())
+}
+
+#[test]
+fn node_debug() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            "number" => {
+                ("lit", 2),
+            },
+            ("ws", 1),
+        },
+        ("eof", 0),
+    };
+
+    let number = tree
+        .first()
+        .and_then(|n| n.first())
+        .ok_or(anyhow!("missing number"))?;
+
+    assert_eq!(
+        format!("{number:?}"),
+        "Node { data: \"number\", kind: Node, span: (0, 2) }"
+    );
+    assert_eq!(format!("{number:#?}"), "\"number\"@0..2\n  \"lit\"@0..2 +");
+
+    let ws = number.next().ok_or(anyhow!("missing ws"))?;
+    assert_eq!(format!("{ws:#?}"), "\"ws\"@2..3 +");
+    Ok(())
+}
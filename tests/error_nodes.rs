@@ -0,0 +1,98 @@
+use anyhow::Result;
+use syntree::edit::ChangeSet;
+use syntree::{print, Builder};
+
+#[test]
+fn error_marks() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    tree.open_error("missing")?;
+    tree.token("lit", 1)?;
+    tree.close()?;
+    tree.error_token("garbage", 2)?;
+    let ok = tree.token("ok", 1)?;
+    tree.close()?;
+
+    let tree = tree.build()?;
+
+    let errors = tree.walk().filter(|n| n.is_error()).map(|n| *n.value());
+    assert_eq!(errors.collect::<Vec<_>>(), ["missing", "garbage"]);
+    assert!(!tree.get(ok).map_or(true, |n| n.is_error()));
+
+    // Error tokens are skipped like any other token, error nodes are kept.
+    let nodes = tree.walk().skip_tokens().map(|n| *n.value());
+    assert_eq!(nodes.collect::<Vec<_>>(), ["root", "missing"]);
+
+    // Marks are not considered when comparing trees.
+    let expected = syntree::tree! {
+        "root" => {
+            "missing" => {
+                ("lit", 1),
+            },
+            ("garbage", 2),
+            ("ok", 1),
+        }
+    };
+
+    assert_eq!(tree, expected);
+
+    let mut s = Vec::new();
+    print::print_with_source(&mut s, &tree, "abcd")?;
+
+    let expected = [
+        "\"root\"@0..4",
+        "  \"missing\"@0..1!",
+        "    \"lit\"@0..1 \"a\"",
+        "  \"garbage\"@1..3! \"bc\"",
+        "  \"ok\"@3..4 \"d\"",
+    ];
+
+    assert_eq!(String::from_utf8(s)?.lines().collect::<Vec<_>>(), expected);
+    Ok(())
+}
+
+#[test]
+fn open_recover_marks_error() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open_recover("root", "error", |tree| {
+        tree.token("lit", 1)?;
+        Err::<(), anyhow::Error>(anyhow::anyhow!("unexpected"))
+    })?;
+
+    let tree = tree.build()?;
+
+    let errors = tree.walk().filter(|n| n.is_error()).map(|n| *n.value());
+    assert_eq!(errors.collect::<Vec<_>>(), ["error"]);
+    Ok(())
+}
+
+#[test]
+fn marks_are_preserved() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    let lit = tree.error_token("lit", 1)?;
+    tree.token("semi", 1)?;
+    tree.close()?;
+
+    let tree = tree.build()?;
+
+    let mut change_set = ChangeSet::new();
+    change_set.wrap(lit, "wrapper");
+    let edited = change_set.modify(&tree)?;
+
+    let errors = edited.walk().filter(|n| n.is_error()).map(|n| *n.value());
+    assert_eq!(errors.collect::<Vec<_>>(), ["lit"]);
+
+    let mut reused = Builder::new();
+    reused.open("outer")?;
+    reused.reuse(tree.first().ok_or(anyhow::anyhow!("missing root"))?)?;
+    reused.close()?;
+    let reused = reused.build()?;
+
+    let errors = reused.walk().filter(|n| n.is_error()).map(|n| *n.value());
+    assert_eq!(errors.collect::<Vec<_>>(), ["lit"]);
+    Ok(())
+}
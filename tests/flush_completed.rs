@@ -0,0 +1,165 @@
+use anyhow::Result;
+use syntree::{Builder, CursorPolicy, Error, Span, Tree};
+
+/// Input records, where each record is a list of fields of the given lengths.
+const RECORDS: [&[usize]; 3] = [&[3, 1, 4], &[1, 5], &[9, 2, 6, 5]];
+
+/// Parse the records, calling `flush` after each record has been closed.
+fn parse<F>(b: &mut Builder<&'static str, Span>, mut flush: F) -> Result<()>
+where
+    F: FnMut(&mut Builder<&'static str, Span>),
+{
+    for fields in RECORDS {
+        b.open("record")?;
+
+        for (n, &len) in fields.iter().enumerate() {
+            if n > 0 {
+                b.token("comma", 1)?;
+            }
+
+            b.open("field")?;
+            b.token("lit", len)?;
+            b.close()?;
+        }
+
+        b.close()?;
+        b.token("newline", 1)?;
+        flush(b);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn flush_three_roots() -> Result<()> {
+    let mut b = Builder::new();
+    parse(&mut b, |_| {})?;
+    let expected = b.build()?;
+
+    let mut b = Builder::new();
+    let mut flushed = Vec::new();
+
+    parse(&mut b, |b| {
+        // A second flush has nothing to return.
+        flushed.extend(b.flush_completed());
+        assert!(b.flush_completed().is_none());
+    })?;
+
+    assert_eq!(flushed.len(), 3);
+    assert!(b.build()?.is_empty());
+
+    let mut roots = expected.children();
+
+    for tree in &flushed {
+        tree.validate()?;
+
+        // Each flushed tree holds one record followed by its newline, with
+        // identifiers starting at zero.
+        assert_eq!(tree.children().count(), 2);
+        assert_eq!(tree.first().map(|n| n.id().index()), Some(0));
+
+        for node in tree.children() {
+            let root = roots.next().ok_or(anyhow::anyhow!("missing root"))?;
+            assert_eq!(node.value(), root.value());
+            assert_eq!(node.span(), root.span());
+            assert!(node.walk().with_depths().eq(root.walk().with_depths()));
+        }
+
+        // Lookups by offset work within each flushed tree.
+        let record = tree.first().ok_or(anyhow::anyhow!("missing record"))?;
+        let found = tree.node_with_range(record.range());
+        assert_eq!(found.map(|n| n.id()), Some(record.id()));
+    }
+
+    assert!(roots.next().is_none());
+
+    let spans = flushed.iter().map(|t| t.range()).collect::<Vec<_>>();
+    assert_eq!(spans, [0..11, 11..19, 19..45]);
+
+    let joined = Tree::concat_ordered(flushed)?;
+    assert_eq!(joined, expected);
+    Ok(())
+}
+
+#[test]
+fn flush_requires_completed_roots() -> Result<()> {
+    let mut b = Builder::<_, Span>::new();
+    assert!(b.flush_completed().is_none());
+
+    b.open("record")?;
+    b.token("lit", 2)?;
+    assert!(b.flush_completed().is_none());
+    b.close()?;
+
+    let first = b.flush_completed().ok_or(anyhow::anyhow!("missing tree"))?;
+    assert_eq!(first.range(), 0..2);
+
+    // Building continues with spans following the flushed tree.
+    let id = b.token("lit", 3)?;
+    let second = b.build()?;
+    assert_eq!(id.index(), 0);
+    assert_eq!(second.get(id).map(|n| n.range()), Some(2..5));
+    Ok(())
+}
+
+#[test]
+fn flush_unordered() -> Result<()> {
+    let build = |b: &mut Builder<&'static str, Span>| -> Result<()> {
+        b.open("record")?;
+        b.token_with_span("b", Span::new(4, 6))?;
+        b.token_with_span("a", Span::new(0, 2))?;
+        b.close()?;
+        Ok(())
+    };
+
+    let mut b = Builder::new().with_policy(CursorPolicy::Unordered);
+    build(&mut b)?;
+    let expected = b.build()?;
+
+    let mut b = Builder::new().with_policy(CursorPolicy::Unordered);
+    build(&mut b)?;
+    let tree = b.flush_completed().ok_or(anyhow::anyhow!("missing tree"))?;
+
+    assert_eq!(tree, expected);
+
+    // Tokens are indexed in order, just like when building.
+    for range in [0..2, 1..1, 4..6, 5..5] {
+        let id = |t: &Tree<_, _>| t.node_with_range(range.clone()).map(|n| n.id());
+        assert_eq!(id(&tree), id(&expected));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn flush_invalidates_checkpoints() -> Result<()> {
+    let mut b = Builder::new();
+
+    // Refers to an existing node after flushing.
+    let existing = b.checkpoint()?;
+
+    for _ in 0..3 {
+        b.token("lit", 1)?;
+    }
+
+    // Refers to a node past the end of the tree after flushing.
+    let past = b.checkpoint()?;
+    b.token("lit", 1)?;
+    assert!(b.flush_completed().is_some());
+
+    b.token("lit", 1)?;
+    b.token("lit", 1)?;
+
+    assert_eq!(b.close_at(&existing, "wrap"), Err(Error::StaleCheckpoint));
+    assert_eq!(b.close_at(&past, "wrap"), Err(Error::StaleCheckpoint));
+
+    // Checkpoints created after flushing still work.
+    let c = b.checkpoint()?;
+    b.token("lit", 1)?;
+    b.close_at(&c, "wrap")?;
+
+    let tree = b.build()?;
+    assert_eq!(tree.children().count(), 3);
+    assert_eq!(tree.last().map(|n| *n.value()), Some("wrap"));
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use anyhow::{Context, Result};
+use syntree::Kind;
+
+#[test]
+fn fold_children_in_order() -> Result<()> {
+    let tree = syntree::tree! {
+        "a" => {
+            "b" => {
+                ("c", 1),
+                "d" => {},
+            },
+            ("e", 1),
+        },
+        "f" => {
+            ("g", 1)
+        }
+    };
+
+    let rendered = tree.fold(|node, children| {
+        let children = children.collect::<Vec<String>>();
+
+        if children.is_empty() {
+            node.value().to_string()
+        } else {
+            format!("{}({})", node.value(), children.join(" "))
+        }
+    });
+
+    assert_eq!(rendered, ["a(b(c d) e)", "f(g)"]);
+
+    let a = tree.first().context("missing a")?;
+    let b = a.first().context("missing b")?;
+
+    // Count the tokens in a subtree.
+    let tokens =
+        b.fold(|node, children| children.sum::<usize>() + usize::from(node.kind() == Kind::Token));
+
+    assert_eq!(tokens, 1);
+    Ok(())
+}
+
+#[test]
+fn fold_tokens_are_leaves() -> Result<()> {
+    let tree = syntree::tree! {
+        ("token", 3)
+    };
+
+    let token = tree.first().context("missing token")?;
+    assert_eq!(
+        token.fold(|node, children| (node.range(), children.len())),
+        (0..3, 0)
+    );
+    Ok(())
+}
+
+#[test]
+fn fold_empty() -> Result<()> {
+    let tree = syntree::Builder::<u32>::new().build()?;
+    assert!(tree.fold(|_, children| children.count()).is_empty());
+    Ok(())
+}
+
+#[test]
+fn fold_deep() -> Result<()> {
+    let mut tree = syntree::Builder::new();
+
+    for _ in 0..100_000 {
+        tree.open("n")?;
+    }
+
+    tree.token("t", 1)?;
+
+    for _ in 0..100_000 {
+        tree.close()?;
+    }
+
+    let tree = tree.build()?;
+
+    let depths = tree.fold(|_, children| children.max().map_or(0, |d| d + 1));
+    assert_eq!(depths, [100_000]);
+    Ok(())
+}
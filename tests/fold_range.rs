@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+use syntree::{Kind, Node, Span, Tree};
+
+fn is_newline(node: &Node<'_, &'static str, Span>) -> bool {
+    *node.value() == "nl"
+}
+
+fn node<'a>(
+    tree: &'a Tree<&'static str, Span>,
+    value: &str,
+) -> Result<Node<'a, &'static str, Span>> {
+    tree.walk()
+        .find(|n| *n.value() == value)
+        .ok_or_else(|| anyhow!("missing {value}"))
+}
+
+#[test]
+fn fold_range_lines() -> Result<()> {
+    // fn a() {
+    //     b
+    // }
+    // fn c() { d }
+    let tree = syntree::tree! {
+        "two" => {
+            ("fn", 8),
+            "block" => {
+                ("open", 1),
+                ("nl", 1),
+                ("ident", 5),
+                ("nl", 1),
+                ("close", 1),
+            },
+        },
+        ("nl", 1),
+        "one" => {
+            ("fn", 7),
+            "block" => {
+                ("open", 1),
+                ("ident", 3),
+                ("close", 1),
+            },
+        },
+        "empty" => {},
+    };
+
+    assert_eq!(node(&tree, "two")?.fold_range(is_newline), Some(9..17));
+    assert_eq!(node(&tree, "one")?.fold_range(is_newline), None);
+    assert_eq!(node(&tree, "empty")?.fold_range(is_newline), None);
+
+    // Tokens never fold.
+    let token = node(&tree, "close")?;
+    assert_eq!(token.fold_range(is_newline), None);
+    Ok(())
+}
+
+#[test]
+fn fold_range_trailing_newline() -> Result<()> {
+    let tree = syntree::tree! {
+        "stmt" => {
+            ("ident", 3),
+            "end" => {
+                ("nl", 1),
+                "empty" => {},
+            },
+        },
+    };
+
+    let stmt = tree.first().ok_or(anyhow!("missing stmt"))?;
+    assert_eq!(stmt.fold_range(is_newline), None);
+    assert_eq!(stmt.last_token().map(|n| n.range()), Some(3..4));
+    Ok(())
+}
+
+#[test]
+fn fold_range_early_exit() -> Result<()> {
+    let mut b = syntree::Builder::new();
+
+    b.open("block")?;
+    b.token("open", 1)?;
+    b.token("nl", 1)?;
+
+    for _ in 0..100 {
+        b.open("stmt")?;
+        b.token("ident", 1)?;
+        b.token("nl", 1)?;
+        b.close()?;
+    }
+
+    b.token("close", 1)?;
+    b.close()?;
+
+    let tree = b.build()?;
+    let block = tree.first().ok_or(anyhow!("missing block"))?;
+
+    let mut visited = 0;
+
+    let range = block.fold_range(|n| {
+        visited += 1;
+        assert_eq!(n.kind(), Kind::Token);
+        is_newline(n)
+    });
+
+    assert_eq!(range, Some(1..203));
+    assert_eq!(visited, 2);
+    Ok(())
+}
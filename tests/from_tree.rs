@@ -0,0 +1,60 @@
+use anyhow::{anyhow, Result};
+use syntree::Builder;
+
+#[test]
+fn ids_survive_build_cycles() -> Result<()> {
+    let mut b = Builder::new();
+    let mut records = Vec::new();
+
+    for n in 0..3usize {
+        let record = b.open("record")?;
+        b.token("field", n + 1)?;
+        b.token("newline", 1)?;
+        b.close()?;
+        records.push((record, n));
+
+        let built = std::mem::take(&mut b).build()?;
+
+        // Every record obtained so far still resolves to the same node.
+        for &(id, m) in &records {
+            let node = built.get(id).ok_or(anyhow!("missing record"))?;
+            assert_eq!(*node.value(), "record");
+            assert_eq!(node.first().map(|n| n.range().len()), Some(m + 1));
+        }
+
+        b = Builder::from_tree(built);
+    }
+
+    let tree = b.build()?;
+
+    let expected = syntree::tree! {
+        "record" => {
+            ("field", 1),
+            ("newline", 1),
+        },
+        "record" => {
+            ("field", 2),
+            ("newline", 1),
+        },
+        "record" => {
+            ("field", 3),
+            ("newline", 1),
+        },
+    };
+
+    assert_eq!(tree, expected);
+    assert_eq!(tree.children().count(), 3);
+    assert_eq!(tree.children().rev().count(), 3);
+    Ok(())
+}
+
+#[test]
+fn from_empty_tree() -> Result<()> {
+    let tree = Builder::<&str>::new().build()?;
+    let mut tree = Builder::from_tree(tree);
+    tree.token("lit", 2)?;
+    let tree = tree.build()?;
+
+    assert_eq!(tree, syntree::tree! { ("lit", 2) });
+    Ok(())
+}
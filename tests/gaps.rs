@@ -0,0 +1,82 @@
+use anyhow::Result;
+use syntree::{Builder, CursorPolicy, Span};
+
+#[test]
+fn leading_gap() -> Result<()> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    b.advance(3)?;
+    b.open("root")?;
+    b.token("lit", 2)?;
+    b.advance(1)?;
+    b.token("lit", 1)?;
+    b.close()?;
+
+    let tree = b.build()?;
+    assert_eq!(tree.span(), &Span::new(0, 7));
+    assert!(tree.gaps().eq([Span::new(0, 3), Span::new(5, 6)]));
+
+    // The span of the root starts at its first token.
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    assert_eq!(root.range(), 3..7);
+    assert!(root.gaps().eq([Span::new(5, 6)]));
+    Ok(())
+}
+
+#[test]
+fn gap_across_node_boundary() -> Result<()> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    b.open("block")?;
+    b.open("stmt")?;
+    b.token("ident", 3)?;
+    b.advance(1)?;
+    b.close()?;
+    b.token("eof", 0)?;
+    b.advance(2)?;
+    b.open("stmt")?;
+    b.token("ident", 2)?;
+    b.close()?;
+    b.close()?;
+    b.advance(1)?;
+
+    let tree = b.build()?;
+    let block = tree.first().ok_or(anyhow::anyhow!("missing block"))?;
+
+    // The gap starts in one statement and ends in another, and the zero-width
+    // token inside of it doesn't split it.
+    assert!(block.gaps().eq([Span::new(3, 6)]));
+    assert!(tree.gaps().eq([Span::new(3, 6), Span::new(8, 9)]));
+
+    for stmt in block.children().filter(|n| *n.value() == "stmt") {
+        assert!(stmt.gaps().next().is_none());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn no_gaps() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("open", 1),
+            "empty" => {},
+            "number" => {
+                ("lit", 3),
+            },
+            ("eof", 0),
+            ("close", 1),
+        },
+        ("ws", 2),
+    };
+
+    assert!(tree.gaps().next().is_none());
+
+    for node in tree.walk() {
+        assert!(node.gaps().next().is_none());
+    }
+
+    let tree: syntree::Tree<&str> = syntree::tree!();
+    assert!(tree.gaps().next().is_none());
+    Ok(())
+}
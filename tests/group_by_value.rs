@@ -0,0 +1,85 @@
+use anyhow::Result;
+use syntree::{Span, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Syntax {
+    File,
+    FnDef,
+    StructDef,
+    Block,
+    Ident,
+}
+
+use Syntax::*;
+
+fn fixture() -> Tree<Syntax, Span> {
+    syntree::tree! {
+        File => {
+            FnDef => {
+                (Ident, 1),
+                Block => {
+                    FnDef => {
+                        (Ident, 1),
+                    },
+                    StructDef => {
+                        (Ident, 1),
+                    },
+                },
+            },
+            StructDef => {
+                (Ident, 1),
+            },
+            FnDef => {
+                (Ident, 1),
+            },
+        }
+    }
+}
+
+#[test]
+fn descendants_with_value() -> Result<()> {
+    let tree = fixture();
+
+    let ranges = tree.descendants_with_value(&FnDef).map(|n| n.range());
+    assert_eq!(ranges.collect::<Vec<_>>(), [0..3, 1..2, 4..5]);
+
+    let ranges = tree.descendants_with_value(&StructDef).map(|n| n.range());
+    assert_eq!(ranges.collect::<Vec<_>>(), [2..3, 3..4]);
+
+    // Only descendants are included, never the node itself.
+    let first = tree
+        .descendants_with_value(&FnDef)
+        .next()
+        .ok_or(anyhow::anyhow!("missing fn"))?;
+
+    let mut it = first.descendants_with_value(&FnDef);
+    assert_eq!(it.next().map(|n| n.range()), Some(1..2));
+    assert!(it.next().is_none());
+
+    let file = tree.first().ok_or(anyhow::anyhow!("missing file"))?;
+    assert_eq!(file.descendants_with_value(&File).count(), 0);
+    assert_eq!(tree.descendants_with_value(&File).count(), 1);
+    Ok(())
+}
+
+#[test]
+fn group_by_value() -> Result<()> {
+    let tree = fixture();
+    let groups = tree.group_by_value();
+
+    assert_eq!(groups.len(), 5);
+
+    // Every group agrees with walking the tree for that value.
+    for value in [File, FnDef, StructDef, Block, Ident] {
+        let expected = tree
+            .descendants_with_value(&value)
+            .map(|n| n.id())
+            .collect::<Vec<_>>();
+
+        assert_eq!(groups.get(&value), Some(&expected));
+    }
+
+    let total = groups.values().map(Vec::len).sum::<usize>();
+    assert_eq!(total, tree.len());
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+
+use anyhow::Result;
+use syntree::{Id, TreeHashes};
+
+mod common;
+
+#[test]
+fn filtered_hashes_match() -> Result<()> {
+    let state = RandomState::new();
+    let mut rng = common::Rng::new(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..200 {
+        let tree = common::Fixture::new()
+            .max_steps(64)
+            .max_value(3)
+            .generate_with(&mut rng)?;
+
+        let skipped = tree
+            .walk()
+            .filter(|_| rng.below(5) == 0)
+            .map(|n| n.id())
+            .collect::<HashSet<Id>>();
+
+        let skip = |n: &syntree::Node<'_, u32>| skipped.contains(&n.id());
+
+        let hashes = TreeHashes::new(&tree, &state);
+        let filtered = TreeHashes::new_filtered(&tree, &state, skip);
+
+        for node in tree.walk() {
+            assert_eq!(hashes.get(node.id()), Some(node.content_hash(&state)));
+            assert_eq!(
+                filtered.get(node.id()),
+                Some(node.content_hash_filtered(&state, skip))
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn skipped_contents_do_not_matter() -> Result<()> {
+    let state = RandomState::new();
+
+    let a = syntree::tree! {
+        "fn" => {
+            "doc" => {
+                ("comment", 4),
+            },
+            "body" => {
+                ("ident", 3),
+            },
+        }
+    };
+
+    let b = syntree::tree! {
+        "fn" => {
+            "doc" => {
+                "nested" => {
+                    ("comment", 2),
+                },
+            },
+            "body" => {
+                ("ident", 3),
+            },
+        }
+    };
+
+    let c = syntree::tree! {
+        "fn" => {
+            "doc" => {},
+            "body" => {
+                ("ident", 4),
+            },
+        }
+    };
+
+    let is_doc = |n: &syntree::Node<'_, &str>| *n.value() == "doc";
+
+    let a = a.first().map(|n| n.content_hash_filtered(&state, is_doc));
+    let b = b.first().map(|n| n.content_hash_filtered(&state, is_doc));
+    let c = c.first().map(|n| n.content_hash_filtered(&state, is_doc));
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    Ok(())
+}
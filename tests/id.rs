@@ -0,0 +1,36 @@
+use anyhow::Result;
+use syntree::{Builder, Id};
+
+#[test]
+fn id_round_trip() -> Result<()> {
+    let mut b = Builder::new();
+
+    let c = b.checkpoint()?;
+    b.token("a", 1)?;
+    b.token("b", 2)?;
+    let wrapped = b.close_at(&c, "wrapped")?;
+    b.open("root")?;
+    b.token("c", 1)?;
+    b.close()?;
+
+    let tree = b.build()?;
+
+    // Checkpoints allocate the wrapping node last, even though it comes
+    // first in the tree.
+    assert_eq!(wrapped.index(), 2);
+    assert_eq!(tree.first().map(|n| n.id()), Some(wrapped));
+
+    let mut ids = tree.walk().map(|n| n.id()).collect::<Vec<_>>();
+    ids.sort();
+
+    for (index, id) in ids.iter().copied().enumerate() {
+        assert_eq!(id.index(), index);
+        assert_eq!(usize::from(id), index);
+        assert_eq!(Id::try_from(index)?, id);
+        assert_eq!(id.to_string(), index.to_string());
+        assert_eq!(tree.get(id).map(|n| n.id()), Some(id));
+    }
+
+    assert!(Id::try_from(usize::MAX).is_err());
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use anyhow::{Context, Result};
+use syntree::{Error, FlagSet, Id, IdMap, IdSet};
+
+#[test]
+fn ids_from_other_tree() -> Result<()> {
+    let small = syntree::tree! {
+        "root" => {
+            ("lit", 1),
+        }
+    };
+
+    let large = syntree::tree! {
+        "root" => {
+            ("lit", 1),
+            ("lit", 2),
+            ("lit", 3),
+        }
+    };
+
+    let mut map = IdMap::with_capacity_for(&small);
+    let mut set = IdSet::new();
+
+    for node in small.walk() {
+        map.insert(node.id(), *node.value());
+        set.insert(node.id());
+    }
+
+    let mut found = 0;
+
+    for node in large.walk() {
+        found += usize::from(map.get(node.id()).is_some());
+        assert_eq!(map.contains(node.id()), set.contains(node.id()));
+        assert_eq!(map.remove(node.id()).is_some(), set.remove(node.id()));
+    }
+
+    assert_eq!(found, 2);
+    assert!(map.is_empty());
+    assert!(set.is_empty());
+    assert_eq!(map.iter().count(), 0);
+    assert_eq!(set.iter().count(), 0);
+    Ok(())
+}
+
+#[test]
+fn iteration_in_id_order() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            "a" => {
+                ("lit", 1),
+            },
+            ("lit", 2),
+        }
+    };
+
+    let mut map = IdMap::new();
+
+    for node in tree.walk().collect::<Vec<_>>().into_iter().rev() {
+        map.insert(node.id(), *node.value());
+    }
+
+    for (_, value) in &mut map {
+        *value = "x";
+    }
+
+    let ids = map.iter().map(|(id, _)| id).collect::<Vec<_>>();
+    let mut expected = tree.walk().map(|n| n.id()).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(ids, expected);
+    assert!(map.iter().all(|(_, v)| *v == "x"));
+
+    let set = ids.iter().copied().rev().collect::<IdSet>();
+    assert!(set.iter().eq(ids.iter().copied()));
+    Ok(())
+}
+
+#[test]
+fn flag_sets() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("lit", 1),
+            ("lit", 2),
+        },
+        "other" => {},
+    };
+
+    let ids = tree.walk().map(|n| n.id()).collect::<Vec<_>>();
+
+    let mut a = FlagSet::new(&tree);
+    let mut b = FlagSet::new(&tree);
+    assert_eq!(a.capacity(), 4);
+
+    a.set(ids[0])?;
+    a.set(ids[1])?;
+    b.set(ids[1])?;
+    b.set(ids[3])?;
+
+    let mut union = a.clone();
+    union.union_with(&b)?;
+    assert_eq!(union.iter().collect::<Vec<_>>(), [ids[0], ids[1], ids[3]]);
+    assert_eq!(union.len(), 3);
+
+    let mut intersection = a.clone();
+    intersection.intersect_with(&b)?;
+    assert_eq!(intersection.iter().collect::<Vec<_>>(), [ids[1]]);
+    assert_eq!(intersection.len(), 1);
+
+    assert_eq!(a.clear(ids[0]), Ok(true));
+    assert_eq!(a.clear(ids[0]), Ok(false));
+    assert!(!a.get(ids[0]));
+
+    let values = b.nodes(&tree).map(|n| *n.value()).collect::<Vec<_>>();
+    assert_eq!(values, ["lit", "other"]);
+
+    // Identifiers past the end of the tree are rejected.
+    let large = syntree::tree! {
+        ("lit", 1),
+        ("lit", 1),
+        ("lit", 1),
+        ("lit", 1),
+        ("lit", 1),
+    };
+
+    let out_of_range = large.last().ok_or(anyhow::anyhow!("missing last"))?.id();
+    assert_eq!(a.set(out_of_range), Err(Error::MissingNode(out_of_range)));
+    assert_eq!(a.clear(out_of_range), Err(Error::MissingNode(out_of_range)));
+    assert!(!a.get(out_of_range));
+
+    assert_eq!(
+        a.union_with(&FlagSet::new(&large)),
+        Err(Error::FlagSetMismatch { len: 4, other: 5 })
+    );
+    Ok(())
+}
+
+#[test]
+fn large_ids_are_sparse() -> Result<()> {
+    let large = Id::from_index(u32::MAX as usize - 1).context("bad id")?;
+    let small = Id::from_index(3).context("bad id")?;
+    let medium = Id::from_index(100).context("bad id")?;
+
+    let mut map = IdMap::new();
+    assert_eq!(map.insert(large, 'a'), None);
+    assert_eq!(map.insert(large, 'b'), Some('a'));
+    *map.get_or_insert_with(medium, || 'c') = 'd';
+    assert_eq!(*map.get_or_insert_with(small, || 'e'), 'e');
+    assert_eq!(map.get(large), Some(&'b'));
+    assert_eq!(map.len(), 3);
+
+    let entries = map.iter().map(|(id, c)| (id, *c)).collect::<Vec<_>>();
+    assert_eq!(entries, [(small, 'e'), (medium, 'd'), (large, 'b')]);
+
+    // Maps with the same entries are equal, regardless of how they were
+    // inserted.
+    let other = entries.iter().rev().copied().collect::<IdMap<_>>();
+    assert_eq!(map, other);
+
+    // Growing the map past sparse values keeps them.
+    for index in 4..100 {
+        map.insert(Id::from_index(index).context("bad id")?, 'f');
+    }
+
+    assert_eq!(map.get(medium), Some(&'d'));
+    assert_eq!(map.len(), 99);
+    assert_eq!(map.iter().count(), 99);
+
+    assert_eq!(map.remove(large), Some('b'));
+    assert_eq!(map.remove(large), None);
+    assert_eq!(map.len(), 98);
+
+    let mut set = IdSet::new();
+    assert!(set.insert(large));
+    assert!(!set.insert(large));
+    assert!(set.insert(medium));
+    assert!(set.insert(small));
+    assert!(set.contains(large));
+    assert!(set.iter().eq([small, medium, large]));
+    assert_eq!(set, [large, small, medium].into_iter().collect::<IdSet>());
+
+    assert!(set.remove(large));
+    assert!(!set.remove(large));
+    assert!(!set.contains(large));
+    assert_eq!(set.len(), 2);
+    Ok(())
+}
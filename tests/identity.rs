@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+
+#[test]
+fn equal_siblings_are_distinguishable() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("error", 0),
+            ("error", 0),
+            ("error", 0),
+        }
+    };
+
+    let root = tree.first().context("missing root")?;
+    let nodes = root.children().collect::<Vec<_>>();
+
+    assert!(nodes.windows(2).all(|w| w[0] == w[1] && !w[0].same(&w[1])));
+
+    let mut set = HashSet::new();
+
+    for node in &nodes {
+        assert!(set.insert(node.identity()));
+    }
+
+    for node in root.children() {
+        assert!(!set.insert(node.identity()));
+    }
+
+    assert_eq!(set.len(), 3);
+    assert_eq!(root.children().collect::<HashSet<_>>().len(), 1);
+    Ok(())
+}
+
+#[test]
+fn identity_across_trees() -> Result<()> {
+    let a = syntree::tree!(("lit", 5));
+    let b = a.clone();
+
+    let a = a.first().context("missing lit")?;
+    let b = b.first().context("missing lit")?;
+
+    assert_eq!(a, b);
+    assert_eq!(a.id(), b.id());
+    assert!(!a.same(&b));
+    assert_ne!(a.identity(), b.identity());
+    Ok(())
+}
+
+#[test]
+fn tree_hash_is_structural() -> Result<()> {
+    let a = syntree::tree! { "root" => { ("lit", 1) }, ("ws", 1) };
+    let b = syntree::tree! { "root" => { ("lit", 1) }, ("ws", 1) };
+    let c = syntree::tree! { "root" => { ("lit", 1), ("ws", 1) } };
+
+    let set = HashSet::from([a, b, c]);
+    assert_eq!(set.len(), 2);
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use anyhow::Result;
+use syntree::{Builder, BuilderLimits, CursorPolicy, Error, Limit, Span};
+
+#[test]
+fn insert_before_first() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    let a = tree.token("a", 1)?;
+    tree.token("b", 1)?;
+    let semi = tree.insert_before(a, "semi", Span::point(0))?;
+    tree.close()?;
+
+    let tree = tree.build()?;
+    tree.validate()?;
+
+    let expected = syntree::tree! {
+        "root" => {
+            ("semi", 0),
+            ("a", 1),
+            ("b", 1)
+        }
+    };
+
+    assert_eq!(tree, expected);
+
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    assert_eq!(root.first().map(|n| n.id()), Some(semi));
+    assert_eq!(root.range(), 0..2);
+    Ok(())
+}
+
+#[test]
+fn insert_before_middle() -> Result<()> {
+    let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    tree.open("root")?;
+    tree.token("ident", 2)?;
+    tree.advance(1)?;
+    let ws = tree.token_trivia("ws", 1)?;
+    tree.token("ident", 3)?;
+    tree.insert_before(ws, "semi", Span::new(2, 3))?;
+
+    // The gap before the anchor has been filled by the inserted token.
+    assert_eq!(
+        tree.insert_before(ws, "semi", Span::new(2, 3)),
+        Err(Error::InsertOutOfBounds { start: 2, end: 3 })
+    );
+
+    tree.close()?;
+
+    let tree = tree.build()?;
+    tree.validate()?;
+
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+
+    let values = root.children().map(|n| (*n.value(), n.range()));
+    assert_eq!(
+        values.collect::<Vec<_>>(),
+        [
+            ("ident", 0..2),
+            ("semi", 2..3),
+            ("ws", 3..4),
+            ("ident", 4..7)
+        ]
+    );
+
+    let values = root.children().rev().map(|n| *n.value());
+    assert_eq!(values.collect::<Vec<_>>(), ["ident", "ws", "semi", "ident"]);
+    assert_eq!(root.range(), 0..7);
+    Ok(())
+}
+
+#[test]
+fn insert_before_root() -> Result<()> {
+    let mut tree = Builder::new();
+
+    let a = tree.open("a")?;
+    tree.token("lit", 2)?;
+    tree.close()?;
+    tree.insert_before(a, "bom", Span::point(0))?;
+
+    let tree = tree.build()?;
+    tree.validate()?;
+
+    let expected = syntree::tree! {
+        ("bom", 0),
+        "a" => {
+            ("lit", 2)
+        }
+    };
+
+    assert_eq!(tree, expected);
+    Ok(())
+}
+
+#[test]
+fn insert_before_closed_parent() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    let child = tree.open("child")?;
+    let lit = tree.token("lit", 1)?;
+    tree.close()?;
+
+    assert_eq!(
+        tree.insert_before(lit, "semi", Span::point(0)),
+        Err(Error::InsertBeforeError)
+    );
+
+    tree.open("other")?;
+
+    // The anchor has to be a child of the innermost open node.
+    assert_eq!(
+        tree.insert_before(child, "semi", Span::point(0)),
+        Err(Error::InsertBeforeError)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn insert_before_out_of_bounds() -> Result<()> {
+    let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    tree.advance(1)?;
+    tree.open("root")?;
+    let a = tree.token("a", 1)?;
+
+    assert_eq!(
+        tree.insert_before(a, "semi", Span::new(0, 1)),
+        Err(Error::InsertOutOfBounds { start: 0, end: 1 })
+    );
+
+    assert_eq!(
+        tree.insert_before(a, "semi", Span::new(1, 2)),
+        Err(Error::InsertOutOfBounds { start: 1, end: 2 })
+    );
+
+    let mut span = Span::point(1);
+    span.end = 0;
+
+    assert_eq!(
+        tree.insert_before(a, "semi", span),
+        Err(Error::BackwardsSpan { start: 1, end: 0 })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn insert_before_limits() -> Result<()> {
+    let mut tree = Builder::new().with_limits(BuilderLimits::new().max_children_per_node(2));
+
+    let root = tree.open("root")?;
+    let a = tree.token("a", 1)?;
+    tree.insert_before(a, "semi", Span::point(0))?;
+
+    assert_eq!(
+        tree.insert_before(a, "semi", Span::point(0)),
+        Err(Error::LimitExceeded {
+            limit: Limit::Children,
+            id: root,
+        })
+    );
+
+    tree.close()?;
+    tree.build()?;
+    Ok(())
+}
+
+#[test]
+fn insert_before_then_reuse() -> Result<()> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    b.open("root")?;
+    b.token("a", 1)?;
+    b.token("b", 1)?;
+    b.advance(4)?;
+    let ws = b.token("ws", 1)?;
+
+    // The inserted tokens are stored after the token they precede.
+    b.insert_before(ws, "semi", Span::new(2, 3))?;
+    b.insert_before(ws, "semi", Span::new(3, 4))?;
+    b.insert_before(ws, "semi", Span::new(4, 5))?;
+
+    for value in ["x", "y", "z"] {
+        b.open(value)?;
+        b.token("lit", 1)?;
+        b.close()?;
+    }
+
+    b.close()?;
+
+    let tree = b.build()?;
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+    b.reuse(root)?;
+    let reused = b.build()?;
+
+    assert_eq!(reused, tree);
+    reused.validate()?;
+
+    let end = tree.span().range().end;
+
+    for start in 0..=end {
+        for end in start..=end {
+            assert_eq!(
+                reused.node_with_range(start..end).map(|n| n.id()),
+                tree.node_with_range(start..end).map(|n| n.id()),
+                "{start}..{end}"
+            );
+        }
+    }
+
+    assert_eq!(
+        reused.node_with_range(5..6).map(|n| *n.value()),
+        Some("root")
+    );
+    Ok(())
+}
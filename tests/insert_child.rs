@@ -0,0 +1,148 @@
+use anyhow::Result;
+use syntree::edit::ChangeSet;
+use syntree::Error;
+
+#[test]
+fn insert_child_of_token() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("lit", 1),
+            ("lit", 2),
+        }
+    };
+
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    let lit = root.first().ok_or(anyhow::anyhow!("missing lit"))?;
+
+    let mut change_set = ChangeSet::new();
+    let insertion = change_set.insert_child(lit.id(), 0, syntree::tree!(("a", 3)))?;
+    change_set.set_text(insertion, "abc");
+
+    assert_eq!(
+        change_set.modify(&tree),
+        Err(Error::InsertChildOfToken(lit.id()))
+    );
+
+    assert_eq!(
+        change_set.text_edits(&tree),
+        Err(Error::InsertChildOfToken(lit.id()))
+    );
+
+    // Inserting the same material as a child of the node is fine.
+    let mut change_set = ChangeSet::new();
+    change_set.insert_child(root.id(), 1, syntree::tree!(("a", 3)))?;
+
+    assert_eq!(
+        change_set.modify(&tree)?,
+        syntree::tree! {
+            "root" => {
+                ("lit", 1),
+                ("a", 3),
+                ("lit", 2),
+            }
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn insert_inside_of_removed_subtree() -> Result<()> {
+    let source = "ab c";
+
+    let tree = syntree::tree! {
+        "root" => {
+            "pair" => {
+                ("a", 1),
+                ("b", 1),
+            },
+            ("ws", 1),
+            ("c", 1),
+        }
+    };
+
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    let pair = root.first().ok_or(anyhow::anyhow!("missing pair"))?;
+    let a = pair.first().ok_or(anyhow::anyhow!("missing a"))?;
+    let b = pair.last().ok_or(anyhow::anyhow!("missing b"))?;
+
+    for replace in [false, true] {
+        for anchor in [a.id(), b.id(), pair.id()] {
+            let mut change_set = ChangeSet::new();
+
+            if replace {
+                let replacement = change_set.replace(pair.id(), syntree::tree!(("y", 1)))?;
+                change_set.set_text(replacement, "y");
+            } else {
+                change_set.remove(pair.id());
+            }
+
+            let material = syntree::tree!(("x", 1));
+
+            let insertion = if anchor == a.id() {
+                change_set.insert_before(anchor, material)?
+            } else if anchor == b.id() {
+                change_set.insert_after(anchor, material)?
+            } else {
+                change_set.insert_child(anchor, 0, material)?
+            };
+
+            change_set.set_text(insertion, "x");
+
+            let expected = Error::ConflictingChange(anchor);
+            assert_eq!(change_set.text_edits(&tree), Err(expected));
+            assert_eq!(change_set.apply_to_source(&tree, source), Err(expected));
+            assert_eq!(change_set.modify(&tree), Err(expected));
+        }
+    }
+
+    // Insertions next to the removed subtree are kept by both.
+    let mut change_set = ChangeSet::new();
+    change_set.remove(pair.id());
+    let insertion = change_set.insert_before(pair.id(), syntree::tree!(("x", 1)))?;
+    change_set.set_text(insertion, "x");
+
+    assert_eq!(change_set.apply_to_source(&tree, source)?, "x c");
+    assert_eq!(
+        change_set.modify(&tree)?,
+        syntree::tree! {
+            "root" => {
+                ("x", 1),
+                ("ws", 1),
+                ("c", 1),
+            }
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn remove_inside_of_replaced_subtree() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("a", 1),
+        }
+    };
+
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    let a = root.first().ok_or(anyhow::anyhow!("missing a"))?;
+
+    // Without any text attached to the replacement.
+    let mut change_set = ChangeSet::new();
+    change_set.replace(root.id(), syntree::tree!(("b", 0)))?;
+    change_set.remove(a.id());
+
+    let expected = Error::ConflictingChange(a.id());
+    assert_eq!(change_set.text_edits(&tree), Err(expected));
+    assert_eq!(change_set.modify(&tree), Err(expected));
+
+    // Nested removals are fine for both.
+    let mut change_set = ChangeSet::new();
+    change_set.remove(root.id());
+    change_set.remove(a.id());
+
+    assert_eq!(change_set.text_edits(&tree)?, [(0..1, "")]);
+    assert!(change_set.modify(&tree)?.is_empty());
+    Ok(())
+}
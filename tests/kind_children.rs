@@ -0,0 +1,111 @@
+use anyhow::Result;
+use syntree::{Kind, Node, Span, Tree};
+
+fn fixture() -> Tree<&'static str, Span> {
+    syntree::tree! {
+        "root" => {
+            "tokens" => {
+                ("a", 1),
+                ("b", 1),
+                ("c", 1),
+            },
+            "nodes" => {
+                "a" => {},
+                "b" => {
+                    ("lit", 1),
+                },
+                "c" => {},
+            },
+            "mixed" => {
+                ("open", 1),
+                ("ws", 1),
+                "a" => {},
+                ("comma", 1),
+                "b" => {},
+                ("ws", 1),
+                ("close", 1),
+            },
+            "empty" => {},
+            ("eof", 0),
+        }
+    }
+}
+
+/// Find the child of the given kind through the children iterator.
+fn expected<'a>(
+    node: Node<'a, &'static str, Span>,
+    kind: Kind,
+    rev: bool,
+) -> Option<Node<'a, &'static str, Span>> {
+    if rev {
+        node.children().rev().find(|n| n.kind() == kind)
+    } else {
+        node.children().find(|n| n.kind() == kind)
+    }
+}
+
+#[test]
+fn kind_children_match_iterators() {
+    let tree = fixture();
+
+    for node in tree.walk() {
+        let cases = [
+            (node.first_node_child(), Kind::Node, false),
+            (node.last_node_child(), Kind::Node, true),
+            (node.first_token_child(), Kind::Token, false),
+            (node.last_token_child(), Kind::Token, true),
+        ];
+
+        for (actual, kind, rev) in cases {
+            let expected = expected(node, kind, rev);
+            assert_eq!(actual.map(|n| n.id()), expected.map(|n| n.id()));
+        }
+    }
+}
+
+#[test]
+fn kind_children() -> Result<()> {
+    let tree = fixture();
+    let find = |value| {
+        tree.walk()
+            .find(|n| *n.value() == value)
+            .ok_or(anyhow::anyhow!("missing {value}"))
+    };
+    let get = |node: Option<Node<'_, &'static str, Span>>| node.map(|n| (*n.value(), n.range()));
+
+    // All tokens.
+    let tokens = find("tokens")?;
+    assert_eq!(tokens.first_node_child(), None);
+    assert_eq!(tokens.last_node_child(), None);
+    assert_eq!(get(tokens.first_token_child()), Some(("a", 0..1)));
+    assert_eq!(get(tokens.last_token_child()), Some(("c", 2..3)));
+
+    // All nodes.
+    let nodes = find("nodes")?;
+    assert_eq!(get(nodes.first_node_child()), Some(("a", 3..3)));
+    assert_eq!(get(nodes.last_node_child()), Some(("c", 4..4)));
+    assert_eq!(nodes.first_token_child(), None);
+    assert_eq!(nodes.last_token_child(), None);
+
+    // Mixed.
+    let mixed = find("mixed")?;
+    assert_eq!(get(mixed.first_node_child()), Some(("a", 6..6)));
+    assert_eq!(get(mixed.last_node_child()), Some(("b", 7..7)));
+    assert_eq!(get(mixed.first_token_child()), Some(("open", 4..5)));
+    assert_eq!(get(mixed.last_token_child()), Some(("close", 8..9)));
+
+    // No children at all, and tokens.
+    for value in ["empty", "eof"] {
+        let node = find(value)?;
+        assert_eq!(node.first_node_child(), None);
+        assert_eq!(node.last_node_child(), None);
+        assert_eq!(node.first_token_child(), None);
+        assert_eq!(node.last_token_child(), None);
+    }
+
+    let root = find("root")?;
+    assert_eq!(root.first_node_child(), Some(find("tokens")?));
+    assert_eq!(root.last_node_child(), Some(find("empty")?));
+    assert_eq!(get(root.last_token_child()), Some(("eof", 9..9)));
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use syntree::{print, sexpr, KindName, Span, Tree};
+
+/// A kind whose debug representation differs from its kind name, so that
+/// output which accidentally uses `Debug` is detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    FileRoot,
+    NumberLiteral,
+    Blank,
+}
+
+syntree::kind_names! {
+    Syntax {
+        FileRoot => "root",
+        NumberLiteral => "number",
+        Blank => "ws",
+    }
+}
+
+use Syntax::{Blank, FileRoot, NumberLiteral};
+
+const SOURCE: &str = "12 3";
+
+fn tree() -> Result<Tree<Syntax, Span>> {
+    Ok(syntree::tree! {
+        FileRoot => {
+            (NumberLiteral, 2),
+            (Blank, 1),
+            (NumberLiteral, 1),
+        }
+    })
+}
+
+#[test]
+fn kind_names() {
+    assert_eq!(Blank.kind_name(), "ws");
+    assert_eq!(format!("{:?}", Blank), "Blank");
+    assert_eq!(Syntax::from_kind_name("number"), Some(NumberLiteral));
+    assert_eq!(Syntax::from_kind_name("NumberLiteral"), None);
+}
+
+#[test]
+fn print_named() -> Result<()> {
+    let tree = tree()?;
+
+    let mut out = Vec::new();
+    print::print_named(&mut out, &tree)?;
+    assert_eq!(
+        String::from_utf8(out)?,
+        "root@0..4\n  number@0..2 +\n  ws@2..3 +\n  number@3..4 +\n"
+    );
+
+    let mut out = Vec::new();
+    print::print_named_with_source(&mut out, &tree, SOURCE)?;
+    assert_eq!(
+        String::from_utf8(out)?,
+        "root@0..4\n  number@0..2 \"12\"\n  ws@2..3 \" \"\n  number@3..4 \"3\"\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn dot_named() -> Result<()> {
+    let tree = tree()?;
+
+    let mut out = Vec::new();
+    print::to_dot_named(&mut out, &tree)?;
+    let out = String::from_utf8(out)?;
+
+    assert!(!out.contains("FileRoot"));
+    assert!(out.contains(r#"0 [label="root@0..4", shape=box];"#));
+    assert!(out.contains(r#"2 [label="ws@2..3", shape=ellipse];"#));
+    Ok(())
+}
+
+#[test]
+fn sexpr_named_roundtrip() -> Result<()> {
+    let tree = tree()?;
+
+    let mut out = Vec::new();
+    print::to_sexpr_named(&mut out, &tree, SOURCE)?;
+    let out = String::from_utf8(out)?;
+
+    assert_eq!(out, r#"(root (number "12") (ws " ") (number "3"))"#);
+    assert_eq!(sexpr::from_sexpr_named::<Syntax>(&out)?, tree);
+
+    let error = sexpr::from_sexpr_named::<Syntax>(r#"(FileRoot)"#).unwrap_err();
+    assert_eq!(
+        *error.kind(),
+        sexpr::ParseErrorKind::UnknownLabel("FileRoot".into())
+    );
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_named() -> Result<()> {
+    use syntree::OwnedEvent;
+
+    let tree = tree()?;
+
+    let mut out = Vec::new();
+    tree.write_events_named(&mut serde_json::Serializer::new(&mut out))?;
+
+    let events: Vec<OwnedEvent<String, Span>> = serde_json::from_slice(&out)?;
+    let names = events
+        .iter()
+        .map(|e| match e {
+            OwnedEvent::Enter(name, _) | OwnedEvent::Token(name, _) => name.as_str(),
+            OwnedEvent::Leave => ")",
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, ["root", "number", "ws", "number", ")"]);
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+
+#[test]
+fn remaining_siblings_are_tokens() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("open", 1),
+            "a" => {
+                ("lit", 1),
+            },
+            "b" => {},
+            ("comma", 1),
+            ("ws", 1),
+            ("close", 1),
+        }
+    };
+
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    let open = root.first().ok_or(anyhow::anyhow!("missing open"))?;
+    let a = open
+        .next_node_sibling()
+        .ok_or(anyhow::anyhow!("missing a"))?;
+    assert_eq!(*a.value(), "a");
+    let b = a.next_node_sibling().ok_or(anyhow::anyhow!("missing b"))?;
+    assert_eq!(*b.value(), "b");
+
+    // Everything after `b` is a token.
+    assert!(b.next_node_sibling().is_none());
+
+    let tokens = std::iter::successors(b.next_token_sibling(), |n| n.next_token_sibling());
+    let tokens = tokens.map(|n| *n.value()).collect::<Vec<_>>();
+    assert_eq!(tokens, ["comma", "ws", "close"]);
+
+    let close = root.last().ok_or(anyhow::anyhow!("missing close"))?;
+    assert!(close.next_token_sibling().is_none());
+    assert!(close.next_node_sibling().is_none());
+    assert_eq!(close.prev_node_sibling(), Some(b));
+
+    let tokens = std::iter::successors(close.prev_token_sibling(), |n| n.prev_token_sibling());
+    let tokens = tokens.map(|n| *n.value()).collect::<Vec<_>>();
+    assert_eq!(tokens, ["ws", "comma", "open"]);
+
+    // The node itself is never included, unlike with `Siblings`.
+    assert_eq!(a.siblings().next_node(), Some(a));
+    assert_eq!(a.next_node_sibling(), Some(b));
+    assert_eq!(b.prev_node_sibling(), Some(a));
+    assert!(a.prev_node_sibling().is_none());
+
+    // Siblings of children don't escape their parent.
+    let lit = a.first().ok_or(anyhow::anyhow!("missing lit"))?;
+    assert!(lit.next_token_sibling().is_none());
+    assert!(lit.prev_token_sibling().is_none());
+    Ok(())
+}
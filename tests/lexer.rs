@@ -0,0 +1,115 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use syntree::{Builder, CursorPolicy, Error, Span, Tree};
+
+/// Collect the ranges of all nodes in the tree in walk order.
+fn ranges(tree: &Tree<&'static str, Span>) -> Vec<(&'static str, Range<usize>)> {
+    tree.walk().map(|n| (*n.value(), n.range())).collect()
+}
+
+#[test]
+fn lexer_contiguous() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    tree.token("a", 1)?;
+    tree.extend_from_lexer([("b", 1..3), ("c", 3..3), ("d", 3..4)])?;
+    tree.close()?;
+    tree.extend_from_lexer([("e", 4..5)])?;
+
+    let tree = tree.build()?;
+
+    assert_eq!(
+        ranges(&tree),
+        [
+            ("root", 0..4),
+            ("a", 0..1),
+            ("b", 1..3),
+            ("c", 3..3),
+            ("d", 3..4),
+            ("e", 4..5)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lexer_gaps() -> Result<()> {
+    let tokens = [("a", 1..2), ("b", 2..3), ("c", 5..6)];
+
+    // Gaps are subject to the cursor policy.
+    let mut tree = Builder::new();
+    assert_eq!(
+        tree.extend_from_lexer(tokens.clone()),
+        Err(Error::CursorGap {
+            cursor: 0,
+            start: 1
+        })
+    );
+
+    let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+    tree.extend_from_lexer(tokens.clone())?;
+    let tree = tree.build()?;
+    assert_eq!(ranges(&tree), [("a", 1..2), ("b", 2..3), ("c", 5..6)]);
+
+    // Unless they are filled.
+    let mut tree = Builder::new();
+    tree.extend_from_lexer_with_gaps(tokens, "gap")?;
+    let tree = tree.build()?;
+
+    assert_eq!(
+        ranges(&tree),
+        [
+            ("gap", 0..1),
+            ("a", 1..2),
+            ("b", 2..3),
+            ("gap", 3..5),
+            ("c", 5..6)
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::reversed_empty_ranges)]
+fn lexer_invalid_ranges() -> Result<()> {
+    // Overlapping.
+    let mut tree = Builder::new();
+    assert_eq!(
+        tree.extend_from_lexer([("a", 0..2), ("b", 1..3)]),
+        Err(Error::LexerRange {
+            index: 1,
+            start: 1,
+            end: 3
+        })
+    );
+
+    // Backwards.
+    let mut tree = Builder::new();
+    assert_eq!(
+        tree.extend_from_lexer([("a", 0..2), ("b", 2..4), ("c", 5..4)]),
+        Err(Error::LexerRange {
+            index: 2,
+            start: 5,
+            end: 4
+        })
+    );
+
+    // Before what has already been added, where the index is relative to the
+    // start of the lexer.
+    let mut tree = Builder::new();
+    tree.token("a", 4)?;
+    assert_eq!(
+        tree.extend_from_lexer_with_gaps([("b", 3..5)], "gap"),
+        Err(Error::LexerRange {
+            index: 0,
+            start: 3,
+            end: 5
+        })
+    );
+
+    Ok(())
+}
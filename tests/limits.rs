@@ -0,0 +1,218 @@
+use anyhow::Result;
+use syntree::{Builder, BuilderLimits, Error, Limit};
+
+fn exceeded(limit: Limit, id: syntree::Id) -> Error {
+    Error::LimitExceeded { limit, id }
+}
+
+#[test]
+fn max_nodes() -> Result<()> {
+    let mut b = Builder::new().with_limits(BuilderLimits::new().max_nodes(3));
+
+    b.open("root")?;
+    b.token("lit", 1)?;
+    let c = b.checkpoint()?;
+    b.token("lit", 1)?;
+
+    let Err(Error::LimitExceeded { limit, .. }) = b.token("lit", 1) else {
+        panic!("expected limit to be exceeded");
+    };
+
+    assert_eq!(limit, Limit::Nodes);
+    assert!(matches!(
+        b.open("child"),
+        Err(Error::LimitExceeded {
+            limit: Limit::Nodes,
+            ..
+        })
+    ));
+    assert!(matches!(
+        b.close_at(&c, "wrap"),
+        Err(Error::LimitExceeded {
+            limit: Limit::Nodes,
+            ..
+        })
+    ));
+
+    b.close()?;
+    let tree = b.build()?;
+
+    let expected = syntree::tree! {
+        "root" => {
+            ("lit", 1),
+            ("lit", 1),
+        }
+    };
+
+    assert_eq!(tree, expected);
+    Ok(())
+}
+
+#[test]
+fn max_depth() -> Result<()> {
+    let mut b = Builder::new().with_limits(BuilderLimits::new().max_depth(3));
+
+    b.open("a")?;
+    b.open("b")?;
+    b.token("lit", 1)?;
+    let c = b.open("c")?;
+
+    let Err(error) = b.token("lit", 1) else {
+        panic!("expected limit to be exceeded");
+    };
+
+    let Error::LimitExceeded { limit, id } = error else {
+        panic!("expected limit error");
+    };
+
+    assert_eq!(limit, Limit::Depth);
+    assert_ne!(id, c);
+
+    b.close()?;
+    b.close()?;
+    b.close()?;
+    b.build()?;
+    Ok(())
+}
+
+#[test]
+fn max_depth_close_at() -> Result<()> {
+    let mut b = Builder::new().with_limits(BuilderLimits::new().max_depth(3));
+
+    b.open("a")?;
+    let c = b.checkpoint()?;
+    b.token("lit", 1)?;
+
+    // Wrapping a token below "a" results in a depth of exactly three.
+    b.close_at(&c, "b")?;
+
+    let c = b.checkpoint()?;
+    b.open("b")?;
+    b.token("lit", 1)?;
+    b.close()?;
+    b.token("lit", 1)?;
+
+    // Wrapping a node which has children would result in a depth of four.
+    let Err(Error::LimitExceeded { limit, .. }) = b.close_at(&c, "wrap") else {
+        panic!("expected limit to be exceeded");
+    };
+
+    assert_eq!(limit, Limit::Depth);
+
+    // Nothing was changed by the failed operation.
+    b.close()?;
+    let tree = b.build()?;
+
+    let expected = syntree::tree! {
+        "a" => {
+            "b" => {
+                ("lit", 1),
+            },
+            "b" => {
+                ("lit", 1),
+            },
+            ("lit", 1),
+        }
+    };
+
+    assert_eq!(tree, expected);
+    Ok(())
+}
+
+#[test]
+fn max_depth_reopen() -> Result<()> {
+    let mut b = Builder::new().with_limits(BuilderLimits::new().max_depth(3));
+
+    b.open("a")?;
+    let child = b.open("b")?;
+    b.close()?;
+    b.reopen(child)?;
+    b.open("c")?;
+
+    assert!(matches!(
+        b.token("lit", 1),
+        Err(Error::LimitExceeded {
+            limit: Limit::Depth,
+            ..
+        })
+    ));
+
+    b.close()?;
+    b.close()?;
+    b.close()?;
+    b.build()?;
+    Ok(())
+}
+
+#[test]
+fn max_children() -> Result<()> {
+    let mut b = Builder::new().with_limits(BuilderLimits::new().max_children_per_node(2));
+
+    // Roots are not limited.
+    b.token("lit", 1)?;
+    b.token("lit", 1)?;
+    b.token("lit", 1)?;
+
+    let root = b.open("root")?;
+    b.token("lit", 1)?;
+    b.open("child")?;
+    b.close()?;
+
+    assert_eq!(b.token("lit", 1), Err(exceeded(Limit::Children, root)));
+    assert_eq!(b.open("child"), Err(exceeded(Limit::Children, root)));
+
+    b.close()?;
+    b.build()?;
+    Ok(())
+}
+
+#[test]
+fn max_children_close_at() -> Result<()> {
+    let mut b = Builder::new().with_limits(BuilderLimits::new().max_children_per_node(2));
+
+    let root = b.open("root")?;
+    b.token("lit", 1)?;
+    let c = b.checkpoint()?;
+    b.token("lit", 1)?;
+
+    // Only the token after the checkpoint is wrapped, so the root still has
+    // exactly two children.
+    b.close_at(&c, "wrap")?;
+    b.close_at(&c, "wrap")?;
+
+    assert_eq!(b.token("lit", 1), Err(exceeded(Limit::Children, root)));
+
+    b.close()?;
+    let tree = b.build()?;
+
+    let expected = syntree::tree! {
+        "root" => {
+            ("lit", 1),
+            "wrap" => {
+                "wrap" => {
+                    ("lit", 1),
+                },
+            },
+        }
+    };
+
+    assert_eq!(tree, expected);
+    Ok(())
+}
+
+#[test]
+fn unlimited() -> Result<()> {
+    let mut b = Builder::new().with_limits(BuilderLimits::default());
+
+    for _ in 0..64 {
+        b.open("node")?;
+        b.token("lit", 1)?;
+    }
+
+    for _ in 0..64 {
+        b.close()?;
+    }
+
+    assert_eq!(b.build()?.len(), 128);
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use syntree::span::{Index, TreeIndex, TreeSpan};
+use syntree::{Builder, CursorPolicy, Span, Tree};
+
+/// A span which also tracks the file it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileSpan {
+    file: u32,
+    start: Index,
+    end: Index,
+}
+
+impl TreeSpan for FileSpan {
+    const EMPTY: Self = FileSpan {
+        file: 0,
+        start: 0,
+        end: 0,
+    };
+    const INDEXES: Self::Indexes = Vec::new();
+
+    type Length = usize;
+    type Indexes = Vec<TreeIndex>;
+
+    fn point(index: Index) -> Self {
+        FileSpan::new(index, index)
+    }
+
+    fn new(start: Index, end: Index) -> Self {
+        FileSpan {
+            file: 0,
+            start,
+            end,
+        }
+    }
+
+    fn start(&self) -> Index {
+        self.start
+    }
+
+    fn end(&self) -> Index {
+        self.end
+    }
+
+    fn set_end(&mut self, end: Index) {
+        self.end = end;
+    }
+
+    fn length(&self) -> usize {
+        self.range().len()
+    }
+
+    fn fmt_span(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}..{}", self.file, self.start, self.end)
+    }
+}
+
+fn build(policy: CursorPolicy) -> Result<Tree<&'static str, Span>> {
+    let mut b = Builder::new().with_policy(policy);
+
+    let c = b.checkpoint()?;
+    b.open("number")?;
+    b.token("lit", 2)?;
+    b.close()?;
+    b.token("ws", 1)?;
+    b.open("empty")?;
+    b.close()?;
+    b.open("ident")?;
+    b.token("lit", 3)?;
+    b.close()?;
+    b.close_at(&c, "root")?;
+    b.token("eof", 0)?;
+    Ok(b.build()?)
+}
+
+#[test]
+fn map_spans_preserves_ids() -> Result<()> {
+    let tree = build(CursorPolicy::Contiguous)?;
+    let before = tree
+        .walk()
+        .map(|n| (n.id(), *n.value(), n.kind(), n.range()))
+        .collect::<Vec<_>>();
+
+    let mapped = tree.clone().map_spans(|s| FileSpan {
+        file: 7,
+        start: s.start,
+        end: s.end,
+    });
+
+    let after = mapped
+        .walk()
+        .map(|n| (n.id(), *n.value(), n.kind(), n.span().range()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(before, after);
+    assert!(mapped.walk().all(|n| n.span().file == 7));
+    assert_eq!(mapped.span().range(), tree.span().range());
+
+    for (id, ..) in before {
+        let a = tree.get(id).ok_or(anyhow!("missing node"))?;
+        let b = mapped.get(id).ok_or(anyhow!("missing node"))?;
+        assert_eq!(a.value(), b.value());
+        assert_eq!(a.parent().map(|n| n.id()), b.parent().map(|n| n.id()));
+    }
+
+    let mut s = Vec::new();
+    syntree::print::print(&mut s, &mapped)?;
+    assert!(String::from_utf8(s)?.starts_with("\"root\"@7:0..6\n"));
+    Ok(())
+}
+
+#[test]
+fn map_spans_range_queries() -> Result<()> {
+    let tree = build(CursorPolicy::Contiguous)?;
+    let shifted = tree
+        .clone()
+        .map_spans(|s| Span::new(s.start + 100, s.end + 100));
+
+    for start in 0..7 {
+        for end in start..7 {
+            let expected = tree.node_with_range(start..end).map(|n| n.id());
+            let actual = shifted
+                .node_with_range(start + 100..end + 100)
+                .map(|n| n.id());
+            assert_eq!(actual, expected, "{start}..{end}");
+
+            let expected = tree
+                .nodes_with_range(start..end)
+                .map(|n| n.id())
+                .collect::<Vec<_>>();
+            let actual = shifted
+                .nodes_with_range(start + 100..end + 100)
+                .map(|n| n.id())
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{start}..{end}");
+        }
+    }
+
+    // Identity mappings produce equal trees.
+    assert_eq!(tree.clone().map_spans(|s| s), tree);
+    assert_eq!(tree.clone().cast_spans::<Span>(), tree);
+    Ok(())
+}
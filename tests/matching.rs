@@ -0,0 +1,120 @@
+use anyhow::{anyhow, Result};
+use syntree::node::{Children, Matching, Walk};
+use syntree::{Node, Span};
+
+type Value = &'static str;
+type Predicate = fn(&Value) -> bool;
+
+fn is_param(value: &Value) -> bool {
+    *value == "param"
+}
+
+struct Params<'a> {
+    iter: Matching<Children<'a, Value, Span>, Predicate>,
+}
+
+impl<'a> Iterator for Params<'a> {
+    type Item = Node<'a, Value, Span>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+#[test]
+fn children_matching() -> Result<()> {
+    let tree = syntree::tree! {
+        "fn" => {
+            ("ident", 3),
+            "param" => {
+                ("ident", 1),
+            },
+            ("comma", 1),
+            "param" => {
+                ("ident", 2),
+            },
+        }
+    };
+
+    let root = tree.first().ok_or(anyhow!("missing root"))?;
+
+    let params = Params {
+        iter: root.children().matching(is_param),
+    };
+
+    let ranges = params.map(|n| n.range()).collect::<Vec<_>>();
+    assert_eq!(ranges, [3..4, 5..7]);
+
+    let mut it = root.children().matching(|v| *v == "param");
+    assert_eq!(it.size_hint(), (0, Some(4)));
+    assert_eq!(it.next_back().map(|n| n.range()), Some(5..7));
+
+    // Clones continue from the same position.
+    let mut cloned = it.clone();
+    assert_eq!(it.next().map(|n| n.range()), Some(3..4));
+    assert_eq!(cloned.next().map(|n| n.range()), Some(3..4));
+    assert!(it.next().is_none());
+    assert!(it.next().is_none());
+    assert!(cloned.next_back().is_none());
+
+    assert_eq!(root.children_with_value(&"param").count(), 2);
+    assert_eq!(root.children_with_value(&"ident").count(), 1);
+    assert_eq!(root.children_with_value(&"missing").count(), 0);
+
+    let values = root
+        .children_with_value(&"comma")
+        .rev()
+        .map(|n| *n.value())
+        .collect::<Vec<_>>();
+    assert_eq!(values, ["comma"]);
+    Ok(())
+}
+
+#[test]
+fn walk_matching() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            "number" => {
+                ("lit", 1),
+            },
+            ("ws", 1),
+            "number" => {
+                ("lit", 2),
+            },
+        },
+        ("lit", 3),
+    };
+
+    let ranges = tree
+        .walk()
+        .matching(|v| *v == "lit")
+        .map(|n| n.range())
+        .collect::<Vec<_>>();
+    assert_eq!(ranges, [0..1, 2..4, 4..7]);
+
+    let root = tree.first().ok_or(anyhow!("missing root"))?;
+    assert_eq!(root.walk().matching(|v| *v == "number").count(), 2);
+
+    // Predicates can keep state.
+    let mut seen = 0;
+    let count = tree
+        .walk()
+        .matching(|_| {
+            seen += 1;
+            seen % 2 == 0
+        })
+        .count();
+    assert_eq!(count, 3);
+    assert_eq!(seen, tree.len());
+    Ok(())
+}
+
+#[test]
+fn matching_default() {
+    let mut it = Matching::<Walk<'_, u32, Span>, fn(&u32) -> bool>::default();
+    assert_eq!(it.size_hint(), (0, Some(0)));
+    assert!(it.next().is_none());
+
+    let mut it = Matching::<Children<'_, u32, Span>, fn(&u32) -> bool>::empty();
+    assert!(it.next_back().is_none());
+}
@@ -0,0 +1,81 @@
+use anyhow::{anyhow, Result};
+
+#[test]
+fn same_span_chain() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("ws", 4),
+            "paren" => {
+                "expr" => {
+                    "literal" => {
+                        ("number", 5),
+                    },
+                },
+            },
+            ("ws", 1),
+        },
+    };
+
+    let values = |it: syntree::node::NodesWithRange<'_, &'static str, _>| {
+        it.map(|n| *n.value()).collect::<Vec<_>>()
+    };
+
+    // The singular lookup returns the innermost node, but never a token.
+    let node = tree.node_with_range(4..9).ok_or(anyhow!("missing node"))?;
+    assert_eq!(*node.value(), "literal");
+
+    assert_eq!(
+        values(tree.nodes_with_range(4..9)),
+        ["paren", "expr", "literal", "number"]
+    );
+    assert_eq!(values(tree.nodes_with_range(0..10)), ["root"]);
+    assert_eq!(values(tree.nodes_with_range(0..4)), ["ws"]);
+    assert!(values(tree.nodes_with_range(4..8)).is_empty());
+    assert!(values(tree.nodes_with_range(3..9)).is_empty());
+    assert!(values(tree.nodes_with_range(20..30)).is_empty());
+
+    let root = tree.first().ok_or(anyhow!("missing root"))?;
+    let paren = root.children().nth(1).ok_or(anyhow!("missing paren"))?;
+    let expr = paren.first().ok_or(anyhow!("missing expr"))?;
+
+    assert_eq!(
+        values(root.nodes_with_range(4..9)),
+        ["paren", "expr", "literal", "number"]
+    );
+    assert_eq!(
+        values(expr.nodes_with_range(4..9)),
+        ["expr", "literal", "number"]
+    );
+    assert!(values(expr.nodes_with_range(0..4)).is_empty());
+    Ok(())
+}
+
+#[test]
+fn same_span_chain_of_tokens() -> Result<()> {
+    let tree = syntree::tree! {
+        "paren" => {
+            "expr" => {
+                ("literal", 5),
+            },
+        },
+        "empty" => {},
+    };
+
+    // With a token at the bottom of the chain, the singular lookup stops at
+    // its parent.
+    let node = tree.node_with_range(0..5).ok_or(anyhow!("missing node"))?;
+    assert_eq!(*node.value(), "expr");
+
+    let nodes = tree
+        .nodes_with_range(0..5)
+        .map(|n| *n.value())
+        .collect::<Vec<_>>();
+    assert_eq!(nodes, ["paren", "expr", "literal"]);
+
+    let nodes = tree
+        .nodes_with_range(5..5)
+        .map(|n| *n.value())
+        .collect::<Vec<_>>();
+    assert_eq!(nodes, ["empty"]);
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+
+#[test]
+fn skip_and_only_tokens_partition() -> Result<()> {
+    let tree = syntree::tree! {
+        ("t1", 1),
+        "n1" => {
+            ("t2", 1),
+            "n2" => {},
+        },
+        ("t3", 1),
+        "n3" => {},
+        ("t4", 1),
+    };
+
+    let nodes = tree.walk().skip_tokens().map(|n| *n.value());
+    assert_eq!(nodes.collect::<Vec<_>>(), ["n1", "n2", "n3"]);
+
+    let tokens = tree.walk().only_tokens().map(|n| *n.value());
+    assert_eq!(tokens.collect::<Vec<_>>(), ["t1", "t2", "t3", "t4"]);
+
+    // Folding goes through the wrapped iterator.
+    let count = tree.walk().only_tokens().fold(0, |n, _| n + 1);
+    assert_eq!(count, 4);
+
+    // Children are double-ended, so both adapters are too.
+    let nodes = tree.children().skip_tokens().rev().map(|n| *n.value());
+    assert_eq!(nodes.collect::<Vec<_>>(), ["n3", "n1"]);
+
+    let tokens = tree.children().only_tokens().rev().map(|n| *n.value());
+    assert_eq!(tokens.collect::<Vec<_>>(), ["t4", "t3", "t1"]);
+
+    let tokens = tree
+        .children()
+        .only_tokens()
+        .rfold(Vec::new(), |mut out, n| {
+            out.push(*n.value());
+            out
+        });
+    assert_eq!(tokens, ["t4", "t3", "t1"]);
+
+    let first = tree.first().ok_or(anyhow::anyhow!("missing first"))?;
+    let tokens = first.siblings().only_tokens().map(|n| *n.value());
+    assert_eq!(tokens.collect::<Vec<_>>(), ["t1", "t3", "t4"]);
+
+    // Switching between node-only and mixed iteration.
+    let mut it = tree.children().skip_tokens();
+    assert_eq!(it.next().map(|n| *n.value()), Some("n1"));
+    let mut it = it.into_inner().only_tokens();
+    assert_eq!(it.next().map(|n| *n.value()), Some("t3"));
+    let rest = it.into_inner().map(|n| *n.value());
+    assert_eq!(rest.collect::<Vec<_>>(), ["n3", "t4"]);
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use anyhow::{bail, Result};
+use syntree::{Builder, Tree};
+
+type Input<'a> = Peekable<Chars<'a>>;
+
+/// Skip over unexpected input, where recovery depends on what we're inside
+/// of. Inside of an argument list we skip to the closing parenthesis,
+/// anywhere else to the end of the statement.
+fn recover(b: &mut Builder<&'static str>, it: &mut Input<'_>) -> Result<()> {
+    let stop: &[char] = match b.current() {
+        Some((&"args", _)) => &[')'],
+        _ => &[';', '}'],
+    };
+
+    let mut len = 0;
+
+    while it.next_if(|c| !stop.contains(c)).is_some() {
+        len += 1;
+    }
+
+    if len > 0 {
+        b.error_token("error", len)?;
+    }
+
+    Ok(())
+}
+
+fn expect(b: &mut Builder<&'static str>, it: &mut Input<'_>, c: char) -> Result<()> {
+    if it.next_if_eq(&c).is_none() {
+        bail!("expected {c:?}");
+    }
+
+    b.token("punct", 1)?;
+    Ok(())
+}
+
+fn call(b: &mut Builder<&'static str>, it: &mut Input<'_>) -> Result<()> {
+    b.open("call")?;
+    expect(b, it, '(')?;
+    b.open("args")?;
+
+    loop {
+        match it.peek() {
+            Some(c) if c.is_alphabetic() => {
+                it.next();
+                b.token("ident", 1)?;
+            }
+            Some(',') => expect(b, it, ',')?,
+            Some(')') | None => break,
+            Some(_) => recover(b, it)?,
+        }
+    }
+
+    b.close()?;
+    expect(b, it, ')')?;
+    b.close()?;
+    Ok(())
+}
+
+fn stmt(b: &mut Builder<&'static str>, it: &mut Input<'_>) -> Result<()> {
+    b.open("stmt")?;
+    it.next();
+    b.token("ident", 1)?;
+
+    if it.peek() == Some(&'(') {
+        call(b, it)?;
+    }
+
+    if it.peek() != Some(&';') {
+        recover(b, it)?;
+    }
+
+    expect(b, it, ';')?;
+    b.close()?;
+    Ok(())
+}
+
+fn block(b: &mut Builder<&'static str>, it: &mut Input<'_>) -> Result<()> {
+    b.open("block")?;
+    expect(b, it, '{')?;
+
+    loop {
+        match it.peek() {
+            Some('}') | None => break,
+            Some(c) if c.is_alphabetic() => stmt(b, it)?,
+            Some(_) => {
+                recover(b, it)?;
+                expect(b, it, ';')?;
+            }
+        }
+    }
+
+    expect(b, it, '}')?;
+    b.close()?;
+    Ok(())
+}
+
+fn parse(source: &str) -> Result<Tree<&'static str>> {
+    let mut b = Builder::new();
+    block(&mut b, &mut source.chars().peekable())?;
+    assert_eq!(b.depth(), 0);
+    Ok(b.build()?)
+}
+
+/// Collect the ranges of error tokens and the nodes they were recovered in.
+fn errors(tree: &Tree<&'static str>) -> Vec<(std::ops::Range<usize>, Vec<&'static str>)> {
+    tree.walk()
+        .filter(|n| n.is_error())
+        .map(|n| {
+            let path = n.ancestors().skip(1).map(|n| *n.value()).collect();
+            (n.range(), path)
+        })
+        .collect()
+}
+
+#[test]
+fn recovery_keyed_on_current() -> Result<()> {
+    let tree = parse("{f(a,b);g;}")?;
+    assert!(errors(&tree).is_empty());
+
+    let tree = parse("{f(a,?b);g;}")?;
+    assert_eq!(
+        errors(&tree),
+        [(5..7, vec!["args", "call", "stmt", "block"])]
+    );
+
+    let tree = parse("{f(a)?x;?;g;}")?;
+    assert_eq!(
+        errors(&tree),
+        [(5..7, vec!["stmt", "block"]), (8..9, vec!["block"])]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn open_stack_views() -> Result<()> {
+    let mut b = Builder::new();
+    assert_eq!(b.current(), None);
+    assert_eq!(b.open_stack().count(), 0);
+
+    let block = b.open("block")?;
+    let stmt = b.open("stmt")?;
+    b.token("ident", 1)?;
+    let call = b.open("call")?;
+
+    assert_eq!(b.depth(), 3);
+    assert_eq!(b.current(), Some((&"call", call)));
+    assert!(b
+        .open_stack()
+        .eq([(&"call", call), (&"stmt", stmt), (&"block", block)]));
+    assert!(b
+        .open_stack()
+        .rev()
+        .eq([(&"block", block), (&"stmt", stmt), (&"call", call)]));
+    assert_eq!(b.open_stack().len(), 3);
+
+    b.close()?;
+    b.close()?;
+
+    assert_eq!(b.current(), Some((&"block", block)));
+    assert_eq!(b.depth(), 1);
+
+    b.close()?;
+    assert_eq!(b.current(), None);
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use anyhow::Result;
+use syntree::owned::TreeNode;
+use syntree::{span, Builder, CursorPolicy, Error, Kind, Span, Tree};
+
+mod common;
+
+/// Count the nodes in an owned tree without recursing.
+fn count<T, S>(nodes: &[TreeNode<T, S>]) -> usize {
+    let mut queue = nodes.iter().collect::<Vec<_>>();
+    let mut count = 0;
+
+    while let Some(node) = queue.pop() {
+        count += 1;
+        queue.extend(node.children.iter());
+    }
+
+    count
+}
+
+#[test]
+fn owned_roundtrip() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            "number" => {
+                ("lit", 3),
+            },
+            ("ws", 1),
+            "empty" => {},
+        },
+        ("eof", 0),
+    };
+
+    let nodes = tree.to_owned_nodes();
+
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[0].kind, Kind::Node);
+    assert_eq!(nodes[0].span, Span::new(0, 4));
+    assert_eq!(nodes[0].children.len(), 3);
+    assert_eq!(nodes[1], TreeNode::token("eof", Span::point(4)));
+
+    assert_eq!(Tree::from_owned_nodes(nodes)?, tree);
+
+    let empty = Tree::<u32, Span>::from_owned_nodes(Vec::new())?;
+    assert!(empty.is_empty());
+    assert!(empty.to_owned_nodes().is_empty());
+    Ok(())
+}
+
+#[test]
+fn owned_roundtrip_generated() -> Result<()> {
+    for seed in 1..=64 {
+        let tree = common::generate(seed)?;
+        let nodes = tree.to_owned_nodes();
+        assert_eq!(count(&nodes), tree.len());
+        assert_eq!(Tree::from_owned_nodes(nodes)?, tree, "seed {seed}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn owned_roundtrip_empty_span() -> Result<()> {
+    let mut b = Builder::<_, span::Empty>::new_with();
+    b.open("root")?;
+    b.token("lit", span::Empty)?;
+    b.close()?;
+    let tree = b.build()?;
+
+    let nodes = tree.to_owned_nodes();
+    assert_eq!(nodes[0].children[0], TreeNode::token("lit", span::Empty));
+    assert_eq!(Tree::from_owned_nodes(nodes)?, tree);
+    Ok(())
+}
+
+#[test]
+fn owned_spans_kept() -> Result<()> {
+    let nodes = vec![
+        TreeNode::node(
+            "root",
+            Span::new(10, 20),
+            vec![
+                TreeNode::token("a", Span::new(5, 7)),
+                TreeNode::node("empty", Span::point(9), Vec::new()),
+            ],
+        ),
+        TreeNode::token("b", Span::new(9, 10)),
+    ];
+
+    // Tokens and empty nodes keep their spans, while the spans of other
+    // nodes are recomputed from their children.
+    let tree = Tree::from_owned_nodes(nodes)?;
+
+    assert_eq!(
+        tree.walk()
+            .map(|n| (*n.value(), n.range()))
+            .collect::<Vec<_>>(),
+        [("root", 5..9), ("a", 5..7), ("empty", 9..9), ("b", 9..10)]
+    );
+
+    let nodes = vec![
+        TreeNode::token("a", Span::new(0, 4)),
+        TreeNode::token("b", Span::new(2, 6)),
+    ];
+
+    assert!(matches!(
+        Tree::from_owned_nodes(nodes),
+        Err(Error::CursorBackwards {
+            start: 2,
+            end: 6,
+            ..
+        })
+    ));
+
+    let nodes = vec![
+        TreeNode::token("a", Span::new(0, 4)),
+        TreeNode::node("empty", Span::point(2), Vec::new()),
+    ];
+
+    assert!(matches!(
+        Tree::from_owned_nodes(nodes),
+        Err(Error::CursorBackwards { start: 2, .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn owned_roundtrip_gaps() -> Result<()> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    b.token("a", 1)?;
+    b.advance(4)?;
+    b.open("root")?;
+    b.token("b", 1)?;
+    b.advance(2)?;
+    b.open("empty")?;
+    b.close()?;
+    b.advance(1)?;
+    b.token("c", 0)?;
+    b.token_with_span("d", Span::new(12, 14))?;
+    b.close()?;
+    b.advance(3)?;
+    b.open("trailing")?;
+    b.close()?;
+
+    let tree = b.build()?;
+
+    assert_eq!(
+        tree.walk()
+            .map(|n| (*n.value(), n.range()))
+            .collect::<Vec<_>>(),
+        [
+            ("a", 0..1),
+            ("root", 5..14),
+            ("b", 5..6),
+            ("empty", 8..8),
+            ("c", 9..9),
+            ("d", 12..14),
+            ("trailing", 17..17)
+        ]
+    );
+
+    assert_eq!(Tree::from_owned_nodes(tree.to_owned_nodes())?, tree);
+    assert_eq!(Tree::from_owned_events(tree.to_owned_events())?, tree);
+    Ok(())
+}
+
+#[test]
+fn owned_token_with_children() {
+    let mut token = TreeNode::token("a", Span::new(0, 1));
+    token
+        .children
+        .push(TreeNode::node("b", Span::point(0), Vec::new()));
+
+    let nodes = vec![TreeNode::node("root", Span::point(0), vec![token])];
+    assert_eq!(Tree::from_owned_nodes(nodes), Err(Error::TokenWithChildren));
+}
+
+#[test]
+fn owned_deep() -> Result<()> {
+    const DEPTH: usize = 100_000;
+
+    let mut b = Builder::new();
+
+    for n in 0..DEPTH {
+        b.open(n)?;
+    }
+
+    b.token(DEPTH, 1)?;
+
+    for _ in 0..DEPTH {
+        b.close()?;
+    }
+
+    let tree = b.build()?;
+    let nodes = tree.to_owned_nodes();
+
+    // Follow the spine of the tree down to the token.
+    let mut current = &nodes[..];
+    let mut depth = 0;
+
+    while let [node] = current {
+        assert_eq!(node.value, depth);
+        assert_eq!(node.span, Span::new(0, 1));
+        current = &node.children;
+        depth += 1;
+    }
+
+    assert!(current.is_empty());
+    assert_eq!(depth, DEPTH + 1);
+
+    // Dropping the nodes doesn't recurse, which would otherwise overflow the
+    // stack.
+    drop(nodes);
+
+    assert_eq!(Tree::from_owned_nodes(tree.to_owned_nodes())?, tree);
+
+    // Nodes which remain when conversion fails are dropped too.
+    let mut token = TreeNode::token(0, Span::new(0, 1));
+    token.children.push(TreeNode::token(0, Span::new(0, 1)));
+
+    let mut nodes = vec![token];
+    nodes.extend(tree.to_owned_nodes());
+    assert_eq!(Tree::from_owned_nodes(nodes), Err(Error::TokenWithChildren));
+    Ok(())
+}
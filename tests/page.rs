@@ -0,0 +1,128 @@
+use anyhow::Result;
+use syntree::{Builder, Error, Id, Tree};
+
+fn wide_tree(width: usize) -> Result<(Tree<usize>, Id)> {
+    let mut b = Builder::new();
+
+    b.token(0, 1)?;
+    let root = b.open(0)?;
+
+    for n in 0..width {
+        if n % 3 == 0 {
+            b.open(n)?;
+            b.token(n, 1)?;
+            b.close()?;
+        } else {
+            b.token(n, 1)?;
+        }
+    }
+
+    b.close()?;
+    Ok((b.build()?, root))
+}
+
+#[test]
+fn page_through_wide_node() -> Result<()> {
+    let (tree, root) = wide_tree(100)?;
+
+    let expected = tree
+        .children_of(root)
+        .map(|n| (n.id(), *n.value()))
+        .collect::<Vec<_>>();
+
+    for limit in [1, 2, 7, 50, 99, 100, 101, 1000] {
+        let mut after = None;
+        let mut children = Vec::new();
+        let mut pages = 0;
+
+        loop {
+            let page = tree.children_page(Some(root), after, limit, |v| *v)?;
+            assert!(page.items().len() <= limit);
+            pages += 1;
+
+            for item in page.items() {
+                assert_eq!(item.has_children(), *item.value() % 3 == 0);
+                children.push((item.id(), *item.value()));
+            }
+
+            if !page.has_more() {
+                break;
+            }
+
+            after = page.after();
+        }
+
+        assert_eq!(children, expected, "limit {limit}");
+        assert_eq!(pages, expected.len().div_ceil(limit), "limit {limit}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn page_roots() -> Result<()> {
+    let (tree, root) = wide_tree(3)?;
+
+    let page = tree.children_page(None, None, 1, |v| *v)?;
+    assert_eq!(page.items().len(), 1);
+    assert!(page.has_more());
+
+    let page = tree.children_page(None, page.after(), 1, |v| *v)?;
+    assert_eq!(page.items()[0].id(), root);
+    assert!(!page.has_more());
+
+    let empty = tree.children_page(None, page.after(), 1, |v| *v)?;
+    assert!(empty.items().is_empty());
+    assert_eq!(empty.after(), page.after());
+    assert!(!empty.has_more());
+    Ok(())
+}
+
+#[test]
+fn cursors_are_stable() -> Result<()> {
+    let (a, a_root) = wide_tree(10)?;
+    let (b, b_root) = wide_tree(10)?;
+    assert_eq!(a_root, b_root);
+
+    let page = a.children_page(Some(a_root), None, 4, |v| *v)?;
+    let a_next = a.children_page(Some(a_root), page.after(), 4, |v| *v)?;
+    let b_next = b.children_page(Some(b_root), page.after(), 4, |v| *v)?;
+    assert_eq!(a_next, b_next);
+    Ok(())
+}
+
+#[test]
+fn invalid_cursors() -> Result<()> {
+    let (tree, root) = wide_tree(10)?;
+
+    let grandchild = tree
+        .children_of(root)
+        .find_map(|n| n.first())
+        .map(|n| n.id())
+        .ok_or(anyhow::anyhow!("missing grandchild"))?;
+
+    // Not a child of the parent being paged.
+    assert_eq!(
+        tree.children_page(Some(root), Some(grandchild), 4, |v| *v),
+        Err(Error::MissingNode(grandchild))
+    );
+
+    // Cursor from a larger tree which does not exist in this one.
+    let (larger, larger_root) = wide_tree(100)?;
+    let last = larger
+        .children_of(larger_root)
+        .next_back()
+        .map(|n| n.id())
+        .ok_or(anyhow::anyhow!("missing last"))?;
+
+    assert_eq!(
+        tree.children_page(Some(root), Some(last), 4, |v| *v),
+        Err(Error::MissingNode(last))
+    );
+
+    assert_eq!(
+        tree.children_page(Some(last), None, 4, |v| *v),
+        Err(Error::MissingNode(last))
+    );
+    Ok(())
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "rayon")]
+
+use anyhow::Result;
+use rayon::prelude::*;
+use syntree::{Builder, Id, Span, Tree};
+
+/// Generate a wide and deep tree.
+fn generate() -> Result<Tree<u32, Span>> {
+    let mut b = Builder::new();
+
+    for n in 0..64u32 {
+        b.open(n)?;
+
+        for m in 0..n % 7 {
+            b.open(m)?;
+
+            for _ in 0..m {
+                b.token(n, 1)?;
+            }
+
+            b.close()?;
+        }
+
+        b.token(n, 1)?;
+        b.close()?;
+    }
+
+    Ok(b.build()?)
+}
+
+fn sorted(mut ids: Vec<Id>) -> Vec<Id> {
+    ids.sort();
+    ids
+}
+
+#[test]
+fn par_walk_visits_every_node_once() -> Result<()> {
+    let tree = generate()?;
+
+    let expected = sorted(tree.walk().map(|n| n.id()).collect());
+    let actual = sorted(tree.par_walk().map(|n| n.id()).collect());
+    assert_eq!(actual, expected);
+    assert_eq!(tree.par_walk().count(), tree.len());
+    Ok(())
+}
+
+#[test]
+fn par_children_visits_every_child_once() -> Result<()> {
+    let tree = generate()?;
+
+    for node in tree.walk() {
+        let expected = sorted(node.children().map(|n| n.id()).collect());
+        let actual = sorted(node.par_children().map(|n| n.id()).collect());
+        assert_eq!(actual, expected);
+    }
+
+    // The multiset of nodes reached through children in parallel is the
+    // same as the sequential walk.
+    let mut actual = tree
+        .walk()
+        .filter(|n| n.parent().is_none())
+        .map(|n| n.id())
+        .collect::<Vec<_>>();
+
+    actual.extend(
+        tree.walk()
+            .collect::<Vec<_>>()
+            .par_iter()
+            .flat_map(|n| n.par_children().map(|n| n.id()).collect::<Vec<_>>())
+            .collect::<Vec<_>>(),
+    );
+
+    assert_eq!(
+        sorted(actual),
+        sorted(tree.walk().map(|n| n.id()).collect())
+    );
+    Ok(())
+}
+
+#[test]
+fn par_fold_subtrees() -> Result<()> {
+    let tree = generate()?;
+
+    for depth in 0..3 {
+        let sequential = tree
+            .walk()
+            .with_depths()
+            .filter(|(d, _)| *d == depth)
+            .map(|(_, n)| n.walk().count() + 1)
+            .sum::<usize>();
+
+        let parallel = tree.par_fold_subtrees(depth, |n| n.walk().count() + 1, |a, b| a + b);
+        assert_eq!(parallel, Some(sequential));
+    }
+
+    assert_eq!(
+        tree.par_fold_subtrees(0, |n| n.walk().count() + 1, |a, b| a + b),
+        Some(tree.len())
+    );
+    assert_eq!(tree.par_fold_subtrees(3, |_| 1, |a, b| a + b), None);
+    Ok(())
+}
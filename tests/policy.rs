@@ -0,0 +1,343 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use syntree::{Builder, CursorPolicy, Error, Span, Tree};
+
+const POLICIES: [CursorPolicy; 3] = [
+    CursorPolicy::Contiguous,
+    CursorPolicy::Monotonic,
+    CursorPolicy::Unordered,
+];
+
+/// Collect the ranges of all nodes in the tree in walk order.
+fn ranges(tree: &Tree<&'static str, Span>) -> Vec<(&'static str, Range<usize>)> {
+    tree.walk().map(|n| (*n.value(), n.range())).collect()
+}
+
+#[test]
+fn contiguous_tokens() -> Result<()> {
+    for policy in POLICIES {
+        let mut tree = Builder::new().with_policy(policy);
+        assert_eq!(tree.policy(), policy);
+
+        tree.open("root")?;
+        tree.token("a", 2)?;
+        tree.token_with_span("b", Span::new(2, 5))?;
+        tree.token_empty("c")?;
+        tree.close()?;
+
+        let tree = tree.build()?;
+
+        assert_eq!(
+            ranges(&tree),
+            [("root", 0..5), ("a", 0..2), ("b", 2..5), ("c", 5..5)],
+            "{policy:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn gaps() -> Result<()> {
+    for policy in POLICIES {
+        let mut tree = Builder::new().with_policy(policy);
+
+        tree.open("root")?;
+        tree.advance(0)?;
+        tree.token("a", 2)?;
+
+        let advance = tree.advance(1);
+        let span = tree.token_with_span("b", Span::new(4, 5)).map(|_| ());
+
+        match policy {
+            CursorPolicy::Contiguous => {
+                assert_eq!(
+                    advance,
+                    Err(Error::CursorGap {
+                        cursor: 2,
+                        start: 3
+                    })
+                );
+                assert_eq!(
+                    span,
+                    Err(Error::CursorGap {
+                        cursor: 2,
+                        start: 4
+                    })
+                );
+                tree.close()?;
+                let tree = tree.build()?;
+                assert_eq!(ranges(&tree), [("root", 0..2), ("a", 0..2)]);
+            }
+            _ => {
+                assert_eq!(advance, Ok(()));
+                assert_eq!(span, Ok(()));
+                tree.close()?;
+                let tree = tree.build()?;
+                assert_eq!(tree.span(), Span::new(0, 5), "{policy:?}");
+                assert_eq!(
+                    ranges(&tree),
+                    [("root", 0..5), ("a", 0..2), ("b", 4..5)],
+                    "{policy:?}"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn leading_gap() -> Result<()> {
+    for policy in POLICIES {
+        let mut tree = Builder::new().with_policy(policy);
+
+        tree.open("root")?;
+        let result = tree.token_with_span("a", Span::new(3, 4)).map(|_| ());
+        tree.close()?;
+
+        let tree = tree.build()?;
+
+        match policy {
+            CursorPolicy::Contiguous => {
+                assert_eq!(
+                    result,
+                    Err(Error::CursorGap {
+                        cursor: 0,
+                        start: 3
+                    })
+                );
+                assert_eq!(ranges(&tree), [("root", 0..0)]);
+            }
+            _ => {
+                assert_eq!(result, Ok(()));
+                assert_eq!(ranges(&tree), [("root", 3..4), ("a", 3..4)], "{policy:?}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn backwards() -> Result<()> {
+    for policy in POLICIES {
+        let mut tree = Builder::new().with_policy(policy);
+
+        tree.open("root")?;
+        tree.open("inner")?;
+        let a = tree.token("a", 4)?;
+        tree.close()?;
+        tree.open("inner")?;
+        let result = tree.token_with_span("b", Span::new(1, 2)).map(|_| ());
+        tree.close()?;
+        tree.close()?;
+
+        let tree = tree.build()?;
+
+        match policy {
+            CursorPolicy::Unordered => {
+                assert_eq!(result, Ok(()));
+                assert_eq!(tree.span(), Span::new(0, 4));
+                assert_eq!(
+                    ranges(&tree),
+                    [
+                        ("root", 0..4),
+                        ("inner", 0..4),
+                        ("a", 0..4),
+                        ("inner", 1..2),
+                        ("b", 1..2)
+                    ]
+                );
+
+                // Tokens are indexed in order of where they end.
+                assert_eq!(tree.node_with_range(1..2).map(|n| n.range()), Some(1..2));
+                assert_eq!(tree.node_with_range(3..4).map(|n| n.range()), Some(0..4));
+            }
+            _ => {
+                assert_eq!(
+                    result,
+                    Err(Error::CursorBackwards {
+                        cursor: 4,
+                        start: 1,
+                        end: 2,
+                        previous: Some(a),
+                        previous_start: 0,
+                        previous_end: 4,
+                    }),
+                    "{policy:?}"
+                );
+                assert_eq!(
+                    ranges(&tree),
+                    [
+                        ("root", 0..4),
+                        ("inner", 0..4),
+                        ("a", 0..4),
+                        ("inner", 4..4)
+                    ],
+                    "{policy:?}"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn backwards_span() -> Result<()> {
+    for policy in POLICIES {
+        let mut tree = Builder::new().with_policy(policy);
+
+        tree.open("root")?;
+        tree.token("a", 2)?;
+
+        let mut span = Span::point(5);
+        span.end = 3;
+
+        assert_eq!(
+            tree.token_with_span("b", span),
+            Err(Error::BackwardsSpan { start: 5, end: 3 }),
+            "{policy:?}"
+        );
+
+        tree.token("c", 1)?;
+        tree.close()?;
+
+        let tree = tree.build()?;
+        assert_eq!(
+            ranges(&tree),
+            [("root", 0..3), ("a", 0..2), ("c", 2..3)],
+            "{policy:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn overlapping_span() -> Result<()> {
+    for policy in [CursorPolicy::Contiguous, CursorPolicy::Monotonic] {
+        let mut tree = Builder::new().with_policy(policy);
+
+        tree.open("root")?;
+        tree.token("a", 2)?;
+        let b = tree.token_with_span("b", Span::new(2, 6))?;
+        tree.token_empty("c")?;
+
+        // Starts inside of `b`.
+        assert_eq!(
+            tree.token_with_span("d", Span::new(4, 8)),
+            Err(Error::CursorBackwards {
+                cursor: 6,
+                start: 4,
+                end: 8,
+                previous: Some(b),
+                previous_start: 2,
+                previous_end: 6,
+            }),
+            "{policy:?}"
+        );
+
+        // Lies entirely before `b`.
+        assert_eq!(
+            tree.token_with_span("e", Span::new(0, 1)),
+            Err(Error::CursorBackwards {
+                cursor: 6,
+                start: 0,
+                end: 1,
+                previous: Some(b),
+                previous_start: 2,
+                previous_end: 6,
+            }),
+            "{policy:?}"
+        );
+
+        tree.close()?;
+
+        let tree = tree.build()?;
+        assert_eq!(
+            ranges(&tree),
+            [("root", 0..6), ("a", 0..2), ("b", 2..6), ("c", 6..6)],
+            "{policy:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn overlapping_span_after_gap() -> Result<()> {
+    let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    tree.advance(3)?;
+
+    // There is no previous token, only a gap.
+    assert_eq!(
+        tree.token_with_span("a", Span::new(1, 2)),
+        Err(Error::CursorBackwards {
+            cursor: 3,
+            start: 1,
+            end: 2,
+            previous: None,
+            previous_start: 3,
+            previous_end: 3,
+        })
+    );
+
+    let a = tree.token_with_span("a", Span::new(9, 12))?;
+
+    assert_eq!(
+        tree.token_with_span("b", Span::new(3, 5)),
+        Err(Error::CursorBackwards {
+            cursor: 12,
+            start: 3,
+            end: 5,
+            previous: Some(a),
+            previous_start: 9,
+            previous_end: 12,
+        })
+    );
+
+    // Gaps after the previous token are allowed.
+    tree.token_with_span("b", Span::new(14, 15))?;
+    tree.token_with_span("c", Span::new(15, 15))?;
+
+    let tree = tree.build()?;
+    assert_eq!(ranges(&tree), [("a", 9..12), ("b", 14..15), ("c", 15..15)]);
+    assert_eq!(tree.span(), Span::new(0, 15));
+    Ok(())
+}
+
+#[test]
+fn close_at() -> Result<()> {
+    for policy in POLICIES {
+        let mut tree = Builder::new().with_policy(policy);
+
+        tree.token("a", 1)?;
+        let c = tree.checkpoint()?;
+        let gap = tree.advance(2).is_ok();
+        tree.token("b", 1)?;
+        tree.token("c", 1)?;
+        tree.close_at(&c, "wrap")?;
+
+        let tree = tree.build()?;
+
+        if gap {
+            assert_eq!(
+                ranges(&tree),
+                [("a", 0..1), ("wrap", 3..5), ("b", 3..4), ("c", 4..5)],
+                "{policy:?}"
+            );
+        } else {
+            assert_eq!(policy, CursorPolicy::Contiguous);
+            assert_eq!(
+                ranges(&tree),
+                [("a", 0..1), ("wrap", 1..3), ("b", 1..2), ("c", 2..3)]
+            );
+        }
+    }
+
+    Ok(())
+}
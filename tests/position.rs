@@ -0,0 +1,75 @@
+use std::cmp::Ordering;
+
+use anyhow::Result;
+use syntree::{Builder, Node};
+
+#[test]
+fn position_order_with_empty_nodes() -> Result<()> {
+    let mut tree = Builder::new();
+
+    let c = tree.checkpoint()?;
+    tree.open("empty1")?;
+    tree.close()?;
+    tree.token_empty("zero1")?;
+    tree.open("inner")?;
+    tree.token_empty("zero2")?;
+    tree.open("empty2")?;
+    tree.close()?;
+    tree.token("lit", 2)?;
+    tree.close()?;
+    tree.token_empty("zero3")?;
+    tree.close_at(&c, "root")?;
+    tree.open("empty3")?;
+    tree.close()?;
+
+    let tree = tree.build()?;
+
+    let walked = tree.walk().collect::<Vec<_>>();
+    let names = walked.iter().map(|n| *n.value()).collect::<Vec<_>>();
+
+    assert_eq!(
+        names,
+        ["root", "empty1", "zero1", "inner", "zero2", "empty2", "lit", "zero3", "empty3"]
+    );
+
+    for (i, a) in walked.iter().enumerate() {
+        for (j, b) in walked.iter().enumerate() {
+            assert_eq!(
+                a.cmp_position(b),
+                i.cmp(&j),
+                "{} vs {}",
+                a.value(),
+                b.value()
+            );
+        }
+    }
+
+    let mut sorted = walked.clone();
+    sorted.reverse();
+    sorted.sort_by(Node::cmp_position);
+    assert!(sorted.iter().map(|n| *n.value()).eq(names.iter().copied()));
+
+    let zero2 = walked[4];
+    let index = sorted.binary_search_by(|n| n.cmp_position(&zero2));
+    assert_eq!(index, Ok(4));
+    Ok(())
+}
+
+#[test]
+fn position_across_trees() -> Result<()> {
+    let a = syntree::tree! { "root" => { ("lit", 1) } };
+    let b = syntree::tree! { "root" => { ("lit", 1) } };
+
+    let a = a
+        .first()
+        .ok_or("missing root")
+        .map_err(anyhow::Error::msg)?;
+    let b = b
+        .first()
+        .ok_or("missing root")
+        .map_err(anyhow::Error::msg)?;
+
+    assert_ne!(a.cmp_position(&b), Ordering::Equal);
+    assert_eq!(a.cmp_position(&b), b.cmp_position(&a).reverse());
+    Ok(())
+}
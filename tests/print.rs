@@ -0,0 +1,559 @@
+use anyhow::Result;
+use syntree::print::{self, Labels, SourceOptions};
+use syntree::span::Empty;
+use syntree::Builder;
+
+#[test]
+fn print_invalid_spans() -> Result<()> {
+    // The `é` is two bytes wide, so the `HALF` token splits it.
+    let source = "aé";
+
+    let tree = syntree::tree! {
+        "ROOT" => {
+            ("A", 1),
+            ("HALF", 1),
+            ("REST", 1),
+        },
+        ("PAST", 2),
+    };
+
+    let mut s = Vec::new();
+    print::print_with_source(&mut s, &tree, source)?;
+
+    let expected = [
+        "\"ROOT\"@0..3",
+        "  \"A\"@0..1 \"a\"",
+        "  \"HALF\"@1..2 <invalid span>",
+        "  \"REST\"@2..3 <invalid span>",
+        "\"PAST\"@3..5 <invalid span>",
+    ];
+
+    assert_eq!(String::from_utf8(s)?.lines().collect::<Vec<_>>(), expected);
+
+    // Without a source every token is rendered the same.
+    let mut s = Vec::new();
+    print::print(&mut s, &tree)?;
+    assert!(!String::from_utf8(s)?.contains("<invalid span>"));
+    Ok(())
+}
+
+#[test]
+fn print_truncated_source() -> Result<()> {
+    let source = "line1\n\tline2ééé";
+
+    let tree = syntree::tree! {
+        ("LONG", 18),
+        ("SHORT", 0),
+    };
+
+    let mut s = Vec::new();
+    print::print_with_source_options(&mut s, &tree, source, &SourceOptions::new())?;
+    assert_eq!(
+        String::from_utf8(s)?,
+        "\"LONG\"@0..18 \"line1\\n\\tline2ééé\"\n\"SHORT\"@18..18 \"\"\n"
+    );
+
+    // Truncation is by characters, so multi-byte characters are never split.
+    let options = SourceOptions::new().with_max_len(13);
+    let mut s = Vec::new();
+    print::print_with_source_options(&mut s, &tree, source, &options)?;
+    assert_eq!(
+        String::from_utf8(s)?,
+        "\"LONG\"@0..18 \"line1\\n\\tline2é\"…\n\"SHORT\"@18..18 \"\"\n"
+    );
+
+    // Snippets which fit exactly are not marked.
+    let options = SourceOptions::new().with_max_len(15);
+    let mut s = Vec::new();
+    print::print_with_source_options(&mut s, &tree, source, &options)?;
+    assert!(!String::from_utf8(s)?.contains('…'));
+    Ok(())
+}
+
+#[test]
+fn print_aligned_source() -> Result<()> {
+    let source = "fn main() { 1 }";
+
+    let tree = syntree::tree! {
+        "FN" => {
+            ("FN_KW", 2),
+            ("WS", 1),
+            ("IDENT", 4),
+            "PARAMS" => {
+                ("OPEN", 1),
+                ("CLOSE", 1),
+            },
+            ("WS", 1),
+            "BLOCK" => {
+                ("OPEN", 1),
+                ("WS", 1),
+                ("NUMBER", 1),
+                ("WS", 1),
+                ("CLOSE", 1),
+            },
+        },
+    };
+
+    let render = |options: &SourceOptions| -> Result<Vec<String>> {
+        let mut s = Vec::new();
+        print::print_with_source_options(&mut s, &tree, source, options)?;
+        Ok(String::from_utf8(s)?.lines().map(str::to_owned).collect())
+    };
+
+    let expected = [
+        "\"FN\"        @ 0..15",
+        "  \"FN_KW\"   @  0..2 \"fn\"",
+        "  \"WS\"      @  2..3 \" \"",
+        "  \"IDENT\"   @  3..7 \"main\"",
+        "  \"PARAMS\"  @  7..9",
+        "    \"OPEN\"  @  7..8 \"(\"",
+        "    \"CLOSE\" @  8..9 \")\"",
+        "  \"WS\"      @ 9..10 \" \"",
+        "  \"BLOCK\"   @10..15",
+        "    \"OPEN\"  @10..11 \"{\"",
+        "    \"WS\"    @11..12 \" \"",
+        "    \"NUMBER\"@12..13 \"1\"",
+        "    \"WS\"    @13..14 \" \"",
+        "    \"CLOSE\" @14..15 \"}\"",
+    ];
+
+    assert_eq!(
+        render(&SourceOptions::new().with_aligned_spans())?,
+        expected
+    );
+
+    // Aligning spans takes precedence over padding values.
+    let options = SourceOptions::new()
+        .with_padded_values()
+        .with_aligned_spans();
+    assert_eq!(render(&options)?, expected);
+
+    let expected = [
+        "\"FN\"@0..15",
+        "  \"FN_KW\" @0..2 \"fn\"",
+        "  \"WS\"    @2..3 \" \"",
+        "  \"IDENT\" @3..7 \"main\"",
+        "  \"PARAMS\"@7..9",
+        "    \"OPEN\"  @7..8 \"(\"",
+        "    \"CLOSE\" @8..9 \")\"",
+        "  \"WS\"    @9..10 \" \"",
+        "  \"BLOCK\" @10..15",
+        "    \"OPEN\"  @10..11 \"{\"",
+        "    \"WS\"    @11..12 \" \"",
+        "    \"NUMBER\"@12..13 \"1\"",
+        "    \"WS\"    @13..14 \" \"",
+        "    \"CLOSE\" @14..15 \"}\"",
+    ];
+
+    assert_eq!(
+        render(&SourceOptions::new().with_padded_values())?,
+        expected
+    );
+
+    // Truncated snippets don't affect the alignment of spans.
+    let options = SourceOptions::new().with_aligned_spans().with_max_len(2);
+    let lines = render(&options)?;
+    assert_eq!(lines[3], "  \"IDENT\"   @  3..7 \"ma\"…");
+    assert_eq!(lines[13], "    \"CLOSE\" @14..15 \"}\"");
+
+    // The default output is left untouched.
+    let mut s = Vec::new();
+    print::print_with_source(&mut s, &tree, source)?;
+    let lines = String::from_utf8(s)?;
+    assert_eq!(lines.lines().nth(1), Some("  \"FN_KW\"@0..2 \"fn\""));
+    Ok(())
+}
+
+#[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
+enum Syntax {
+    NUMBER,
+    WHITESPACE,
+    OPERATOR,
+    PLUS,
+}
+
+use Syntax::*;
+
+#[test]
+fn print_snapshot() -> Result<()> {
+    let tree = syntree::tree! {
+        "ROOT" => {
+            ("A", 1),
+            "EMPTY" => {},
+            ("B", 2),
+        },
+        ("EOF", 0),
+    };
+
+    let mut s = Vec::new();
+    print::print(&mut s, &tree)?;
+
+    let expected = [
+        "\"ROOT\"@0..3",
+        "  \"A\"@0..1 +",
+        "  \"EMPTY\"@1..1",
+        "  \"B\"@1..3 +",
+        "\"EOF\"@3..3 +",
+    ];
+
+    assert_eq!(String::from_utf8(s)?.lines().collect::<Vec<_>>(), expected);
+
+    let mut s = Vec::new();
+    print::print_spanless(&mut s, &tree)?;
+
+    let expected = [
+        "\"ROOT\"",
+        "  \"A\" +",
+        "  \"EMPTY\"",
+        "  \"B\" +",
+        "\"EOF\" +",
+    ];
+
+    assert_eq!(String::from_utf8(s)?.lines().collect::<Vec<_>>(), expected);
+    Ok(())
+}
+
+#[test]
+fn print_span_without_display() -> Result<()> {
+    let mut b = Builder::<_, Empty>::new_with();
+
+    b.open("ROOT")?;
+    b.token("A", Empty)?;
+    b.close()?;
+
+    let tree = b.build()?;
+
+    let mut s = Vec::new();
+    print::print(&mut s, &tree)?;
+    assert_eq!(String::from_utf8(s)?, "\"ROOT\"@0..0\n  \"A\"@0..0 +\n");
+
+    let mut s = Vec::new();
+    print::print_spanless(&mut s, &tree)?;
+    assert_eq!(String::from_utf8(s)?, "\"ROOT\"\n  \"A\" +\n");
+    Ok(())
+}
+
+#[test]
+fn to_json_snapshot() -> Result<()> {
+    let source = "128 + 64";
+
+    let tree = syntree::tree! {
+        NUMBER => {
+            (NUMBER, 3),
+        },
+        (WHITESPACE, 1),
+        OPERATOR => {
+            (PLUS, 1)
+        },
+        (WHITESPACE, 1),
+        NUMBER => {
+            (NUMBER, 2),
+        },
+    };
+
+    let mut s = Vec::new();
+    print::to_json_with_source(&mut s, &tree, source)?;
+    let s = String::from_utf8(s)?;
+
+    let expected = serde_json::json!([
+        {"value": "NUMBER", "kind": "node", "span": {"start": 0, "end": 3}, "children": [
+            {"value": "NUMBER", "kind": "token", "span": {"start": 0, "end": 3}, "text": "128", "children": []},
+        ]},
+        {"value": "WHITESPACE", "kind": "token", "span": {"start": 3, "end": 4}, "text": " ", "children": []},
+        {"value": "OPERATOR", "kind": "node", "span": {"start": 4, "end": 5}, "children": [
+            {"value": "PLUS", "kind": "token", "span": {"start": 4, "end": 5}, "text": "+", "children": []},
+        ]},
+        {"value": "WHITESPACE", "kind": "token", "span": {"start": 5, "end": 6}, "text": " ", "children": []},
+        {"value": "NUMBER", "kind": "node", "span": {"start": 6, "end": 8}, "children": [
+            {"value": "NUMBER", "kind": "token", "span": {"start": 6, "end": 8}, "text": "64", "children": []},
+        ]},
+    ]);
+
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&s)?, expected);
+
+    // Lock the field order in addition to the structure.
+    let snapshot = concat!(
+        r#"[{"value":"NUMBER","kind":"node","span":{"start":0,"end":3},"children":["#,
+        r#"{"value":"NUMBER","kind":"token","span":{"start":0,"end":3},"text":"128","children":[]}]},"#,
+        r#"{"value":"WHITESPACE","kind":"token","span":{"start":3,"end":4},"text":" ","children":[]},"#,
+        r#"{"value":"OPERATOR","kind":"node","span":{"start":4,"end":5},"children":["#,
+        r#"{"value":"PLUS","kind":"token","span":{"start":4,"end":5},"text":"+","children":[]}]},"#,
+        r#"{"value":"WHITESPACE","kind":"token","span":{"start":5,"end":6},"text":" ","children":[]},"#,
+        r#"{"value":"NUMBER","kind":"node","span":{"start":6,"end":8},"children":["#,
+        r#"{"value":"NUMBER","kind":"token","span":{"start":6,"end":8},"text":"64","children":[]}]}]"#,
+    );
+
+    assert_eq!(s, snapshot);
+
+    let mut s = Vec::new();
+    print::to_json(&mut s, &tree)?;
+    assert!(!String::from_utf8(s)?.contains("\"text\""));
+
+    let mut s = Vec::new();
+    assert!(print::to_json_with_source(&mut s, &tree, "128").is_err());
+    Ok(())
+}
+
+#[test]
+fn to_json_escapes() -> Result<()> {
+    let source = "\"\\\n\r\t\u{1}é";
+
+    let tree = syntree::tree! {
+        "a\"b" => {
+            ("lit", source.len()),
+        },
+        "empty" => {},
+    };
+
+    let mut s = Vec::new();
+    print::to_json_with_source(&mut s, &tree, source)?;
+    let s = String::from_utf8(s)?;
+    assert!(s.contains(r#""text":"\"\\\n\r\t\u0001é""#));
+
+    let value = serde_json::from_str::<serde_json::Value>(&s)?;
+    assert_eq!(value[0]["value"], "\"a\\\"b\"");
+    assert_eq!(value[0]["children"][0]["text"], source);
+    assert_eq!(value[1]["children"], serde_json::json!([]));
+    Ok(())
+}
+
+#[test]
+fn to_json_deep() -> Result<()> {
+    const DEPTH: usize = 100_000;
+
+    let mut b = syntree::Builder::new();
+
+    for _ in 0..DEPTH {
+        b.open("n")?;
+    }
+
+    b.token("t", 1)?;
+
+    for _ in 0..DEPTH {
+        b.close()?;
+    }
+
+    let tree = b.build()?;
+
+    let mut s = Vec::new();
+    print::to_json(&mut s, &tree)?;
+    let s = String::from_utf8(s)?;
+
+    assert_eq!(s.matches("\"kind\":\"node\"").count(), DEPTH);
+    assert!(s.ends_with(&format!("[]}}{}]", "]}".repeat(DEPTH))));
+    Ok(())
+}
+
+/// Strip tags from and unescape the output of `to_html`.
+fn html_text(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+
+    out.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[test]
+fn to_html_escapes() -> Result<()> {
+    let source = "a<b && \"c\" > 'd'";
+
+    let mut b = syntree::Builder::new().with_policy(syntree::CursorPolicy::Monotonic);
+    b.open("EXPR")?;
+    b.token("IDENT", 1)?;
+    b.token("LT", 1)?;
+    b.open("<&\">")?;
+    b.token("IDENT", 1)?;
+    b.advance(4)?;
+    b.token("STRING", 3)?;
+    b.close()?;
+    b.close()?;
+    b.advance(3)?;
+    b.open("EMPTY")?;
+    b.close()?;
+    let tree = b.build()?;
+
+    let options = print::HtmlOptions::new().with_classifier(|value: &&str| match *value {
+        "LT" => Some("a\"b"),
+        _ => None,
+    });
+
+    let mut s = Vec::new();
+    print::to_html(&mut s, &tree, source, &options)?;
+    let s = String::from_utf8(s)?;
+
+    let expected = concat!(
+        r#"<span data-kind="node" data-value="&quot;EXPR&quot;" data-range="0..10">"#,
+        r#"<span data-kind="token" data-value="&quot;IDENT&quot;" data-range="0..1">a</span>"#,
+        r#"<span data-kind="token" data-value="&quot;LT&quot;" data-range="1..2" class="a&quot;b">&lt;</span>"#,
+        r#"<span data-kind="node" data-value="&quot;&lt;&amp;\&quot;&gt;&quot;" data-range="2..10">"#,
+        r#"<span data-kind="token" data-value="&quot;IDENT&quot;" data-range="2..3">b</span>"#,
+        r#" &amp;&amp; "#,
+        r#"<span data-kind="token" data-value="&quot;STRING&quot;" data-range="7..10">&quot;c&quot;</span>"#,
+        r#"</span></span>"#,
+        r#" &gt; "#,
+        r#"<span data-kind="node" data-value="&quot;EMPTY&quot;" data-range="13..13"></span>"#,
+        r#"&#39;d&#39;"#,
+    );
+
+    assert_eq!(s, expected);
+    assert_eq!(html_text(&s), source);
+
+    let mut s = Vec::new();
+    assert!(print::to_html(&mut s, &tree, "a<b", &print::HtmlOptions::new()).is_err());
+    Ok(())
+}
+
+/// A graph node or edge parsed from a line of `to_dot` output.
+#[derive(Debug, PartialEq, Eq)]
+enum DotLine {
+    Node(usize, String, String),
+    Edge(usize, usize),
+}
+
+/// Parse a statement emitted by `to_dot`, unescaping the label.
+fn parse_dot_line(line: &str) -> Option<DotLine> {
+    let line = line.strip_prefix("  ")?.strip_suffix(';')?;
+
+    if let Some((from, to)) = line.split_once(" -> ") {
+        return Some(DotLine::Edge(from.parse().ok()?, to.parse().ok()?));
+    }
+
+    let (id, rest) = line.split_once(" [label=\"")?;
+    let (label, shape) = rest.rsplit_once("\", shape=")?;
+    let shape = shape.strip_suffix(']')?;
+
+    let mut out = String::new();
+    let mut chars = label.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => out.push('\n'),
+                c @ ('"' | '\\') => out.push(c),
+                _ => return None,
+            },
+            // Quotes must be escaped inside of the label.
+            '"' => return None,
+            c => out.push(c),
+        }
+    }
+
+    Some(DotLine::Node(id.parse().ok()?, out, shape.to_owned()))
+}
+
+#[test]
+fn to_dot_structure() -> Result<()> {
+    let mut b = syntree::Builder::new();
+    b.open("fn")?;
+    b.token("a\"b\\c", 2)?;
+    let c = b.checkpoint()?;
+    b.token("x\ny", 1)?;
+    b.open("empty")?;
+    b.close()?;
+    b.token("z", 1)?;
+    b.close_at(&c, "wrap")?;
+    b.close()?;
+    b.token("eof", 0)?;
+    let tree = b.build()?;
+
+    let mut s = Vec::new();
+    print::to_dot(&mut s, &tree)?;
+    let s = String::from_utf8(s)?;
+
+    let mut lines = s.lines();
+    assert_eq!(lines.next(), Some("digraph {"));
+    assert_eq!(lines.next(), Some("  ordering=out;"));
+    assert_eq!(lines.next_back(), Some("}"));
+
+    let parsed = lines
+        .map(|line| parse_dot_line(line).ok_or(anyhow::anyhow!("bad line: {line:?}")))
+        .collect::<Result<Vec<_>>>()?;
+
+    // One declaration per element, identified by its id, in walk order and
+    // before any edge which refers to it. Every child gets an edge from its
+    // parent, in child order.
+    let mut expected = Vec::new();
+
+    for node in tree.walk() {
+        let shape = if node.kind() == syntree::Kind::Token {
+            "ellipse"
+        } else {
+            "box"
+        };
+
+        let label = format!("{:?}@{:?}", node.value(), node.range());
+        expected.push(DotLine::Node(node.id().index(), label, shape.to_owned()));
+
+        if let Some(parent) = node.parent() {
+            expected.push(DotLine::Edge(parent.id().index(), node.id().index()));
+        }
+    }
+
+    assert_eq!(parsed, expected);
+
+    // The checkpoint means that ids are not in walk order, so identifiers
+    // really are the ids of the elements.
+    let ids = parsed
+        .iter()
+        .filter_map(|line| match line {
+            DotLine::Node(id, ..) => Some(*id),
+            DotLine::Edge(..) => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(ids, [0, 1, 5, 2, 3, 4, 6]);
+
+    let children = |id: usize| {
+        parsed
+            .iter()
+            .filter_map(move |line| match *line {
+                DotLine::Edge(from, to) if from == id => Some(to),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(children(0), [1, 5]);
+    assert_eq!(children(5), [2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn labels_are_one_to_one() -> Result<()> {
+    let mut labels = Labels::new();
+    labels.insert(1u32, "NUMBER");
+    labels.insert(2u32, "IDENT");
+
+    // Moving a label to another value removes it from the previous one.
+    assert_eq!(labels.insert(2u32, "NUMBER").as_deref(), Some("IDENT"));
+    assert_eq!(labels.label(&1), None);
+    assert_eq!(labels.label(&2), Some("NUMBER"));
+    assert_eq!(labels.value("NUMBER"), Some(&2));
+    assert_eq!(labels.value("IDENT"), None);
+
+    let tree = syntree::tree! {
+        1u32 => {
+            (2u32, 1),
+        }
+    };
+
+    let mut s = Vec::new();
+    print::print_labeled(&mut s, &tree, &labels)?;
+    let s = String::from_utf8(s)?;
+    assert!(s.contains("NUMBER"), "{s}");
+    assert_eq!(s.matches("NUMBER").count(), 1, "{s}");
+    Ok(())
+}
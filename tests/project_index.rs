@@ -0,0 +1,65 @@
+//! Exercises the `project_index` example.
+
+#[allow(dead_code)]
+#[path = "../examples/project_index/index.rs"]
+mod index;
+
+use anyhow::{Context, Result};
+
+use self::index::{Project, Symbol, Syntax};
+
+const FILES: &[(&str, &str)] = &[
+    ("math.txt", "def add\ndef sub\n"),
+    ("main.txt", "use add\n  use mul\nuse div\n"),
+    ("util.txt", "def mul\ndef add\n"),
+];
+
+#[test]
+fn resolve_across_files() -> Result<()> {
+    let project = Project::new(FILES)?;
+
+    let uses = project.uses(1).collect::<Vec<_>>();
+    assert_eq!(uses.len(), 3);
+
+    let add = project.resolution(uses[0]).context("add is unresolved")?;
+    assert_eq!(add, project.definition("add").context("missing add")?);
+    assert_eq!(add.file, 0);
+
+    let mul = project.resolution(uses[1]).context("mul is unresolved")?;
+    assert_eq!(mul.file, 2);
+
+    let node = project.node(mul).context("missing mul")?;
+    assert_eq!(*node.value(), Syntax::Ident);
+    assert_eq!(project.files[2].text(node), "mul");
+    assert_eq!(project.files[2].position(node), "util.txt:1:5");
+
+    assert_eq!(project.resolution(uses[2]), None);
+
+    // Identifiers of one file don't resolve in another.
+    assert_eq!(
+        project.resolution(Symbol {
+            file: 0,
+            id: uses[0].id
+        }),
+        None
+    );
+
+    Ok(())
+}
+
+#[test]
+fn render_diagnostics() -> Result<()> {
+    let project = Project::new(FILES)?;
+
+    let expected = "\
+main.txt:3:5: error: unresolved name `div`
+  | use div
+  |     ^^^
+util.txt:2:5: error: `add` is already defined at math.txt:1:5
+  | def add
+  |     ^^^
+";
+
+    assert_eq!(project.render_diagnostics(), expected);
+    Ok(())
+}
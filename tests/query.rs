@@ -0,0 +1,154 @@
+use syntree::query::Query;
+use syntree::{Kind, Span, Tree};
+
+fn fixture() -> Tree<&'static str, Span> {
+    syntree::tree! {
+        "fn" => {
+            ("keyword", 2),
+            "ident" => { ("name", 4) },
+            "block" => {
+                "let" => {
+                    "ident" => { ("name", 1) },
+                    ("eq", 1),
+                    "call" => {
+                        "ident" => { ("name", 3) },
+                        "args" => {
+                            "number" => { ("lit", 1) },
+                            ("comma", 1),
+                            "call" => {
+                                "ident" => { ("name", 1) },
+                                "args" => {},
+                            },
+                        },
+                    },
+                },
+                "call" => {
+                    "path" => { ("name", 2) },
+                    "args" => {
+                        "string" => { ("lit", 5) },
+                    },
+                },
+            },
+        },
+        ("whitespace", 1),
+    }
+}
+
+fn run(tree: &Tree<&'static str, Span>, query: &Query<&'static str>) -> Vec<(&'static str, usize)> {
+    tree.query(query)
+        .map(|n| (*n.value(), n.id().index()))
+        .collect()
+}
+
+#[test]
+fn queries() {
+    let tree = fixture();
+
+    // Everything, in document order.
+    let all = run(&tree, &Query::new());
+    let walk = tree
+        .walk()
+        .map(|n| (*n.value(), n.id().index()))
+        .collect::<Vec<_>>();
+    assert_eq!(all, walk);
+
+    // Only tokens.
+    let tokens = run(&tree, &Query::new().kind(Kind::Token));
+    assert_eq!(tokens.len(), tree.walk().only_tokens().count());
+
+    // Nodes by value.
+    let calls = run(&tree, &Query::new().value("call"));
+    assert_eq!(calls, [("call", 9), ("call", 16), ("call", 20)]);
+
+    // No matches.
+    assert!(run(&tree, &Query::new().value("struct")).is_empty());
+    assert!(run(&tree, &Query::new().value("call").kind(Kind::Token)).is_empty());
+
+    // Calls of a plain identifier.
+    let query = Query::new()
+        .value("call")
+        .child(Query::new().value("ident"));
+    assert_eq!(run(&tree, &query), [("call", 9), ("call", 16)]);
+
+    // Calls with at least one argument.
+    let query = Query::new().value("call").child(
+        Query::new()
+            .value("args")
+            .child(Query::new().kind(Kind::Node)),
+    );
+    assert_eq!(run(&tree, &query), [("call", 9), ("call", 20)]);
+
+    // Overlapping matches: nested calls both contain a call to an ident.
+    let query = Query::new().descendant(
+        Query::new()
+            .value("call")
+            .child(Query::new().value("ident")),
+    );
+    assert_eq!(
+        run(&tree, &query),
+        [
+            ("fn", 0),
+            ("block", 4),
+            ("let", 5),
+            ("call", 9),
+            ("args", 12)
+        ]
+    );
+
+    // Descendants don't include the node itself.
+    let query = Query::new()
+        .value("number")
+        .descendant(Query::new().value("number"));
+    assert!(run(&tree, &query).is_empty());
+
+    // Containing a token at any depth.
+    let query = Query::new().value("call").has_token("comma");
+    assert_eq!(run(&tree, &query), [("call", 9)]);
+
+    let query = Query::new().kind(Kind::Node).has_token("lit");
+    assert_eq!(
+        run(&tree, &query),
+        [
+            ("fn", 0),
+            ("block", 4),
+            ("let", 5),
+            ("call", 9),
+            ("args", 12),
+            ("number", 13),
+            ("call", 20),
+            ("args", 23),
+            ("string", 24),
+        ]
+    );
+
+    // Tokens never contain tokens, not even themselves.
+    let query = Query::new().kind(Kind::Token).has_token("lit");
+    assert!(run(&tree, &query).is_empty());
+
+    // Queries compose through shared subqueries.
+    let ident = Query::new().value("ident");
+    let let_binding = Query::new().value("let").child(ident.clone());
+    let named_fn = Query::new().value("fn").child(ident);
+    assert_eq!(run(&tree, &let_binding), [("let", 5)]);
+    assert_eq!(run(&tree, &named_fn), [("fn", 0)]);
+}
+
+#[test]
+fn queries_are_reusable() {
+    let query = Query::new().value("call").has_token("lit");
+    let cloned = query.clone();
+
+    let a = fixture();
+    let b = syntree::tree! {
+        "call" => {
+            ("lit", 1),
+        },
+    };
+
+    assert_eq!(run(&a, &query).len(), 2);
+    assert_eq!(run(&b, &query), [("call", 0)]);
+    assert_eq!(run(&b, &cloned), [("call", 0)]);
+
+    let tree = Tree::<&'static str, Span>::default();
+    assert!(tree.query(&query).next().is_none());
+}
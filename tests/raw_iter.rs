@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use syntree::{Builder, Kind};
+
+#[test]
+fn raw_iter_counts_match_walk() -> Result<()> {
+    let mut b = Builder::new();
+
+    for n in 0..32usize {
+        let c = b.checkpoint()?;
+
+        for m in 0..n % 5 {
+            b.open("number")?;
+            b.token("lit", m + 1)?;
+            b.close()?;
+            b.token("whitespace", 1)?;
+        }
+
+        b.close_at(&c, if n % 2 == 0 { "even" } else { "odd" })?;
+    }
+
+    let tree = b.build()?;
+
+    let mut walk = HashMap::<_, usize>::new();
+    let mut walk_width = 0;
+
+    for node in tree.walk() {
+        *walk
+            .entry((node.kind() == Kind::Token, *node.value()))
+            .or_default() += 1;
+
+        if node.kind() == Kind::Token {
+            walk_width += node.range().len();
+        }
+    }
+
+    let mut raw = HashMap::<_, usize>::new();
+    let mut raw_width = 0;
+
+    for entry in tree.raw_iter() {
+        *raw.entry((entry.kind() == Kind::Token, *entry.value()))
+            .or_default() += 1;
+
+        if entry.kind() == Kind::Token {
+            raw_width += entry.span().range().len();
+        }
+
+        let node = tree.get(entry.id()).expect("missing node");
+        assert_eq!(node.value(), entry.value());
+        assert_eq!(node.parent().map(|n| n.id()), entry.parent());
+    }
+
+    assert_eq!(walk, raw);
+    assert_eq!(walk_width, raw_width);
+    assert_eq!(tree.raw_iter().len(), tree.len());
+    assert_eq!(tree.raw_iter().rev().count(), tree.len());
+    Ok(())
+}
+
+#[test]
+fn flat_iterators_follow_storage_order() -> Result<()> {
+    let mut b = Builder::new();
+
+    let c = b.checkpoint()?;
+    b.token("lit", 1)?;
+    b.token("ws", 2)?;
+    b.close_at(&c, "root")?;
+    b.token("eof", 0)?;
+
+    let tree = b.build()?;
+
+    let raw = tree.raw_iter().collect::<Vec<_>>();
+
+    assert_eq!(tree.values().len(), tree.len());
+    assert_eq!(tree.spans().len(), tree.len());
+    assert_eq!(tree.entries().len(), tree.len());
+
+    assert!(tree.values().eq(raw.iter().map(|e| e.value())));
+    assert!(tree.spans().eq(raw.iter().map(|e| e.span())));
+    assert!(tree
+        .entries()
+        .eq(raw.iter().map(|e| (e.id(), e.value(), e.kind(), e.span()))));
+
+    // Storage order differs from document order when wrapping nodes.
+    assert_eq!(
+        tree.values().copied().collect::<Vec<_>>(),
+        ["lit", "ws", "root", "eof"]
+    );
+    assert_eq!(
+        tree.walk().map(|n| *n.value()).collect::<Vec<_>>(),
+        ["root", "lit", "ws", "eof"]
+    );
+
+    assert_eq!(tree.values().next_back(), Some(&"eof"));
+    assert_eq!(
+        tree.into_values().rev().collect::<Vec<_>>(),
+        ["eof", "root", "ws", "lit"]
+    );
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use syntree::{Builder, Id, Node, Tree};
+
+fn fixture() -> Result<Tree<&'static str>> {
+    let mut b = Builder::new();
+
+    b.open("fn")?;
+    b.token("ident", 3)?;
+    let c = b.checkpoint()?;
+    b.token("open", 1)?;
+    b.open("stmt")?;
+    b.token("number", 2)?;
+    b.token("semi", 1)?;
+    b.close()?;
+    b.open("empty")?;
+    b.close()?;
+    b.token("close", 1)?;
+    b.close_at(&c, "block")?;
+    b.close()?;
+    b.token("ws", 1)?;
+    b.open("struct")?;
+    b.token("ident", 2)?;
+    b.close()?;
+    b.token("eof", 0)?;
+
+    Ok(b.build()?)
+}
+
+fn ids<'a, I>(nodes: I) -> Vec<Id>
+where
+    I: IntoIterator<Item = Node<'a, &'static str>>,
+{
+    nodes.into_iter().map(|n| n.id()).collect()
+}
+
+#[test]
+fn raw_tree_matches_nodes() -> Result<()> {
+    let tree = fixture()?;
+    let raw = tree.as_raw();
+
+    assert_eq!(raw.len(), tree.len());
+    assert_eq!(raw.is_empty(), tree.is_empty());
+    assert_eq!(raw.first(), tree.first().map(|n| n.id()));
+    assert_eq!(raw.last(), tree.last().map(|n| n.id()));
+
+    assert_eq!(raw.values().len(), tree.len());
+    assert_eq!(raw.kinds().len(), tree.len());
+    assert_eq!(raw.spans().len(), tree.len());
+
+    let columns = raw.values().zip(raw.kinds()).zip(raw.spans());
+    let mut seen = 0;
+
+    for (index, ((value, kind), span)) in columns.enumerate() {
+        let id = Id::from_index(index).ok_or(anyhow::anyhow!("bad index"))?;
+        let node = tree.get(id).ok_or(anyhow::anyhow!("missing node"))?;
+
+        assert_eq!(value, node.value());
+        assert_eq!(kind, node.kind());
+        assert_eq!(span, node.span());
+
+        assert_eq!(raw.value_of(id), Some(node.value()));
+        assert_eq!(raw.kind_of(id), Some(node.kind()));
+        assert_eq!(raw.span_of(id), Some(node.span()));
+        assert_eq!(raw.parent_of(id), node.parent().map(|n| n.id()));
+        assert_eq!(raw.prev_of(id), node.prev().map(|n| n.id()));
+        assert_eq!(raw.next_of(id), node.next().map(|n| n.id()));
+        assert_eq!(raw.first_of(id), node.first().map(|n| n.id()));
+        assert_eq!(raw.last_of(id), node.last().map(|n| n.id()));
+        assert_eq!(raw.children_len_of(id), Some(node.children().len()));
+        seen += 1;
+    }
+
+    assert_eq!(seen, tree.walk().count());
+
+    let missing = Id::from_index(tree.len()).ok_or(anyhow::anyhow!("bad index"))?;
+    assert_eq!(raw.value_of(missing), None);
+    assert_eq!(raw.parent_of(missing), None);
+    assert_eq!(raw.children_len_of(missing), None);
+    Ok(())
+}
+
+#[test]
+fn raw_tree_storage_order() -> Result<()> {
+    let tree = fixture()?;
+    let raw = tree.as_raw();
+
+    // Storage follows allocation order, so the block created through a
+    // checkpoint is stored after its children.
+    assert_eq!(
+        raw.values().copied().collect::<Vec<_>>(),
+        [
+            "fn", "ident", "open", "stmt", "number", "semi", "empty", "close", "block", "ws",
+            "struct", "ident", "eof"
+        ]
+    );
+
+    // Children can be navigated through identifiers alone.
+    let root = raw.first().ok_or(anyhow::anyhow!("missing root"))?;
+    let mut roots = Vec::new();
+    let mut next = Some(root);
+
+    while let Some(id) = next {
+        roots.push(id);
+        next = raw.next_of(id);
+    }
+
+    assert_eq!(roots, ids(tree.children()));
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anyhow::Result;
+use syntree::{Builder, Error};
+
+#[test]
+fn recover_success() -> Result<()> {
+    let mut tree = Builder::new();
+
+    let id = tree.open_recover("root", "error", |tree| {
+        tree.token("lit", 3)?;
+        Ok::<_, Error>(())
+    })?;
+
+    assert!(tree.take_recovered_errors().is_empty());
+
+    let tree = tree.build()?;
+
+    let expected = syntree::tree! {
+        "root" => {
+            ("lit", 3),
+        }
+    };
+
+    assert_eq!(tree, expected);
+    assert_eq!(tree.first().map(|n| n.id()), Some(id));
+    Ok(())
+}
+
+#[test]
+fn recover_partial_children() -> Result<()> {
+    let mut tree = Builder::new();
+
+    let id = tree.open_recover("root", "error", |tree| {
+        tree.token("lit", 3)?;
+        tree.open("a")?;
+        tree.open("b")?;
+        tree.token("lit", 1)?;
+        Err(anyhow::anyhow!("oops"))
+    })?;
+
+    tree.token("after", 2)?;
+
+    let errors = tree.take_recovered_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].id(), id);
+    assert_eq!(errors[0].message(), "oops");
+
+    let tree = tree.build()?;
+
+    let expected = syntree::tree! {
+        "root" => {
+            ("lit", 3),
+            "a" => {
+                "b" => {
+                    ("lit", 1),
+                }
+            },
+            ("error", 0),
+        },
+        ("after", 2),
+    };
+
+    assert_eq!(tree, expected);
+    Ok(())
+}
+
+#[test]
+fn recover_nested() -> Result<()> {
+    let mut tree = Builder::new();
+
+    let outer = tree.open_recover("outer", "error", |tree| {
+        tree.open_recover("inner", "error", |tree| {
+            tree.token("lit", 1)?;
+            Err(anyhow::anyhow!("inner failed"))
+        })?;
+
+        tree.token("lit", 2)?;
+        Err(Error::BuildError)
+    })?;
+
+    let errors = tree.take_recovered_errors();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].message(), "inner failed");
+    assert_eq!(errors[1].id(), outer);
+    assert_eq!(errors[1].message(), Error::BuildError.to_string());
+
+    let tree = tree.build()?;
+
+    let expected = syntree::tree! {
+        "outer" => {
+            "inner" => {
+                ("lit", 1),
+                ("error", 0),
+            },
+            ("lit", 2),
+            ("error", 0),
+        }
+    };
+
+    assert_eq!(tree, expected);
+    Ok(())
+}
+
+#[test]
+fn recover_body_closes_node() -> Result<()> {
+    let mut tree = Builder::new();
+
+    let result = tree.open_recover("root", "error", |tree| {
+        tree.close()?;
+        Ok::<_, Error>(())
+    });
+
+    assert_eq!(result, Err(Error::CloseError));
+    Ok(())
+}
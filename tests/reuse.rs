@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use syntree::{Builder, BuilderLimits, CursorPolicy, Error, Kind, Node, Span, Tree};
+
+fn replay<'a>(b: &mut Builder<&'a str>, node: Node<'_, &'a str>) -> Result<()> {
+    match node.kind() {
+        Kind::Token => {
+            b.token(node.value(), node.range().len())?;
+        }
+        _ => {
+            b.open(node.value())?;
+
+            for child in node.children() {
+                replay(b, child)?;
+            }
+
+            b.close()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn old_tree() -> Result<Tree<&'static str>> {
+    let mut b = Builder::new();
+
+    b.open("root")?;
+
+    // Built through a checkpoint, so not stored contiguously.
+    let c = b.checkpoint()?;
+    b.token("lit", 2)?;
+    b.token("op", 1)?;
+    b.token("lit", 3)?;
+    b.close_at(&c, "binary")?;
+
+    b.token("ws", 1)?;
+
+    b.open("call")?;
+    b.token("ident", 4)?;
+    b.open("args")?;
+    b.token("lit", 1)?;
+    b.token_empty("missing")?;
+    b.close()?;
+    b.close()?;
+
+    b.close()?;
+
+    Ok(b.build()?)
+}
+
+#[test]
+fn reuse_matches_replay() -> Result<()> {
+    let old = old_tree()?;
+
+    let mut reused = Builder::new();
+    let mut replayed = Builder::new();
+
+    reused.open("root")?;
+    replayed.open("root")?;
+
+    reused.token("new", 5)?;
+    replayed.token("new", 5)?;
+
+    for node in old.reusable_nodes(0..0) {
+        reused.reuse(node)?;
+        replay(&mut replayed, node)?;
+    }
+
+    reused.close()?;
+    replayed.close()?;
+
+    let reused = reused.build()?;
+    let replayed = replayed.build()?;
+
+    assert_eq!(reused, replayed);
+    assert_eq!(reused.span(), replayed.span());
+
+    for (a, b) in reused.walk().zip(replayed.walk()) {
+        assert_eq!(a.range(), b.range());
+        assert_eq!(
+            a.parent().map(|n| *n.value()),
+            b.parent().map(|n| *n.value())
+        );
+        assert_eq!(a.prev().map(|n| *n.value()), b.prev().map(|n| *n.value()));
+        assert_eq!(a.next().map(|n| *n.value()), b.next().map(|n| *n.value()));
+    }
+
+    for index in 0..reused.span().range().end {
+        let a = reused.node_with_range(index..index + 1).map(|n| *n.value());
+        let b = replayed
+            .node_with_range(index..index + 1)
+            .map(|n| *n.value());
+        assert_eq!(a, b);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn reuse_roots() -> Result<()> {
+    let old = old_tree()?;
+    let root = old.first().ok_or(anyhow::anyhow!("missing root"))?;
+
+    let mut b = Builder::new();
+    b.token("before", 2)?;
+    let id = b.reuse(root)?;
+    b.reuse(root)?;
+    b.token("after", 1)?;
+    let tree = b.build()?;
+
+    let root = tree.get(id).ok_or(anyhow::anyhow!("missing reused root"))?;
+    assert_eq!(*root.value(), "root");
+    assert_eq!(root.range(), 2..14);
+    assert_eq!(tree.span().range(), 0..27);
+    assert_eq!(tree.children().count(), 4);
+    assert_eq!(tree.last().map(|n| n.range()), Some(26..27));
+    Ok(())
+}
+
+#[test]
+fn reuse_keeps_gaps() -> Result<()> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+    b.open("root")?;
+    b.token("a", 1)?;
+    b.advance(2)?;
+    b.open("group")?;
+    b.token("b", 1)?;
+    b.advance(2)?;
+    b.open("empty")?;
+    b.close()?;
+    b.token_with_span("c", Span::new(7, 8))?;
+    b.close()?;
+    b.close()?;
+    let old = b.build()?;
+
+    let group = old.node_with_range(3..8).context("missing group")?;
+    assert_eq!(*group.value(), "group");
+
+    // Once through the fast path and once replayed because of limits.
+    for limits in [None, Some(BuilderLimits::new().max_nodes(100))] {
+        let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+        if let Some(limits) = limits {
+            b = b.with_limits(limits);
+        }
+
+        b.token("x", 2)?;
+        b.reuse(group)?;
+        let tree = b.build()?;
+
+        let spans = tree
+            .walk()
+            .map(|n| (*n.value(), n.range()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            spans,
+            [
+                ("x", 0..2),
+                ("group", 2..7),
+                ("b", 2..3),
+                ("empty", 5..5),
+                ("c", 6..7)
+            ],
+            "{limits:?}"
+        );
+
+        // Gaps are rejected by the default policy in both cases.
+        let mut b = Builder::new();
+
+        if let Some(limits) = limits {
+            b = b.with_limits(limits);
+        }
+
+        b.token("x", 2)?;
+        assert_eq!(
+            b.reuse(group),
+            Err(Error::CursorGap {
+                cursor: 3,
+                start: 5
+            }),
+            "{limits:?}"
+        );
+    }
+
+    Ok(())
+}
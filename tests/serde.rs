@@ -0,0 +1,75 @@
+#![cfg(feature = "serde")]
+
+use anyhow::Result;
+use syntree::{Error, OwnedEvent, Page, Span, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
+enum Syntax {
+    ROOT,
+    NUMBER,
+    LIT,
+    WHITESPACE,
+}
+
+use Syntax::{LIT, NUMBER, ROOT, WHITESPACE};
+
+#[test]
+fn owned_events_roundtrip() -> Result<()> {
+    let tree = syntree::tree! {
+        ROOT => {
+            NUMBER => {
+                (LIT, 2)
+            },
+            (WHITESPACE, 3),
+            NUMBER => {}
+        },
+        (WHITESPACE, 1)
+    };
+
+    let json = serde_json::to_string(&tree.to_owned_events())?;
+    let events: Vec<OwnedEvent<Syntax, Span>> = serde_json::from_str(&json)?;
+    assert_eq!(events, tree.to_owned_events());
+    assert_eq!(Tree::from_owned_events(events)?, tree);
+
+    let mut streamed = Vec::new();
+    tree.write_events(&mut serde_json::Serializer::new(&mut streamed))?;
+    assert_eq!(std::str::from_utf8(&streamed)?, json);
+    Ok(())
+}
+
+#[test]
+fn owned_events_unbalanced() -> Result<()> {
+    let json = r#"[{"Enter":["ROOT",{"start":0,"end":0}]},{"Token":["LIT",{"start":0,"end":2}]}]"#;
+    let events: Vec<OwnedEvent<Syntax, Span>> = serde_json::from_str(json)?;
+    assert_eq!(Tree::from_owned_events(events), Err(Error::BuildError));
+
+    let json = r#"[{"Token":["LIT",{"start":0,"end":2}]},"Leave"]"#;
+    let events: Vec<OwnedEvent<Syntax, Span>> = serde_json::from_str(json)?;
+    assert_eq!(Tree::from_owned_events(events), Err(Error::CloseError));
+    Ok(())
+}
+
+#[test]
+fn page_roundtrip() -> Result<()> {
+    let tree = syntree::tree! {
+        ROOT => {
+            NUMBER => {
+                (LIT, 2)
+            },
+            (WHITESPACE, 3),
+            NUMBER => {}
+        },
+    };
+
+    let root = tree.first().map(|n| n.id());
+    let page = tree.children_page(root, None, 2, |v| *v)?;
+
+    let json = serde_json::to_string(&page)?;
+    let decoded: Page<Syntax, Span> = serde_json::from_str(&json)?;
+    assert_eq!(decoded, page);
+
+    let next = tree.children_page(root, decoded.after(), 2, |v| *v)?;
+    assert_eq!(next.items().len(), 1);
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use anyhow::Result;
+use syntree::{Builder, Node, Postfix, Tree};
+
+mod common;
+
+/// Check that every child iterator in the tree reports its exact length.
+fn check_children<T, S>(tree: &Tree<T, S>)
+where
+    S: syntree::span::TreeSpan,
+{
+    assert_eq!(tree.children().len(), tree.children().count());
+    assert_eq!(tree.walk().size_hint(), (tree.len(), Some(tree.len())));
+    assert_eq!(tree.walk().count(), tree.len());
+
+    for node in tree.walk() {
+        check_node(node);
+    }
+}
+
+fn check_node<T, S>(node: Node<'_, T, S>) {
+    let len = node.children().len();
+    assert_eq!(len, node.children().count());
+    assert_eq!(node.children().size_hint(), (len, Some(len)));
+
+    let (lower, upper) = node.siblings().size_hint();
+    let count = node.siblings().count();
+    assert!(lower <= count && upper.map_or(false, |upper| count <= upper));
+
+    let (lower, upper) = node.walk().size_hint();
+    let count = node.walk().count();
+    assert!(lower <= count && upper.map_or(false, |upper| count <= upper));
+}
+
+#[test]
+fn children_len() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("t1", 1),
+            "child" => {
+                ("t2", 1),
+            },
+            ("t3", 1),
+            "empty" => {},
+        },
+        ("t4", 1)
+    };
+
+    assert_eq!(tree.children().len(), 2);
+
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    let mut it = root.children();
+    assert_eq!(it.len(), 4);
+    assert!(it.next().is_some());
+    assert_eq!(it.len(), 3);
+    assert!(it.next_back().is_some());
+    assert_eq!(it.len(), 2);
+    assert_eq!(it.count(), 2);
+
+    // Skipping tokens can no longer be exact.
+    let skip = root.children().skip_tokens();
+    assert_eq!(skip.size_hint(), (0, Some(4)));
+    assert_eq!(skip.count(), 2);
+
+    check_children(&tree);
+    Ok(())
+}
+
+#[test]
+fn children_len_close_at() -> Result<()> {
+    let mut b = Builder::new();
+
+    let c = b.checkpoint()?;
+    b.token("a", 1)?;
+    b.token("b", 1)?;
+    b.token("c", 1)?;
+    b.close_at(&c, "wrapped")?;
+    b.token("d", 1)?;
+
+    b.open("root")?;
+    let c = b.checkpoint()?;
+    b.token("e", 1)?;
+    b.open("inner")?;
+    b.close()?;
+    b.close_at(&c, "wrapped")?;
+    b.close()?;
+
+    let tree = b.build()?;
+
+    assert_eq!(tree.children().len(), 3);
+
+    let wrapped = tree.first().ok_or(anyhow::anyhow!("missing wrapped"))?;
+    assert_eq!(wrapped.children().len(), 3);
+
+    let root = tree.last().ok_or(anyhow::anyhow!("missing root"))?;
+    assert_eq!(root.children().len(), 1);
+
+    check_children(&tree);
+    Ok(())
+}
+
+#[test]
+fn children_len_reuse() -> Result<()> {
+    let source = syntree::tree! {
+        "root" => {
+            "item" => {
+                ("a", 1),
+                ("b", 1),
+            }
+        }
+    };
+
+    let item = source
+        .first()
+        .and_then(|n| n.first())
+        .ok_or(anyhow::anyhow!("missing item"))?;
+
+    let mut b = Builder::new();
+    b.open("root")?;
+    b.token("c", 1)?;
+    b.reuse(item)?;
+    b.close()?;
+    b.reuse(item)?;
+    let tree = b.build()?;
+
+    assert_eq!(tree.children().len(), 2);
+    check_children(&tree);
+    Ok(())
+}
+
+#[test]
+fn children_len_postfix() -> Result<()> {
+    let mut b = Builder::new();
+    b.push_postfix(Postfix::Operand {
+        value: "number",
+        len: 1,
+    })?;
+    b.push_postfix(Postfix::Operand {
+        value: "number",
+        len: 1,
+    })?;
+    b.push_postfix(Postfix::Operator {
+        value: "add",
+        arity: 2,
+    })?;
+    b.push_postfix(Postfix::Operand {
+        value: "number",
+        len: 1,
+    })?;
+    b.push_postfix(Postfix::Operator {
+        value: "mul",
+        arity: 2,
+    })?;
+    b.finish_postfix()?;
+    let tree = b.build()?;
+
+    assert_eq!(tree.children().len(), 1);
+    check_children(&tree);
+    Ok(())
+}
+
+#[test]
+fn children_len_generated() -> Result<()> {
+    for seed in 1..=64 {
+        check_children(&common::generate(seed)?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use anyhow::Result;
+use syntree::print::{self, SourceOptions};
+use syntree::{Source, Span, Tree};
+
+/// A source stored in fixed-size chunks, which assembles slices piece by
+/// piece.
+struct Chunked {
+    chunks: Vec<Vec<u8>>,
+    size: usize,
+}
+
+impl Chunked {
+    fn new(source: &str, size: usize) -> Self {
+        Self {
+            chunks: source.as_bytes().chunks(size).map(<[u8]>::to_vec).collect(),
+            size,
+        }
+    }
+
+    fn is_char_boundary(&self, at: usize) -> bool {
+        let byte = self
+            .chunks
+            .get(at / self.size)
+            .and_then(|c| c.get(at % self.size));
+        byte.map_or(true, |&b| (b as i8) >= -0x40)
+    }
+}
+
+impl Source for Chunked {
+    fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+        let len = self.chunks.iter().map(Vec::len).sum::<usize>();
+
+        if range.start > range.end || range.end > len {
+            return None;
+        }
+
+        if !self.is_char_boundary(range.start) || !self.is_char_boundary(range.end) {
+            return None;
+        }
+
+        let mut bytes = Vec::with_capacity(range.len());
+        let mut at = range.start;
+
+        while at < range.end {
+            let chunk = self.chunks.get(at / self.size)?;
+            let start = at % self.size;
+            let end = (start + (range.end - at)).min(chunk.len());
+            bytes.extend_from_slice(chunk.get(start..end)?);
+            at += end - start;
+        }
+
+        Some(Cow::Owned(String::from_utf8(bytes).ok()?))
+    }
+}
+
+fn fixture() -> Tree<&'static str, Span> {
+    syntree::tree! {
+        "STRING" => {
+            ("LIT", 15),
+            ("NEWLINE", 1),
+        },
+        ("E", 2),
+        ("EOF", 0),
+    }
+}
+
+const SOURCE: &str = "\"a long string\"\né";
+
+fn render(print: impl Fn(&mut Vec<u8>) -> std::io::Result<()>) -> Result<String> {
+    let mut out = Vec::new();
+    print(&mut out)?;
+    Ok(String::from_utf8(out)?)
+}
+
+#[test]
+fn chunked_slices() {
+    for size in 1..=SOURCE.len() {
+        let source = Chunked::new(SOURCE, size);
+
+        for start in 0..=SOURCE.len() {
+            for end in start..=SOURCE.len() {
+                assert_eq!(
+                    source.slice(start..end).as_deref(),
+                    SOURCE.get(start..end),
+                    "{size}: {start}..{end}"
+                );
+            }
+        }
+
+        assert_eq!(source.slice(0..SOURCE.len() + 1), None);
+    }
+}
+
+#[test]
+fn print_chunked() -> Result<()> {
+    let tree = fixture();
+    let options = SourceOptions::new().with_max_len(8);
+
+    for size in [1, 2, 3, 7, 64] {
+        let source = Chunked::new(SOURCE, size);
+
+        assert_eq!(
+            render(|o| print::print_with_source(o, &tree, &source))?,
+            render(|o| print::print_with_source(o, &tree, SOURCE))?,
+        );
+
+        assert_eq!(
+            render(|o| print::print_with_source_options(o, &tree, &source, &options))?,
+            render(|o| print::print_with_source_options(o, &tree, SOURCE, &options))?,
+        );
+
+        assert_eq!(
+            render(|o| print::to_sexpr(o, &tree, &source))?,
+            render(|o| print::to_sexpr(o, &tree, SOURCE))?,
+        );
+
+        assert_eq!(
+            render(|o| print::to_json_with_source(o, &tree, &source))?,
+            render(|o| print::to_json_with_source(o, &tree, SOURCE))?,
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn print_owned_string() -> Result<()> {
+    let tree = fixture();
+    let source = String::from(SOURCE);
+
+    assert_eq!(
+        render(|o| print::print_with_source(o, &tree, &source))?,
+        render(|o| print::print_with_source(o, &tree, SOURCE))?,
+    );
+
+    let out = render(|o| print::print_with_source(o, &tree, source.clone()))?;
+    assert!(out.contains("\"E\"@16..18 \"é\""));
+
+    // A span splitting a character in the chunked source is invalid.
+    let tree = syntree::tree!(("E", 1));
+    let out = render(|o| print::print_with_source(o, &tree, Chunked::new("é", 1)))?;
+    assert_eq!(out, "\"E\"@0..1 <invalid span>\n");
+    Ok(())
+}
+
+#[cfg(feature = "ropey")]
+#[test]
+fn print_rope() -> Result<()> {
+    let tree = fixture();
+    let source = ropey::Rope::from_str(SOURCE);
+
+    assert_eq!(
+        render(|o| print::print_with_source(o, &tree, &source))?,
+        render(|o| print::print_with_source(o, &tree, SOURCE))?,
+    );
+
+    assert_eq!(Source::slice(&source, 17..18), None);
+    Ok(())
+}
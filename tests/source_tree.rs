@@ -0,0 +1,107 @@
+use anyhow::{anyhow, Result};
+use syntree::{Builder, CursorPolicy, Error, Kind};
+
+#[test]
+fn source_node_text() -> Result<()> {
+    let tree = syntree::tree! {
+        "let" => {
+            ("kw", 3),
+            ("ws", 1),
+            "ident" => {
+                ("lit", 5),
+            },
+            ("eq", 1),
+            "string" => {
+                ("lit", 4),
+            },
+        },
+        ("eof", 0),
+    };
+
+    let tree = tree.with_source("letxnäme=\"ö\"")?;
+    assert_eq!(tree.source(), "letxnäme=\"ö\"");
+
+    let texts = tree
+        .walk()
+        .map(|n| (*n.value(), n.text()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        texts,
+        [
+            ("let", "letxnäme=\"ö\""),
+            ("kw", "let"),
+            ("ws", "x"),
+            ("ident", "näme"),
+            ("lit", "näme"),
+            ("eq", "="),
+            ("string", "\"ö\""),
+            ("lit", "\"ö\""),
+            ("eof", ""),
+        ]
+    );
+
+    let root = tree.first().ok_or(anyhow!("missing root"))?;
+    let ident = root
+        .children()
+        .find(|n| *n.value() == "ident")
+        .ok_or(anyhow!("missing ident"))?;
+
+    assert_eq!(ident.prev().map(|n| n.text()), Some("x"));
+    assert_eq!(ident.next().map(|n| n.text()), Some("="));
+    assert_eq!(ident.first().map(|n| n.text()), Some("näme"));
+    assert_eq!(ident.parent(), Some(root));
+    assert_eq!(tree.get(ident.id()), Some(ident));
+    assert_eq!(tree.last().map(|n| *n.value()), Some("eof"));
+
+    let range = ident.range();
+    let (tree, source) = tree.into_parts();
+    assert_eq!(source.get(range), Some("näme"));
+    assert_eq!(tree.len(), 9);
+    Ok(())
+}
+
+#[test]
+fn source_tree_token_at() -> Result<()> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    b.open("root")?;
+    b.token("a", 2)?;
+    b.token("empty", 0)?;
+    b.advance(2)?;
+    b.open("inner")?;
+    b.token("b", 3)?;
+    b.close()?;
+    b.close()?;
+
+    let tree = b.build()?.with_source("ab  cde!")?;
+    let len = tree.source().len();
+
+    for offset in 0..=len {
+        let expected = tree
+            .tree()
+            .walk()
+            .find(|n| n.kind() == Kind::Token && n.range().contains(&offset))
+            .map(|n| n.id());
+
+        let actual = tree.token_at(offset)?.map(|n| n.id());
+        assert_eq!(actual, expected, "offset {offset}");
+    }
+
+    assert_eq!(tree.token_at(5)?.map(|n| n.text()), Some("cde"));
+    assert_eq!(
+        tree.token_at(len + 1).err(),
+        Some(Error::OffsetOutOfBounds {
+            offset: len + 1,
+            len
+        })
+    );
+
+    let mut s = Vec::new();
+    tree.print(&mut s)?;
+    assert_eq!(
+        String::from_utf8(s)?,
+        "\"root\"@0..7\n  \"a\"@0..2 \"ab\"\n  \"empty\"@2..2 \"\"\n  \"inner\"@4..7\n    \"b\"@4..7 \"cde\"\n"
+    );
+    Ok(())
+}
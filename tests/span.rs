@@ -0,0 +1,173 @@
+use std::ops::Range;
+
+use syntree::span::Index;
+use syntree::Span;
+
+#[test]
+fn conversions() {
+    let span = Span::new(4, 8);
+
+    assert_eq!(Range::<usize>::from(span), 4..8);
+    assert_eq!(<(usize, usize)>::from(span), (4, 8));
+    assert_eq!(Span::from(4usize..8), span);
+    assert_eq!(Span::try_from_range(4..8), Some(span));
+
+    // Empty spans convert into empty ranges.
+    assert_eq!(Range::<usize>::from(Span::point(3)), 3..3);
+    assert_eq!(<(usize, usize)>::from(Span::point(3)), (3, 3));
+    assert_eq!(Span::try_from_range(3..3), Some(Span::point(3)));
+}
+
+#[test]
+#[allow(clippy::reversed_empty_ranges)]
+fn backwards_range() {
+    assert_eq!(Span::try_from_range(8..4), None);
+    assert!(std::panic::catch_unwind(|| Span::from(8usize..4)).is_err());
+}
+
+#[test]
+fn to_and_between() {
+    let a = Span::new(2, 4);
+    let b = Span::new(4, 6);
+
+    // Adjacent spans.
+    assert_eq!(a.to(b), Span::new(2, 6));
+    assert_eq!(a.between(b), Some(Span::point(4)));
+    assert_eq!(b.between(a), Some(Span::point(4)));
+
+    // Empty spans, both at the edges and inside of another span.
+    let empty = Span::point(4);
+    assert_eq!(a.to(empty), a);
+    assert_eq!(empty.to(b), b);
+    assert_eq!(a.between(empty), Some(empty));
+    assert_eq!(empty.between(b), Some(empty));
+    assert_eq!(empty.between(empty), Some(empty));
+    assert_eq!(Span::new(2, 6).between(empty), None);
+
+    // Overlapping spans have no gap.
+    assert_eq!(Span::new(2, 5).between(Span::new(4, 6)), None);
+    assert_eq!(Span::new(2, 5).to(Span::new(4, 6)), Span::new(2, 6));
+
+    // The same span.
+    assert_eq!(a.between(a), None);
+    assert_eq!(a.to(a), a);
+
+    assert_eq!(a.len(), 2);
+    assert!(!a.is_empty());
+    assert_eq!(empty.len(), 0);
+    assert!(empty.is_empty());
+}
+
+#[cfg(feature = "miette")]
+#[test]
+fn miette_source_span() {
+    let span = miette::SourceSpan::from(&Span::new(4, 8));
+    assert_eq!(span.offset(), 4);
+    assert_eq!(span.len(), 4);
+
+    let span = miette::SourceSpan::from(Span::point(3));
+    assert_eq!(span.offset(), 3);
+    assert!(span.is_empty());
+}
+
+#[test]
+fn const_constructors() {
+    const POINT: Span = Span::point(4);
+    const WITH_LEN: Span = Span::with_len(4, 3);
+    const SHIFTED: Span = WITH_LEN.shift(-4);
+    const LEN: Index = WITH_LEN.len();
+    const EMPTY: [bool; 2] = [POINT.is_empty(), WITH_LEN.is_empty()];
+
+    assert_eq!(POINT, Span::new(4, 4));
+    assert_eq!(WITH_LEN, Span::new(4, 7));
+    assert_eq!(SHIFTED, Span::new(0, 3));
+    assert_eq!(LEN, 3);
+    assert_eq!(EMPTY, [true, false]);
+}
+
+#[test]
+fn with_len() {
+    for start in 0..8 {
+        for len in 0..8 {
+            let span = Span::with_len(start, len);
+            assert_eq!(span, Span::new(start, start + len));
+            assert_eq!(span.len(), len);
+            assert_eq!(span.is_empty(), len == 0);
+        }
+    }
+
+    assert_eq!(Span::with_len(Index::MAX, 0), Span::point(Index::MAX));
+    assert!(std::panic::catch_unwind(|| Span::with_len(Index::MAX, 1)).is_err());
+}
+
+#[test]
+fn shift() {
+    for start in 0..8 {
+        for end in start..8 {
+            let span = Span::new(start, end);
+
+            for delta in -10isize..10 {
+                let moved = |index: Index| (index as isize + delta) as Index;
+                let expected =
+                    (start as isize + delta >= 0).then(|| Span::new(moved(start), moved(end)));
+
+                assert_eq!(span.checked_shift(delta), expected, "{span:?} {delta}");
+
+                match expected {
+                    Some(expected) => {
+                        assert_eq!(span.shift(delta), expected);
+                        assert_eq!(expected.len(), span.len());
+                        assert_eq!(expected.shift(-delta), span);
+                    }
+                    None => {
+                        assert!(std::panic::catch_unwind(|| span.shift(delta)).is_err());
+                    }
+                }
+            }
+        }
+    }
+
+    let end = Span::point(Index::MAX);
+    assert_eq!(end.checked_shift(0), Some(end));
+    assert_eq!(end.checked_shift(1), None);
+    assert_eq!(Span::point(0).checked_shift(isize::MIN), None);
+}
+
+#[test]
+fn ordering() {
+    let mut spans = Vec::new();
+
+    for start in 0..4 {
+        for end in start..4 {
+            spans.push(Span::new(start, end));
+        }
+    }
+
+    for a in &spans {
+        for b in &spans {
+            let expected = (a.start, a.end).cmp(&(b.start, b.end));
+            assert_eq!(a.cmp(b), expected, "{a:?} {b:?}");
+            assert_eq!(a.partial_cmp(b), Some(expected));
+        }
+    }
+
+    let mut sorted = spans.clone();
+    sorted.reverse();
+    sorted.sort();
+    assert_eq!(sorted, spans);
+}
+
+#[test]
+fn into_iter() {
+    for start in 0..4 {
+        for end in start..4 {
+            let span = Span::new(start, end);
+            assert!(span.into_iter().eq(Range::<usize>::from(span)));
+            assert_eq!(span.into_iter().count(), span.range().len());
+
+            for offset in span {
+                assert!(span.contains(offset as Index));
+            }
+        }
+    }
+}
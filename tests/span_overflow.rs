@@ -0,0 +1,98 @@
+use anyhow::Result;
+use syntree::span::Index;
+use syntree::{Builder, CursorPolicy, Error, Id, Span};
+
+#[allow(clippy::unnecessary_cast)]
+const MAX: usize = Index::MAX as usize;
+
+fn id(index: usize) -> Result<Id> {
+    Id::from_index(index).ok_or(anyhow::anyhow!("bad id"))
+}
+
+#[test]
+fn token_overflow() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    tree.token("lit", MAX - 1)?;
+    tree.token("lit", 1)?;
+
+    assert_eq!(
+        tree.token("lit", 1),
+        Err(Error::SpanOverflow { at: id(3)? })
+    );
+    assert_eq!(tree.advance(1), Err(Error::SpanOverflow { at: id(3)? }));
+
+    // Empty tokens still fit at the end.
+    tree.token("eof", 0)?;
+    tree.close()?;
+
+    let tree = tree.build()?;
+    tree.validate()?;
+
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    assert_eq!(root.range(), 0..MAX);
+    assert_eq!(tree.span().range(), 0..MAX);
+
+    let ranges = root.children().map(|n| n.range()).collect::<Vec<_>>();
+    assert_eq!(ranges, [0..MAX - 1, MAX - 1..MAX, MAX..MAX]);
+    Ok(())
+}
+
+#[test]
+fn length_overflow() -> Result<()> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    tree.token("lit", 1)?;
+
+    // A single length which is too large on its own, or only once added to
+    // the cursor.
+    assert_eq!(
+        tree.token("lit", usize::MAX),
+        Err(Error::SpanOverflow { at: id(2)? })
+    );
+
+    tree.close()?;
+
+    let tree = tree.build()?;
+    assert_eq!(tree.span(), &Span::new(0, 1));
+    Ok(())
+}
+
+#[test]
+fn reuse_overflow() -> Result<()> {
+    let source = syntree::tree! {
+        "number" => {
+            ("lit", 2),
+        }
+    };
+
+    let number = source.first().ok_or(anyhow::anyhow!("missing number"))?;
+
+    let mut tree = Builder::new();
+    tree.open("root")?;
+    tree.token("lit", MAX - 1)?;
+
+    assert_eq!(tree.reuse(number), Err(Error::SpanOverflow { at: id(2)? }));
+
+    // Nothing was added by the failed reuse.
+    tree.token("lit", 1)?;
+    tree.close()?;
+
+    let tree = tree.build()?;
+    tree.validate()?;
+    assert_eq!(tree.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn lexer_overflow() -> Result<()> {
+    let mut tree = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    tree.extend_from_lexer([("lit", MAX - 1..MAX)])?;
+
+    let result = tree.token("lit", 1);
+    assert_eq!(result, Err(Error::SpanOverflow { at: id(1)? }));
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use anyhow::{anyhow, Result};
+use syntree::edit::ChangeSet;
+use syntree::{Span, Tree};
+
+const SYNTHESIZED: u8 = 0b10;
+const OTHER: u8 = 0b01;
+
+fn parse() -> Result<Tree<&'static str, Span>> {
+    Ok(syntree::tree! {
+        "let" => {
+            ("kw", 3),
+            ("ws", 1),
+            ("ident", 1),
+            ("ws", 1),
+            ("eq", 1),
+            ("ws", 1),
+            ("number", 1),
+        }
+    })
+}
+
+fn child(tree: &Tree<&'static str, Span>, value: &str) -> Result<syntree::Id> {
+    let node = tree
+        .walk()
+        .find(|n| *n.value() == value)
+        .ok_or_else(|| anyhow!("missing {value}"))?;
+
+    Ok(node.id())
+}
+
+/// Get the synthesized ranges as pairs of offsets.
+fn ranges(tree: &Tree<&'static str, Span>, flag: u8) -> Vec<(usize, usize)> {
+    tree.synthesized_ranges(flag)
+        .map(|range| (range.start, range.end))
+        .collect()
+}
+
+/// Get the source covered by the synthesized ranges.
+fn synthesized<'a>(tree: &Tree<&'static str, Span>, source: &'a str) -> Vec<&'a str> {
+    tree.synthesized_ranges(SYNTHESIZED)
+        .map(|range| &source[range])
+        .collect()
+}
+
+#[test]
+fn synthesized_ranges_sequential_edits() -> Result<()> {
+    let source = "let a = 1";
+    let tree = parse()?;
+
+    // First edit: terminate the statement.
+    let mut change_set = ChangeSet::new().with_inserted_flags(SYNTHESIZED);
+    let semi = change_set.insert_after(child(&tree, "number")?, syntree::tree!(("semi", 1)))?;
+    change_set.set_text(semi, ";");
+
+    let source = change_set.apply_to_source(&tree, source)?;
+    let tree = change_set.modify(&tree)?;
+
+    assert_eq!(source, "let a = 1;");
+    assert_eq!(ranges(&tree, SYNTHESIZED), [(9, 10)]);
+
+    // Second edit: rename the binding and add a type annotation. The
+    // terminator from the first edit keeps its flag and moves along.
+    let mut change_set = ChangeSet::new().with_inserted_flags(SYNTHESIZED);
+
+    let ident = child(&tree, "ident")?;
+    let name = change_set.replace(ident, syntree::tree!(("ident", 5)))?;
+    change_set.set_text(name, "value");
+
+    let ty = change_set.insert_after(
+        ident,
+        syntree::tree! {
+            "type" => {
+                ("colon", 1),
+                ("ws", 1),
+                ("ident", 2),
+            }
+        },
+    )?;
+    change_set.set_text(ty, ": u8");
+
+    let source = change_set.apply_to_source(&tree, &source)?;
+    let tree = change_set.modify(&tree)?;
+
+    assert_eq!(source, "let value: u8 = 1;");
+    assert_eq!(ranges(&tree, SYNTHESIZED), [(4, 13), (17, 18)]);
+    assert_eq!(synthesized(&tree, &source), ["value: u8", ";"]);
+
+    // Everything else is original material.
+    let original = tree
+        .walk()
+        .filter(|n| n.kind() == syntree::Kind::Token && n.flags() == 0)
+        .map(|n| &source[n.range()])
+        .collect::<String>();
+
+    assert_eq!(original, "let  = 1");
+    Ok(())
+}
+
+#[test]
+fn synthesized_ranges_other_flags() -> Result<()> {
+    let tree = parse()?;
+
+    let mut change_set = ChangeSet::new().with_inserted_flags(OTHER);
+    change_set.insert_before(child(&tree, "kw")?, syntree::tree!(("pub", 4)))?;
+    let tree = change_set.modify(&tree)?;
+
+    let mut change_set = ChangeSet::new().with_inserted_flags(SYNTHESIZED);
+    change_set.insert_after(child(&tree, "number")?, syntree::tree!(("semi", 1)))?;
+    let tree = change_set.modify(&tree)?;
+
+    assert_eq!(ranges(&tree, OTHER), [(0, 4)]);
+    assert_eq!(ranges(&tree, SYNTHESIZED), [(13, 14)]);
+    assert_eq!(ranges(&tree, OTHER | SYNTHESIZED), [(0, 4), (13, 14)]);
+    Ok(())
+}
+
+#[test]
+fn synthesized_ranges_wrap() -> Result<()> {
+    let tree = parse()?;
+
+    let mut change_set = ChangeSet::new().with_inserted_flags(SYNTHESIZED);
+    change_set.wrap(child(&tree, "number")?, "expr");
+    let tree = change_set.modify(&tree)?;
+
+    // The wrapper is tagged, but it covers no synthesized material.
+    let expr = tree
+        .walk()
+        .find(|n| *n.value() == "expr")
+        .ok_or_else(|| anyhow!("missing expr"))?;
+
+    assert_eq!(expr.flags(), SYNTHESIZED);
+    assert_eq!(tree.synthesized_ranges(SYNTHESIZED).count(), 0);
+    Ok(())
+}
+
+#[test]
+fn synthesized_ranges_untagged() -> Result<()> {
+    let tree = parse()?;
+
+    let mut change_set = ChangeSet::new();
+    change_set.insert_after(child(&tree, "number")?, syntree::tree!(("semi", 1)))?;
+    let tree = change_set.modify(&tree)?;
+
+    assert!(tree.walk().all(|n| n.flags() == 0));
+    assert_eq!(tree.synthesized_ranges(u8::MAX).count(), 0);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use syntree::{Builder, CursorPolicy, Kind, Node, TokenIndex, Tree};
+
+fn build() -> Result<Tree<u32>> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+
+    for n in 0..24u32 {
+        b.open(n)?;
+
+        for m in 0..=n % 3 {
+            b.token(m, (m as usize + n as usize) % 3)?;
+        }
+
+        if n % 5 == 0 {
+            b.advance(2)?;
+        }
+
+        b.close()?;
+    }
+
+    Ok(b.build()?)
+}
+
+fn tokens(tree: &Tree<u32>) -> Vec<Node<'_, u32>> {
+    tree.walk().filter(|n| n.kind() == Kind::Token).collect()
+}
+
+#[test]
+fn token_at_matches_scan() -> Result<()> {
+    let tree = build()?;
+    let index = TokenIndex::new(&tree);
+    let tokens = tokens(&tree);
+
+    assert_eq!(index.len(), tokens.len());
+
+    for offset in 0..tree.span().range().end + 2 {
+        let expected = tokens
+            .iter()
+            .find(|n| n.range().contains(&offset))
+            .or_else(|| tokens.iter().find(|n| n.range() == (offset..offset)))
+            .copied();
+
+        assert_eq!(index.token_at(offset), expected, "offset {offset}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tokens_in_matches_scan() -> Result<()> {
+    let tree = build()?;
+    let index = TokenIndex::new(&tree);
+    let tokens = tokens(&tree);
+    let end = tree.span().range().end;
+
+    for start in 0..end + 2 {
+        for stop in start..end + 2 {
+            let expected = tokens
+                .iter()
+                .filter(|n| {
+                    let r = n.range();
+
+                    if r.is_empty() {
+                        start <= r.start && r.start <= stop
+                    } else {
+                        r.start < stop && r.end > start
+                            || start == stop && r.start < start && r.end > start
+                    }
+                })
+                .copied()
+                .collect::<Vec<_>>();
+
+            let actual = index.tokens_in(start..stop).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "range {start}..{stop}");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn node_covering_is_deepest() -> Result<()> {
+    let tree = build()?;
+    let index = TokenIndex::new(&tree);
+    let end = tree.span().range().end;
+
+    for start in 0..end {
+        for stop in start..end {
+            let covers = |n: &Node<'_, u32>| n.range().start <= start && n.range().end >= stop;
+
+            let Some(node) = index.node_covering(start..stop) else {
+                assert!(
+                    !tree.children().any(|n| covers(&n)),
+                    "range {start}..{stop}"
+                );
+                continue;
+            };
+
+            assert!(covers(&node), "range {start}..{stop}");
+            assert!(
+                !node
+                    .children()
+                    .any(|n| covers(&n) && n.range() != node.range()),
+                "range {start}..{stop}"
+            );
+        }
+    }
+
+    Ok(())
+}
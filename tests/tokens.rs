@@ -0,0 +1,81 @@
+use anyhow::{anyhow, Result};
+use syntree::{Builder, CursorPolicy, Span};
+
+#[test]
+fn first_and_last_token() -> Result<()> {
+    let tree = syntree::tree! {
+        "empty" => {},
+        "root" => {
+            "empty" => {
+                "empty" => {},
+            },
+            ("a", 2),
+            "inner" => {
+                ("b", 1),
+                "empty" => {},
+            },
+        },
+        "trailing" => {
+            "empty" => {},
+        },
+    };
+
+    let first = tree.first_token().ok_or(anyhow!("missing first"))?;
+    assert_eq!((*first.value(), first.range()), ("a", 0..2));
+
+    let last = tree.last_token().ok_or(anyhow!("missing last"))?;
+    assert_eq!((*last.value(), last.range()), ("b", 2..3));
+
+    assert_eq!(tree.span(), Span::new(0, 3));
+    Ok(())
+}
+
+#[test]
+fn single_token() -> Result<()> {
+    let tree = syntree::tree! {
+        ("eof", 0),
+    };
+
+    let first = tree.first_token().ok_or(anyhow!("missing first"))?;
+    let last = tree.last_token().ok_or(anyhow!("missing last"))?;
+    assert!(first.same(&last));
+    assert_eq!(tree.span(), Span::point(0));
+    Ok(())
+}
+
+#[test]
+fn only_empty_nodes() -> Result<()> {
+    let tree = syntree::tree! {
+        "a" => {
+            "b" => {},
+        },
+        "c" => {},
+    };
+
+    assert!(tree.first_token().is_none());
+    assert!(tree.last_token().is_none());
+    assert_eq!(tree.span(), Span::point(0));
+
+    let tree = Builder::<u32>::new().build()?;
+    assert!(tree.first_token().is_none());
+    assert!(tree.last_token().is_none());
+    assert_eq!(tree.span(), Span::point(0));
+    Ok(())
+}
+
+#[test]
+fn end_of_file_after_gap() -> Result<()> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+    b.open("root")?;
+    b.token("a", 2)?;
+    b.close()?;
+    b.advance(3)?;
+    let tree = b.build()?;
+
+    // The span of the tree includes trailing gaps, which the last token
+    // doesn't.
+    let last = tree.last_token().ok_or(anyhow!("missing last"))?;
+    assert_eq!(last.range(), 0..2);
+    assert_eq!(tree.span(), Span::new(0, 5));
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use anyhow::Result;
+use syntree::transform::{self, WsClass};
+use syntree::{Builder, CursorPolicy, Error, Kind, Span, Tree};
+
+fn classify(value: &&str) -> WsClass {
+    match *value {
+        "ws" => WsClass::Whitespace,
+        "nl" => WsClass::Newline,
+        _ => WsClass::Other,
+    }
+}
+
+/// Check that the tokens of `tree` cover `source` exactly.
+fn assert_lossless(tree: &Tree<&str, Span>, source: &str) {
+    let text = tree
+        .walk()
+        .filter(|n| n.kind() == Kind::Token)
+        .map(|n| &source[n.range()])
+        .collect::<String>();
+
+    assert_eq!(text, source);
+    assert_eq!(tree.span().range(), 0..source.len());
+    assert!(tree.gaps().next().is_none());
+    assert!(tree.validate().is_ok());
+}
+
+#[test]
+fn normalize_messy_snippet() -> Result<()> {
+    let source = "fn  main( )  {\t\n    let x =  1 ;   \n}  ";
+
+    let mut b = Builder::new();
+
+    b.open("fn")?;
+    b.token("kw", 2)?;
+    b.token("ws", 2)?;
+    b.token("ident", 4)?;
+    b.open("params")?;
+    b.token("open", 1)?;
+    b.token("ws", 1)?;
+    b.token("close", 1)?;
+    b.close()?;
+    b.token("ws", 2)?;
+    b.open("block")?;
+    b.token("open", 1)?;
+    b.token_trivia("ws", 1)?;
+    b.token("nl", 1)?;
+    b.token("ws", 4)?;
+    b.open("let")?;
+    b.token("kw", 3)?;
+    b.token("ws", 1)?;
+    b.token("ident", 1)?;
+    b.token("ws", 1)?;
+    b.token("eq", 1)?;
+    b.token("ws", 2)?;
+    b.token("number", 1)?;
+    b.token("ws", 1)?;
+    b.token("semi", 1)?;
+    b.close()?;
+    b.token("ws", 3)?;
+    b.token("nl", 1)?;
+    b.token("close", 1)?;
+    b.close()?;
+    b.close()?;
+    b.token("ws", 2)?;
+
+    let tree = b.build()?;
+    assert_lossless(&tree, source);
+
+    let (output_tree, output) = transform::normalize_whitespace(&tree, source, classify)?;
+
+    assert_eq!(output, "fn main( ) {\n    let x = 1 ;\n}");
+    assert_lossless(&output_tree, &output);
+
+    // Every token which isn't whitespace keeps its text, and the structure is
+    // unchanged.
+    let texts = |tree: &Tree<&str, Span>, source: &str| {
+        tree.walk()
+            .filter(|n| n.kind() == Kind::Token && *n.value() != "ws")
+            .map(|n| source[n.range()].to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(texts(&output_tree, &output), texts(&tree, source));
+    assert!(output_tree
+        .walk()
+        .map(|n| *n.value())
+        .eq(tree.walk().map(|n| *n.value())));
+
+    // Marks are kept on replaced tokens.
+    let trivia = output_tree
+        .walk()
+        .filter(|n| n.is_trivia())
+        .map(|n| *n.span())
+        .collect::<Vec<_>>();
+
+    assert_eq!(trivia, [Span::point(12)]);
+    Ok(())
+}
+
+#[test]
+fn normalize_long_whitespace_run() -> Result<()> {
+    const LEN: usize = 100_000;
+
+    let mut b = Builder::new();
+    b.token("word", 1)?;
+
+    for _ in 0..LEN {
+        b.token("ws", 1)?;
+    }
+
+    b.token("word", 1)?;
+
+    for _ in 0..LEN {
+        b.token("ws", 1)?;
+    }
+
+    b.token("nl", 1)?;
+
+    let tree = b.build()?;
+    let source = format!("a{}b{}\n", " ".repeat(LEN), " ".repeat(LEN));
+
+    let (tree, output) = transform::normalize_whitespace(&tree, &source, classify)?;
+    assert_eq!(output, "a b\n");
+    assert_lossless(&tree, &output);
+    Ok(())
+}
+
+#[test]
+fn normalize_invalid_source() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("word", 1),
+            ("ws", 2),
+            ("word", 1),
+        }
+    };
+
+    // The last token extends past the end of the source.
+    assert_eq!(
+        transform::normalize_whitespace(&tree, "a  ", classify),
+        Err(Error::SourceMismatch)
+    );
+
+    // The first token splits a character.
+    assert_eq!(
+        transform::normalize_whitespace(&tree, "éa b", classify),
+        Err(Error::SourceMismatch)
+    );
+
+    // Gaps can't be preserved.
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+    b.token("word", 1)?;
+    b.advance(1)?;
+    b.token("word", 1)?;
+    let tree = b.build()?;
+
+    assert_eq!(
+        transform::normalize_whitespace(&tree, "a b", classify),
+        Err(Error::SourceMismatch)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use anyhow::Result;
+use syntree::{span, Builder, CursorPolicy, Span, Tree};
+
+#[test]
+fn tree_offset() -> Result<()> {
+    let tree = syntree::tree! {
+        offset = 10;
+        "root" => {
+            ("tok", 3),
+            "empty" => {},
+        },
+        "empty" => {},
+        ("tok", 2),
+    };
+
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+    b.advance(10)?;
+    b.open("root")?;
+    b.token("tok", 3)?;
+    b.open("empty")?;
+    b.close()?;
+    b.close()?;
+    b.open("empty")?;
+    b.close()?;
+    b.token("tok", 2)?;
+    let expected = b.build()?;
+
+    assert_eq!(tree, expected);
+
+    let ranges = tree.walk().map(|n| n.range()).collect::<Vec<_>>();
+    assert_eq!(ranges, [10..13, 10..13, 13..13, 13..13, 13..15]);
+
+    // An empty tree with an offset.
+    let tree: Tree<&str, Span> = syntree::tree!(offset = 4;);
+    assert!(tree.is_empty());
+    Ok(())
+}
+
+#[test]
+fn tree_explicit_ranges() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("a", 1),
+            ("b", 3..5),
+            ("c", 2),
+            "empty" => {},
+            "inner" => {
+                ("d", 10..12)
+            }
+        },
+        ("e", 12..12),
+        "last"
+    };
+
+    let ranges = tree
+        .walk()
+        .map(|n| (*n.value(), n.range()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        ranges,
+        [
+            ("root", 0..12),
+            ("a", 0..1),
+            ("b", 3..5),
+            ("c", 5..7),
+            ("empty", 7..7),
+            ("inner", 10..12),
+            ("d", 10..12),
+            ("e", 12..12),
+            ("last", 12..12),
+        ]
+    );
+
+    // Mixing an offset with explicit ranges and trailing commas.
+    let tree = syntree::tree! {
+        offset = 2;
+        "root" => {
+            ("a", 2..4),
+            ("b", 1),
+            "empty" => {},
+        },
+    };
+
+    let ranges = tree.walk().map(|n| n.range()).collect::<Vec<_>>();
+    assert_eq!(ranges, [2..5, 2..4, 4..5, 5..5]);
+    Ok(())
+}
+
+#[test]
+fn tree_explicit_range_backwards() {
+    fn build() -> Result<Tree<&'static str, Span>> {
+        Ok(syntree::try_tree! {
+            ("a", 4),
+            ("b", 2..3),
+        })
+    }
+
+    assert!(build().is_err());
+}
+
+#[test]
+#[should_panic = "failed to build tree from literal"]
+fn tree_panics() {
+    let _ = syntree::tree! {
+        ("a", 4),
+        ("b", 2..3),
+    };
+}
+
+#[test]
+fn tree_without_result() {
+    let tree = syntree::tree! {
+        offset = 1;
+        "root" => {
+            ("a", 1),
+        }
+    };
+
+    let empty: Tree<_, span::Empty> = syntree::tree_with! {
+        "root" => {
+            "a"
+        }
+    };
+
+    assert_eq!(tree.len(), 2);
+    assert_eq!(empty.len(), 2);
+
+    syntree::assert_tree!(tree, {
+        "root" => {
+            "a"
+        }
+    });
+}
+
+#[test]
+fn tree_with_offset() -> Result<()> {
+    let tree: Tree<_, span::Empty> = syntree::tree_with! {
+        offset = span::Empty;
+        "root" => {
+            ("token", span::Empty),
+            "empty" => {},
+        },
+    };
+
+    let expected: Tree<_, span::Empty> = syntree::tree_with! {
+        "root" => {
+            "token",
+            "empty" => {}
+        }
+    };
+
+    assert_eq!(tree, expected);
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use syntree::edit::ChangeSet;
+use syntree::{print, Builder, Span, Tree};
+
+fn build() -> Result<Tree<&'static str, Span>> {
+    let mut tree = Builder::new();
+
+    tree.open("root")?;
+    tree.token_trivia("ws", 1)?;
+    tree.token("a", 1)?;
+    tree.token_trivia("comment", 2)?;
+    tree.open("group")?;
+    tree.token("b", 1)?;
+    tree.close()?;
+    tree.token_trivia("ws", 1)?;
+    tree.close()?;
+
+    Ok(tree.build()?)
+}
+
+#[test]
+fn skip_trivia() -> Result<()> {
+    let tree = build()?;
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+
+    let trivia = tree.walk().filter(|n| n.is_trivia()).map(|n| *n.value());
+    assert_eq!(trivia.collect::<Vec<_>>(), ["ws", "comment", "ws"]);
+
+    // Trivia at the start, middle and end of the children is skipped.
+    let children = root.children().skip_trivia().map(|n| *n.value());
+    assert_eq!(children.collect::<Vec<_>>(), ["a", "group"]);
+
+    let children = root.children().skip_trivia().rev().map(|n| *n.value());
+    assert_eq!(children.collect::<Vec<_>>(), ["group", "a"]);
+
+    let first = root.first().ok_or(anyhow::anyhow!("missing first"))?;
+    let siblings = first.siblings().skip_trivia().map(|n| *n.value());
+    assert_eq!(siblings.collect::<Vec<_>>(), ["a", "group"]);
+
+    let walk = tree.walk().skip_trivia().map(|n| *n.value());
+    assert_eq!(walk.collect::<Vec<_>>(), ["root", "a", "group", "b"]);
+
+    // Skipping trivia doesn't change the span of the node.
+    assert_eq!(root.span(), &Span::new(0, 6));
+    Ok(())
+}
+
+#[test]
+fn print_trivia() -> Result<()> {
+    let tree = build()?;
+
+    let mut s = Vec::new();
+    print::print_with_source(&mut s, &tree, " a//b ")?;
+
+    let expected = [
+        "\"root\"@0..6",
+        "  \"ws\"@0..1~ \" \"",
+        "  \"a\"@1..2 \"a\"",
+        "  \"comment\"@2..4~ \"//\"",
+        "  \"group\"@4..5",
+        "    \"b\"@4..5 \"b\"",
+        "  \"ws\"@5..6~ \" \"",
+    ];
+
+    assert_eq!(String::from_utf8(s)?.lines().collect::<Vec<_>>(), expected);
+    Ok(())
+}
+
+#[test]
+fn trivia_is_preserved() -> Result<()> {
+    let tree = build()?;
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    let a = root.children().skip_trivia().next();
+    let a = a.ok_or(anyhow::anyhow!("missing a"))?;
+
+    let mut change_set = ChangeSet::new();
+    change_set.wrap(a.id(), "wrapper");
+    let edited = change_set.modify(&tree)?;
+
+    let trivia = edited.walk().filter(|n| n.is_trivia()).map(|n| *n.value());
+    assert_eq!(trivia.collect::<Vec<_>>(), ["ws", "comment", "ws"]);
+
+    let mut reused = Builder::new();
+    reused.open("outer")?;
+    reused.reuse(root)?;
+    reused.close()?;
+    let reused = reused.build()?;
+
+    let trivia = reused.walk().filter(|n| n.is_trivia()).map(|n| *n.value());
+    assert_eq!(trivia.collect::<Vec<_>>(), ["ws", "comment", "ws"]);
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use anyhow::{Context, Result};
+use syntree::edit::{ChangeSet, UndoLog};
+use syntree::{Builder, Error, Span, Tree};
+
+type Step = fn(&Tree<&'static str, Span>, &mut ChangeSet<&'static str, Span>) -> Result<()>;
+
+fn child(tree: &Tree<&'static str, Span>, path: &[usize]) -> Result<syntree::Id> {
+    let mut node = tree.children().nth(path[0]).context("missing root")?;
+
+    for &n in &path[1..] {
+        node = node.children().nth(n).context("missing child")?;
+    }
+
+    Ok(node.id())
+}
+
+#[test]
+fn undo_redo_mixed_edits() -> Result<()> {
+    let source = "fn add(a, b) { a + b }";
+
+    let tree = syntree::tree! {
+        "fn" => {
+            ("kw", 2),
+            ("ws", 1),
+            ("ident", 3),
+            "params" => {
+                ("open", 1),
+                ("ident", 1),
+                ("comma", 1),
+                ("ws", 1),
+                ("ident", 1),
+                ("close", 1),
+            },
+            ("ws", 1),
+            "block" => {
+                ("open", 1),
+                ("ws", 1),
+                "expr" => {
+                    ("ident", 1),
+                    ("ws", 1),
+                    ("plus", 1),
+                    ("ws", 1),
+                    ("ident", 1),
+                },
+                ("ws", 1),
+                ("close", 1),
+            },
+        }
+    };
+
+    let steps: [Step; 5] = [
+        // Rename the function and remove the second parameter.
+        |tree, cs| {
+            let name = child(tree, &[0, 2])?;
+            let insertion = cs.replace(name, syntree::tree!(("ident", 3)))?;
+            cs.set_text(insertion, "sum");
+
+            for n in 2..5 {
+                cs.remove(child(tree, &[0, 3, n])?);
+            }
+
+            Ok(())
+        },
+        // Wrap the expression, change a value and insert a statement.
+        |tree, cs| {
+            let expr = child(tree, &[0, 5, 2])?;
+            cs.wrap(expr, "stmt");
+            cs.replace_value(child(tree, &[0, 5, 2, 2])?, "op");
+
+            let insertion = cs.insert_child(child(tree, &[0, 5])?, 2, |b: &mut Builder<_>| {
+                b.open("let")?;
+                b.token("kw", 3)?;
+                b.token("ws", 1)?;
+                b.close()?;
+                Ok::<_, Error>(())
+            })?;
+
+            cs.set_text(insertion, "let ");
+            Ok(())
+        },
+        // Unwrap the parameters and insert a root after the function.
+        |tree, cs| {
+            cs.unwrap(child(tree, &[0, 3])?);
+            let insertion = cs.insert_after(child(tree, &[0])?, syntree::tree!(("eof", 0)))?;
+            cs.set_text(insertion, "");
+            let insertion = cs.insert_before(child(tree, &[0])?, syntree::tree!(("ws", 1)))?;
+            cs.set_text(insertion, "\n");
+            Ok(())
+        },
+        // Remove the keyword of the function and indent its name.
+        |tree, cs| {
+            cs.remove(child(tree, &[1, 0])?);
+            cs.remove(child(tree, &[1, 1])?);
+            let insertion = cs.insert_before(child(tree, &[1, 2])?, syntree::tree!(("ws", 2)))?;
+            cs.set_text(insertion, "  ");
+            Ok(())
+        },
+        // Unwrap the inserted statement.
+        |tree, cs| {
+            cs.unwrap(child(tree, &[1, 6, 3])?);
+            Ok(())
+        },
+    ];
+
+    let mut log = UndoLog::new();
+    let mut states = vec![(tree.clone(), source.to_owned())];
+
+    for step in steps {
+        let (tree, source) = states.last().context("missing state")?;
+        let mut change_set = ChangeSet::new();
+        step(tree, &mut change_set)?;
+        let (tree, source) = log.apply(&mut change_set, tree, source)?;
+        assert_eq!(tree.span().range(), 0..source.len());
+        states.push((tree, source));
+    }
+
+    assert_eq!(states[5].1, "\n  sum(a) { let a + b }");
+
+    let (mut tree, mut source) = states.last().cloned().context("missing state")?;
+
+    for (expected_tree, expected_source) in states.iter().rev().skip(1) {
+        (tree, source) = log.undo(&tree, &source)?.context("missing undo")?;
+        assert_eq!(&tree, expected_tree);
+        assert_eq!(&source, expected_source);
+    }
+
+    assert!(!log.can_undo());
+    assert!(log.undo(&tree, &source)?.is_none());
+
+    for (expected_tree, expected_source) in states.iter().skip(1) {
+        (tree, source) = log.redo(&tree, &source)?.context("missing redo")?;
+        assert_eq!(&tree, expected_tree);
+        assert_eq!(&source, expected_source);
+    }
+
+    assert!(!log.can_redo());
+    Ok(())
+}
+
+#[test]
+fn undo_remove_everything() -> Result<()> {
+    let source = "a b";
+
+    let tree = syntree::tree! {
+        ("a", 1),
+        ("ws", 1),
+        ("b", 1),
+    };
+
+    let mut change_set = ChangeSet::new();
+
+    for node in tree.children() {
+        change_set.remove(node.id());
+    }
+
+    let mut log = UndoLog::new();
+    let (edited, edited_source) = log.apply(&mut change_set, &tree, source)?;
+    assert!(edited.is_empty());
+    assert!(edited_source.is_empty());
+
+    let (undone, undone_source) = log.undo(&edited, &edited_source)?.context("missing undo")?;
+    assert_eq!(undone, tree);
+    assert_eq!(undone_source, source);
+
+    let (redone, redone_source) = log.redo(&undone, &undone_source)?.context("missing redo")?;
+    assert_eq!(redone, edited);
+    assert_eq!(redone_source, edited_source);
+    Ok(())
+}
+
+#[test]
+fn apply_discards_redo() -> Result<()> {
+    let source = "ab";
+    let tree = syntree::tree! { ("a", 1), ("b", 1) };
+
+    let mut log = UndoLog::new();
+    let mut change_set = ChangeSet::new();
+    change_set.remove(child(&tree, &[0])?);
+    let (tree, source) = log.apply(&mut change_set, &tree, source)?;
+    let (tree, source) = log.undo(&tree, &source)?.context("missing undo")?;
+    assert!(log.can_redo());
+
+    let mut change_set = ChangeSet::new();
+    change_set.replace_value(child(&tree, &[1])?, "c");
+    log.apply(&mut change_set, &tree, &source)?;
+    assert!(!log.can_redo());
+    assert!(log.can_undo());
+    Ok(())
+}
+
+#[test]
+fn undo_keeps_flags_marks_and_gaps() -> Result<()> {
+    let source = "a b c";
+
+    let mut builder = Builder::new().with_policy(syntree::CursorPolicy::Monotonic);
+    builder.open("root")?;
+    builder.token("a", 1)?;
+    builder.token_trivia("ws", 1)?;
+    let group = builder.open("group")?;
+    builder.token_with_span("b", Span::new(2, 3))?;
+    let c = builder.token_with_span("c", Span::new(4, 5))?;
+    builder.close()?;
+    builder.close()?;
+    builder.set_flags(group, 0b101)?;
+    builder.mark_error(c)?;
+    let tree = builder.build()?;
+
+    let mut log = UndoLog::new();
+    let mut change_set = ChangeSet::new();
+    change_set.remove(group);
+    let (edited, edited_source) = log.apply(&mut change_set, &tree, source)?;
+    assert_eq!(edited_source, "a ");
+
+    let (undone, undone_source) = log.undo(&edited, &edited_source)?.context("missing undo")?;
+    assert_eq!(undone, tree);
+    assert_eq!(undone_source, source);
+
+    // Tree equality doesn't cover marks, so check them explicitly.
+    let marks = |tree: &Tree<&'static str, Span>| {
+        tree.walk()
+            .map(|n| {
+                (
+                    *n.value(),
+                    n.range(),
+                    n.flags(),
+                    n.is_error(),
+                    n.is_trivia(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        marks(&undone),
+        [
+            ("root", 0..5, 0, false, false),
+            ("a", 0..1, 0, false, false),
+            ("ws", 1..2, 0, false, true),
+            ("group", 2..5, 0b101, false, false),
+            ("b", 2..3, 0, false, false),
+            ("c", 4..5, 0, true, false),
+        ]
+    );
+
+    assert_eq!(marks(&undone), marks(&tree));
+    Ok(())
+}
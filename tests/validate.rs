@@ -0,0 +1,173 @@
+use anyhow::{anyhow, Result};
+use syntree::edit::ChangeSet;
+use syntree::owned::TreeNode;
+use syntree::{Builder, CursorPolicy, Invariant, Postfix, Span, Tree};
+
+mod common;
+
+#[test]
+fn validate_builder() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            "number" => {
+                ("lit", 3),
+            },
+            ("ws", 1),
+            "empty" => {},
+        },
+        ("eof", 0),
+        "empty" => {},
+    };
+
+    tree.validate()?;
+
+    Builder::<u32>::new().build()?.validate()?;
+
+    for seed in 1..=64 {
+        common::Fixture::new().reuse().generate(seed)?.validate()?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn validate_postfix() -> Result<()> {
+    let mut b = Builder::new();
+
+    for item in [
+        Postfix::Operand {
+            value: "number",
+            len: 1,
+        },
+        Postfix::Operand {
+            value: "number",
+            len: 2,
+        },
+        Postfix::Operator {
+            value: "add",
+            arity: 2,
+        },
+    ] {
+        b.push_postfix(item)?;
+    }
+
+    b.finish_postfix()?;
+    b.build()?.validate()?;
+    Ok(())
+}
+
+#[test]
+fn validate_policies() -> Result<()> {
+    let mut b = Builder::new().with_policy(CursorPolicy::Monotonic);
+    b.open("root")?;
+    b.advance(2)?;
+    b.token("a", 1)?;
+    b.token_with_span("b", Span::new(5, 6))?;
+    b.close()?;
+    b.build()?.validate()?;
+
+    // Out of order siblings are reported.
+    let mut b = Builder::new().with_policy(CursorPolicy::Unordered);
+    b.open("root")?;
+    b.token_with_span("a", Span::new(4, 6))?;
+    let second = b.token_with_span("b", Span::new(1, 2))?;
+    b.close()?;
+
+    let error = b
+        .build()?
+        .validate()
+        .err()
+        .ok_or(anyhow!("expected corrupt tree"))?;
+
+    assert_eq!(error.id(), Some(second));
+    assert_eq!(error.invariant(), Invariant::SpanOrder);
+    assert_eq!(
+        error.to_string(),
+        "node with id `2` has a span which overlaps its previous sibling"
+    );
+    Ok(())
+}
+
+#[test]
+fn validate_conversions() -> Result<()> {
+    for seed in 1..=16 {
+        let tree = common::Fixture::new().reuse().generate(seed)?;
+
+        Tree::from_owned_events(tree.to_owned_events())?.validate()?;
+        Tree::from_owned_nodes(tree.to_owned_nodes())?.validate()?;
+    }
+
+    let nodes = vec![TreeNode::node(
+        "root",
+        Span::point(0),
+        vec![TreeNode::token("a", Span::new(4, 5))],
+    )];
+
+    Tree::from_owned_nodes(nodes)?.validate()?;
+    Ok(())
+}
+
+#[test]
+fn validate_edits() -> Result<()> {
+    let mut modified = 0;
+
+    for seed in 1..=32 {
+        let tree = common::Fixture::new().reuse().generate(seed)?;
+        let mut rng = common::Rng::new(seed);
+        let mut change_set = ChangeSet::new();
+
+        for node in tree.walk() {
+            match rng.below(8) {
+                0 => change_set.remove(node.id()),
+                1 => {
+                    change_set.insert_after(node.id(), syntree::tree!((1000, 2)))?;
+                }
+                2 => {
+                    change_set.insert_before(node.id(), syntree::tree!(1001 => {}))?;
+                }
+                3 => change_set.wrap(node.id(), 1002),
+                _ => {}
+            }
+        }
+
+        // Conflicting changes are fine to skip, the rest has to be valid.
+        if let Ok(tree) = change_set.modify(&tree) {
+            tree.validate()?;
+            modified += 1;
+        }
+    }
+
+    assert!(modified > 0);
+    Ok(())
+}
+
+#[cfg(feature = "binary")]
+#[test]
+fn validate_binary() -> Result<()> {
+    for seed in 1..=16 {
+        let tree = common::Fixture::new().reuse().generate(seed)?;
+
+        let mut out = Vec::new();
+        syntree::binary::write(&tree, &mut out)?;
+
+        let decoded: Tree<u32, Span> = syntree::binary::read(&mut &out[..])?;
+        decoded.validate()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn validate_arbitrary() -> Result<()> {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let mut rng = common::Rng::new(1);
+
+    for _ in 0..64 {
+        let data = (0..1024).map(|_| rng.next() as u8).collect::<Vec<_>>();
+        Tree::<u8, Span>::arbitrary(&mut Unstructured::new(&data))?.validate()?;
+    }
+
+    Ok(())
+}
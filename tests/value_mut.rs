@@ -0,0 +1,73 @@
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use syntree::{Builder, Span, Tree};
+
+/// A payload which is neither `Copy` nor `Clone`.
+#[derive(Debug, PartialEq, Eq)]
+enum Value {
+    Root,
+    Ident(Box<str>),
+    Placeholder,
+    Literal(Box<u64>),
+}
+
+fn layout(tree: &Tree<Value, Span>) -> Vec<(usize, Range<usize>)> {
+    tree.walk().map(|n| (n.id().index(), n.range())).collect()
+}
+
+#[test]
+fn value_mut_leaves_structure() -> Result<()> {
+    let mut b = Builder::new();
+
+    b.open(Value::Root)?;
+    b.token(Value::Ident("a".into()), 1)?;
+    let placeholder = b.token(Value::Placeholder, 2)?;
+    b.token(Value::Ident("b".into()), 1)?;
+    b.close()?;
+
+    let mut tree = b.build()?;
+    let before = layout(&tree);
+
+    *tree
+        .value_mut(placeholder)
+        .ok_or(anyhow!("missing placeholder"))? = Value::Literal(Box::new(42));
+
+    assert_eq!(layout(&tree), before);
+
+    let node = tree
+        .get(placeholder)
+        .ok_or(anyhow!("missing placeholder"))?;
+    assert_eq!(node.value(), &Value::Literal(Box::new(42)));
+    assert_eq!(node.range(), 1..3);
+    assert_eq!(
+        node.prev().map(|n| n.value()),
+        Some(&Value::Ident("a".into()))
+    );
+    assert_eq!(
+        node.next().map(|n| n.value()),
+        Some(&Value::Ident("b".into()))
+    );
+
+    for value in tree.values_mut() {
+        if let Value::Ident(name) = value {
+            *name = name.to_uppercase().into();
+        }
+    }
+
+    assert_eq!(layout(&tree), before);
+
+    let values = tree.walk().map(|n| n.value()).collect::<Vec<_>>();
+
+    assert_eq!(
+        values,
+        [
+            &Value::Root,
+            &Value::Ident("A".into()),
+            &Value::Literal(Box::new(42)),
+            &Value::Ident("B".into()),
+        ]
+    );
+
+    Ok(())
+}
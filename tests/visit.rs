@@ -0,0 +1,194 @@
+use anyhow::Result;
+use syntree::{Node, Span, Tree, VisitControl, Visitor};
+
+/// A visitor which records every callback and applies a fixed control to
+/// nodes with a matching value.
+struct Recorder {
+    events: Vec<String>,
+    target: &'static str,
+    control: VisitControl,
+}
+
+impl Recorder {
+    fn new(target: &'static str, control: VisitControl) -> Self {
+        Self {
+            events: Vec::new(),
+            target,
+            control,
+        }
+    }
+}
+
+impl<'a> Visitor<'a, &'static str, Span> for Recorder {
+    fn enter_node(&mut self, node: Node<'a, &'static str, Span>) -> VisitControl {
+        self.events.push(format!("enter {}", node.value()));
+
+        if *node.value() == self.target {
+            self.control
+        } else {
+            VisitControl::Continue
+        }
+    }
+
+    fn leave_node(&mut self, node: Node<'a, &'static str, Span>) {
+        self.events.push(format!("leave {}", node.value()));
+    }
+
+    fn token(&mut self, node: Node<'a, &'static str, Span>) {
+        self.events.push(format!("token {}", node.value()));
+    }
+}
+
+fn tree() -> Result<Tree<&'static str, Span>> {
+    Ok(syntree::tree! {
+        "root" => {
+            "a" => {
+                ("a1", 1),
+                "b" => {
+                    ("b1", 1)
+                },
+                ("a2", 1)
+            },
+            ("r1", 1)
+        },
+        "other" => {}
+    })
+}
+
+#[test]
+fn visit_continue() -> Result<()> {
+    let tree = tree()?;
+    let mut v = Recorder::new("", VisitControl::Continue);
+    tree.visit(&mut v);
+
+    assert_eq!(
+        v.events,
+        [
+            "enter root",
+            "enter a",
+            "token a1",
+            "enter b",
+            "token b1",
+            "leave b",
+            "token a2",
+            "leave a",
+            "token r1",
+            "leave root",
+            "enter other",
+            "leave other",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn visit_skip_subtree() -> Result<()> {
+    let tree = tree()?;
+    let mut v = Recorder::new("a", VisitControl::SkipSubtree);
+    tree.visit(&mut v);
+
+    assert_eq!(
+        v.events,
+        [
+            "enter root",
+            "enter a",
+            "leave a",
+            "token r1",
+            "leave root",
+            "enter other",
+            "leave other",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn visit_stop() -> Result<()> {
+    let tree = tree()?;
+    let mut v = Recorder::new("b", VisitControl::Stop);
+    tree.visit(&mut v);
+
+    assert_eq!(
+        v.events,
+        [
+            "enter root",
+            "enter a",
+            "token a1",
+            "enter b",
+            "leave b",
+            "leave a",
+            "leave root",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn visit_node() -> Result<()> {
+    let tree = tree()?;
+    let root = tree.first().ok_or(anyhow::anyhow!("missing root"))?;
+    let a = root.first().ok_or(anyhow::anyhow!("missing a"))?;
+
+    let mut v = Recorder::new("", VisitControl::Continue);
+    a.visit(&mut v);
+
+    assert_eq!(
+        v.events,
+        ["enter a", "token a1", "enter b", "token b1", "leave b", "token a2", "leave a"]
+    );
+
+    // Stopping at the root of the visit only leaves the root.
+    let mut v = Recorder::new("a", VisitControl::Stop);
+    a.visit(&mut v);
+    assert_eq!(v.events, ["enter a", "leave a"]);
+
+    // Visiting a token only visits the token.
+    let a1 = a.first().ok_or(anyhow::anyhow!("missing a1"))?;
+    let mut v = Recorder::new("", VisitControl::Continue);
+    a1.visit(&mut v);
+    assert_eq!(v.events, ["token a1"]);
+    Ok(())
+}
+
+#[test]
+fn visit_deep() -> Result<()> {
+    let mut tree = syntree::Builder::new();
+
+    for _ in 0..100_000 {
+        tree.open("n")?;
+    }
+
+    tree.token("t", 1)?;
+
+    for _ in 0..100_000 {
+        tree.close()?;
+    }
+
+    let tree = tree.build()?;
+
+    #[derive(Default)]
+    struct Count(usize, usize, usize);
+
+    impl<'a> Visitor<'a, &'static str, Span> for Count {
+        fn enter_node(&mut self, _: Node<'a, &'static str, Span>) -> VisitControl {
+            self.0 += 1;
+            VisitControl::Continue
+        }
+
+        fn leave_node(&mut self, _: Node<'a, &'static str, Span>) {
+            self.1 += 1;
+        }
+
+        fn token(&mut self, _: Node<'a, &'static str, Span>) {
+            self.2 += 1;
+        }
+    }
+
+    let mut count = Count::default();
+    tree.visit(&mut count);
+    assert_eq!((count.0, count.1, count.2), (100_000, 100_000, 1));
+    Ok(())
+}
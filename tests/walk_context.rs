@@ -0,0 +1,73 @@
+use anyhow::{anyhow, Result};
+use syntree::node::WithContext;
+use syntree::Span;
+
+fn check(mut it: WithContext<'_, &'static str, Span>) -> usize {
+    let mut count = 0;
+
+    while let Some((node, cx)) = it.next() {
+        assert!(cx.ancestors().eq(node.ancestors().skip(1)));
+        assert_eq!(cx.depth(), node.ancestors().count() - 1);
+        assert_eq!(cx.parent(), node.parent());
+        assert_eq!(
+            cx.nearest(|n| *n.value() == "block"),
+            node.ancestors().skip(1).find(|n| *n.value() == "block")
+        );
+        count += 1;
+    }
+
+    count
+}
+
+#[test]
+fn walk_context_matches_ancestors() -> Result<()> {
+    let tree = syntree::tree! {
+        "fn" => {
+            ("ident", 2),
+            "block" => {
+                "let" => {
+                    ("ident", 1),
+                    "expr" => {
+                        "block" => {
+                            ("lit", 1),
+                        },
+                        ("op", 1),
+                        "empty" => {},
+                    },
+                },
+                ("semi", 1),
+            },
+            "empty" => {},
+        },
+        ("ws", 1),
+        "fn" => {
+            ("ident", 1),
+        },
+    };
+
+    assert_eq!(check(tree.walk().with_context()), tree.walk().count());
+
+    for node in tree.walk() {
+        assert_eq!(check(node.walk().with_context()), node.walk().count());
+    }
+
+    // Partially consumed walks pick up the ancestors of the next node.
+    for n in 0..tree.len() {
+        let mut walk = tree.walk();
+        walk.by_ref().take(n).for_each(drop);
+        let remaining = walk.clone().count();
+        assert_eq!(check(walk.with_context()), remaining);
+    }
+
+    let block = tree
+        .walk()
+        .find(|n| *n.value() == "block")
+        .ok_or(anyhow!("missing block"))?;
+
+    let mut it = block.walk().with_context();
+    let (first, cx) = it.next().ok_or(anyhow!("missing first"))?;
+    assert_eq!(*first.value(), "let");
+    assert_eq!(cx.depth(), 2);
+    assert!(cx.ancestors().rev().map(|n| *n.value()).eq(["fn", "block"]));
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use anyhow::{anyhow, Result};
+use syntree::node::Event;
+use syntree::{Builder, Kind};
+
+#[test]
+fn walk_events_balanced() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("lit", 1),
+            "empty" => {},
+            "nested" => {
+                "inner" => {
+                    ("lit", 2),
+                },
+                ("ws", 1),
+            },
+        },
+        ("trailing", 1),
+        "last" => {},
+    };
+
+    let root = tree.first().ok_or(anyhow!("missing root"))?;
+
+    for events in [tree.walk_events(), root.walk_events()] {
+        let mut stack = Vec::new();
+
+        for (event, node) in events {
+            if event.is_enter() {
+                assert!(!event.is_exit());
+
+                if !node.is_empty() {
+                    stack.push(node.id());
+                }
+            } else {
+                assert!(event.is_exit());
+                assert_eq!(event, Event::Up);
+                assert_eq!(stack.pop(), Some(node.id()));
+            }
+        }
+
+        assert!(stack.is_empty());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn walk_events_round_trip() -> Result<()> {
+    let tree = syntree::tree! {
+        "root" => {
+            ("lit", 1),
+            "empty" => {},
+            "nested" => {
+                "inner" => {
+                    ("lit", 2),
+                    ("empty", 0),
+                },
+                ("ws", 1),
+            },
+        },
+        ("trailing", 1),
+        "last" => {},
+    };
+
+    let mut b = Builder::new();
+    let mut depth = 0;
+
+    for (event, node) in tree.walk_events() {
+        depth += event.depth_delta();
+
+        if event.is_exit() {
+            b.close()?;
+            continue;
+        }
+
+        if node.kind() == Kind::Token {
+            b.token(*node.value(), node.range().len())?;
+            continue;
+        }
+
+        b.open(*node.value())?;
+
+        if node.is_empty() {
+            b.close()?;
+        }
+    }
+
+    assert_eq!(depth, 0);
+    assert_eq!(b.build()?, tree);
+    Ok(())
+}
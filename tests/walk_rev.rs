@@ -0,0 +1,105 @@
+use anyhow::Result;
+use syntree::{Builder, Id, Node, Span};
+
+mod common;
+
+fn ids<'a>(it: impl Iterator<Item = Node<'a, u32, Span>>) -> Vec<Id> {
+    it.map(|n| n.id()).collect()
+}
+
+#[test]
+fn walk_rev_is_reversed_walk() -> Result<()> {
+    for seed in 1..=64 {
+        let tree = common::generate(seed)?;
+
+        let mut forward = ids(tree.walk());
+        forward.reverse();
+        assert_eq!(ids(tree.walk_rev()), forward, "seed {seed}");
+
+        let mut forward = tree
+            .walk()
+            .with_depths()
+            .map(|(d, n)| (d, n.id()))
+            .collect::<Vec<_>>();
+        forward.reverse();
+
+        let reverse = tree
+            .walk_rev()
+            .with_depths()
+            .map(|(d, n)| (d, n.id()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(reverse, forward, "seed {seed}");
+
+        for node in tree.walk() {
+            let mut forward = ids(node.walk());
+            forward.reverse();
+            assert_eq!(ids(node.walk_rev()), forward, "seed {seed}");
+
+            let mut forward = node
+                .walk()
+                .with_depths()
+                .map(|(d, n)| (d, n.id()))
+                .collect::<Vec<_>>();
+            forward.reverse();
+
+            let reverse = node
+                .walk_rev()
+                .with_depths()
+                .map(|(d, n)| (d, n.id()))
+                .collect::<Vec<_>>();
+
+            assert_eq!(reverse, forward, "seed {seed}");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn walk_rev_empty() -> Result<()> {
+    let tree = Builder::<u32>::new().build()?;
+    assert!(tree.walk_rev().next().is_none());
+
+    let tree = syntree::tree! {
+        "a" => {},
+        "b" => {},
+    };
+
+    assert!(tree.walk_rev().map(|n| *n.value()).eq(["b", "a"]));
+    Ok(())
+}
+
+#[test]
+fn walk_rev_empty_nodes_mid_sequence() -> Result<()> {
+    let tree = syntree::tree! {
+        "a" => {},
+        "b" => {
+            "c" => {},
+            ("d", 1),
+            "e" => {
+                "f" => {},
+                "g" => {
+                    "h" => {},
+                },
+            },
+            "i" => {},
+        },
+        ("j", 1),
+        "k" => {
+            "l" => {},
+        },
+        "m" => {},
+    };
+
+    let mut forward = tree.walk().map(|n| *n.value()).collect::<Vec<_>>();
+    forward.reverse();
+
+    let reverse = tree.walk_rev().map(|n| *n.value()).collect::<Vec<_>>();
+    assert_eq!(reverse, forward);
+    assert_eq!(
+        reverse,
+        ["m", "l", "k", "j", "i", "h", "g", "f", "e", "d", "c", "b", "a"]
+    );
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+
+#[test]
+fn walk_with_source_pairs_tokens() -> Result<()> {
+    let source = "fn é(ü) {}";
+
+    let tree = syntree::tree! {
+        "fn" => {
+            ("kw", 2),
+            ("ws", 1),
+            ("ident", 2),
+            "params" => {
+                ("open", 1),
+                ("ident", 2),
+                ("close", 1),
+            },
+            ("ws", 1),
+            "block" => {
+                ("open", 1),
+                ("close", 1),
+            },
+        },
+        ("eof", 0),
+    };
+
+    let texts = tree
+        .walk_with_source(source)
+        .map(|(n, text)| (*n.value(), text))
+        .collect::<Vec<_>>();
+
+    let expected = [
+        ("fn", None),
+        ("kw", Some("fn")),
+        ("ws", Some(" ")),
+        ("ident", Some("é")),
+        ("params", None),
+        ("open", Some("(")),
+        ("ident", Some("ü")),
+        ("close", Some(")")),
+        ("ws", Some(" ")),
+        ("block", None),
+        ("open", Some("{")),
+        ("close", Some("}")),
+        ("eof", Some("")),
+    ];
+
+    assert_eq!(texts, expected);
+
+    // Every node is visited in the same order as a plain walk.
+    assert!(tree
+        .walk_with_source(source)
+        .map(|(n, _)| n)
+        .eq(tree.walk()));
+
+    let params = tree
+        .walk()
+        .find(|n| *n.value() == "params")
+        .ok_or(anyhow::anyhow!("missing params"))?;
+
+    let texts = params
+        .walk_with_source(source)
+        .map(|(_, text)| text)
+        .collect::<Vec<_>>();
+
+    assert_eq!(texts, [Some("("), Some("ü"), Some(")")]);
+    Ok(())
+}
+
+#[test]
+fn walk_with_invalid_spans() {
+    // The `é` is two bytes wide, so the `HALF` token splits it.
+    let source = "aé";
+
+    let tree = syntree::tree! {
+        "root" => {
+            ("A", 1),
+            ("HALF", 1),
+            ("REST", 1),
+        },
+        ("PAST", 2),
+        ("END", 0),
+    };
+
+    let texts = tree
+        .walk_with_source(source)
+        .map(|(n, text)| (*n.value(), text))
+        .collect::<Vec<_>>();
+
+    let expected = [
+        ("root", None),
+        ("A", Some("a")),
+        ("HALF", None),
+        ("REST", None),
+        ("PAST", None),
+        ("END", None),
+    ];
+
+    assert_eq!(texts, expected);
+}
@@ -0,0 +1,66 @@
+use syntree::{Span, Tree};
+
+fn fixture() -> Tree<&'static str, Span> {
+    syntree::tree! {
+        "fn" => {
+            ("ident", 3),
+            "body" => {
+                "return" => {
+                    ("number", 1),
+                },
+                "block" => {
+                    "return" => {},
+                    "empty" => {},
+                },
+                ("semi", 1),
+            },
+        },
+        ("ws", 1),
+        "struct" => {
+            ("ident", 2),
+        },
+        ("eof", 0),
+    }
+}
+
+#[test]
+fn with_parents_matches_parent() {
+    let tree = fixture();
+
+    let pairs = tree.walk().with_parents().collect::<Vec<_>>();
+    assert_eq!(pairs.len(), tree.len());
+
+    for ((parent, node), expected) in pairs.iter().zip(tree.walk()) {
+        assert!(node.same(&expected));
+        assert_eq!(parent.map(|n| n.id()), node.parent().map(|n| n.id()));
+    }
+}
+
+#[test]
+fn with_parents_from_node() {
+    let tree = fixture();
+
+    for node in tree.walk() {
+        let mut count = 0;
+
+        for (parent, child) in node.walk().with_parents() {
+            let expected = child.parent().filter(|p| !p.same(&node));
+            assert_eq!(parent.map(|n| n.id()), expected.map(|n| n.id()));
+            count += 1;
+        }
+
+        assert_eq!(count, node.walk().count());
+    }
+}
+
+#[test]
+fn with_parents_size_hint() {
+    let tree = fixture();
+    let mut it = tree.walk().with_parents();
+    assert_eq!(it.size_hint(), (tree.len(), Some(tree.len())));
+    it.next();
+    assert_eq!(it.size_hint(), (tree.len() - 1, Some(tree.len() - 1)));
+
+    let mut it = syntree::node::WithParents::<u32, Span>::default();
+    assert!(it.next().is_none());
+}